@@ -27,6 +27,7 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 const THROUGHPUT_PERIOD: Duration = Duration::from_secs(20);
+const BATCH_SIZE: u32 = 32;
 
 fn main() {
     gst::init().unwrap();
@@ -66,21 +67,49 @@ fn main() {
     let counter = Arc::new(AtomicU64::new(0));
 
     for i in 0..n_streams {
-        let sink =
-            gst::ElementFactory::make("fakesink", Some(format!("sink-{}", i).as_str())).unwrap();
-        sink.set_property("sync", false);
-        sink.set_property("async", false);
+        let sink = match source.as_str() {
+            // Relay the received packets to check the send path too
+            "ts-udprelay" | "ts-udprelay-batched" => {
+                let sink =
+                    gst::ElementFactory::make("ts-udpsink", Some(format!("sink-{}", i).as_str()))
+                        .unwrap();
+                sink.set_property("clients", format!("127.0.0.1:{}", 50000u32 + i as u32));
+                sink.set_property("context", format!("context-{}", (i as u32) % n_groups));
+                sink.set_property("context-wait", wait);
+                sink.set_property("sync", false);
+                if source == "ts-udprelay-batched" {
+                    sink.set_property("batch-send", true);
+                    sink.set_property("gso", true);
+                }
+
+                sink
+            }
+            _ => {
+                let sink =
+                    gst::ElementFactory::make("fakesink", Some(format!("sink-{}", i).as_str()))
+                        .unwrap();
+                sink.set_property("sync", false);
+                sink.set_property("async", false);
+
+                sink
+            }
+        };
 
         let counter_clone = Arc::clone(&counter);
         sink.static_pad("sink").unwrap().add_probe(
-            gst::PadProbeType::BUFFER,
-            move |_pad, _probe_info| {
-                let _ = counter_clone.fetch_add(1, Ordering::SeqCst);
+            gst::PadProbeType::BUFFER | gst::PadProbeType::BUFFER_LIST,
+            move |_pad, probe_info| {
+                let count = match probe_info.data {
+                    Some(gst::PadProbeData::BufferList(ref list)) => list.len() as u64,
+                    _ => 1,
+                };
+                let _ = counter_clone.fetch_add(count, Ordering::SeqCst);
                 gst::PadProbeReturn::Ok
             },
         );
 
-        let source = match source.as_str() {
+        let source_name = source.as_str();
+        let source = match source_name {
             "udpsrc" => {
                 let source =
                     gst::ElementFactory::make("udpsrc", Some(format!("source-{}", i).as_str()))
@@ -90,13 +119,16 @@ fn main() {
 
                 source
             }
-            "ts-udpsrc" => {
+            "ts-udpsrc" | "ts-udpsrc-batched" | "ts-udprelay" | "ts-udprelay-batched" => {
                 let source =
                     gst::ElementFactory::make("ts-udpsrc", Some(format!("source-{}", i).as_str()))
                         .unwrap();
                 source.set_property("port", 40000i32 + i as i32);
                 source.set_property("context", format!("context-{}", (i as u32) % n_groups));
                 source.set_property("context-wait", wait);
+                if source_name.ends_with("-batched") {
+                    source.set_property("batch-size", BATCH_SIZE);
+                }
 
                 source
             }
@@ -136,11 +168,25 @@ fn main() {
 
                 source
             }
-            "ts-tonesrc" => {
-                let source =
-                    gst::ElementFactory::make("ts-tonesrc", Some(format!("source-{}", i).as_str()))
-                        .unwrap();
-                source.set_property("samples-per-buffer", (wait as u32) * 8000 / 1000);
+            "ts-audiotestsrc" => {
+                let source = gst::ElementFactory::make(
+                    "ts-audiotestsrc",
+                    Some(format!("source-{}", i).as_str()),
+                )
+                .unwrap();
+                // One buffer per context wait at the default 48kHz sample rate
+                source.set_property("samples-per-buffer", wait.max(1) * 48_000 / 1000);
+                source.set_property("context", format!("context-{}", (i as u32) % n_groups));
+                source.set_property("context-wait", wait);
+
+                source
+            }
+            "ts-videotestsrc" => {
+                let source = gst::ElementFactory::make(
+                    "ts-videotestsrc",
+                    Some(format!("source-{}", i).as_str()),
+                )
+                .unwrap();
                 source.set_property("context", format!("context-{}", (i as u32) % n_groups));
                 source.set_property("context-wait", wait);
 
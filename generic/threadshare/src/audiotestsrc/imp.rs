@@ -0,0 +1,804 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use futures::future::BoxFuture;
+use futures::prelude::*;
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+
+use once_cell::sync::Lazy;
+
+use std::f64::consts::PI;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+use std::{i32, u32};
+
+use crate::runtime::prelude::*;
+use crate::runtime::{self, Context, PadSrc, PadSrcRef, PadSrcWeak, Task, Timer};
+
+const DEFAULT_CONTEXT: &str = "";
+const DEFAULT_CONTEXT_WAIT: Duration = Duration::ZERO;
+const DEFAULT_SAMPLES_PER_BUFFER: u32 = 1024;
+const DEFAULT_FREQ: f64 = 440.0;
+const DEFAULT_VOLUME: f64 = 0.8;
+const DEFAULT_IS_LIVE: bool = true;
+const DEFAULT_DO_TIMESTAMP: bool = false;
+const DEFAULT_NUM_BUFFERS: i32 = -1;
+
+const DEFAULT_RATE: i32 = 48_000;
+const DEFAULT_CHANNELS: i32 = 1;
+
+#[derive(Debug, Clone)]
+struct Settings {
+    context: String,
+    context_wait: Duration,
+    samples_per_buffer: u32,
+    freq: f64,
+    volume: f64,
+    is_live: bool,
+    do_timestamp: bool,
+    num_buffers: i32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            context: DEFAULT_CONTEXT.into(),
+            context_wait: DEFAULT_CONTEXT_WAIT,
+            samples_per_buffer: DEFAULT_SAMPLES_PER_BUFFER,
+            freq: DEFAULT_FREQ,
+            volume: DEFAULT_VOLUME,
+            is_live: DEFAULT_IS_LIVE,
+            do_timestamp: DEFAULT_DO_TIMESTAMP,
+            num_buffers: DEFAULT_NUM_BUFFERS,
+        }
+    }
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "ts-audiotestsrc",
+        gst::DebugColorFlags::empty(),
+        Some("Thread-sharing audio test source"),
+    )
+});
+
+#[derive(Debug, Clone, Copy)]
+struct AudioFormat {
+    rate: u32,
+    channels: u32,
+}
+
+#[derive(Debug, Default)]
+struct AudioTestSrcPadHandlerInner {
+    configured_caps: StdMutex<Option<gst::Caps>>,
+    latency: StdMutex<Option<gst::ClockTime>>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct AudioTestSrcPadHandler(Arc<AudioTestSrcPadHandlerInner>);
+
+impl AudioTestSrcPadHandler {
+    fn reset(&self) {
+        *self.0.configured_caps.lock().unwrap() = None;
+        *self.0.latency.lock().unwrap() = None;
+    }
+}
+
+impl PadSrcHandler for AudioTestSrcPadHandler {
+    type ElementImpl = AudioTestSrc;
+
+    fn src_event(
+        &self,
+        pad: &PadSrcRef,
+        audiotestsrc: &AudioTestSrc,
+        _element: &gst::Element,
+        event: gst::Event,
+    ) -> bool {
+        use gst::EventView;
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", event);
+
+        let ret = match event.view() {
+            EventView::FlushStart(..) => audiotestsrc.task.flush_start().is_ok(),
+            EventView::FlushStop(..) => audiotestsrc.task.flush_stop().is_ok(),
+            EventView::Reconfigure(..) => true,
+            EventView::Latency(..) => true,
+            _ => false,
+        };
+
+        if ret {
+            gst::log!(CAT, obj: pad.gst_pad(), "Handled {:?}", event);
+        } else {
+            gst::log!(CAT, obj: pad.gst_pad(), "Didn't handle {:?}", event);
+        }
+
+        ret
+    }
+
+    fn src_query(
+        &self,
+        pad: &PadSrcRef,
+        audiotestsrc: &AudioTestSrc,
+        _element: &gst::Element,
+        query: &mut gst::QueryRef,
+    ) -> bool {
+        use gst::QueryViewMut;
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", query);
+        let ret = match query.view_mut() {
+            QueryViewMut::Latency(q) => {
+                if audiotestsrc.settings.lock().unwrap().is_live {
+                    let latency = self
+                        .0
+                        .latency
+                        .lock()
+                        .unwrap()
+                        .unwrap_or(gst::ClockTime::ZERO);
+                    q.set(true, latency, latency);
+                } else {
+                    q.set(false, gst::ClockTime::ZERO, gst::ClockTime::NONE);
+                }
+                true
+            }
+            QueryViewMut::Scheduling(q) => {
+                q.set(gst::SchedulingFlags::SEQUENTIAL, 1, -1, 0);
+                q.add_scheduling_modes(&[gst::PadMode::Push]);
+                true
+            }
+            QueryViewMut::Caps(q) => {
+                let caps = if let Some(caps) = self.0.configured_caps.lock().unwrap().as_ref() {
+                    q.filter()
+                        .map(|f| f.intersect_with_mode(caps, gst::CapsIntersectMode::First))
+                        .unwrap_or_else(|| caps.clone())
+                } else {
+                    let caps = pad.gst_pad().pad_template_caps();
+                    q.filter()
+                        .map(|f| f.intersect_with_mode(&caps, gst::CapsIntersectMode::First))
+                        .unwrap_or(caps)
+                };
+
+                q.set_result(&caps);
+
+                true
+            }
+            _ => false,
+        };
+
+        if ret {
+            gst::log!(CAT, obj: pad.gst_pad(), "Handled {:?}", query);
+        } else {
+            gst::log!(CAT, obj: pad.gst_pad(), "Didn't handle {:?}", query);
+        }
+        ret
+    }
+}
+
+#[derive(Debug)]
+struct AudioTestSrcTask {
+    element: super::AudioTestSrc,
+    src_pad: PadSrcWeak,
+    src_pad_handler: AudioTestSrcPadHandler,
+    need_initial_events: bool,
+    need_segment: bool,
+    format: Option<AudioFormat>,
+    interval: Option<Timer>,
+    start_running_time: Option<gst::ClockTime>,
+    accumulator: f64,
+    n_samples: u64,
+    n_buffers: u64,
+}
+
+impl AudioTestSrcTask {
+    fn new(
+        element: &super::AudioTestSrc,
+        src_pad: &PadSrc,
+        src_pad_handler: &AudioTestSrcPadHandler,
+    ) -> Self {
+        AudioTestSrcTask {
+            element: element.clone(),
+            src_pad: src_pad.downgrade(),
+            src_pad_handler: src_pad_handler.clone(),
+            need_initial_events: true,
+            need_segment: true,
+            format: None,
+            interval: None,
+            start_running_time: None,
+            accumulator: 0.0,
+            n_samples: 0,
+            n_buffers: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.need_initial_events = true;
+        self.need_segment = true;
+        self.format = None;
+        self.interval = None;
+        self.start_running_time = None;
+        self.accumulator = 0.0;
+        self.n_samples = 0;
+        self.n_buffers = 0;
+
+        self.src_pad_handler.reset();
+    }
+
+    async fn negotiate(&mut self, pad: &PadSrcRef<'_>) -> Result<AudioFormat, gst::FlowError> {
+        let templ_caps = pad.gst_pad().pad_template_caps();
+        let mut caps = pad.gst_pad().peer_query_caps(Some(&templ_caps));
+        if caps.is_any() {
+            caps = templ_caps;
+        }
+
+        gst::debug!(CAT, obj: pad.gst_pad(), "Negotiating from {:?}", caps);
+
+        if caps.is_empty() {
+            gst::element_error!(
+                &self.element,
+                gst::CoreError::Negotiation,
+                ["No supported caps downstream"]
+            );
+            return Err(gst::FlowError::NotNegotiated);
+        }
+
+        caps.truncate();
+        {
+            let caps = caps.make_mut();
+            let s = caps.structure_mut(0).unwrap();
+            s.fixate_field_nearest_int("rate", DEFAULT_RATE);
+            s.fixate_field_nearest_int("channels", DEFAULT_CHANNELS);
+        }
+        caps.fixate();
+
+        let format = {
+            let s = caps.structure(0).unwrap();
+            AudioFormat {
+                rate: s
+                    .get::<i32>("rate")
+                    .map_err(|_| gst::FlowError::NotNegotiated)? as u32,
+                channels: s
+                    .get::<i32>("channels")
+                    .map_err(|_| gst::FlowError::NotNegotiated)? as u32,
+            }
+        };
+
+        gst::debug!(CAT, obj: pad.gst_pad(), "Configuring {:?}", caps);
+
+        pad.push_event(gst::event::Caps::new(&caps)).await;
+        *self.src_pad_handler.0.configured_caps.lock().unwrap() = Some(caps);
+
+        Ok(format)
+    }
+
+    async fn push_prelude(&mut self, pad: &PadSrcRef<'_>) -> Result<AudioFormat, gst::FlowError> {
+        if self.need_initial_events {
+            gst::debug!(CAT, obj: pad.gst_pad(), "Pushing initial events");
+
+            let stream_id = format!("{:08x}{:08x}", rand::random::<u32>(), rand::random::<u32>());
+            let stream_start_evt = gst::event::StreamStart::builder(&stream_id)
+                .group_id(gst::GroupId::next())
+                .build();
+            pad.push_event(stream_start_evt).await;
+
+            self.format = Some(self.negotiate(pad).await?);
+            self.need_initial_events = false;
+        }
+
+        if self.need_segment {
+            let segment_evt =
+                gst::event::Segment::new(&gst::FormattedSegment::<gst::format::Time>::new());
+            pad.push_event(segment_evt).await;
+
+            self.need_segment = false;
+        }
+
+        Ok(self.format.expect("negotiated"))
+    }
+
+    fn generate(&mut self, format: AudioFormat, settings: &Settings) -> gst::Buffer {
+        let n_samples = settings.samples_per_buffer as u64;
+        let frame_size = format.channels as usize * 2;
+
+        let mut buffer = gst::Buffer::with_size(n_samples as usize * frame_size).unwrap();
+        {
+            let buffer = buffer.get_mut().unwrap();
+
+            // Carry the phase around rather than deriving it from the sample offset,
+            // which would lose precision as the offset grows
+            let step = 2.0 * PI * settings.freq / format.rate as f64;
+            let amplitude = settings.volume * i16::MAX as f64;
+
+            let mut map = buffer.map_writable().unwrap();
+            for frame in map.chunks_exact_mut(frame_size) {
+                let value = ((self.accumulator.sin() * amplitude) as i16).to_le_bytes();
+                for sample in frame.chunks_exact_mut(2) {
+                    sample.copy_from_slice(&value);
+                }
+
+                self.accumulator += step;
+                if self.accumulator >= 2.0 * PI {
+                    self.accumulator -= 2.0 * PI;
+                }
+            }
+            drop(map);
+
+            let start = self.start_running_time.unwrap_or(gst::ClockTime::ZERO);
+            let pts = start
+                + gst::ClockTime::SECOND
+                    .mul_div_floor(self.n_samples, format.rate as u64)
+                    .unwrap();
+            let next_pts = start
+                + gst::ClockTime::SECOND
+                    .mul_div_floor(self.n_samples + n_samples, format.rate as u64)
+                    .unwrap();
+
+            if settings.do_timestamp {
+                buffer.set_pts(self.element.current_running_time().or(Some(pts)));
+            } else {
+                buffer.set_pts(pts);
+            }
+            buffer.set_duration(next_pts - pts);
+            buffer.set_offset(self.n_samples);
+            buffer.set_offset_end(self.n_samples + n_samples);
+
+            if self.n_buffers == 0 {
+                buffer.set_flags(gst::BufferFlags::DISCONT);
+            }
+        }
+
+        self.n_samples += n_samples;
+        self.n_buffers += 1;
+
+        buffer
+    }
+}
+
+impl TaskImpl for AudioTestSrcTask {
+    fn start(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Starting task");
+
+            // Pacing restarts from now when resuming after a pause
+            self.interval = None;
+
+            gst::log!(CAT, obj: &self.element, "Task started");
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn iterate(&mut self) -> BoxFuture<'_, Result<(), gst::FlowError>> {
+        async move {
+            let pad = self.src_pad.upgrade().expect("PadSrc no longer exists");
+            let format = self.push_prelude(&pad).await?;
+
+            let settings = self.element.imp().settings.lock().unwrap().clone();
+
+            if settings.num_buffers >= 0 && self.n_buffers >= settings.num_buffers as u64 {
+                gst::debug!(CAT, obj: &self.element, "Reached num-buffers, pushing EOS");
+                pad.push_event(gst::event::Eos::new()).await;
+                return Err(gst::FlowError::Eos);
+            }
+
+            if settings.is_live {
+                if self.start_running_time.is_none() {
+                    self.start_running_time = self.element.current_running_time();
+                }
+
+                if self.interval.is_none() {
+                    let period = gst::ClockTime::SECOND
+                        .mul_div_floor(settings.samples_per_buffer as u64, format.rate as u64)
+                        .unwrap();
+
+                    let prev_latency = self
+                        .src_pad_handler
+                        .0
+                        .latency
+                        .lock()
+                        .unwrap()
+                        .replace(period);
+                    if prev_latency != Some(period) {
+                        let _ = self.element.post_message(
+                            gst::message::Latency::builder().src(&self.element).build(),
+                        );
+                    }
+
+                    self.interval = Some(runtime::time::interval(period.into()));
+                }
+
+                // A buffer can only be complete once its whole duration has elapsed
+                self.interval.as_mut().unwrap().next().await;
+            }
+
+            let buffer = self.generate(format, &settings);
+
+            gst::log!(CAT, obj: &self.element, "Pushing {:?}", buffer);
+            let res = pad.push(buffer).await;
+            match res {
+                Ok(_) => {
+                    gst::log!(CAT, obj: &self.element, "Successfully pushed buffer");
+                }
+                Err(gst::FlowError::Flushing) => {
+                    gst::debug!(CAT, obj: &self.element, "Flushing");
+                }
+                Err(gst::FlowError::Eos) => {
+                    gst::debug!(CAT, obj: &self.element, "EOS");
+                    pad.push_event(gst::event::Eos::new()).await;
+                }
+                Err(err) => {
+                    gst::error!(CAT, obj: &self.element, "Got error {}", err);
+                    gst::element_error!(
+                        &self.element,
+                        gst::StreamError::Failed,
+                        ("Internal data stream error"),
+                        ["streaming stopped, reason {}", err]
+                    );
+                }
+            }
+
+            res.map(drop)
+        }
+        .boxed()
+    }
+
+    fn stop(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Stopping task");
+
+            self.reset();
+
+            gst::log!(CAT, obj: &self.element, "Task stopped");
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn flush_start(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Starting task flush");
+
+            self.need_segment = true;
+
+            gst::log!(CAT, obj: &self.element, "Task flush started");
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+#[derive(Debug)]
+pub struct AudioTestSrc {
+    src_pad: PadSrc,
+    src_pad_handler: AudioTestSrcPadHandler,
+    task: Task,
+    settings: StdMutex<Settings>,
+}
+
+impl AudioTestSrc {
+    fn prepare(&self, element: &super::AudioTestSrc) -> Result<(), gst::ErrorMessage> {
+        let settings = self.settings.lock().unwrap().clone();
+        gst::debug!(CAT, obj: element, "Preparing");
+
+        let context =
+            Context::acquire(&settings.context, settings.context_wait).map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::OpenRead,
+                    ["Failed to acquire Context: {}", err]
+                )
+            })?;
+
+        self.task
+            .prepare(
+                AudioTestSrcTask::new(element, &self.src_pad, &self.src_pad_handler),
+                context,
+            )
+            .map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::OpenRead,
+                    ["Error preparing Task: {:?}", err]
+                )
+            })?;
+
+        gst::debug!(CAT, obj: element, "Prepared");
+
+        Ok(())
+    }
+
+    fn unprepare(&self, element: &super::AudioTestSrc) {
+        gst::debug!(CAT, obj: element, "Unpreparing");
+        self.task.unprepare().unwrap();
+        gst::debug!(CAT, obj: element, "Unprepared");
+    }
+
+    fn stop(&self, element: &super::AudioTestSrc) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Stopping");
+        self.task.stop()?;
+        gst::debug!(CAT, obj: element, "Stopped");
+        Ok(())
+    }
+
+    fn start(&self, element: &super::AudioTestSrc) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Starting");
+        self.task.start()?;
+        gst::debug!(CAT, obj: element, "Started");
+        Ok(())
+    }
+
+    fn pause(&self, element: &super::AudioTestSrc) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Pausing");
+        self.task.pause()?;
+        gst::debug!(CAT, obj: element, "Paused");
+        Ok(())
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for AudioTestSrc {
+    const NAME: &'static str = "RsTsAudioTestSrc";
+    type Type = super::AudioTestSrc;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        let src_pad_handler = AudioTestSrcPadHandler::default();
+
+        Self {
+            src_pad: PadSrc::new(
+                gst::Pad::from_template(&klass.pad_template("src").unwrap(), Some("src")),
+                src_pad_handler.clone(),
+            ),
+            src_pad_handler,
+            task: Task::default(),
+            settings: StdMutex::new(Settings::default()),
+        }
+    }
+}
+
+impl ObjectImpl for AudioTestSrc {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecString::new(
+                    "context",
+                    "Context",
+                    "Context name to share threads with",
+                    Some(DEFAULT_CONTEXT),
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "context-wait",
+                    "Context Wait",
+                    "Throttle poll loop to run at most once every this many ms",
+                    0,
+                    1000,
+                    DEFAULT_CONTEXT_WAIT.as_millis() as u32,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "samples-per-buffer",
+                    "Samples Per Buffer",
+                    "Number of samples in each outgoing buffer",
+                    1,
+                    u32::MAX,
+                    DEFAULT_SAMPLES_PER_BUFFER,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecDouble::new(
+                    "freq",
+                    "Frequency",
+                    "Frequency of the sine wave in Hz",
+                    0.0,
+                    20_000.0,
+                    DEFAULT_FREQ,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecDouble::new(
+                    "volume",
+                    "Volume",
+                    "Volume of the sine wave",
+                    0.0,
+                    1.0,
+                    DEFAULT_VOLUME,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "is-live",
+                    "Is Live",
+                    "Pace buffers in real time and timestamp them in running time",
+                    DEFAULT_IS_LIVE,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "do-timestamp",
+                    "Do Timestamp",
+                    "Timestamp buffers with the current running time when they are generated",
+                    DEFAULT_DO_TIMESTAMP,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecInt::new(
+                    "num-buffers",
+                    "Num Buffers",
+                    "Number of buffers to output before sending EOS (-1 = unlimited)",
+                    -1,
+                    i32::MAX,
+                    DEFAULT_NUM_BUFFERS,
+                    glib::ParamFlags::READWRITE,
+                ),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        let mut settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "context" => {
+                settings.context = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream")
+                    .unwrap_or_else(|| DEFAULT_CONTEXT.into());
+            }
+            "context-wait" => {
+                settings.context_wait = Duration::from_millis(
+                    value.get::<u32>().expect("type checked upstream").into(),
+                );
+            }
+            "samples-per-buffer" => {
+                settings.samples_per_buffer = value.get().expect("type checked upstream");
+            }
+            "freq" => {
+                settings.freq = value.get().expect("type checked upstream");
+            }
+            "volume" => {
+                settings.volume = value.get().expect("type checked upstream");
+            }
+            "is-live" => {
+                settings.is_live = value.get().expect("type checked upstream");
+            }
+            "do-timestamp" => {
+                settings.do_timestamp = value.get().expect("type checked upstream");
+            }
+            "num-buffers" => {
+                settings.num_buffers = value.get().expect("type checked upstream");
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        let settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "context" => settings.context.to_value(),
+            "context-wait" => (settings.context_wait.as_millis() as u32).to_value(),
+            "samples-per-buffer" => settings.samples_per_buffer.to_value(),
+            "freq" => settings.freq.to_value(),
+            "volume" => settings.volume.to_value(),
+            "is-live" => settings.is_live.to_value(),
+            "do-timestamp" => settings.do_timestamp.to_value(),
+            "num-buffers" => settings.num_buffers.to_value(),
+            _ => unimplemented!(),
+        }
+    }
+
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(self.src_pad.gst_pad()).unwrap();
+
+        crate::set_element_flags(obj, gst::ElementFlags::SOURCE);
+    }
+}
+
+impl GstObjectImpl for AudioTestSrc {}
+
+impl ElementImpl for AudioTestSrc {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Thread-sharing audio test source",
+                "Source/Audio",
+                "Generates a sine wave on a thread-sharing context",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::builder("audio/x-raw")
+                .field("format", "S16LE")
+                .field("layout", "interleaved")
+                .field("rate", gst::IntRange::new(1, i32::MAX))
+                .field("channels", gst::IntRange::new(1, i32::MAX))
+                .build();
+            let src_pad_template = gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![src_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        let is_live = self.settings.lock().unwrap().is_live;
+
+        match transition {
+            gst::StateChange::NullToReady => {
+                self.prepare(element).map_err(|err| {
+                    element.post_error_message(err);
+                    gst::StateChangeError
+                })?;
+            }
+            gst::StateChange::PlayingToPaused if is_live => {
+                self.pause(element).map_err(|_| gst::StateChangeError)?;
+            }
+            gst::StateChange::ReadyToNull => {
+                self.unprepare(element);
+            }
+            _ => (),
+        }
+
+        let mut success = self.parent_change_state(element, transition)?;
+
+        match transition {
+            gst::StateChange::ReadyToPaused => {
+                if is_live {
+                    success = gst::StateChangeSuccess::NoPreroll;
+                } else {
+                    self.start(element).map_err(|_| gst::StateChangeError)?;
+                }
+            }
+            gst::StateChange::PausedToPlaying if is_live => {
+                self.start(element).map_err(|_| gst::StateChangeError)?;
+            }
+            gst::StateChange::PlayingToPaused if is_live => {
+                success = gst::StateChangeSuccess::NoPreroll;
+            }
+            gst::StateChange::PausedToReady => {
+                self.stop(element).map_err(|_| gst::StateChangeError)?;
+            }
+            _ => (),
+        }
+
+        Ok(success)
+    }
+}
@@ -17,8 +17,10 @@
 //
 // SPDX-License-Identifier: LGPL-2.1-or-later
 
+use futures::channel::oneshot;
 use futures::future::{self, abortable, AbortHandle};
 
+use gst::glib;
 use gst::prelude::*;
 
 use once_cell::sync::Lazy;
@@ -36,7 +38,7 @@ static DATA_QUEUE_CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
     )
 });
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum DataQueueItem {
     Buffer(gst::Buffer),
     BufferList(gst::BufferList),
@@ -70,6 +72,27 @@ pub enum DataQueueState {
     Stopped,
 }
 
+/// What to do with buffers when the `DataQueue` is full.
+///
+/// Events are never dropped.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, glib::Enum)]
+#[repr(u32)]
+#[enum_type(name = "GstTsQueueLeaky")]
+pub enum DataQueueLeaky {
+    #[enum_value(name = "Not Leaky", nick = "no")]
+    No = 0,
+    #[enum_value(name = "Leaky on upstream (new buffers)", nick = "upstream")]
+    Upstream = 1,
+    #[enum_value(name = "Leaky on downstream (old buffers)", nick = "downstream")]
+    Downstream = 2,
+}
+
+impl Default for DataQueueLeaky {
+    fn default() -> Self {
+        DataQueueLeaky::No
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct DataQueue(Arc<StdMutex<DataQueueInner>>);
 
@@ -86,8 +109,11 @@ struct DataQueueInner {
     max_size_buffers: Option<u32>,
     max_size_bytes: Option<u32>,
     max_size_time: Option<gst::ClockTime>,
+    leaky: DataQueueLeaky,
+    dropped: u64,
 
     pending_handle: Option<AbortHandle>,
+    space_waiters: Vec<oneshot::Sender<()>>,
 }
 
 impl DataQueueInner {
@@ -96,6 +122,104 @@ impl DataQueueInner {
             pending_handle.abort();
         }
     }
+
+    fn notify_space(&mut self) {
+        self.space_waiters.clear();
+    }
+
+    fn is_full(&self, ts: Option<gst::ClockTime>) -> bool {
+        if let Some(max) = self.max_size_buffers {
+            if max <= self.cur_size_buffers {
+                gst::debug!(DATA_QUEUE_CAT, obj: &self.element, "Queue is full (buffers): {} <= {}", max, self.cur_size_buffers);
+                return true;
+            }
+        }
+
+        if let Some(max) = self.max_size_bytes {
+            if max <= self.cur_size_bytes {
+                gst::debug!(DATA_QUEUE_CAT, obj: &self.element, "Queue is full (bytes): {} <= {}", max, self.cur_size_bytes);
+                return true;
+            }
+        }
+
+        // FIXME: Use running time
+        let queue_ts = self.queue.iter().find_map(|i| i.timestamp());
+        if let (Some(max), Some(queue_ts), Some(ts)) = (self.max_size_time, queue_ts, ts) {
+            let level = if queue_ts > ts {
+                queue_ts - ts
+            } else {
+                ts - queue_ts
+            };
+
+            if max <= level {
+                gst::debug!(DATA_QUEUE_CAT, obj: &self.element, "Queue is full (time): {} <= {}", max, level);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Drops the oldest buffer or buffer list, keeping the events.
+    fn drop_oldest(&mut self) -> bool {
+        let idx = match self
+            .queue
+            .iter()
+            .position(|item| !matches!(item, DataQueueItem::Event(_)))
+        {
+            Some(idx) => idx,
+            None => return false,
+        };
+
+        let item = self.queue.remove(idx).unwrap();
+        gst::debug!(DATA_QUEUE_CAT, obj: &self.element, "Queue is full, dropping old {:?}", item);
+
+        let (count, bytes) = item.size();
+        self.cur_size_buffers -= count;
+        self.cur_size_bytes -= bytes;
+        self.dropped += u64::from(count);
+
+        true
+    }
+
+    fn push(&mut self, item: DataQueueItem) -> Result<(), DataQueueItem> {
+        if self.state == DataQueueState::Stopped {
+            gst::debug!(
+                DATA_QUEUE_CAT,
+                obj: &self.element,
+                "Rejecting item {:?} in state {:?}",
+                item,
+                self.state
+            );
+            return Err(item);
+        }
+
+        gst::debug!(DATA_QUEUE_CAT, obj: &self.element, "Pushing item {:?}", item);
+
+        let (count, bytes) = item.size();
+        let ts = item.timestamp();
+
+        if self.is_full(ts) {
+            match self.leaky {
+                DataQueueLeaky::No => return Err(item),
+                _ if matches!(item, DataQueueItem::Event(_)) => (),
+                DataQueueLeaky::Upstream => {
+                    gst::debug!(DATA_QUEUE_CAT, obj: &self.element, "Queue is full, dropping new {:?}", item);
+                    self.dropped += u64::from(count);
+                    return Ok(());
+                }
+                DataQueueLeaky::Downstream => while self.is_full(ts) && self.drop_oldest() {},
+            }
+        }
+
+        self.queue.push_back(item);
+        self.cur_size_buffers += count;
+        self.cur_size_bytes += bytes;
+
+        self.wake();
+
+        Ok(())
+    }
 }
 
 impl DataQueue {
@@ -116,10 +240,22 @@ impl DataQueue {
             max_size_buffers,
             max_size_bytes,
             max_size_time: max_size_time.into(),
+            leaky: DataQueueLeaky::default(),
+            dropped: 0,
             pending_handle: None,
+            space_waiters: Vec::new(),
         })))
     }
 
+    pub fn set_leaky(&self, leaky: DataQueueLeaky) {
+        self.0.lock().unwrap().leaky = leaky;
+    }
+
+    /// Returns the number of buffers dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.0.lock().unwrap().dropped
+    }
+
     pub fn state(&self) -> DataQueueState {
         self.0.lock().unwrap().state
     }
@@ -144,6 +280,7 @@ impl DataQueue {
         gst::debug!(DATA_QUEUE_CAT, obj: &inner.element, "Stopping data queue");
         inner.state = DataQueueState::Stopped;
         inner.wake();
+        inner.notify_space();
     }
 
     pub fn clear(&self) {
@@ -162,65 +299,45 @@ impl DataQueue {
                 }
             }
         }
+        // Nothing is queued anymore: reset the level and wake up pending pushes
+        inner.cur_size_buffers = 0;
+        inner.cur_size_bytes = 0;
+        inner.notify_space();
 
         gst::debug!(DATA_QUEUE_CAT, obj: &inner.element, "Data queue cleared");
     }
 
     pub fn push(&self, item: DataQueueItem) -> Result<(), DataQueueItem> {
-        let mut inner = self.0.lock().unwrap();
-
-        if inner.state == DataQueueState::Stopped {
-            gst::debug!(
-                DATA_QUEUE_CAT,
-                obj: &inner.element,
-                "Rejecting item {:?} in state {:?}",
-                item,
-                inner.state
-            );
-            return Err(item);
-        }
-
-        gst::debug!(DATA_QUEUE_CAT, obj: &inner.element, "Pushing item {:?}", item);
+        self.0.lock().unwrap().push(item)
+    }
 
-        let (count, bytes) = item.size();
-        let queue_ts = inner.queue.iter().filter_map(|i| i.timestamp()).next();
-        let ts = item.timestamp();
+    /// Pushes `item`, waiting for room in the queue if needed.
+    ///
+    /// Returns the item back if the queue is or gets stopped.
+    pub async fn push_wait(&self, mut item: DataQueueItem) -> Result<(), DataQueueItem> {
+        loop {
+            let space_receiver = {
+                let mut inner = self.0.lock().unwrap();
+                if inner.state == DataQueueState::Stopped {
+                    return Err(item);
+                }
 
-        if let Some(max) = inner.max_size_buffers {
-            if max <= inner.cur_size_buffers {
-                gst::debug!(DATA_QUEUE_CAT, obj: &inner.element, "Queue is full (buffers): {} <= {}", max, inner.cur_size_buffers);
-                return Err(item);
-            }
-        }
+                match inner.push(item) {
+                    Ok(()) => return Ok(()),
+                    Err(failed_item) => {
+                        item = failed_item;
 
-        if let Some(max) = inner.max_size_bytes {
-            if max <= inner.cur_size_bytes {
-                gst::debug!(DATA_QUEUE_CAT, obj: &inner.element, "Queue is full (bytes): {} <= {}", max, inner.cur_size_bytes);
-                return Err(item);
-            }
-        }
+                        gst::log!(DATA_QUEUE_CAT, obj: &inner.element, "Waiting for room in the queue");
+                        let (sender, receiver) = oneshot::channel();
+                        inner.space_waiters.push(sender);
 
-        // FIXME: Use running time
-        if let (Some(max), Some(queue_ts), Some(ts)) = (inner.max_size_time, queue_ts, ts) {
-            let level = if queue_ts > ts {
-                queue_ts - ts
-            } else {
-                ts - queue_ts
+                        receiver
+                    }
+                }
             };
 
-            if max <= level {
-                gst::debug!(DATA_QUEUE_CAT, obj: &inner.element, "Queue is full (time): {} <= {}", max, level);
-                return Err(item);
-            }
+            let _ = space_receiver.await;
         }
-
-        inner.queue.push_back(item);
-        inner.cur_size_buffers += count;
-        inner.cur_size_bytes += bytes;
-
-        inner.wake();
-
-        Ok(())
     }
 
     // TODO: implement as a Stream now that we use a StdMutex
@@ -240,6 +357,7 @@ impl DataQueue {
                             let (count, bytes) = item.size();
                             inner.cur_size_buffers -= count;
                             inner.cur_size_bytes -= bytes;
+                            inner.notify_space();
 
                             return Some(item);
                         }
@@ -0,0 +1,493 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use futures::future::{AbortHandle, Abortable, BoxFuture};
+use futures::prelude::*;
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+use gst::EventView;
+
+use once_cell::sync::Lazy;
+
+use crate::runtime::prelude::*;
+use crate::runtime::{self, PadSink, PadSinkRef};
+
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+use std::u32;
+
+const DEFAULT_SYNC: bool = false;
+const DEFAULT_SIGNAL_HANDOFFS: bool = false;
+const DEFAULT_STATS_INTERVAL: u32 = 0;
+
+#[derive(Debug, Clone)]
+struct Settings {
+    sync: bool,
+    signal_handoffs: bool,
+    stats_interval: Duration,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            sync: DEFAULT_SYNC,
+            signal_handoffs: DEFAULT_SIGNAL_HANDOFFS,
+            stats_interval: Duration::from_millis(DEFAULT_STATS_INTERVAL as u64),
+        }
+    }
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "ts-fakesink",
+        gst::DebugColorFlags::empty(),
+        Some("Thread-sharing fake sink"),
+    )
+});
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Stats {
+    num_buffers: u64,
+    num_bytes: u64,
+    num_late: u64,
+}
+
+impl Stats {
+    fn to_structure(self) -> gst::Structure {
+        gst::Structure::builder("application/x-ts-fakesink-stats")
+            .field("num-buffers", self.num_buffers)
+            .field("num-bytes", self.num_bytes)
+            .field("num-late", self.num_late)
+            .build()
+    }
+}
+
+#[derive(Debug, Default)]
+struct FakeSinkPadHandlerInner {
+    segment: Option<gst::FormattedSegment<gst::ClockTime>>,
+    latency: Option<gst::ClockTime>,
+    flushing: bool,
+    sync_abort_handle: Option<AbortHandle>,
+    stats: Stats,
+    last_stats: Option<Instant>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct FakeSinkPadHandler(Arc<StdMutex<FakeSinkPadHandlerInner>>);
+
+impl FakeSinkPadHandler {
+    fn set_latency(&self, latency: gst::ClockTime) {
+        self.0.lock().unwrap().latency = Some(latency);
+    }
+
+    fn set_flushing(&self, flushing: bool) {
+        let mut inner = self.0.lock().unwrap();
+        inner.flushing = flushing;
+        if let Some(abort_handle) = inner.sync_abort_handle.take() {
+            abort_handle.abort();
+        }
+    }
+
+    fn reset(&self) {
+        *self.0.lock().unwrap() = FakeSinkPadHandlerInner::default();
+    }
+
+    fn stats(&self) -> Stats {
+        self.0.lock().unwrap().stats
+    }
+
+    /* Wait until the running time of the buffer is reached */
+    async fn sync(
+        &self,
+        element: &super::FakeSink,
+        pts: Option<gst::ClockTime>,
+    ) -> Result<(), gst::FlowError> {
+        let (timer, abort_registration) = {
+            let mut inner = self.0.lock().unwrap();
+
+            let running_time = inner
+                .segment
+                .as_ref()
+                .and_then(|segment| segment.to_running_time(pts))
+                .opt_add(inner.latency);
+
+            let timer = match runtime::delay_until_running_time(element, running_time) {
+                Some(timer) => timer,
+                None => {
+                    if running_time.opt_lt(element.current_running_time()) == Some(true) {
+                        inner.stats.num_late += 1;
+                    }
+                    return Ok(());
+                }
+            };
+
+            let (abort_handle, abort_registration) = AbortHandle::new_pair();
+            inner.sync_abort_handle = Some(abort_handle);
+
+            (timer, abort_registration)
+        };
+
+        let res = Abortable::new(timer, abort_registration).await;
+        self.0.lock().unwrap().sync_abort_handle = None;
+
+        res.map_err(|_| {
+            gst::debug!(CAT, obj: element, "Sync aborted, flushing");
+            gst::FlowError::Flushing
+        })
+    }
+
+    async fn render(
+        &self,
+        element: &super::FakeSink,
+        buffer: gst::Buffer,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        let settings = element.imp().settings.lock().unwrap().clone();
+
+        if self.0.lock().unwrap().flushing {
+            return Err(gst::FlowError::Flushing);
+        }
+
+        if settings.sync {
+            self.sync(element, buffer.pts()).await?;
+        }
+
+        gst::log!(CAT, obj: element, "Rendering {:?}", buffer);
+
+        if settings.signal_handoffs {
+            element.emit_by_name::<()>("handoff", &[&buffer, &element.imp().sink_pad.gst_pad()]);
+        }
+
+        let stats = {
+            let mut inner = self.0.lock().unwrap();
+            inner.stats.num_buffers += 1;
+            inner.stats.num_bytes += buffer.size() as u64;
+
+            if settings.stats_interval.is_zero() {
+                None
+            } else {
+                let now = Instant::now();
+                match inner.last_stats {
+                    Some(last_stats) if now - last_stats < settings.stats_interval => None,
+                    _ => {
+                        inner.last_stats = Some(now);
+                        Some(inner.stats)
+                    }
+                }
+            }
+        };
+
+        if let Some(stats) = stats {
+            element.emit_by_name::<()>("stats", &[&stats.to_structure()]);
+        }
+
+        Ok(gst::FlowSuccess::Ok)
+    }
+}
+
+impl PadSinkHandler for FakeSinkPadHandler {
+    type ElementImpl = FakeSink;
+
+    fn sink_chain(
+        &self,
+        _pad: &PadSinkRef,
+        _fakesink: &FakeSink,
+        element: &gst::Element,
+        buffer: gst::Buffer,
+    ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
+        let this = self.clone();
+        let element = element.clone().downcast::<super::FakeSink>().unwrap();
+
+        async move { this.render(&element, buffer).await }.boxed()
+    }
+
+    fn sink_chain_list(
+        &self,
+        _pad: &PadSinkRef,
+        _fakesink: &FakeSink,
+        element: &gst::Element,
+        list: gst::BufferList,
+    ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
+        let this = self.clone();
+        let element = element.clone().downcast::<super::FakeSink>().unwrap();
+
+        async move {
+            for buffer in list.iter_owned() {
+                this.render(&element, buffer).await?;
+            }
+
+            Ok(gst::FlowSuccess::Ok)
+        }
+        .boxed()
+    }
+
+    fn sink_event_serialized(
+        &self,
+        _pad: &PadSinkRef,
+        _fakesink: &FakeSink,
+        element: &gst::Element,
+        event: gst::Event,
+    ) -> BoxFuture<'static, bool> {
+        let this = self.clone();
+        let element = element.clone().downcast::<super::FakeSink>().unwrap();
+
+        async move {
+            gst::log!(CAT, obj: &element, "Handling {:?}", event);
+
+            match event.view() {
+                EventView::FlushStop(_) => {
+                    this.set_flushing(false);
+                    this.0.lock().unwrap().segment = None;
+                }
+                EventView::Segment(e) => {
+                    this.0.lock().unwrap().segment =
+                        e.segment().downcast_ref::<gst::format::Time>().cloned();
+                }
+                EventView::Eos(_) => {
+                    let _ =
+                        element.post_message(gst::message::Eos::builder().src(&element).build());
+                }
+                EventView::SinkMessage(e) => {
+                    let _ = element.post_message(e.message());
+                }
+                _ => (),
+            }
+
+            true
+        }
+        .boxed()
+    }
+
+    fn sink_event(
+        &self,
+        _pad: &PadSinkRef,
+        _fakesink: &FakeSink,
+        _element: &gst::Element,
+        event: gst::Event,
+    ) -> bool {
+        if let EventView::FlushStart(..) = event.view() {
+            self.set_flushing(true);
+        }
+
+        true
+    }
+}
+
+#[derive(Debug)]
+pub struct FakeSink {
+    sink_pad: PadSink,
+    sink_pad_handler: FakeSinkPadHandler,
+    settings: StdMutex<Settings>,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for FakeSink {
+    const NAME: &'static str = "RsTsFakeSink";
+    type Type = super::FakeSink;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        let sink_pad_handler = FakeSinkPadHandler::default();
+
+        Self {
+            sink_pad: PadSink::new(
+                gst::Pad::from_template(&klass.pad_template("sink").unwrap(), Some("sink")),
+                sink_pad_handler.clone(),
+            ),
+            sink_pad_handler,
+            settings: StdMutex::new(Settings::default()),
+        }
+    }
+}
+
+impl ObjectImpl for FakeSink {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecBoolean::new(
+                    "sync",
+                    "Sync",
+                    "Sync on the clock",
+                    DEFAULT_SYNC,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "signal-handoffs",
+                    "Signal handoffs",
+                    "Send a signal before unreffing the buffer",
+                    DEFAULT_SIGNAL_HANDOFFS,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "stats-interval",
+                    "Stats Interval",
+                    "Emit the stats signal at most once every this many ms (0 = disabled)",
+                    0,
+                    u32::MAX,
+                    DEFAULT_STATS_INTERVAL,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoxed::new(
+                    "stats",
+                    "Statistics",
+                    "Various statistics",
+                    gst::Structure::static_type(),
+                    glib::ParamFlags::READABLE,
+                ),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn signals() -> &'static [glib::subclass::Signal] {
+        static SIGNALS: Lazy<Vec<glib::subclass::Signal>> = Lazy::new(|| {
+            vec![
+                glib::subclass::Signal::builder(
+                    "handoff",
+                    &[
+                        gst::Buffer::static_type().into(),
+                        gst::Pad::static_type().into(),
+                    ],
+                    glib::types::Type::UNIT.into(),
+                )
+                .build(),
+                glib::subclass::Signal::builder(
+                    "stats",
+                    &[gst::Structure::static_type().into()],
+                    glib::types::Type::UNIT.into(),
+                )
+                .build(),
+            ]
+        });
+
+        SIGNALS.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        let mut settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "sync" => {
+                settings.sync = value.get().expect("type checked upstream");
+            }
+            "signal-handoffs" => {
+                settings.signal_handoffs = value.get().expect("type checked upstream");
+            }
+            "stats-interval" => {
+                settings.stats_interval = Duration::from_millis(
+                    value.get::<u32>().expect("type checked upstream").into(),
+                );
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            "sync" => self.settings.lock().unwrap().sync.to_value(),
+            "signal-handoffs" => self.settings.lock().unwrap().signal_handoffs.to_value(),
+            "stats-interval" => {
+                (self.settings.lock().unwrap().stats_interval.as_millis() as u32).to_value()
+            }
+            "stats" => self.sink_pad_handler.stats().to_structure().to_value(),
+            _ => unimplemented!(),
+        }
+    }
+
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(self.sink_pad.gst_pad()).unwrap();
+
+        crate::set_element_flags(obj, gst::ElementFlags::SINK);
+    }
+}
+
+impl GstObjectImpl for FakeSink {}
+
+impl ElementImpl for FakeSink {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Thread-sharing fake sink",
+                "Sink",
+                "Thread-sharing sink discarding everything",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::new_any();
+
+            let sink_pad_template = gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![sink_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        if let gst::StateChange::PausedToReady = transition {
+            self.sink_pad_handler.set_flushing(true);
+        }
+
+        let success = self.parent_change_state(element, transition)?;
+
+        if let gst::StateChange::ReadyToPaused = transition {
+            self.sink_pad_handler.reset();
+        }
+
+        Ok(success)
+    }
+
+    fn send_event(&self, _element: &Self::Type, event: gst::Event) -> bool {
+        match event.view() {
+            EventView::Latency(ev) => {
+                self.sink_pad_handler.set_latency(ev.latency());
+                self.sink_pad.gst_pad().push_event(event)
+            }
+            EventView::Step(..) => false,
+            _ => self.sink_pad.gst_pad().push_event(event),
+        }
+    }
+}
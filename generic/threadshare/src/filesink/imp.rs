@@ -0,0 +1,606 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use futures::future::{AbortHandle, Abortable, BoxFuture};
+use futures::prelude::*;
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+use gst::EventView;
+
+use once_cell::sync::Lazy;
+
+use crate::runtime::prelude::*;
+use crate::runtime::{self, PadSink, PadSinkRef};
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+use std::u32;
+
+const DEFAULT_LOCATION: Option<PathBuf> = None;
+const DEFAULT_APPEND: bool = false;
+const DEFAULT_SYNC: bool = false;
+const DEFAULT_STATS_INTERVAL: u32 = 0;
+
+#[derive(Debug, Clone)]
+struct Settings {
+    location: Option<PathBuf>,
+    append: bool,
+    sync: bool,
+    stats_interval: Duration,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            location: DEFAULT_LOCATION,
+            append: DEFAULT_APPEND,
+            sync: DEFAULT_SYNC,
+            stats_interval: Duration::from_millis(DEFAULT_STATS_INTERVAL as u64),
+        }
+    }
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "ts-filesink",
+        gst::DebugColorFlags::empty(),
+        Some("Thread-sharing file sink"),
+    )
+});
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Stats {
+    num_buffers: u64,
+    num_bytes: u64,
+    num_late: u64,
+}
+
+impl Stats {
+    fn to_structure(self) -> gst::Structure {
+        gst::Structure::builder("application/x-ts-filesink-stats")
+            .field("num-buffers", self.num_buffers)
+            .field("num-bytes", self.num_bytes)
+            .field("num-late", self.num_late)
+            .build()
+    }
+}
+
+#[derive(Debug, Default)]
+struct FileSinkPadHandlerInner {
+    // Shared with the blocking pool, where it stays if a write gets cancelled
+    file: Option<Arc<StdMutex<File>>>,
+    segment: Option<gst::FormattedSegment<gst::ClockTime>>,
+    latency: Option<gst::ClockTime>,
+    flushing: bool,
+    sync_abort_handle: Option<AbortHandle>,
+    stats: Stats,
+    last_stats: Option<Instant>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct FileSinkPadHandler(Arc<StdMutex<FileSinkPadHandlerInner>>);
+
+impl FileSinkPadHandler {
+    fn set_latency(&self, latency: gst::ClockTime) {
+        self.0.lock().unwrap().latency = Some(latency);
+    }
+
+    fn set_flushing(&self, flushing: bool) {
+        let mut inner = self.0.lock().unwrap();
+        inner.flushing = flushing;
+        if let Some(abort_handle) = inner.sync_abort_handle.take() {
+            abort_handle.abort();
+        }
+    }
+
+    fn reset(&self) {
+        let mut inner = self.0.lock().unwrap();
+        let file = inner.file.take();
+        *inner = FileSinkPadHandlerInner {
+            file,
+            ..Default::default()
+        };
+    }
+
+    fn set_file(&self, file: Option<File>) {
+        self.0.lock().unwrap().file = file.map(|file| Arc::new(StdMutex::new(file)));
+    }
+
+    async fn write(
+        &self,
+        element: &super::FileSink,
+        buffer: &gst::Buffer,
+    ) -> Result<(), gst::FlowError> {
+        let file = self.0.lock().unwrap().file.clone().ok_or_else(|| {
+            gst::element_error!(element, gst::CoreError::Failed, ["File not opened"]);
+            gst::FlowError::Error
+        })?;
+
+        // The file stays in the state if this write gets cancelled. A blocking write
+        // already in progress still completes, holding the file lock meanwhile, so the
+        // next write is performed after it
+        let buffer = buffer.clone();
+        runtime::spawn_blocking(move || {
+            let map = buffer.map_readable().map_err(|_| {
+                gst::error_msg!(gst::StreamError::Format, ["Failed to map buffer readable"])
+            })?;
+
+            file.lock().unwrap().write_all(&map).map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::Write,
+                    ["Failed to write buffer: {}", err]
+                )
+            })
+        })
+        .await
+        .map_err(|err| {
+            element.post_error_message(err);
+            gst::FlowError::Error
+        })
+    }
+
+    fn stats(&self) -> Stats {
+        self.0.lock().unwrap().stats
+    }
+
+    /* Wait until the running time of the buffer is reached */
+    async fn sync(
+        &self,
+        element: &super::FileSink,
+        pts: Option<gst::ClockTime>,
+    ) -> Result<(), gst::FlowError> {
+        let (timer, abort_registration) = {
+            let mut inner = self.0.lock().unwrap();
+
+            let running_time = inner
+                .segment
+                .as_ref()
+                .and_then(|segment| segment.to_running_time(pts))
+                .opt_add(inner.latency);
+
+            let timer = match runtime::delay_until_running_time(element, running_time) {
+                Some(timer) => timer,
+                None => {
+                    if running_time.opt_lt(element.current_running_time()) == Some(true) {
+                        inner.stats.num_late += 1;
+                    }
+                    return Ok(());
+                }
+            };
+
+            let (abort_handle, abort_registration) = AbortHandle::new_pair();
+            inner.sync_abort_handle = Some(abort_handle);
+
+            (timer, abort_registration)
+        };
+
+        let res = Abortable::new(timer, abort_registration).await;
+        self.0.lock().unwrap().sync_abort_handle = None;
+
+        res.map_err(|_| {
+            gst::debug!(CAT, obj: element, "Sync aborted, flushing");
+            gst::FlowError::Flushing
+        })
+    }
+
+    async fn render(
+        &self,
+        element: &super::FileSink,
+        buffer: gst::Buffer,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        let settings = element.imp().settings.lock().unwrap().clone();
+
+        if self.0.lock().unwrap().flushing {
+            return Err(gst::FlowError::Flushing);
+        }
+
+        if settings.sync {
+            self.sync(element, buffer.pts()).await?;
+        }
+
+        gst::log!(CAT, obj: element, "Writing {:?}", buffer);
+
+        self.write(element, &buffer).await?;
+
+        let stats = {
+            let mut inner = self.0.lock().unwrap();
+            inner.stats.num_buffers += 1;
+            inner.stats.num_bytes += buffer.size() as u64;
+
+            if settings.stats_interval.is_zero() {
+                None
+            } else {
+                let now = Instant::now();
+                match inner.last_stats {
+                    Some(last_stats) if now - last_stats < settings.stats_interval => None,
+                    _ => {
+                        inner.last_stats = Some(now);
+                        Some(inner.stats)
+                    }
+                }
+            }
+        };
+
+        if let Some(stats) = stats {
+            element.emit_by_name::<()>("stats", &[&stats.to_structure()]);
+        }
+
+        Ok(gst::FlowSuccess::Ok)
+    }
+}
+
+impl PadSinkHandler for FileSinkPadHandler {
+    type ElementImpl = FileSink;
+
+    fn sink_chain(
+        &self,
+        _pad: &PadSinkRef,
+        _filesink: &FileSink,
+        element: &gst::Element,
+        buffer: gst::Buffer,
+    ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
+        let this = self.clone();
+        let element = element.clone().downcast::<super::FileSink>().unwrap();
+
+        async move { this.render(&element, buffer).await }.boxed()
+    }
+
+    fn sink_chain_list(
+        &self,
+        _pad: &PadSinkRef,
+        _filesink: &FileSink,
+        element: &gst::Element,
+        list: gst::BufferList,
+    ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
+        let this = self.clone();
+        let element = element.clone().downcast::<super::FileSink>().unwrap();
+
+        async move {
+            for buffer in list.iter_owned() {
+                this.render(&element, buffer).await?;
+            }
+
+            Ok(gst::FlowSuccess::Ok)
+        }
+        .boxed()
+    }
+
+    fn sink_event_serialized(
+        &self,
+        _pad: &PadSinkRef,
+        _filesink: &FileSink,
+        element: &gst::Element,
+        event: gst::Event,
+    ) -> BoxFuture<'static, bool> {
+        let this = self.clone();
+        let element = element.clone().downcast::<super::FileSink>().unwrap();
+
+        async move {
+            gst::log!(CAT, obj: &element, "Handling {:?}", event);
+
+            match event.view() {
+                EventView::FlushStop(_) => {
+                    this.set_flushing(false);
+                    this.0.lock().unwrap().segment = None;
+                }
+                EventView::Segment(e) => {
+                    this.0.lock().unwrap().segment =
+                        e.segment().downcast_ref::<gst::format::Time>().cloned();
+                }
+                EventView::Eos(_) => {
+                    let _ =
+                        element.post_message(gst::message::Eos::builder().src(&element).build());
+                }
+                EventView::SinkMessage(e) => {
+                    let _ = element.post_message(e.message());
+                }
+                _ => (),
+            }
+
+            true
+        }
+        .boxed()
+    }
+
+    fn sink_event(
+        &self,
+        _pad: &PadSinkRef,
+        _filesink: &FileSink,
+        _element: &gst::Element,
+        event: gst::Event,
+    ) -> bool {
+        if let EventView::FlushStart(..) = event.view() {
+            self.set_flushing(true);
+        }
+
+        true
+    }
+}
+
+#[derive(Debug)]
+pub struct FileSink {
+    sink_pad: PadSink,
+    sink_pad_handler: FileSinkPadHandler,
+    settings: StdMutex<Settings>,
+}
+
+impl FileSink {
+    fn prepare(&self, element: &super::FileSink) -> Result<(), gst::ErrorMessage> {
+        let settings = self.settings.lock().unwrap().clone();
+        gst::debug!(CAT, obj: element, "Preparing");
+
+        let location = settings.location.ok_or_else(|| {
+            gst::error_msg!(gst::ResourceError::NotFound, ["No location specified"])
+        })?;
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(settings.append)
+            .truncate(!settings.append)
+            .open(&location)
+            .map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::OpenWrite,
+                    [
+                        "Could not open file {} for writing: {}",
+                        location.display(),
+                        err
+                    ]
+                )
+            })?;
+
+        self.sink_pad_handler.set_file(Some(file));
+
+        gst::debug!(CAT, obj: element, "Prepared");
+
+        Ok(())
+    }
+
+    fn unprepare(&self, element: &super::FileSink) {
+        gst::debug!(CAT, obj: element, "Unpreparing");
+        self.sink_pad_handler.set_file(None);
+        gst::debug!(CAT, obj: element, "Unprepared");
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for FileSink {
+    const NAME: &'static str = "RsTsFileSink";
+    type Type = super::FileSink;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        let sink_pad_handler = FileSinkPadHandler::default();
+
+        Self {
+            sink_pad: PadSink::new(
+                gst::Pad::from_template(&klass.pad_template("sink").unwrap(), Some("sink")),
+                sink_pad_handler.clone(),
+            ),
+            sink_pad_handler,
+            settings: StdMutex::new(Settings::default()),
+        }
+    }
+}
+
+impl ObjectImpl for FileSink {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecString::new(
+                    "location",
+                    "File Location",
+                    "Location of the file to write",
+                    None,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "append",
+                    "Append",
+                    "Append to an already existing file",
+                    DEFAULT_APPEND,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "sync",
+                    "Sync",
+                    "Sync on the clock",
+                    DEFAULT_SYNC,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "stats-interval",
+                    "Stats Interval",
+                    "Emit the stats signal at most once every this many ms (0 = disabled)",
+                    0,
+                    u32::MAX,
+                    DEFAULT_STATS_INTERVAL,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoxed::new(
+                    "stats",
+                    "Statistics",
+                    "Various statistics",
+                    gst::Structure::static_type(),
+                    glib::ParamFlags::READABLE,
+                ),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn signals() -> &'static [glib::subclass::Signal] {
+        static SIGNALS: Lazy<Vec<glib::subclass::Signal>> = Lazy::new(|| {
+            vec![glib::subclass::Signal::builder(
+                "stats",
+                &[gst::Structure::static_type().into()],
+                glib::types::Type::UNIT.into(),
+            )
+            .build()]
+        });
+
+        SIGNALS.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        let mut settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "location" => {
+                settings.location = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream")
+                    .map(PathBuf::from);
+            }
+            "append" => {
+                settings.append = value.get().expect("type checked upstream");
+            }
+            "sync" => {
+                settings.sync = value.get().expect("type checked upstream");
+            }
+            "stats-interval" => {
+                settings.stats_interval = Duration::from_millis(
+                    value.get::<u32>().expect("type checked upstream").into(),
+                );
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            "sync" => self.settings.lock().unwrap().sync.to_value(),
+            "location" => self
+                .settings
+                .lock()
+                .unwrap()
+                .location
+                .as_ref()
+                .map(|location| location.to_string_lossy().into_owned())
+                .to_value(),
+            "append" => self.settings.lock().unwrap().append.to_value(),
+            "stats-interval" => {
+                (self.settings.lock().unwrap().stats_interval.as_millis() as u32).to_value()
+            }
+            "stats" => self.sink_pad_handler.stats().to_structure().to_value(),
+            _ => unimplemented!(),
+        }
+    }
+
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(self.sink_pad.gst_pad()).unwrap();
+
+        crate::set_element_flags(obj, gst::ElementFlags::SINK);
+    }
+}
+
+impl GstObjectImpl for FileSink {}
+
+impl ElementImpl for FileSink {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Thread-sharing file sink",
+                "Sink/File",
+                "Thread-sharing sink writing to a file",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::new_any();
+
+            let sink_pad_template = gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![sink_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        match transition {
+            gst::StateChange::NullToReady => {
+                self.prepare(element).map_err(|err| {
+                    element.post_error_message(err);
+                    gst::StateChangeError
+                })?;
+            }
+            gst::StateChange::PausedToReady => {
+                self.sink_pad_handler.set_flushing(true);
+            }
+            _ => (),
+        }
+
+        let success = self.parent_change_state(element, transition)?;
+
+        match transition {
+            gst::StateChange::ReadyToPaused => {
+                self.sink_pad_handler.reset();
+            }
+            gst::StateChange::ReadyToNull => {
+                self.unprepare(element);
+            }
+            _ => (),
+        }
+
+        Ok(success)
+    }
+
+    fn send_event(&self, _element: &Self::Type, event: gst::Event) -> bool {
+        match event.view() {
+            EventView::Latency(ev) => {
+                self.sink_pad_handler.set_latency(ev.latency());
+                self.sink_pad.gst_pad().push_event(event)
+            }
+            EventView::Step(..) => false,
+            _ => self.sink_pad.gst_pad().push_event(event),
+        }
+    }
+}
@@ -0,0 +1,584 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use futures::future::BoxFuture;
+use futures::prelude::*;
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+
+use once_cell::sync::Lazy;
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+use std::u32;
+
+use crate::runtime::prelude::*;
+use crate::runtime::{self, Context, PadSrc, PadSrcRef, PadSrcWeak, Task};
+
+const DEFAULT_CONTEXT: &str = "";
+const DEFAULT_CONTEXT_WAIT: Duration = Duration::ZERO;
+const DEFAULT_LOCATION: Option<PathBuf> = None;
+const DEFAULT_BLOCKSIZE: u32 = 4096;
+
+#[derive(Debug, Clone)]
+struct Settings {
+    context: String,
+    context_wait: Duration,
+    location: Option<PathBuf>,
+    blocksize: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            context: DEFAULT_CONTEXT.into(),
+            context_wait: DEFAULT_CONTEXT_WAIT,
+            location: DEFAULT_LOCATION,
+            blocksize: DEFAULT_BLOCKSIZE,
+        }
+    }
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "ts-filesrc",
+        gst::DebugColorFlags::empty(),
+        Some("Thread-sharing file source"),
+    )
+});
+
+#[derive(Clone, Debug, Default)]
+struct FileSrcPadHandler;
+
+impl PadSrcHandler for FileSrcPadHandler {
+    type ElementImpl = FileSrc;
+
+    fn src_event(
+        &self,
+        pad: &PadSrcRef,
+        filesrc: &FileSrc,
+        _element: &gst::Element,
+        event: gst::Event,
+    ) -> bool {
+        use gst::EventView;
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", event);
+
+        let ret = match event.view() {
+            EventView::FlushStart(..) => filesrc.task.flush_start().is_ok(),
+            EventView::FlushStop(..) => filesrc.task.flush_stop().is_ok(),
+            EventView::Reconfigure(..) => true,
+            EventView::Latency(..) => true,
+            _ => false,
+        };
+
+        if ret {
+            gst::log!(CAT, obj: pad.gst_pad(), "Handled {:?}", event);
+        } else {
+            gst::log!(CAT, obj: pad.gst_pad(), "Didn't handle {:?}", event);
+        }
+
+        ret
+    }
+
+    fn src_query(
+        &self,
+        pad: &PadSrcRef,
+        filesrc: &FileSrc,
+        _element: &gst::Element,
+        query: &mut gst::QueryRef,
+    ) -> bool {
+        use gst::QueryViewMut;
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", query);
+        let ret = match query.view_mut() {
+            QueryViewMut::Latency(q) => {
+                q.set(false, gst::ClockTime::ZERO, gst::ClockTime::NONE);
+                true
+            }
+            QueryViewMut::Scheduling(q) => {
+                q.set(gst::SchedulingFlags::SEQUENTIAL, 1, -1, 0);
+                q.add_scheduling_modes(&[gst::PadMode::Push]);
+                true
+            }
+            QueryViewMut::Duration(q) if q.format() == gst::Format::Bytes => {
+                let location = filesrc.settings.lock().unwrap().location.clone();
+                match location.and_then(|location| std::fs::metadata(location).ok()) {
+                    Some(metadata) => {
+                        q.set(gst::format::Bytes(metadata.len()));
+                        true
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        };
+
+        if ret {
+            gst::log!(CAT, obj: pad.gst_pad(), "Handled {:?}", query);
+        } else {
+            gst::log!(CAT, obj: pad.gst_pad(), "Didn't handle {:?}", query);
+        }
+        ret
+    }
+}
+
+#[derive(Debug)]
+struct FileSrcTask {
+    element: super::FileSrc,
+    src_pad: PadSrcWeak,
+    location: PathBuf,
+    file: Option<File>,
+    offset: u64,
+    need_initial_events: bool,
+    need_segment: bool,
+}
+
+impl FileSrcTask {
+    fn new(element: &super::FileSrc, src_pad: &PadSrc, location: PathBuf) -> Self {
+        FileSrcTask {
+            element: element.clone(),
+            src_pad: src_pad.downgrade(),
+            location,
+            file: None,
+            offset: 0,
+            need_initial_events: true,
+            need_segment: true,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.file = None;
+        self.offset = 0;
+        self.need_initial_events = true;
+        self.need_segment = true;
+    }
+
+    async fn push_prelude(&mut self, pad: &PadSrcRef<'_>) {
+        if self.need_initial_events {
+            gst::debug!(CAT, obj: pad.gst_pad(), "Pushing initial events");
+
+            let stream_id = format!("{:08x}{:08x}", rand::random::<u32>(), rand::random::<u32>());
+            let stream_start_evt = gst::event::StreamStart::builder(&stream_id)
+                .group_id(gst::GroupId::next())
+                .build();
+            pad.push_event(stream_start_evt).await;
+
+            self.need_initial_events = false;
+        }
+
+        if self.need_segment {
+            let mut segment = gst::FormattedSegment::<gst::format::Bytes>::new();
+            segment.set_start(gst::format::Bytes(self.offset));
+            segment.set_time(gst::format::Bytes(self.offset));
+            pad.push_event(gst::event::Segment::new(&segment)).await;
+
+            self.need_segment = false;
+        }
+    }
+
+    /* Reads the next block on the blocking pool so the Context is never stalled */
+    async fn read(&mut self, blocksize: usize) -> Result<Option<gst::Buffer>, gst::ErrorMessage> {
+        // The File is moved to the blocking pool for the duration of the read.
+        // If the iteration gets cancelled meanwhile, it is lost and reopened
+        // at the current offset on the next iteration.
+        let file = self.file.take();
+        let location = self.location.clone();
+        let offset = self.offset;
+
+        let (file, res) = runtime::spawn_blocking(move || {
+            let mut file = match file {
+                Some(file) => file,
+                None => {
+                    let mut file = File::open(&location).map_err(|err| {
+                        gst::error_msg!(
+                            gst::ResourceError::OpenRead,
+                            ["Could not open file {}: {}", location.display(), err]
+                        )
+                    })?;
+                    file.seek(SeekFrom::Start(offset)).map_err(|err| {
+                        gst::error_msg!(
+                            gst::ResourceError::Seek,
+                            ["Failed to seek to {}: {}", offset, err]
+                        )
+                    })?;
+                    file
+                }
+            };
+
+            let mut buffer = gst::Buffer::with_size(blocksize).unwrap();
+            let res = {
+                let mut map = buffer.get_mut().unwrap().map_writable().unwrap();
+                file.read(&mut map)
+            };
+
+            Ok((file, res.map(|size| (buffer, size))))
+        })
+        .await?;
+
+        self.file = Some(file);
+
+        let (mut buffer, size) = res.map_err(|err| {
+            gst::error_msg!(
+                gst::ResourceError::Read,
+                ["Failed to read at offset {}: {}", self.offset, err]
+            )
+        })?;
+
+        if size == 0 {
+            return Ok(None);
+        }
+
+        {
+            let buffer = buffer.get_mut().unwrap();
+            buffer.set_size(size);
+            buffer.set_offset(self.offset);
+            buffer.set_offset_end(self.offset + size as u64);
+        }
+        self.offset += size as u64;
+
+        Ok(Some(buffer))
+    }
+}
+
+impl TaskImpl for FileSrcTask {
+    fn iterate(&mut self) -> BoxFuture<'_, Result<(), gst::FlowError>> {
+        async move {
+            let pad = self.src_pad.upgrade().expect("PadSrc no longer exists");
+            self.push_prelude(&pad).await;
+
+            let blocksize = self.element.imp().settings.lock().unwrap().blocksize as usize;
+
+            let buffer = match self.read(blocksize).await {
+                Ok(Some(buffer)) => buffer,
+                Ok(None) => {
+                    gst::debug!(CAT, obj: &self.element, "End of file, pushing EOS");
+                    pad.push_event(gst::event::Eos::new()).await;
+                    return Err(gst::FlowError::Eos);
+                }
+                Err(err) => {
+                    gst::error!(CAT, obj: &self.element, "Got error {}", err);
+                    self.element.post_error_message(err);
+                    return Err(gst::FlowError::Error);
+                }
+            };
+
+            gst::log!(CAT, obj: &self.element, "Pushing {:?}", buffer);
+            let res = pad.push(buffer).await;
+            match res {
+                Ok(_) => {
+                    gst::log!(CAT, obj: &self.element, "Successfully pushed buffer");
+                }
+                Err(gst::FlowError::Flushing) => {
+                    gst::debug!(CAT, obj: &self.element, "Flushing");
+                }
+                Err(gst::FlowError::Eos) => {
+                    gst::debug!(CAT, obj: &self.element, "EOS");
+                    pad.push_event(gst::event::Eos::new()).await;
+                }
+                Err(err) => {
+                    gst::error!(CAT, obj: &self.element, "Got error {}", err);
+                    gst::element_error!(
+                        &self.element,
+                        gst::StreamError::Failed,
+                        ("Internal data stream error"),
+                        ["streaming stopped, reason {}", err]
+                    );
+                }
+            }
+
+            res.map(drop)
+        }
+        .boxed()
+    }
+
+    fn stop(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Stopping task");
+
+            self.reset();
+
+            gst::log!(CAT, obj: &self.element, "Task stopped");
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn flush_start(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Starting task flush");
+
+            self.need_segment = true;
+
+            gst::log!(CAT, obj: &self.element, "Task flush started");
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+#[derive(Debug)]
+pub struct FileSrc {
+    src_pad: PadSrc,
+    task: Task,
+    settings: StdMutex<Settings>,
+}
+
+impl FileSrc {
+    fn prepare(&self, element: &super::FileSrc) -> Result<(), gst::ErrorMessage> {
+        let settings = self.settings.lock().unwrap().clone();
+        gst::debug!(CAT, obj: element, "Preparing");
+
+        let location = settings.location.ok_or_else(|| {
+            gst::error_msg!(gst::ResourceError::NotFound, ["No location specified"])
+        })?;
+
+        let context =
+            Context::acquire(&settings.context, settings.context_wait).map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::OpenRead,
+                    ["Failed to acquire Context: {}", err]
+                )
+            })?;
+
+        self.task
+            .prepare(FileSrcTask::new(element, &self.src_pad, location), context)
+            .map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::OpenRead,
+                    ["Error preparing Task: {:?}", err]
+                )
+            })?;
+
+        gst::debug!(CAT, obj: element, "Prepared");
+
+        Ok(())
+    }
+
+    fn unprepare(&self, element: &super::FileSrc) {
+        gst::debug!(CAT, obj: element, "Unpreparing");
+        self.task.unprepare().unwrap();
+        gst::debug!(CAT, obj: element, "Unprepared");
+    }
+
+    fn stop(&self, element: &super::FileSrc) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Stopping");
+        self.task.stop()?;
+        gst::debug!(CAT, obj: element, "Stopped");
+        Ok(())
+    }
+
+    fn start(&self, element: &super::FileSrc) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Starting");
+        self.task.start()?;
+        gst::debug!(CAT, obj: element, "Started");
+        Ok(())
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for FileSrc {
+    const NAME: &'static str = "RsTsFileSrc";
+    type Type = super::FileSrc;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        Self {
+            src_pad: PadSrc::new(
+                gst::Pad::from_template(&klass.pad_template("src").unwrap(), Some("src")),
+                FileSrcPadHandler,
+            ),
+            task: Task::default(),
+            settings: StdMutex::new(Settings::default()),
+        }
+    }
+}
+
+impl ObjectImpl for FileSrc {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecString::new(
+                    "context",
+                    "Context",
+                    "Context name to share threads with",
+                    Some(DEFAULT_CONTEXT),
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "context-wait",
+                    "Context Wait",
+                    "Throttle poll loop to run at most once every this many ms",
+                    0,
+                    1000,
+                    DEFAULT_CONTEXT_WAIT.as_millis() as u32,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecString::new(
+                    "location",
+                    "File Location",
+                    "Location of the file to read",
+                    None,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "blocksize",
+                    "Block Size",
+                    "Size in bytes to read per buffer",
+                    1,
+                    u32::MAX,
+                    DEFAULT_BLOCKSIZE,
+                    glib::ParamFlags::READWRITE,
+                ),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        let mut settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "context" => {
+                settings.context = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream")
+                    .unwrap_or_else(|| DEFAULT_CONTEXT.into());
+            }
+            "context-wait" => {
+                settings.context_wait = Duration::from_millis(
+                    value.get::<u32>().expect("type checked upstream").into(),
+                );
+            }
+            "location" => {
+                settings.location = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream")
+                    .map(PathBuf::from);
+            }
+            "blocksize" => {
+                settings.blocksize = value.get().expect("type checked upstream");
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        let settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "context" => settings.context.to_value(),
+            "context-wait" => (settings.context_wait.as_millis() as u32).to_value(),
+            "location" => settings
+                .location
+                .as_ref()
+                .map(|location| location.to_string_lossy().into_owned())
+                .to_value(),
+            "blocksize" => settings.blocksize.to_value(),
+            _ => unimplemented!(),
+        }
+    }
+
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(self.src_pad.gst_pad()).unwrap();
+
+        crate::set_element_flags(obj, gst::ElementFlags::SOURCE);
+    }
+}
+
+impl GstObjectImpl for FileSrc {}
+
+impl ElementImpl for FileSrc {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Thread-sharing file source",
+                "Source/File",
+                "Reads a file on a thread-sharing context",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::new_any();
+            let src_pad_template = gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![src_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        match transition {
+            gst::StateChange::NullToReady => {
+                self.prepare(element).map_err(|err| {
+                    element.post_error_message(err);
+                    gst::StateChangeError
+                })?;
+            }
+            gst::StateChange::ReadyToNull => {
+                self.unprepare(element);
+            }
+            _ => (),
+        }
+
+        let success = self.parent_change_state(element, transition)?;
+
+        match transition {
+            gst::StateChange::ReadyToPaused => {
+                self.start(element).map_err(|_| gst::StateChangeError)?;
+            }
+            gst::StateChange::PausedToReady => {
+                self.stop(element).map_err(|_| gst::StateChangeError)?;
+            }
+            _ => (),
+        }
+
+        Ok(success)
+    }
+}
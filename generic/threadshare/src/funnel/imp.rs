@@ -0,0 +1,956 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use futures::channel::oneshot;
+use futures::future::{self, BoxFuture, Either};
+use futures::prelude::*;
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+
+use once_cell::sync::Lazy;
+
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+use std::{u32, u64};
+
+use crate::runtime::prelude::*;
+use crate::runtime::{Context, PadSink, PadSinkRef, PadSrc, PadSrcRef, PadSrcWeak, Task};
+
+use crate::dataqueue::{DataQueue, DataQueueItem, DataQueueState};
+
+const DEFAULT_MAX_SIZE_BUFFERS: u32 = 200;
+const DEFAULT_MAX_SIZE_BYTES: u32 = 1024 * 1024;
+const DEFAULT_MAX_SIZE_TIME: gst::ClockTime = gst::ClockTime::SECOND;
+const DEFAULT_CONTEXT: &str = "";
+const DEFAULT_CONTEXT_WAIT: Duration = Duration::ZERO;
+
+#[derive(Debug, Clone)]
+struct Settings {
+    max_size_buffers: u32,
+    max_size_bytes: u32,
+    max_size_time: gst::ClockTime,
+    context: String,
+    context_wait: Duration,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            max_size_buffers: DEFAULT_MAX_SIZE_BUFFERS,
+            max_size_bytes: DEFAULT_MAX_SIZE_BYTES,
+            max_size_time: DEFAULT_MAX_SIZE_TIME,
+            context: DEFAULT_CONTEXT.into(),
+            context_wait: DEFAULT_CONTEXT_WAIT,
+        }
+    }
+}
+
+/// The parts of a sink pad branch which are needed by the streaming threads.
+#[derive(Clone, Debug)]
+struct BranchQueue {
+    pad: gst::Pad,
+    dataqueue: DataQueue,
+    eos: Arc<AtomicBool>,
+}
+
+#[derive(Debug)]
+struct Branch {
+    _sink_pad: PadSink,
+    queue: BranchQueue,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    started: bool,
+    pad_serial: u32,
+    branches: Vec<Branch>,
+    pads_changed_sender: Option<oneshot::Sender<()>>,
+}
+
+impl State {
+    fn notify_pads_changed(&mut self) {
+        self.pads_changed_sender.take();
+    }
+}
+
+#[derive(Clone)]
+struct FunnelPadSinkHandler;
+
+impl PadSinkHandler for FunnelPadSinkHandler {
+    type ElementImpl = Funnel;
+
+    fn sink_chain(
+        &self,
+        pad: &PadSinkRef,
+        _funnel: &Funnel,
+        element: &gst::Element,
+        buffer: gst::Buffer,
+    ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
+        let pad_weak = pad.downgrade();
+        let element = element.clone().downcast::<super::Funnel>().unwrap();
+        async move {
+            let pad = pad_weak.upgrade().expect("PadSink no longer exists");
+            gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", buffer);
+            let funnel = element.imp();
+            funnel
+                .enqueue_item(pad.gst_pad(), DataQueueItem::Buffer(buffer))
+                .await
+        }
+        .boxed()
+    }
+
+    fn sink_chain_list(
+        &self,
+        pad: &PadSinkRef,
+        _funnel: &Funnel,
+        element: &gst::Element,
+        list: gst::BufferList,
+    ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
+        let pad_weak = pad.downgrade();
+        let element = element.clone().downcast::<super::Funnel>().unwrap();
+        async move {
+            let pad = pad_weak.upgrade().expect("PadSink no longer exists");
+            gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", list);
+            let funnel = element.imp();
+            funnel
+                .enqueue_item(pad.gst_pad(), DataQueueItem::BufferList(list))
+                .await
+        }
+        .boxed()
+    }
+
+    fn sink_event(
+        &self,
+        pad: &PadSinkRef,
+        funnel: &Funnel,
+        element: &gst::Element,
+        event: gst::Event,
+    ) -> bool {
+        use gst::EventView;
+
+        gst::debug!(CAT, obj: pad.gst_pad(), "Handling non-serialized {:?}", event);
+
+        if let EventView::FlushStart(..) = event.view() {
+            if let Some(branch) = funnel.branch_queue(pad.gst_pad()) {
+                branch.dataqueue.stop();
+                branch.dataqueue.clear();
+            }
+
+            if let Err(err) = funnel.task.flush_start() {
+                gst::error!(CAT, obj: pad.gst_pad(), "FlushStart failed {:?}", err);
+                gst::element_error!(
+                    element,
+                    gst::StreamError::Failed,
+                    ("Internal data stream error"),
+                    ["FlushStart failed {:?}", err]
+                );
+                return false;
+            }
+        }
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Forwarding non-serialized {:?}", event);
+        funnel.src_pad.gst_pad().push_event(event)
+    }
+
+    fn sink_event_serialized(
+        &self,
+        pad: &PadSinkRef,
+        _funnel: &Funnel,
+        element: &gst::Element,
+        event: gst::Event,
+    ) -> BoxFuture<'static, bool> {
+        use gst::EventView;
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling serialized {:?}", event);
+
+        let pad_weak = pad.downgrade();
+        let element = element.clone().downcast::<super::Funnel>().unwrap();
+        async move {
+            let pad = pad_weak.upgrade().expect("PadSink no longer exists");
+            let funnel = element.imp();
+
+            if let EventView::FlushStop(..) = event.view() {
+                // Forward now, before the Task resumes pushing the other branches
+                gst::log!(CAT, obj: pad.gst_pad(), "Forwarding {:?}", event);
+                let res = funnel.src_pad.gst_pad().push_event(event);
+
+                if let Some(branch) = funnel.branch_queue(pad.gst_pad()) {
+                    branch.eos.store(false, Ordering::SeqCst);
+                    branch.dataqueue.start();
+                }
+
+                if let Err(err) = funnel.task.flush_stop() {
+                    gst::error!(CAT, obj: pad.gst_pad(), "FlushStop failed {:?}", err);
+                    gst::element_error!(
+                        element,
+                        gst::StreamError::Failed,
+                        ("Internal data stream error"),
+                        ["FlushStop failed {:?}", err]
+                    );
+                    return false;
+                }
+
+                return res;
+            }
+
+            gst::log!(CAT, obj: pad.gst_pad(), "Queuing serialized {:?}", event);
+            funnel
+                .enqueue_item(pad.gst_pad(), DataQueueItem::Event(event))
+                .await
+                .is_ok()
+        }
+        .boxed()
+    }
+
+    fn sink_query(
+        &self,
+        pad: &PadSinkRef,
+        funnel: &Funnel,
+        _element: &gst::Element,
+        query: &mut gst::QueryRef,
+    ) -> bool {
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", query);
+
+        if query.is_serialized() {
+            // FIXME: How can we do this?
+            gst::log!(CAT, obj: pad.gst_pad(), "Dropping serialized {:?}", query);
+            false
+        } else {
+            gst::log!(CAT, obj: pad.gst_pad(), "Forwarding {:?}", query);
+            funnel.src_pad.gst_pad().peer_query(query)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FunnelPadSrcHandler;
+
+impl PadSrcHandler for FunnelPadSrcHandler {
+    type ElementImpl = Funnel;
+
+    fn src_event(
+        &self,
+        pad: &PadSrcRef,
+        funnel: &Funnel,
+        _element: &gst::Element,
+        event: gst::Event,
+    ) -> bool {
+        gst::log!(CAT, obj: pad.gst_pad(), "Forwarding {:?}", event);
+
+        // Succeed if at least one upstream branch accepted the event
+        funnel.sink_pads().iter().fold(false, |res, sink_pad| {
+            sink_pad.push_event(event.clone()) || res
+        })
+    }
+
+    fn src_query(
+        &self,
+        pad: &PadSrcRef,
+        funnel: &Funnel,
+        _element: &gst::Element,
+        query: &mut gst::QueryRef,
+    ) -> bool {
+        use gst::QueryViewMut;
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", query);
+
+        let sink_pads = funnel.sink_pads();
+
+        if let QueryViewMut::Latency(q) = query.view_mut() {
+            let mut ret = true;
+            let mut min_latency = gst::ClockTime::ZERO;
+            let mut max_latency = gst::ClockTime::NONE;
+
+            for sink_pad in sink_pads {
+                let mut peer_query = gst::query::Latency::new();
+
+                ret = sink_pad.peer_query(&mut peer_query);
+
+                if ret {
+                    let (live, min, max) = peer_query.result();
+                    if live {
+                        min_latency = min.max(min_latency);
+                        max_latency = max.opt_min(max_latency).or(max);
+                    }
+                }
+            }
+
+            q.set(true, min_latency, max_latency);
+
+            return ret;
+        }
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Forwarding {:?}", query);
+        sink_pads.iter().any(|sink_pad| sink_pad.peer_query(query))
+    }
+}
+
+#[derive(Debug)]
+struct FunnelTask {
+    element: super::Funnel,
+    src_pad: PadSrcWeak,
+    active_pad: Option<gst::Pad>,
+    next_branch: usize,
+    eos_sent: bool,
+}
+
+impl FunnelTask {
+    fn new(element: &super::Funnel, src_pad: &PadSrc) -> Self {
+        FunnelTask {
+            element: element.clone(),
+            src_pad: src_pad.downgrade(),
+            active_pad: None,
+            next_branch: 0,
+            eos_sent: false,
+        }
+    }
+
+    /* Returns the next item from any of the started branches,
+     * or None if the branches changed in the meantime */
+    async fn next_item(&mut self) -> Option<(BranchQueue, DataQueueItem)> {
+        let funnel = self.element.imp();
+
+        let (mut queues, all_eos, pads_changed_receiver) = {
+            let mut state = funnel.state.lock().unwrap();
+            let (sender, receiver) = oneshot::channel();
+            state.pads_changed_sender = Some(sender);
+
+            let all_eos = !state.branches.is_empty()
+                && state
+                    .branches
+                    .iter()
+                    .all(|branch| branch.queue.eos.load(Ordering::SeqCst));
+
+            let queues = state
+                .branches
+                .iter()
+                .filter(|branch| branch.queue.dataqueue.state() == DataQueueState::Started)
+                .map(|branch| branch.queue.clone())
+                .collect::<Vec<_>>();
+
+            (queues, all_eos, receiver)
+        };
+
+        if all_eos && !self.eos_sent {
+            // A released branch might have been the last one not to be EOS
+            self.eos_sent = true;
+            let pad = self.src_pad.upgrade().expect("PadSrc no longer exists");
+            pad.push_event(gst::event::Eos::new()).await;
+        }
+
+        if queues.is_empty() {
+            let _ = pads_changed_receiver.await;
+            return None;
+        }
+
+        // Rotate the branches so that none of them can starve the others
+        let first = self.next_branch % queues.len();
+        queues.rotate_left(first);
+        self.next_branch = self.next_branch.wrapping_add(1);
+
+        let next_fut = future::select_all(queues.into_iter().map(|mut queue| {
+            async move {
+                let item = queue.dataqueue.next().await;
+                (queue, item)
+            }
+            .boxed()
+        }));
+
+        match future::select(next_fut, pads_changed_receiver).await {
+            Either::Left((((queue, Some(item)), _, _), _)) => Some((queue, item)),
+            _ => None,
+        }
+    }
+
+    /* Pushes the sticky events of the branch we are switching to,
+     * so that downstream gets the configuration matching its data */
+    async fn switch_to(&mut self, pad: &PadSrcRef<'_>, queue: &BranchQueue) {
+        gst::debug!(CAT, obj: &self.element, "Switching to {}", queue.pad.name());
+
+        let mut sticky_events = Vec::new();
+        queue.pad.sticky_events_foreach(|event| {
+            if event.type_() != gst::EventType::Eos {
+                sticky_events.push(event.clone());
+            }
+            ControlFlow::Continue(gst::EventForeachAction::Keep)
+        });
+
+        for event in sticky_events {
+            pad.push_event(event).await;
+        }
+
+        self.active_pad = Some(queue.pad.clone());
+    }
+}
+
+impl TaskImpl for FunnelTask {
+    fn start(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Starting task");
+
+            let funnel = self.element.imp();
+            let mut last_res = funnel.last_res.lock().unwrap();
+
+            {
+                let mut state = funnel.state.lock().unwrap();
+                for branch in state.branches.iter() {
+                    branch.queue.eos.store(false, Ordering::SeqCst);
+                    branch.queue.dataqueue.start();
+                }
+                state.started = true;
+            }
+
+            self.active_pad = None;
+            self.eos_sent = false;
+            *last_res = Ok(gst::FlowSuccess::Ok);
+
+            gst::log!(CAT, obj: &self.element, "Task started");
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn iterate(&mut self) -> BoxFuture<'_, Result<(), gst::FlowError>> {
+        async move {
+            let (queue, item) = match self.next_item().await {
+                Some(next) => next,
+                None => {
+                    gst::log!(CAT, obj: &self.element, "Branches changed");
+                    return Ok(());
+                }
+            };
+
+            let pad = self.src_pad.upgrade().expect("PadSrc no longer exists");
+            let funnel = self.element.imp();
+
+            let res = match item {
+                DataQueueItem::Event(event) => {
+                    if event.type_() == gst::EventType::Eos {
+                        queue.eos.store(true, Ordering::SeqCst);
+                        // EOS is forwarded by `next_item` once all the branches are EOS
+                        gst::debug!(CAT, obj: &queue.pad, "EOS");
+                    } else if event.is_sticky() && self.active_pad.as_ref() != Some(&queue.pad) {
+                        // Will be pushed when switching to this branch
+                        gst::log!(CAT, obj: &queue.pad, "Holding back {:?}", event);
+                    } else {
+                        gst::log!(CAT, obj: &queue.pad, "Forwarding {:?}", event);
+                        pad.push_event(event).await;
+                    }
+
+                    return Ok(());
+                }
+                DataQueueItem::Buffer(buffer) => {
+                    if self.active_pad.as_ref() != Some(&queue.pad) {
+                        self.switch_to(&pad, &queue).await;
+                    }
+
+                    gst::log!(CAT, obj: &queue.pad, "Forwarding {:?}", buffer);
+                    pad.push(buffer).await.map(drop)
+                }
+                DataQueueItem::BufferList(list) => {
+                    if self.active_pad.as_ref() != Some(&queue.pad) {
+                        self.switch_to(&pad, &queue).await;
+                    }
+
+                    gst::log!(CAT, obj: &queue.pad, "Forwarding {:?}", list);
+                    pad.push_list(list).await.map(drop)
+                }
+            };
+
+            match res {
+                Ok(()) => {
+                    gst::log!(CAT, obj: &self.element, "Successfully pushed item");
+                    *funnel.last_res.lock().unwrap() = Ok(gst::FlowSuccess::Ok);
+                }
+                Err(gst::FlowError::Flushing) => {
+                    gst::debug!(CAT, obj: &self.element, "Flushing");
+                    *funnel.last_res.lock().unwrap() = Err(gst::FlowError::Flushing);
+                }
+                Err(gst::FlowError::Eos) => {
+                    gst::debug!(CAT, obj: &self.element, "EOS");
+                    *funnel.last_res.lock().unwrap() = Err(gst::FlowError::Eos);
+                    self.eos_sent = true;
+                    pad.push_event(gst::event::Eos::new()).await;
+                }
+                Err(err) => {
+                    gst::error!(CAT, obj: &self.element, "Got error {}", err);
+                    gst::element_error!(
+                        &self.element,
+                        gst::StreamError::Failed,
+                        ("Internal data stream error"),
+                        ["streaming stopped, reason {}", err]
+                    );
+                    *funnel.last_res.lock().unwrap() = Err(err);
+                }
+            }
+
+            res
+        }
+        .boxed()
+    }
+
+    fn stop(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Stopping task");
+
+            let funnel = self.element.imp();
+            let mut last_res = funnel.last_res.lock().unwrap();
+
+            {
+                let mut state = funnel.state.lock().unwrap();
+                for branch in state.branches.iter() {
+                    branch.queue.dataqueue.stop();
+                    branch.queue.dataqueue.clear();
+                }
+                state.started = false;
+            }
+
+            *last_res = Err(gst::FlowError::Flushing);
+
+            gst::log!(CAT, obj: &self.element, "Task stopped");
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn flush_start(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Starting task flush");
+
+            let funnel = self.element.imp();
+            *funnel.last_res.lock().unwrap() = Err(gst::FlowError::Flushing);
+
+            gst::log!(CAT, obj: &self.element, "Task flush started");
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn flush_stop(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Stopping task flush");
+
+            let funnel = self.element.imp();
+            *funnel.last_res.lock().unwrap() = Ok(gst::FlowSuccess::Ok);
+
+            // Downstream got flushed, make sure it receives
+            // the sticky events again before the next buffer
+            self.active_pad = None;
+            self.eos_sent = false;
+
+            gst::log!(CAT, obj: &self.element, "Task flush stopped");
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+#[derive(Debug)]
+pub struct Funnel {
+    src_pad: PadSrc,
+    task: Task,
+    state: StdMutex<State>,
+    last_res: StdMutex<Result<gst::FlowSuccess, gst::FlowError>>,
+    settings: StdMutex<Settings>,
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "ts-funnel",
+        gst::DebugColorFlags::empty(),
+        Some("Thread-sharing funnel"),
+    )
+});
+
+impl Funnel {
+    fn branch_queue(&self, pad: &gst::Pad) -> Option<BranchQueue> {
+        let state = self.state.lock().unwrap();
+        state
+            .branches
+            .iter()
+            .find(|branch| &branch.queue.pad == pad)
+            .map(|branch| branch.queue.clone())
+    }
+
+    fn sink_pads(&self) -> Vec<gst::Pad> {
+        let state = self.state.lock().unwrap();
+        state
+            .branches
+            .iter()
+            .map(|branch| branch.queue.pad.clone())
+            .collect()
+    }
+
+    async fn enqueue_item(
+        &self,
+        pad: &gst::Pad,
+        item: DataQueueItem,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        let branch = self.branch_queue(pad).ok_or_else(|| {
+            gst::error!(CAT, obj: pad, "No DataQueue");
+            gst::FlowError::Error
+        })?;
+
+        if branch.dataqueue.push_wait(item).await.is_err() {
+            gst::debug!(CAT, obj: pad, "Flushing");
+            return Err(gst::FlowError::Flushing);
+        }
+
+        *self.last_res.lock().unwrap()
+    }
+
+    fn prepare(&self, element: &super::Funnel) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Preparing");
+
+        let settings = self.settings.lock().unwrap().clone();
+
+        let context =
+            Context::acquire(&settings.context, settings.context_wait).map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::OpenRead,
+                    ["Failed to acquire Context: {}", err]
+                )
+            })?;
+
+        self.task
+            .prepare(FunnelTask::new(element, &self.src_pad), context)
+            .map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::OpenRead,
+                    ["Error preparing Task: {:?}", err]
+                )
+            })?;
+
+        gst::debug!(CAT, obj: element, "Prepared");
+
+        Ok(())
+    }
+
+    fn unprepare(&self, element: &super::Funnel) {
+        gst::debug!(CAT, obj: element, "Unpreparing");
+
+        self.task.unprepare().unwrap();
+        *self.last_res.lock().unwrap() = Ok(gst::FlowSuccess::Ok);
+
+        gst::debug!(CAT, obj: element, "Unprepared");
+    }
+
+    fn stop(&self, element: &super::Funnel) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Stopping");
+        self.task.stop()?;
+        gst::debug!(CAT, obj: element, "Stopped");
+        Ok(())
+    }
+
+    fn start(&self, element: &super::Funnel) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Starting");
+        self.task.start()?;
+        gst::debug!(CAT, obj: element, "Started");
+        Ok(())
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for Funnel {
+    const NAME: &'static str = "RsTsFunnel";
+    type Type = super::Funnel;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        Self {
+            src_pad: PadSrc::new(
+                gst::Pad::from_template(&klass.pad_template("src").unwrap(), Some("src")),
+                FunnelPadSrcHandler,
+            ),
+            task: Task::default(),
+            state: StdMutex::new(State::default()),
+            last_res: StdMutex::new(Ok(gst::FlowSuccess::Ok)),
+            settings: StdMutex::new(Settings::default()),
+        }
+    }
+}
+
+impl ObjectImpl for Funnel {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecString::new(
+                    "context",
+                    "Context",
+                    "Context name to share threads with",
+                    Some(DEFAULT_CONTEXT),
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "context-wait",
+                    "Context Wait",
+                    "Throttle poll loop to run at most once every this many ms",
+                    0,
+                    1000,
+                    DEFAULT_CONTEXT_WAIT.as_millis() as u32,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "max-size-buffers",
+                    "Max Size Buffers",
+                    "Maximum number of buffers to queue per sink pad (0=unlimited)",
+                    0,
+                    u32::MAX,
+                    DEFAULT_MAX_SIZE_BUFFERS,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "max-size-bytes",
+                    "Max Size Bytes",
+                    "Maximum number of bytes to queue per sink pad (0=unlimited)",
+                    0,
+                    u32::MAX,
+                    DEFAULT_MAX_SIZE_BYTES,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt64::new(
+                    "max-size-time",
+                    "Max Size Time",
+                    "Maximum number of nanoseconds to queue per sink pad (0=unlimited)",
+                    0,
+                    u64::MAX - 1,
+                    DEFAULT_MAX_SIZE_TIME.nseconds(),
+                    glib::ParamFlags::READWRITE,
+                ),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        let mut settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "max-size-buffers" => {
+                settings.max_size_buffers = value.get().expect("type checked upstream");
+            }
+            "max-size-bytes" => {
+                settings.max_size_bytes = value.get().expect("type checked upstream");
+            }
+            "max-size-time" => {
+                settings.max_size_time =
+                    gst::ClockTime::from_nseconds(value.get().expect("type checked upstream"));
+            }
+            "context" => {
+                settings.context = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream")
+                    .unwrap_or_else(|| DEFAULT_CONTEXT.into());
+            }
+            "context-wait" => {
+                settings.context_wait = Duration::from_millis(
+                    value.get::<u32>().expect("type checked upstream").into(),
+                );
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        let settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "max-size-buffers" => settings.max_size_buffers.to_value(),
+            "max-size-bytes" => settings.max_size_bytes.to_value(),
+            "max-size-time" => settings.max_size_time.nseconds().to_value(),
+            "context" => settings.context.to_value(),
+            "context-wait" => (settings.context_wait.as_millis() as u32).to_value(),
+            _ => unimplemented!(),
+        }
+    }
+
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(self.src_pad.gst_pad()).unwrap();
+    }
+}
+
+impl GstObjectImpl for Funnel {}
+
+impl ElementImpl for Funnel {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Thread-sharing funnel",
+                "Generic",
+                "Queues multiple streams into a single one",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::new_any();
+
+            let sink_pad_template = gst::PadTemplate::new(
+                "sink_%u",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Request,
+                &caps,
+            )
+            .unwrap();
+
+            let src_pad_template = gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![sink_pad_template, src_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        match transition {
+            gst::StateChange::NullToReady => {
+                self.prepare(element).map_err(|err| {
+                    element.post_error_message(err);
+                    gst::StateChangeError
+                })?;
+            }
+            gst::StateChange::PausedToReady => {
+                self.stop(element).map_err(|_| gst::StateChangeError)?;
+            }
+            gst::StateChange::ReadyToNull => {
+                self.unprepare(element);
+            }
+            _ => (),
+        }
+
+        let success = self.parent_change_state(element, transition)?;
+
+        if transition == gst::StateChange::ReadyToPaused {
+            self.start(element).map_err(|_| gst::StateChangeError)?;
+        }
+
+        Ok(success)
+    }
+
+    fn request_new_pad(
+        &self,
+        element: &Self::Type,
+        templ: &gst::PadTemplate,
+        _name: Option<String>,
+        _caps: Option<&gst::Caps>,
+    ) -> Option<gst::Pad> {
+        let settings = self.settings.lock().unwrap().clone();
+        let mut state = self.state.lock().unwrap();
+
+        let sink_pad = PadSink::new(
+            gst::Pad::from_template(templ, Some(format!("sink_{}", state.pad_serial).as_str())),
+            FunnelPadSinkHandler,
+        );
+        state.pad_serial += 1;
+
+        let dataqueue = DataQueue::new(
+            &element.clone().upcast(),
+            sink_pad.gst_pad(),
+            if settings.max_size_buffers == 0 {
+                None
+            } else {
+                Some(settings.max_size_buffers)
+            },
+            if settings.max_size_bytes == 0 {
+                None
+            } else {
+                Some(settings.max_size_bytes)
+            },
+            if settings.max_size_time.is_zero() {
+                None
+            } else {
+                Some(settings.max_size_time)
+            },
+        );
+        if state.started {
+            dataqueue.start();
+        }
+
+        let ret = sink_pad.gst_pad().clone();
+        ret.set_active(true).unwrap();
+
+        state.branches.push(Branch {
+            _sink_pad: sink_pad,
+            queue: BranchQueue {
+                pad: ret.clone(),
+                dataqueue,
+                eos: Arc::new(AtomicBool::new(false)),
+            },
+        });
+        state.notify_pads_changed();
+        drop(state);
+
+        element.add_pad(&ret).unwrap();
+
+        let _ = element.post_message(gst::message::Latency::builder().src(element).build());
+
+        Some(ret)
+    }
+
+    fn release_pad(&self, element: &Self::Type, pad: &gst::Pad) {
+        let branch = {
+            let mut state = self.state.lock().unwrap();
+            let idx = state
+                .branches
+                .iter()
+                .position(|branch| &branch.queue.pad == pad)
+                .unwrap();
+            let branch = state.branches.remove(idx);
+            state.notify_pads_changed();
+
+            branch
+        };
+
+        // Wake up the sink pad in case it was waiting for room in its queue
+        branch.queue.dataqueue.stop();
+        drop(branch);
+
+        element.remove_pad(pad).unwrap();
+
+        let _ = element.post_message(gst::message::Latency::builder().src(element).build());
+    }
+}
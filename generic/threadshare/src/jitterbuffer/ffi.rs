@@ -61,7 +61,6 @@ extern "C" {
     pub fn rtp_jitter_buffer_get_type() -> GType;
     #[allow(dead_code)]
     pub fn rtp_jitter_buffer_get_mode(jbuf: *mut RTPJitterBuffer) -> RTPJitterBufferMode;
-    #[allow(dead_code)]
     pub fn rtp_jitter_buffer_set_mode(jbuf: *mut RTPJitterBuffer, mode: RTPJitterBufferMode);
     #[allow(dead_code)]
     pub fn rtp_jitter_buffer_get_delay(jbuf: *mut RTPJitterBuffer) -> GstClockTime;
@@ -69,7 +68,22 @@ extern "C" {
     pub fn rtp_jitter_buffer_set_clock_rate(jbuf: *mut RTPJitterBuffer, clock_rate: c_uint);
     #[allow(dead_code)]
     pub fn rtp_jitter_buffer_get_clock_rate(jbuf: *mut RTPJitterBuffer) -> c_uint;
+    pub fn rtp_jitter_buffer_set_media_clock(
+        jbuf: *mut RTPJitterBuffer,
+        clock: *mut gst::ffi::GstClock,
+        clock_offset: u64,
+    );
+    pub fn rtp_jitter_buffer_set_pipeline_clock(
+        jbuf: *mut RTPJitterBuffer,
+        clock: *mut gst::ffi::GstClock,
+    );
+    pub fn rtp_jitter_buffer_set_rfc7273_sync(jbuf: *mut RTPJitterBuffer, rfc7273_sync: gboolean);
     pub fn rtp_jitter_buffer_reset_skew(jbuf: *mut RTPJitterBuffer);
+    pub fn rtp_jitter_buffer_is_buffering(jbuf: *mut RTPJitterBuffer) -> gboolean;
+    pub fn rtp_jitter_buffer_can_fast_start(
+        jbuf: *mut RTPJitterBuffer,
+        num_packet: c_int,
+    ) -> gboolean;
 
     pub fn rtp_jitter_buffer_flush(jbuf: *mut RTPJitterBuffer, free_func: glib::ffi::GFunc);
     pub fn rtp_jitter_buffer_find_earliest(
@@ -35,24 +35,71 @@ use std::sync::Arc;
 use std::sync::Mutex as StdMutex;
 use std::time::Duration;
 
+use crate::rtx::BUFFER_FLAG_RETRANSMISSION;
 use crate::runtime::prelude::*;
 use crate::runtime::{self, Context, PadSink, PadSinkRef, PadSrc, PadSrcRef, Task};
 
-use super::jitterbuffer::{RTPJitterBuffer, RTPJitterBufferItem, RTPPacketRateCtx};
+use super::jitterbuffer::{
+    RTPJitterBuffer, RTPJitterBufferItem, RTPJitterBufferMode, RTPPacketRateCtx,
+};
 
 const DEFAULT_LATENCY: gst::ClockTime = gst::ClockTime::from_mseconds(200);
 const DEFAULT_DO_LOST: bool = false;
+const DEFAULT_MODE: JitterBufferMode = JitterBufferMode::Slave;
+const DEFAULT_RFC7273_SYNC: bool = false;
+const DEFAULT_FASTSTART_MIN_PACKETS: u32 = 0;
 const DEFAULT_MAX_DROPOUT_TIME: u32 = 60000;
 const DEFAULT_MAX_MISORDER_TIME: u32 = 2000;
+const DEFAULT_DO_RETRANSMISSION: bool = false;
+const DEFAULT_RTX_DELAY: i32 = -1;
+const DEFAULT_RTX_RETRY_TIMEOUT: i32 = -1;
+const DEFAULT_RTX_RETRY_PERIOD: i32 = -1;
+const DEFAULT_RTX_MAX_RETRIES: i32 = -1;
 const DEFAULT_CONTEXT: &str = "";
 const DEFAULT_CONTEXT_WAIT: gst::ClockTime = gst::ClockTime::ZERO;
 
+const AUTO_RTX_DELAY: gst::ClockTime = gst::ClockTime::from_mseconds(20);
+const AUTO_RTX_TIMEOUT: gst::ClockTime = gst::ClockTime::from_mseconds(40);
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, glib::Enum)]
+#[repr(u32)]
+#[enum_type(name = "GstTsJitterBufferMode")]
+enum JitterBufferMode {
+    #[enum_value(name = "Only use RTP timestamps", nick = "none")]
+    None = 0,
+    #[enum_value(name = "Slave receiver to sender clock", nick = "slave")]
+    Slave = 1,
+    #[enum_value(name = "Do low/high watermark buffering", nick = "buffer")]
+    Buffer = 2,
+    #[enum_value(name = "Synchronized sender and receiver clocks", nick = "synced")]
+    Synced = 4,
+}
+
+impl From<JitterBufferMode> for RTPJitterBufferMode {
+    fn from(mode: JitterBufferMode) -> Self {
+        match mode {
+            JitterBufferMode::None => RTPJitterBufferMode::None,
+            JitterBufferMode::Slave => RTPJitterBufferMode::Slave,
+            JitterBufferMode::Buffer => RTPJitterBufferMode::Buffer,
+            JitterBufferMode::Synced => RTPJitterBufferMode::Synced,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct Settings {
     latency: gst::ClockTime,
     do_lost: bool,
+    mode: JitterBufferMode,
+    rfc7273_sync: bool,
+    faststart_min_packets: u32,
     max_dropout_time: u32,
     max_misorder_time: u32,
+    do_retransmission: bool,
+    rtx_delay: i32,
+    rtx_retry_timeout: i32,
+    rtx_retry_period: i32,
+    rtx_max_retries: i32,
     context: String,
     context_wait: gst::ClockTime,
 }
@@ -62,14 +109,81 @@ impl Default for Settings {
         Settings {
             latency: DEFAULT_LATENCY,
             do_lost: DEFAULT_DO_LOST,
+            mode: DEFAULT_MODE,
+            rfc7273_sync: DEFAULT_RFC7273_SYNC,
+            faststart_min_packets: DEFAULT_FASTSTART_MIN_PACKETS,
             max_dropout_time: DEFAULT_MAX_DROPOUT_TIME,
             max_misorder_time: DEFAULT_MAX_MISORDER_TIME,
+            do_retransmission: DEFAULT_DO_RETRANSMISSION,
+            rtx_delay: DEFAULT_RTX_DELAY,
+            rtx_retry_timeout: DEFAULT_RTX_RETRY_TIMEOUT,
+            rtx_retry_period: DEFAULT_RTX_RETRY_PERIOD,
+            rtx_max_retries: DEFAULT_RTX_MAX_RETRIES,
             context: DEFAULT_CONTEXT.into(),
             context_wait: DEFAULT_CONTEXT_WAIT,
         }
     }
 }
 
+/// Creates the reference clock described by an RFC 7273 `a-ts-refclk` attribute.
+///
+/// Returns `None` for `local` and unsupported reference clocks, in which case the
+/// pipeline clock is used.
+fn media_clock(element: &super::JitterBuffer, ts_refclk: &str) -> Option<gst::Clock> {
+    if let Some(ntp) = ts_refclk.strip_prefix("ntp=") {
+        let (host, port) = if ntp.starts_with("/traceable/") {
+            ("pool.ntp.org", 123)
+        } else if let Some(ntp) = ntp.strip_prefix('[') {
+            let (host, port) = ntp.split_once(']')?;
+            match port.strip_prefix(':') {
+                Some(port) => (host, port.parse::<i32>().ok()?),
+                None => (host, 123),
+            }
+        } else {
+            match ntp.split_once(':') {
+                Some((host, port)) => (host, port.parse::<i32>().ok()?),
+                None => (ntp, 123),
+            }
+        };
+
+        gst::info!(CAT, obj: element, "Using NTP clock {}:{}", host, port);
+
+        Some(gst_net::NtpClock::new(None, host, port, gst::ClockTime::ZERO).upcast())
+    } else if let Some(ptp) = ts_refclk.strip_prefix("ptp=IEEE1588-2008:") {
+        // The grandmaster clock identity is optionally followed by the domain
+        let domain = match ptp.split_once(':') {
+            Some((_, domain)) => domain.parse::<u32>().ok()?,
+            None => 0,
+        };
+
+        if let Err(err) = gst_net::PtpClock::init(None, &[]) {
+            gst::warning!(CAT, obj: element, "Failed to initialize PTP: {}", err);
+            return None;
+        }
+
+        gst::info!(CAT, obj: element, "Using PTP clock for domain {}", domain);
+
+        match gst_net::PtpClock::new(None, domain) {
+            Ok(clock) => Some(clock.upcast()),
+            Err(err) => {
+                gst::warning!(CAT, obj: element, "Failed to create PTP clock: {}", err);
+                None
+            }
+        }
+    } else {
+        if ts_refclk != "local" {
+            gst::warning!(
+                CAT,
+                obj: element,
+                "Unsupported reference clock {}",
+                ts_refclk
+            );
+        }
+
+        None
+    }
+}
+
 #[derive(Eq)]
 struct GapPacket {
     buffer: gst::Buffer,
@@ -164,6 +278,9 @@ impl SinkHandler {
         state.earliest_pts = None;
         state.earliest_seqnum = None;
 
+        state.rtx_timers.clear();
+        state.rtx_highest_seqnum = None;
+
         inner.ips_rtptime = None;
         inner.ips_pts = None;
 
@@ -201,6 +318,33 @@ impl SinkHandler {
         inner.packet_rate_ctx.reset(clock_rate);
         state.jbuf.borrow().set_clock_rate(clock_rate as u32);
 
+        // RFC 7273 reference and media clock signalling
+        let ts_refclk = s.get::<String>("a-ts-refclk").ok();
+        let clock_offset = s
+            .get::<&str>("a-mediaclk")
+            .ok()
+            .and_then(|mediaclk| mediaclk.strip_prefix("direct="))
+            .and_then(|offset| offset.parse::<u64>().ok());
+
+        if ts_refclk != state.ts_refclk {
+            state.media_clock = ts_refclk
+                .as_deref()
+                .and_then(|ts_refclk| media_clock(element, ts_refclk));
+            state.ts_refclk = ts_refclk;
+        }
+
+        gst::debug!(
+            CAT,
+            obj: element,
+            "Using media clock {:?} with offset {:?}",
+            state.media_clock,
+            clock_offset
+        );
+        state
+            .jbuf
+            .borrow()
+            .set_media_clock(state.media_clock.as_ref(), clock_offset);
+
         Ok(gst::FlowSuccess::Ok)
     }
 
@@ -296,6 +440,92 @@ impl SinkHandler {
         reset
     }
 
+    fn update_rtx_timers(
+        &self,
+        state: &mut State,
+        element: &super::JitterBuffer,
+        seq: u16,
+        pts: gst::ClockTime,
+        is_rtx: bool,
+        max_dropout: i32,
+    ) {
+        if let Some(idx) = state.rtx_timers.iter().position(|timer| timer.seq == seq) {
+            let timer = state.rtx_timers.remove(idx);
+
+            if is_rtx && timer.num_retries > 0 {
+                state.stats.num_rtx_success += 1;
+
+                // Only take unambiguous samples into account for the RTT estimation
+                if timer.num_retries == 1 {
+                    let rtt = element
+                        .current_running_time()
+                        .opt_checked_sub(timer.last_request)
+                        .ok()
+                        .flatten();
+                    if let Some(rtt) = rtt {
+                        let avg_rtx_rtt = match state.avg_rtx_rtt {
+                            Some(avg_rtx_rtt) => (rtt + 7 * avg_rtx_rtt) / 8,
+                            None => rtt,
+                        };
+
+                        gst::debug!(
+                            CAT,
+                            obj: element,
+                            "Retransmission RTT {}, average {}",
+                            rtt,
+                            avg_rtx_rtt
+                        );
+
+                        state.avg_rtx_rtt = Some(avg_rtx_rtt);
+                    }
+                }
+            }
+        }
+
+        let highest_seqnum = match state.rtx_highest_seqnum {
+            Some(highest_seqnum) => highest_seqnum,
+            None => {
+                state.rtx_highest_seqnum = Some(seq);
+                return;
+            }
+        };
+
+        let gap = gst_rtp::compare_seqnum(highest_seqnum, seq);
+        if gap <= 0 {
+            return;
+        }
+
+        state.rtx_highest_seqnum = Some(seq);
+
+        if gap == 1 || gap >= max_dropout {
+            return;
+        }
+
+        let rtx_delay = state.rtx_delay(&element.imp().settings.lock().unwrap());
+        let spacing = state.packet_spacing;
+
+        for idx in 1..gap {
+            let missing_seqnum = highest_seqnum.wrapping_add(idx as u16);
+            let expected = pts.saturating_sub((gap - idx) as u64 * spacing);
+
+            gst::debug!(
+                CAT,
+                obj: element,
+                "Scheduling retransmission of #{} expected at {}",
+                missing_seqnum,
+                expected
+            );
+
+            state.rtx_timers.push(RtxTimer {
+                seq: missing_seqnum,
+                expected,
+                deadline: expected + rtx_delay,
+                last_request: None,
+                num_retries: 0,
+            });
+        }
+    }
+
     fn store(
         &self,
         inner: &mut SinkHandlerInner,
@@ -306,20 +536,26 @@ impl SinkHandler {
         let jb = element.imp();
         let mut state = jb.state.lock().unwrap();
 
-        let (max_misorder_time, max_dropout_time) = {
+        let (max_misorder_time, max_dropout_time, do_retransmission) = {
             let settings = jb.settings.lock().unwrap();
-            (settings.max_misorder_time, settings.max_dropout_time)
+            (
+                settings.max_misorder_time,
+                settings.max_dropout_time,
+                settings.do_retransmission,
+            )
         };
 
-        let (seq, rtptime, pt) = {
+        let (seq, rtptime, pt, ssrc) = {
             let rtp_buffer =
                 RTPBuffer::from_buffer_readable(&buffer).map_err(|_| gst::FlowError::Error)?;
             (
                 rtp_buffer.seq(),
                 rtp_buffer.timestamp(),
                 rtp_buffer.payload_type(),
+                rtp_buffer.ssrc(),
             )
         };
+        let is_rtx = buffer.flags().contains(BUFFER_FLAG_RETRANSMISSION);
 
         let mut pts = buffer.pts();
         let mut dts = buffer.dts();
@@ -436,6 +672,18 @@ impl SinkHandler {
             }
         }
 
+        state.last_ssrc = Some(ssrc);
+        if do_retransmission {
+            self.update_rtx_timers(
+                &mut state,
+                element,
+                seq,
+                pts.unwrap(),
+                is_rtx,
+                max_dropout as i32,
+            );
+        }
+
         inner.last_in_seqnum = Some(seq);
 
         let jb_item = if estimated_dts {
@@ -444,7 +692,11 @@ impl SinkHandler {
             RTPJitterBufferItem::new(buffer, dts, pts, Some(seq), rtptime)
         };
 
-        let (success, _, _) = state.jbuf.borrow().insert(jb_item);
+        let (success, _, percent) = state.jbuf.borrow().insert(jb_item);
+
+        if percent != -1 {
+            state.buffering_percent = Some(percent);
+        }
 
         if !success {
             /* duplicate */
@@ -520,7 +772,7 @@ impl SinkHandler {
         // Reschedule if needed
         let (_, next_wakeup) =
             jb.src_pad_handler
-                .next_wakeup(element, &state, latency, context_wait);
+                .next_task_wakeup(element, &state, latency, context_wait);
         if let Some((next_wakeup, _)) = next_wakeup {
             if let Some((previous_next_wakeup, ref abort_handle)) = state.wait_handle {
                 if previous_next_wakeup.is_none()
@@ -538,7 +790,16 @@ impl SinkHandler {
                 }
             }
         }
-        state.last_res
+
+        let res = state.last_res;
+        let percent = state.buffering_percent.take();
+        drop(state);
+
+        if let Some(percent) = percent {
+            jb.post_buffering(element, percent);
+        }
+
+        res
     }
 }
 
@@ -756,11 +1017,11 @@ impl SrcHandler {
     ) -> Result<gst::FlowSuccess, gst::FlowError> {
         let jb = element.imp();
 
-        let (lost_events, buffer, seq) = {
+        let (lost_events, buffer, seq, percent) = {
             let mut state = jb.state.lock().unwrap();
 
             let mut discont = false;
-            let (jb_item, _) = state.jbuf.borrow().pop();
+            let (jb_item, percent) = state.jbuf.borrow().pop();
 
             let jb_item = match jb_item {
                 None => {
@@ -812,11 +1073,22 @@ impl SrcHandler {
             }
             state.last_popped_seqnum = seq;
 
+            if let Some(seq) = seq {
+                // Packets up to this one can't be pushed anymore
+                state
+                    .rtx_timers
+                    .retain(|timer| gst_rtp::compare_seqnum(seq, timer.seq) > 0);
+            }
+
             state.stats.num_pushed += 1;
 
-            (lost_events, buffer, seq)
+            (lost_events, buffer, seq, percent)
         };
 
+        if percent != -1 {
+            jb.post_buffering(element, percent);
+        }
+
         for event in lost_events {
             gst::debug!(CAT, obj: jb.src_pad.gst_pad(), "Pushing lost event {:?}", event);
             let _ = jb.src_pad.push_event(event).await;
@@ -859,6 +1131,20 @@ impl SrcHandler {
             return (now, None);
         }
 
+        if state.jbuf.borrow().is_buffering() {
+            gst::debug!(CAT, obj: element, "Buffering, not waking up");
+            return (now, None);
+        }
+
+        let faststart_min_packets = element.imp().settings.lock().unwrap().faststart_min_packets;
+        if faststart_min_packets > 0
+            && state.last_popped_seqnum.is_none()
+            && state.jbuf.borrow().can_fast_start(faststart_min_packets)
+        {
+            gst::debug!(CAT, obj: element, "Enough consecutive packets, fast starting");
+            return (now, Some((now, Duration::ZERO)));
+        }
+
         let next_wakeup = state
             .earliest_pts
             .map(|earliest_pts| earliest_pts + latency - state.packet_spacing - context_wait / 2);
@@ -877,6 +1163,122 @@ impl SrcHandler {
 
         (now, Some((next_wakeup, delay.into())))
     }
+
+    // Same as `next_wakeup`, but also takes pending retransmission requests into account
+    fn next_task_wakeup(
+        &self,
+        element: &super::JitterBuffer,
+        state: &State,
+        latency: gst::ClockTime,
+        context_wait: gst::ClockTime,
+    ) -> (
+        Option<gst::ClockTime>,
+        Option<(Option<gst::ClockTime>, Duration)>,
+    ) {
+        let (now, next_wakeup) = self.next_wakeup(element, state, latency, context_wait);
+
+        let rtx_wakeup = match state.rtx_timers.iter().map(|timer| timer.deadline).min() {
+            Some(rtx_wakeup) => rtx_wakeup,
+            None => return (now, next_wakeup),
+        };
+
+        let rtx_delay: Duration = Some(rtx_wakeup)
+            .opt_saturating_sub(now)
+            .unwrap_or(gst::ClockTime::ZERO)
+            .into();
+
+        match next_wakeup {
+            Some((_, delay)) if delay <= rtx_delay => (now, next_wakeup),
+            _ => {
+                gst::debug!(
+                    CAT,
+                    obj: element,
+                    "Next retransmission request at {} with delay {:?}",
+                    rtx_wakeup,
+                    rtx_delay
+                );
+
+                (now, Some((Some(rtx_wakeup), rtx_delay)))
+            }
+        }
+    }
+
+    fn rtx_requests(&self, element: &super::JitterBuffer) -> Vec<gst::Event> {
+        let jb = element.imp();
+        let settings = jb.settings.lock().unwrap().clone();
+        let mut state = jb.state.lock().unwrap();
+
+        let mut events = vec![];
+
+        if !settings.do_retransmission {
+            state.rtx_timers.clear();
+            return events;
+        }
+
+        let (now, ssrc) = match (element.current_running_time(), state.last_ssrc) {
+            (Some(now), Some(ssrc)) => (now, ssrc),
+            _ => return events,
+        };
+
+        let retry_timeout = state.rtx_retry_timeout(&settings);
+        let retry_period = if settings.rtx_retry_period >= 0 {
+            gst::ClockTime::from_mseconds(settings.rtx_retry_period as u64)
+        } else {
+            settings.latency
+        };
+
+        let mut rtx_timers = Vec::with_capacity(state.rtx_timers.len());
+        for mut timer in mem::take(&mut state.rtx_timers) {
+            if timer.deadline > now {
+                rtx_timers.push(timer);
+                continue;
+            }
+
+            let delay = now.saturating_sub(timer.expected);
+            if delay >= retry_period
+                || (settings.rtx_max_retries >= 0
+                    && timer.num_retries >= settings.rtx_max_retries as u32)
+            {
+                gst::debug!(
+                    CAT,
+                    obj: element,
+                    "Giving up on #{} after {} retries",
+                    timer.seq,
+                    timer.num_retries
+                );
+                continue;
+            }
+
+            let s = gst::Structure::builder("GstRTPRetransmissionRequest")
+                .field("seqnum", timer.seq as u32)
+                .field("running-time", timer.expected)
+                .field("delay", delay.mseconds() as u32)
+                .field("retry", timer.num_retries)
+                .field("frequency", retry_timeout.mseconds() as u32)
+                .field("period", retry_period.mseconds() as u32)
+                .field("deadline", settings.latency.mseconds() as u32)
+                .field("packet-spacing", state.packet_spacing)
+                .field(
+                    "avg-rtt",
+                    state.avg_rtx_rtt.map_or(0, |rtt| rtt.mseconds() as u32),
+                )
+                .field("ssrc", ssrc)
+                .build();
+
+            events.push(gst::event::CustomUpstream::new(s));
+
+            timer.num_retries += 1;
+            timer.last_request = Some(now);
+            timer.deadline = now + retry_timeout;
+            state.stats.num_rtx_requests += 1;
+
+            rtx_timers.push(timer);
+        }
+
+        state.rtx_timers = rtx_timers;
+
+        events
+    }
 }
 
 impl PadSrcHandler for SrcHandler {
@@ -973,6 +1375,17 @@ struct Stats {
     num_pushed: u64,
     num_lost: u64,
     num_late: u64,
+    num_rtx_requests: u64,
+    num_rtx_success: u64,
+}
+
+#[derive(Debug)]
+struct RtxTimer {
+    seq: u16,
+    expected: gst::ClockTime,
+    deadline: gst::ClockTime,
+    last_request: Option<gst::ClockTime>,
+    num_retries: u32,
 }
 
 // Shared state between element, sink and source pad
@@ -999,6 +1412,15 @@ struct State {
     earliest_pts: Option<gst::ClockTime>,
     earliest_seqnum: Option<u16>,
 
+    ts_refclk: Option<String>,
+    media_clock: Option<gst::Clock>,
+    buffering_percent: Option<i32>,
+
+    last_ssrc: Option<u32>,
+    rtx_timers: Vec<RtxTimer>,
+    rtx_highest_seqnum: Option<u16>,
+    avg_rtx_rtt: Option<gst::ClockTime>,
+
     wait_handle: Option<(Option<gst::ClockTime>, AbortHandle)>,
 }
 
@@ -1027,11 +1449,40 @@ impl Default for State {
             earliest_pts: None,
             earliest_seqnum: None,
 
+            ts_refclk: None,
+            media_clock: None,
+            buffering_percent: None,
+
+            last_ssrc: None,
+            rtx_timers: Vec::new(),
+            rtx_highest_seqnum: None,
+            avg_rtx_rtt: None,
+
             wait_handle: None,
         }
     }
 }
 
+impl State {
+    fn rtx_delay(&self, settings: &Settings) -> gst::ClockTime {
+        if settings.rtx_delay >= 0 {
+            gst::ClockTime::from_mseconds(settings.rtx_delay as u64)
+        } else {
+            max(self.packet_spacing, AUTO_RTX_DELAY)
+        }
+    }
+
+    fn rtx_retry_timeout(&self, settings: &Settings) -> gst::ClockTime {
+        if settings.rtx_retry_timeout >= 0 {
+            gst::ClockTime::from_mseconds(settings.rtx_retry_timeout as u64)
+        } else if let Some(avg_rtx_rtt) = self.avg_rtx_rtt {
+            max(2 * avg_rtx_rtt, self.packet_spacing)
+        } else {
+            AUTO_RTX_TIMEOUT
+        }
+    }
+}
+
 struct JitterBufferTask {
     element: super::JitterBuffer,
     src_pad_handler: SrcHandler,
@@ -1061,7 +1512,10 @@ impl TaskImpl for JitterBufferTask {
             self.sink_pad_handler.clear();
 
             let jb = self.element.imp();
-            *jb.state.lock().unwrap() = State::default();
+            let mut state = jb.state.lock().unwrap();
+            *state = State::default();
+            jb.configure_jbuf(&self.element, &state);
+            drop(state);
 
             gst::log!(CAT, obj: &self.element, "Task started");
             Ok(())
@@ -1080,7 +1534,7 @@ impl TaskImpl for JitterBufferTask {
             loop {
                 let delay_fut = {
                     let mut state = jb.state.lock().unwrap();
-                    let (_, next_wakeup) = self.src_pad_handler.next_wakeup(
+                    let (_, next_wakeup) = self.src_pad_handler.next_task_wakeup(
                         &self.element,
                         &state,
                         latency,
@@ -1120,6 +1574,11 @@ impl TaskImpl for JitterBufferTask {
                     }
                 }
 
+                for event in self.src_pad_handler.rtx_requests(&self.element) {
+                    gst::debug!(CAT, obj: jb.sink_pad.gst_pad(), "Requesting {:?}", event);
+                    let _ = jb.sink_pad.gst_pad().push_event(event);
+                }
+
                 let (head_pts, head_seq) = {
                     let state = jb.state.lock().unwrap();
                     //
@@ -1255,6 +1714,26 @@ impl JitterBuffer {
         state.jbuf.borrow().reset_skew();
     }
 
+    fn configure_jbuf(&self, element: &super::JitterBuffer, state: &State) {
+        let settings = self.settings.lock().unwrap();
+        let jbuf = state.jbuf.borrow();
+
+        jbuf.set_mode(settings.mode.into());
+        jbuf.set_delay(settings.latency);
+        jbuf.set_rfc7273_sync(settings.rfc7273_sync);
+        jbuf.set_pipeline_clock(element.clock().as_ref());
+    }
+
+    fn post_buffering(&self, element: &super::JitterBuffer, percent: i32) {
+        gst::debug!(CAT, obj: element, "Buffering {}%", percent);
+
+        let _ = element.post_message(
+            gst::message::Buffering::builder(percent)
+                .src(element)
+                .build(),
+        );
+    }
+
     fn prepare(&self, element: &super::JitterBuffer) -> Result<(), gst::ErrorMessage> {
         gst::info!(CAT, obj: element, "Preparing");
 
@@ -1365,6 +1844,30 @@ impl ObjectImpl for JitterBuffer {
                     DEFAULT_DO_LOST,
                     glib::ParamFlags::READWRITE,
                 ),
+                glib::ParamSpecEnum::new(
+                    "mode",
+                    "Mode",
+                    "Control the buffering algorithm in use",
+                    JitterBufferMode::static_type(),
+                    DEFAULT_MODE as i32,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "rfc7273-sync",
+                    "Sync on RFC7273 clock",
+                    "Synchronize received streams to the RFC7273 clock (requires clock and offset to be provided)",
+                    DEFAULT_RFC7273_SYNC,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "faststart-min-packets",
+                    "Faststart minimum packets",
+                    "The number of consecutive packets needed to start (set to 0 to disable)",
+                    0,
+                    std::u32::MAX,
+                    DEFAULT_FASTSTART_MIN_PACKETS,
+                    glib::ParamFlags::READWRITE,
+                ),
                 glib::ParamSpecUInt::new(
                     "max-dropout-time",
                     "Max dropout time",
@@ -1383,6 +1886,49 @@ impl ObjectImpl for JitterBuffer {
                     DEFAULT_MAX_MISORDER_TIME,
                     glib::ParamFlags::READWRITE,
                 ),
+                glib::ParamSpecBoolean::new(
+                    "do-retransmission",
+                    "Do Retransmission",
+                    "Send retransmission events upstream when a packet is late/lost",
+                    DEFAULT_DO_RETRANSMISSION,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecInt::new(
+                    "rtx-delay",
+                    "RTX Delay",
+                    "Extra time in ms to wait before sending retransmission event (-1 automatic)",
+                    -1,
+                    std::i32::MAX,
+                    DEFAULT_RTX_DELAY,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecInt::new(
+                    "rtx-retry-timeout",
+                    "RTX Retry Timeout",
+                    "Retry sending a retransmission event after this timeout in ms (-1 automatic)",
+                    -1,
+                    std::i32::MAX,
+                    DEFAULT_RTX_RETRY_TIMEOUT,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecInt::new(
+                    "rtx-retry-period",
+                    "RTX Retry Period",
+                    "Try to get a retransmission for this many ms (-1 automatic)",
+                    -1,
+                    std::i32::MAX,
+                    DEFAULT_RTX_RETRY_PERIOD,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecInt::new(
+                    "rtx-max-retries",
+                    "RTX Max Retries",
+                    "The maximum number of retries to request a retransmission (-1 unlimited)",
+                    -1,
+                    std::i32::MAX,
+                    DEFAULT_RTX_MAX_RETRIES,
+                    glib::ParamFlags::READWRITE,
+                ),
                 glib::ParamSpecBoxed::new(
                     "stats",
                     "Statistics",
@@ -1442,6 +1988,26 @@ impl ObjectImpl for JitterBuffer {
                 let mut settings = self.settings.lock().unwrap();
                 settings.do_lost = value.get().expect("type checked upstream");
             }
+            "mode" => {
+                let mode = value
+                    .get::<JitterBufferMode>()
+                    .expect("type checked upstream");
+                self.settings.lock().unwrap().mode = mode;
+
+                let state = self.state.lock().unwrap();
+                state.jbuf.borrow().set_mode(mode.into());
+            }
+            "rfc7273-sync" => {
+                let rfc7273_sync = value.get().expect("type checked upstream");
+                self.settings.lock().unwrap().rfc7273_sync = rfc7273_sync;
+
+                let state = self.state.lock().unwrap();
+                state.jbuf.borrow().set_rfc7273_sync(rfc7273_sync);
+            }
+            "faststart-min-packets" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.faststart_min_packets = value.get().expect("type checked upstream");
+            }
             "max-dropout-time" => {
                 let mut settings = self.settings.lock().unwrap();
                 settings.max_dropout_time = value.get().expect("type checked upstream");
@@ -1450,6 +2016,26 @@ impl ObjectImpl for JitterBuffer {
                 let mut settings = self.settings.lock().unwrap();
                 settings.max_misorder_time = value.get().expect("type checked upstream");
             }
+            "do-retransmission" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.do_retransmission = value.get().expect("type checked upstream");
+            }
+            "rtx-delay" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.rtx_delay = value.get().expect("type checked upstream");
+            }
+            "rtx-retry-timeout" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.rtx_retry_timeout = value.get().expect("type checked upstream");
+            }
+            "rtx-retry-period" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.rtx_retry_period = value.get().expect("type checked upstream");
+            }
+            "rtx-max-retries" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.rtx_max_retries = value.get().expect("type checked upstream");
+            }
             "context" => {
                 let mut settings = self.settings.lock().unwrap();
                 settings.context = value
@@ -1477,6 +2063,18 @@ impl ObjectImpl for JitterBuffer {
                 let settings = self.settings.lock().unwrap();
                 settings.do_lost.to_value()
             }
+            "mode" => {
+                let settings = self.settings.lock().unwrap();
+                settings.mode.to_value()
+            }
+            "rfc7273-sync" => {
+                let settings = self.settings.lock().unwrap();
+                settings.rfc7273_sync.to_value()
+            }
+            "faststart-min-packets" => {
+                let settings = self.settings.lock().unwrap();
+                settings.faststart_min_packets.to_value()
+            }
             "max-dropout-time" => {
                 let settings = self.settings.lock().unwrap();
                 settings.max_dropout_time.to_value()
@@ -1485,12 +2083,35 @@ impl ObjectImpl for JitterBuffer {
                 let settings = self.settings.lock().unwrap();
                 settings.max_misorder_time.to_value()
             }
+            "do-retransmission" => {
+                let settings = self.settings.lock().unwrap();
+                settings.do_retransmission.to_value()
+            }
+            "rtx-delay" => {
+                let settings = self.settings.lock().unwrap();
+                settings.rtx_delay.to_value()
+            }
+            "rtx-retry-timeout" => {
+                let settings = self.settings.lock().unwrap();
+                settings.rtx_retry_timeout.to_value()
+            }
+            "rtx-retry-period" => {
+                let settings = self.settings.lock().unwrap();
+                settings.rtx_retry_period.to_value()
+            }
+            "rtx-max-retries" => {
+                let settings = self.settings.lock().unwrap();
+                settings.rtx_max_retries.to_value()
+            }
             "stats" => {
                 let state = self.state.lock().unwrap();
                 let s = gst::Structure::builder("application/x-rtp-jitterbuffer-stats")
                     .field("num-pushed", state.stats.num_pushed)
                     .field("num-lost", state.stats.num_lost)
                     .field("num-late", state.stats.num_late)
+                    .field("rtx-count", state.stats.num_rtx_requests)
+                    .field("rtx-success-count", state.stats.num_rtx_success)
+                    .field("rtx-rtt", state.avg_rtx_rtt.map_or(0, |rtt| rtt.nseconds()))
                     .build();
                 s.to_value()
             }
@@ -1599,4 +2220,12 @@ impl ElementImpl for JitterBuffer {
     fn provide_clock(&self, _element: &Self::Type) -> Option<gst::Clock> {
         Some(gst::SystemClock::obtain())
     }
+
+    fn set_clock(&self, element: &Self::Type, clock: Option<&gst::Clock>) -> bool {
+        let state = self.state.lock().unwrap();
+        state.jbuf.borrow().set_pipeline_clock(clock);
+        drop(state);
+
+        self.parent_set_clock(element, clock)
+    }
 }
@@ -230,7 +230,6 @@ impl RTPJitterBuffer {
         unsafe { from_glib(ffi::rtp_jitter_buffer_get_mode(self.to_glib_none().0)) }
     }
 
-    #[allow(dead_code)]
     pub fn set_mode(&self, mode: RTPJitterBufferMode) {
         unsafe { ffi::rtp_jitter_buffer_set_mode(self.to_glib_none().0, mode.into_glib()) }
     }
@@ -366,6 +365,41 @@ impl RTPJitterBuffer {
     pub fn reset_skew(&self) {
         unsafe { ffi::rtp_jitter_buffer_reset_skew(self.to_glib_none().0) }
     }
+
+    pub fn set_media_clock(&self, clock: Option<&gst::Clock>, clock_offset: Option<u64>) {
+        unsafe {
+            ffi::rtp_jitter_buffer_set_media_clock(
+                self.to_glib_none().0,
+                clock.to_glib_full(),
+                clock_offset.unwrap_or(u64::MAX),
+            )
+        }
+    }
+
+    pub fn set_pipeline_clock(&self, clock: Option<&gst::Clock>) {
+        unsafe {
+            ffi::rtp_jitter_buffer_set_pipeline_clock(self.to_glib_none().0, clock.to_glib_none().0)
+        }
+    }
+
+    pub fn set_rfc7273_sync(&self, rfc7273_sync: bool) {
+        unsafe {
+            ffi::rtp_jitter_buffer_set_rfc7273_sync(self.to_glib_none().0, rfc7273_sync.into_glib())
+        }
+    }
+
+    pub fn is_buffering(&self) -> bool {
+        unsafe { from_glib(ffi::rtp_jitter_buffer_is_buffering(self.to_glib_none().0)) }
+    }
+
+    pub fn can_fast_start(&self, num_packets: u32) -> bool {
+        unsafe {
+            from_glib(ffi::rtp_jitter_buffer_can_fast_start(
+                self.to_glib_none().0,
+                num_packets as i32,
+            ))
+        }
+    }
 }
 
 impl Default for RTPJitterBuffer {
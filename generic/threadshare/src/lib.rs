@@ -12,15 +12,27 @@ pub mod runtime;
 
 pub mod socket;
 mod tcpclientsrc;
+mod tcpserversink;
+mod tcpserversrc;
 mod udpsink;
 mod udpsrc;
 
 mod appsrc;
+mod audiotestsrc;
 pub mod dataqueue;
+mod fakesink;
+mod filesink;
+mod filesrc;
+mod funnel;
 mod inputselector;
 mod jitterbuffer;
 mod proxy;
 mod queue;
+mod rtprtxreceive;
+mod rtprtxsend;
+mod rtx;
+mod tee;
+mod videotestsrc;
 
 use glib::translate::*;
 use gst::glib;
@@ -29,11 +41,22 @@ fn plugin_init(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
     udpsrc::register(plugin)?;
     udpsink::register(plugin)?;
     tcpclientsrc::register(plugin)?;
+    tcpserversrc::register(plugin)?;
+    tcpserversink::register(plugin)?;
     queue::register(plugin)?;
     proxy::register(plugin)?;
     appsrc::register(plugin)?;
     jitterbuffer::register(plugin)?;
     inputselector::register(plugin)?;
+    rtprtxsend::register(plugin)?;
+    rtprtxreceive::register(plugin)?;
+    audiotestsrc::register(plugin)?;
+    videotestsrc::register(plugin)?;
+    fakesink::register(plugin)?;
+    filesrc::register(plugin)?;
+    filesink::register(plugin)?;
+    tee::register(plugin)?;
+    funnel::register(plugin)?;
 
     Ok(())
 }
@@ -19,6 +19,8 @@
 
 use futures::channel::oneshot;
 use futures::future::BoxFuture;
+#[cfg(target_os = "linux")]
+use futures::future::{abortable, AbortHandle};
 use futures::prelude::*;
 
 use gst::glib;
@@ -28,6 +30,9 @@ use gst::subclass::prelude::*;
 use once_cell::sync::Lazy;
 
 use std::collections::{HashMap, VecDeque};
+#[cfg(target_os = "linux")]
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
 use std::sync::Mutex as StdMutex;
 use std::sync::MutexGuard as StdMutexGuard;
 use std::sync::{Arc, Weak};
@@ -35,11 +40,16 @@ use std::time::Duration;
 use std::{u32, u64};
 
 use crate::runtime::prelude::*;
+#[cfg(target_os = "linux")]
+use crate::runtime::{self, Async};
 use crate::runtime::{
     Context, PadSink, PadSinkRef, PadSinkWeak, PadSrc, PadSrcRef, PadSrcWeak, Task,
 };
 
-use crate::dataqueue::{DataQueue, DataQueueItem};
+use crate::dataqueue::{DataQueue, DataQueueItem, DataQueueLeaky};
+
+#[cfg(target_os = "linux")]
+use super::ipc;
 
 static PROXY_CONTEXTS: Lazy<StdMutex<HashMap<String, Weak<StdMutex<ProxyContextInner>>>>> =
     Lazy::new(|| StdMutex::new(HashMap::new()));
@@ -49,6 +59,9 @@ static PROXY_SINK_PADS: Lazy<StdMutex<HashMap<String, PadSinkWeak>>> =
     Lazy::new(|| StdMutex::new(HashMap::new()));
 
 const DEFAULT_PROXY_CONTEXT: &str = "";
+const DEFAULT_SOCKET_PATH: Option<&str> = None;
+const DEFAULT_RING_SIZE: u32 = 4 * 1024 * 1024;
+const DEFAULT_LEAKY: DataQueueLeaky = DataQueueLeaky::No;
 
 const DEFAULT_MAX_SIZE_BUFFERS: u32 = 200;
 const DEFAULT_MAX_SIZE_BYTES: u32 = 1024 * 1024;
@@ -56,15 +69,24 @@ const DEFAULT_MAX_SIZE_TIME: gst::ClockTime = gst::ClockTime::SECOND;
 const DEFAULT_CONTEXT: &str = "";
 const DEFAULT_CONTEXT_WAIT: Duration = Duration::ZERO;
 
+#[cfg(target_os = "linux")]
+const IPC_ACCEPT_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
 #[derive(Debug, Clone)]
 struct SettingsSink {
     proxy_context: String,
+    socket_path: Option<String>,
+    ring_size: u32,
+    leaky: DataQueueLeaky,
 }
 
 impl Default for SettingsSink {
     fn default() -> Self {
         SettingsSink {
             proxy_context: DEFAULT_PROXY_CONTEXT.into(),
+            socket_path: DEFAULT_SOCKET_PATH.map(Into::into),
+            ring_size: DEFAULT_RING_SIZE,
+            leaky: DEFAULT_LEAKY,
         }
     }
 }
@@ -77,6 +99,7 @@ struct SettingsSrc {
     context: String,
     context_wait: Duration,
     proxy_context: String,
+    socket_path: Option<String>,
 }
 
 impl Default for SettingsSrc {
@@ -88,6 +111,20 @@ impl Default for SettingsSrc {
             context: DEFAULT_CONTEXT.into(),
             context_wait: DEFAULT_CONTEXT_WAIT,
             proxy_context: DEFAULT_PROXY_CONTEXT.into(),
+            socket_path: DEFAULT_SOCKET_PATH.map(Into::into),
+        }
+    }
+}
+
+impl SettingsSrc {
+    /// Name of the `ProxyContext` used by the src.
+    ///
+    /// In cross-process mode, the src uses a private `ProxyContext`
+    /// so that no `ts-proxysink` from the same process can join it.
+    fn proxy_context_name(&self) -> String {
+        match self.socket_path {
+            Some(ref socket_path) => format!("ipc:{}", socket_path),
+            None => self.proxy_context.clone(),
         }
     }
 }
@@ -113,10 +150,21 @@ struct ProxyContextInner {
     dataqueue: Option<DataQueue>,
     last_res: Result<gst::FlowSuccess, gst::FlowError>,
     pending_queue: Option<PendingQueue>,
+    // Set by the sink, applies to the DataQueue of the src
+    leaky: DataQueueLeaky,
     have_sink: bool,
     have_src: bool,
 }
 
+impl ProxyContextInner {
+    fn set_leaky(&mut self, leaky: DataQueueLeaky) {
+        self.leaky = leaky;
+        if let Some(ref dataqueue) = self.dataqueue {
+            dataqueue.set_leaky(leaky);
+        }
+    }
+}
+
 impl Drop for ProxyContextInner {
     fn drop(&mut self) {
         let mut proxy_ctxs = PROXY_CONTEXTS.lock().unwrap();
@@ -176,6 +224,7 @@ impl ProxyContext {
                 dataqueue: None,
                 last_res: Err(gst::FlowError::Flushing),
                 pending_queue: None,
+                leaky: DataQueueLeaky::No,
                 have_sink: as_sink,
                 have_src: !as_sink,
             }));
@@ -200,6 +249,7 @@ impl Drop for ProxyContext {
             assert!(shared_ctx.have_sink);
             shared_ctx.have_sink = false;
             let _ = shared_ctx.pending_queue.take();
+            shared_ctx.set_leaky(DataQueueLeaky::No);
         } else {
             assert!(shared_ctx.have_src);
             shared_ctx.have_src = false;
@@ -266,27 +316,37 @@ impl PadSinkHandler for ProxySinkPadHandler {
 
         gst::debug!(SINK_CAT, obj: pad.gst_pad(), "Handling non-serialized {:?}", event);
 
+        // No `ProxyContext` in cross-process mode
         let src_pad = {
             let proxy_ctx = proxysink.proxy_ctx.lock().unwrap();
 
-            PROXY_SRC_PADS
-                .lock()
-                .unwrap()
-                .get(&proxy_ctx.as_ref().unwrap().name)
-                .and_then(|src_pad| src_pad.upgrade())
-                .map(|src_pad| src_pad.gst_pad().clone())
+            proxy_ctx.as_ref().map(|proxy_ctx| {
+                PROXY_SRC_PADS
+                    .lock()
+                    .unwrap()
+                    .get(&proxy_ctx.name)
+                    .and_then(|src_pad| src_pad.upgrade())
+                    .map(|src_pad| src_pad.gst_pad().clone())
+            })
         };
 
         if let EventView::FlushStart(..) = event.view() {
             proxysink.stop(element.downcast_ref::<super::ProxySink>().unwrap());
         }
 
-        if let Some(src_pad) = src_pad {
-            gst::log!(SINK_CAT, obj: pad.gst_pad(), "Forwarding non-serialized {:?}", event);
-            src_pad.push_event(event)
-        } else {
-            gst::error!(SINK_CAT, obj: pad.gst_pad(), "No src pad to forward non-serialized {:?} to", event);
-            true
+        match src_pad {
+            Some(Some(src_pad)) => {
+                gst::log!(SINK_CAT, obj: pad.gst_pad(), "Forwarding non-serialized {:?}", event);
+                src_pad.push_event(event)
+            }
+            Some(None) => {
+                gst::error!(SINK_CAT, obj: pad.gst_pad(), "No src pad to forward non-serialized {:?} to", event);
+                true
+            }
+            None => {
+                gst::debug!(SINK_CAT, obj: pad.gst_pad(), "Not forwarding non-serialized {:?} to other process", event);
+                true
+            }
         }
     }
 
@@ -330,9 +390,19 @@ impl PadSinkHandler for ProxySinkPadHandler {
 pub struct ProxySink {
     sink_pad: PadSink,
     proxy_ctx: StdMutex<Option<ProxyContext>>,
+    #[cfg(target_os = "linux")]
+    ipc: StdMutex<Option<IpcSink>>,
     settings: StdMutex<SettingsSink>,
 }
 
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+struct IpcSink {
+    sender: ipc::SenderThread,
+    shutdown_handle: ipc::ShutdownHandle,
+    flushing: bool,
+}
+
 static SINK_CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
     gst::DebugCategory::new(
         "ts-proxysink",
@@ -400,6 +470,13 @@ impl ProxySink {
         element: &super::ProxySink,
         item: DataQueueItem,
     ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        #[cfg(target_os = "linux")]
+        {
+            if self.ipc.lock().unwrap().is_some() {
+                return self.send_ipc_item(element, item).await;
+            }
+        }
+
         let wait_fut = {
             let proxy_ctx = self.proxy_ctx.lock().unwrap();
             let mut shared_ctx = proxy_ctx.as_ref().unwrap().lock_shared();
@@ -508,10 +585,90 @@ impl ProxySink {
         shared_ctx.last_res
     }
 
+    /// Sends `item` to the `ts-proxysrc` of another process.
+    ///
+    /// Items are dropped while the `ts-proxysrc` is not reachable.
+    #[cfg(target_os = "linux")]
+    async fn send_ipc_item(
+        &self,
+        element: &super::ProxySink,
+        item: DataQueueItem,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        let send_fut = {
+            let ipc = self.ipc.lock().unwrap();
+            let ipc = ipc.as_ref().unwrap();
+            if ipc.flushing {
+                gst::log!(SINK_CAT, obj: element, "Flushing, dropping {:?}", item);
+                return Err(gst::FlowError::Flushing);
+            }
+
+            ipc.sender
+                .send(element.upcast_ref(), self.sink_pad.gst_pad(), item)
+        };
+
+        send_fut.await;
+
+        if self.ipc.lock().unwrap().as_ref().unwrap().flushing {
+            Err(gst::FlowError::Flushing)
+        } else {
+            Ok(gst::FlowSuccess::Ok)
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn prepare_ipc(
+        &self,
+        element: &super::ProxySink,
+        socket_path: PathBuf,
+        ring_size: u32,
+    ) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(
+            SINK_CAT,
+            obj: element,
+            "Sending to {} in cross-process mode",
+            socket_path.display()
+        );
+
+        let sender = ipc::SenderThread::spawn(socket_path, ring_size as usize).map_err(|err| {
+            gst::error_msg!(
+                gst::ResourceError::OpenWrite,
+                ["Failed to spawn sender thread: {}", err]
+            )
+        })?;
+        *self.ipc.lock().unwrap() = Some(IpcSink {
+            shutdown_handle: sender.shutdown_handle(),
+            sender,
+            flushing: true,
+        });
+
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn prepare_ipc(
+        &self,
+        _element: &super::ProxySink,
+        _socket_path: PathBuf,
+        _ring_size: u32,
+    ) -> Result<(), gst::ErrorMessage> {
+        Err(gst::error_msg!(
+            gst::ResourceError::Settings,
+            ["Cross-process mode is only supported on Linux"]
+        ))
+    }
+
     fn prepare(&self, element: &super::ProxySink) -> Result<(), gst::ErrorMessage> {
         gst::debug!(SINK_CAT, obj: element, "Preparing");
 
-        let proxy_context = self.settings.lock().unwrap().proxy_context.to_string();
+        let settings = self.settings.lock().unwrap().clone();
+        if let Some(socket_path) = settings.socket_path {
+            self.prepare_ipc(element, socket_path.into(), settings.ring_size)?;
+
+            gst::debug!(SINK_CAT, obj: element, "Prepared");
+            return Ok(());
+        }
+
+        let proxy_context = settings.proxy_context;
 
         let proxy_ctx = ProxyContext::get(&proxy_context, true).ok_or_else(|| {
             gst::error_msg!(
@@ -520,6 +677,8 @@ impl ProxySink {
             )
         })?;
 
+        proxy_ctx.lock_shared().set_leaky(settings.leaky);
+
         {
             let mut proxy_sink_pads = PROXY_SINK_PADS.lock().unwrap();
             assert!(!proxy_sink_pads.contains_key(&proxy_context));
@@ -535,11 +694,25 @@ impl ProxySink {
 
     fn unprepare(&self, element: &super::ProxySink) {
         gst::debug!(SINK_CAT, obj: element, "Unpreparing");
+        #[cfg(target_os = "linux")]
+        {
+            *self.ipc.lock().unwrap() = None;
+        }
         *self.proxy_ctx.lock().unwrap() = None;
         gst::debug!(SINK_CAT, obj: element, "Unprepared");
     }
 
     fn start(&self, element: &super::ProxySink) {
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(ref mut ipc) = *self.ipc.lock().unwrap() {
+                gst::debug!(SINK_CAT, obj: element, "Starting");
+                ipc.flushing = false;
+                gst::debug!(SINK_CAT, obj: element, "Started");
+                return;
+            }
+        }
+
         let proxy_ctx = self.proxy_ctx.lock().unwrap();
         let mut shared_ctx = proxy_ctx.as_ref().unwrap().lock_shared();
 
@@ -557,6 +730,18 @@ impl ProxySink {
     }
 
     fn stop(&self, element: &super::ProxySink) {
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(ref mut ipc) = *self.ipc.lock().unwrap() {
+                gst::debug!(SINK_CAT, obj: element, "Stopping");
+                // Unblock any pending send, the connection is reestablished on next item
+                ipc.flushing = true;
+                ipc.shutdown_handle.shutdown();
+                gst::debug!(SINK_CAT, obj: element, "Stopped");
+                return;
+            }
+        }
+
         let proxy_ctx = self.proxy_ctx.lock().unwrap();
         let mut shared_ctx = proxy_ctx.as_ref().unwrap().lock_shared();
 
@@ -582,6 +767,8 @@ impl ObjectSubclass for ProxySink {
                 ProxySinkPadHandler,
             ),
             proxy_ctx: StdMutex::new(None),
+            #[cfg(target_os = "linux")]
+            ipc: StdMutex::new(None),
             settings: StdMutex::new(SettingsSink::default()),
         }
     }
@@ -590,13 +777,48 @@ impl ObjectSubclass for ProxySink {
 impl ObjectImpl for ProxySink {
     fn properties() -> &'static [glib::ParamSpec] {
         static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
-            vec![glib::ParamSpecString::new(
-                "proxy-context",
-                "Proxy Context",
-                "Context name of the proxy to share with",
-                Some(DEFAULT_PROXY_CONTEXT),
-                glib::ParamFlags::READWRITE,
-            )]
+            vec![
+                glib::ParamSpecString::new(
+                    "proxy-context",
+                    "Proxy Context",
+                    "Context name of the proxy to share with",
+                    Some(DEFAULT_PROXY_CONTEXT),
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecString::new(
+                    "socket-path",
+                    "Socket Path",
+                    "Path of the socket of a ts-proxysrc in another process (overrides proxy-context)",
+                    DEFAULT_SOCKET_PATH,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecEnum::new(
+                    "leaky",
+                    "Leaky",
+                    "Where the queue of the ts-proxysrc leaks buffers when full, if at all",
+                    DataQueueLeaky::static_type(),
+                    DEFAULT_LEAKY as i32,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt64::new(
+                    "dropped",
+                    "Dropped",
+                    "Number of buffers leaked by the queue of the ts-proxysrc",
+                    0,
+                    u64::MAX,
+                    0,
+                    glib::ParamFlags::READABLE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "ring-size",
+                    "Ring Size",
+                    "Size in bytes of the shared memory ring used in cross-process mode",
+                    4096,
+                    u32::MAX,
+                    DEFAULT_RING_SIZE,
+                    glib::ParamFlags::READWRITE,
+                ),
+            ]
         });
 
         PROPERTIES.as_ref()
@@ -617,14 +839,38 @@ impl ObjectImpl for ProxySink {
                     .expect("type checked upstream")
                     .unwrap_or_else(|| DEFAULT_PROXY_CONTEXT.into());
             }
+            "socket-path" => {
+                settings.socket_path = value.get().expect("type checked upstream");
+            }
+            "ring-size" => {
+                settings.ring_size = value.get().expect("type checked upstream");
+            }
+            "leaky" => {
+                settings.leaky = value.get().expect("type checked upstream");
+            }
             _ => unimplemented!(),
         }
     }
 
     fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        if pspec.name() == "dropped" {
+            let proxy_ctx = self.proxy_ctx.lock().unwrap();
+            return proxy_ctx
+                .as_ref()
+                .and_then(|proxy_ctx| {
+                    let shared_ctx = proxy_ctx.lock_shared();
+                    shared_ctx.dataqueue.as_ref().map(DataQueue::dropped)
+                })
+                .unwrap_or(0)
+                .to_value();
+        }
+
         let settings = self.settings.lock().unwrap();
         match pspec.name() {
             "proxy-context" => settings.proxy_context.to_value(),
+            "socket-path" => settings.socket_path.to_value(),
+            "ring-size" => settings.ring_size.to_value(),
+            "leaky" => settings.leaky.to_value(),
             _ => unimplemented!(),
         }
     }
@@ -754,6 +1000,7 @@ impl PadSrcHandler for ProxySrcPadHandler {
 
         gst::log!(SRC_CAT, obj: pad.gst_pad(), "Handling {:?}", event);
 
+        let is_ipc = proxysrc.settings.lock().unwrap().socket_path.is_some();
         let sink_pad = {
             let proxy_ctx = proxysrc.proxy_ctx.lock().unwrap();
 
@@ -796,6 +1043,9 @@ impl PadSrcHandler for ProxySrcPadHandler {
         if let Some(sink_pad) = sink_pad {
             gst::log!(SRC_CAT, obj: pad.gst_pad(), "Forwarding {:?}", event);
             sink_pad.push_event(event)
+        } else if is_ipc {
+            gst::debug!(SRC_CAT, obj: pad.gst_pad(), "Not forwarding {:?} to other process", event);
+            false
         } else {
             gst::error!(SRC_CAT, obj: pad.gst_pad(), "No sink pad to forward {:?} to", event);
             false
@@ -990,10 +1240,21 @@ pub struct ProxySrc {
     src_pad: PadSrc,
     task: Task,
     proxy_ctx: StdMutex<Option<ProxyContext>>,
+    #[cfg(target_os = "linux")]
+    ipc: StdMutex<Option<IpcSrc>>,
     dataqueue: StdMutex<Option<DataQueue>>,
     settings: StdMutex<SettingsSrc>,
 }
 
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+struct IpcSrc {
+    socket_path: PathBuf,
+    abort_handle: AbortHandle,
+    // Holds the sink side of the private `ProxyContext`
+    _proxy_ctx: ProxyContext,
+}
+
 static SRC_CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
     gst::DebugCategory::new(
         "ts-proxysrc",
@@ -1003,12 +1264,149 @@ static SRC_CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
 });
 
 impl ProxySrc {
+    /// Enqueues an item received from a `ts-proxysink` in another process.
+    ///
+    /// Waits for room in the `DataQueue`, which in turn blocks the remote
+    /// `ts-proxysink` since the item is only acknowledged afterwards.
+    #[cfg(target_os = "linux")]
+    async fn enqueue_ipc_item(&self, element: &super::ProxySrc, mut item: DataQueueItem) {
+        loop {
+            let more_queue_space_receiver = {
+                let proxy_ctx = self.proxy_ctx.lock().unwrap();
+                let mut shared_ctx = proxy_ctx.as_ref().unwrap().lock_shared();
+
+                match shared_ctx.last_res {
+                    Ok(_) | Err(gst::FlowError::Flushing) => (),
+                    Err(err) => {
+                        gst::log!(SRC_CAT, obj: element, "Dropping {:?}: {:?}", item, err);
+                        return;
+                    }
+                }
+
+                let res = match shared_ctx.dataqueue {
+                    Some(ref dataqueue) => dataqueue.push(item),
+                    None => Err(item),
+                };
+
+                match res {
+                    Ok(()) => return,
+                    Err(failed_item) => {
+                        item = failed_item;
+
+                        let (sender, receiver) = oneshot::channel();
+                        shared_ctx.pending_queue = Some(PendingQueue {
+                            more_queue_space_sender: Some(sender),
+                            ..Default::default()
+                        });
+
+                        receiver
+                    }
+                }
+            };
+
+            gst::log!(SRC_CAT, obj: element, "Waiting for more queue space");
+            let _ = more_queue_space_receiver.await;
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    async fn ipc_receive_loop(element: super::ProxySrc, listener: Async<UnixListener>) {
+        loop {
+            let mut receiver = match ipc::Receiver::accept(&listener).await {
+                Ok(receiver) => receiver,
+                Err(err) => {
+                    gst::warning!(SRC_CAT, obj: &element, "Failed to accept peer: {}", err);
+                    runtime::delay_for(IPC_ACCEPT_RETRY_INTERVAL).await;
+                    continue;
+                }
+            };
+
+            gst::info!(SRC_CAT, obj: &element, "Peer connected");
+
+            loop {
+                match receiver.next().await {
+                    Ok(Some(item)) => element.imp().enqueue_ipc_item(&element, item).await,
+                    Ok(None) => {
+                        gst::info!(SRC_CAT, obj: &element, "Peer disconnected");
+                        break;
+                    }
+                    Err(err) => {
+                        gst::warning!(SRC_CAT, obj: &element, "Connection to peer lost: {}", err);
+                        break;
+                    }
+                }
+
+                if let Err(err) = receiver.ack().await {
+                    gst::warning!(SRC_CAT, obj: &element, "Connection to peer lost: {}", err);
+                    break;
+                }
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn prepare_ipc(
+        &self,
+        element: &super::ProxySrc,
+        proxy_context: &str,
+        socket_path: PathBuf,
+        ts_ctx: &Context,
+    ) -> Result<(), gst::ErrorMessage> {
+        let proxy_ctx = ProxyContext::get(proxy_context, true).ok_or_else(|| {
+            gst::error_msg!(
+                gst::ResourceError::OpenRead,
+                ["Failed to create or get ProxyContext"]
+            )
+        })?;
+
+        let listener = ts_ctx.enter(|| ipc::bind(&socket_path)).map_err(|err| {
+            gst::error_msg!(
+                gst::ResourceError::OpenRead,
+                ["Failed to bind {}: {}", socket_path.display(), err]
+            )
+        })?;
+
+        gst::debug!(
+            SRC_CAT,
+            obj: element,
+            "Listening on {} in cross-process mode",
+            socket_path.display()
+        );
+
+        let (receive_loop, abort_handle) =
+            abortable(Self::ipc_receive_loop(element.clone(), listener));
+        ts_ctx.spawn(receive_loop);
+
+        *self.ipc.lock().unwrap() = Some(IpcSrc {
+            socket_path,
+            abort_handle,
+            _proxy_ctx: proxy_ctx,
+        });
+
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn prepare_ipc(
+        &self,
+        _element: &super::ProxySrc,
+        _proxy_context: &str,
+        _socket_path: PathBuf,
+        _ts_ctx: &Context,
+    ) -> Result<(), gst::ErrorMessage> {
+        Err(gst::error_msg!(
+            gst::ResourceError::Settings,
+            ["Cross-process mode is only supported on Linux"]
+        ))
+    }
+
     fn prepare(&self, element: &super::ProxySrc) -> Result<(), gst::ErrorMessage> {
         gst::debug!(SRC_CAT, obj: element, "Preparing");
 
         let settings = self.settings.lock().unwrap().clone();
+        let proxy_context = settings.proxy_context_name();
 
-        let proxy_ctx = ProxyContext::get(&settings.proxy_context, false).ok_or_else(|| {
+        let proxy_ctx = ProxyContext::get(&proxy_context, false).ok_or_else(|| {
             gst::error_msg!(
                 gst::ResourceError::OpenRead,
                 ["Failed to create get shared_state"]
@@ -1044,15 +1442,20 @@ impl ProxySrc {
 
         {
             let mut shared_ctx = proxy_ctx.lock_shared();
+            dataqueue.set_leaky(shared_ctx.leaky);
             shared_ctx.dataqueue = Some(dataqueue.clone());
 
             let mut proxy_src_pads = PROXY_SRC_PADS.lock().unwrap();
-            assert!(!proxy_src_pads.contains_key(&settings.proxy_context));
-            proxy_src_pads.insert(settings.proxy_context, self.src_pad.downgrade());
+            assert!(!proxy_src_pads.contains_key(&proxy_context));
+            proxy_src_pads.insert(proxy_context.clone(), self.src_pad.downgrade());
         }
 
         *self.proxy_ctx.lock().unwrap() = Some(proxy_ctx);
 
+        if let Some(socket_path) = settings.socket_path {
+            self.prepare_ipc(element, &proxy_context, socket_path.into(), &ts_ctx)?;
+        }
+
         *self.dataqueue.lock().unwrap() = Some(dataqueue.clone());
 
         self.task
@@ -1072,10 +1475,18 @@ impl ProxySrc {
     fn unprepare(&self, element: &super::ProxySrc) {
         gst::debug!(SRC_CAT, obj: element, "Unpreparing");
 
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(ipc) = self.ipc.lock().unwrap().take() {
+                ipc.abort_handle.abort();
+                let _ = std::fs::remove_file(&ipc.socket_path);
+            }
+        }
+
         {
             let settings = self.settings.lock().unwrap();
             let mut proxy_src_pads = PROXY_SRC_PADS.lock().unwrap();
-            proxy_src_pads.remove(&settings.proxy_context);
+            proxy_src_pads.remove(&settings.proxy_context_name());
         }
 
         self.task.unprepare().unwrap();
@@ -1122,6 +1533,8 @@ impl ObjectSubclass for ProxySrc {
             ),
             task: Task::default(),
             proxy_ctx: StdMutex::new(None),
+            #[cfg(target_os = "linux")]
+            ipc: StdMutex::new(None),
             dataqueue: StdMutex::new(None),
             settings: StdMutex::new(SettingsSrc::default()),
         }
@@ -1155,6 +1568,13 @@ impl ObjectImpl for ProxySrc {
                     Some(DEFAULT_PROXY_CONTEXT),
                     glib::ParamFlags::READWRITE,
                 ),
+                glib::ParamSpecString::new(
+                    "socket-path",
+                    "Socket Path",
+                    "Path of the socket to receive from a ts-proxysink in another process (overrides proxy-context)",
+                    DEFAULT_SOCKET_PATH,
+                    glib::ParamFlags::READWRITE,
+                ),
                 glib::ParamSpecUInt::new(
                     "max-size-buffers",
                     "Max Size Buffers",
@@ -1224,6 +1644,9 @@ impl ObjectImpl for ProxySrc {
                     .expect("type checked upstream")
                     .unwrap_or_else(|| DEFAULT_PROXY_CONTEXT.into());
             }
+            "socket-path" => {
+                settings.socket_path = value.get().expect("type checked upstream");
+            }
             _ => unimplemented!(),
         }
     }
@@ -1237,6 +1660,7 @@ impl ObjectImpl for ProxySrc {
             "context" => settings.context.to_value(),
             "context-wait" => (settings.context_wait.as_millis() as u32).to_value(),
             "proxy-context" => settings.proxy_context.to_value(),
+            "socket-path" => settings.socket_path.to_value(),
             _ => unimplemented!(),
         }
     }
@@ -0,0 +1,1037 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+//! Cross-process transport for `ts-proxysink` / `ts-proxysrc`.
+//!
+//! The `ts-proxysrc` listens on a Unix socket. When a `ts-proxysink` connects,
+//! it creates a shared memory ring with `memfd_create` and passes the file
+//! descriptor along with the `Hello` frame. Buffer payloads are written to the
+//! ring and only their description goes through the socket. Payloads larger
+//! than the ring are sent inline, up to 64MiB. Serialized events are sent
+//! through the socket.
+//!
+//! The ring is sealed against resizing, so that the `ts-proxysrc` can check
+//! that it is at least as large as announced before mapping it.
+//!
+//! Each frame sent by the sink is acknowledged once the src enqueued it, which
+//! releases its room in the ring. The sink blocks when the ring is full or too
+//! many frames are in flight, so the `max-size-*` limits of the `ts-proxysrc`
+//! apply backpressure across processes. This can last indefinitely, so each
+//! `ts-proxysink` sends from its own thread, see [`SenderThread`].
+
+use futures::channel::oneshot;
+use futures::prelude::*;
+
+use gst::prelude::*;
+
+use once_cell::sync::Lazy;
+
+use std::collections::VecDeque;
+use std::convert::TryInto;
+use std::ffi::CString;
+use std::io::{self, Read};
+use std::net::Shutdown;
+use std::ops::ControlFlow;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use std::{mem, ptr, slice};
+
+use crate::dataqueue::DataQueueItem;
+use crate::runtime::Async;
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "ts-proxy-ipc",
+        gst::DebugColorFlags::empty(),
+        Some("Thread-sharing cross-process proxy"),
+    )
+});
+
+/// Minimum interval between two connection attempts.
+const RECONNECT_INTERVAL: Duration = Duration::from_millis(100);
+/// Maximum number of frames awaiting acknowledgement.
+const MAX_IN_FLIGHT: usize = 64;
+/// Maximum payload length of a frame.
+const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+const MAGIC: u32 = 0x5854_5350; // "PSTX"
+const VERSION: u32 = 1;
+
+const FRAME_HEADER_LEN: usize = 5;
+const HELLO_LEN: usize = 16;
+/// Length of the description preceding an inline payload.
+const INLINE_HEADER_LEN: usize = 7 * 8 + 4;
+
+const KIND_HELLO: u8 = 1;
+const KIND_BUFFER: u8 = 2;
+const KIND_EVENT: u8 = 3;
+const KIND_ACK: u8 = 4;
+
+const EVENT_STREAM_START: u8 = 1;
+const EVENT_CAPS: u8 = 2;
+const EVENT_SEGMENT: u8 = 3;
+const EVENT_EOS: u8 = 4;
+const EVENT_GAP: u8 = 5;
+const EVENT_CUSTOM: u8 = 6;
+const EVENT_CUSTOM_STICKY: u8 = 7;
+
+const SEGMENT_TIME: u8 = 0;
+const SEGMENT_BYTES: u8 = 1;
+
+const INLINE: u64 = u64::MAX;
+const NONE: u64 = u64::MAX;
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+/// A shared memory mapping of the ring.
+#[derive(Debug)]
+struct Ring {
+    fd: RawFd,
+    ptr: *mut u8,
+    len: usize,
+}
+
+// Safety: the mapping is owned by the `Ring` and only accessed through `&mut self`
+// on the writer side and `&self` on the reader side.
+unsafe impl Send for Ring {}
+unsafe impl Sync for Ring {}
+
+impl Ring {
+    fn create(len: usize) -> io::Result<Self> {
+        let name = CString::new("ts-proxysink").unwrap();
+
+        unsafe {
+            let fd = libc::memfd_create(name.as_ptr(), libc::MFD_CLOEXEC | libc::MFD_ALLOW_SEALING);
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            if libc::ftruncate(fd, len as libc::off_t) < 0
+                || libc::fcntl(
+                    fd,
+                    libc::F_ADD_SEALS,
+                    libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_SEAL,
+                ) < 0
+            {
+                let err = io::Error::last_os_error();
+                libc::close(fd);
+                return Err(err);
+            }
+
+            Self::map(fd, len, libc::PROT_READ | libc::PROT_WRITE)
+        }
+    }
+
+    /// Maps the ring received from the peer, checking that it can't be
+    /// resized and is at least `len` bytes long.
+    ///
+    /// # Safety
+    ///
+    /// `fd` must be a valid file descriptor, ownership is transferred to the `Ring`.
+    unsafe fn map_sealed(fd: RawFd, len: u64) -> io::Result<Self> {
+        let required = libc::F_SEAL_SHRINK | libc::F_SEAL_GROW;
+        let seals = libc::fcntl(fd, libc::F_GET_SEALS);
+        if seals < 0 || seals & required != required {
+            libc::close(fd);
+            return Err(invalid_data("Ring is not sealed"));
+        }
+
+        let mut stat: libc::stat = mem::zeroed();
+        if libc::fstat(fd, &mut stat) < 0 {
+            let err = io::Error::last_os_error();
+            libc::close(fd);
+            return Err(err);
+        }
+
+        if len == 0 || len > stat.st_size as u64 {
+            libc::close(fd);
+            return Err(invalid_data("Invalid ring size"));
+        }
+
+        Self::map(fd, len as usize, libc::PROT_READ)
+    }
+
+    /// # Safety
+    ///
+    /// `fd` must be a valid file descriptor, ownership is transferred to the `Ring`.
+    unsafe fn map(fd: RawFd, len: usize, prot: libc::c_int) -> io::Result<Self> {
+        let ptr = libc::mmap(ptr::null_mut(), len, prot, libc::MAP_SHARED, fd, 0);
+        if ptr == libc::MAP_FAILED {
+            let err = io::Error::last_os_error();
+            libc::close(fd);
+            return Err(err);
+        }
+
+        Ok(Ring {
+            fd,
+            ptr: ptr as *mut u8,
+            len,
+        })
+    }
+
+    fn write(&mut self, offset: usize, data: &[u8]) {
+        assert!(offset + data.len() <= self.len);
+        unsafe {
+            ptr::copy_nonoverlapping(data.as_ptr(), self.ptr.add(offset), data.len());
+        }
+    }
+
+    fn read(&self, offset: usize, len: usize) -> io::Result<&[u8]> {
+        if offset.checked_add(len).map_or(true, |end| end > self.len) {
+            return Err(invalid_data("Out of ring bounds"));
+        }
+
+        Ok(unsafe { slice::from_raw_parts(self.ptr.add(offset), len) })
+    }
+}
+
+impl Drop for Ring {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr as *mut libc::c_void, self.len);
+            libc::close(self.fd);
+        }
+    }
+}
+
+fn send_with_fd(stream: &UnixStream, data: &[u8], fd: RawFd) -> io::Result<()> {
+    unsafe {
+        let mut iov = libc::iovec {
+            iov_base: data.as_ptr() as *mut libc::c_void,
+            iov_len: data.len(),
+        };
+
+        let mut cmsg_buf = vec![0u8; libc::CMSG_SPACE(mem::size_of::<RawFd>() as u32) as usize];
+
+        let mut msg: libc::msghdr = mem::zeroed();
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = cmsg_buf.as_mut_ptr() as *mut libc::c_void;
+        msg.msg_controllen = cmsg_buf.len() as _;
+
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(mem::size_of::<RawFd>() as u32) as _;
+        ptr::write_unaligned(libc::CMSG_DATA(cmsg) as *mut RawFd, fd);
+
+        let res = libc::sendmsg(stream.as_raw_fd(), &msg, libc::MSG_NOSIGNAL);
+        if res < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if res as usize != data.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::WriteZero,
+                "Short write sending Hello",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn recv_with_fd(stream: &UnixStream, data: &mut [u8]) -> io::Result<(usize, Option<RawFd>)> {
+    unsafe {
+        let mut iov = libc::iovec {
+            iov_base: data.as_mut_ptr() as *mut libc::c_void,
+            iov_len: data.len(),
+        };
+
+        let mut cmsg_buf = vec![0u8; libc::CMSG_SPACE(mem::size_of::<RawFd>() as u32) as usize];
+
+        let mut msg: libc::msghdr = mem::zeroed();
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = cmsg_buf.as_mut_ptr() as *mut libc::c_void;
+        msg.msg_controllen = cmsg_buf.len() as _;
+
+        let res = libc::recvmsg(stream.as_raw_fd(), &mut msg, libc::MSG_CMSG_CLOEXEC);
+        if res < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut fd = None;
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+        while !cmsg.is_null() {
+            if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                fd = Some(ptr::read_unaligned(libc::CMSG_DATA(cmsg) as *const RawFd));
+            }
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+
+        Ok((res as usize, fd))
+    }
+}
+
+/// Writes all of `data`, without raising `SIGPIPE` if the peer is gone.
+fn send_all(stream: &UnixStream, mut data: &[u8]) -> io::Result<()> {
+    while !data.is_empty() {
+        let res = unsafe {
+            libc::send(
+                stream.as_raw_fd(),
+                data.as_ptr() as *const libc::c_void,
+                data.len(),
+                libc::MSG_NOSIGNAL,
+            )
+        };
+        if res < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(err);
+        }
+
+        data = &data[res as usize..];
+    }
+
+    Ok(())
+}
+
+/// Payload encoder for a frame.
+#[derive(Debug)]
+struct Encoder(Vec<u8>);
+
+impl Encoder {
+    fn new(kind: u8) -> Self {
+        let mut data = Vec::with_capacity(128);
+        data.extend_from_slice(&[0; 4]);
+        data.push(kind);
+
+        Encoder(data)
+    }
+
+    fn u8(&mut self, val: u8) -> &mut Self {
+        self.0.push(val);
+        self
+    }
+
+    fn u32(&mut self, val: u32) -> &mut Self {
+        self.0.extend_from_slice(&val.to_le_bytes());
+        self
+    }
+
+    fn u64(&mut self, val: u64) -> &mut Self {
+        self.0.extend_from_slice(&val.to_le_bytes());
+        self
+    }
+
+    fn f64(&mut self, val: f64) -> &mut Self {
+        self.0.extend_from_slice(&val.to_le_bytes());
+        self
+    }
+
+    fn bytes(&mut self, val: &[u8]) -> &mut Self {
+        self.0.extend_from_slice(val);
+        self
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let len = (self.0.len() - FRAME_HEADER_LEN) as u32;
+        self.0[..4].copy_from_slice(&len.to_le_bytes());
+        self.0
+    }
+}
+
+/// Payload decoder for a frame.
+#[derive(Debug)]
+struct Decoder<'a>(&'a [u8]);
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(invalid_data("Truncated frame"));
+        }
+
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn f64(&mut self) -> io::Result<f64> {
+        Ok(f64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn clock_time(&mut self) -> io::Result<Option<gst::ClockTime>> {
+        let val = self.u64()?;
+        Ok(if val == NONE {
+            None
+        } else {
+            Some(gst::ClockTime::from_nseconds(val))
+        })
+    }
+
+    fn bytes(&mut self) -> io::Result<Option<gst::format::Bytes>> {
+        let val = self.u64()?;
+        Ok(if val == NONE {
+            None
+        } else {
+            Some(gst::format::Bytes(val))
+        })
+    }
+
+    fn str(&mut self) -> io::Result<&'a str> {
+        let rest = self.take(self.0.len())?;
+        std::str::from_utf8(rest).map_err(|_| invalid_data("Invalid string"))
+    }
+}
+
+fn clock_time_to_u64(val: Option<gst::ClockTime>) -> u64 {
+    val.map_or(NONE, gst::ClockTime::nseconds)
+}
+
+fn encode_buffer(buffer: &gst::BufferRef, ring_offset: u64) -> Encoder {
+    let mut enc = Encoder::new(KIND_BUFFER);
+    enc.u64(ring_offset)
+        .u64(buffer.size() as u64)
+        .u64(clock_time_to_u64(buffer.pts()))
+        .u64(clock_time_to_u64(buffer.dts()))
+        .u64(clock_time_to_u64(buffer.duration()))
+        .u64(buffer.offset())
+        .u64(buffer.offset_end())
+        .u32(buffer.flags().bits());
+
+    enc
+}
+
+/// Encodes the serialized event, returns `None` if it can't be transferred.
+fn encode_event(event: &gst::Event) -> Option<Vec<u8>> {
+    use gst::EventView;
+
+    let mut enc = Encoder::new(KIND_EVENT);
+
+    match event.view() {
+        EventView::StreamStart(e) => {
+            enc.u8(EVENT_STREAM_START)
+                .u32(e.stream_flags().bits())
+                .bytes(e.stream_id().as_bytes());
+        }
+        EventView::Caps(e) => {
+            enc.u8(EVENT_CAPS).bytes(e.caps().to_string().as_bytes());
+        }
+        EventView::Segment(e) => {
+            let segment = e.segment();
+            enc.u8(EVENT_SEGMENT);
+
+            if let Some(segment) = segment.downcast_ref::<gst::format::Time>() {
+                enc.u8(SEGMENT_TIME)
+                    .f64(segment.rate())
+                    .f64(segment.applied_rate())
+                    .u32(segment.flags().bits())
+                    .u64(clock_time_to_u64(segment.start()))
+                    .u64(clock_time_to_u64(segment.stop()))
+                    .u64(clock_time_to_u64(segment.time()))
+                    .u64(clock_time_to_u64(segment.position()))
+                    .u64(clock_time_to_u64(segment.base()))
+                    .u64(clock_time_to_u64(segment.offset()))
+                    .u64(clock_time_to_u64(segment.duration()));
+            } else if let Some(segment) = segment.downcast_ref::<gst::format::Bytes>() {
+                let to_u64 = |val: Option<gst::format::Bytes>| val.map_or(NONE, |val| val.0);
+                enc.u8(SEGMENT_BYTES)
+                    .f64(segment.rate())
+                    .f64(segment.applied_rate())
+                    .u32(segment.flags().bits())
+                    .u64(to_u64(segment.start()))
+                    .u64(to_u64(segment.stop()))
+                    .u64(to_u64(segment.time()))
+                    .u64(to_u64(segment.position()))
+                    .u64(to_u64(segment.base()))
+                    .u64(to_u64(segment.offset()))
+                    .u64(to_u64(segment.duration()));
+            } else {
+                return None;
+            }
+        }
+        EventView::Eos(_) => {
+            enc.u8(EVENT_EOS);
+        }
+        EventView::Gap(e) => {
+            let (timestamp, duration) = e.get();
+            enc.u8(EVENT_GAP)
+                .u64(timestamp.nseconds())
+                .u64(clock_time_to_u64(duration));
+        }
+        EventView::CustomDownstream(_) | EventView::CustomDownstreamSticky(_) => {
+            let kind = if event.type_() == gst::EventType::CustomDownstreamSticky {
+                EVENT_CUSTOM_STICKY
+            } else {
+                EVENT_CUSTOM
+            };
+            enc.u8(kind)
+                .bytes(event.structure()?.to_string().as_bytes());
+        }
+        _ => return None,
+    }
+
+    Some(enc.finish())
+}
+
+fn decode_event(mut dec: Decoder, group_id: gst::GroupId) -> io::Result<gst::Event> {
+    let event = match dec.u8()? {
+        EVENT_STREAM_START => {
+            let flags = gst::StreamFlags::from_bits_truncate(dec.u32()?);
+            gst::event::StreamStart::builder(dec.str()?)
+                .flags(flags)
+                .group_id(group_id)
+                .build()
+        }
+        EVENT_CAPS => {
+            let caps = gst::Caps::from_str(dec.str()?).map_err(|_| invalid_data("Invalid caps"))?;
+            gst::event::Caps::new(&caps)
+        }
+        EVENT_SEGMENT => match dec.u8()? {
+            SEGMENT_TIME => {
+                let mut segment = gst::FormattedSegment::<gst::format::Time>::new();
+                segment.set_rate(dec.f64()?);
+                segment.set_applied_rate(dec.f64()?);
+                segment.set_flags(gst::SegmentFlags::from_bits_truncate(dec.u32()?));
+                segment.set_start(dec.clock_time()?);
+                segment.set_stop(dec.clock_time()?);
+                segment.set_time(dec.clock_time()?);
+                segment.set_position(dec.clock_time()?);
+                segment.set_base(dec.clock_time()?);
+                segment.set_offset(dec.clock_time()?);
+                segment.set_duration(dec.clock_time()?);
+                gst::event::Segment::new(&segment)
+            }
+            SEGMENT_BYTES => {
+                let mut segment = gst::FormattedSegment::<gst::format::Bytes>::new();
+                segment.set_rate(dec.f64()?);
+                segment.set_applied_rate(dec.f64()?);
+                segment.set_flags(gst::SegmentFlags::from_bits_truncate(dec.u32()?));
+                segment.set_start(dec.bytes()?);
+                segment.set_stop(dec.bytes()?);
+                segment.set_time(dec.bytes()?);
+                segment.set_position(dec.bytes()?);
+                segment.set_base(dec.bytes()?);
+                segment.set_offset(dec.bytes()?);
+                segment.set_duration(dec.bytes()?);
+                gst::event::Segment::new(&segment)
+            }
+            _ => return Err(invalid_data("Unknown segment format")),
+        },
+        EVENT_EOS => gst::event::Eos::new(),
+        EVENT_GAP => {
+            let timestamp = gst::ClockTime::from_nseconds(dec.u64()?);
+            gst::event::Gap::builder(timestamp)
+                .duration(dec.clock_time()?)
+                .build()
+        }
+        kind @ EVENT_CUSTOM | kind @ EVENT_CUSTOM_STICKY => {
+            let structure = gst::Structure::from_str(dec.str()?)
+                .map_err(|_| invalid_data("Invalid structure"))?;
+            if kind == EVENT_CUSTOM_STICKY {
+                gst::event::CustomDownstreamSticky::new(structure)
+            } else {
+                gst::event::CustomDownstream::new(structure)
+            }
+        }
+        _ => return Err(invalid_data("Unknown event")),
+    };
+
+    Ok(event)
+}
+
+fn decode_buffer(mut dec: Decoder, ring: &Ring) -> io::Result<gst::Buffer> {
+    let ring_offset = dec.u64()?;
+    let size = dec.u64()? as usize;
+    let pts = dec.clock_time()?;
+    let dts = dec.clock_time()?;
+    let duration = dec.clock_time()?;
+    let offset = dec.u64()?;
+    let offset_end = dec.u64()?;
+    let flags = gst::BufferFlags::from_bits_truncate(dec.u32()?);
+
+    // The payload is copied out so that its room in the ring
+    // can be released as soon as the buffer is enqueued.
+    let data = if ring_offset == INLINE {
+        dec.take(size)?.to_vec()
+    } else {
+        ring.read(ring_offset as usize, size)?.to_vec()
+    };
+
+    let mut buffer = gst::Buffer::from_mut_slice(data);
+    {
+        let buffer = buffer.get_mut().unwrap();
+        buffer.set_pts(pts);
+        buffer.set_dts(dts);
+        buffer.set_duration(duration);
+        buffer.set_offset(offset);
+        buffer.set_offset_end(offset_end);
+        buffer.set_flags(flags);
+    }
+
+    Ok(buffer)
+}
+
+#[derive(Debug)]
+struct Connection {
+    stream: UnixStream,
+    ring: Ring,
+    write_pos: usize,
+    used: usize,
+    /// Room taken in the ring by each frame awaiting acknowledgement.
+    in_flight: VecDeque<usize>,
+}
+
+impl Connection {
+    fn connect(path: &Path, ring_size: usize) -> io::Result<Self> {
+        let stream = UnixStream::connect(path)?;
+        let ring = Ring::create(ring_size)?;
+
+        let mut hello = Vec::with_capacity(FRAME_HEADER_LEN + HELLO_LEN);
+        hello.extend_from_slice(&(HELLO_LEN as u32).to_le_bytes());
+        hello.push(KIND_HELLO);
+        hello.extend_from_slice(&MAGIC.to_le_bytes());
+        hello.extend_from_slice(&VERSION.to_le_bytes());
+        hello.extend_from_slice(&(ring_size as u64).to_le_bytes());
+        send_with_fd(&stream, &hello, ring.fd)?;
+
+        Ok(Connection {
+            stream,
+            ring,
+            write_pos: 0,
+            used: 0,
+            in_flight: VecDeque::new(),
+        })
+    }
+
+    fn wait_ack(&mut self) -> io::Result<()> {
+        let mut frame = [0u8; FRAME_HEADER_LEN];
+        self.stream.read_exact(&mut frame)?;
+        if frame[4] != KIND_ACK || frame[..4] != [0; 4] {
+            return Err(invalid_data("Expected Ack"));
+        }
+
+        let room = self
+            .in_flight
+            .pop_front()
+            .ok_or_else(|| invalid_data("Unexpected Ack"))?;
+        self.used -= room;
+
+        Ok(())
+    }
+
+    fn send_frame(&mut self, frame: &[u8]) -> io::Result<()> {
+        while self.in_flight.len() >= MAX_IN_FLIGHT {
+            self.wait_ack()?;
+        }
+
+        self.in_flight.push_back(0);
+        send_all(&self.stream, frame)
+    }
+
+    fn send_buffer(&mut self, buffer: &gst::BufferRef) -> io::Result<()> {
+        let map = buffer
+            .map_readable()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Failed to map buffer"))?;
+        let size = map.size();
+
+        if size > self.ring.len {
+            if size > MAX_FRAME_LEN - INLINE_HEADER_LEN {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Buffer too large",
+                ));
+            }
+
+            let mut enc = encode_buffer(buffer, INLINE);
+            enc.bytes(&map);
+            return self.send_frame(&enc.finish());
+        }
+
+        // Payloads are contiguous in the ring, skip the end of the ring if needed
+        let (offset, room) = loop {
+            if self.in_flight.is_empty() {
+                self.write_pos = 0;
+            }
+
+            let (offset, room) = if self.write_pos + size > self.ring.len {
+                (0, self.ring.len - self.write_pos + size)
+            } else {
+                (self.write_pos, size)
+            };
+
+            if self.in_flight.len() < MAX_IN_FLIGHT && self.used + room <= self.ring.len {
+                break (offset, room);
+            }
+
+            self.wait_ack()?;
+        };
+
+        self.in_flight.push_back(room);
+        self.used += room;
+        self.ring.write(offset, &map);
+        self.write_pos = offset + size;
+
+        send_all(&self.stream, &encode_buffer(buffer, offset as u64).finish())
+    }
+
+    fn send_item(&mut self, item: &DataQueueItem) -> io::Result<()> {
+        match item {
+            DataQueueItem::Buffer(buffer) => self.send_buffer(buffer),
+            DataQueueItem::BufferList(list) => {
+                for buffer in list.iter() {
+                    self.send_buffer(buffer)?;
+                }
+                Ok(())
+            }
+            DataQueueItem::Event(event) => match encode_event(event) {
+                Some(frame) => self.send_frame(&frame),
+                None => {
+                    gst::debug!(CAT, "Not transferring {:?}", event);
+                    Ok(())
+                }
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ShutdownState {
+    stream: Option<UnixStream>,
+    closed: bool,
+}
+
+/// Shuts down the connection of a [`SenderThread`], unblocking it.
+#[derive(Clone, Debug, Default)]
+pub struct ShutdownHandle(Arc<Mutex<ShutdownState>>);
+
+impl ShutdownHandle {
+    /// Shuts down the current connection, the next item reconnects.
+    pub fn shutdown(&self) {
+        if let Some(ref stream) = self.0.lock().unwrap().stream {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    /// Shuts down the current connection and any later one.
+    fn close(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.closed = true;
+        if let Some(ref stream) = state.stream {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    fn set(&self, stream: Option<UnixStream>) {
+        let mut state = self.0.lock().unwrap();
+        if state.closed {
+            if let Some(ref stream) = stream {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+        }
+        state.stream = stream;
+    }
+}
+
+/// The `ts-proxysink` side of the transport.
+///
+/// Operations are blocking, see [`SenderThread`].
+#[derive(Debug)]
+struct Sender {
+    path: PathBuf,
+    ring_size: usize,
+    conn: Option<Connection>,
+    last_attempt: Option<Instant>,
+    shutdown_handle: ShutdownHandle,
+}
+
+impl Sender {
+    fn new(path: PathBuf, ring_size: usize) -> Self {
+        Sender {
+            path,
+            ring_size,
+            conn: None,
+            last_attempt: None,
+            shutdown_handle: ShutdownHandle::default(),
+        }
+    }
+
+    fn disconnect(&mut self) {
+        self.conn = None;
+        self.shutdown_handle.set(None);
+    }
+
+    fn connect(&mut self, element: &gst::Element, sticky_events: &[gst::Event]) -> bool {
+        if self
+            .last_attempt
+            .map_or(false, |last| last.elapsed() < RECONNECT_INTERVAL)
+        {
+            return false;
+        }
+        self.last_attempt = Some(Instant::now());
+
+        let mut conn = match Connection::connect(&self.path, self.ring_size) {
+            Ok(conn) => conn,
+            Err(err) => {
+                gst::debug!(
+                    CAT,
+                    obj: element,
+                    "Failed to connect to {}: {}",
+                    self.path.display(),
+                    err
+                );
+                return false;
+            }
+        };
+        self.shutdown_handle.set(conn.stream.try_clone().ok());
+
+        for event in sticky_events {
+            if let Err(err) = conn.send_item(&DataQueueItem::Event(event.clone())) {
+                gst::warning!(CAT, obj: element, "Failed to send sticky events: {}", err);
+                self.shutdown_handle.set(None);
+                return false;
+            }
+        }
+
+        gst::info!(CAT, obj: element, "Connected to {}", self.path.display());
+        self.conn = Some(conn);
+
+        true
+    }
+
+    /// Sends `item` to the peer, (re)connecting if needed.
+    ///
+    /// `sticky_events` are sent first when a new connection is established.
+    /// Items are dropped while no peer is available.
+    fn send(&mut self, element: &gst::Element, item: &DataQueueItem, sticky_events: &[gst::Event]) {
+        if self.conn.is_none() && !self.connect(element, sticky_events) {
+            gst::log!(CAT, obj: element, "Not connected, dropping {:?}", item);
+            return;
+        }
+
+        match self.conn.as_mut().unwrap().send_item(item) {
+            Ok(()) => (),
+            Err(err) if err.kind() == io::ErrorKind::InvalidInput => {
+                gst::warning!(CAT, obj: element, "Dropping {:?}: {}", item, err);
+            }
+            Err(err) => {
+                gst::warning!(
+                    CAT,
+                    obj: element,
+                    "Connection to {} lost: {}",
+                    self.path.display(),
+                    err
+                );
+                self.disconnect();
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SendRequest {
+    element: gst::Element,
+    pad: gst::Pad,
+    item: DataQueueItem,
+    done: oneshot::Sender<()>,
+}
+
+/// Runs a [`Sender`] on a dedicated thread.
+///
+/// Sending blocks for as long as the peer applies backpressure, so it must
+/// not take up a thread shared with other elements.
+#[derive(Debug)]
+pub struct SenderThread {
+    requests: Option<mpsc::Sender<SendRequest>>,
+    shutdown_handle: ShutdownHandle,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl SenderThread {
+    pub fn spawn(path: PathBuf, ring_size: usize) -> io::Result<Self> {
+        let mut sender = Sender::new(path, ring_size);
+        let shutdown_handle = sender.shutdown_handle.clone();
+        let (requests, receiver) = mpsc::channel::<SendRequest>();
+
+        let thread = thread::Builder::new()
+            .name("ts-proxysink-ipc".into())
+            .spawn(move || {
+                for request in receiver {
+                    let mut sticky_events = Vec::new();
+                    if sender.conn.is_none() {
+                        request.pad.sticky_events_foreach(|event| {
+                            sticky_events.push(event.clone());
+                            ControlFlow::Continue(gst::EventForeachAction::Keep)
+                        });
+                    }
+
+                    sender.send(&request.element, &request.item, &sticky_events);
+                    let _ = request.done.send(());
+                }
+            })?;
+
+        Ok(SenderThread {
+            requests: Some(requests),
+            shutdown_handle,
+            thread: Some(thread),
+        })
+    }
+
+    /// Returns a handle to unblock the sender thread.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown_handle.clone()
+    }
+
+    /// Sends `item` to the peer from the sender thread, (re)connecting if needed.
+    ///
+    /// The sticky events of `pad` are sent first when a new connection is
+    /// established. Items are dropped while no peer is available. The returned
+    /// future resolves once `item` is handled.
+    pub fn send(
+        &self,
+        element: &gst::Element,
+        pad: &gst::Pad,
+        item: DataQueueItem,
+    ) -> impl Future<Output = ()> {
+        let (done, done_receiver) = oneshot::channel();
+        let _ = self.requests.as_ref().unwrap().send(SendRequest {
+            element: element.clone(),
+            pad: pad.clone(),
+            item,
+            done,
+        });
+
+        done_receiver.map(|_| ())
+    }
+}
+
+impl Drop for SenderThread {
+    fn drop(&mut self) {
+        // Pending requests fail right away, then closing the channel terminates the thread
+        self.shutdown_handle.close();
+        self.requests = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Binds the `ts-proxysrc` side of the transport, removing any stale socket.
+pub fn bind(path: &Path) -> io::Result<Async<UnixListener>> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+        _ => (),
+    }
+
+    Async::<UnixListener>::bind(path)
+}
+
+/// A connection accepted by the `ts-proxysrc`.
+#[derive(Debug)]
+pub struct Receiver {
+    stream: Async<UnixStream>,
+    ring: Ring,
+    group_id: gst::GroupId,
+}
+
+impl Receiver {
+    pub async fn accept(listener: &Async<UnixListener>) -> io::Result<Self> {
+        let (stream, _) = listener.accept().await?;
+
+        let mut hello = [0u8; FRAME_HEADER_LEN + HELLO_LEN];
+        let (len, fd) = stream
+            .read_with(|stream| recv_with_fd(stream, &mut hello))
+            .await?;
+        let fd = fd.ok_or_else(|| invalid_data("Missing ring"))?;
+
+        // From now on, the fd is owned by the ring
+        let ring_size = {
+            let mut dec = Decoder(&hello[..len]);
+            let header = (|| -> io::Result<(u32, u8, u32, u32, u64)> {
+                Ok((dec.u32()?, dec.u8()?, dec.u32()?, dec.u32()?, dec.u64()?))
+            })();
+
+            match header {
+                Ok((len, KIND_HELLO, MAGIC, VERSION, ring_size)) if len as usize == HELLO_LEN => {
+                    ring_size
+                }
+                _ => {
+                    unsafe { libc::close(fd) };
+                    return Err(invalid_data("Invalid Hello"));
+                }
+            }
+        };
+
+        let ring = unsafe { Ring::map_sealed(fd, ring_size)? };
+
+        Ok(Receiver {
+            stream,
+            ring,
+            group_id: gst::GroupId::next(),
+        })
+    }
+
+    /// Returns the next item or `None` if the peer disconnected.
+    pub async fn next(&mut self) -> io::Result<Option<DataQueueItem>> {
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        match (&self.stream).read_exact(&mut header).await {
+            Ok(()) => (),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+
+        let len = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(invalid_data("Frame too large"));
+        }
+
+        let mut payload = vec![0u8; len];
+        (&self.stream).read_exact(&mut payload).await?;
+
+        let dec = Decoder(&payload);
+        let item = match header[4] {
+            KIND_BUFFER => DataQueueItem::Buffer(decode_buffer(dec, &self.ring)?),
+            KIND_EVENT => DataQueueItem::Event(decode_event(dec, self.group_id)?),
+            _ => return Err(invalid_data("Unexpected frame")),
+        };
+
+        Ok(Some(item))
+    }
+
+    /// Acknowledges the last item, releasing its room in the ring.
+    pub async fn ack(&self) -> io::Result<()> {
+        let mut frame = [0u8; FRAME_HEADER_LEN];
+        frame[4] = KIND_ACK;
+        self.stream
+            .write_with(|stream| send_all(stream, &frame))
+            .await
+    }
+}
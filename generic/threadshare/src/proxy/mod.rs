@@ -21,6 +21,8 @@ use gst::glib;
 use gst::prelude::*;
 
 mod imp;
+#[cfg(target_os = "linux")]
+mod ipc;
 
 glib::wrapper! {
     pub struct ProxySink(ObjectSubclass<imp::ProxySink>) @extends gst::Element, gst::Object;
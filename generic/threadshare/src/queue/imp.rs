@@ -18,7 +18,7 @@
 // SPDX-License-Identifier: LGPL-2.1-or-later
 
 use futures::channel::oneshot;
-use futures::future::BoxFuture;
+use futures::future::{self, AbortHandle, BoxFuture};
 use futures::prelude::*;
 
 use gst::glib;
@@ -33,23 +33,27 @@ use std::time::Duration;
 use std::{u32, u64};
 
 use crate::runtime::prelude::*;
-use crate::runtime::{Context, PadSink, PadSinkRef, PadSrc, PadSrcRef, PadSrcWeak, Task};
+use crate::runtime::{self, Context, PadSink, PadSinkRef, PadSrc, PadSrcRef, PadSrcWeak, Task};
 
-use crate::dataqueue::{DataQueue, DataQueueItem};
+use crate::dataqueue::{DataQueue, DataQueueItem, DataQueueLeaky};
 
 const DEFAULT_MAX_SIZE_BUFFERS: u32 = 200;
 const DEFAULT_MAX_SIZE_BYTES: u32 = 1024 * 1024;
 const DEFAULT_MAX_SIZE_TIME: gst::ClockTime = gst::ClockTime::SECOND;
+const DEFAULT_LEAKY: DataQueueLeaky = DataQueueLeaky::No;
 const DEFAULT_CONTEXT: &str = "";
 const DEFAULT_CONTEXT_WAIT: Duration = Duration::ZERO;
+const DEFAULT_CONTEXT_STATS_INTERVAL: Duration = Duration::ZERO;
 
 #[derive(Debug, Clone)]
 struct Settings {
     max_size_buffers: u32,
     max_size_bytes: u32,
     max_size_time: gst::ClockTime,
+    leaky: DataQueueLeaky,
     context: String,
     context_wait: Duration,
+    context_stats_interval: Duration,
 }
 
 impl Default for Settings {
@@ -58,8 +62,10 @@ impl Default for Settings {
             max_size_buffers: DEFAULT_MAX_SIZE_BUFFERS,
             max_size_bytes: DEFAULT_MAX_SIZE_BYTES,
             max_size_time: DEFAULT_MAX_SIZE_TIME,
+            leaky: DEFAULT_LEAKY,
             context: DEFAULT_CONTEXT.into(),
             context_wait: DEFAULT_CONTEXT_WAIT,
+            context_stats_interval: DEFAULT_CONTEXT_STATS_INTERVAL,
         }
     }
 }
@@ -452,6 +458,7 @@ pub struct Queue {
     dataqueue: StdMutex<Option<DataQueue>>,
     pending_queue: StdMutex<Option<PendingQueue>>,
     last_res: StdMutex<Result<gst::FlowSuccess, gst::FlowError>>,
+    context_stats_handle: StdMutex<Option<AbortHandle>>,
     settings: StdMutex<Settings>,
 }
 
@@ -636,6 +643,7 @@ impl Queue {
                 Some(settings.max_size_time)
             },
         );
+        dataqueue.set_leaky(settings.leaky);
 
         *self.dataqueue.lock().unwrap() = Some(dataqueue.clone());
 
@@ -647,6 +655,14 @@ impl Queue {
                 )
             })?;
 
+        if !settings.context_stats_interval.is_zero() {
+            *self.context_stats_handle.lock().unwrap() = Some(Self::spawn_context_stats(
+                element,
+                &context,
+                settings.context_stats_interval,
+            ));
+        }
+
         self.task
             .prepare(QueueTask::new(element, &self.src_pad, dataqueue), context)
             .map_err(|err| {
@@ -666,6 +682,10 @@ impl Queue {
 
         self.task.unprepare().unwrap();
 
+        if let Some(context_stats_handle) = self.context_stats_handle.lock().unwrap().take() {
+            context_stats_handle.abort();
+        }
+
         *self.dataqueue.lock().unwrap() = None;
         *self.pending_queue.lock().unwrap() = None;
 
@@ -674,6 +694,33 @@ impl Queue {
         gst::debug!(CAT, obj: element, "Unprepared");
     }
 
+    /// Posts the statistics of `context` as element messages every `interval`,
+    /// until the returned handle is aborted.
+    fn spawn_context_stats(
+        element: &super::Queue,
+        context: &Context,
+        interval: Duration,
+    ) -> AbortHandle {
+        let element_weak = element.downgrade();
+        let context_clone = context.clone();
+        let (stats_fut, abort_handle) = future::abortable(async move {
+            let mut interval = runtime::time::interval(interval);
+            while interval.next().await.is_some() {
+                match element_weak.upgrade() {
+                    Some(element) => {
+                        context_clone.post_stats(&element);
+                    }
+                    None => break,
+                }
+            }
+        });
+
+        // Only the abort handle is needed to stop posting
+        let _ = context.spawn(stats_fut);
+
+        abort_handle
+    }
+
     fn stop(&self, element: &super::Queue) -> Result<(), gst::ErrorMessage> {
         gst::debug!(CAT, obj: element, "Stopping");
         self.task.stop()?;
@@ -709,6 +756,7 @@ impl ObjectSubclass for Queue {
             dataqueue: StdMutex::new(None),
             pending_queue: StdMutex::new(None),
             last_res: StdMutex::new(Ok(gst::FlowSuccess::Ok)),
+            context_stats_handle: StdMutex::new(None),
             settings: StdMutex::new(Settings::default()),
         }
     }
@@ -734,6 +782,15 @@ impl ObjectImpl for Queue {
                     DEFAULT_CONTEXT_WAIT.as_millis() as u32,
                     glib::ParamFlags::READWRITE,
                 ),
+                glib::ParamSpecUInt::new(
+                    "context-stats-interval",
+                    "Context Stats Interval",
+                    "Post the statistics of the Context as element messages every this many ms (0 = disabled)",
+                    0,
+                    u32::MAX,
+                    DEFAULT_CONTEXT_STATS_INTERVAL.as_millis() as u32,
+                    glib::ParamFlags::READWRITE,
+                ),
                 glib::ParamSpecUInt::new(
                     "max-size-buffers",
                     "Max Size Buffers",
@@ -761,6 +818,23 @@ impl ObjectImpl for Queue {
                     DEFAULT_MAX_SIZE_TIME.nseconds(),
                     glib::ParamFlags::READWRITE,
                 ),
+                glib::ParamSpecEnum::new(
+                    "leaky",
+                    "Leaky",
+                    "Where the queue leaks buffers when full, if at all",
+                    DataQueueLeaky::static_type(),
+                    DEFAULT_LEAKY as i32,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt64::new(
+                    "dropped",
+                    "Dropped",
+                    "Number of buffers leaked since the queue was prepared",
+                    0,
+                    u64::MAX,
+                    0,
+                    glib::ParamFlags::READABLE,
+                ),
             ]
         });
 
@@ -786,6 +860,9 @@ impl ObjectImpl for Queue {
                 settings.max_size_time =
                     gst::ClockTime::from_nseconds(value.get().expect("type checked upstream"));
             }
+            "leaky" => {
+                settings.leaky = value.get().expect("type checked upstream");
+            }
             "context" => {
                 settings.context = value
                     .get::<Option<String>>()
@@ -797,18 +874,32 @@ impl ObjectImpl for Queue {
                     value.get::<u32>().expect("type checked upstream").into(),
                 );
             }
+            "context-stats-interval" => {
+                settings.context_stats_interval = Duration::from_millis(
+                    value.get::<u32>().expect("type checked upstream").into(),
+                );
+            }
             _ => unimplemented!(),
         }
     }
 
     fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        if pspec.name() == "dropped" {
+            let dataqueue = self.dataqueue.lock().unwrap();
+            return dataqueue.as_ref().map_or(0, DataQueue::dropped).to_value();
+        }
+
         let settings = self.settings.lock().unwrap();
         match pspec.name() {
             "max-size-buffers" => settings.max_size_buffers.to_value(),
             "max-size-bytes" => settings.max_size_bytes.to_value(),
             "max-size-time" => settings.max_size_time.nseconds().to_value(),
+            "leaky" => settings.leaky.to_value(),
             "context" => settings.context.to_value(),
             "context-wait" => (settings.context_wait.as_millis() as u32).to_value(),
+            "context-stats-interval" => {
+                (settings.context_stats_interval.as_millis() as u32).to_value()
+            }
             _ => unimplemented!(),
         }
     }
@@ -0,0 +1,443 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use futures::future::BoxFuture;
+use futures::prelude::*;
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+use gst_rtp::RTPBuffer;
+
+use once_cell::sync::Lazy;
+
+use std::collections::HashMap;
+use std::sync::Mutex as StdMutex;
+
+use crate::rtx::{self, BUFFER_FLAG_RETRANSMISSION};
+use crate::runtime::prelude::*;
+use crate::runtime::{PadSink, PadSinkRef, PadSrc, PadSrcRef};
+
+// Upper bound for requests which were neither answered nor made obsolete
+const MAX_PENDING_REQUESTS: usize = 1024;
+
+#[derive(Debug, Clone, Default)]
+struct Settings {
+    payload_type_map: Option<gst::Structure>,
+    // Retransmission payload type to original payload type
+    rtx_pt_map: HashMap<u8, u8>,
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    num_rtx_requests: u64,
+    num_rtx_packets: u64,
+    num_rtx_assoc_packets: u64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    // Retransmission SSRC to original SSRC
+    ssrc2_ssrc1: HashMap<u32, u32>,
+    // Requested seqnum to original SSRC
+    pending_requests: HashMap<u16, u32>,
+    stats: Stats,
+}
+
+#[derive(Clone, Debug)]
+struct RtpRtxReceivePadSinkHandler;
+
+impl PadSinkHandler for RtpRtxReceivePadSinkHandler {
+    type ElementImpl = RtpRtxReceive;
+
+    fn sink_chain(
+        &self,
+        pad: &PadSinkRef,
+        _rtxreceive: &RtpRtxReceive,
+        element: &gst::Element,
+        buffer: gst::Buffer,
+    ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
+        let pad_weak = pad.downgrade();
+        let element = element.clone().downcast::<super::RtpRtxReceive>().unwrap();
+
+        async move {
+            let pad = pad_weak.upgrade().expect("PadSink no longer exists");
+            gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", buffer);
+
+            let rtxreceive = element.imp();
+            match rtxreceive.handle_buffer(&element, buffer) {
+                Some(buffer) => rtxreceive.src_pad.push(buffer).await,
+                None => Ok(gst::FlowSuccess::Ok),
+            }
+        }
+        .boxed()
+    }
+
+    fn sink_chain_list(
+        &self,
+        pad: &PadSinkRef,
+        _rtxreceive: &RtpRtxReceive,
+        element: &gst::Element,
+        list: gst::BufferList,
+    ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
+        let pad_weak = pad.downgrade();
+        let element = element.clone().downcast::<super::RtpRtxReceive>().unwrap();
+
+        async move {
+            let pad = pad_weak.upgrade().expect("PadSink no longer exists");
+            gst::log!(CAT, obj: pad.gst_pad(), "Handling buffer list {:?}", list);
+
+            let rtxreceive = element.imp();
+            let mut out_list = gst::BufferList::new_sized(list.len());
+            {
+                let out_list = out_list.get_mut().unwrap();
+                for buffer in list.iter_owned() {
+                    if let Some(buffer) = rtxreceive.handle_buffer(&element, buffer) {
+                        out_list.add(buffer);
+                    }
+                }
+            }
+
+            if out_list.is_empty() {
+                return Ok(gst::FlowSuccess::Ok);
+            }
+
+            rtxreceive.src_pad.push_list(out_list).await
+        }
+        .boxed()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct RtpRtxReceivePadSrcHandler;
+
+impl PadSrcHandler for RtpRtxReceivePadSrcHandler {
+    type ElementImpl = RtpRtxReceive;
+
+    fn src_event(
+        &self,
+        pad: &PadSrcRef,
+        rtxreceive: &RtpRtxReceive,
+        _element: &gst::Element,
+        event: gst::Event,
+    ) -> bool {
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", event);
+
+        if let gst::EventView::CustomUpstream(e) = event.view() {
+            if let Some(s) = e
+                .structure()
+                .filter(|s| s.name() == rtx::RETRANSMISSION_REQUEST)
+            {
+                if let (Ok(seqnum), Ok(ssrc)) = (s.get::<u32>("seqnum"), s.get::<u32>("ssrc")) {
+                    let mut state = rtxreceive.state.lock().unwrap();
+                    if state.pending_requests.len() >= MAX_PENDING_REQUESTS {
+                        gst::warning!(CAT, obj: pad.gst_pad(), "Too many pending requests");
+                        state.pending_requests.clear();
+                    }
+                    state.pending_requests.insert(seqnum as u16, ssrc);
+                    state.stats.num_rtx_requests += 1;
+                }
+            }
+        }
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Forwarding {:?}", event);
+        rtxreceive.sink_pad.gst_pad().push_event(event)
+    }
+}
+
+pub struct RtpRtxReceive {
+    sink_pad: PadSink,
+    src_pad: PadSrc,
+    state: StdMutex<State>,
+    settings: StdMutex<Settings>,
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "ts-rtprtxreceive",
+        gst::DebugColorFlags::empty(),
+        Some("Thread-sharing RTP retransmission receiver"),
+    )
+});
+
+impl RtpRtxReceive {
+    fn handle_buffer(
+        &self,
+        element: &super::RtpRtxReceive,
+        buffer: gst::Buffer,
+    ) -> Option<gst::Buffer> {
+        let (seqnum, pt, ssrc) = match RTPBuffer::from_buffer_readable(&buffer) {
+            Ok(rtp_buffer) => (
+                rtp_buffer.seq(),
+                rtp_buffer.payload_type(),
+                rtp_buffer.ssrc(),
+            ),
+            // Let downstream decide what to do with it
+            Err(_) => return Some(buffer),
+        };
+
+        let orig_pt = {
+            let settings = self.settings.lock().unwrap();
+            settings.rtx_pt_map.get(&pt).copied()
+        };
+
+        let mut state = self.state.lock().unwrap();
+
+        let orig_pt = match orig_pt {
+            Some(orig_pt) => orig_pt,
+            None => {
+                // The original packet made it after all
+                if state.pending_requests.get(&seqnum) == Some(&ssrc) {
+                    state.pending_requests.remove(&seqnum);
+                }
+
+                return Some(buffer);
+            }
+        };
+
+        state.stats.num_rtx_packets += 1;
+
+        let map = buffer.map_readable().ok()?;
+        let data = map.as_slice();
+        let header_len = match rtx::header_len(data) {
+            Some(header_len) if data.len() >= header_len + 2 => header_len,
+            _ => {
+                gst::warning!(CAT, obj: element, "Dropping invalid retransmission packet");
+                return None;
+            }
+        };
+
+        let osn = u16::from_be_bytes([data[header_len], data[header_len + 1]]);
+
+        let orig_ssrc = match state.ssrc2_ssrc1.get(&ssrc) {
+            Some(orig_ssrc) => *orig_ssrc,
+            None => match state.pending_requests.get(&osn) {
+                Some(orig_ssrc) => {
+                    let orig_ssrc = *orig_ssrc;
+                    gst::debug!(
+                        CAT,
+                        obj: element,
+                        "Associated rtx ssrc {:#010x} with ssrc {:#010x}",
+                        ssrc,
+                        orig_ssrc
+                    );
+                    state.ssrc2_ssrc1.insert(ssrc, orig_ssrc);
+                    orig_ssrc
+                }
+                None => {
+                    gst::debug!(
+                        CAT,
+                        obj: element,
+                        "Dropping unassociated retransmission of #{} from ssrc {:#010x}",
+                        osn,
+                        ssrc
+                    );
+                    return None;
+                }
+            },
+        };
+
+        state.pending_requests.remove(&osn);
+        state.stats.num_rtx_assoc_packets += 1;
+
+        let mut orig_data = Vec::with_capacity(data.len() - 2);
+        orig_data.extend_from_slice(&data[..header_len]);
+        orig_data.extend_from_slice(&data[header_len + 2..]);
+        rtx::rewrite_header(&mut orig_data, orig_pt, osn, orig_ssrc);
+
+        gst::log!(
+            CAT,
+            obj: element,
+            "Restored #{} of ssrc {:#010x} from retransmission #{}",
+            osn,
+            orig_ssrc,
+            seqnum
+        );
+
+        let mut orig_buffer = gst::Buffer::from_mut_slice(orig_data);
+        {
+            let orig_buffer = orig_buffer.get_mut().unwrap();
+            orig_buffer.set_pts(buffer.pts());
+            orig_buffer.set_dts(buffer.dts());
+            orig_buffer.set_flags(BUFFER_FLAG_RETRANSMISSION);
+        }
+
+        Some(orig_buffer)
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for RtpRtxReceive {
+    const NAME: &'static str = "RsTsRtpRtxReceive";
+    type Type = super::RtpRtxReceive;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        Self {
+            sink_pad: PadSink::new(
+                gst::Pad::from_template(&klass.pad_template("sink").unwrap(), Some("sink")),
+                RtpRtxReceivePadSinkHandler,
+            ),
+            src_pad: PadSrc::new(
+                gst::Pad::from_template(&klass.pad_template("src").unwrap(), Some("src")),
+                RtpRtxReceivePadSrcHandler,
+            ),
+            state: StdMutex::new(State::default()),
+            settings: StdMutex::new(Settings::default()),
+        }
+    }
+}
+
+impl ObjectImpl for RtpRtxReceive {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecBoxed::new(
+                    "payload-type-map",
+                    "Payload Type Map",
+                    "Map of original payload types to their retransmission payload types",
+                    gst::Structure::static_type(),
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoxed::new(
+                    "stats",
+                    "Statistics",
+                    "Various statistics",
+                    gst::Structure::static_type(),
+                    glib::ParamFlags::READABLE,
+                ),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        match pspec.name() {
+            "payload-type-map" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.payload_type_map = value
+                    .get::<Option<gst::Structure>>()
+                    .expect("type checked upstream");
+                settings.rtx_pt_map = settings
+                    .payload_type_map
+                    .as_ref()
+                    .map(|s| {
+                        rtx::parse_pt_map(s)
+                            .into_iter()
+                            .map(|(pt, rtx_pt)| (rtx_pt, pt))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            "payload-type-map" => {
+                let settings = self.settings.lock().unwrap();
+                settings.payload_type_map.to_value()
+            }
+            "stats" => {
+                let state = self.state.lock().unwrap();
+                let s = gst::Structure::builder("application/x-rtp-rtx-receive-stats")
+                    .field("num-rtx-requests", state.stats.num_rtx_requests)
+                    .field("num-rtx-packets", state.stats.num_rtx_packets)
+                    .field("num-rtx-assoc-packets", state.stats.num_rtx_assoc_packets)
+                    .build();
+                s.to_value()
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(self.sink_pad.gst_pad()).unwrap();
+        obj.add_pad(self.src_pad.gst_pad()).unwrap();
+    }
+}
+
+impl GstObjectImpl for RtpRtxReceive {}
+
+impl ElementImpl for RtpRtxReceive {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Thread-sharing RTP Retransmission receiver",
+                "Codec",
+                "Restores original RTP packets from a retransmission stream (RFC 4588)",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::builder("application/x-rtp").build();
+
+            let sink_pad_template = gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            let src_pad_template = gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![sink_pad_template, src_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        let success = self.parent_change_state(element, transition)?;
+
+        if let gst::StateChange::PausedToReady = transition {
+            *self.state.lock().unwrap() = State::default();
+        }
+
+        Ok(success)
+    }
+}
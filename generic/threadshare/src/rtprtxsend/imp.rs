@@ -0,0 +1,544 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use futures::future::BoxFuture;
+use futures::prelude::*;
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+use gst_rtp::RTPBuffer;
+
+use once_cell::sync::Lazy;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex as StdMutex;
+use std::u32;
+
+use crate::rtx;
+use crate::runtime::prelude::*;
+use crate::runtime::{PadSink, PadSinkRef, PadSrc, PadSrcRef};
+
+const DEFAULT_MAX_SIZE_PACKETS: u32 = 100;
+const DEFAULT_MAX_SIZE_TIME: u32 = 0;
+
+#[derive(Debug, Clone)]
+struct Settings {
+    payload_type_map: Option<gst::Structure>,
+    pt_map: HashMap<u8, u8>,
+    ssrc_map: Option<gst::Structure>,
+    max_size_packets: u32,
+    max_size_time: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            payload_type_map: None,
+            pt_map: HashMap::new(),
+            ssrc_map: None,
+            max_size_packets: DEFAULT_MAX_SIZE_PACKETS,
+            max_size_time: DEFAULT_MAX_SIZE_TIME,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SsrcData {
+    rtx_ssrc: u32,
+    next_seqnum: u16,
+    history: VecDeque<(u16, gst::Buffer)>,
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    num_rtx_requests: u64,
+    num_rtx_packets: u64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    ssrcs: HashMap<u32, SsrcData>,
+    stats: Stats,
+}
+
+fn make_rtx_buffer(
+    buffer: &gst::Buffer,
+    rtx_pt: u8,
+    rtx_seqnum: u16,
+    rtx_ssrc: u32,
+) -> Option<gst::Buffer> {
+    let map = buffer.map_readable().ok()?;
+    let data = map.as_slice();
+    let header_len = rtx::header_len(data)?;
+
+    // RFC 4588: the original sequence number is prepended to the original payload
+    let mut rtx_data = Vec::with_capacity(data.len() + 2);
+    rtx_data.extend_from_slice(&data[..header_len]);
+    rtx_data.extend_from_slice(&data[2..4]);
+    rtx_data.extend_from_slice(&data[header_len..]);
+    rtx::rewrite_header(&mut rtx_data, rtx_pt, rtx_seqnum, rtx_ssrc);
+
+    let mut rtx_buffer = gst::Buffer::from_mut_slice(rtx_data);
+    {
+        let rtx_buffer = rtx_buffer.get_mut().unwrap();
+        rtx_buffer.set_pts(buffer.pts());
+        rtx_buffer.set_dts(buffer.dts());
+    }
+
+    Some(rtx_buffer)
+}
+
+#[derive(Clone, Debug)]
+struct RtpRtxSendPadSinkHandler;
+
+impl PadSinkHandler for RtpRtxSendPadSinkHandler {
+    type ElementImpl = RtpRtxSend;
+
+    fn sink_chain(
+        &self,
+        pad: &PadSinkRef,
+        _rtxsend: &RtpRtxSend,
+        element: &gst::Element,
+        buffer: gst::Buffer,
+    ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
+        let pad_weak = pad.downgrade();
+        let element = element.clone().downcast::<super::RtpRtxSend>().unwrap();
+
+        async move {
+            let pad = pad_weak.upgrade().expect("PadSink no longer exists");
+            gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", buffer);
+
+            let rtxsend = element.imp();
+            rtxsend.store(&element, &buffer);
+            rtxsend.src_pad.push(buffer).await
+        }
+        .boxed()
+    }
+
+    fn sink_chain_list(
+        &self,
+        pad: &PadSinkRef,
+        _rtxsend: &RtpRtxSend,
+        element: &gst::Element,
+        list: gst::BufferList,
+    ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
+        let pad_weak = pad.downgrade();
+        let element = element.clone().downcast::<super::RtpRtxSend>().unwrap();
+
+        async move {
+            let pad = pad_weak.upgrade().expect("PadSink no longer exists");
+            gst::log!(CAT, obj: pad.gst_pad(), "Handling buffer list {:?}", list);
+
+            let rtxsend = element.imp();
+            for buffer in list.iter_owned() {
+                rtxsend.store(&element, &buffer);
+            }
+            rtxsend.src_pad.push_list(list).await
+        }
+        .boxed()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct RtpRtxSendPadSrcHandler;
+
+impl PadSrcHandler for RtpRtxSendPadSrcHandler {
+    type ElementImpl = RtpRtxSend;
+
+    fn src_event(
+        &self,
+        pad: &PadSrcRef,
+        rtxsend: &RtpRtxSend,
+        element: &gst::Element,
+        event: gst::Event,
+    ) -> bool {
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", event);
+
+        if let gst::EventView::CustomUpstream(e) = event.view() {
+            if let Some(s) = e
+                .structure()
+                .filter(|s| s.name() == rtx::RETRANSMISSION_REQUEST)
+            {
+                let element = element.downcast_ref::<super::RtpRtxSend>().unwrap();
+                if let Some(rtx_buffer) = rtxsend.retransmit(element, s) {
+                    gst::debug!(CAT, obj: pad.gst_pad(), "Pushing {:?}", rtx_buffer);
+                    // Requests are sent from the downstream element's task,
+                    // so the push ends up as a sub task of that context
+                    let _ = rtxsend.src_pad.gst_pad().push(rtx_buffer);
+                }
+
+                return true;
+            }
+        }
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Forwarding {:?}", event);
+        rtxsend.sink_pad.gst_pad().push_event(event)
+    }
+}
+
+pub struct RtpRtxSend {
+    sink_pad: PadSink,
+    src_pad: PadSrc,
+    state: StdMutex<State>,
+    settings: StdMutex<Settings>,
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "ts-rtprtxsend",
+        gst::DebugColorFlags::empty(),
+        Some("Thread-sharing RTP retransmission sender"),
+    )
+});
+
+impl RtpRtxSend {
+    fn rtx_ssrc(settings: &Settings, state: &State, ssrc: u32) -> u32 {
+        if let Some(rtx_ssrc) = settings
+            .ssrc_map
+            .as_ref()
+            .and_then(|s| s.get::<u32>(&ssrc.to_string()).ok())
+        {
+            return rtx_ssrc;
+        }
+
+        loop {
+            let rtx_ssrc = rand::random::<u32>();
+            if rtx_ssrc != ssrc
+                && !state.ssrcs.contains_key(&rtx_ssrc)
+                && !state.ssrcs.values().any(|data| data.rtx_ssrc == rtx_ssrc)
+            {
+                return rtx_ssrc;
+            }
+        }
+    }
+
+    fn store(&self, element: &super::RtpRtxSend, buffer: &gst::Buffer) {
+        let (seqnum, pt, ssrc) = match RTPBuffer::from_buffer_readable(buffer) {
+            Ok(rtp_buffer) => (
+                rtp_buffer.seq(),
+                rtp_buffer.payload_type(),
+                rtp_buffer.ssrc(),
+            ),
+            Err(_) => {
+                gst::warning!(CAT, obj: element, "Not storing invalid RTP buffer");
+                return;
+            }
+        };
+
+        let settings = self.settings.lock().unwrap();
+        if !settings.pt_map.contains_key(&pt) {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if !state.ssrcs.contains_key(&ssrc) {
+            let rtx_ssrc = Self::rtx_ssrc(&settings, &state, ssrc);
+            gst::debug!(
+                CAT,
+                obj: element,
+                "Using rtx ssrc {:#010x} for ssrc {:#010x}",
+                rtx_ssrc,
+                ssrc
+            );
+
+            state.ssrcs.insert(
+                ssrc,
+                SsrcData {
+                    rtx_ssrc,
+                    next_seqnum: rand::random::<u16>(),
+                    history: VecDeque::new(),
+                },
+            );
+        }
+
+        let history = &mut state.ssrcs.get_mut(&ssrc).unwrap().history;
+        history.push_back((seqnum, buffer.clone()));
+
+        if settings.max_size_packets > 0 {
+            while history.len() > settings.max_size_packets as usize {
+                history.pop_front();
+            }
+        }
+
+        if settings.max_size_time > 0 {
+            let max_size_time = gst::ClockTime::from_mseconds(settings.max_size_time as u64);
+            while let (Some(first), Some(last)) = (history.front(), history.back()) {
+                let duration = last.1.pts().opt_saturating_sub(first.1.pts());
+                if duration.map_or(true, |duration| duration <= max_size_time) {
+                    break;
+                }
+                history.pop_front();
+            }
+        }
+    }
+
+    fn retransmit(
+        &self,
+        element: &super::RtpRtxSend,
+        s: &gst::StructureRef,
+    ) -> Option<gst::Buffer> {
+        let (seqnum, ssrc) = match (s.get::<u32>("seqnum"), s.get::<u32>("ssrc")) {
+            (Ok(seqnum), Ok(ssrc)) => (seqnum as u16, ssrc),
+            _ => {
+                gst::warning!(CAT, obj: element, "Invalid retransmission request {:?}", s);
+                return None;
+            }
+        };
+
+        let settings = self.settings.lock().unwrap();
+        let mut state = self.state.lock().unwrap();
+        state.stats.num_rtx_requests += 1;
+
+        let data = match state.ssrcs.get_mut(&ssrc) {
+            Some(data) => data,
+            None => {
+                gst::debug!(CAT, obj: element, "Unknown ssrc {:#010x}", ssrc);
+                return None;
+            }
+        };
+
+        let buffer = match data.history.iter().rev().find(|(seq, _)| *seq == seqnum) {
+            Some((_, buffer)) => buffer,
+            None => {
+                gst::debug!(CAT, obj: element, "#{} not in history anymore", seqnum);
+                return None;
+            }
+        };
+
+        let pt = RTPBuffer::from_buffer_readable(buffer).ok()?.payload_type();
+        let rtx_pt = *settings.pt_map.get(&pt)?;
+
+        let rtx_buffer = make_rtx_buffer(buffer, rtx_pt, data.next_seqnum, data.rtx_ssrc)?;
+        gst::log!(
+            CAT,
+            obj: element,
+            "Retransmitting #{} of ssrc {:#010x} as #{}",
+            seqnum,
+            ssrc,
+            data.next_seqnum
+        );
+        data.next_seqnum = data.next_seqnum.wrapping_add(1);
+        state.stats.num_rtx_packets += 1;
+
+        Some(rtx_buffer)
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for RtpRtxSend {
+    const NAME: &'static str = "RsTsRtpRtxSend";
+    type Type = super::RtpRtxSend;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        Self {
+            sink_pad: PadSink::new(
+                gst::Pad::from_template(&klass.pad_template("sink").unwrap(), Some("sink")),
+                RtpRtxSendPadSinkHandler,
+            ),
+            src_pad: PadSrc::new(
+                gst::Pad::from_template(&klass.pad_template("src").unwrap(), Some("src")),
+                RtpRtxSendPadSrcHandler,
+            ),
+            state: StdMutex::new(State::default()),
+            settings: StdMutex::new(Settings::default()),
+        }
+    }
+}
+
+impl ObjectImpl for RtpRtxSend {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecBoxed::new(
+                    "payload-type-map",
+                    "Payload Type Map",
+                    "Map of original payload types to their retransmission payload types",
+                    gst::Structure::static_type(),
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoxed::new(
+                    "ssrc-map",
+                    "SSRC Map",
+                    "Map of SSRCs to their retransmission SSRCs (random if not set)",
+                    gst::Structure::static_type(),
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "max-size-packets",
+                    "Max Size Packets",
+                    "Amount of packets to keep per SSRC (0 = unlimited)",
+                    0,
+                    u32::MAX,
+                    DEFAULT_MAX_SIZE_PACKETS,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "max-size-time",
+                    "Max Size Time",
+                    "Amount of ms to keep per SSRC (0 = unlimited)",
+                    0,
+                    u32::MAX,
+                    DEFAULT_MAX_SIZE_TIME,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoxed::new(
+                    "stats",
+                    "Statistics",
+                    "Various statistics",
+                    gst::Structure::static_type(),
+                    glib::ParamFlags::READABLE,
+                ),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        let mut settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "payload-type-map" => {
+                settings.payload_type_map = value
+                    .get::<Option<gst::Structure>>()
+                    .expect("type checked upstream");
+                settings.pt_map = settings
+                    .payload_type_map
+                    .as_ref()
+                    .map(|s| rtx::parse_pt_map(s))
+                    .unwrap_or_default();
+            }
+            "ssrc-map" => {
+                settings.ssrc_map = value
+                    .get::<Option<gst::Structure>>()
+                    .expect("type checked upstream");
+            }
+            "max-size-packets" => {
+                settings.max_size_packets = value.get().expect("type checked upstream");
+            }
+            "max-size-time" => {
+                settings.max_size_time = value.get().expect("type checked upstream");
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            "payload-type-map" => {
+                let settings = self.settings.lock().unwrap();
+                settings.payload_type_map.to_value()
+            }
+            "ssrc-map" => {
+                let settings = self.settings.lock().unwrap();
+                settings.ssrc_map.to_value()
+            }
+            "max-size-packets" => {
+                let settings = self.settings.lock().unwrap();
+                settings.max_size_packets.to_value()
+            }
+            "max-size-time" => {
+                let settings = self.settings.lock().unwrap();
+                settings.max_size_time.to_value()
+            }
+            "stats" => {
+                let state = self.state.lock().unwrap();
+                let s = gst::Structure::builder("application/x-rtp-rtx-send-stats")
+                    .field("num-rtx-requests", state.stats.num_rtx_requests)
+                    .field("num-rtx-packets", state.stats.num_rtx_packets)
+                    .build();
+                s.to_value()
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(self.sink_pad.gst_pad()).unwrap();
+        obj.add_pad(self.src_pad.gst_pad()).unwrap();
+    }
+}
+
+impl GstObjectImpl for RtpRtxSend {}
+
+impl ElementImpl for RtpRtxSend {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Thread-sharing RTP Retransmission Sender",
+                "Codec",
+                "Keeps a history of RTP packets and retransmits them on request (RFC 4588)",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::builder("application/x-rtp").build();
+
+            let sink_pad_template = gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            let src_pad_template = gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![sink_pad_template, src_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        let success = self.parent_change_state(element, transition)?;
+
+        if let gst::StateChange::PausedToReady = transition {
+            *self.state.lock().unwrap() = State::default();
+        }
+
+        Ok(success)
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+//! Helpers shared by the RFC 4588 retransmission elements and the jitterbuffer.
+
+use std::collections::HashMap;
+
+/// Same flag as `GST_RTP_BUFFER_FLAG_RETRANSMISSION`, set on packets restored
+/// from a retransmission stream.
+pub const BUFFER_FLAG_RETRANSMISSION: gst::BufferFlags = unsafe {
+    gst::BufferFlags::from_bits_unchecked(gst_rtp::ffi::GST_RTP_BUFFER_FLAG_RETRANSMISSION)
+};
+
+pub const RETRANSMISSION_REQUEST: &str = "GstRTPRetransmissionRequest";
+
+/// Returns the length of the fixed header, CSRCs and extension of an RTP packet.
+pub fn header_len(data: &[u8]) -> Option<usize> {
+    if data.len() < 12 {
+        return None;
+    }
+
+    let mut len = 12 + 4 * (data[0] & 0x0f) as usize;
+    if data[0] & 0x10 != 0 {
+        if data.len() < len + 4 {
+            return None;
+        }
+        let ext_len = u16::from_be_bytes([data[len + 2], data[len + 3]]) as usize;
+        len += 4 + 4 * ext_len;
+    }
+
+    if data.len() < len {
+        None
+    } else {
+        Some(len)
+    }
+}
+
+/// Overwrites the payload type, sequence number and SSRC of an RTP packet.
+pub fn rewrite_header(data: &mut [u8], pt: u8, seqnum: u16, ssrc: u32) {
+    data[1] = (data[1] & 0x80) | (pt & 0x7f);
+    data[2..4].copy_from_slice(&seqnum.to_be_bytes());
+    data[8..12].copy_from_slice(&ssrc.to_be_bytes());
+}
+
+/// Parses a `payload-type-map` structure, mapping original payload types
+/// to their retransmission payload types.
+pub fn parse_pt_map(s: &gst::StructureRef) -> HashMap<u8, u8> {
+    s.iter()
+        .filter_map(|(name, value)| {
+            let pt = name.parse::<u8>().ok()?;
+            let rtx_pt = value.get::<u32>().ok()?;
+            Some((pt, rtx_pt as u8))
+        })
+        .collect()
+}
@@ -0,0 +1,45 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+//! A small pool of `thread`s for operations which can't be made non-blocking.
+//!
+//! Regular files for instance can't be registered with the [`Reactor`], so reading from
+//! or writing to them would block the [`Context`] `thread` and every other `Element`
+//! sharing it.
+//!
+//! [`Reactor`]: ../reactor/struct.Reactor.html
+//! [`Context`]: ../struct.Context.html
+
+use futures::executor::ThreadPool;
+use futures::future::RemoteHandle;
+use futures::task::SpawnExt;
+
+use once_cell::sync::Lazy;
+
+const POOL_SIZE: usize = 4;
+
+static POOL: Lazy<ThreadPool> = Lazy::new(|| {
+    ThreadPool::builder()
+        .pool_size(POOL_SIZE)
+        .name_prefix("ts-blocking-")
+        .create()
+        .expect("Failed to create blocking thread pool")
+});
+
+/// Executes the blocking function `f` on the shared blocking thread pool.
+///
+/// The returned `Future` resolves to the output of `f`. It can be awaited
+/// from a [`Context`] without blocking its `thread`.
+///
+/// Dropping the returned `Future` doesn't interrupt `f` if it is already running.
+///
+/// [`Context`]: ../struct.Context.html
+pub fn spawn_blocking<F, T>(f: F) -> RemoteHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    POOL.spawn_with_handle(async move { f() })
+        .expect("Failed to spawn on blocking thread pool")
+}
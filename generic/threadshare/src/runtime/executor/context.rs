@@ -5,6 +5,8 @@
 
 use futures::prelude::*;
 
+use gst::prelude::*;
+
 use once_cell::sync::Lazy;
 
 use std::collections::HashMap;
@@ -14,7 +16,7 @@ use std::sync::{Arc, Mutex};
 use std::task::{self, Poll};
 use std::time::Duration;
 
-use super::{Handle, HandleWeak, JoinHandle, Scheduler, SubTaskOutput, TaskId};
+use super::{ContextStats, Handle, HandleWeak, JoinHandle, Scheduler, SubTaskOutput, TaskId};
 use crate::runtime::RUNTIME_CAT;
 
 // We are bound to using `sync` for the `runtime` `Mutex`es. Attempts to use `async` `Mutex`es
@@ -148,11 +150,94 @@ impl PartialEq for Context {
 impl Eq for Context {}
 
 impl Context {
+    /// Prefix of the `Context` names which designate a pool of `Context`s.
+    ///
+    /// See [`Context::acquire`].
+    pub const POOL_PREFIX: &'static str = "pool:";
+
+    /// Acquires the `Context` named `context_name`, starting it if necessary.
+    ///
+    /// If `context_name` is of the form `pool:NAME[:SIZE]`, the `Context` is
+    /// picked from the pool `NAME` of `SIZE` `Context`s (defaults to the number
+    /// of available CPUs). See [`Context::acquire_from_pool`].
     pub fn acquire(context_name: &str, wait: Duration) -> Result<Self, io::Error> {
         assert_ne!(context_name, Scheduler::DUMMY_NAME);
 
+        if let Some(pool) = context_name.strip_prefix(Self::POOL_PREFIX) {
+            let (pool_name, size) = match pool.rsplit_once(':') {
+                Some((pool_name, size)) => match size.parse::<usize>() {
+                    Ok(size) => (pool_name, size),
+                    Err(_) => (pool, Self::default_pool_size()),
+                },
+                None => (pool, Self::default_pool_size()),
+            };
+
+            return Self::acquire_from_pool(pool_name, size, wait);
+        }
+
+        let mut contexts = CONTEXTS.lock().unwrap();
+        Self::acquire_locked(&mut contexts, context_name, wait)
+    }
+
+    /// Acquires the least loaded `Context` from the pool `pool_name` of `size` `Context`s.
+    ///
+    /// The load of a `Context` is the number of tasks alive on it, see
+    /// [`ContextStats::tasks_alive`]. This includes the state machine of the [`Task`]s
+    /// of the elements prepared on the `Context`. `Context`s which are not started
+    /// yet are the least loaded. Ties are resolved in favor of the lowest index in
+    /// the pool, so placement is deterministic for a given sequence of acquisitions
+    /// and releases.
+    ///
+    /// Placement only occurs at acquisition, which elements perform when preparing:
+    /// elements are not moved to another `Context` when paused and resumed, they
+    /// are rebalanced only when they go through the `Null` state.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if `size` is 0.
+    ///
+    /// [`ContextStats::tasks_alive`]: struct.ContextStats.html#structfield.tasks_alive
+    /// [`Task`]: ../task/struct.Task.html
+    pub fn acquire_from_pool(
+        pool_name: &str,
+        size: usize,
+        wait: Duration,
+    ) -> Result<Self, io::Error> {
+        if size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Context pool '{}' has a size of 0", pool_name),
+            ));
+        }
+
         let mut contexts = CONTEXTS.lock().unwrap();
 
+        let (index, _) = (0..size)
+            .map(|index| {
+                let load = contexts
+                    .get(Self::pool_context_name(pool_name, index).as_str())
+                    .and_then(ContextWeak::upgrade)
+                    .map_or(0, |context| context.stats().tasks_alive);
+
+                (index, load)
+            })
+            .min_by_key(|&(index, load)| (load, index))
+            .unwrap();
+
+        let context_name = Self::pool_context_name(pool_name, index);
+        gst::debug!(
+            RUNTIME_CAT,
+            "Picked Context '{}' from pool '{}'",
+            context_name,
+            pool_name
+        );
+
+        Self::acquire_locked(&mut contexts, &context_name, wait)
+    }
+
+    fn acquire_locked(
+        contexts: &mut HashMap<Arc<str>, ContextWeak>,
+        context_name: &str,
+        wait: Duration,
+    ) -> Result<Self, io::Error> {
         if let Some(context_weak) = contexts.get(context_name) {
             if let Some(context) = context_weak.upgrade() {
                 gst::debug!(RUNTIME_CAT, "Joining Context '{}'", context.name());
@@ -167,6 +252,14 @@ impl Context {
         Ok(context)
     }
 
+    fn pool_context_name(pool_name: &str, index: usize) -> String {
+        format!("{}{}-{}", Self::POOL_PREFIX, pool_name, index)
+    }
+
+    fn default_pool_size() -> usize {
+        num_cpus::get()
+    }
+
     pub fn downgrade(&self) -> ContextWeak {
         ContextWeak(self.0.downgrade())
     }
@@ -182,6 +275,42 @@ impl Context {
         self.0.max_throttling()
     }
 
+    /// Returns a snapshot of the statistics of this `Context`.
+    pub fn stats(&self) -> ContextStats {
+        self.0.stats()
+    }
+
+    /// Resets the cumulative statistics of this `Context`.
+    ///
+    /// Gauges such as the number of alive tasks or timers are not affected.
+    pub fn reset_stats(&self) {
+        self.0.reset_stats()
+    }
+
+    /// Posts the statistics of this `Context` as an element message from `element`.
+    ///
+    /// `ts-queue` posts them periodically when its `context-stats-interval` is set.
+    pub fn post_stats(&self, element: &impl IsA<gst::Element>) -> bool {
+        let structure = self.stats().to_structure(self.name());
+        element
+            .post_message(
+                gst::message::Element::builder(structure)
+                    .src(element)
+                    .build(),
+            )
+            .is_ok()
+    }
+
+    /// Returns the `Context`s which are currently alive.
+    pub fn list() -> Vec<Context> {
+        CONTEXTS
+            .lock()
+            .unwrap()
+            .values()
+            .filter_map(ContextWeak::upgrade)
+            .collect()
+    }
+
     /// Returns `true` if a `Context` is running on current thread.
     pub fn is_context_thread() -> bool {
         Scheduler::is_scheduler_thread()
@@ -294,7 +423,7 @@ impl From<Handle> for Context {
 
 #[cfg(test)]
 mod tests {
-    use futures::channel::mpsc;
+    use futures::channel::{mpsc, oneshot};
     use futures::lock::Mutex;
     use futures::prelude::*;
 
@@ -555,4 +684,91 @@ mod tests {
         // Due to throttling, `Delay` may be fired earlier
         assert!(elapsed + SLEEP_DURATION / 2 >= DELAY);
     }
+
+    #[test]
+    fn stats() {
+        gst::init().unwrap();
+
+        let context = Context::acquire("context_stats", SLEEP_DURATION).unwrap();
+        assert!(Context::list().contains(&context));
+
+        let join_handle = context.spawn(async {
+            crate::runtime::time::delay_for(DELAY).await;
+        });
+        futures::executor::block_on(join_handle).unwrap();
+
+        let stats = context.stats();
+        assert!(stats.tasks_spawned >= 1);
+        assert_eq!(stats.tasks_alive, 0);
+        assert!(stats.polls >= 2);
+        assert!(stats.iterations > 0);
+        assert!(stats.throttling > Duration::ZERO);
+        assert!((0.0..=1.0).contains(&stats.busy_ratio()));
+
+        let s = stats.to_structure(context.name());
+        assert_eq!(s.get::<&str>("context").unwrap(), "context_stats");
+        assert_eq!(s.get::<u64>("polls").unwrap(), stats.polls);
+
+        context.reset_stats();
+        let stats = context.stats();
+        assert!(stats.tasks_spawned >= 1);
+        assert!(stats.polls <= 1);
+    }
+
+    #[test]
+    fn pool() {
+        gst::init().unwrap();
+
+        let ctx_0 = Context::acquire("pool:context_pool:2", SLEEP_DURATION).unwrap();
+        assert_eq!(ctx_0.name(), "pool:context_pool-0");
+
+        // Tie: no tasks alive on ctx_0 and ctx_1 is not started, lowest index wins
+        let ctx = Context::acquire("pool:context_pool:2", SLEEP_DURATION).unwrap();
+        assert_eq!(ctx, ctx_0);
+
+        let (sender_0, receiver_0) = oneshot::channel::<()>();
+        let join_handle_0 = ctx_0.spawn(receiver_0);
+
+        // ctx_1 is now the least loaded
+        let ctx_1 = Context::acquire_from_pool("context_pool", 2, SLEEP_DURATION).unwrap();
+        assert_eq!(ctx_1.name(), "pool:context_pool-1");
+
+        let (sender_1, receiver_1) = oneshot::channel::<()>();
+        let join_handle_1 = ctx_1.spawn(receiver_1);
+        let (sender_2, receiver_2) = oneshot::channel::<()>();
+        let join_handle_2 = ctx_1.spawn(receiver_2);
+
+        // 1 task alive on ctx_0 vs 2 on ctx_1
+        let ctx = Context::acquire_from_pool("context_pool", 2, SLEEP_DURATION).unwrap();
+        assert_eq!(ctx, ctx_0);
+
+        drop(sender_1);
+        drop(sender_2);
+        futures::executor::block_on(join_handle_1)
+            .unwrap()
+            .unwrap_err();
+        futures::executor::block_on(join_handle_2)
+            .unwrap()
+            .unwrap_err();
+
+        // ctx_1 is back to no tasks alive
+        let ctx = Context::acquire_from_pool("context_pool", 2, SLEEP_DURATION).unwrap();
+        assert_eq!(ctx, ctx_1);
+
+        drop(sender_0);
+        futures::executor::block_on(join_handle_0)
+            .unwrap()
+            .unwrap_err();
+    }
+
+    #[test]
+    fn empty_pool() {
+        gst::init().unwrap();
+
+        let err = Context::acquire("pool:empty_pool:0", SLEEP_DURATION).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+        let err = Context::acquire_from_pool("empty_pool", 0, SLEEP_DURATION).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
 }
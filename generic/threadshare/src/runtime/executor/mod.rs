@@ -23,6 +23,9 @@
 pub mod async_wrapper;
 pub use async_wrapper::Async;
 
+mod blocking;
+pub use blocking::spawn_blocking;
+
 mod context;
 pub use context::{block_on, block_on_or_add_sub_task, yield_now, Context};
 
@@ -37,6 +40,10 @@ use reactor::{Reactor, Readable, ReadableOwned, Source, Writable, WritableOwned}
 mod scheduler;
 use scheduler::{Handle, HandleWeak, Scheduler};
 
+mod stats;
+pub use stats::ContextStats;
+use stats::StatsCounters;
+
 mod task;
 pub use task::{SubTaskOutput, TaskId};
 
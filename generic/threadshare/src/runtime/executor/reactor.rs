@@ -162,6 +162,16 @@ impl Reactor {
         self.ticker.load(Ordering::SeqCst)
     }
 
+    /// Returns the number of registered timers.
+    pub fn timer_count(&self) -> usize {
+        self.timers.len()
+    }
+
+    /// Returns the number of registered i/o sources.
+    pub fn io_source_count(&self) -> usize {
+        self.sources.len()
+    }
+
     pub fn half_max_throttling(&self) -> Duration {
         self.half_max_throttling
     }
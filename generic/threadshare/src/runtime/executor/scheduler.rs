@@ -22,7 +22,7 @@ use std::time::{Duration, Instant};
 use waker_fn::waker_fn;
 
 use super::task::{SubTaskOutput, TaskId, TaskQueue};
-use super::{CallOnDrop, JoinHandle, Reactor, Source};
+use super::{CallOnDrop, ContextStats, JoinHandle, Reactor, Source, StatsCounters};
 use crate::runtime::RUNTIME_CAT;
 
 thread_local! {
@@ -40,6 +40,7 @@ pub(super) struct Scheduler {
     cleanup_ops: ConcurrentQueue<CleanUpOps>,
     must_awake: Mutex<bool>,
     must_awake_cvar: Condvar,
+    stats: StatsCounters,
 }
 
 impl Scheduler {
@@ -111,6 +112,7 @@ impl Scheduler {
                 cleanup_ops: ConcurrentQueue::bounded(1000),
                 must_awake: Mutex::new(false),
                 must_awake_cvar: Condvar::new(),
+                stats: StatsCounters::default(),
             }));
 
             *cur_scheduler = Some(handle.downgrade());
@@ -196,10 +198,15 @@ impl Scheduler {
                     let _ = reactor.remove_io(&op.0);
                 }
 
-                reactor.react().ok()
+                let res = reactor.react().ok();
+                self.stats
+                    .set_reactor_load(reactor.timer_count(), reactor.io_source_count());
+
+                res
             });
 
             while let Ok(runnable) = self.tasks.pop_runnable() {
+                let poll_start = Instant::now();
                 panic::catch_unwind(|| runnable.run()).map_err(|err| {
                     gst::error!(
                         RUNTIME_CAT,
@@ -209,8 +216,11 @@ impl Scheduler {
 
                     err
                 })?;
+                self.stats.add_poll(poll_start.elapsed());
             }
 
+            let busy = last.elapsed();
+
             let mut must_awake = self.must_awake.lock().unwrap();
             loop {
                 if *must_awake {
@@ -230,6 +240,9 @@ impl Scheduler {
                     break;
                 }
             }
+            drop(must_awake);
+
+            self.stats.add_iteration(busy, last.elapsed() - busy);
         }
     }
 
@@ -377,6 +390,17 @@ impl Handle {
         self.0.scheduler.max_throttling
     }
 
+    pub fn stats(&self) -> ContextStats {
+        let scheduler = &self.0.scheduler;
+        scheduler
+            .stats
+            .snapshot(scheduler.tasks.spawned(), scheduler.tasks.alive())
+    }
+
+    pub fn reset_stats(&self) {
+        self.0.scheduler.stats.reset();
+    }
+
     /// Executes the provided function relatively to this [`Scheduler`]'s [`Reactor`].
     ///
     /// Usefull to initialze i/o sources and timers from outside
@@ -0,0 +1,136 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Counters updated by a `Scheduler` thread while it is running.
+///
+/// Cumulative counters can be reset, gauges reflect the state
+/// as of the last `Scheduler` loop iteration.
+#[derive(Debug, Default)]
+pub(super) struct StatsCounters {
+    iterations: AtomicU64,
+    polls: AtomicU64,
+    busy_ns: AtomicU64,
+    throttling_ns: AtomicU64,
+    max_poll_ns: AtomicU64,
+    timers: AtomicUsize,
+    io_sources: AtomicUsize,
+}
+
+impl StatsCounters {
+    pub fn add_iteration(&self, busy: Duration, throttling: Duration) {
+        self.iterations.fetch_add(1, Ordering::Relaxed);
+        self.busy_ns
+            .fetch_add(busy.as_nanos() as u64, Ordering::Relaxed);
+        self.throttling_ns
+            .fetch_add(throttling.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub fn add_poll(&self, duration: Duration) {
+        self.polls.fetch_add(1, Ordering::Relaxed);
+        self.max_poll_ns
+            .fetch_max(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub fn set_reactor_load(&self, timers: usize, io_sources: usize) {
+        self.timers.store(timers, Ordering::Relaxed);
+        self.io_sources.store(io_sources, Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        self.iterations.store(0, Ordering::Relaxed);
+        self.polls.store(0, Ordering::Relaxed);
+        self.busy_ns.store(0, Ordering::Relaxed);
+        self.throttling_ns.store(0, Ordering::Relaxed);
+        self.max_poll_ns.store(0, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self, tasks_spawned: u64, tasks_alive: usize) -> ContextStats {
+        ContextStats {
+            tasks_spawned,
+            tasks_alive,
+            iterations: self.iterations.load(Ordering::Relaxed),
+            polls: self.polls.load(Ordering::Relaxed),
+            timers: self.timers.load(Ordering::Relaxed),
+            io_sources: self.io_sources.load(Ordering::Relaxed),
+            busy: Duration::from_nanos(self.busy_ns.load(Ordering::Relaxed)),
+            throttling: Duration::from_nanos(self.throttling_ns.load(Ordering::Relaxed)),
+            max_poll_duration: Duration::from_nanos(self.max_poll_ns.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// A snapshot of the statistics of a [`Context`].
+///
+/// Use these figures to size the `context-wait` of the elements and the
+/// number of `Context`s: a `Context` with a high [`busy_ratio`] or long
+/// [`max_poll_duration`] is overloaded and its elements would benefit from
+/// being spread over more `Context`s.
+///
+/// [`Context`]: struct.Context.html
+/// [`busy_ratio`]: struct.ContextStats.html#method.busy_ratio
+/// [`max_poll_duration`]: struct.ContextStats.html#structfield.max_poll_duration
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ContextStats {
+    /// Number of tasks spawned on the `Context` since it was started.
+    pub tasks_spawned: u64,
+    /// Number of tasks currently alive on the `Context`.
+    pub tasks_alive: usize,
+    /// Number of `Scheduler` loop iterations.
+    pub iterations: u64,
+    /// Number of task polls.
+    pub polls: u64,
+    /// Number of timers registered as of the last iteration.
+    pub timers: usize,
+    /// Number of i/o sources registered as of the last iteration.
+    pub io_sources: usize,
+    /// Time spent processing i/o, timers and tasks.
+    pub busy: Duration,
+    /// Time spent waiting because of `context-wait` throttling.
+    pub throttling: Duration,
+    /// Longest duration of a single task poll.
+    pub max_poll_duration: Duration,
+}
+
+impl ContextStats {
+    /// Returns the ratio of the time spent processing vs the elapsed time
+    /// in the `Scheduler` loop, in the range `[0.0, 1.0]`.
+    pub fn busy_ratio(&self) -> f64 {
+        let total = self.busy + self.throttling;
+        if total.is_zero() {
+            0.0
+        } else {
+            self.busy.as_secs_f64() / total.as_secs_f64()
+        }
+    }
+
+    /// Returns the statistics as a `gst::Structure`
+    /// suitable for element messages.
+    pub fn to_structure(&self, context_name: &str) -> gst::Structure {
+        gst::Structure::builder("ts-context-stats")
+            .field("context", context_name)
+            .field("tasks-spawned", self.tasks_spawned)
+            .field("tasks-alive", self.tasks_alive as u64)
+            .field("iterations", self.iterations)
+            .field("polls", self.polls)
+            .field("timers", self.timers as u64)
+            .field("io-sources", self.io_sources as u64)
+            .field(
+                "busy",
+                gst::ClockTime::from_nseconds(self.busy.as_nanos() as u64),
+            )
+            .field(
+                "throttling",
+                gst::ClockTime::from_nseconds(self.throttling.as_nanos() as u64),
+            )
+            .field("busy-ratio", self.busy_ratio())
+            .field(
+                "max-poll-duration",
+                gst::ClockTime::from_nseconds(self.max_poll_duration.as_nanos() as u64),
+            )
+            .build()
+    }
+}
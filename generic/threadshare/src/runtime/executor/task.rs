@@ -17,6 +17,7 @@ use std::cell::Cell;
 use std::collections::VecDeque;
 use std::fmt;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::task::Poll;
 
@@ -114,6 +115,7 @@ pub(super) struct TaskQueue {
     // which can harm debugging. If this is not acceptable, I'll switch back to using
     // a HashMap.
     tasks: Arc<Mutex<Slab<Task>>>,
+    spawned: AtomicU64,
     context_name: Arc<str>,
 }
 
@@ -122,6 +124,7 @@ impl TaskQueue {
         TaskQueue {
             runnables: Arc::new(ConcurrentQueue::unbounded()),
             tasks: Arc::new(Mutex::new(Slab::new())),
+            spawned: AtomicU64::new(0),
             context_name,
         }
     }
@@ -178,6 +181,7 @@ impl TaskQueue {
         });
         tasks.insert(Task::new(task_id));
         drop(tasks);
+        self.spawned.fetch_add(1, Ordering::Relaxed);
 
         runnable.schedule();
 
@@ -230,12 +234,23 @@ impl TaskQueue {
         });
         tasks.insert(Task::new(task_id));
         drop(tasks);
+        self.spawned.fetch_add(1, Ordering::Relaxed);
 
         runnable.schedule();
 
         task
     }
 
+    /// Returns the number of tasks added since this `TaskQueue` was created.
+    pub fn spawned(&self) -> u64 {
+        self.spawned.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of tasks which are still alive.
+    pub fn alive(&self) -> usize {
+        self.tasks.lock().unwrap().len()
+    }
+
     pub fn pop_runnable(&self) -> Result<Runnable, concurrent_queue::PopError> {
         self.runnables.pop()
     }
@@ -31,7 +31,9 @@
 //! [`PadSink`]: pad/struct.PadSink.html
 
 pub mod executor;
-pub use executor::{Async, Context, JoinHandle, SubTaskOutput, Timer};
+pub use executor::{
+    spawn_blocking, Async, Context, ContextStats, JoinHandle, SubTaskOutput, Timer,
+};
 
 pub mod pad;
 pub use pad::{PadSink, PadSinkRef, PadSinkWeak, PadSrc, PadSrcRef, PadSrcWeak};
@@ -45,7 +47,7 @@ pub mod prelude {
 }
 
 pub mod time;
-pub use time::{delay_for, delay_for_at_least};
+pub use time::{delay_for, delay_for_at_least, delay_until_running_time};
 
 use once_cell::sync::Lazy;
 
@@ -26,7 +26,10 @@
 //! The diagram below shows how the [`PadSrc`] & [`PadSink`] and the related `struct`s integrate in
 //! `ts` `Element`s.
 //!
-//! Note: [`PadSrc`] & [`PadSink`] only support `gst::PadMode::Push` at the moment.
+//! [`PadSrc`] & [`PadSink`] operate in `gst::PadMode::Push` by default. A [`PadSrc`] can also be
+//! activated in `gst::PadMode::Pull` if its handler accepts the mode in
+//! [`src_activatemode`] and implements [`src_getrange`], see the latter for details. [`PadSink`]
+//! only supports `gst::PadMode::Push`: pulling from upstream is not implemented.
 //!
 //! ```text
 //!    ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓          ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -67,6 +70,8 @@
 //! [`PadSink`]: struct.PadSink.html
 //! [`PadSrc`]: struct.PadSrc.html
 //! [`Context`]: ../executor/struct.Context.html
+//! [`src_activatemode`]: trait.PadSrcHandler.html#method.src_activatemode
+//! [`src_getrange`]: trait.PadSrcHandler.html#method.src_getrange
 
 use futures::future;
 use futures::future::BoxFuture;
@@ -78,7 +83,7 @@ use gst::{FlowError, FlowSuccess};
 
 use std::marker::PhantomData;
 use std::ops::Deref;
-use std::sync::{Arc, Weak};
+use std::sync::{Arc, Mutex, Weak};
 
 use super::executor::{block_on_or_add_sub_task, Context};
 use super::RUNTIME_CAT;
@@ -150,17 +155,74 @@ pub trait PadSrcHandler: Clone + Send + Sync + 'static {
             })
     }
 
+    /// Handles the activation of the [`PadSrc`] in `mode`.
+    ///
+    /// The default implementation rejects `gst::PadMode::Pull`, so that peers fall back to
+    /// `gst::PadMode::Push`. Handlers implementing [`src_getrange`] must accept it.
+    ///
+    /// [`PadSrc`]: struct.PadSrc.html
+    /// [`src_getrange`]: trait.PadSrcHandler.html#method.src_getrange
     fn src_activatemode(
         &self,
-        _pad: &PadSrcRef,
+        pad: &PadSrcRef,
         _imp: &Self::ElementImpl,
         _element: &gst::Element,
-        _mode: gst::PadMode,
-        _active: bool,
+        mode: gst::PadMode,
+        active: bool,
     ) -> Result<(), gst::LoggableError> {
+        if mode == gst::PadMode::Pull && active {
+            gst::error!(RUNTIME_CAT, obj: pad.gst_pad(), "Pull mode not supported by PadSrc");
+            return Err(gst::loggable_error!(
+                RUNTIME_CAT,
+                "Pull mode not supported by PadSrc"
+            ));
+        }
+
         Ok(())
     }
 
+    /// Produces a `gst::Buffer` of `length` bytes at `offset` in `gst::PadMode::Pull`.
+    ///
+    /// The returned `Future` is spawned on the [`Context`] set with [`PadSrc::set_context`],
+    /// so it can await IO & time related `Future`s, while the thread of the downstream element
+    /// which pulls the data waits for the result by means of [`block_on_or_add_sub_task`].
+    /// The [`PadSrc`] returns `FlowError::Flushing` if no [`Context`] is set. Pulling from a
+    /// [`Context`] task is not supported since it can't be blocked and fails with
+    /// `FlowError::Error`.
+    ///
+    /// In `gst::PadMode::Pull`, the dataflow is driven by downstream, so the [`Task`] of the
+    /// element must not push on the [`PadSrc`]: implementations usually check the mode in
+    /// [`src_activatemode`] and don't start their [`Task`] (or keep it paused) while activated
+    /// in pull mode. While flushing, the `gst::Pad` returns `FlowError::Flushing` without
+    /// calling this handler, so this doesn't need to be synchronized with the [`Task`] flush
+    /// transitions. If downstream provided a `gst::Buffer` to fill, the data is copied into it.
+    ///
+    /// The default implementation returns `FlowError::NotSupported`.
+    ///
+    /// [`block_on_or_add_sub_task`]: ../executor/fn.block_on_or_add_sub_task.html
+    /// [`Context`]: ../executor/struct.Context.html
+    /// [`PadSrc`]: struct.PadSrc.html
+    /// [`PadSrc::set_context`]: struct.PadSrc.html#method.set_context
+    /// [`Task`]: ../task/struct.Task.html
+    /// [`src_activatemode`]: trait.PadSrcHandler.html#method.src_activatemode
+    fn src_getrange(
+        &self,
+        pad: &PadSrcRef,
+        _imp: &Self::ElementImpl,
+        _element: &gst::Element,
+        offset: u64,
+        length: u32,
+    ) -> BoxFuture<'static, Result<gst::Buffer, FlowError>> {
+        gst::error!(
+            RUNTIME_CAT,
+            obj: pad.gst_pad(),
+            "getrange({}, {}) not supported by PadSrc",
+            offset,
+            length
+        );
+        future::err(FlowError::NotSupported).boxed()
+    }
+
     fn src_event(
         &self,
         pad: &PadSrcRef,
@@ -206,6 +268,7 @@ pub trait PadSrcHandler: Clone + Send + Sync + 'static {
 #[derive(Debug)]
 pub struct PadSrcInner {
     gst_pad: gst::Pad,
+    context: Mutex<Option<Context>>,
 }
 
 impl PadSrcInner {
@@ -214,7 +277,10 @@ impl PadSrcInner {
             panic!("Wrong pad direction for PadSrc");
         }
 
-        PadSrcInner { gst_pad }
+        PadSrcInner {
+            gst_pad,
+            context: Mutex::new(None),
+        }
     }
 
     pub fn gst_pad(&self) -> &gst::Pad {
@@ -334,14 +400,6 @@ impl<'a> PadSrcRef<'a> {
         // in the default `activatemode` handling
         gst::log!(RUNTIME_CAT, obj: self.gst_pad(), "ActivateMode {:?}, {}", mode, active);
 
-        if mode == gst::PadMode::Pull {
-            gst::error!(RUNTIME_CAT, obj: self.gst_pad(), "Pull mode not supported by PadSrc");
-            return Err(gst::loggable_error!(
-                RUNTIME_CAT,
-                "Pull mode not supported by PadSrc"
-            ));
-        }
-
         Ok(())
     }
 }
@@ -385,6 +443,18 @@ impl PadSrc {
         self.0.gst_pad().check_reconfigure()
     }
 
+    /// Sets the [`Context`] on which [`src_getrange`] `Future`s are executed.
+    ///
+    /// Elements supporting `gst::PadMode::Pull` usually set it along with preparing their
+    /// [`Task`] and unset it when unpreparing.
+    ///
+    /// [`Context`]: ../executor/struct.Context.html
+    /// [`src_getrange`]: trait.PadSrcHandler.html#method.src_getrange
+    /// [`Task`]: ../task/struct.Task.html
+    pub fn set_context(&self, context: Option<Context>) {
+        *self.0.context.lock().unwrap() = context;
+    }
+
     fn init_pad_functions<H: PadSrcHandler>(&self, handler: H) {
         // FIXME: Do this better
         unsafe {
@@ -467,6 +537,58 @@ impl PadSrc {
                     )
                 });
 
+            let handler_clone = handler.clone();
+            let inner_arc = Arc::clone(&self.0);
+            self.gst_pad().set_getrange_function(
+                move |gst_pad, parent, offset, _buffer, length| {
+                    let handler = handler_clone.clone();
+                    let inner_arc = inner_arc.clone();
+                    H::ElementImpl::catch_panic_pad_function(
+                        parent,
+                        || Err(FlowError::Error),
+                        move |imp, element| {
+                            if Context::current_task().is_some() {
+                                gst::error!(
+                                    RUNTIME_CAT,
+                                    obj: gst_pad,
+                                    "Can't block a Context task on PadSrc getrange"
+                                );
+                                return Err(FlowError::Error);
+                            }
+
+                            let context = match *inner_arc.context.lock().unwrap() {
+                                Some(ref context) => context.clone(),
+                                None => {
+                                    gst::debug!(
+                                        RUNTIME_CAT,
+                                        obj: gst_pad,
+                                        "No Context for PadSrc getrange"
+                                    );
+                                    return Err(FlowError::Flushing);
+                                }
+                            };
+
+                            let this_ref = PadSrcRef::new(inner_arc);
+                            let getrange_fut = handler.src_getrange(
+                                &this_ref,
+                                imp,
+                                element.dynamic_cast_ref::<gst::Element>().unwrap(),
+                                offset,
+                                length,
+                            );
+                            let join_handle = context.spawn(getrange_fut);
+
+                            // Not in a Context task: `block_on_or_add_sub_task` blocks on the
+                            // `JoinHandle` and always returns its output
+                            block_on_or_add_sub_task(join_handle)
+                                .unwrap_or(Ok(Err(FlowError::Error)))
+                                .unwrap_or(Err(FlowError::Flushing))
+                                .map(gst::PadGetRangeSuccess::NewBuffer)
+                        },
+                    )
+                },
+            );
+
             let inner_arc = Arc::clone(&self.0);
             self.gst_pad()
             .set_query_function(move |_gst_pad, parent, query| {
@@ -506,6 +628,9 @@ impl Drop for PadSrc {
                 .set_event_function(move |_gst_pad, _parent, _event| false);
             self.gst_pad()
                 .set_event_full_function(move |_gst_pad, _parent, _event| Err(FlowError::Flushing));
+            self.gst_pad().set_getrange_function(
+                move |_gst_pad, _parent, _offset, _buffer, _length| Err(FlowError::Flushing),
+            );
             self.gst_pad()
                 .set_query_function(move |_gst_pad, _parent, _query| false);
         }
@@ -748,6 +873,7 @@ impl<'a> PadSinkRef<'a> {
         // in the default `activatemode` handling
         gst::log!(RUNTIME_CAT, obj: self.gst_pad(), "ActivateMode {:?}, {}", mode, active);
 
+        // Pulling from upstream would require a dedicated Task, which is not implemented
         if mode == gst::PadMode::Pull {
             gst::error!(RUNTIME_CAT, obj: self.gst_pad(), "Pull mode not supported by PadSink");
             return Err(gst::loggable_error!(
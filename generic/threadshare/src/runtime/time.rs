@@ -4,7 +4,9 @@
 
 //! Wrappers for the underlying runtime specific time related Futures.
 
-use std::time::Duration;
+use gst::prelude::*;
+
+use std::time::{Duration, Instant};
 
 use super::executor::Timer;
 
@@ -40,3 +42,23 @@ pub fn delay_for_at_least(delay: Duration) -> Timer {
 pub fn interval(interval: Duration) -> Timer {
     Timer::interval(interval)
 }
+
+/// Builds a `Timer` which fires when the `element`'s clock reaches `running_time`.
+///
+/// Returns `None` if `running_time` is already reached or can't be determined,
+/// e.g. because the `element` has no clock yet.
+///
+/// This must be called from within the target runtime environment.
+pub fn delay_until_running_time(
+    element: &impl IsA<gst::Element>,
+    running_time: impl Into<Option<gst::ClockTime>>,
+) -> Option<Timer> {
+    let now = element.current_running_time();
+
+    match running_time.into().opt_checked_sub(now) {
+        Ok(Some(delay)) if !delay.is_zero() => {
+            Some(Timer::at(Instant::now() + Duration::from(delay)))
+        }
+        _ => None,
+    }
+}
@@ -19,6 +19,7 @@
 // SPDX-License-Identifier: LGPL-2.1-or-later
 
 use futures::future::BoxFuture;
+use futures::prelude::*;
 
 use gst::glib;
 use gst::prelude::*;
@@ -30,7 +31,7 @@ use gio::prelude::*;
 use std::error;
 use std::fmt;
 use std::io;
-use std::net::UdpSocket;
+use std::net::{IpAddr, UdpSocket};
 
 use crate::runtime::Async;
 
@@ -55,13 +56,28 @@ pub trait SocketRead: Send + Unpin {
         &'buf mut self,
         buffer: &'buf mut [u8],
     ) -> BoxFuture<'buf, io::Result<(usize, Option<std::net::SocketAddr>)>>;
+
+    /// Reads up to `buffers.len()` packets at once, returning the size and
+    /// origin of each packet read, in the order of `buffers`.
+    ///
+    /// The default implementation reads a single packet.
+    fn read_batch<'buf>(
+        &'buf mut self,
+        buffers: &'buf mut [&'buf mut [u8]],
+    ) -> BoxFuture<'buf, io::Result<Vec<(usize, Option<std::net::SocketAddr>)>>> {
+        async move {
+            let res = self.read(&mut *buffers[0]).await?;
+            Ok(vec![res])
+        }
+        .boxed()
+    }
 }
 
 pub struct Socket<T: SocketRead> {
     element: gst::Element,
     buffer_pool: gst::BufferPool,
     reader: T,
-    mapped_buffer: Option<gst::MappedBuffer<gst::buffer::Writable>>,
+    mapped_buffers: Vec<gst::MappedBuffer<gst::buffer::Writable>>,
     clock: Option<gst::Clock>,
     base_time: Option<gst::ClockTime>,
 }
@@ -88,7 +104,7 @@ impl<T: SocketRead> Socket<T> {
             buffer_pool,
             element,
             reader,
-            mapped_buffer: None,
+            mapped_buffers: Vec::new(),
             clock: None,
             base_time: None,
         })
@@ -100,6 +116,82 @@ impl<T: SocketRead> Socket<T> {
     }
 }
 
+/// Framing of the packets exchanged over a TCP stream.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, glib::Enum)]
+#[repr(u32)]
+#[enum_type(name = "GstTsTcpFraming")]
+pub enum TcpFraming {
+    #[enum_value(name = "Raw: unframed byte stream", nick = "raw")]
+    Raw = 0,
+    #[enum_value(
+        name = "RFC 4571: packets prefixed with their 16 bit length, e.g. RTP over TCP",
+        nick = "rfc4571"
+    )]
+    Rfc4571 = 1,
+}
+
+impl TcpFraming {
+    /// Maximum size of a packet which can be framed.
+    pub fn max_size(self) -> Option<usize> {
+        match self {
+            TcpFraming::Raw => None,
+            TcpFraming::Rfc4571 => Some(u16::MAX as usize),
+        }
+    }
+
+    /// Reads the next packet from `reader`.
+    ///
+    /// In `Raw` mode, at most `blocksize` bytes are read at once.
+    /// Returns `None` when the peer closed the stream.
+    pub async fn read_frame<R: AsyncRead + Unpin>(
+        self,
+        reader: &mut R,
+        blocksize: usize,
+    ) -> io::Result<Option<Vec<u8>>> {
+        match self {
+            TcpFraming::Raw => {
+                let mut data = vec![0; blocksize];
+                let len = reader.read(&mut data).await?;
+                if len == 0 {
+                    return Ok(None);
+                }
+                data.truncate(len);
+
+                Ok(Some(data))
+            }
+            TcpFraming::Rfc4571 => {
+                let mut len = [0u8; 2];
+                match reader.read_exact(&mut len).await {
+                    Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+                    res => res?,
+                }
+
+                let mut data = vec![0; u16::from_be_bytes(len) as usize];
+                reader.read_exact(&mut data).await?;
+
+                Ok(Some(data))
+            }
+        }
+    }
+
+    /// Writes `data` as one packet to `writer`.
+    ///
+    /// The caller must make sure `data` doesn't exceed `max_size()`.
+    pub async fn write_frame<W: AsyncWrite + Unpin>(
+        self,
+        writer: &mut W,
+        data: &[u8],
+    ) -> io::Result<()> {
+        if self == TcpFraming::Rfc4571 {
+            let len = u16::try_from(data.len())
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Packet too large"))?;
+            writer.write_all(&len.to_be_bytes()).await?;
+        }
+
+        writer.write_all(data).await
+    }
+}
+
 #[derive(Debug)]
 pub enum SocketError {
     Gst(gst::FlowError),
@@ -119,58 +211,82 @@ impl fmt::Display for SocketError {
 
 pub type SocketStreamItem = Result<(gst::Buffer, Option<std::net::SocketAddr>), SocketError>;
 
-impl<T: SocketRead> Socket<T> {
-    // Can't implement this as a Stream trait because we end up using things like
-    // tokio::net::UdpSocket which don't implement pollable functions.
-    #[allow(clippy::should_implement_trait)]
-    pub async fn next(&mut self) -> Option<SocketStreamItem> {
-        gst::log!(SOCKET_CAT, obj: &self.element, "Trying to read data");
+pub type SocketListItem = Result<Vec<(gst::Buffer, Option<std::net::SocketAddr>)>, SocketError>;
 
-        if self.mapped_buffer.is_none() {
+impl<T: SocketRead> Socket<T> {
+    fn acquire_buffers(&mut self, count: usize) -> Result<(), SocketError> {
+        while self.mapped_buffers.len() < count {
             match self.buffer_pool.acquire_buffer(None) {
                 Ok(buffer) => {
-                    self.mapped_buffer = Some(buffer.into_mapped_buffer_writable().unwrap());
+                    self.mapped_buffers
+                        .push(buffer.into_mapped_buffer_writable().unwrap());
                 }
                 Err(err) => {
                     gst::debug!(SOCKET_CAT, obj: &self.element, "Failed to acquire buffer {:?}", err);
-                    return Some(Err(SocketError::Gst(err)));
+                    return Err(SocketError::Gst(err));
                 }
             }
         }
 
+        Ok(())
+    }
+
+    fn dts(&self, len: usize) -> Option<gst::ClockTime> {
+        if T::DO_TIMESTAMP {
+            let time = self.clock.as_ref().unwrap().time();
+            let running_time = time.opt_checked_sub(self.base_time).ok().flatten();
+            // FIXME maybe we should check if running_time.is_none
+            // so as to display another message
+            gst::debug!(
+                SOCKET_CAT,
+                obj: &self.element,
+                "Read {} bytes at {} (clock {})",
+                len,
+                running_time.display(),
+                time.display(),
+            );
+            running_time
+        } else {
+            gst::debug!(SOCKET_CAT, obj: &self.element, "Read {} bytes", len);
+            gst::ClockTime::NONE
+        }
+    }
+
+    fn finish_buffer(
+        mapped_buffer: gst::MappedBuffer<gst::buffer::Writable>,
+        len: usize,
+        dts: Option<gst::ClockTime>,
+    ) -> gst::Buffer {
+        let mut buffer = mapped_buffer.into_buffer();
+        {
+            let buffer = buffer.get_mut().unwrap();
+            if len < buffer.size() {
+                buffer.set_size(len);
+            }
+            buffer.set_dts(dts);
+        }
+
+        buffer
+    }
+
+    // Can't implement this as a Stream trait because we end up using things like
+    // tokio::net::UdpSocket which don't implement pollable functions.
+    #[allow(clippy::should_implement_trait)]
+    pub async fn next(&mut self) -> Option<SocketStreamItem> {
+        gst::log!(SOCKET_CAT, obj: &self.element, "Trying to read data");
+
+        if let Err(err) = self.acquire_buffers(1) {
+            return Some(Err(err));
+        }
+
         match self
             .reader
-            .read(self.mapped_buffer.as_mut().unwrap().as_mut_slice())
+            .read(self.mapped_buffers[0].as_mut_slice())
             .await
         {
             Ok((len, saddr)) => {
-                let dts = if T::DO_TIMESTAMP {
-                    let time = self.clock.as_ref().unwrap().time();
-                    let running_time = time.opt_checked_sub(self.base_time).ok().flatten();
-                    // FIXME maybe we should check if running_time.is_none
-                    // so as to display another message
-                    gst::debug!(
-                        SOCKET_CAT,
-                        obj: &self.element,
-                        "Read {} bytes at {} (clock {})",
-                        len,
-                        running_time.display(),
-                        time.display(),
-                    );
-                    running_time
-                } else {
-                    gst::debug!(SOCKET_CAT, obj: &self.element, "Read {} bytes", len);
-                    gst::ClockTime::NONE
-                };
-
-                let mut buffer = self.mapped_buffer.take().unwrap().into_buffer();
-                {
-                    let buffer = buffer.get_mut().unwrap();
-                    if len < buffer.size() {
-                        buffer.set_size(len);
-                    }
-                    buffer.set_dts(dts);
-                }
+                let dts = self.dts(len);
+                let buffer = Self::finish_buffer(self.mapped_buffers.remove(0), len, dts);
 
                 Some(Ok((buffer, saddr)))
             }
@@ -181,6 +297,55 @@ impl<T: SocketRead> Socket<T> {
             }
         }
     }
+
+    /// Reads up to `max_count` packets at once.
+    ///
+    /// All the buffers read at once share the same DTS.
+    pub async fn next_list(&mut self, max_count: usize) -> Option<SocketListItem> {
+        gst::log!(
+            SOCKET_CAT,
+            obj: &self.element,
+            "Trying to read up to {} packets",
+            max_count
+        );
+
+        if let Err(err) = self.acquire_buffers(max_count.max(1)) {
+            return Some(Err(err));
+        }
+
+        let res = {
+            let mut slices = self
+                .mapped_buffers
+                .iter_mut()
+                .map(|mapped_buffer| mapped_buffer.as_mut_slice())
+                .collect::<Vec<_>>();
+
+            self.reader.read_batch(&mut slices).await
+        };
+
+        match res {
+            Ok(packets) => {
+                let count = packets.len();
+                let total = packets.iter().map(|(len, _)| *len).sum();
+                let dts = self.dts(total);
+
+                let buffers = packets
+                    .into_iter()
+                    .zip(self.mapped_buffers.drain(..count))
+                    .map(|((len, saddr), mapped_buffer)| {
+                        (Self::finish_buffer(mapped_buffer, len, dts), saddr)
+                    })
+                    .collect();
+
+                Some(Ok(buffers))
+            }
+            Err(err) => {
+                gst::debug!(SOCKET_CAT, obj: &self.element, "Read error {:?}", err);
+
+                Some(Err(SocketError::Io(err)))
+            }
+        }
+    }
 }
 
 impl<T: SocketRead> Drop for Socket<T> {
@@ -244,6 +409,210 @@ impl GioSocketWrapper {
         Ok(())
     }
 
+    /// Sets the hop limit of outgoing multicast packets, for IPv4 and IPv6 sockets alike.
+    pub fn set_multicast_hops(&self, hops: u32) -> Result<(), glib::Error> {
+        self.as_socket().set_multicast_ttl(hops);
+
+        Ok(())
+    }
+
+    /// Sets the hop limit of outgoing unicast packets, for IPv4 and IPv6 sockets alike.
+    pub fn set_unicast_hops(&self, hops: u32) -> Result<(), glib::Error> {
+        self.as_socket().set_ttl(hops);
+
+        Ok(())
+    }
+
+    /// Joins multicast `group` on `iface`, or on the default interface if `None`.
+    ///
+    /// If `source` is set, only the packets sent by `source` are received
+    /// (source-specific multicast).
+    pub fn join_multicast_group(
+        &self,
+        group: IpAddr,
+        source: Option<IpAddr>,
+        iface: Option<&str>,
+    ) -> Result<(), glib::Error> {
+        match source {
+            None => {
+                self.as_socket()
+                    .join_multicast_group(&gio::InetAddress::from(group), false, iface)
+            }
+            Some(source) => self.set_source_membership(group, source, iface, true),
+        }
+    }
+
+    /// Leaves a multicast group previously joined with `join_multicast_group()`.
+    pub fn leave_multicast_group(
+        &self,
+        group: IpAddr,
+        source: Option<IpAddr>,
+        iface: Option<&str>,
+    ) -> Result<(), glib::Error> {
+        match source {
+            None => {
+                self.as_socket()
+                    .leave_multicast_group(&gio::InetAddress::from(group), false, iface)
+            }
+            Some(source) => self.set_source_membership(group, source, iface, false),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn set_source_membership(
+        &self,
+        group: IpAddr,
+        source: IpAddr,
+        iface: Option<&str>,
+        join: bool,
+    ) -> Result<(), glib::Error> {
+        use std::mem;
+
+        // From linux/in.h, protocol independent for IPv4 and IPv6
+        const MCAST_JOIN_SOURCE_GROUP: libc::c_int = 46;
+        const MCAST_LEAVE_SOURCE_GROUP: libc::c_int = 47;
+
+        #[repr(C)]
+        struct GroupSourceReq {
+            gsr_interface: u32,
+            gsr_group: libc::sockaddr_storage,
+            gsr_source: libc::sockaddr_storage,
+        }
+
+        if group.is_ipv4() != source.is_ipv4() {
+            return Err(glib::Error::new(
+                gio::IOErrorEnum::InvalidArgument,
+                &format!(
+                    "Multicast source {} and group {} are of different families",
+                    source, group
+                ),
+            ));
+        }
+
+        let sockaddr_storage = |addr: IpAddr| {
+            let addr = socket2::SockAddr::from(std::net::SocketAddr::new(addr, 0));
+            // SAFETY: all-zeroes is a valid sockaddr_storage and `addr` fits in it
+            unsafe {
+                let mut storage = mem::zeroed::<libc::sockaddr_storage>();
+                std::ptr::copy_nonoverlapping(
+                    addr.as_ptr() as *const u8,
+                    &mut storage as *mut libc::sockaddr_storage as *mut u8,
+                    addr.len() as usize,
+                );
+                storage
+            }
+        };
+
+        let req = GroupSourceReq {
+            gsr_interface: iface.map(iface_index).transpose()?.unwrap_or(0),
+            gsr_group: sockaddr_storage(group),
+            gsr_source: sockaddr_storage(source),
+        };
+
+        let level = if group.is_ipv4() {
+            libc::IPPROTO_IP
+        } else {
+            libc::IPPROTO_IPV6
+        };
+        let optname = if join {
+            MCAST_JOIN_SOURCE_GROUP
+        } else {
+            MCAST_LEAVE_SOURCE_GROUP
+        };
+
+        // SAFETY: `req` has the layout of `struct group_source_req`
+        let res = unsafe {
+            libc::setsockopt(
+                gio::ffi::g_socket_get_fd(self.socket),
+                level,
+                optname,
+                &req as *const GroupSourceReq as *const libc::c_void,
+                mem::size_of::<GroupSourceReq>() as libc::socklen_t,
+            )
+        };
+        if res < 0 {
+            return Err(io_to_glib_error(
+                io::Error::last_os_error(),
+                "Failed to change source-specific multicast membership",
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn set_source_membership(
+        &self,
+        _group: IpAddr,
+        _source: IpAddr,
+        _iface: Option<&str>,
+        _join: bool,
+    ) -> Result<(), glib::Error> {
+        Err(glib::Error::new(
+            gio::IOErrorEnum::NotSupported,
+            "Source-specific multicast is not supported on this platform",
+        ))
+    }
+
+    /// Sends outgoing multicast packets through the interface called `iface`.
+    #[cfg(target_os = "linux")]
+    pub fn set_multicast_iface(&self, iface: &str) -> Result<(), glib::Error> {
+        let index = iface_index(iface)?;
+        let socket = self.as_socket();
+
+        if socket.family() == gio::SocketFamily::Ipv6 {
+            return socket.set_option(libc::IPPROTO_IPV6, libc::IPV6_MULTICAST_IF, index as i32);
+        }
+
+        let req = libc::ip_mreqn {
+            imr_multiaddr: libc::in_addr { s_addr: 0 },
+            imr_address: libc::in_addr { s_addr: 0 },
+            imr_ifindex: index as libc::c_int,
+        };
+
+        // SAFETY: IP_MULTICAST_IF accepts a `struct ip_mreqn` on Linux
+        let res = unsafe {
+            libc::setsockopt(
+                gio::ffi::g_socket_get_fd(self.socket),
+                libc::IPPROTO_IP,
+                libc::IP_MULTICAST_IF,
+                &req as *const libc::ip_mreqn as *const libc::c_void,
+                std::mem::size_of::<libc::ip_mreqn>() as libc::socklen_t,
+            )
+        };
+        if res < 0 {
+            return Err(io_to_glib_error(
+                io::Error::last_os_error(),
+                "Failed to set multicast interface",
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    pub fn set_multicast_iface(&self, iface: &str) -> Result<(), glib::Error> {
+        let socket = self.as_socket();
+
+        if socket.family() == gio::SocketFamily::Ipv6 {
+            let index = iface_index(iface)?;
+            return socket.set_option(libc::IPPROTO_IPV6, libc::IPV6_MULTICAST_IF, index as i32);
+        }
+
+        Err(glib::Error::new(
+            gio::IOErrorEnum::NotSupported,
+            "Selecting the IPv4 multicast interface by name is not supported on this platform",
+        ))
+    }
+
+    #[cfg(not(unix))]
+    pub fn set_multicast_iface(&self, _iface: &str) -> Result<(), glib::Error> {
+        Err(glib::Error::new(
+            gio::IOErrorEnum::NotSupported,
+            "Selecting the multicast interface is not supported on this platform",
+        ))
+    }
+
     #[cfg(unix)]
     pub fn get<T: FromRawFd>(&self) -> T {
         unsafe { FromRawFd::from_raw_fd(libc::dup(gio::ffi::g_socket_get_fd(self.socket))) }
@@ -259,6 +628,53 @@ impl GioSocketWrapper {
     }
 }
 
+/// Parses the comma separated `multicast-source` list, `None` meaning any source.
+pub fn parse_multicast_sources(
+    sources: Option<&str>,
+) -> Result<Vec<Option<IpAddr>>, gst::ErrorMessage> {
+    let sources = match sources {
+        Some(sources) if !sources.trim().is_empty() => sources,
+        _ => return Ok(vec![None]),
+    };
+
+    sources
+        .split(',')
+        .map(|source| {
+            let source = source.trim();
+            source.parse().map(Some).map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::Settings,
+                    ["Invalid multicast source '{}': {}", source, err]
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+fn iface_index(iface: &str) -> Result<u32, glib::Error> {
+    let name = std::ffi::CString::new(iface).map_err(|_| {
+        glib::Error::new(
+            gio::IOErrorEnum::InvalidArgument,
+            &format!("Invalid interface name '{}'", iface),
+        )
+    })?;
+
+    // SAFETY: `name` is a valid nul-terminated string
+    match unsafe { libc::if_nametoindex(name.as_ptr()) } {
+        0 => Err(io_to_glib_error(
+            io::Error::last_os_error(),
+            &format!("Unknown interface '{}'", iface),
+        )),
+        index => Ok(index),
+    }
+}
+
+#[cfg(unix)]
+fn io_to_glib_error(err: io::Error, context: &str) -> glib::Error {
+    glib::Error::new(gio::IOErrorEnum::Failed, &format!("{}: {}", context, err))
+}
+
 impl Clone for GioSocketWrapper {
     fn clone(&self) -> Self {
         Self {
@@ -354,3 +770,175 @@ pub fn wrap_socket(socket: &Async<UdpSocket>) -> Result<GioSocketWrapper, gst::E
         Ok(GioSocketWrapper::new(&gio_socket))
     }
 }
+
+/// Maximum number of segments the kernel accepts in one UDP GSO send.
+#[cfg(target_os = "linux")]
+pub const GSO_MAX_SEGMENTS: usize = 64;
+
+/// Maximum payload size of one UDP GSO send.
+#[cfg(target_os = "linux")]
+pub const GSO_MAX_SIZE: usize = 65_000;
+
+/// Receives up to `buffers.len()` datagrams with a single `recvmmsg()` call.
+///
+/// Returns the size and origin of each datagram received, in the order of `buffers`.
+#[cfg(target_os = "linux")]
+pub fn recv_mmsg(
+    socket: &UdpSocket,
+    buffers: &mut [&mut [u8]],
+) -> io::Result<Vec<(usize, std::net::SocketAddr)>> {
+    use std::mem;
+    use std::ptr;
+
+    // SAFETY: all-zeroes is a valid representation of these plain C structs
+    let mut addrs = vec![unsafe { mem::zeroed::<libc::sockaddr_storage>() }; buffers.len()];
+    let mut iovecs = buffers
+        .iter_mut()
+        .map(|buffer| libc::iovec {
+            iov_base: buffer.as_mut_ptr() as *mut libc::c_void,
+            iov_len: buffer.len(),
+        })
+        .collect::<Vec<_>>();
+    let mut msgs = iovecs
+        .iter_mut()
+        .zip(addrs.iter_mut())
+        .map(|(iovec, addr)| {
+            let mut msg = unsafe { mem::zeroed::<libc::mmsghdr>() };
+            msg.msg_hdr.msg_name = addr as *mut libc::sockaddr_storage as *mut libc::c_void;
+            msg.msg_hdr.msg_namelen = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+            msg.msg_hdr.msg_iov = iovec;
+            msg.msg_hdr.msg_iovlen = 1;
+            msg
+        })
+        .collect::<Vec<_>>();
+
+    // SAFETY: the message headers point to buffers which outlive the call
+    let res = unsafe {
+        libc::recvmmsg(
+            socket.as_raw_fd(),
+            msgs.as_mut_ptr(),
+            msgs.len() as libc::c_uint,
+            libc::MSG_DONTWAIT,
+            ptr::null_mut(),
+        )
+    };
+    if res < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    msgs[..res as usize]
+        .iter()
+        .zip(addrs.iter())
+        .map(|(msg, addr)| {
+            // SAFETY: the kernel filled in the address and its length
+            let saddr = unsafe { socket2::SockAddr::new(*addr, msg.msg_hdr.msg_namelen) };
+            let saddr = saddr.as_socket().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "Unsupported address family")
+            })?;
+
+            Ok((msg.msg_len as usize, saddr))
+        })
+        .collect()
+}
+
+/// Sends each `(data, destination)` pair as one datagram with a single `sendmmsg()` call.
+///
+/// Returns how many datagrams were sent, which may be less than `packets.len()`.
+#[cfg(target_os = "linux")]
+pub fn send_mmsg(
+    socket: &UdpSocket,
+    packets: &[(&[u8], std::net::SocketAddr)],
+) -> io::Result<usize> {
+    use std::mem;
+
+    let addrs = packets
+        .iter()
+        .map(|(_, saddr)| socket2::SockAddr::from(*saddr))
+        .collect::<Vec<_>>();
+    let mut iovecs = packets
+        .iter()
+        .map(|(data, _)| libc::iovec {
+            iov_base: data.as_ptr() as *mut libc::c_void,
+            iov_len: data.len(),
+        })
+        .collect::<Vec<_>>();
+    let mut msgs = iovecs
+        .iter_mut()
+        .zip(addrs.iter())
+        .map(|(iovec, addr)| {
+            // SAFETY: all-zeroes is a valid representation of this plain C struct
+            let mut msg = unsafe { mem::zeroed::<libc::mmsghdr>() };
+            msg.msg_hdr.msg_name = addr.as_ptr() as *mut libc::c_void;
+            msg.msg_hdr.msg_namelen = addr.len();
+            msg.msg_hdr.msg_iov = iovec;
+            msg.msg_hdr.msg_iovlen = 1;
+            msg
+        })
+        .collect::<Vec<_>>();
+
+    // SAFETY: the message headers point to buffers which outlive the call
+    let res = unsafe {
+        libc::sendmmsg(
+            socket.as_raw_fd(),
+            msgs.as_mut_ptr(),
+            msgs.len() as libc::c_uint,
+            libc::MSG_DONTWAIT,
+        )
+    };
+    if res < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(res as usize)
+}
+
+/// Sends `data` to `saddr` using UDP generic segmentation offload.
+///
+/// The kernel splits `data` into datagrams of `segment_size` bytes, the last
+/// one possibly being shorter.
+#[cfg(target_os = "linux")]
+pub fn send_gso(
+    socket: &UdpSocket,
+    data: &[u8],
+    segment_size: u16,
+    saddr: std::net::SocketAddr,
+) -> io::Result<usize> {
+    use std::mem;
+    use std::ptr;
+
+    let addr = socket2::SockAddr::from(saddr);
+    let mut iovec = libc::iovec {
+        iov_base: data.as_ptr() as *mut libc::c_void,
+        iov_len: data.len(),
+    };
+    // Large enough and suitably aligned for one cmsghdr carrying a u16
+    let mut control = [0u64; 4];
+
+    // SAFETY: the message header points to buffers which outlive the call and
+    // the control buffer is large enough for the single control message
+    let res = unsafe {
+        let control_len = libc::CMSG_SPACE(mem::size_of::<u16>() as libc::c_uint) as usize;
+        assert!(control_len <= mem::size_of_val(&control));
+
+        let mut msg = mem::zeroed::<libc::msghdr>();
+        msg.msg_name = addr.as_ptr() as *mut libc::c_void;
+        msg.msg_namelen = addr.len();
+        msg.msg_iov = &mut iovec;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+        msg.msg_controllen = control_len as _;
+
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_UDP;
+        (*cmsg).cmsg_type = libc::UDP_SEGMENT;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(mem::size_of::<u16>() as libc::c_uint) as _;
+        ptr::write_unaligned(libc::CMSG_DATA(cmsg) as *mut u16, segment_size);
+
+        libc::sendmsg(socket.as_raw_fd(), &msg, libc::MSG_DONTWAIT)
+    };
+    if res < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(res as usize)
+}
@@ -0,0 +1,895 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use futures::channel::mpsc;
+use futures::future::{self, abortable, AbortHandle, BoxFuture, Either};
+use futures::prelude::*;
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+use gst::EventView;
+use gst::{element_error, error_msg};
+
+use once_cell::sync::Lazy;
+
+use crate::runtime::prelude::*;
+use crate::runtime::{self, Async, Context, PadSink, PadSinkRef, Task};
+use crate::socket::TcpFraming;
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
+use std::sync::Mutex as StdMutex;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use std::u16;
+
+const DEFAULT_HOST: Option<&str> = Some("127.0.0.1");
+const DEFAULT_PORT: i32 = 4953;
+const DEFAULT_SYNC: bool = true;
+const DEFAULT_FRAMING: TcpFraming = TcpFraming::Raw;
+const DEFAULT_CONTEXT: &str = "";
+const DEFAULT_CONTEXT_WAIT: Duration = Duration::ZERO;
+
+/// Number of buffers queued for a client before it is considered too slow.
+const CLIENT_QUEUE_SIZE: usize = 64;
+
+#[derive(Debug, Clone)]
+struct Settings {
+    host: Option<String>,
+    port: i32,
+    current_port: i32,
+    sync: bool,
+    framing: TcpFraming,
+    context: String,
+    context_wait: Duration,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            host: DEFAULT_HOST.map(Into::into),
+            port: DEFAULT_PORT,
+            current_port: 0,
+            sync: DEFAULT_SYNC,
+            framing: DEFAULT_FRAMING,
+            context: DEFAULT_CONTEXT.into(),
+            context_wait: DEFAULT_CONTEXT_WAIT,
+        }
+    }
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "ts-tcpserversink",
+        gst::DebugColorFlags::empty(),
+        Some("Thread-sharing TCP server sink"),
+    )
+});
+
+#[derive(Debug)]
+enum TaskItem {
+    Buffer(gst::Buffer),
+    Event(gst::Event),
+}
+
+#[derive(Debug, Default)]
+struct TcpServerSinkPadHandlerInner {
+    latency: Option<gst::ClockTime>,
+    sender: Option<mpsc::Sender<TaskItem>>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct TcpServerSinkPadHandler(Arc<RwLock<TcpServerSinkPadHandlerInner>>);
+
+impl TcpServerSinkPadHandler {
+    fn set_latency(&self, latency: gst::ClockTime) {
+        self.0.write().unwrap().latency = Some(latency);
+    }
+
+    fn latency(&self) -> Option<gst::ClockTime> {
+        self.0.read().unwrap().latency
+    }
+
+    fn set_sender(&self, sender: Option<mpsc::Sender<TaskItem>>) {
+        self.0.write().unwrap().sender = sender;
+    }
+
+    fn sender(&self) -> Option<mpsc::Sender<TaskItem>> {
+        self.0.read().unwrap().sender.clone()
+    }
+}
+
+impl PadSinkHandler for TcpServerSinkPadHandler {
+    type ElementImpl = TcpServerSink;
+
+    fn sink_chain(
+        &self,
+        _pad: &PadSinkRef,
+        _tcpserversink: &TcpServerSink,
+        element: &gst::Element,
+        buffer: gst::Buffer,
+    ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
+        let sender = self.sender();
+        let element = element.clone().downcast::<super::TcpServerSink>().unwrap();
+
+        async move {
+            if let Some(mut sender) = sender {
+                if sender.send(TaskItem::Buffer(buffer)).await.is_err() {
+                    gst::debug!(CAT, obj: &element, "Flushing");
+                    return Err(gst::FlowError::Flushing);
+                }
+            }
+            Ok(gst::FlowSuccess::Ok)
+        }
+        .boxed()
+    }
+
+    fn sink_chain_list(
+        &self,
+        _pad: &PadSinkRef,
+        _tcpserversink: &TcpServerSink,
+        element: &gst::Element,
+        list: gst::BufferList,
+    ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
+        let sender = self.sender();
+        let element = element.clone().downcast::<super::TcpServerSink>().unwrap();
+
+        async move {
+            if let Some(mut sender) = sender {
+                for buffer in list.iter_owned() {
+                    if sender.send(TaskItem::Buffer(buffer)).await.is_err() {
+                        gst::debug!(CAT, obj: &element, "Flushing");
+                        return Err(gst::FlowError::Flushing);
+                    }
+                }
+            }
+
+            Ok(gst::FlowSuccess::Ok)
+        }
+        .boxed()
+    }
+
+    fn sink_event_serialized(
+        &self,
+        _pad: &PadSinkRef,
+        _tcpserversink: &TcpServerSink,
+        element: &gst::Element,
+        event: gst::Event,
+    ) -> BoxFuture<'static, bool> {
+        let sender = self.sender();
+        let element = element.clone().downcast::<super::TcpServerSink>().unwrap();
+
+        async move {
+            if let EventView::FlushStop(_) = event.view() {
+                let tcpserversink = element.imp();
+                return tcpserversink.task.flush_stop().is_ok();
+            } else if let Some(mut sender) = sender {
+                if sender.send(TaskItem::Event(event)).await.is_err() {
+                    gst::debug!(CAT, obj: &element, "Flushing");
+                }
+            }
+
+            true
+        }
+        .boxed()
+    }
+
+    fn sink_event(
+        &self,
+        _pad: &PadSinkRef,
+        tcpserversink: &TcpServerSink,
+        _element: &gst::Element,
+        event: gst::Event,
+    ) -> bool {
+        if let EventView::FlushStart(..) = event.view() {
+            return tcpserversink.task.flush_start().is_ok();
+        }
+
+        true
+    }
+}
+
+enum SinkEvent {
+    Accepted(std::io::Result<(Async<TcpStream>, SocketAddr)>),
+    Item(Option<TaskItem>),
+}
+
+struct Client {
+    saddr: SocketAddr,
+    framing: TcpFraming,
+    sender: mpsc::Sender<gst::Buffer>,
+    abort_handle: AbortHandle,
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        self.abort_handle.abort();
+    }
+}
+
+/// Writes the buffers queued for a client until it fails.
+///
+/// Each client is served by its own future so that a slow client doesn't
+/// delay the others. The future owns the socket: when it's aborted in the
+/// middle of a frame, the connection is closed instead of being reused.
+async fn client_writer(
+    element: super::TcpServerSink,
+    mut socket: Async<TcpStream>,
+    saddr: SocketAddr,
+    framing: TcpFraming,
+    mut receiver: mpsc::Receiver<gst::Buffer>,
+) {
+    while let Some(buffer) = receiver.next().await {
+        let data = match buffer.map_readable() {
+            Ok(data) => data,
+            Err(_) => {
+                gst::warning!(CAT, obj: &element, "Failed to map buffer readable");
+                continue;
+            }
+        };
+
+        gst::log!(CAT, obj: &element, "Sending to {}", saddr);
+
+        if let Err(err) = framing.write_frame(&mut socket, &data).await {
+            gst::warning!(CAT, obj: &element, "Failed to send to {}: {}", saddr, err);
+            return;
+        }
+    }
+}
+
+struct TcpServerSinkTask {
+    element: super::TcpServerSink,
+    context: Context,
+    sink_pad_handler: TcpServerSinkPadHandler,
+    listener: Async<TcpListener>,
+    sync: bool,
+    framing: TcpFraming,
+    segment: Option<gst::Segment>,
+    clients: Vec<Client>,
+    receiver: Option<mpsc::Receiver<TaskItem>>,
+}
+
+impl TcpServerSinkTask {
+    fn new(
+        element: &super::TcpServerSink,
+        context: &Context,
+        sink_pad_handler: &TcpServerSinkPadHandler,
+        listener: Async<TcpListener>,
+        settings: &Settings,
+    ) -> Self {
+        TcpServerSinkTask {
+            element: element.clone(),
+            context: context.clone(),
+            sink_pad_handler: sink_pad_handler.clone(),
+            listener,
+            sync: settings.sync,
+            framing: settings.framing,
+            segment: None,
+            clients: Vec::new(),
+            receiver: None,
+        }
+    }
+
+    fn client_connected(&mut self, socket: Async<TcpStream>, saddr: SocketAddr) {
+        gst::info!(CAT, obj: &self.element, "Client {} connected", saddr);
+
+        // Handlers can select the framing of this client
+        self.element.emit_by_name::<()>(
+            "client-connected",
+            &[&saddr.ip().to_string(), &(saddr.port() as i32)],
+        );
+
+        let framing = self.element.imp().take_client_framing(saddr, self.framing);
+        gst::debug!(CAT, obj: &self.element, "Using {:?} framing for {}", framing, saddr);
+
+        let (sender, receiver) = mpsc::channel(CLIENT_QUEUE_SIZE);
+        let (writer, abort_handle) = abortable(client_writer(
+            self.element.clone(),
+            socket,
+            saddr,
+            framing,
+            receiver,
+        ));
+        self.context.spawn(writer);
+
+        self.clients.push(Client {
+            saddr,
+            framing,
+            sender,
+            abort_handle,
+        });
+    }
+
+    fn client_disconnected(&self, saddr: SocketAddr) {
+        gst::info!(CAT, obj: &self.element, "Client {} disconnected", saddr);
+
+        self.element.emit_by_name::<()>(
+            "client-disconnected",
+            &[&saddr.ip().to_string(), &(saddr.port() as i32)],
+        );
+    }
+
+    fn disconnect_all(&mut self) {
+        for client in std::mem::take(&mut self.clients) {
+            self.client_disconnected(client.saddr);
+        }
+    }
+
+    async fn render(&mut self, buffer: gst::Buffer) -> Result<(), gst::FlowError> {
+        if self.sync {
+            let rtime = self.segment.as_ref().and_then(|segment| {
+                segment
+                    .downcast_ref::<gst::format::Time>()
+                    .and_then(|segment| {
+                        segment
+                            .to_running_time(buffer.pts())
+                            .opt_add(self.sink_pad_handler.latency())
+                    })
+            });
+
+            self.sync(rtime).await;
+        }
+
+        let size = buffer.size();
+        let mut too_large = false;
+        let mut disconnected = Vec::new();
+        for (idx, client) in self.clients.iter_mut().enumerate() {
+            if client.framing.max_size().map_or(false, |max| size > max) {
+                too_large = true;
+                continue;
+            }
+
+            gst::log!(CAT, obj: &self.element, "Queueing for {}", client.saddr);
+
+            if let Err(err) = client.sender.try_send(buffer.clone()) {
+                // Otherwise, the writer failed and already logged why
+                if err.is_full() {
+                    gst::warning!(
+                        CAT,
+                        obj: &self.element,
+                        "Client {} too slow, disconnecting",
+                        client.saddr
+                    );
+                }
+                disconnected.push(idx);
+            }
+        }
+
+        for idx in disconnected.into_iter().rev() {
+            let client = self.clients.remove(idx);
+            self.client_disconnected(client.saddr);
+        }
+
+        if too_large {
+            gst::element_warning!(
+                self.element,
+                gst::StreamError::Format,
+                ["Dropping buffer of {} bytes, too large for framing", size]
+            );
+        }
+
+        gst::log!(
+            CAT,
+            obj: &self.element,
+            "Queued buffer {:?} for all clients",
+            &buffer
+        );
+
+        Ok(())
+    }
+
+    /* Wait until specified time */
+    async fn sync(&self, running_time: Option<gst::ClockTime>) {
+        let now = self.element.current_running_time();
+
+        match running_time.opt_checked_sub(now) {
+            Ok(Some(delay)) => {
+                let _ = runtime::time::delay_for(delay.into()).await;
+            }
+            _ => runtime::executor::yield_now().await,
+        }
+    }
+
+    fn handle_event(&mut self, event: gst::Event) {
+        match event.view() {
+            EventView::Eos(_) => {
+                let _ = self
+                    .element
+                    .post_message(gst::message::Eos::builder().src(&self.element).build());
+            }
+            EventView::Segment(e) => {
+                self.segment = Some(e.segment().clone());
+            }
+            EventView::SinkMessage(e) => {
+                let _ = self.element.post_message(e.message());
+            }
+            _ => (),
+        }
+    }
+}
+
+impl TaskImpl for TcpServerSinkTask {
+    fn start(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Starting task");
+
+            let (sender, receiver) = mpsc::channel(0);
+            self.sink_pad_handler.set_sender(Some(sender));
+            self.receiver = Some(receiver);
+
+            gst::log!(CAT, obj: &self.element, "Task started");
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn iterate(&mut self) -> BoxFuture<'_, Result<(), gst::FlowError>> {
+        async move {
+            let event = {
+                let accept = self.listener.accept();
+                futures::pin_mut!(accept);
+
+                match future::select(accept, self.receiver.as_mut().unwrap().next()).await {
+                    Either::Left((res, _)) => SinkEvent::Accepted(res),
+                    Either::Right((item, _)) => SinkEvent::Item(item),
+                }
+            };
+
+            let item = match event {
+                SinkEvent::Accepted(Ok((socket, saddr))) => {
+                    self.client_connected(socket, saddr);
+                    return Ok(());
+                }
+                SinkEvent::Accepted(Err(err)) => {
+                    gst::warning!(CAT, obj: &self.element, "Failed to accept client: {}", err);
+                    return Ok(());
+                }
+                SinkEvent::Item(item) => item,
+            };
+
+            match item {
+                Some(TaskItem::Buffer(buffer)) => match self.render(buffer).await {
+                    Err(err) => {
+                        element_error!(
+                            &self.element,
+                            gst::StreamError::Failed,
+                            ["Failed to render item, stopping task: {}", err]
+                        );
+
+                        Err(gst::FlowError::Error)
+                    }
+                    _ => Ok(()),
+                },
+                Some(TaskItem::Event(event)) => {
+                    self.handle_event(event);
+                    Ok(())
+                }
+                None => Err(gst::FlowError::Flushing),
+            }
+        }
+        .boxed()
+    }
+
+    fn stop(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Stopping task");
+            self.sink_pad_handler.set_sender(None);
+            self.receiver = None;
+            self.segment = None;
+            self.disconnect_all();
+            gst::log!(CAT, obj: &self.element, "Task stopped");
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+#[derive(Debug)]
+pub struct TcpServerSink {
+    sink_pad: PadSink,
+    sink_pad_handler: TcpServerSinkPadHandler,
+    task: Task,
+    settings: StdMutex<Settings>,
+    client_framings: StdMutex<HashMap<SocketAddr, TcpFraming>>,
+}
+
+impl TcpServerSink {
+    fn set_client_framing(
+        &self,
+        element: &super::TcpServerSink,
+        host: &str,
+        port: i32,
+        framing: TcpFraming,
+    ) {
+        let saddr = match host.parse::<IpAddr>() {
+            Ok(addr) => SocketAddr::new(addr, port as u16),
+            Err(err) => {
+                gst::warning!(CAT, obj: element, "Invalid client host '{}': {}", host, err);
+                return;
+            }
+        };
+
+        self.client_framings.lock().unwrap().insert(saddr, framing);
+    }
+
+    /// Returns the framing selected for the client being connected,
+    /// forgetting those selected for other clients.
+    fn take_client_framing(&self, saddr: SocketAddr, default: TcpFraming) -> TcpFraming {
+        std::mem::take(&mut *self.client_framings.lock().unwrap())
+            .remove(&saddr)
+            .unwrap_or(default)
+    }
+
+    fn prepare(&self, element: &super::TcpServerSink) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Preparing");
+
+        let mut settings = self.settings.lock().unwrap();
+
+        let context =
+            Context::acquire(&settings.context, settings.context_wait).map_err(|err| {
+                error_msg!(
+                    gst::ResourceError::OpenWrite,
+                    ["Failed to acquire Context: {}", err]
+                )
+            })?;
+
+        let host: IpAddr = match settings.host {
+            None => {
+                return Err(error_msg!(gst::ResourceError::Settings, ["No host set"]));
+            }
+            Some(ref host) => host.parse().map_err(|err| {
+                error_msg!(
+                    gst::ResourceError::Settings,
+                    ["Invalid host '{}' set: {}", host, err]
+                )
+            })?,
+        };
+
+        let saddr = SocketAddr::new(host, settings.port as u16);
+        gst::debug!(CAT, obj: element, "Listening on {:?}", saddr);
+
+        let listener = context.enter(|| {
+            Async::<TcpListener>::bind(saddr).map_err(|err| {
+                error_msg!(
+                    gst::ResourceError::OpenWrite,
+                    ["Failed to listen on {:?}: {}", saddr, err]
+                )
+            })
+        })?;
+
+        settings.current_port = listener
+            .get_ref()
+            .local_addr()
+            .map(|addr| addr.port() as i32)
+            .unwrap_or(0);
+
+        let task_impl = TcpServerSinkTask::new(
+            element,
+            &context,
+            &self.sink_pad_handler,
+            listener,
+            &settings,
+        );
+        drop(settings);
+
+        self.task.prepare(task_impl, context).map_err(|err| {
+            error_msg!(
+                gst::ResourceError::OpenWrite,
+                ["Error preparing Task: {:?}", err]
+            )
+        })?;
+
+        gst::debug!(CAT, obj: element, "Prepared");
+
+        Ok(())
+    }
+
+    fn unprepare(&self, element: &super::TcpServerSink) {
+        gst::debug!(CAT, obj: element, "Unpreparing");
+        self.task.unprepare().unwrap();
+        self.settings.lock().unwrap().current_port = 0;
+        gst::debug!(CAT, obj: element, "Unprepared");
+    }
+
+    fn stop(&self, element: &super::TcpServerSink) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Stopping");
+        self.task.stop()?;
+        gst::debug!(CAT, obj: element, "Stopped");
+        Ok(())
+    }
+
+    fn start(&self, element: &super::TcpServerSink) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Starting");
+        self.task.start()?;
+        gst::debug!(CAT, obj: element, "Started");
+        Ok(())
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for TcpServerSink {
+    const NAME: &'static str = "RsTsTcpServerSink";
+    type Type = super::TcpServerSink;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        let sink_pad_handler = TcpServerSinkPadHandler::default();
+
+        Self {
+            sink_pad: PadSink::new(
+                gst::Pad::from_template(&klass.pad_template("sink").unwrap(), Some("sink")),
+                sink_pad_handler.clone(),
+            ),
+            sink_pad_handler,
+            task: Task::default(),
+            settings: StdMutex::new(Settings::default()),
+            client_framings: StdMutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl ObjectImpl for TcpServerSink {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecString::new(
+                    "context",
+                    "Context",
+                    "Context name to share threads with",
+                    Some(DEFAULT_CONTEXT),
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "context-wait",
+                    "Context Wait",
+                    "Throttle poll loop to run at most once every this many ms",
+                    0,
+                    1000,
+                    DEFAULT_CONTEXT_WAIT.as_millis() as u32,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecString::new(
+                    "host",
+                    "Host",
+                    "The host IP address to listen on",
+                    DEFAULT_HOST,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecInt::new(
+                    "port",
+                    "Port",
+                    "Port to listen on (0 = random available port)",
+                    0,
+                    u16::MAX as i32,
+                    DEFAULT_PORT,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecInt::new(
+                    "current-port",
+                    "Current Port",
+                    "The port number the socket is currently bound to",
+                    0,
+                    u16::MAX as i32,
+                    0,
+                    glib::ParamFlags::READABLE,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "sync",
+                    "Sync",
+                    "Sync on the clock",
+                    DEFAULT_SYNC,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecEnum::new(
+                    "framing",
+                    "Framing",
+                    "How buffers are framed by default when sent to a client, \
+                     see the set-client-framing signal",
+                    TcpFraming::static_type(),
+                    DEFAULT_FRAMING as i32,
+                    glib::ParamFlags::READWRITE,
+                ),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn signals() -> &'static [glib::subclass::Signal] {
+        static SIGNALS: Lazy<Vec<glib::subclass::Signal>> = Lazy::new(|| {
+            vec![
+                glib::subclass::Signal::builder(
+                    "client-connected",
+                    &[String::static_type().into(), i32::static_type().into()],
+                    glib::types::Type::UNIT.into(),
+                )
+                .build(),
+                glib::subclass::Signal::builder(
+                    "client-disconnected",
+                    &[String::static_type().into(), i32::static_type().into()],
+                    glib::types::Type::UNIT.into(),
+                )
+                .build(),
+                /*
+                 * Selects the framing of a client, from a handler
+                 * of the client-connected signal for this client
+                 */
+                glib::subclass::Signal::builder(
+                    "set-client-framing",
+                    &[
+                        String::static_type().into(),
+                        i32::static_type().into(),
+                        TcpFraming::static_type().into(),
+                    ],
+                    glib::types::Type::UNIT.into(),
+                )
+                .action()
+                .class_handler(|_, args| {
+                    let element = args[0].get::<super::TcpServerSink>().expect("signal arg");
+                    let host = args[1].get::<String>().expect("signal arg");
+                    let port = args[2].get::<i32>().expect("signal arg");
+                    let framing = args[3].get::<TcpFraming>().expect("signal arg");
+
+                    element
+                        .imp()
+                        .set_client_framing(&element, &host, port, framing);
+
+                    None
+                })
+                .build(),
+            ]
+        });
+
+        SIGNALS.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        let mut settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "host" => {
+                settings.host = value.get().expect("type checked upstream");
+            }
+            "port" => {
+                settings.port = value.get().expect("type checked upstream");
+            }
+            "sync" => {
+                settings.sync = value.get().expect("type checked upstream");
+            }
+            "framing" => {
+                settings.framing = value.get().expect("type checked upstream");
+            }
+            "context" => {
+                settings.context = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream")
+                    .unwrap_or_else(|| DEFAULT_CONTEXT.into());
+            }
+            "context-wait" => {
+                settings.context_wait = Duration::from_millis(
+                    value.get::<u32>().expect("type checked upstream").into(),
+                );
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        let settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "host" => settings.host.to_value(),
+            "port" => settings.port.to_value(),
+            "current-port" => settings.current_port.to_value(),
+            "sync" => settings.sync.to_value(),
+            "framing" => settings.framing.to_value(),
+            "context" => settings.context.to_value(),
+            "context-wait" => (settings.context_wait.as_millis() as u32).to_value(),
+            _ => unimplemented!(),
+        }
+    }
+
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(self.sink_pad.gst_pad()).unwrap();
+
+        crate::set_element_flags(obj, gst::ElementFlags::SINK);
+    }
+}
+
+impl GstObjectImpl for TcpServerSink {}
+
+impl ElementImpl for TcpServerSink {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Thread-sharing TCP server sink",
+                "Sink/Network",
+                "Sends data to TCP clients connecting over the network",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::new_any();
+
+            let sink_pad_template = gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![sink_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        match transition {
+            gst::StateChange::NullToReady => {
+                self.prepare(element).map_err(|err| {
+                    element.post_error_message(err);
+                    gst::StateChangeError
+                })?;
+            }
+            gst::StateChange::ReadyToPaused => {
+                self.start(element).map_err(|_| gst::StateChangeError)?;
+            }
+            gst::StateChange::PausedToReady => {
+                self.stop(element).map_err(|_| gst::StateChangeError)?;
+            }
+            gst::StateChange::ReadyToNull => {
+                self.unprepare(element);
+            }
+            _ => (),
+        }
+
+        self.parent_change_state(element, transition)
+    }
+
+    fn send_event(&self, _element: &Self::Type, event: gst::Event) -> bool {
+        match event.view() {
+            EventView::Latency(ev) => {
+                self.sink_pad_handler.set_latency(ev.latency());
+                self.sink_pad.gst_pad().push_event(event)
+            }
+            EventView::Step(..) => false,
+            _ => self.sink_pad.gst_pad().push_event(event),
+        }
+    }
+}
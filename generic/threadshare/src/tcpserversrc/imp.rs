@@ -0,0 +1,937 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use futures::future::{self, BoxFuture, Either};
+use futures::lock::Mutex as FutMutex;
+use futures::prelude::*;
+use futures::stream::{BoxStream, SelectAll};
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+
+use once_cell::sync::Lazy;
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+use std::u16;
+use std::u32;
+
+use crate::runtime::prelude::*;
+use crate::runtime::task;
+use crate::runtime::{Async, Context, PadSrc, PadSrcRef, PadSrcWeak, Task, TaskState};
+use crate::socket::TcpFraming;
+
+const DEFAULT_HOST: Option<&str> = Some("127.0.0.1");
+const DEFAULT_PORT: i32 = 4953;
+const DEFAULT_CAPS: Option<gst::Caps> = None;
+const DEFAULT_BLOCKSIZE: u32 = 4096;
+const DEFAULT_FRAMING: TcpFraming = TcpFraming::Raw;
+const DEFAULT_CONTEXT: &str = "";
+const DEFAULT_CONTEXT_WAIT: Duration = Duration::ZERO;
+
+#[derive(Debug, Clone)]
+struct Settings {
+    host: Option<String>,
+    port: i32,
+    current_port: i32,
+    caps: Option<gst::Caps>,
+    blocksize: u32,
+    framing: TcpFraming,
+    context: String,
+    context_wait: Duration,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            host: DEFAULT_HOST.map(Into::into),
+            port: DEFAULT_PORT,
+            current_port: 0,
+            caps: DEFAULT_CAPS,
+            blocksize: DEFAULT_BLOCKSIZE,
+            framing: DEFAULT_FRAMING,
+            context: DEFAULT_CONTEXT.into(),
+            context_wait: DEFAULT_CONTEXT_WAIT,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum ClientItem {
+    Data(Vec<u8>),
+    Disconnected(Option<io::Error>),
+}
+
+type ClientStream = BoxStream<'static, (SocketAddr, ClientItem)>;
+
+/// Reads the packets of a connected client until it disconnects.
+fn client_stream(
+    socket: Async<TcpStream>,
+    saddr: SocketAddr,
+    framing: TcpFraming,
+    blocksize: usize,
+) -> ClientStream {
+    stream::unfold(Some(socket), move |socket| async move {
+        let mut socket = socket?;
+
+        match framing.read_frame(&mut socket, blocksize).await {
+            Ok(Some(data)) => Some(((saddr, ClientItem::Data(data)), Some(socket))),
+            Ok(None) => Some(((saddr, ClientItem::Disconnected(None)), None)),
+            Err(err) => Some(((saddr, ClientItem::Disconnected(Some(err))), None)),
+        }
+    })
+    .boxed()
+}
+
+#[derive(Debug)]
+struct TcpServerSrcPadHandlerState {
+    need_initial_events: bool,
+    need_segment: bool,
+    caps: Option<gst::Caps>,
+}
+
+impl Default for TcpServerSrcPadHandlerState {
+    fn default() -> Self {
+        TcpServerSrcPadHandlerState {
+            need_initial_events: true,
+            need_segment: true,
+            caps: None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct TcpServerSrcPadHandlerInner {
+    state: FutMutex<TcpServerSrcPadHandlerState>,
+    configured_caps: StdMutex<Option<gst::Caps>>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct TcpServerSrcPadHandler(Arc<TcpServerSrcPadHandlerInner>);
+
+impl TcpServerSrcPadHandler {
+    fn prepare(&self, caps: Option<gst::Caps>) {
+        self.0
+            .state
+            .try_lock()
+            .expect("State locked elsewhere")
+            .caps = caps;
+    }
+
+    async fn reset_state(&self) {
+        *self.0.configured_caps.lock().unwrap() = None;
+    }
+
+    async fn set_need_segment(&self) {
+        self.0.state.lock().await.need_segment = true;
+    }
+
+    async fn push_prelude(&self, pad: &PadSrcRef<'_>, _element: &super::TcpServerSrc) {
+        let mut state = self.0.state.lock().await;
+        if state.need_initial_events {
+            gst::debug!(CAT, obj: pad.gst_pad(), "Pushing initial events");
+
+            let stream_id = format!("{:08x}{:08x}", rand::random::<u32>(), rand::random::<u32>());
+            let stream_start_evt = gst::event::StreamStart::builder(&stream_id)
+                .group_id(gst::GroupId::next())
+                .build();
+            pad.push_event(stream_start_evt).await;
+
+            if let Some(ref caps) = state.caps {
+                pad.push_event(gst::event::Caps::new(caps)).await;
+                *self.0.configured_caps.lock().unwrap() = Some(caps.clone());
+            }
+
+            state.need_initial_events = false;
+        }
+
+        if state.need_segment {
+            let segment_evt =
+                gst::event::Segment::new(&gst::FormattedSegment::<gst::format::Time>::new());
+            pad.push_event(segment_evt).await;
+
+            state.need_segment = false;
+        }
+    }
+
+    async fn push_buffer(
+        &self,
+        pad: &PadSrcRef<'_>,
+        element: &super::TcpServerSrc,
+        buffer: gst::Buffer,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", buffer);
+
+        self.push_prelude(pad, element).await;
+
+        pad.push(buffer).await
+    }
+}
+
+impl PadSrcHandler for TcpServerSrcPadHandler {
+    type ElementImpl = TcpServerSrc;
+
+    fn src_event(
+        &self,
+        pad: &PadSrcRef,
+        tcpserversrc: &TcpServerSrc,
+        _element: &gst::Element,
+        event: gst::Event,
+    ) -> bool {
+        use gst::EventView;
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", event);
+
+        let ret = match event.view() {
+            EventView::FlushStart(..) => tcpserversrc.task.flush_start().is_ok(),
+            EventView::FlushStop(..) => tcpserversrc.task.flush_stop().is_ok(),
+            EventView::Reconfigure(..) => true,
+            EventView::Latency(..) => true,
+            _ => false,
+        };
+
+        if ret {
+            gst::log!(CAT, obj: pad.gst_pad(), "Handled {:?}", event);
+        } else {
+            gst::log!(CAT, obj: pad.gst_pad(), "Didn't handle {:?}", event);
+        }
+
+        ret
+    }
+
+    fn src_query(
+        &self,
+        pad: &PadSrcRef,
+        _tcpserversrc: &TcpServerSrc,
+        _element: &gst::Element,
+        query: &mut gst::QueryRef,
+    ) -> bool {
+        use gst::QueryViewMut;
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", query);
+        let ret = match query.view_mut() {
+            QueryViewMut::Latency(q) => {
+                q.set(true, gst::ClockTime::ZERO, gst::ClockTime::NONE);
+                true
+            }
+            QueryViewMut::Scheduling(q) => {
+                q.set(gst::SchedulingFlags::SEQUENTIAL, 1, -1, 0);
+                q.add_scheduling_modes(&[gst::PadMode::Push]);
+                true
+            }
+            QueryViewMut::Caps(q) => {
+                let caps = if let Some(caps) = self.0.configured_caps.lock().unwrap().as_ref() {
+                    q.filter()
+                        .map(|f| f.intersect_with_mode(caps, gst::CapsIntersectMode::First))
+                        .unwrap_or_else(|| caps.clone())
+                } else {
+                    q.filter()
+                        .map(|f| f.to_owned())
+                        .unwrap_or_else(gst::Caps::new_any)
+                };
+
+                q.set_result(&caps);
+
+                true
+            }
+            _ => false,
+        };
+
+        if ret {
+            gst::log!(CAT, obj: pad.gst_pad(), "Handled {:?}", query);
+        } else {
+            gst::log!(CAT, obj: pad.gst_pad(), "Didn't handle {:?}", query);
+        }
+
+        ret
+    }
+}
+
+enum ServerEvent {
+    Accepted(io::Result<(Async<TcpStream>, SocketAddr)>),
+    Client(SocketAddr, ClientItem),
+}
+
+struct TcpServerSrcTask {
+    element: super::TcpServerSrc,
+    src_pad: PadSrcWeak,
+    src_pad_handler: TcpServerSrcPadHandler,
+    listener: Async<TcpListener>,
+    framing: TcpFraming,
+    blocksize: usize,
+    clients: SelectAll<ClientStream>,
+    client_addrs: Vec<(SocketAddr, TcpFraming)>,
+    clock: Option<gst::Clock>,
+    base_time: Option<gst::ClockTime>,
+}
+
+impl TcpServerSrcTask {
+    fn new(
+        element: &super::TcpServerSrc,
+        src_pad: &PadSrc,
+        src_pad_handler: &TcpServerSrcPadHandler,
+        listener: Async<TcpListener>,
+        framing: TcpFraming,
+        blocksize: usize,
+    ) -> Self {
+        TcpServerSrcTask {
+            element: element.clone(),
+            src_pad: src_pad.downgrade(),
+            src_pad_handler: src_pad_handler.clone(),
+            listener,
+            framing,
+            blocksize,
+            clients: SelectAll::new(),
+            client_addrs: Vec::new(),
+            clock: None,
+            base_time: None,
+        }
+    }
+
+    fn client_connected(&mut self, socket: Async<TcpStream>, saddr: SocketAddr) {
+        gst::info!(CAT, obj: &self.element, "Client {} connected", saddr);
+
+        // Handlers can select the framing of this client
+        self.element.emit_by_name::<()>(
+            "client-connected",
+            &[&saddr.ip().to_string(), &(saddr.port() as i32)],
+        );
+
+        let framing = self.element.imp().take_client_framing(saddr, self.framing);
+        gst::debug!(CAT, obj: &self.element, "Using {:?} framing for {}", framing, saddr);
+
+        self.clients
+            .push(client_stream(socket, saddr, framing, self.blocksize));
+        self.client_addrs.push((saddr, framing));
+    }
+
+    fn client_disconnected(&mut self, saddr: SocketAddr) {
+        gst::info!(CAT, obj: &self.element, "Client {} disconnected", saddr);
+
+        self.client_addrs.retain(|(addr, _)| *addr != saddr);
+
+        self.element.emit_by_name::<()>(
+            "client-disconnected",
+            &[&saddr.ip().to_string(), &(saddr.port() as i32)],
+        );
+    }
+
+    fn disconnect_all(&mut self) {
+        self.clients = SelectAll::new();
+
+        for (saddr, _) in std::mem::take(&mut self.client_addrs) {
+            self.client_disconnected(saddr);
+        }
+    }
+
+    fn new_buffer(&self, data: Vec<u8>, saddr: SocketAddr) -> gst::Buffer {
+        let mut buffer = gst::Buffer::from_mut_slice(data);
+        {
+            let buffer = buffer.get_mut().unwrap();
+
+            // Packets of all clients end up on the same pad, timestamp them on
+            // arrival so that downstream can handle them like datagrams
+            let framing = self
+                .client_addrs
+                .iter()
+                .find(|(addr, _)| *addr == saddr)
+                .map_or(self.framing, |(_, framing)| *framing);
+            if framing == TcpFraming::Rfc4571 {
+                let time = self.clock.as_ref().and_then(|clock| clock.time());
+                buffer.set_dts(time.opt_checked_sub(self.base_time).ok().flatten());
+            }
+
+            gst_net::NetAddressMeta::add(buffer, &gio::InetSocketAddress::from(saddr));
+        }
+
+        buffer
+    }
+}
+
+impl TaskImpl for TcpServerSrcTask {
+    fn start(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Starting task");
+            self.clock = self.element.clock();
+            self.base_time = self.element.base_time();
+            gst::log!(CAT, obj: &self.element, "Task started");
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn handle_action_error(
+        &mut self,
+        trigger: task::Trigger,
+        state: TaskState,
+        err: gst::ErrorMessage,
+    ) -> BoxFuture<'_, task::Trigger> {
+        async move {
+            match trigger {
+                task::Trigger::Prepare => {
+                    gst::error!(CAT, "Task preparation failed: {:?}", err);
+                    self.element.post_error_message(err);
+
+                    task::Trigger::Error
+                }
+                other => unreachable!("Action error for {:?} in state {:?}", other, state),
+            }
+        }
+        .boxed()
+    }
+
+    fn iterate(&mut self) -> BoxFuture<'_, Result<(), gst::FlowError>> {
+        async move {
+            let event = if self.clients.is_empty() {
+                ServerEvent::Accepted(self.listener.accept().await)
+            } else {
+                let accept = self.listener.accept();
+                futures::pin_mut!(accept);
+
+                match future::select(accept, self.clients.next()).await {
+                    Either::Left((res, _)) => ServerEvent::Accepted(res),
+                    Either::Right((Some((saddr, item)), _)) => ServerEvent::Client(saddr, item),
+                    Either::Right((None, _)) => unreachable!("clients were not empty"),
+                }
+            };
+
+            let (buffer, saddr) = match event {
+                ServerEvent::Accepted(Ok((socket, saddr))) => {
+                    self.client_connected(socket, saddr);
+                    return Ok(());
+                }
+                ServerEvent::Accepted(Err(err)) => {
+                    gst::warning!(CAT, obj: &self.element, "Failed to accept client: {}", err);
+                    return Ok(());
+                }
+                ServerEvent::Client(saddr, ClientItem::Disconnected(err)) => {
+                    if let Some(err) = err {
+                        gst::warning!(CAT, obj: &self.element, "Client {} failed: {}", saddr, err);
+                    }
+                    self.client_disconnected(saddr);
+                    return Ok(());
+                }
+                ServerEvent::Client(saddr, ClientItem::Data(data)) => {
+                    gst::log!(
+                        CAT,
+                        obj: &self.element,
+                        "Read {} bytes from {}",
+                        data.len(),
+                        saddr
+                    );
+                    (self.new_buffer(data, saddr), saddr)
+                }
+            };
+
+            let pad = self.src_pad.upgrade().expect("PadSrc no longer exists");
+            let res = self
+                .src_pad_handler
+                .push_buffer(&pad, &self.element, buffer)
+                .await;
+            match res {
+                Ok(_) => {
+                    gst::log!(CAT, obj: &self.element, "Successfully pushed buffer from {}", saddr);
+                }
+                Err(gst::FlowError::Flushing) => {
+                    gst::debug!(CAT, obj: &self.element, "Flushing");
+                }
+                Err(gst::FlowError::Eos) => {
+                    gst::debug!(CAT, obj: &self.element, "EOS");
+                    pad.push_event(gst::event::Eos::new()).await;
+                }
+                Err(err) => {
+                    gst::error!(CAT, obj: &self.element, "Got error {}", err);
+                    gst::element_error!(
+                        self.element,
+                        gst::StreamError::Failed,
+                        ("Internal data stream error"),
+                        ["streaming stopped, reason {}", err]
+                    );
+                }
+            }
+
+            res.map(drop)
+        }
+        .boxed()
+    }
+
+    fn stop(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Stopping task");
+            self.disconnect_all();
+            self.src_pad_handler.reset_state().await;
+            gst::log!(CAT, obj: &self.element, "Task stopped");
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn flush_stop(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Stopping task flush");
+            self.src_pad_handler.set_need_segment().await;
+            gst::log!(CAT, obj: &self.element, "Task flush stopped");
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+pub struct TcpServerSrc {
+    src_pad: PadSrc,
+    src_pad_handler: TcpServerSrcPadHandler,
+    task: Task,
+    settings: StdMutex<Settings>,
+    client_framings: StdMutex<HashMap<SocketAddr, TcpFraming>>,
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "ts-tcpserversrc",
+        gst::DebugColorFlags::empty(),
+        Some("Thread-sharing TCP Server source"),
+    )
+});
+
+impl TcpServerSrc {
+    fn set_client_framing(
+        &self,
+        element: &super::TcpServerSrc,
+        host: &str,
+        port: i32,
+        framing: TcpFraming,
+    ) {
+        let saddr = match host.parse::<IpAddr>() {
+            Ok(addr) => SocketAddr::new(addr, port as u16),
+            Err(err) => {
+                gst::warning!(CAT, obj: element, "Invalid client host '{}': {}", host, err);
+                return;
+            }
+        };
+
+        self.client_framings.lock().unwrap().insert(saddr, framing);
+    }
+
+    /// Returns the framing selected for the client being connected,
+    /// forgetting those selected for other clients.
+    fn take_client_framing(&self, saddr: SocketAddr, default: TcpFraming) -> TcpFraming {
+        std::mem::take(&mut *self.client_framings.lock().unwrap())
+            .remove(&saddr)
+            .unwrap_or(default)
+    }
+
+    fn prepare(&self, element: &super::TcpServerSrc) -> Result<(), gst::ErrorMessage> {
+        let mut settings = self.settings.lock().unwrap();
+
+        gst::debug!(CAT, obj: element, "Preparing");
+
+        let context =
+            Context::acquire(&settings.context, settings.context_wait).map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::OpenRead,
+                    ["Failed to acquire Context: {}", err]
+                )
+            })?;
+
+        let host: IpAddr = match settings.host {
+            None => {
+                return Err(gst::error_msg!(
+                    gst::ResourceError::Settings,
+                    ["No host set"]
+                ));
+            }
+            Some(ref host) => match host.parse() {
+                Err(err) => {
+                    return Err(gst::error_msg!(
+                        gst::ResourceError::Settings,
+                        ["Invalid host '{}' set: {}", host, err]
+                    ));
+                }
+                Ok(host) => host,
+            },
+        };
+
+        let saddr = SocketAddr::new(host, settings.port as u16);
+        gst::debug!(CAT, obj: element, "Listening on {:?}", saddr);
+
+        let listener = context.enter(|| {
+            Async::<TcpListener>::bind(saddr).map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::OpenRead,
+                    ["Failed to listen on {:?}: {}", saddr, err]
+                )
+            })
+        })?;
+
+        settings.current_port = listener
+            .get_ref()
+            .local_addr()
+            .map(|addr| addr.port() as i32)
+            .unwrap_or(0);
+
+        self.src_pad_handler.prepare(settings.caps.clone());
+
+        let framing = settings.framing;
+        let blocksize = settings.blocksize as usize;
+        drop(settings);
+
+        self.task
+            .prepare(
+                TcpServerSrcTask::new(
+                    element,
+                    &self.src_pad,
+                    &self.src_pad_handler,
+                    listener,
+                    framing,
+                    blocksize,
+                ),
+                context,
+            )
+            .map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::OpenRead,
+                    ["Error preparing Task: {:?}", err]
+                )
+            })?;
+
+        gst::debug!(CAT, obj: element, "Prepared");
+
+        Ok(())
+    }
+
+    fn unprepare(&self, element: &super::TcpServerSrc) {
+        gst::debug!(CAT, obj: element, "Unpreparing");
+        self.task.unprepare().unwrap();
+        self.settings.lock().unwrap().current_port = 0;
+        gst::debug!(CAT, obj: element, "Unprepared");
+    }
+
+    fn stop(&self, element: &super::TcpServerSrc) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Stopping");
+        self.task.stop()?;
+        gst::debug!(CAT, obj: element, "Stopped");
+        Ok(())
+    }
+
+    fn start(&self, element: &super::TcpServerSrc) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Starting");
+        self.task.start()?;
+        gst::debug!(CAT, obj: element, "Started");
+        Ok(())
+    }
+
+    fn pause(&self, element: &super::TcpServerSrc) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Pausing");
+        self.task.pause()?;
+        gst::debug!(CAT, obj: element, "Paused");
+        Ok(())
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for TcpServerSrc {
+    const NAME: &'static str = "RsTsTcpServerSrc";
+    type Type = super::TcpServerSrc;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        let src_pad_handler = TcpServerSrcPadHandler::default();
+
+        Self {
+            src_pad: PadSrc::new(
+                gst::Pad::from_template(&klass.pad_template("src").unwrap(), Some("src")),
+                src_pad_handler.clone(),
+            ),
+            src_pad_handler,
+            task: Task::default(),
+            settings: StdMutex::new(Settings::default()),
+            client_framings: StdMutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl ObjectImpl for TcpServerSrc {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecString::new(
+                    "context",
+                    "Context",
+                    "Context name to share threads with",
+                    Some(DEFAULT_CONTEXT),
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "context-wait",
+                    "Context Wait",
+                    "Throttle poll loop to run at most once every this many ms",
+                    0,
+                    1000,
+                    DEFAULT_CONTEXT_WAIT.as_millis() as u32,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecString::new(
+                    "host",
+                    "Host",
+                    "The host IP address to listen on",
+                    DEFAULT_HOST,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecInt::new(
+                    "port",
+                    "Port",
+                    "Port to listen on (0 = random available port)",
+                    0,
+                    u16::MAX as i32,
+                    DEFAULT_PORT,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecInt::new(
+                    "current-port",
+                    "Current Port",
+                    "The port number the socket is currently bound to",
+                    0,
+                    u16::MAX as i32,
+                    0,
+                    glib::ParamFlags::READABLE,
+                ),
+                glib::ParamSpecBoxed::new(
+                    "caps",
+                    "Caps",
+                    "Caps to use",
+                    gst::Caps::static_type(),
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "blocksize",
+                    "Blocksize",
+                    "Size in bytes to read per buffer in raw framing",
+                    1,
+                    u32::MAX,
+                    DEFAULT_BLOCKSIZE,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecEnum::new(
+                    "framing",
+                    "Framing",
+                    "How the data received from a client is split into buffers by default, \
+                     see the set-client-framing signal",
+                    TcpFraming::static_type(),
+                    DEFAULT_FRAMING as i32,
+                    glib::ParamFlags::READWRITE,
+                ),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn signals() -> &'static [glib::subclass::Signal] {
+        static SIGNALS: Lazy<Vec<glib::subclass::Signal>> = Lazy::new(|| {
+            vec![
+                glib::subclass::Signal::builder(
+                    "client-connected",
+                    &[String::static_type().into(), i32::static_type().into()],
+                    glib::types::Type::UNIT.into(),
+                )
+                .build(),
+                glib::subclass::Signal::builder(
+                    "client-disconnected",
+                    &[String::static_type().into(), i32::static_type().into()],
+                    glib::types::Type::UNIT.into(),
+                )
+                .build(),
+                /*
+                 * Selects the framing of a client, from a handler
+                 * of the client-connected signal for this client
+                 */
+                glib::subclass::Signal::builder(
+                    "set-client-framing",
+                    &[
+                        String::static_type().into(),
+                        i32::static_type().into(),
+                        TcpFraming::static_type().into(),
+                    ],
+                    glib::types::Type::UNIT.into(),
+                )
+                .action()
+                .class_handler(|_, args| {
+                    let element = args[0].get::<super::TcpServerSrc>().expect("signal arg");
+                    let host = args[1].get::<String>().expect("signal arg");
+                    let port = args[2].get::<i32>().expect("signal arg");
+                    let framing = args[3].get::<TcpFraming>().expect("signal arg");
+
+                    element
+                        .imp()
+                        .set_client_framing(&element, &host, port, framing);
+
+                    None
+                })
+                .build(),
+            ]
+        });
+
+        SIGNALS.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        let mut settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "host" => {
+                settings.host = value.get().expect("type checked upstream");
+            }
+            "port" => {
+                settings.port = value.get().expect("type checked upstream");
+            }
+            "caps" => {
+                settings.caps = value.get().expect("type checked upstream");
+            }
+            "blocksize" => {
+                settings.blocksize = value.get().expect("type checked upstream");
+            }
+            "framing" => {
+                settings.framing = value.get().expect("type checked upstream");
+            }
+            "context" => {
+                settings.context = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream")
+                    .unwrap_or_else(|| DEFAULT_CONTEXT.into());
+            }
+            "context-wait" => {
+                settings.context_wait = Duration::from_millis(
+                    value.get::<u32>().expect("type checked upstream").into(),
+                );
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        let settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "host" => settings.host.to_value(),
+            "port" => settings.port.to_value(),
+            "current-port" => settings.current_port.to_value(),
+            "caps" => settings.caps.to_value(),
+            "blocksize" => settings.blocksize.to_value(),
+            "framing" => settings.framing.to_value(),
+            "context" => settings.context.to_value(),
+            "context-wait" => (settings.context_wait.as_millis() as u32).to_value(),
+            _ => unimplemented!(),
+        }
+    }
+
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(self.src_pad.gst_pad()).unwrap();
+
+        crate::set_element_flags(obj, gst::ElementFlags::SOURCE);
+    }
+}
+
+impl GstObjectImpl for TcpServerSrc {}
+
+impl ElementImpl for TcpServerSrc {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Thread-sharing TCP server source",
+                "Source/Network",
+                "Receives data from TCP clients connecting over the network",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::new_any();
+            let src_pad_template = gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![src_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        match transition {
+            gst::StateChange::NullToReady => {
+                self.prepare(element).map_err(|err| {
+                    element.post_error_message(err);
+                    gst::StateChangeError
+                })?;
+            }
+            gst::StateChange::PlayingToPaused => {
+                self.pause(element).map_err(|_| gst::StateChangeError)?;
+            }
+            gst::StateChange::ReadyToNull => {
+                self.unprepare(element);
+            }
+            _ => (),
+        }
+
+        let mut success = self.parent_change_state(element, transition)?;
+
+        match transition {
+            gst::StateChange::ReadyToPaused => {
+                success = gst::StateChangeSuccess::NoPreroll;
+            }
+            gst::StateChange::PausedToPlaying => {
+                self.start(element).map_err(|_| gst::StateChangeError)?;
+            }
+            gst::StateChange::PlayingToPaused => {
+                success = gst::StateChangeSuccess::NoPreroll;
+            }
+            gst::StateChange::PausedToReady => {
+                self.stop(element).map_err(|_| gst::StateChangeError)?;
+            }
+            _ => (),
+        }
+
+        Ok(success)
+    }
+}
@@ -0,0 +1,982 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use futures::future::BoxFuture;
+use futures::prelude::*;
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+
+use once_cell::sync::Lazy;
+
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+use std::{u32, u64};
+
+use crate::runtime::prelude::*;
+use crate::runtime::{Context, PadSink, PadSinkRef, PadSrc, PadSrcRef, PadSrcWeak, Task};
+
+use crate::dataqueue::{DataQueue, DataQueueItem, DataQueueLeaky};
+
+const DEFAULT_MAX_SIZE_BUFFERS: u32 = 200;
+const DEFAULT_MAX_SIZE_BYTES: u32 = 1024 * 1024;
+const DEFAULT_MAX_SIZE_TIME: gst::ClockTime = gst::ClockTime::SECOND;
+const DEFAULT_LEAKY: DataQueueLeaky = DataQueueLeaky::No;
+const DEFAULT_CONTEXT: &str = "";
+const DEFAULT_CONTEXT_WAIT: Duration = Duration::ZERO;
+
+#[derive(Debug, Clone)]
+struct Settings {
+    max_size_buffers: u32,
+    max_size_bytes: u32,
+    max_size_time: gst::ClockTime,
+    leaky: DataQueueLeaky,
+    context: String,
+    context_wait: Duration,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            max_size_buffers: DEFAULT_MAX_SIZE_BUFFERS,
+            max_size_bytes: DEFAULT_MAX_SIZE_BYTES,
+            max_size_time: DEFAULT_MAX_SIZE_TIME,
+            leaky: DEFAULT_LEAKY,
+            context: DEFAULT_CONTEXT.into(),
+            context_wait: DEFAULT_CONTEXT_WAIT,
+        }
+    }
+}
+
+/// The parts of a src pad branch which are needed by the streaming threads.
+#[derive(Clone, Debug)]
+struct BranchQueue {
+    pad: gst::Pad,
+    task: Task,
+    dataqueue: DataQueue,
+    last_res: Arc<StdMutex<Result<gst::FlowSuccess, gst::FlowError>>>,
+}
+
+#[derive(Debug)]
+struct Branch {
+    src_pad: PadSrc,
+    queue: BranchQueue,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    ts_ctx: Option<Context>,
+    started: bool,
+    pad_serial: u32,
+    branches: Vec<Branch>,
+}
+
+#[derive(Clone)]
+struct TeePadSinkHandler;
+
+impl PadSinkHandler for TeePadSinkHandler {
+    type ElementImpl = Tee;
+
+    fn sink_chain(
+        &self,
+        pad: &PadSinkRef,
+        _tee: &Tee,
+        element: &gst::Element,
+        buffer: gst::Buffer,
+    ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
+        let pad_weak = pad.downgrade();
+        let element = element.clone().downcast::<super::Tee>().unwrap();
+        async move {
+            let pad = pad_weak.upgrade().expect("PadSink no longer exists");
+            gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", buffer);
+            let tee = element.imp();
+            tee.enqueue_item(DataQueueItem::Buffer(buffer)).await?;
+            tee.combined_flow()
+        }
+        .boxed()
+    }
+
+    fn sink_chain_list(
+        &self,
+        pad: &PadSinkRef,
+        _tee: &Tee,
+        element: &gst::Element,
+        list: gst::BufferList,
+    ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
+        let pad_weak = pad.downgrade();
+        let element = element.clone().downcast::<super::Tee>().unwrap();
+        async move {
+            let pad = pad_weak.upgrade().expect("PadSink no longer exists");
+            gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", list);
+            let tee = element.imp();
+            tee.enqueue_item(DataQueueItem::BufferList(list)).await?;
+            tee.combined_flow()
+        }
+        .boxed()
+    }
+
+    fn sink_event(
+        &self,
+        pad: &PadSinkRef,
+        tee: &Tee,
+        element: &gst::Element,
+        event: gst::Event,
+    ) -> bool {
+        use gst::EventView;
+
+        gst::debug!(CAT, obj: pad.gst_pad(), "Handling non-serialized {:?}", event);
+
+        if let EventView::FlushStart(..) = event.view() {
+            for branch in tee.branch_queues() {
+                if let Err(err) = branch.task.flush_start() {
+                    gst::error!(CAT, obj: &branch.pad, "FlushStart failed {:?}", err);
+                    gst::element_error!(
+                        element,
+                        gst::StreamError::Failed,
+                        ("Internal data stream error"),
+                        ["FlushStart failed {:?}", err]
+                    );
+                    return false;
+                }
+            }
+        }
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Forwarding non-serialized {:?}", event);
+        tee.push_event_to_src_pads(event)
+    }
+
+    fn sink_event_serialized(
+        &self,
+        pad: &PadSinkRef,
+        _tee: &Tee,
+        element: &gst::Element,
+        event: gst::Event,
+    ) -> BoxFuture<'static, bool> {
+        use gst::EventView;
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling serialized {:?}", event);
+
+        let pad_weak = pad.downgrade();
+        let element = element.clone().downcast::<super::Tee>().unwrap();
+        async move {
+            let pad = pad_weak.upgrade().expect("PadSink no longer exists");
+            let tee = element.imp();
+
+            if let EventView::FlushStop(..) = event.view() {
+                for branch in tee.branch_queues() {
+                    if let Err(err) = branch.task.flush_stop() {
+                        gst::error!(CAT, obj: &branch.pad, "FlushStop failed {:?}", err);
+                        gst::element_error!(
+                            element,
+                            gst::StreamError::Failed,
+                            ("Internal data stream error"),
+                            ["FlushStop failed {:?}", err]
+                        );
+                        return false;
+                    }
+                }
+            }
+
+            gst::log!(CAT, obj: pad.gst_pad(), "Queuing serialized {:?}", event);
+            tee.enqueue_item(DataQueueItem::Event(event)).await.is_ok()
+        }
+        .boxed()
+    }
+
+    fn sink_query(
+        &self,
+        pad: &PadSinkRef,
+        tee: &Tee,
+        _element: &gst::Element,
+        query: &mut gst::QueryRef,
+    ) -> bool {
+        use gst::QueryViewMut;
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", query);
+
+        if query.is_serialized() {
+            // FIXME: How can we do this?
+            gst::log!(CAT, obj: pad.gst_pad(), "Dropping serialized {:?}", query);
+            return false;
+        }
+
+        let src_pads = tee.src_pads();
+
+        if let QueryViewMut::Caps(q) = query.view_mut() {
+            // Only accept the caps all the linked branches can handle
+            let mut caps = q
+                .filter()
+                .map(|filter| filter.to_owned())
+                .unwrap_or_else(gst::Caps::new_any);
+            for src_pad in src_pads.iter().filter(|src_pad| src_pad.is_linked()) {
+                caps = src_pad.peer_query_caps(Some(&caps));
+            }
+
+            gst::log!(CAT, obj: pad.gst_pad(), "Returning {:?}", caps);
+            q.set_result(&caps);
+            return true;
+        }
+
+        if let QueryViewMut::AcceptCaps(q) = query.view_mut() {
+            let caps = q.caps().to_owned();
+            let res = src_pads
+                .iter()
+                .filter(|src_pad| src_pad.is_linked())
+                .all(|src_pad| src_pad.peer_query_accept_caps(&caps));
+
+            gst::log!(CAT, obj: pad.gst_pad(), "Returning {} for {:?}", res, caps);
+            q.set_result(res);
+            return true;
+        }
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Forwarding {:?}", query);
+        src_pads.iter().any(|src_pad| src_pad.peer_query(query))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct TeePadSrcHandler;
+
+impl TeePadSrcHandler {
+    async fn push_item(pad: &PadSrcRef<'_>, item: DataQueueItem) -> Result<(), gst::FlowError> {
+        match item {
+            DataQueueItem::Buffer(buffer) => {
+                gst::log!(CAT, obj: pad.gst_pad(), "Forwarding {:?}", buffer);
+                pad.push(buffer).await.map(drop)
+            }
+            DataQueueItem::BufferList(list) => {
+                gst::log!(CAT, obj: pad.gst_pad(), "Forwarding {:?}", list);
+                pad.push_list(list).await.map(drop)
+            }
+            DataQueueItem::Event(event) => {
+                gst::log!(CAT, obj: pad.gst_pad(), "Forwarding {:?}", event);
+                pad.push_event(event).await;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl PadSrcHandler for TeePadSrcHandler {
+    type ElementImpl = Tee;
+
+    fn src_event(
+        &self,
+        pad: &PadSrcRef,
+        tee: &Tee,
+        _element: &gst::Element,
+        event: gst::Event,
+    ) -> bool {
+        // Flush events coming from downstream are forwarded upstream
+        // and will get back to the branches through the sink pad
+        gst::log!(CAT, obj: pad.gst_pad(), "Forwarding {:?}", event);
+        tee.sink_pad.gst_pad().push_event(event)
+    }
+
+    fn src_query(
+        &self,
+        pad: &PadSrcRef,
+        tee: &Tee,
+        _element: &gst::Element,
+        query: &mut gst::QueryRef,
+    ) -> bool {
+        use gst::QueryViewMut;
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", query);
+
+        if let QueryViewMut::Scheduling(q) = query.view_mut() {
+            let mut new_query = gst::query::Scheduling::new();
+            let res = tee.sink_pad.gst_pad().peer_query(&mut new_query);
+            if !res {
+                return res;
+            }
+
+            gst::log!(CAT, obj: pad.gst_pad(), "Upstream returned {:?}", new_query);
+
+            let (flags, min, max, align) = new_query.result();
+            q.set(flags, min, max, align);
+            q.add_scheduling_modes(
+                &new_query
+                    .scheduling_modes()
+                    .iter()
+                    .cloned()
+                    .filter(|m| m != &gst::PadMode::Pull)
+                    .collect::<Vec<_>>(),
+            );
+            gst::log!(CAT, obj: pad.gst_pad(), "Returning {:?}", q.query_mut());
+            return true;
+        }
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Forwarding {:?}", query);
+        tee.sink_pad.gst_pad().peer_query(query)
+    }
+}
+
+#[derive(Debug)]
+struct TeeBranchTask {
+    element: super::Tee,
+    src_pad: PadSrcWeak,
+    queue: BranchQueue,
+}
+
+impl TeeBranchTask {
+    fn new(element: &super::Tee, src_pad: &PadSrc, queue: BranchQueue) -> Self {
+        TeeBranchTask {
+            element: element.clone(),
+            src_pad: src_pad.downgrade(),
+            queue,
+        }
+    }
+}
+
+impl TaskImpl for TeeBranchTask {
+    fn start(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.queue.pad, "Starting task");
+
+            let mut last_res = self.queue.last_res.lock().unwrap();
+            self.queue.dataqueue.start();
+            *last_res = Ok(gst::FlowSuccess::Ok);
+
+            gst::log!(CAT, obj: &self.queue.pad, "Task started");
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn iterate(&mut self) -> BoxFuture<'_, Result<(), gst::FlowError>> {
+        async move {
+            let item = match self.queue.dataqueue.next().await {
+                Some(item) => item,
+                None => {
+                    gst::log!(CAT, obj: &self.queue.pad, "DataQueue Stopped");
+                    return Err(gst::FlowError::Flushing);
+                }
+            };
+
+            let pad = self.src_pad.upgrade().expect("PadSrc no longer exists");
+            let res = TeePadSrcHandler::push_item(&pad, item).await;
+            *self.queue.last_res.lock().unwrap() = res.map(|()| gst::FlowSuccess::Ok);
+
+            match res {
+                Ok(()) => {
+                    gst::log!(CAT, obj: &self.queue.pad, "Successfully pushed item");
+                }
+                Err(gst::FlowError::NotLinked) => {
+                    // Other branches might still be linked, keep on draining this one
+                    gst::log!(CAT, obj: &self.queue.pad, "Not linked");
+                    return Ok(());
+                }
+                Err(gst::FlowError::Flushing) => {
+                    gst::debug!(CAT, obj: &self.queue.pad, "Flushing");
+                }
+                Err(gst::FlowError::Eos) => {
+                    gst::debug!(CAT, obj: &self.queue.pad, "EOS");
+                    pad.push_event(gst::event::Eos::new()).await;
+                }
+                Err(err) => {
+                    gst::error!(CAT, obj: &self.queue.pad, "Got error {}", err);
+                    gst::element_error!(
+                        &self.element,
+                        gst::StreamError::Failed,
+                        ("Internal data stream error"),
+                        ["streaming stopped, reason {}", err]
+                    );
+                }
+            }
+
+            res
+        }
+        .boxed()
+    }
+
+    fn stop(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.queue.pad, "Stopping task");
+
+            let mut last_res = self.queue.last_res.lock().unwrap();
+            self.queue.dataqueue.stop();
+            self.queue.dataqueue.clear();
+            *last_res = Err(gst::FlowError::Flushing);
+
+            gst::log!(CAT, obj: &self.queue.pad, "Task stopped");
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn flush_start(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.queue.pad, "Starting task flush");
+
+            let mut last_res = self.queue.last_res.lock().unwrap();
+            self.queue.dataqueue.stop();
+            self.queue.dataqueue.clear();
+            *last_res = Err(gst::FlowError::Flushing);
+
+            gst::log!(CAT, obj: &self.queue.pad, "Task flush started");
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn flush_stop(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.queue.pad, "Stopping task flush");
+
+            let mut last_res = self.queue.last_res.lock().unwrap();
+            self.queue.dataqueue.start();
+            *last_res = Ok(gst::FlowSuccess::Ok);
+
+            gst::log!(CAT, obj: &self.queue.pad, "Task flush stopped");
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+#[derive(Debug)]
+pub struct Tee {
+    sink_pad: PadSink,
+    state: StdMutex<State>,
+    settings: StdMutex<Settings>,
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "ts-tee",
+        gst::DebugColorFlags::empty(),
+        Some("Thread-sharing tee"),
+    )
+});
+
+impl Tee {
+    fn branch_queues(&self) -> Vec<BranchQueue> {
+        let state = self.state.lock().unwrap();
+        state
+            .branches
+            .iter()
+            .map(|branch| branch.queue.clone())
+            .collect()
+    }
+
+    fn src_pads(&self) -> Vec<gst::Pad> {
+        let state = self.state.lock().unwrap();
+        state
+            .branches
+            .iter()
+            .map(|branch| branch.queue.pad.clone())
+            .collect()
+    }
+
+    fn has_src_pad(&self, pad: &gst::Pad) -> bool {
+        let state = self.state.lock().unwrap();
+        state.branches.iter().any(|branch| &branch.queue.pad == pad)
+    }
+
+    fn push_event_to_src_pads(&self, event: gst::Event) -> bool {
+        let src_pads = self.src_pads();
+        if src_pads.is_empty() {
+            return true;
+        }
+
+        // Succeed if at least one branch accepted the event
+        src_pads.iter().fold(false, |res, src_pad| {
+            src_pad.push_event(event.clone()) || res
+        })
+    }
+
+    /* Queues the item on every branch, blocking until each of them
+     * has room for it unless the branch leaks */
+    async fn enqueue_item(&self, item: DataQueueItem) -> Result<(), gst::FlowError> {
+        for branch in self.branch_queues() {
+            if branch.dataqueue.push_wait(item.clone()).await.is_err() {
+                if !self.has_src_pad(&branch.pad) {
+                    // The pad was released while we were waiting
+                    continue;
+                }
+
+                gst::debug!(CAT, obj: &branch.pad, "Flushing");
+                return Err(gst::FlowError::Flushing);
+            }
+        }
+
+        Ok(())
+    }
+
+    /* Succeeds as soon as one of the branches succeeds,
+     * otherwise reports the first meaningful error */
+    fn combined_flow(&self) -> Result<gst::FlowSuccess, gst::FlowError> {
+        let mut res = Err(gst::FlowError::NotLinked);
+        for branch in self.branch_queues() {
+            match *branch.last_res.lock().unwrap() {
+                Ok(success) => return Ok(success),
+                Err(gst::FlowError::NotLinked) => (),
+                Err(err) => {
+                    if res == Err(gst::FlowError::NotLinked) {
+                        res = Err(err);
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    fn prepare_branch(
+        element: &super::Tee,
+        branch: &Branch,
+        ts_ctx: &Context,
+    ) -> Result<(), gst::ErrorMessage> {
+        branch
+            .queue
+            .task
+            .prepare(
+                TeeBranchTask::new(element, &branch.src_pad, branch.queue.clone()),
+                ts_ctx.clone(),
+            )
+            .map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::OpenRead,
+                    ["Error preparing Task: {:?}", err]
+                )
+            })?;
+
+        Ok(())
+    }
+
+    fn stats(&self) -> gst::Structure {
+        let state = self.state.lock().unwrap();
+
+        let mut stats = gst::Structure::new_empty("application/x-ts-tee-stats");
+        for branch in state.branches.iter() {
+            stats.set(
+                branch.queue.pad.name().as_str(),
+                branch.queue.dataqueue.dropped(),
+            );
+        }
+
+        stats
+    }
+
+    fn prepare(&self, element: &super::Tee) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Preparing");
+
+        let settings = self.settings.lock().unwrap().clone();
+
+        let context =
+            Context::acquire(&settings.context, settings.context_wait).map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::OpenRead,
+                    ["Failed to acquire Context: {}", err]
+                )
+            })?;
+
+        let mut state = self.state.lock().unwrap();
+        for branch in state.branches.iter() {
+            Self::prepare_branch(element, branch, &context)?;
+        }
+        state.ts_ctx = Some(context);
+
+        gst::debug!(CAT, obj: element, "Prepared");
+
+        Ok(())
+    }
+
+    fn unprepare(&self, element: &super::Tee) {
+        gst::debug!(CAT, obj: element, "Unpreparing");
+
+        let mut state = self.state.lock().unwrap();
+        for branch in state.branches.iter() {
+            branch.queue.task.unprepare().unwrap();
+        }
+        state.ts_ctx = None;
+
+        gst::debug!(CAT, obj: element, "Unprepared");
+    }
+
+    fn stop(&self, element: &super::Tee) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Stopping");
+
+        let tasks = {
+            let mut state = self.state.lock().unwrap();
+            state.started = false;
+            state
+                .branches
+                .iter()
+                .map(|branch| branch.queue.task.clone())
+                .collect::<Vec<_>>()
+        };
+        for task in tasks {
+            task.stop()?;
+        }
+
+        gst::debug!(CAT, obj: element, "Stopped");
+        Ok(())
+    }
+
+    fn start(&self, element: &super::Tee) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Starting");
+
+        let tasks = {
+            let mut state = self.state.lock().unwrap();
+            state.started = true;
+            state
+                .branches
+                .iter()
+                .map(|branch| branch.queue.task.clone())
+                .collect::<Vec<_>>()
+        };
+        for task in tasks {
+            task.start()?;
+        }
+
+        gst::debug!(CAT, obj: element, "Started");
+        Ok(())
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for Tee {
+    const NAME: &'static str = "RsTsTee";
+    type Type = super::Tee;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        Self {
+            sink_pad: PadSink::new(
+                gst::Pad::from_template(&klass.pad_template("sink").unwrap(), Some("sink")),
+                TeePadSinkHandler,
+            ),
+            state: StdMutex::new(State::default()),
+            settings: StdMutex::new(Settings::default()),
+        }
+    }
+}
+
+impl ObjectImpl for Tee {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecString::new(
+                    "context",
+                    "Context",
+                    "Context name to share threads with",
+                    Some(DEFAULT_CONTEXT),
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "context-wait",
+                    "Context Wait",
+                    "Throttle poll loop to run at most once every this many ms",
+                    0,
+                    1000,
+                    DEFAULT_CONTEXT_WAIT.as_millis() as u32,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "max-size-buffers",
+                    "Max Size Buffers",
+                    "Maximum number of buffers to queue per branch (0=unlimited)",
+                    0,
+                    u32::MAX,
+                    DEFAULT_MAX_SIZE_BUFFERS,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "max-size-bytes",
+                    "Max Size Bytes",
+                    "Maximum number of bytes to queue per branch (0=unlimited)",
+                    0,
+                    u32::MAX,
+                    DEFAULT_MAX_SIZE_BYTES,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt64::new(
+                    "max-size-time",
+                    "Max Size Time",
+                    "Maximum number of nanoseconds to queue per branch (0=unlimited)",
+                    0,
+                    u64::MAX - 1,
+                    DEFAULT_MAX_SIZE_TIME.nseconds(),
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecEnum::new(
+                    "leaky",
+                    "Leaky",
+                    "Where the branches leak buffers when full, if at all",
+                    DataQueueLeaky::static_type(),
+                    DEFAULT_LEAKY as i32,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoxed::new(
+                    "stats",
+                    "Statistics",
+                    "Number of buffers dropped by each branch",
+                    gst::Structure::static_type(),
+                    glib::ParamFlags::READABLE,
+                ),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        let mut settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "max-size-buffers" => {
+                settings.max_size_buffers = value.get().expect("type checked upstream");
+            }
+            "max-size-bytes" => {
+                settings.max_size_bytes = value.get().expect("type checked upstream");
+            }
+            "max-size-time" => {
+                settings.max_size_time =
+                    gst::ClockTime::from_nseconds(value.get().expect("type checked upstream"));
+            }
+            "leaky" => {
+                settings.leaky = value.get().expect("type checked upstream");
+
+                let state = self.state.lock().unwrap();
+                for branch in state.branches.iter() {
+                    branch.queue.dataqueue.set_leaky(settings.leaky);
+                }
+            }
+            "context" => {
+                settings.context = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream")
+                    .unwrap_or_else(|| DEFAULT_CONTEXT.into());
+            }
+            "context-wait" => {
+                settings.context_wait = Duration::from_millis(
+                    value.get::<u32>().expect("type checked upstream").into(),
+                );
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        if pspec.name() == "stats" {
+            return self.stats().to_value();
+        }
+
+        let settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "max-size-buffers" => settings.max_size_buffers.to_value(),
+            "max-size-bytes" => settings.max_size_bytes.to_value(),
+            "max-size-time" => settings.max_size_time.nseconds().to_value(),
+            "leaky" => settings.leaky.to_value(),
+            "context" => settings.context.to_value(),
+            "context-wait" => (settings.context_wait.as_millis() as u32).to_value(),
+            _ => unimplemented!(),
+        }
+    }
+
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(self.sink_pad.gst_pad()).unwrap();
+    }
+}
+
+impl GstObjectImpl for Tee {}
+
+impl ElementImpl for Tee {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Thread-sharing tee",
+                "Generic",
+                "Queues the stream to multiple branches",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::new_any();
+
+            let sink_pad_template = gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            let src_pad_template = gst::PadTemplate::new(
+                "src_%u",
+                gst::PadDirection::Src,
+                gst::PadPresence::Request,
+                &caps,
+            )
+            .unwrap();
+
+            vec![sink_pad_template, src_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        match transition {
+            gst::StateChange::NullToReady => {
+                self.prepare(element).map_err(|err| {
+                    element.post_error_message(err);
+                    gst::StateChangeError
+                })?;
+            }
+            gst::StateChange::PausedToReady => {
+                self.stop(element).map_err(|_| gst::StateChangeError)?;
+            }
+            gst::StateChange::ReadyToNull => {
+                self.unprepare(element);
+            }
+            _ => (),
+        }
+
+        let success = self.parent_change_state(element, transition)?;
+
+        if transition == gst::StateChange::ReadyToPaused {
+            self.start(element).map_err(|_| gst::StateChangeError)?;
+        }
+
+        Ok(success)
+    }
+
+    fn request_new_pad(
+        &self,
+        element: &Self::Type,
+        templ: &gst::PadTemplate,
+        _name: Option<String>,
+        _caps: Option<&gst::Caps>,
+    ) -> Option<gst::Pad> {
+        let settings = self.settings.lock().unwrap().clone();
+        let mut state = self.state.lock().unwrap();
+
+        let src_pad = PadSrc::new(
+            gst::Pad::from_template(templ, Some(format!("src_{}", state.pad_serial).as_str())),
+            TeePadSrcHandler,
+        );
+        state.pad_serial += 1;
+
+        let dataqueue = DataQueue::new(
+            &element.clone().upcast(),
+            src_pad.gst_pad(),
+            if settings.max_size_buffers == 0 {
+                None
+            } else {
+                Some(settings.max_size_buffers)
+            },
+            if settings.max_size_bytes == 0 {
+                None
+            } else {
+                Some(settings.max_size_bytes)
+            },
+            if settings.max_size_time.is_zero() {
+                None
+            } else {
+                Some(settings.max_size_time)
+            },
+        );
+        dataqueue.set_leaky(settings.leaky);
+
+        let branch = Branch {
+            queue: BranchQueue {
+                pad: src_pad.gst_pad().clone(),
+                task: Task::default(),
+                dataqueue,
+                last_res: Arc::new(StdMutex::new(Err(gst::FlowError::Flushing))),
+            },
+            src_pad,
+        };
+
+        if let Some(ts_ctx) = state.ts_ctx.as_ref() {
+            if let Err(err) = Self::prepare_branch(element, &branch, ts_ctx) {
+                gst::error!(CAT, obj: element, "Failed to prepare new branch: {:?}", err);
+                return None;
+            }
+
+            if state.started {
+                if let Err(err) = branch.queue.task.start() {
+                    gst::error!(CAT, obj: element, "Failed to start new branch: {:?}", err);
+                    let _ = branch.queue.task.unprepare();
+                    return None;
+                }
+            }
+        }
+
+        let ret = branch.queue.pad.clone();
+        ret.set_active(true).unwrap();
+
+        // Late branches start with the current stream configuration
+        self.sink_pad.gst_pad().sticky_events_foreach(|event| {
+            let _ = ret.store_sticky_event(event);
+            ControlFlow::Continue(gst::EventForeachAction::Keep)
+        });
+
+        state.branches.push(branch);
+        drop(state);
+
+        element.add_pad(&ret).unwrap();
+
+        Some(ret)
+    }
+
+    fn release_pad(&self, element: &Self::Type, pad: &gst::Pad) {
+        let branch = {
+            let mut state = self.state.lock().unwrap();
+            let idx = state
+                .branches
+                .iter()
+                .position(|branch| &branch.queue.pad == pad)
+                .unwrap();
+            state.branches.remove(idx)
+        };
+
+        // Wake up the sink pad in case it was waiting for room in this branch
+        branch.queue.dataqueue.stop();
+        let _ = branch.queue.task.stop();
+        let _ = branch.queue.task.unprepare();
+        drop(branch);
+
+        element.remove_pad(pad).unwrap();
+    }
+}
@@ -32,10 +32,10 @@ use once_cell::sync::Lazy;
 
 use crate::runtime::prelude::*;
 use crate::runtime::{self, Async, Context, PadSink, PadSinkRef, Task};
-use crate::socket::{wrap_socket, GioSocketWrapper};
+use crate::socket::{parse_multicast_sources, wrap_socket, GioSocketWrapper};
 
 use std::mem;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::net::{IpAddr, SocketAddr, UdpSocket};
 use std::sync::Mutex as StdMutex;
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
@@ -61,6 +61,10 @@ const DEFAULT_QOS_DSCP: i32 = -1;
 const DEFAULT_CLIENTS: &str = "";
 const DEFAULT_CONTEXT: &str = "";
 const DEFAULT_CONTEXT_WAIT: Duration = Duration::ZERO;
+const DEFAULT_BATCH_SEND: bool = false;
+const DEFAULT_GSO: bool = false;
+const DEFAULT_MULTICAST_IFACE: Option<&str> = None;
+const DEFAULT_MULTICAST_SOURCE: Option<&str> = None;
 
 #[derive(Debug, Clone)]
 struct Settings {
@@ -80,6 +84,10 @@ struct Settings {
     qos_dscp: i32,
     context: String,
     context_wait: Duration,
+    batch_send: bool,
+    gso: bool,
+    multicast_iface: Option<String>,
+    multicast_source: Option<String>,
 }
 
 impl Default for Settings {
@@ -101,6 +109,10 @@ impl Default for Settings {
             qos_dscp: DEFAULT_QOS_DSCP,
             context: DEFAULT_CONTEXT.into(),
             context_wait: DEFAULT_CONTEXT_WAIT,
+            batch_send: DEFAULT_BATCH_SEND,
+            gso: DEFAULT_GSO,
+            multicast_iface: DEFAULT_MULTICAST_IFACE.map(Into::into),
+            multicast_source: DEFAULT_MULTICAST_SOURCE.map(Into::into),
         }
     }
 }
@@ -116,6 +128,7 @@ static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
 #[derive(Debug)]
 enum TaskItem {
     Buffer(gst::Buffer),
+    List(gst::BufferList),
     Event(gst::Event),
 }
 
@@ -132,6 +145,7 @@ struct UdpSinkPadHandlerInner {
     clients_to_unconfigure: Vec<SocketAddr>,
     sender: Arc<Mutex<Option<mpsc::Sender<TaskItem>>>>,
     settings: Arc<StdMutex<Settings>>,
+    gso_failed: bool,
 }
 
 impl UdpSinkPadHandlerInner {
@@ -150,6 +164,7 @@ impl UdpSinkPadHandlerInner {
             clients_to_unconfigure: vec![],
             sender: Arc::new(Mutex::new(None)),
             settings,
+            gso_failed: false,
         }
     }
 
@@ -263,85 +278,65 @@ impl UdpSinkPadHandler {
         socket_v6: &mut Option<Async<UdpSocket>>,
         client: &SocketAddr,
     ) -> Result<(), gst::ErrorMessage> {
+        let (socket, wrapper) = match client.ip() {
+            IpAddr::V4(_) => (socket.as_ref(), settings.used_socket.as_ref()),
+            IpAddr::V6(_) => (socket_v6.as_ref(), settings.used_socket_v6.as_ref()),
+        };
+        let (socket, wrapper) = match (socket, wrapper) {
+            (Some(socket), Some(wrapper)) => (socket, wrapper),
+            _ => return Ok(()),
+        };
+
         if client.ip().is_multicast() {
-            match client.ip() {
-                IpAddr::V4(addr) => {
-                    if let Some(socket) = socket.as_mut() {
-                        if settings.auto_multicast {
-                            socket
-                                .as_ref()
-                                .join_multicast_v4(&addr, &Ipv4Addr::new(0, 0, 0, 0))
-                                .map_err(|err| {
-                                    error_msg!(
-                                        gst::ResourceError::OpenWrite,
-                                        ["Failed to join multicast group: {}", err]
-                                    )
-                                })?;
-                        }
-                        if settings.multicast_loop {
-                            socket.as_ref().set_multicast_loop_v4(true).map_err(|err| {
-                                error_msg!(
-                                    gst::ResourceError::OpenWrite,
-                                    ["Failed to set multicast loop: {}", err]
-                                )
-                            })?;
-                        }
-                        socket
-                            .as_ref()
-                            .set_multicast_ttl_v4(settings.ttl_mc)
-                            .map_err(|err| {
-                                error_msg!(
-                                    gst::ResourceError::OpenWrite,
-                                    ["Failed to set multicast ttl: {}", err]
-                                )
-                            })?;
-                    }
-                }
-                IpAddr::V6(addr) => {
-                    if let Some(socket) = socket_v6.as_mut() {
-                        if settings.auto_multicast {
-                            socket.as_ref().join_multicast_v6(&addr, 0).map_err(|err| {
-                                error_msg!(
-                                    gst::ResourceError::OpenWrite,
-                                    ["Failed to join multicast group: {}", err]
-                                )
-                            })?;
-                        }
-                        if settings.multicast_loop {
-                            socket.as_ref().set_multicast_loop_v6(true).map_err(|err| {
-                                error_msg!(
-                                    gst::ResourceError::OpenWrite,
-                                    ["Failed to set multicast loop: {}", err]
-                                )
-                            })?;
-                        }
-                        /* FIXME no API for set_multicast_ttl_v6 ? */
-                    }
-                }
-            }
-        } else {
-            match client.ip() {
-                IpAddr::V4(_) => {
-                    if let Some(socket) = socket.as_mut() {
-                        socket.as_ref().set_ttl(settings.ttl).map_err(|err| {
+            if settings.auto_multicast {
+                for source in parse_multicast_sources(settings.multicast_source.as_deref())? {
+                    wrapper
+                        .join_multicast_group(
+                            client.ip(),
+                            source,
+                            settings.multicast_iface.as_deref(),
+                        )
+                        .map_err(|err| {
                             error_msg!(
                                 gst::ResourceError::OpenWrite,
-                                ["Failed to set unicast ttl: {}", err]
+                                ["Failed to join multicast group: {}", err]
                             )
                         })?;
-                    }
                 }
-                IpAddr::V6(_) => {
-                    if let Some(socket) = socket_v6.as_mut() {
-                        socket.as_ref().set_ttl(settings.ttl).map_err(|err| {
-                            error_msg!(
-                                gst::ResourceError::OpenWrite,
-                                ["Failed to set unicast ttl: {}", err]
-                            )
-                        })?;
-                    }
+            }
+            if let Some(ref iface) = settings.multicast_iface {
+                wrapper.set_multicast_iface(iface).map_err(|err| {
+                    error_msg!(
+                        gst::ResourceError::OpenWrite,
+                        ["Failed to set multicast interface: {}", err]
+                    )
+                })?;
+            }
+            if settings.multicast_loop {
+                match client.ip() {
+                    IpAddr::V4(_) => socket.as_ref().set_multicast_loop_v4(true),
+                    IpAddr::V6(_) => socket.as_ref().set_multicast_loop_v6(true),
                 }
+                .map_err(|err| {
+                    error_msg!(
+                        gst::ResourceError::OpenWrite,
+                        ["Failed to set multicast loop: {}", err]
+                    )
+                })?;
             }
+            wrapper.set_multicast_hops(settings.ttl_mc).map_err(|err| {
+                error_msg!(
+                    gst::ResourceError::OpenWrite,
+                    ["Failed to set multicast ttl: {}", err]
+                )
+            })?;
+        } else {
+            wrapper.set_unicast_hops(settings.ttl).map_err(|err| {
+                error_msg!(
+                    gst::ResourceError::OpenWrite,
+                    ["Failed to set unicast ttl: {}", err]
+                )
+            })?;
         }
 
         Ok(())
@@ -354,38 +349,24 @@ impl UdpSinkPadHandler {
         socket_v6: &mut Option<Async<UdpSocket>>,
         client: &SocketAddr,
     ) -> Result<(), gst::ErrorMessage> {
-        if client.ip().is_multicast() {
-            match client.ip() {
-                IpAddr::V4(addr) => {
-                    if let Some(socket) = socket.as_mut() {
-                        if settings.auto_multicast {
-                            socket
-                                .as_ref()
-                                .leave_multicast_v4(&addr, &Ipv4Addr::new(0, 0, 0, 0))
-                                .map_err(|err| {
-                                    error_msg!(
-                                        gst::ResourceError::OpenWrite,
-                                        ["Failed to join multicast group: {}", err]
-                                    )
-                                })?;
-                        }
-                    }
-                }
-                IpAddr::V6(addr) => {
-                    if let Some(socket) = socket_v6.as_mut() {
-                        if settings.auto_multicast {
-                            socket
-                                .as_ref()
-                                .leave_multicast_v6(&addr, 0)
-                                .map_err(|err| {
-                                    error_msg!(
-                                        gst::ResourceError::OpenWrite,
-                                        ["Failed to join multicast group: {}", err]
-                                    )
-                                })?;
-                        }
-                    }
-                }
+        if !client.ip().is_multicast() || !settings.auto_multicast {
+            return Ok(());
+        }
+
+        let (socket, wrapper) = match client.ip() {
+            IpAddr::V4(_) => (socket.as_ref(), settings.used_socket.as_ref()),
+            IpAddr::V6(_) => (socket_v6.as_ref(), settings.used_socket_v6.as_ref()),
+        };
+        if let (Some(_), Some(wrapper)) = (socket, wrapper) {
+            for source in parse_multicast_sources(settings.multicast_source.as_deref())? {
+                wrapper
+                    .leave_multicast_group(client.ip(), source, settings.multicast_iface.as_deref())
+                    .map_err(|err| {
+                        error_msg!(
+                            gst::ResourceError::OpenWrite,
+                            ["Failed to leave multicast group: {}", err]
+                        )
+                    })?;
             }
         }
 
@@ -395,7 +376,7 @@ impl UdpSinkPadHandler {
     async fn render(
         &self,
         element: &super::UdpSink,
-        buffer: gst::Buffer,
+        buffers: &[gst::Buffer],
     ) -> Result<gst::FlowSuccess, gst::FlowError> {
         let (
             do_sync,
@@ -415,7 +396,9 @@ impl UdpSinkPadHandler {
                 rtime = segment
                     .downcast_ref::<gst::format::Time>()
                     .and_then(|segment| {
-                        segment.to_running_time(buffer.pts()).opt_add(inner.latency)
+                        segment
+                            .to_running_time(buffers[0].pts())
+                            .opt_add(inner.latency)
                     });
             }
 
@@ -473,52 +456,247 @@ impl UdpSinkPadHandler {
             self.sync(element, rtime).await;
         }
 
-        let data = buffer.map_readable().map_err(|_| {
+        let maps = buffers
+            .iter()
+            .map(|buffer| buffer.map_readable())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| {
+                element_error!(
+                    element,
+                    gst::StreamError::Format,
+                    ["Failed to map buffer readable"]
+                );
+
+                gst::FlowError::Error
+            })?;
+        let datas = maps.iter().map(|map| map.as_slice()).collect::<Vec<_>>();
+
+        #[cfg(target_os = "linux")]
+        {
+            if settings.batch_send && datas.len() > 1 {
+                self.send_batched(element, &settings, &socket, &socket_v6, &clients, &datas)
+                    .await?;
+
+                gst::log!(
+                    CAT,
+                    obj: element,
+                    "Sent {} buffers to all clients",
+                    datas.len()
+                );
+
+                return Ok(gst::FlowSuccess::Ok);
+            }
+        }
+
+        for (buffer, data) in buffers.iter().zip(datas) {
+            for client in clients.iter() {
+                let socket = match client.ip() {
+                    IpAddr::V4(_) => &mut socket,
+                    IpAddr::V6(_) => &mut socket_v6,
+                };
+
+                if let Some(socket) = socket.as_mut() {
+                    gst::log!(CAT, obj: element, "Sending to {:?}", &client);
+                    socket.send_to(data, *client).await.map_err(|err| {
+                        element_error!(
+                            element,
+                            gst::StreamError::Failed,
+                            ("I/O error"),
+                            ["streaming stopped, I/O error {}", err]
+                        );
+                        gst::FlowError::Error
+                    })?;
+                } else {
+                    element_error!(
+                        element,
+                        gst::StreamError::Failed,
+                        ("I/O error"),
+                        ["No socket available for sending to {}", client]
+                    );
+                    return Err(gst::FlowError::Error);
+                }
+            }
+
+            gst::log!(
+                CAT,
+                obj: element,
+                "Sent buffer {:?} to all clients",
+                buffer
+            );
+        }
+
+        Ok(gst::FlowSuccess::Ok)
+    }
+
+    /// Sends all `datas` to all `clients` with as few system calls as possible,
+    /// using UDP GSO if enabled and `sendmmsg()` otherwise.
+    #[cfg(target_os = "linux")]
+    async fn send_batched(
+        &self,
+        element: &super::UdpSink,
+        settings: &Settings,
+        socket: &Option<Async<UdpSocket>>,
+        socket_v6: &Option<Async<UdpSocket>>,
+        clients: &[SocketAddr],
+        datas: &[&[u8]],
+    ) -> Result<(), gst::FlowError> {
+        let io_error = |err: std::io::Error| {
             element_error!(
                 element,
-                gst::StreamError::Format,
-                ["Failed to map buffer readable"]
+                gst::StreamError::Failed,
+                ("I/O error"),
+                ["streaming stopped, I/O error {}", err]
             );
-
             gst::FlowError::Error
-        })?;
+        };
 
-        for client in clients.iter() {
-            let socket = match client.ip() {
-                IpAddr::V4(_) => &mut socket,
-                IpAddr::V6(_) => &mut socket_v6,
-            };
+        for (socket, is_ipv6) in [(socket, false), (socket_v6, true)] {
+            let clients = clients
+                .iter()
+                .filter(|client| client.is_ipv6() == is_ipv6)
+                .copied()
+                .collect::<Vec<_>>();
+            if clients.is_empty() {
+                continue;
+            }
 
-            if let Some(socket) = socket.as_mut() {
-                gst::log!(CAT, obj: element, "Sending to {:?}", &client);
-                socket.send_to(&data, *client).await.map_err(|err| {
+            let socket = match socket.as_ref() {
+                Some(socket) => socket,
+                None => {
                     element_error!(
                         element,
                         gst::StreamError::Failed,
                         ("I/O error"),
-                        ["streaming stopped, I/O error {}", err]
+                        ["No socket available for sending to {}", clients[0]]
                     );
-                    gst::FlowError::Error
-                })?;
+                    return Err(gst::FlowError::Error);
+                }
+            };
+
+            let packets = if settings.gso && !self.0.read().unwrap().gso_failed {
+                match Self::send_gso(socket, &clients, datas).await {
+                    Ok(()) => continue,
+                    Err((err, unsent)) => {
+                        // Only the packets GSO didn't send yet are sent again
+                        gst::element_warning!(
+                            element,
+                            gst::ResourceError::Write,
+                            ["UDP GSO not usable, falling back to sendmmsg(): {}", err]
+                        );
+                        self.0.write().unwrap().gso_failed = true;
+
+                        unsent
+                    }
+                }
             } else {
-                element_error!(
-                    element,
-                    gst::StreamError::Failed,
-                    ("I/O error"),
-                    ["No socket available for sending to {}", client]
-                );
-                return Err(gst::FlowError::Error);
+                Self::packets(datas, &clients)
+            };
+
+            gst::log!(
+                CAT,
+                obj: element,
+                "Sending {} packets with sendmmsg()",
+                packets.len()
+            );
+
+            let mut sent = 0;
+            while sent < packets.len() {
+                let count = socket
+                    .write_with(|socket| crate::socket::send_mmsg(socket, &packets[sent..]))
+                    .await
+                    .map_err(io_error)?;
+                sent += count;
             }
         }
 
-        gst::log!(
-            CAT,
-            obj: element,
-            "Sent buffer {:?} to all clients",
-            &buffer
-        );
+        Ok(())
+    }
+
+    /// Pairs each of the `datas` with each of the `clients`, in sending order.
+    #[cfg(target_os = "linux")]
+    fn packets<'a>(datas: &[&'a [u8]], clients: &[SocketAddr]) -> Vec<(&'a [u8], SocketAddr)> {
+        datas
+            .iter()
+            .flat_map(|data| clients.iter().map(move |client| (*data, *client)))
+            .collect()
+    }
 
-        Ok(gst::FlowSuccess::Ok)
+    /// Sends `datas` to each of the `clients` by groups of equally sized
+    /// packets, each group handed to the kernel at once for segmentation.
+    ///
+    /// On error, returns the packets which were not sent yet.
+    #[cfg(target_os = "linux")]
+    #[allow(clippy::type_complexity)]
+    async fn send_gso<'a>(
+        socket: &Async<UdpSocket>,
+        clients: &[SocketAddr],
+        datas: &[&'a [u8]],
+    ) -> Result<(), (std::io::Error, Vec<(&'a [u8], SocketAddr)>)> {
+        use crate::socket::{GSO_MAX_SEGMENTS, GSO_MAX_SIZE};
+
+        // Packets of `group` from `clients[client_idx..]` on, then all later packets
+        let unsent = |group: &[&'a [u8]], client_idx: usize, rest: &[&'a [u8]]| {
+            let mut packets = group
+                .iter()
+                .flat_map(|data| {
+                    clients[client_idx..]
+                        .iter()
+                        .map(move |client| (*data, *client))
+                })
+                .collect::<Vec<_>>();
+            packets.extend(Self::packets(rest, clients));
+            packets
+        };
+
+        let mut remaining = datas;
+        while !remaining.is_empty() {
+            // All segments of a group must have the size of the first one,
+            // except for the last one which may be shorter
+            let segment_size = remaining[0].len();
+            let mut count = 1;
+            let mut size = segment_size;
+            while count < remaining.len()
+                && count < GSO_MAX_SEGMENTS
+                && size + remaining[count].len() <= GSO_MAX_SIZE
+                && remaining[count].len() <= segment_size
+            {
+                size += remaining[count].len();
+                count += 1;
+                if remaining[count - 1].len() < segment_size {
+                    break;
+                }
+            }
+
+            let (group, rest) = remaining.split_at(count);
+            remaining = rest;
+
+            if group.len() == 1 || segment_size == 0 || segment_size > u16::MAX as usize {
+                for (i, data) in group.iter().enumerate() {
+                    for (j, client) in clients.iter().enumerate() {
+                        if let Err(err) = socket.send_to(data, *client).await {
+                            let mut packets = unsent(&group[i..=i], j, &group[i + 1..]);
+                            packets.extend(Self::packets(rest, clients));
+                            return Err((err, packets));
+                        }
+                    }
+                }
+                continue;
+            }
+
+            let data = group.concat();
+            for (j, client) in clients.iter().enumerate() {
+                if let Err(err) = socket
+                    .write_with(|socket| {
+                        crate::socket::send_gso(socket, &data, segment_size as u16, *client)
+                    })
+                    .await
+                {
+                    return Err((err, unsent(group, j, rest)));
+                }
+            }
+        }
+
+        Ok(())
     }
 
     /* Wait until specified time */
@@ -585,11 +763,24 @@ impl PadSinkHandler for UdpSinkPadHandler {
         element: &gst::Element,
         list: gst::BufferList,
     ) -> BoxFuture<'static, Result<gst::FlowSuccess, gst::FlowError>> {
-        let sender = Arc::clone(&self.0.read().unwrap().sender);
+        let (sender, batch_send) = {
+            let inner = self.0.read().unwrap();
+            let batch_send = inner.settings.lock().unwrap().batch_send;
+            (Arc::clone(&inner.sender), batch_send)
+        };
         let element = element.clone().downcast::<super::UdpSink>().unwrap();
 
         async move {
             if let Some(sender) = sender.lock().await.as_mut() {
+                if batch_send {
+                    if sender.send(TaskItem::List(list)).await.is_err() {
+                        gst::debug!(CAT, obj: &element, "Flushing");
+                        return Err(gst::FlowError::Flushing);
+                    }
+
+                    return Ok(gst::FlowSuccess::Ok);
+                }
+
                 for buffer in list.iter_owned() {
                     if sender.send(TaskItem::Buffer(buffer)).await.is_err() {
                         gst::debug!(CAT, obj: &element, "Flushing");
@@ -658,6 +849,21 @@ impl UdpSinkTask {
             receiver: None,
         }
     }
+
+    async fn render(&self, buffers: &[gst::Buffer]) -> Result<(), gst::FlowError> {
+        match self.sink_pad_handler.render(&self.element, buffers).await {
+            Err(err) => {
+                element_error!(
+                    &self.element,
+                    gst::StreamError::Failed,
+                    ["Failed to render item, stopping task: {}", err]
+                );
+
+                Err(gst::FlowError::Error)
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 impl TaskImpl for UdpSinkTask {
@@ -681,19 +887,10 @@ impl TaskImpl for UdpSinkTask {
     fn iterate(&mut self) -> BoxFuture<'_, Result<(), gst::FlowError>> {
         async move {
             match self.receiver.as_mut().unwrap().next().await {
-                Some(TaskItem::Buffer(buffer)) => {
-                    match self.sink_pad_handler.render(&self.element, buffer).await {
-                        Err(err) => {
-                            element_error!(
-                                &self.element,
-                                gst::StreamError::Failed,
-                                ["Failed to render item, stopping task: {}", err]
-                            );
-
-                            Err(gst::FlowError::Error)
-                        }
-                        _ => Ok(()),
-                    }
+                Some(TaskItem::Buffer(buffer)) => self.render(std::slice::from_ref(&buffer)).await,
+                Some(TaskItem::List(list)) => {
+                    let buffers = list.iter_owned().collect::<Vec<_>>();
+                    self.render(&buffers).await
                 }
                 Some(TaskItem::Event(event)) => {
                     self.sink_pad_handler
@@ -1083,7 +1280,7 @@ impl ObjectImpl for UdpSink {
                 glib::ParamSpecUInt::new(
                     "ttl-mc",
                     "Time To Live Multicast",
-                    "Used for setting the multicast TTL parameter",
+                    "Used for setting the multicast TTL parameter, or hop limit for IPv6",
                     0,
                     u8::MAX as u32,
                     DEFAULT_TTL_MC,
@@ -1098,6 +1295,35 @@ impl ObjectImpl for UdpSink {
                     DEFAULT_QOS_DSCP,
                     glib::ParamFlags::READWRITE,
                 ),
+                glib::ParamSpecBoolean::new(
+                    "batch-send",
+                    "Batch Send",
+                    "Send buffer lists to all clients at once with sendmmsg() (Linux only), \
+                        a list is synchronized on the timestamp of its first buffer",
+                    DEFAULT_BATCH_SEND,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "gso",
+                    "GSO",
+                    "Use UDP generic segmentation offload for batched sends of equally sized buffers (Linux only, requires batch-send)",
+                    DEFAULT_GSO,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecString::new(
+                    "multicast-iface",
+                    "Multicast Interface",
+                    "The single network interface on which to send and join multicast, unlike the list accepted by ts-udpsrc (None = default)",
+                    DEFAULT_MULTICAST_IFACE,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecString::new(
+                    "multicast-source",
+                    "Multicast Source",
+                    "Comma separated list of sources to join the multicast groups for with auto-multicast, using source-specific multicast (None = any source)",
+                    DEFAULT_MULTICAST_SOURCE,
+                    glib::ParamFlags::READWRITE,
+                ),
                 glib::ParamSpecString::new(
                     "clients",
                     "Clients",
@@ -1232,6 +1458,18 @@ impl ObjectImpl for UdpSink {
             "qos-dscp" => {
                 settings.qos_dscp = value.get().expect("type checked upstream");
             }
+            "batch-send" => {
+                settings.batch_send = value.get().expect("type checked upstream");
+            }
+            "gso" => {
+                settings.gso = value.get().expect("type checked upstream");
+            }
+            "multicast-iface" => {
+                settings.multicast_iface = value.get().expect("type checked upstream");
+            }
+            "multicast-source" => {
+                settings.multicast_source = value.get().expect("type checked upstream");
+            }
             "clients" => {
                 let clients = value
                     .get::<Option<String>>()
@@ -1305,6 +1543,10 @@ impl ObjectImpl for UdpSink {
             "ttl" => settings.ttl.to_value(),
             "ttl-mc" => settings.ttl_mc.to_value(),
             "qos-dscp" => settings.qos_dscp.to_value(),
+            "batch-send" => settings.batch_send.to_value(),
+            "gso" => settings.gso.to_value(),
+            "multicast-iface" => settings.multicast_iface.to_value(),
+            "multicast-source" => settings.multicast_source.to_value(),
             "clients" => {
                 drop(settings);
 
@@ -39,7 +39,9 @@ use std::u16;
 use crate::runtime::prelude::*;
 use crate::runtime::{Async, Context, PadSrc, PadSrcRef, PadSrcWeak, Task};
 
-use crate::socket::{wrap_socket, GioSocketWrapper, Socket, SocketError, SocketRead};
+use crate::socket::{
+    parse_multicast_sources, wrap_socket, GioSocketWrapper, Socket, SocketError, SocketRead,
+};
 
 const DEFAULT_ADDRESS: Option<&str> = Some("0.0.0.0");
 const DEFAULT_PORT: i32 = 5000;
@@ -51,6 +53,9 @@ const DEFAULT_USED_SOCKET: Option<GioSocketWrapper> = None;
 const DEFAULT_CONTEXT: &str = "";
 const DEFAULT_CONTEXT_WAIT: Duration = Duration::ZERO;
 const DEFAULT_RETRIEVE_SENDER_ADDRESS: bool = true;
+const DEFAULT_BATCH_SIZE: u32 = 1;
+const DEFAULT_MULTICAST_IFACE: Option<&str> = None;
+const DEFAULT_MULTICAST_SOURCE: Option<&str> = None;
 
 #[derive(Debug, Clone)]
 struct Settings {
@@ -64,6 +69,9 @@ struct Settings {
     context: String,
     context_wait: Duration,
     retrieve_sender_address: bool,
+    batch_size: u32,
+    multicast_iface: Option<String>,
+    multicast_source: Option<String>,
 }
 
 impl Default for Settings {
@@ -79,6 +87,9 @@ impl Default for Settings {
             context: DEFAULT_CONTEXT.into(),
             context_wait: DEFAULT_CONTEXT_WAIT,
             retrieve_sender_address: DEFAULT_RETRIEVE_SENDER_ADDRESS,
+            batch_size: DEFAULT_BATCH_SIZE,
+            multicast_iface: DEFAULT_MULTICAST_IFACE.map(Into::into),
+            multicast_source: DEFAULT_MULTICAST_SOURCE.map(Into::into),
         }
     }
 }
@@ -107,6 +118,25 @@ impl SocketRead for UdpReader {
         }
         .boxed()
     }
+
+    #[cfg(target_os = "linux")]
+    fn read_batch<'buf>(
+        &'buf mut self,
+        buffers: &'buf mut [&'buf mut [u8]],
+    ) -> BoxFuture<'buf, io::Result<Vec<(usize, Option<std::net::SocketAddr>)>>> {
+        async move {
+            let packets = self
+                .0
+                .read_with(|socket| crate::socket::recv_mmsg(socket, buffers))
+                .await?;
+
+            Ok(packets
+                .into_iter()
+                .map(|(read_size, saddr)| (read_size, Some(saddr)))
+                .collect())
+        }
+        .boxed()
+    }
 }
 
 #[derive(Debug)]
@@ -193,6 +223,19 @@ impl UdpSrcPadHandler {
 
         pad.push(buffer).await
     }
+
+    async fn push_list(
+        &self,
+        pad: &PadSrcRef<'_>,
+        element: &super::UdpSrc,
+        list: gst::BufferList,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", list);
+
+        self.push_prelude(pad, element).await;
+
+        pad.push_list(list).await
+    }
 }
 
 impl PadSrcHandler for UdpSrcPadHandler {
@@ -280,6 +323,7 @@ struct UdpSrcTask {
     src_pad: PadSrcWeak,
     src_pad_handler: UdpSrcPadHandler,
     socket: Socket<UdpReader>,
+    batch_size: usize,
 }
 
 impl UdpSrcTask {
@@ -288,12 +332,14 @@ impl UdpSrcTask {
         src_pad: &PadSrc,
         src_pad_handler: &UdpSrcPadHandler,
         socket: Socket<UdpReader>,
+        batch_size: u32,
     ) -> Self {
         UdpSrcTask {
             element: element.clone(),
             src_pad: src_pad.downgrade(),
             src_pad_handler: src_pad_handler.clone(),
             socket,
+            batch_size: batch_size as usize,
         }
     }
 }
@@ -312,10 +358,17 @@ impl TaskImpl for UdpSrcTask {
 
     fn iterate(&mut self) -> BoxFuture<'_, Result<(), gst::FlowError>> {
         async move {
-            let item = self.socket.next().await;
+            let item = if self.batch_size > 1 {
+                self.socket.next_list(self.batch_size).await
+            } else {
+                self.socket
+                    .next()
+                    .await
+                    .map(|res| res.map(|item| vec![item]))
+            };
 
-            let (mut buffer, saddr) = match item {
-                Some(Ok((buffer, saddr))) => (buffer, saddr),
+            let mut buffers = match item {
+                Some(Ok(buffers)) => buffers,
                 Some(Err(err)) => {
                     gst::error!(CAT, obj: &self.element, "Got error {:?}", err);
                     match err {
@@ -344,27 +397,43 @@ impl TaskImpl for UdpSrcTask {
                 }
             };
 
-            if let Some(saddr) = saddr {
-                if self
-                    .src_pad_handler
-                    .0
-                    .state
-                    .lock()
-                    .await
-                    .retrieve_sender_address
-                {
-                    NetAddressMeta::add(
-                        buffer.get_mut().unwrap(),
-                        &gio::InetSocketAddress::from(saddr),
-                    );
+            if self
+                .src_pad_handler
+                .0
+                .state
+                .lock()
+                .await
+                .retrieve_sender_address
+            {
+                for (buffer, saddr) in buffers.iter_mut() {
+                    if let Some(saddr) = saddr {
+                        NetAddressMeta::add(
+                            buffer.get_mut().unwrap(),
+                            &gio::InetSocketAddress::from(*saddr),
+                        );
+                    }
                 }
             }
 
             let pad = self.src_pad.upgrade().expect("PadSrc no longer exists");
-            let res = self
-                .src_pad_handler
-                .push_buffer(&pad, &self.element, buffer)
-                .await;
+            let res = if buffers.len() == 1 {
+                let (buffer, _) = buffers.pop().unwrap();
+                self.src_pad_handler
+                    .push_buffer(&pad, &self.element, buffer)
+                    .await
+            } else {
+                let mut list = gst::BufferList::new_sized(buffers.len());
+                {
+                    let list = list.get_mut().unwrap();
+                    for (buffer, _) in buffers {
+                        list.add(buffer);
+                    }
+                }
+
+                self.src_pad_handler
+                    .push_list(&pad, &self.element, list)
+                    .await
+            };
             match res {
                 Ok(_) => gst::log!(CAT, obj: &self.element, "Successfully pushed buffer"),
                 Err(gst::FlowError::Flushing) => gst::debug!(CAT, obj: &self.element, "Flushing"),
@@ -562,13 +631,29 @@ impl UdpSrc {
                 })
             })?;
 
+            let wrapper = wrap_socket(&socket)?;
+
             if addr.is_multicast() {
-                // TODO: Multicast interface configuration, going to be tricky
-                match addr {
-                    IpAddr::V4(addr) => {
-                        socket
-                            .as_ref()
-                            .join_multicast_v4(&addr, &Ipv4Addr::new(0, 0, 0, 0))
+                let sources = parse_multicast_sources(settings_guard.multicast_source.as_deref())?;
+                let ifaces = settings_guard
+                    .multicast_iface
+                    .as_deref()
+                    .map(|ifaces| ifaces.split(',').map(str::trim).map(Some).collect())
+                    .unwrap_or_else(|| vec![None]);
+
+                for iface in ifaces {
+                    for source in &sources {
+                        gst::debug!(
+                            CAT,
+                            obj: element,
+                            "Joining multicast group {} (source {:?}) on interface {:?}",
+                            addr,
+                            source,
+                            iface
+                        );
+
+                        wrapper
+                            .join_multicast_group(addr, *source, iface)
                             .map_err(|err| {
                                 gst::error_msg!(
                                     gst::ResourceError::OpenRead,
@@ -576,18 +661,10 @@ impl UdpSrc {
                                 )
                             })?;
                     }
-                    IpAddr::V6(addr) => {
-                        socket.as_ref().join_multicast_v6(&addr, 0).map_err(|err| {
-                            gst::error_msg!(
-                                gst::ResourceError::OpenRead,
-                                ["Failed to join multicast group: {}", err]
-                            )
-                        })?;
-                    }
                 }
             }
 
-            settings_guard.used_socket = Some(wrap_socket(&socket)?);
+            settings_guard.used_socket = Some(wrapper);
 
             socket
         };
@@ -631,12 +708,27 @@ impl UdpSrc {
 
         element.notify("used-socket");
 
+        let batch_size = if cfg!(target_os = "linux") {
+            settings.batch_size
+        } else {
+            if settings.batch_size > 1 {
+                gst::warning!(CAT, obj: element, "Batched reception is only supported on Linux");
+            }
+            1
+        };
+
         self.src_pad_handler
             .prepare(settings.caps, settings.retrieve_sender_address);
 
         self.task
             .prepare(
-                UdpSrcTask::new(element, &self.src_pad, &self.src_pad_handler, socket),
+                UdpSrcTask::new(
+                    element,
+                    &self.src_pad,
+                    &self.src_pad_handler,
+                    socket,
+                    batch_size,
+                ),
                 context,
             )
             .map_err(|err| {
@@ -771,6 +863,29 @@ impl ObjectImpl for UdpSrc {
                     DEFAULT_RETRIEVE_SENDER_ADDRESS,
                     glib::ParamFlags::READWRITE,
                 ),
+                glib::ParamSpecUInt::new(
+                    "batch-size",
+                    "Batch Size",
+                    "Maximum number of packets to read at once with recvmmsg() and push as a buffer list (Linux only, 1 = no batching)",
+                    1,
+                    1024,
+                    DEFAULT_BATCH_SIZE,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecString::new(
+                    "multicast-iface",
+                    "Multicast Interface",
+                    "Comma separated list of network interfaces on which to join the multicast group, unlike the single interface of ts-udpsink (None = default)",
+                    DEFAULT_MULTICAST_IFACE,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecString::new(
+                    "multicast-source",
+                    "Multicast Source",
+                    "Comma separated list of sources to receive the multicast group from, using source-specific multicast (None = any source)",
+                    DEFAULT_MULTICAST_SOURCE,
+                    glib::ParamFlags::READWRITE,
+                ),
             ];
 
             #[cfg(not(windows))]
@@ -844,6 +959,15 @@ impl ObjectImpl for UdpSrc {
             "retrieve-sender-address" => {
                 settings.retrieve_sender_address = value.get().expect("type checked upstream");
             }
+            "batch-size" => {
+                settings.batch_size = value.get().expect("type checked upstream");
+            }
+            "multicast-iface" => {
+                settings.multicast_iface = value.get().expect("type checked upstream");
+            }
+            "multicast-source" => {
+                settings.multicast_source = value.get().expect("type checked upstream");
+            }
             _ => unimplemented!(),
         }
     }
@@ -869,6 +993,9 @@ impl ObjectImpl for UdpSrc {
             "context" => settings.context.to_value(),
             "context-wait" => (settings.context_wait.as_millis() as u32).to_value(),
             "retrieve-sender-address" => settings.retrieve_sender_address.to_value(),
+            "batch-size" => settings.batch_size.to_value(),
+            "multicast-iface" => settings.multicast_iface.to_value(),
+            "multicast-source" => settings.multicast_source.to_value(),
             _ => unimplemented!(),
         }
     }
@@ -0,0 +1,835 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use futures::future::BoxFuture;
+use futures::prelude::*;
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+
+use once_cell::sync::Lazy;
+
+use rand::Rng;
+
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+use std::{i32, u32};
+
+use crate::runtime::prelude::*;
+use crate::runtime::{self, Context, PadSrc, PadSrcRef, PadSrcWeak, Task, Timer};
+
+const DEFAULT_CONTEXT: &str = "";
+const DEFAULT_CONTEXT_WAIT: Duration = Duration::ZERO;
+const DEFAULT_PATTERN: VideoTestSrcPattern = VideoTestSrcPattern::Bars;
+const DEFAULT_IS_LIVE: bool = true;
+const DEFAULT_DO_TIMESTAMP: bool = false;
+const DEFAULT_NUM_BUFFERS: i32 = -1;
+
+const DEFAULT_WIDTH: i32 = 320;
+const DEFAULT_HEIGHT: i32 = 240;
+const DEFAULT_FPS_N: i32 = 30;
+const DEFAULT_FPS_D: i32 = 1;
+
+// 75% color bars, left to right
+const BARS: [[u8; 4]; 7] = [
+    [191, 191, 191, 255],
+    [191, 191, 0, 255],
+    [0, 191, 191, 255],
+    [0, 191, 0, 255],
+    [191, 0, 191, 255],
+    [191, 0, 0, 255],
+    [0, 0, 191, 255],
+];
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, glib::Enum)]
+#[repr(u32)]
+#[enum_type(name = "GstTsVideoTestSrcPattern")]
+enum VideoTestSrcPattern {
+    #[enum_value(name = "Color bars", nick = "bars")]
+    Bars = 0,
+    #[enum_value(name = "Black", nick = "black")]
+    Black = 1,
+    #[enum_value(name = "White", nick = "white")]
+    White = 2,
+    #[enum_value(name = "Random gray noise", nick = "snow")]
+    Snow = 3,
+}
+
+#[derive(Debug, Clone)]
+struct Settings {
+    context: String,
+    context_wait: Duration,
+    pattern: VideoTestSrcPattern,
+    is_live: bool,
+    do_timestamp: bool,
+    num_buffers: i32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            context: DEFAULT_CONTEXT.into(),
+            context_wait: DEFAULT_CONTEXT_WAIT,
+            pattern: DEFAULT_PATTERN,
+            is_live: DEFAULT_IS_LIVE,
+            do_timestamp: DEFAULT_DO_TIMESTAMP,
+            num_buffers: DEFAULT_NUM_BUFFERS,
+        }
+    }
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "ts-videotestsrc",
+        gst::DebugColorFlags::empty(),
+        Some("Thread-sharing video test source"),
+    )
+});
+
+#[derive(Debug, Clone, Copy)]
+struct VideoFormat {
+    width: u32,
+    height: u32,
+    fps_n: u64,
+    fps_d: u64,
+}
+
+#[derive(Debug, Default)]
+struct VideoTestSrcPadHandlerInner {
+    configured_caps: StdMutex<Option<gst::Caps>>,
+    latency: StdMutex<Option<gst::ClockTime>>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct VideoTestSrcPadHandler(Arc<VideoTestSrcPadHandlerInner>);
+
+impl VideoTestSrcPadHandler {
+    fn reset(&self) {
+        *self.0.configured_caps.lock().unwrap() = None;
+        *self.0.latency.lock().unwrap() = None;
+    }
+}
+
+impl PadSrcHandler for VideoTestSrcPadHandler {
+    type ElementImpl = VideoTestSrc;
+
+    fn src_event(
+        &self,
+        pad: &PadSrcRef,
+        videotestsrc: &VideoTestSrc,
+        _element: &gst::Element,
+        event: gst::Event,
+    ) -> bool {
+        use gst::EventView;
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", event);
+
+        let ret = match event.view() {
+            EventView::FlushStart(..) => videotestsrc.task.flush_start().is_ok(),
+            EventView::FlushStop(..) => videotestsrc.task.flush_stop().is_ok(),
+            EventView::Reconfigure(..) => true,
+            EventView::Latency(..) => true,
+            _ => false,
+        };
+
+        if ret {
+            gst::log!(CAT, obj: pad.gst_pad(), "Handled {:?}", event);
+        } else {
+            gst::log!(CAT, obj: pad.gst_pad(), "Didn't handle {:?}", event);
+        }
+
+        ret
+    }
+
+    fn src_query(
+        &self,
+        pad: &PadSrcRef,
+        videotestsrc: &VideoTestSrc,
+        _element: &gst::Element,
+        query: &mut gst::QueryRef,
+    ) -> bool {
+        use gst::QueryViewMut;
+
+        gst::log!(CAT, obj: pad.gst_pad(), "Handling {:?}", query);
+        let ret = match query.view_mut() {
+            QueryViewMut::Latency(q) => {
+                if videotestsrc.settings.lock().unwrap().is_live {
+                    let latency = self
+                        .0
+                        .latency
+                        .lock()
+                        .unwrap()
+                        .unwrap_or(gst::ClockTime::ZERO);
+                    q.set(true, latency, latency);
+                } else {
+                    q.set(false, gst::ClockTime::ZERO, gst::ClockTime::NONE);
+                }
+                true
+            }
+            QueryViewMut::Scheduling(q) => {
+                q.set(gst::SchedulingFlags::SEQUENTIAL, 1, -1, 0);
+                q.add_scheduling_modes(&[gst::PadMode::Push]);
+                true
+            }
+            QueryViewMut::Caps(q) => {
+                let caps = if let Some(caps) = self.0.configured_caps.lock().unwrap().as_ref() {
+                    q.filter()
+                        .map(|f| f.intersect_with_mode(caps, gst::CapsIntersectMode::First))
+                        .unwrap_or_else(|| caps.clone())
+                } else {
+                    let caps = pad.gst_pad().pad_template_caps();
+                    q.filter()
+                        .map(|f| f.intersect_with_mode(&caps, gst::CapsIntersectMode::First))
+                        .unwrap_or(caps)
+                };
+
+                q.set_result(&caps);
+
+                true
+            }
+            _ => false,
+        };
+
+        if ret {
+            gst::log!(CAT, obj: pad.gst_pad(), "Handled {:?}", query);
+        } else {
+            gst::log!(CAT, obj: pad.gst_pad(), "Didn't handle {:?}", query);
+        }
+        ret
+    }
+}
+
+#[derive(Debug)]
+struct VideoTestSrcTask {
+    element: super::VideoTestSrc,
+    src_pad: PadSrcWeak,
+    src_pad_handler: VideoTestSrcPadHandler,
+    need_initial_events: bool,
+    need_segment: bool,
+    format: Option<VideoFormat>,
+    interval: Option<Timer>,
+    start_running_time: Option<gst::ClockTime>,
+    n_frames: u64,
+    n_buffers: u64,
+}
+
+impl VideoTestSrcTask {
+    fn new(
+        element: &super::VideoTestSrc,
+        src_pad: &PadSrc,
+        src_pad_handler: &VideoTestSrcPadHandler,
+    ) -> Self {
+        VideoTestSrcTask {
+            element: element.clone(),
+            src_pad: src_pad.downgrade(),
+            src_pad_handler: src_pad_handler.clone(),
+            need_initial_events: true,
+            need_segment: true,
+            format: None,
+            interval: None,
+            start_running_time: None,
+            n_frames: 0,
+            n_buffers: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.need_initial_events = true;
+        self.need_segment = true;
+        self.format = None;
+        self.interval = None;
+        self.start_running_time = None;
+        self.n_frames = 0;
+        self.n_buffers = 0;
+
+        self.src_pad_handler.reset();
+    }
+
+    async fn negotiate(&mut self, pad: &PadSrcRef<'_>) -> Result<VideoFormat, gst::FlowError> {
+        let templ_caps = pad.gst_pad().pad_template_caps();
+        let mut caps = pad.gst_pad().peer_query_caps(Some(&templ_caps));
+        if caps.is_any() {
+            caps = templ_caps;
+        }
+
+        gst::debug!(CAT, obj: pad.gst_pad(), "Negotiating from {:?}", caps);
+
+        if caps.is_empty() {
+            gst::element_error!(
+                &self.element,
+                gst::CoreError::Negotiation,
+                ["No supported caps downstream"]
+            );
+            return Err(gst::FlowError::NotNegotiated);
+        }
+
+        caps.truncate();
+        {
+            let caps = caps.make_mut();
+            let s = caps.structure_mut(0).unwrap();
+            s.fixate_field_nearest_int("width", DEFAULT_WIDTH);
+            s.fixate_field_nearest_int("height", DEFAULT_HEIGHT);
+            s.fixate_field_nearest_fraction(
+                "framerate",
+                gst::Fraction::new(DEFAULT_FPS_N, DEFAULT_FPS_D),
+            );
+        }
+        caps.fixate();
+
+        let format = {
+            let s = caps.structure(0).unwrap();
+            let width = s
+                .get::<i32>("width")
+                .map_err(|_| gst::FlowError::NotNegotiated)?;
+            let height = s
+                .get::<i32>("height")
+                .map_err(|_| gst::FlowError::NotNegotiated)?;
+            let framerate = s
+                .get::<gst::Fraction>("framerate")
+                .map_err(|_| gst::FlowError::NotNegotiated)?;
+
+            VideoFormat {
+                width: width as u32,
+                height: height as u32,
+                fps_n: framerate.numer() as u64,
+                fps_d: framerate.denom() as u64,
+            }
+        };
+
+        gst::debug!(CAT, obj: pad.gst_pad(), "Configuring {:?}", caps);
+
+        pad.push_event(gst::event::Caps::new(&caps)).await;
+        *self.src_pad_handler.0.configured_caps.lock().unwrap() = Some(caps);
+
+        Ok(format)
+    }
+
+    async fn push_prelude(&mut self, pad: &PadSrcRef<'_>) -> Result<VideoFormat, gst::FlowError> {
+        if self.need_initial_events {
+            gst::debug!(CAT, obj: pad.gst_pad(), "Pushing initial events");
+
+            let stream_id = format!("{:08x}{:08x}", rand::random::<u32>(), rand::random::<u32>());
+            let stream_start_evt = gst::event::StreamStart::builder(&stream_id)
+                .group_id(gst::GroupId::next())
+                .build();
+            pad.push_event(stream_start_evt).await;
+
+            self.format = Some(self.negotiate(pad).await?);
+            self.need_initial_events = false;
+        }
+
+        if self.need_segment {
+            let segment_evt =
+                gst::event::Segment::new(&gst::FormattedSegment::<gst::format::Time>::new());
+            pad.push_event(segment_evt).await;
+
+            self.need_segment = false;
+        }
+
+        Ok(self.format.expect("negotiated"))
+    }
+
+    fn frame_duration(format: VideoFormat) -> gst::ClockTime {
+        gst::ClockTime::SECOND
+            .mul_div_floor(format.fps_d, format.fps_n)
+            .unwrap()
+    }
+
+    fn fill(data: &mut [u8], format: VideoFormat, pattern: VideoTestSrcPattern) {
+        let stride = format.width as usize * 4;
+
+        match pattern {
+            VideoTestSrcPattern::Bars => {
+                let mut line = vec![0u8; stride];
+                for (x, pixel) in line.chunks_exact_mut(4).enumerate() {
+                    let bar = x * BARS.len() / format.width as usize;
+                    pixel.copy_from_slice(&BARS[bar]);
+                }
+
+                for row in data.chunks_exact_mut(stride) {
+                    row.copy_from_slice(&line);
+                }
+            }
+            VideoTestSrcPattern::Black => {
+                for pixel in data.chunks_exact_mut(4) {
+                    pixel.copy_from_slice(&[0, 0, 0, 255]);
+                }
+            }
+            VideoTestSrcPattern::White => data.fill(255),
+            VideoTestSrcPattern::Snow => {
+                let mut rng = rand::thread_rng();
+                for pixel in data.chunks_exact_mut(4) {
+                    let value = rng.gen::<u8>();
+                    pixel.copy_from_slice(&[value, value, value, 255]);
+                }
+            }
+        }
+    }
+
+    fn generate(&mut self, format: VideoFormat, settings: &Settings) -> gst::Buffer {
+        let size = format.width as usize * format.height as usize * 4;
+
+        let mut buffer = gst::Buffer::with_size(size).unwrap();
+        {
+            let buffer = buffer.get_mut().unwrap();
+
+            let mut map = buffer.map_writable().unwrap();
+            Self::fill(&mut map, format, settings.pattern);
+            drop(map);
+
+            let start = self.start_running_time.unwrap_or(gst::ClockTime::ZERO);
+            let pts = start
+                + gst::ClockTime::SECOND
+                    .mul_div_floor(self.n_frames * format.fps_d, format.fps_n)
+                    .unwrap();
+            let next_pts = start
+                + gst::ClockTime::SECOND
+                    .mul_div_floor((self.n_frames + 1) * format.fps_d, format.fps_n)
+                    .unwrap();
+
+            if settings.do_timestamp {
+                buffer.set_pts(self.element.current_running_time().or(Some(pts)));
+            } else {
+                buffer.set_pts(pts);
+            }
+            buffer.set_duration(next_pts - pts);
+            buffer.set_offset(self.n_frames);
+            buffer.set_offset_end(self.n_frames + 1);
+
+            if self.n_buffers == 0 {
+                buffer.set_flags(gst::BufferFlags::DISCONT);
+            }
+        }
+
+        self.n_frames += 1;
+        self.n_buffers += 1;
+
+        buffer
+    }
+}
+
+impl TaskImpl for VideoTestSrcTask {
+    fn start(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Starting task");
+
+            // Pacing restarts from now when resuming after a pause
+            self.interval = None;
+
+            gst::log!(CAT, obj: &self.element, "Task started");
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn iterate(&mut self) -> BoxFuture<'_, Result<(), gst::FlowError>> {
+        async move {
+            let pad = self.src_pad.upgrade().expect("PadSrc no longer exists");
+            let format = self.push_prelude(&pad).await?;
+
+            let settings = self.element.imp().settings.lock().unwrap().clone();
+
+            if settings.num_buffers >= 0 && self.n_buffers >= settings.num_buffers as u64 {
+                gst::debug!(CAT, obj: &self.element, "Reached num-buffers, pushing EOS");
+                pad.push_event(gst::event::Eos::new()).await;
+                return Err(gst::FlowError::Eos);
+            }
+
+            if settings.is_live {
+                if self.start_running_time.is_none() {
+                    self.start_running_time = self.element.current_running_time();
+                }
+
+                if self.interval.is_none() {
+                    let period = Self::frame_duration(format);
+
+                    let prev_latency = self
+                        .src_pad_handler
+                        .0
+                        .latency
+                        .lock()
+                        .unwrap()
+                        .replace(period);
+                    if prev_latency != Some(period) {
+                        let _ = self.element.post_message(
+                            gst::message::Latency::builder().src(&self.element).build(),
+                        );
+                    }
+
+                    self.interval = Some(runtime::time::interval(period.into()));
+                }
+
+                // A frame can only be complete once its whole duration has elapsed
+                self.interval.as_mut().unwrap().next().await;
+            }
+
+            let buffer = self.generate(format, &settings);
+
+            gst::log!(CAT, obj: &self.element, "Pushing {:?}", buffer);
+            let res = pad.push(buffer).await;
+            match res {
+                Ok(_) => {
+                    gst::log!(CAT, obj: &self.element, "Successfully pushed buffer");
+                }
+                Err(gst::FlowError::Flushing) => {
+                    gst::debug!(CAT, obj: &self.element, "Flushing");
+                }
+                Err(gst::FlowError::Eos) => {
+                    gst::debug!(CAT, obj: &self.element, "EOS");
+                    pad.push_event(gst::event::Eos::new()).await;
+                }
+                Err(err) => {
+                    gst::error!(CAT, obj: &self.element, "Got error {}", err);
+                    gst::element_error!(
+                        &self.element,
+                        gst::StreamError::Failed,
+                        ("Internal data stream error"),
+                        ["streaming stopped, reason {}", err]
+                    );
+                }
+            }
+
+            res.map(drop)
+        }
+        .boxed()
+    }
+
+    fn stop(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Stopping task");
+
+            self.reset();
+
+            gst::log!(CAT, obj: &self.element, "Task stopped");
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn flush_start(&mut self) -> BoxFuture<'_, Result<(), gst::ErrorMessage>> {
+        async move {
+            gst::log!(CAT, obj: &self.element, "Starting task flush");
+
+            self.need_segment = true;
+
+            gst::log!(CAT, obj: &self.element, "Task flush started");
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+#[derive(Debug)]
+pub struct VideoTestSrc {
+    src_pad: PadSrc,
+    src_pad_handler: VideoTestSrcPadHandler,
+    task: Task,
+    settings: StdMutex<Settings>,
+}
+
+impl VideoTestSrc {
+    fn prepare(&self, element: &super::VideoTestSrc) -> Result<(), gst::ErrorMessage> {
+        let settings = self.settings.lock().unwrap().clone();
+        gst::debug!(CAT, obj: element, "Preparing");
+
+        let context =
+            Context::acquire(&settings.context, settings.context_wait).map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::OpenRead,
+                    ["Failed to acquire Context: {}", err]
+                )
+            })?;
+
+        self.task
+            .prepare(
+                VideoTestSrcTask::new(element, &self.src_pad, &self.src_pad_handler),
+                context,
+            )
+            .map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::OpenRead,
+                    ["Error preparing Task: {:?}", err]
+                )
+            })?;
+
+        gst::debug!(CAT, obj: element, "Prepared");
+
+        Ok(())
+    }
+
+    fn unprepare(&self, element: &super::VideoTestSrc) {
+        gst::debug!(CAT, obj: element, "Unpreparing");
+        self.task.unprepare().unwrap();
+        gst::debug!(CAT, obj: element, "Unprepared");
+    }
+
+    fn stop(&self, element: &super::VideoTestSrc) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Stopping");
+        self.task.stop()?;
+        gst::debug!(CAT, obj: element, "Stopped");
+        Ok(())
+    }
+
+    fn start(&self, element: &super::VideoTestSrc) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Starting");
+        self.task.start()?;
+        gst::debug!(CAT, obj: element, "Started");
+        Ok(())
+    }
+
+    fn pause(&self, element: &super::VideoTestSrc) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: element, "Pausing");
+        self.task.pause()?;
+        gst::debug!(CAT, obj: element, "Paused");
+        Ok(())
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for VideoTestSrc {
+    const NAME: &'static str = "RsTsVideoTestSrc";
+    type Type = super::VideoTestSrc;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        let src_pad_handler = VideoTestSrcPadHandler::default();
+
+        Self {
+            src_pad: PadSrc::new(
+                gst::Pad::from_template(&klass.pad_template("src").unwrap(), Some("src")),
+                src_pad_handler.clone(),
+            ),
+            src_pad_handler,
+            task: Task::default(),
+            settings: StdMutex::new(Settings::default()),
+        }
+    }
+}
+
+impl ObjectImpl for VideoTestSrc {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecString::new(
+                    "context",
+                    "Context",
+                    "Context name to share threads with",
+                    Some(DEFAULT_CONTEXT),
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecUInt::new(
+                    "context-wait",
+                    "Context Wait",
+                    "Throttle poll loop to run at most once every this many ms",
+                    0,
+                    1000,
+                    DEFAULT_CONTEXT_WAIT.as_millis() as u32,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecEnum::new(
+                    "pattern",
+                    "Pattern",
+                    "Type of test pattern to generate",
+                    VideoTestSrcPattern::static_type(),
+                    DEFAULT_PATTERN as i32,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "is-live",
+                    "Is Live",
+                    "Pace buffers in real time and timestamp them in running time",
+                    DEFAULT_IS_LIVE,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "do-timestamp",
+                    "Do Timestamp",
+                    "Timestamp buffers with the current running time when they are generated",
+                    DEFAULT_DO_TIMESTAMP,
+                    glib::ParamFlags::READWRITE,
+                ),
+                glib::ParamSpecInt::new(
+                    "num-buffers",
+                    "Num Buffers",
+                    "Number of buffers to output before sending EOS (-1 = unlimited)",
+                    -1,
+                    i32::MAX,
+                    DEFAULT_NUM_BUFFERS,
+                    glib::ParamFlags::READWRITE,
+                ),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        let mut settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "context" => {
+                settings.context = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream")
+                    .unwrap_or_else(|| DEFAULT_CONTEXT.into());
+            }
+            "context-wait" => {
+                settings.context_wait = Duration::from_millis(
+                    value.get::<u32>().expect("type checked upstream").into(),
+                );
+            }
+            "pattern" => {
+                settings.pattern = value.get().expect("type checked upstream");
+            }
+            "is-live" => {
+                settings.is_live = value.get().expect("type checked upstream");
+            }
+            "do-timestamp" => {
+                settings.do_timestamp = value.get().expect("type checked upstream");
+            }
+            "num-buffers" => {
+                settings.num_buffers = value.get().expect("type checked upstream");
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        let settings = self.settings.lock().unwrap();
+        match pspec.name() {
+            "context" => settings.context.to_value(),
+            "context-wait" => (settings.context_wait.as_millis() as u32).to_value(),
+            "pattern" => settings.pattern.to_value(),
+            "is-live" => settings.is_live.to_value(),
+            "do-timestamp" => settings.do_timestamp.to_value(),
+            "num-buffers" => settings.num_buffers.to_value(),
+            _ => unimplemented!(),
+        }
+    }
+
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(self.src_pad.gst_pad()).unwrap();
+
+        crate::set_element_flags(obj, gst::ElementFlags::SOURCE);
+    }
+}
+
+impl GstObjectImpl for VideoTestSrc {}
+
+impl ElementImpl for VideoTestSrc {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Thread-sharing video test source",
+                "Source/Video",
+                "Generates video test patterns on a thread-sharing context",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::builder("video/x-raw")
+                .field("format", "RGBx")
+                .field("width", gst::IntRange::new(1, i32::MAX))
+                .field("height", gst::IntRange::new(1, i32::MAX))
+                .field(
+                    "framerate",
+                    gst::FractionRange::new(
+                        gst::Fraction::new(1, i32::MAX),
+                        gst::Fraction::new(i32::MAX, 1),
+                    ),
+                )
+                .build();
+            let src_pad_template = gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![src_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        let is_live = self.settings.lock().unwrap().is_live;
+
+        match transition {
+            gst::StateChange::NullToReady => {
+                self.prepare(element).map_err(|err| {
+                    element.post_error_message(err);
+                    gst::StateChangeError
+                })?;
+            }
+            gst::StateChange::PlayingToPaused if is_live => {
+                self.pause(element).map_err(|_| gst::StateChangeError)?;
+            }
+            gst::StateChange::ReadyToNull => {
+                self.unprepare(element);
+            }
+            _ => (),
+        }
+
+        let mut success = self.parent_change_state(element, transition)?;
+
+        match transition {
+            gst::StateChange::ReadyToPaused => {
+                if is_live {
+                    success = gst::StateChangeSuccess::NoPreroll;
+                } else {
+                    self.start(element).map_err(|_| gst::StateChangeError)?;
+                }
+            }
+            gst::StateChange::PausedToPlaying if is_live => {
+                self.start(element).map_err(|_| gst::StateChangeError)?;
+            }
+            gst::StateChange::PlayingToPaused if is_live => {
+                success = gst::StateChangeSuccess::NoPreroll;
+            }
+            gst::StateChange::PausedToReady => {
+                self.stop(element).map_err(|_| gst::StateChangeError)?;
+            }
+            _ => (),
+        }
+
+        Ok(success)
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use gst::prelude::*;
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstthreadshare::plugin_register_static().expect("gstthreadshare audiotestsrc test");
+    });
+}
+
+#[test]
+fn num_buffers() {
+    init();
+
+    let mut h = gst_check::Harness::new("ts-audiotestsrc");
+    {
+        let audiotestsrc = h.element().unwrap();
+        audiotestsrc.set_property("context", "audiotestsrc-num-buffers");
+        audiotestsrc.set_property("is-live", false);
+        audiotestsrc.set_property("num-buffers", 3i32);
+        audiotestsrc.set_property("samples-per-buffer", 480u32);
+    }
+    h.set_sink_caps_str("audio/x-raw,rate=48000,channels=2");
+
+    h.play();
+
+    for idx in 0..3u64 {
+        let buffer = h.pull().unwrap();
+        assert_eq!(buffer.size(), 480 * 2 * 2);
+        assert_eq!(buffer.pts(), Some(gst::ClockTime::from_mseconds(10 * idx)));
+        assert_eq!(buffer.duration(), Some(gst::ClockTime::from_mseconds(10)));
+        assert_eq!(buffer.offset(), 480 * idx);
+    }
+
+    loop {
+        let event = h.pull_event().unwrap();
+        match event.view() {
+            gst::EventView::Caps(caps) => {
+                let s = caps.caps().structure(0).unwrap();
+                assert_eq!(s.get::<i32>("rate").unwrap(), 48000);
+                assert_eq!(s.get::<i32>("channels").unwrap(), 2);
+            }
+            gst::EventView::Eos(..) => break,
+            _ => (),
+        }
+    }
+}
+
+#[test]
+fn live() {
+    init();
+
+    let mut h = gst_check::Harness::new("ts-audiotestsrc");
+    {
+        let audiotestsrc = h.element().unwrap();
+        audiotestsrc.set_property("context", "audiotestsrc-live");
+        audiotestsrc.set_property("samples-per-buffer", 480u32);
+    }
+    h.set_sink_caps_str("audio/x-raw,rate=48000,channels=1");
+    h.use_systemclock();
+
+    h.play();
+
+    let start = std::time::Instant::now();
+    let first = h.pull().unwrap();
+    let second = h.pull().unwrap();
+
+    // Buffers are paced in real time
+    assert!(start.elapsed() >= std::time::Duration::from_millis(15));
+    assert_eq!(
+        second.pts().unwrap() - first.pts().unwrap(),
+        gst::ClockTime::from_mseconds(10)
+    );
+
+    let mut q = gst::query::Latency::new();
+    assert!(h
+        .element()
+        .unwrap()
+        .static_pad("src")
+        .unwrap()
+        .query(&mut q));
+    let (live, min, _max) = q.result();
+    assert!(live);
+    assert_eq!(min, gst::ClockTime::from_mseconds(10));
+}
@@ -0,0 +1,103 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use gst::prelude::*;
+
+use std::sync::{Arc, Mutex};
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstthreadshare::plugin_register_static().expect("gstthreadshare fakesink test");
+    });
+}
+
+#[test]
+fn handoff_and_stats() {
+    init();
+
+    let mut h = gst_check::Harness::new("ts-fakesink");
+    let fakesink = h.element().unwrap();
+    fakesink.set_property("signal-handoffs", true);
+    fakesink.set_property("stats-interval", 1u32);
+
+    let handoffs = Arc::new(Mutex::new(Vec::new()));
+    let handoffs_clone = handoffs.clone();
+    fakesink.connect("handoff", false, move |args| {
+        let buffer = args[1].get::<gst::Buffer>().unwrap();
+        handoffs_clone.lock().unwrap().push(buffer.size());
+        None
+    });
+
+    let stats_signals = Arc::new(Mutex::new(0u32));
+    let stats_signals_clone = stats_signals.clone();
+    fakesink.connect("stats", false, move |args| {
+        let s = args[1].get::<gst::Structure>().unwrap();
+        assert!(s.get::<u64>("num-buffers").unwrap() > 0);
+        *stats_signals_clone.lock().unwrap() += 1;
+        None
+    });
+
+    h.play();
+    h.set_src_caps_str("application/x-test");
+
+    for size in 1..=3 {
+        assert_eq!(
+            h.push(gst::Buffer::with_size(size).unwrap()),
+            Ok(gst::FlowSuccess::Ok)
+        );
+    }
+
+    assert_eq!(*handoffs.lock().unwrap(), vec![1, 2, 3]);
+    assert!(*stats_signals.lock().unwrap() >= 1);
+
+    let stats = fakesink.property::<gst::Structure>("stats");
+    assert_eq!(stats.get::<u64>("num-buffers").unwrap(), 3);
+    assert_eq!(stats.get::<u64>("num-bytes").unwrap(), 6);
+    assert_eq!(stats.get::<u64>("num-late").unwrap(), 0);
+}
+
+#[test]
+fn sync() {
+    init();
+
+    let mut h = gst_check::Harness::new("ts-fakesink");
+    let fakesink = h.element().unwrap();
+    fakesink.set_property("sync", true);
+
+    h.use_testclock();
+    h.play();
+    h.set_src_caps_str("application/x-test");
+
+    // Lies in the past of the running time once the clock has advanced
+    let mut buffer = gst::Buffer::with_size(1).unwrap();
+    buffer.get_mut().unwrap().set_pts(gst::ClockTime::ZERO);
+
+    let testclock = h.testclock().unwrap();
+    testclock.set_time(gst::ClockTime::SECOND);
+
+    assert_eq!(h.push(buffer), Ok(gst::FlowSuccess::Ok));
+
+    let stats = fakesink.property::<gst::Structure>("stats");
+    assert_eq!(stats.get::<u64>("num-buffers").unwrap(), 1);
+    assert_eq!(stats.get::<u64>("num-late").unwrap(), 1);
+}
@@ -0,0 +1,92 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use gst::prelude::*;
+
+use std::fs;
+use std::path::PathBuf;
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstthreadshare::plugin_register_static().expect("gstthreadshare file test");
+    });
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("ts-file-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn filesrc_to_filesink() {
+    init();
+
+    let input = temp_path("input");
+    let output = temp_path("output");
+
+    let data = (0..100_000u32).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+    fs::write(&input, &data).unwrap();
+
+    let pipeline = gst::Pipeline::new(None);
+
+    let filesrc = gst::ElementFactory::make("ts-filesrc", None).unwrap();
+    filesrc.set_property("context", "file-test");
+    filesrc.set_property("location", input.to_str().unwrap());
+    filesrc.set_property("blocksize", 1000u32);
+
+    let filesink = gst::ElementFactory::make("ts-filesink", None).unwrap();
+    filesink.set_property("location", output.to_str().unwrap());
+
+    pipeline.add_many(&[&filesrc, &filesink]).unwrap();
+    filesrc.link(&filesink).unwrap();
+
+    pipeline.set_state(gst::State::Playing).unwrap();
+
+    let bus = pipeline.bus().unwrap();
+    for msg in bus.iter_timed(gst::ClockTime::from_seconds(10)) {
+        use gst::MessageView;
+
+        match msg.view() {
+            MessageView::Eos(..) => break,
+            MessageView::Error(err) => panic!("{:?}", err),
+            _ => (),
+        }
+    }
+
+    let stats = filesink.property::<gst::Structure>("stats");
+    assert_eq!(stats.get::<u64>("num-buffers").unwrap(), 100);
+
+    pipeline.set_state(gst::State::Null).unwrap();
+
+    assert_eq!(fs::read(&output).unwrap(), data);
+
+    fs::remove_file(&input).unwrap();
+    fs::remove_file(&output).unwrap();
+}
+
+#[test]
+fn filesrc_missing_location() {
+    init();
+
+    let filesrc = gst::ElementFactory::make("ts-filesrc", None).unwrap();
+    assert!(filesrc.set_state(gst::State::Ready).is_err());
+}
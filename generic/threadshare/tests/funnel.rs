@@ -0,0 +1,99 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use gst::prelude::*;
+
+use std::sync::{Arc, Mutex};
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstthreadshare::plugin_register_static().expect("gstthreadshare funnel test");
+    });
+}
+
+#[test]
+fn test_push() {
+    init();
+
+    let pipeline = gst::Pipeline::new(None);
+    let fakesrc_0 = gst::ElementFactory::make("fakesrc", None).unwrap();
+    let fakesrc_1 = gst::ElementFactory::make("fakesrc", None).unwrap();
+    let funnel = gst::ElementFactory::make("ts-funnel", None).unwrap();
+    let appsink = gst::ElementFactory::make("appsink", None).unwrap();
+
+    pipeline
+        .add_many(&[&fakesrc_0, &fakesrc_1, &funnel, &appsink])
+        .unwrap();
+    fakesrc_0.link(&funnel).unwrap();
+    fakesrc_1.link(&funnel).unwrap();
+    funnel.link(&appsink).unwrap();
+
+    fakesrc_0.set_property("num-buffers", 3i32);
+    fakesrc_1.set_property("num-buffers", 5i32);
+    funnel.set_property("context", "funnel::test_push");
+
+    appsink.set_property("emit-signals", true);
+
+    let samples = Arc::new(Mutex::new(Vec::new()));
+
+    let appsink = appsink.dynamic_cast::<gst_app::AppSink>().unwrap();
+    let samples_clone = samples.clone();
+    appsink.set_callbacks(
+        gst_app::AppSinkCallbacks::builder()
+            .new_sample(move |appsink| {
+                let sample = appsink.pull_sample().unwrap();
+
+                samples_clone.lock().unwrap().push(sample);
+
+                Ok(gst::FlowSuccess::Ok)
+            })
+            .build(),
+    );
+
+    pipeline.set_state(gst::State::Playing).unwrap();
+
+    let mut eos = false;
+    let bus = pipeline.bus().unwrap();
+    while let Some(msg) = bus.timed_pop(5 * gst::ClockTime::SECOND) {
+        use gst::MessageView;
+        match msg.view() {
+            MessageView::Eos(..) => {
+                eos = true;
+                break;
+            }
+            MessageView::Error(err) => unreachable!("funnel::test_push {:?}", err),
+            _ => (),
+        }
+    }
+
+    // EOS is only forwarded once both branches are done
+    assert!(eos);
+    let samples = samples.lock().unwrap();
+    assert_eq!(samples.len(), 8);
+
+    for sample in samples.iter() {
+        assert!(sample.buffer().is_some());
+    }
+
+    pipeline.set_state(gst::State::Null).unwrap();
+}
@@ -162,3 +162,170 @@ fn jb_ts_pipeline() {
 
     pipeline.set_state(gst::State::Null).unwrap();
 }
+
+#[test]
+fn jb_retransmission_request() {
+    init();
+
+    const SSRC: u32 = 0x1234;
+
+    let mut h = gst_check::Harness::new("ts-jitterbuffer");
+    let jb = h.element().unwrap();
+    jb.set_property("context", "jb_retransmission_request");
+    jb.set_property("latency", 200u32);
+    jb.set_property("do-retransmission", true);
+    jb.set_property("rtx-delay", 0i32);
+    jb.set_property("rtx-max-retries", 1i32);
+
+    h.play();
+    h.set_src_caps_str(
+        "application/x-rtp,media=audio,payload=96,clock-rate=8000,encoding-name=L16",
+    );
+
+    for seqnum in [0u16, 1, 3] {
+        let mut data = vec![0x80, 96];
+        data.extend_from_slice(&seqnum.to_be_bytes());
+        data.extend_from_slice(&(seqnum as u32 * 160).to_be_bytes());
+        data.extend_from_slice(&SSRC.to_be_bytes());
+        data.extend_from_slice(&[0u8; 320]);
+        assert_eq!(
+            h.push(gst::Buffer::from_mut_slice(data)),
+            Ok(gst::FlowSuccess::Ok)
+        );
+    }
+
+    let s = loop {
+        let event = h.pull_upstream_event().unwrap();
+        if let gst::EventView::CustomUpstream(e) = event.view() {
+            let s = e.structure().unwrap();
+            if s.name() == "GstRTPRetransmissionRequest" {
+                break s.to_owned();
+            }
+        }
+    };
+
+    assert_eq!(s.get::<u32>("seqnum").unwrap(), 2);
+    assert_eq!(s.get::<u32>("ssrc").unwrap(), SSRC);
+    assert_eq!(s.get::<u32>("retry").unwrap(), 0);
+
+    let stats = jb.property::<gst::Structure>("stats");
+    assert_eq!(stats.get::<u64>("rtx-count").unwrap(), 1);
+    assert_eq!(stats.get::<u64>("rtx-success-count").unwrap(), 0);
+}
+
+fn rtp_buffer(seqnum: u16, pts: gst::ClockTime) -> gst::Buffer {
+    let mut data = vec![0x80, 96];
+    data.extend_from_slice(&seqnum.to_be_bytes());
+    data.extend_from_slice(&(seqnum as u32 * 160).to_be_bytes());
+    data.extend_from_slice(&0x1234u32.to_be_bytes());
+    data.extend_from_slice(&[0u8; 320]);
+
+    let mut buffer = gst::Buffer::from_mut_slice(data);
+    {
+        let buffer = buffer.get_mut().unwrap();
+        buffer.set_pts(pts);
+        buffer.set_dts(pts);
+    }
+
+    buffer
+}
+
+#[test]
+fn jb_faststart() {
+    init();
+
+    let mut h = gst_check::Harness::new("ts-jitterbuffer");
+    let jb = h.element().unwrap();
+    jb.set_property("context", "jb_faststart");
+    jb.set_property("latency", 2000u32);
+    jb.set_property("faststart-min-packets", 3u32);
+
+    h.use_systemclock();
+    h.play();
+    h.set_src_caps_str(
+        "application/x-rtp,media=audio,payload=96,clock-rate=8000,encoding-name=L16",
+    );
+
+    let start = std::time::Instant::now();
+    for seqnum in 0..3u16 {
+        let pts = gst::ClockTime::from_mseconds(20 * seqnum as u64);
+        assert_eq!(h.push(rtp_buffer(seqnum, pts)), Ok(gst::FlowSuccess::Ok));
+    }
+
+    let buffer = h.pull().unwrap();
+    assert!(start.elapsed() < std::time::Duration::from_secs(1));
+    assert_eq!(
+        gst_rtp::RTPBuffer::from_buffer_readable(&buffer)
+            .unwrap()
+            .seq(),
+        0
+    );
+}
+
+#[test]
+fn jb_buffer_mode() {
+    init();
+
+    let mut h = gst_check::Harness::new("ts-jitterbuffer");
+    let jb = h.element().unwrap();
+    jb.set_property("context", "jb_buffer_mode");
+    jb.set_property_from_str("mode", "buffer");
+
+    let bus = gst::Bus::new();
+    jb.set_bus(Some(&bus));
+
+    h.play();
+    h.set_src_caps_str(
+        "application/x-rtp,media=audio,payload=96,clock-rate=8000,encoding-name=L16",
+    );
+
+    assert_eq!(
+        h.push(rtp_buffer(0, gst::ClockTime::ZERO)),
+        Ok(gst::FlowSuccess::Ok)
+    );
+
+    let msg = bus
+        .timed_pop_filtered(
+            gst::ClockTime::from_seconds(5),
+            &[gst::MessageType::Buffering],
+        )
+        .expect("no buffering message");
+    match msg.view() {
+        gst::MessageView::Buffering(buffering) => assert!(buffering.percent() < 100),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn jb_rfc7273_local_clock() {
+    init();
+
+    let mut h = gst_check::Harness::new("ts-jitterbuffer");
+    let jb = h.element().unwrap();
+    jb.set_property("context", "jb_rfc7273_local_clock");
+    jb.set_property("latency", 20u32);
+    jb.set_property_from_str("mode", "synced");
+    jb.set_property("rfc7273-sync", true);
+
+    h.use_systemclock();
+    h.play();
+    h.set_src_caps_str(
+        "application/x-rtp,media=audio,payload=96,clock-rate=8000,encoding-name=L16,\
+         a-ts-refclk=local,a-mediaclk=(string)\"direct=0\"",
+    );
+
+    for seqnum in 0..3u16 {
+        let pts = gst::ClockTime::from_mseconds(20 * seqnum as u64);
+        assert_eq!(h.push(rtp_buffer(seqnum, pts)), Ok(gst::FlowSuccess::Ok));
+    }
+
+    for seqnum in 0..3u16 {
+        let buffer = h.pull().unwrap();
+        assert_eq!(
+            gst_rtp::RTPBuffer::from_buffer_readable(&buffer)
+                .unwrap()
+                .seq(),
+            seqnum
+        );
+    }
+}
@@ -752,6 +752,185 @@ glib::wrapper! {
     pub struct ElementSinkTest(ObjectSubclass<imp_sink::ElementSinkTest>) @extends gst::Element, gst::Object;
 }
 
+// Pull Src
+
+const PULL_SRC_SIZE: u64 = 1024;
+
+mod imp_pull_src {
+    use super::*;
+
+    pub static PULL_SRC_CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+        gst::DebugCategory::new(
+            "ts-element-pull-src-test",
+            gst::DebugColorFlags::empty(),
+            Some("Thread-sharing Test Pull Src Element"),
+        )
+    });
+
+    #[derive(Clone, Debug)]
+    struct PadSrcPullTestHandler;
+
+    impl PadSrcHandler for PadSrcPullTestHandler {
+        type ElementImpl = ElementPullSrcTest;
+
+        fn src_activatemode(
+            &self,
+            pad: &PadSrcRef,
+            _elem_pull_src_test: &ElementPullSrcTest,
+            _element: &gst::Element,
+            mode: gst::PadMode,
+            active: bool,
+        ) -> Result<(), gst::LoggableError> {
+            gst::debug!(PULL_SRC_CAT, obj: pad.gst_pad(), "{:?} mode active {}", mode, active);
+            Ok(())
+        }
+
+        fn src_query(
+            &self,
+            pad: &PadSrcRef,
+            _elem_pull_src_test: &ElementPullSrcTest,
+            element: &gst::Element,
+            query: &mut gst::QueryRef,
+        ) -> bool {
+            use gst::QueryViewMut;
+
+            match query.view_mut() {
+                QueryViewMut::Scheduling(q) => {
+                    q.set(gst::SchedulingFlags::SEEKABLE, 1, -1, 0);
+                    q.add_scheduling_modes(&[gst::PadMode::Pull, gst::PadMode::Push]);
+                    true
+                }
+                _ => pad.gst_pad().query_default(Some(element), query),
+            }
+        }
+
+        fn src_getrange(
+            &self,
+            pad: &PadSrcRef,
+            _elem_pull_src_test: &ElementPullSrcTest,
+            _element: &gst::Element,
+            offset: u64,
+            length: u32,
+        ) -> BoxFuture<'static, Result<gst::Buffer, gst::FlowError>> {
+            gst::log!(PULL_SRC_CAT, obj: pad.gst_pad(), "getrange({}, {})", offset, length);
+
+            async move {
+                // The PadSrc executes the Future on the element's Context
+                assert_eq!(
+                    Context::current().map(|context| context.name().to_string()),
+                    Some("pull_src_test".to_string())
+                );
+
+                if offset >= PULL_SRC_SIZE {
+                    return Err(gst::FlowError::Eos);
+                }
+
+                let end = PULL_SRC_SIZE.min(offset + length as u64);
+                let mut buffer = gst::Buffer::from_mut_slice(
+                    (offset..end).map(|pos| pos as u8).collect::<Vec<u8>>(),
+                );
+                buffer.get_mut().unwrap().set_offset(offset);
+
+                Ok(buffer)
+            }
+            .boxed()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct ElementPullSrcTest {
+        src_pad: PadSrc,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ElementPullSrcTest {
+        const NAME: &'static str = "TsElementPullSrcTest";
+        type Type = super::ElementPullSrcTest;
+        type ParentType = gst::Element;
+
+        fn with_class(klass: &Self::Class) -> Self {
+            ElementPullSrcTest {
+                src_pad: PadSrc::new(
+                    gst::Pad::from_template(&klass.pad_template("src").unwrap(), Some("src")),
+                    PadSrcPullTestHandler,
+                ),
+            }
+        }
+    }
+
+    impl ObjectImpl for ElementPullSrcTest {
+        fn constructed(&self, obj: &Self::Type) {
+            self.parent_constructed(obj);
+
+            obj.add_pad(self.src_pad.gst_pad()).unwrap();
+        }
+    }
+
+    impl GstObjectImpl for ElementPullSrcTest {}
+
+    impl ElementImpl for ElementPullSrcTest {
+        fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+            static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+                gst::subclass::ElementMetadata::new(
+                    "Thread-sharing Test Pull Src Element",
+                    "Generic",
+                    "Pull mode Src Element for Pad Src Test",
+                    "Sebastian Dröge <sebastian@centricular.com>",
+                )
+            });
+
+            Some(&*ELEMENT_METADATA)
+        }
+
+        fn pad_templates() -> &'static [gst::PadTemplate] {
+            static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+                let caps = gst::Caps::new_any();
+                let src_pad_template = gst::PadTemplate::new(
+                    "src",
+                    gst::PadDirection::Src,
+                    gst::PadPresence::Always,
+                    &caps,
+                )
+                .unwrap();
+
+                vec![src_pad_template]
+            });
+
+            PAD_TEMPLATES.as_ref()
+        }
+
+        fn change_state(
+            &self,
+            element: &Self::Type,
+            transition: gst::StateChange,
+        ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+            gst::log!(PULL_SRC_CAT, obj: element, "Changing state {:?}", transition);
+
+            match transition {
+                gst::StateChange::NullToReady => {
+                    let context = Context::acquire("pull_src_test", THROTTLING_DURATION).map_err(
+                        |err| {
+                            gst::error!(PULL_SRC_CAT, obj: element, "Failed to acquire Context: {}", err);
+                            gst::StateChangeError
+                        },
+                    )?;
+                    self.src_pad.set_context(Some(context));
+                }
+                gst::StateChange::ReadyToNull => {
+                    self.src_pad.set_context(None);
+                }
+                _ => (),
+            }
+
+            self.parent_change_state(element, transition)
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ElementPullSrcTest(ObjectSubclass<imp_pull_src::ElementPullSrcTest>) @extends gst::Element, gst::Object;
+}
+
 fn setup(
     context_name: &str,
     mut middle_element_1: Option<gst::Element>,
@@ -1296,3 +1475,72 @@ fn start_flush() {
 
     pipeline.set_state(gst::State::Null).unwrap();
 }
+
+#[test]
+fn pull_src_getrange() {
+    init();
+
+    let pull_src = glib::Object::new::<ElementPullSrcTest>(&[]).unwrap();
+    let sink_pad = gst::Pad::new(Some("sink"), gst::PadDirection::Sink);
+    pull_src.static_pad("src").unwrap().link(&sink_pad).unwrap();
+
+    pull_src.set_state(gst::State::Ready).unwrap();
+
+    let mut query = gst::query::Scheduling::new();
+    assert!(sink_pad.peer_query(&mut query));
+    assert!(query.has_scheduling_mode(gst::PadMode::Pull));
+
+    sink_pad.activate_mode(gst::PadMode::Pull, true).unwrap();
+
+    let buffer = sink_pad.pull_range(10, 4).unwrap();
+    assert_eq!(buffer.offset(), 10);
+    let data = buffer.map_readable().unwrap();
+    assert_eq!(data.as_slice(), vec![10, 11, 12, 13].as_slice());
+    drop(data);
+
+    // Truncated at the end of the stream
+    let buffer = sink_pad.pull_range(PULL_SRC_SIZE - 2, 16).unwrap();
+    assert_eq!(buffer.size(), 2);
+
+    assert_eq!(
+        sink_pad.pull_range(PULL_SRC_SIZE, 1).unwrap_err(),
+        gst::FlowError::Eos
+    );
+
+    // Pulling from a Context task would block it
+    let caller_context = Context::acquire("pull_src_test_caller", THROTTLING_DURATION).unwrap();
+    let sink_pad_clone = sink_pad.clone();
+    let res = futures::executor::block_on(
+        caller_context.spawn(async move { sink_pad_clone.pull_range(0, 1) }),
+    )
+    .unwrap();
+    assert_eq!(res.unwrap_err(), gst::FlowError::Error);
+
+    // Deactivated pads are flushing
+    sink_pad.activate_mode(gst::PadMode::Pull, false).unwrap();
+    assert_eq!(
+        sink_pad.pull_range(0, 1).unwrap_err(),
+        gst::FlowError::Flushing
+    );
+
+    pull_src.set_state(gst::State::Null).unwrap();
+}
+
+#[test]
+fn src_rejects_pull_mode() {
+    init();
+
+    let src_element = glib::Object::new::<ElementSrcTest>(&[]).unwrap();
+    let sink_pad = gst::Pad::new(Some("sink"), gst::PadDirection::Sink);
+    src_element
+        .static_pad("src")
+        .unwrap()
+        .link(&sink_pad)
+        .unwrap();
+
+    src_element.set_state(gst::State::Ready).unwrap();
+
+    assert!(sink_pad.activate_mode(gst::PadMode::Pull, true).is_err());
+
+    src_element.set_state(gst::State::Null).unwrap();
+}
@@ -19,7 +19,8 @@
 
 use gst::prelude::*;
 
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
 
 fn init() {
     use std::sync::Once;
@@ -162,3 +163,434 @@ fn test_from_pipeline_to_pipeline_and_back() {
     pipe_1.set_state(gst::State::Null).unwrap();
     pipe_2.set_state(gst::State::Null).unwrap();
 }
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_socket_path() {
+    init();
+
+    let mut socket_path = std::env::temp_dir();
+    socket_path.push(format!("ts-proxy-test-{}.sock", std::process::id()));
+    let socket_path = socket_path.to_str().unwrap();
+
+    let pipe_src = gst::Pipeline::new(None);
+    let pxsrc = gst::ElementFactory::make("ts-proxysrc", Some("proxysrc::test5")).unwrap();
+    let appsink = gst::ElementFactory::make("appsink", None).unwrap();
+
+    pipe_src.add_many(&[&pxsrc, &appsink]).unwrap();
+    pxsrc.link(&appsink).unwrap();
+
+    pxsrc.set_property("socket-path", socket_path);
+    pxsrc.set_property("context", "proxy::test");
+
+    let pipe_sink = gst::Pipeline::new(None);
+    let fakesrc = gst::ElementFactory::make("fakesrc", None).unwrap();
+    let pxsink = gst::ElementFactory::make("ts-proxysink", Some("proxysink::test5")).unwrap();
+
+    pipe_sink.add_many(&[&fakesrc, &pxsink]).unwrap();
+    fakesrc.link(&pxsink).unwrap();
+
+    fakesrc.set_property("num-buffers", 3i32);
+    fakesrc.set_property_from_str("sizetype", "fixed");
+    fakesrc.set_property("sizemax", 1000i32);
+    pxsink.set_property("socket-path", socket_path);
+    pxsink.set_property("ring-size", 4096u32);
+
+    let samples = Arc::new(Mutex::new(Vec::new()));
+
+    let appsink = appsink.dynamic_cast::<gst_app::AppSink>().unwrap();
+    let samples_clone = samples.clone();
+    appsink.set_callbacks(
+        gst_app::AppSinkCallbacks::builder()
+            .new_sample(move |appsink| {
+                let sample = appsink.pull_sample().unwrap();
+
+                samples_clone.lock().unwrap().push(sample);
+
+                Ok(gst::FlowSuccess::Ok)
+            })
+            .build(),
+    );
+
+    pipe_src.set_state(gst::State::Playing).unwrap();
+    pipe_sink.set_state(gst::State::Playing).unwrap();
+
+    let mut eos = false;
+    let bus = pipe_src.bus().unwrap();
+    while let Some(msg) = bus.timed_pop(5 * gst::ClockTime::SECOND) {
+        use gst::MessageView;
+        match msg.view() {
+            MessageView::Eos(..) => {
+                eos = true;
+                break;
+            }
+            MessageView::Error(err) => unreachable!("proxy::test_socket_path {:?}", err),
+            _ => (),
+        }
+    }
+
+    assert!(eos);
+    let samples = samples.lock().unwrap();
+    assert_eq!(samples.len(), 3);
+
+    for sample in samples.iter() {
+        assert_eq!(sample.buffer().unwrap().size(), 1000);
+    }
+
+    pipe_sink.set_state(gst::State::Null).unwrap();
+    pipe_src.set_state(gst::State::Null).unwrap();
+}
+
+#[test]
+fn test_socket_path_backpressure() {
+    init();
+
+    let mut socket_path = std::env::temp_dir();
+    socket_path.push(format!(
+        "ts-proxy-test-backpressure-{}.sock",
+        std::process::id()
+    ));
+    let socket_path = socket_path.to_str().unwrap();
+
+    let pipe_src = gst::Pipeline::new(None);
+    let pxsrc = gst::ElementFactory::make("ts-proxysrc", Some("proxysrc::test6")).unwrap();
+    let appsink = gst::ElementFactory::make("appsink", None).unwrap();
+
+    pipe_src.add_many(&[&pxsrc, &appsink]).unwrap();
+    pxsrc.link(&appsink).unwrap();
+
+    pxsrc.set_property("socket-path", socket_path);
+    pxsrc.set_property("context", "proxy::test");
+    pxsrc.set_property("max-size-buffers", 2u32);
+    pxsrc.set_property("max-size-bytes", 0u32);
+    pxsrc.set_property("max-size-time", 0u64);
+
+    let pipe_sink = gst::Pipeline::new(None);
+    let fakesrc = gst::ElementFactory::make("fakesrc", None).unwrap();
+    let pxsink = gst::ElementFactory::make("ts-proxysink", Some("proxysink::test6")).unwrap();
+
+    pipe_sink.add_many(&[&fakesrc, &pxsink]).unwrap();
+    fakesrc.link(&pxsink).unwrap();
+
+    fakesrc.set_property("num-buffers", 50i32);
+    fakesrc.set_property_from_str("sizetype", "fixed");
+    fakesrc.set_property("sizemax", 1000i32);
+    pxsink.set_property("socket-path", socket_path);
+    pxsink.set_property("ring-size", 4096u32);
+
+    let sent = Arc::new(Mutex::new(0));
+    let sent_clone = sent.clone();
+    pxsink
+        .static_pad("sink")
+        .unwrap()
+        .add_probe(gst::PadProbeType::BUFFER, move |_, _| {
+            *sent_clone.lock().unwrap() += 1;
+            gst::PadProbeReturn::Ok
+        });
+
+    // Holds the appsink until released
+    let released = Arc::new((Mutex::new(false), Condvar::new()));
+    let received = Arc::new(Mutex::new(0));
+
+    let appsink = appsink.dynamic_cast::<gst_app::AppSink>().unwrap();
+    let released_clone = released.clone();
+    let received_clone = received.clone();
+    appsink.set_callbacks(
+        gst_app::AppSinkCallbacks::builder()
+            .new_sample(move |appsink| {
+                let _ = appsink.pull_sample().unwrap();
+
+                let (lock, cond) = &*released_clone;
+                let _ = cond.wait_while(lock.lock().unwrap(), |released| !*released);
+
+                *received_clone.lock().unwrap() += 1;
+
+                Ok(gst::FlowSuccess::Ok)
+            })
+            .build(),
+    );
+
+    pipe_src.set_state(gst::State::Playing).unwrap();
+    pipe_sink.set_state(gst::State::Playing).unwrap();
+
+    // Let the queues and the ring fill up
+    std::thread::sleep(Duration::from_millis(500));
+    let sent_blocked = *sent.lock().unwrap();
+    std::thread::sleep(Duration::from_millis(500));
+
+    // The ring holds 4 buffers at most, in addition to those
+    // held by the ts-proxysrc queue and downstream
+    assert_eq!(*sent.lock().unwrap(), sent_blocked);
+    assert!(sent_blocked > 0 && sent_blocked < 12, "{}", sent_blocked);
+    assert_eq!(*received.lock().unwrap(), 0);
+
+    {
+        let (lock, cond) = &*released;
+        *lock.lock().unwrap() = true;
+        cond.notify_all();
+    }
+
+    let mut eos = false;
+    let bus = pipe_src.bus().unwrap();
+    while let Some(msg) = bus.timed_pop(5 * gst::ClockTime::SECOND) {
+        use gst::MessageView;
+        match msg.view() {
+            MessageView::Eos(..) => {
+                eos = true;
+                break;
+            }
+            MessageView::Error(err) => {
+                unreachable!("proxy::test_socket_path_backpressure {:?}", err)
+            }
+            _ => (),
+        }
+    }
+
+    assert!(eos);
+    assert_eq!(*sent.lock().unwrap(), 50);
+    assert_eq!(*received.lock().unwrap(), 50);
+
+    pipe_sink.set_state(gst::State::Null).unwrap();
+    pipe_src.set_state(gst::State::Null).unwrap();
+}
+
+#[test]
+fn test_socket_path_reconnect() {
+    init();
+
+    let mut socket_path = std::env::temp_dir();
+    socket_path.push(format!(
+        "ts-proxy-test-reconnect-{}.sock",
+        std::process::id()
+    ));
+    let socket_path = socket_path.to_str().unwrap();
+
+    let pipe_src = gst::Pipeline::new(None);
+    let pxsrc = gst::ElementFactory::make("ts-proxysrc", Some("proxysrc::test7")).unwrap();
+    let appsink = gst::ElementFactory::make("appsink", None).unwrap();
+
+    pipe_src.add_many(&[&pxsrc, &appsink]).unwrap();
+    pxsrc.link(&appsink).unwrap();
+
+    pxsrc.set_property("socket-path", socket_path);
+    pxsrc.set_property("context", "proxy::test");
+
+    let pipe_sink = gst::Pipeline::new(None);
+    let appsrc = gst::ElementFactory::make("appsrc", None).unwrap();
+    let pxsink = gst::ElementFactory::make("ts-proxysink", Some("proxysink::test7")).unwrap();
+
+    pipe_sink.add_many(&[&appsrc, &pxsink]).unwrap();
+    appsrc.link(&pxsink).unwrap();
+
+    pxsink.set_property("socket-path", socket_path);
+    pxsink.set_property("ring-size", 4096u32);
+
+    let appsrc = appsrc.dynamic_cast::<gst_app::AppSrc>().unwrap();
+    appsrc.set_caps(Some(&gst::Caps::new_simple("foo/bar", &[])));
+    appsrc.set_format(gst::Format::Time);
+
+    let received = Arc::new(Mutex::new(0));
+
+    let appsink = appsink.dynamic_cast::<gst_app::AppSink>().unwrap();
+    let received_clone = received.clone();
+    appsink.set_callbacks(
+        gst_app::AppSinkCallbacks::builder()
+            .new_sample(move |appsink| {
+                let _ = appsink.pull_sample().unwrap();
+                *received_clone.lock().unwrap() += 1;
+
+                Ok(gst::FlowSuccess::Ok)
+            })
+            .build(),
+    );
+
+    // Buffers are dropped while the ts-proxysink is not connected,
+    // so keep pushing until enough made it through
+    let push_until_received = |count| {
+        let start = Instant::now();
+        while *received.lock().unwrap() < count {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "Only received {} buffers",
+                received.lock().unwrap()
+            );
+
+            appsrc
+                .push_buffer(gst::Buffer::with_size(1000).unwrap())
+                .unwrap();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    };
+
+    pipe_src.set_state(gst::State::Playing).unwrap();
+    pipe_sink.set_state(gst::State::Playing).unwrap();
+
+    push_until_received(3);
+
+    // Restart the peer, the ts-proxysink reconnects to the new listener
+    pipe_src.set_state(gst::State::Null).unwrap();
+    *received.lock().unwrap() = 0;
+    pipe_src.set_state(gst::State::Playing).unwrap();
+
+    push_until_received(3);
+
+    pipe_sink.set_state(gst::State::Null).unwrap();
+    pipe_src.set_state(gst::State::Null).unwrap();
+}
+
+/// Appsink callbacks which record the PTS of the buffers, in ms, and
+/// hold the streaming thread after the first one until released.
+#[derive(Default)]
+struct Gate {
+    received: Mutex<(Vec<u64>, bool)>,
+    cond: Condvar,
+}
+
+impl Gate {
+    fn install(self: &Arc<Self>, appsink: &gst::Element) {
+        let gate = self.clone();
+        appsink
+            .clone()
+            .dynamic_cast::<gst_app::AppSink>()
+            .unwrap()
+            .set_callbacks(
+                gst_app::AppSinkCallbacks::builder()
+                    .new_sample(move |appsink| {
+                        let sample = appsink.pull_sample().unwrap();
+                        let pts = sample.buffer().unwrap().pts().unwrap();
+
+                        let mut received = gate.received.lock().unwrap();
+                        received.0.push(pts.mseconds());
+                        gate.cond.notify_all();
+                        let _ = gate.cond.wait_while(received, |(_, released)| !*released);
+
+                        Ok(gst::FlowSuccess::Ok)
+                    })
+                    .build(),
+            );
+    }
+
+    fn wait_blocked(&self) {
+        let received = self.received.lock().unwrap();
+        let (received, _) = self
+            .cond
+            .wait_timeout_while(received, Duration::from_secs(5), |(received, _)| {
+                received.is_empty()
+            })
+            .unwrap();
+        assert_eq!(received.0, vec![0]);
+    }
+
+    fn release(&self) -> Vec<u64> {
+        let mut received = self.received.lock().unwrap();
+        received.1 = true;
+        self.cond.notify_all();
+
+        received.0.clone()
+    }
+
+    fn received(&self) -> Vec<u64> {
+        self.received.lock().unwrap().0.clone()
+    }
+}
+
+fn push_buffer(appsrc: &gst_app::AppSrc, idx: u64) {
+    let mut buffer = gst::Buffer::from_slice(vec![0; 100]);
+    buffer
+        .get_mut()
+        .unwrap()
+        .set_pts(idx * 10 * gst::ClockTime::MSECOND);
+    appsrc.push_buffer(buffer).unwrap();
+}
+
+fn wait_dropped(element: &gst::Element, dropped: u64) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while element.property::<u64>("dropped") < dropped {
+        assert!(Instant::now() < deadline, "Timed out waiting for drops");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn wait_eos(pipeline: &gst::Pipeline) {
+    let mut eos = false;
+    let bus = pipeline.bus().unwrap();
+    while let Some(msg) = bus.timed_pop(5 * gst::ClockTime::SECOND) {
+        use gst::MessageView;
+        match msg.view() {
+            MessageView::Eos(..) => {
+                eos = true;
+                break;
+            }
+            MessageView::Error(err) => unreachable!("proxy::test_leaky {:?}", err),
+            _ => (),
+        }
+    }
+
+    assert!(eos);
+}
+
+/* Fills a queue of 2 buffers while downstream is blocked on the first
+ * buffer, then checks which of the 5 following buffers went through */
+fn check_leaky(leaky: &str, expected: &[u64]) {
+    init();
+
+    let pipeline = gst::Pipeline::new(None);
+    let appsrc = gst::ElementFactory::make("appsrc", None).unwrap();
+    let proxysink = gst::ElementFactory::make("ts-proxysink", None).unwrap();
+    let proxysrc = gst::ElementFactory::make("ts-proxysrc", None).unwrap();
+    let appsink = gst::ElementFactory::make("appsink", None).unwrap();
+
+    pipeline
+        .add_many(&[&appsrc, &proxysink, &proxysrc, &appsink])
+        .unwrap();
+    appsrc.link(&proxysink).unwrap();
+    proxysrc.link(&appsink).unwrap();
+
+    let proxy_context = format!("proxy::test_leaky_{}", leaky);
+    appsrc.set_property("format", gst::Format::Time);
+    proxysink.set_property("proxy-context", &proxy_context);
+    proxysink.set_property_from_str("leaky", leaky);
+    proxysrc.set_property("proxy-context", &proxy_context);
+    // The streaming thread of the Context gets blocked downstream
+    proxysrc.set_property("context", &proxy_context);
+    proxysrc.set_property("max-size-buffers", 2u32);
+    proxysrc.set_property("max-size-bytes", 0u32);
+    proxysrc.set_property("max-size-time", 0u64);
+    appsink.set_property("sync", false);
+
+    let gate = Arc::new(Gate::default());
+    gate.install(&appsink);
+
+    let appsrc = appsrc.dynamic_cast::<gst_app::AppSrc>().unwrap();
+
+    pipeline.set_state(gst::State::Playing).unwrap();
+
+    push_buffer(&appsrc, 0);
+    gate.wait_blocked();
+
+    for idx in 1..=5 {
+        push_buffer(&appsrc, idx);
+    }
+    wait_dropped(&proxysink, 3);
+
+    assert_eq!(gate.release(), vec![0]);
+    appsrc.end_of_stream().unwrap();
+    wait_eos(&pipeline);
+
+    assert_eq!(gate.received(), expected);
+    assert_eq!(proxysink.property::<u64>("dropped"), 3);
+
+    pipeline.set_state(gst::State::Null).unwrap();
+}
+
+#[test]
+fn test_leaky_upstream() {
+    // The new buffers are dropped
+    check_leaky("upstream", &[0, 10, 20]);
+}
+
+#[test]
+fn test_leaky_downstream() {
+    // The old buffers are dropped
+    check_leaky("downstream", &[0, 40, 50]);
+}
@@ -19,7 +19,8 @@
 
 use gst::prelude::*;
 
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
 
 fn init() {
     use std::sync::Once;
@@ -90,3 +91,182 @@ fn test_push() {
 
     pipeline.set_state(gst::State::Null).unwrap();
 }
+
+/// Appsink callbacks which record the PTS of the buffers, in ms, and
+/// hold the streaming thread after the first one until released.
+#[derive(Default)]
+struct Gate {
+    received: Mutex<(Vec<u64>, bool)>,
+    cond: Condvar,
+}
+
+impl Gate {
+    fn install(self: &Arc<Self>, appsink: &gst::Element) {
+        let gate = self.clone();
+        appsink
+            .clone()
+            .dynamic_cast::<gst_app::AppSink>()
+            .unwrap()
+            .set_callbacks(
+                gst_app::AppSinkCallbacks::builder()
+                    .new_sample(move |appsink| {
+                        let sample = appsink.pull_sample().unwrap();
+                        let pts = sample.buffer().unwrap().pts().unwrap();
+
+                        let mut received = gate.received.lock().unwrap();
+                        received.0.push(pts.mseconds());
+                        gate.cond.notify_all();
+                        let _ = gate.cond.wait_while(received, |(_, released)| !*released);
+
+                        Ok(gst::FlowSuccess::Ok)
+                    })
+                    .build(),
+            );
+    }
+
+    fn wait_blocked(&self) {
+        let received = self.received.lock().unwrap();
+        let (received, _) = self
+            .cond
+            .wait_timeout_while(received, Duration::from_secs(5), |(received, _)| {
+                received.is_empty()
+            })
+            .unwrap();
+        assert_eq!(received.0, vec![0]);
+    }
+
+    fn release(&self) -> Vec<u64> {
+        let mut received = self.received.lock().unwrap();
+        received.1 = true;
+        self.cond.notify_all();
+
+        received.0.clone()
+    }
+
+    fn received(&self) -> Vec<u64> {
+        self.received.lock().unwrap().0.clone()
+    }
+}
+
+fn push_buffer(appsrc: &gst_app::AppSrc, idx: u64) {
+    let mut buffer = gst::Buffer::from_slice(vec![0; 100]);
+    buffer
+        .get_mut()
+        .unwrap()
+        .set_pts(idx * 10 * gst::ClockTime::MSECOND);
+    appsrc.push_buffer(buffer).unwrap();
+}
+
+fn wait_dropped(element: &gst::Element, dropped: u64) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while element.property::<u64>("dropped") < dropped {
+        assert!(Instant::now() < deadline, "Timed out waiting for drops");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn wait_eos(pipeline: &gst::Pipeline) {
+    let mut eos = false;
+    let bus = pipeline.bus().unwrap();
+    while let Some(msg) = bus.timed_pop(5 * gst::ClockTime::SECOND) {
+        use gst::MessageView;
+        match msg.view() {
+            MessageView::Eos(..) => {
+                eos = true;
+                break;
+            }
+            MessageView::Error(err) => unreachable!("queue::test_leaky {:?}", err),
+            _ => (),
+        }
+    }
+
+    assert!(eos);
+}
+
+/* Fills a queue of 2 buffers while downstream is blocked on the first
+ * buffer, then checks which of the 5 following buffers went through */
+fn check_leaky(leaky: &str, expected: &[u64]) {
+    init();
+
+    let pipeline = gst::Pipeline::new(None);
+    let appsrc = gst::ElementFactory::make("appsrc", None).unwrap();
+    let queue = gst::ElementFactory::make("ts-queue", None).unwrap();
+    let appsink = gst::ElementFactory::make("appsink", None).unwrap();
+
+    pipeline.add_many(&[&appsrc, &queue, &appsink]).unwrap();
+    appsrc.link(&queue).unwrap();
+    queue.link(&appsink).unwrap();
+
+    appsrc.set_property("format", gst::Format::Time);
+    // The streaming thread of the Context gets blocked downstream
+    queue.set_property("context", format!("queue::test_leaky_{}", leaky));
+    queue.set_property("max-size-buffers", 2u32);
+    queue.set_property("max-size-bytes", 0u32);
+    queue.set_property("max-size-time", 0u64);
+    queue.set_property_from_str("leaky", leaky);
+    appsink.set_property("sync", false);
+
+    let gate = Arc::new(Gate::default());
+    gate.install(&appsink);
+
+    let appsrc = appsrc.dynamic_cast::<gst_app::AppSrc>().unwrap();
+
+    pipeline.set_state(gst::State::Playing).unwrap();
+
+    push_buffer(&appsrc, 0);
+    gate.wait_blocked();
+
+    for idx in 1..=5 {
+        push_buffer(&appsrc, idx);
+    }
+    wait_dropped(&queue, 3);
+
+    assert_eq!(gate.release(), vec![0]);
+    appsrc.end_of_stream().unwrap();
+    wait_eos(&pipeline);
+
+    assert_eq!(gate.received(), expected);
+    assert_eq!(queue.property::<u64>("dropped"), 3);
+
+    pipeline.set_state(gst::State::Null).unwrap();
+}
+
+#[test]
+fn test_leaky_upstream() {
+    // The new buffers are dropped
+    check_leaky("upstream", &[0, 10, 20]);
+}
+
+#[test]
+fn test_leaky_downstream() {
+    // The old buffers are dropped
+    check_leaky("downstream", &[0, 40, 50]);
+}
+
+#[test]
+fn test_context_stats() {
+    init();
+
+    let pipeline = gst::Pipeline::new(None);
+    let queue = gst::ElementFactory::make("ts-queue", None).unwrap();
+    queue.set_property("context", "queue-context-stats");
+    queue.set_property("context-stats-interval", 10u32);
+    pipeline.add(&queue).unwrap();
+
+    // The Context is acquired when the queue is prepared
+    pipeline.set_state(gst::State::Ready).unwrap();
+
+    let bus = pipeline.bus().unwrap();
+    let msg = bus
+        .timed_pop_filtered(5 * gst::ClockTime::SECOND, &[gst::MessageType::Element])
+        .expect("No Context statistics posted");
+    assert_eq!(msg.src().map(|src| src.name()), Some(queue.name()));
+
+    let s = msg.structure().unwrap();
+    assert_eq!(s.name(), "ts-context-stats");
+    assert_eq!(s.get::<&str>("context").unwrap(), "queue-context-stats");
+    assert!(s.get::<u64>("tasks-alive").unwrap() >= 1);
+    assert!((0.0..=1.0).contains(&s.get::<f64>("busy-ratio").unwrap()));
+
+    pipeline.set_state(gst::State::Null).unwrap();
+}
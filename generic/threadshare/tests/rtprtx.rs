@@ -0,0 +1,147 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use gst::prelude::*;
+
+const CAPS: &str = "application/x-rtp,media=audio,payload=96,clock-rate=8000,encoding-name=L16";
+const SSRC: u32 = 0x1234;
+const RTX_SSRC: u32 = 0x5678;
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstthreadshare::plugin_register_static().expect("gstthreadshare rtprtx test");
+    });
+}
+
+fn rtp_buffer(pt: u8, seqnum: u16, ssrc: u32, payload: &[u8]) -> gst::Buffer {
+    let mut data = vec![0x80, pt];
+    data.extend_from_slice(&seqnum.to_be_bytes());
+    data.extend_from_slice(&(seqnum as u32 * 160).to_be_bytes());
+    data.extend_from_slice(&ssrc.to_be_bytes());
+    data.extend_from_slice(payload);
+
+    gst::Buffer::from_mut_slice(data)
+}
+
+fn pt_map() -> gst::Structure {
+    gst::Structure::builder("application/x-rtp-pt-map")
+        .field("96", 97u32)
+        .build()
+}
+
+fn retransmission_request(seqnum: u16, ssrc: u32) -> gst::Event {
+    gst::event::CustomUpstream::new(
+        gst::Structure::builder("GstRTPRetransmissionRequest")
+            .field("seqnum", seqnum as u32)
+            .field("ssrc", ssrc)
+            .build(),
+    )
+}
+
+#[test]
+fn test_rtx_send_receive() {
+    init();
+
+    let mut h_send = gst_check::Harness::new("ts-rtprtxsend");
+    let rtxsend = h_send.element().unwrap();
+    rtxsend.set_property("payload-type-map", pt_map());
+    rtxsend.set_property(
+        "ssrc-map",
+        gst::Structure::builder("application/x-rtp-ssrc-map")
+            .field(&SSRC.to_string(), RTX_SSRC)
+            .build(),
+    );
+    h_send.play();
+    h_send.set_src_caps_str(CAPS);
+
+    for seqnum in 100..105u16 {
+        let buffer = rtp_buffer(96, seqnum, SSRC, &[seqnum as u8; 4]);
+        assert_eq!(h_send.push(buffer), Ok(gst::FlowSuccess::Ok));
+        let buffer = h_send.pull().unwrap();
+        assert_eq!(buffer.map_readable().unwrap()[3], seqnum as u8);
+    }
+
+    assert!(h_send.push_upstream_event(retransmission_request(102, SSRC)));
+    // Unknown packets are not retransmitted
+    assert!(h_send.push_upstream_event(retransmission_request(10, SSRC)));
+
+    let rtx_buffer = h_send.pull().unwrap();
+    {
+        let data = rtx_buffer.map_readable().unwrap();
+        assert_eq!(data.len(), 12 + 2 + 4);
+        assert_eq!(data[1] & 0x7f, 97);
+        assert_eq!(&data[8..12], &RTX_SSRC.to_be_bytes());
+        assert_eq!(&data[12..14], &102u16.to_be_bytes());
+        assert_eq!(&data[14..], &[102u8; 4]);
+    }
+    assert!(h_send.try_pull().is_none());
+
+    let stats = rtxsend.property::<gst::Structure>("stats");
+    assert_eq!(stats.get::<u64>("num-rtx-requests").unwrap(), 2);
+    assert_eq!(stats.get::<u64>("num-rtx-packets").unwrap(), 1);
+
+    let mut h_receive = gst_check::Harness::new("ts-rtprtxreceive");
+    let rtxreceive = h_receive.element().unwrap();
+    rtxreceive.set_property("payload-type-map", pt_map());
+    h_receive.play();
+    h_receive.set_src_caps_str(CAPS);
+
+    // Retransmissions can't be associated without a request
+    assert_eq!(h_receive.push(rtx_buffer.clone()), Ok(gst::FlowSuccess::Ok));
+    assert!(h_receive.try_pull().is_none());
+
+    assert!(h_receive.push_upstream_event(retransmission_request(102, SSRC)));
+    assert_eq!(h_receive.push(rtx_buffer), Ok(gst::FlowSuccess::Ok));
+
+    let buffer = h_receive.pull().unwrap();
+    assert_ne!(
+        buffer.flags().bits() & gst_rtp::ffi::GST_RTP_BUFFER_FLAG_RETRANSMISSION,
+        0
+    );
+    {
+        let data = buffer.map_readable().unwrap();
+        assert_eq!(
+            data.as_slice(),
+            rtp_buffer(96, 102, SSRC, &[102u8; 4])
+                .map_readable()
+                .unwrap()
+                .as_slice()
+        );
+    }
+
+    // Media packets are forwarded untouched
+    assert_eq!(
+        h_receive.push(rtp_buffer(96, 105, SSRC, &[105u8; 4])),
+        Ok(gst::FlowSuccess::Ok)
+    );
+    let buffer = h_receive.pull().unwrap();
+    assert_eq!(
+        buffer.flags().bits() & gst_rtp::ffi::GST_RTP_BUFFER_FLAG_RETRANSMISSION,
+        0
+    );
+
+    let stats = rtxreceive.property::<gst::Structure>("stats");
+    assert_eq!(stats.get::<u64>("num-rtx-requests").unwrap(), 1);
+    assert_eq!(stats.get::<u64>("num-rtx-packets").unwrap(), 2);
+    assert_eq!(stats.get::<u64>("num-rtx-assoc-packets").unwrap(), 1);
+}
@@ -0,0 +1,210 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use gst::glib;
+use gst::prelude::*;
+
+use std::io::Read;
+use std::net;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstthreadshare::plugin_register_static().expect("gstthreadshare tcpserversink test");
+    });
+}
+
+#[test]
+fn test_rfc4571() {
+    init();
+
+    let mut h = gst_check::Harness::new("ts-tcpserversink");
+    let tcpserversink = h.element().unwrap();
+    tcpserversink.set_property("port", 0i32);
+    tcpserversink.set_property("sync", false);
+    tcpserversink.set_property_from_str("framing", "rfc4571");
+
+    let (conn_tx, conn_rx) = mpsc::channel();
+    let conn_tx = Mutex::new(conn_tx);
+    tcpserversink.connect("client-connected", false, move |args| {
+        let port = args[2].get::<i32>().unwrap();
+        conn_tx.lock().unwrap().send(port).unwrap();
+        None
+    });
+
+    h.play();
+    h.set_src_caps_str("foo/bar");
+
+    let port = tcpserversink.property::<i32>("current-port");
+    assert_ne!(port, 0);
+
+    let mut socket = net::TcpStream::connect(("127.0.0.1", port as u16)).unwrap();
+    let client_port = socket.local_addr().unwrap().port() as i32;
+    assert_eq!(conn_rx.recv().unwrap(), client_port);
+
+    for size in [100usize, 300] {
+        let buffer = gst::Buffer::from_mut_slice(vec![size as u8; size]);
+        assert_eq!(h.push(buffer), Ok(gst::FlowSuccess::Ok));
+    }
+
+    for size in [100usize, 300] {
+        let mut header = [0u8; 2];
+        socket.read_exact(&mut header).unwrap();
+        assert_eq!(u16::from_be_bytes(header) as usize, size);
+
+        let mut data = vec![0u8; size];
+        socket.read_exact(&mut data).unwrap();
+        assert!(data.iter().all(|b| *b == size as u8));
+    }
+}
+
+#[test]
+fn test_client_framing() {
+    init();
+
+    let mut h = gst_check::Harness::new("ts-tcpserversink");
+    let tcpserversink = h.element().unwrap();
+    tcpserversink.set_property("port", 0i32);
+    tcpserversink.set_property("sync", false);
+
+    let framing_type = tcpserversink.find_property("framing").unwrap().value_type();
+    let rfc4571 = glib::EnumClass::new(framing_type)
+        .unwrap()
+        .to_value_by_nick("rfc4571")
+        .unwrap();
+
+    // Only the first client uses RFC 4571 framing
+    let (conn_tx, conn_rx) = mpsc::channel();
+    let conn_tx = Mutex::new(conn_tx);
+    let first = AtomicBool::new(true);
+    tcpserversink.connect("client-connected", false, move |args| {
+        let element = args[0].get::<gst::Element>().unwrap();
+        let host = args[1].get::<String>().unwrap();
+        let port = args[2].get::<i32>().unwrap();
+
+        if first.swap(false, Ordering::SeqCst) {
+            element.emit_by_name::<()>("set-client-framing", &[&host, &port, &rfc4571]);
+        }
+
+        conn_tx.lock().unwrap().send(port).unwrap();
+        None
+    });
+
+    h.play();
+    h.set_src_caps_str("foo/bar");
+
+    let port = tcpserversink.property::<i32>("current-port");
+    assert_ne!(port, 0);
+
+    let mut framed = net::TcpStream::connect(("127.0.0.1", port as u16)).unwrap();
+    assert_eq!(
+        conn_rx.recv().unwrap(),
+        framed.local_addr().unwrap().port() as i32
+    );
+
+    let mut raw = net::TcpStream::connect(("127.0.0.1", port as u16)).unwrap();
+    assert_eq!(
+        conn_rx.recv().unwrap(),
+        raw.local_addr().unwrap().port() as i32
+    );
+
+    let buffer = gst::Buffer::from_mut_slice(vec![1u8; 100]);
+    assert_eq!(h.push(buffer), Ok(gst::FlowSuccess::Ok));
+
+    let mut header = [0u8; 2];
+    framed.read_exact(&mut header).unwrap();
+    assert_eq!(u16::from_be_bytes(header), 100);
+
+    let mut data = vec![0u8; 100];
+    framed.read_exact(&mut data).unwrap();
+    assert!(data.iter().all(|b| *b == 1));
+
+    let mut data = vec![0u8; 100];
+    raw.read_exact(&mut data).unwrap();
+    assert!(data.iter().all(|b| *b == 1));
+}
+
+/* A client not reading doesn't prevent the others from being served,
+ * it gets disconnected once its queue is full */
+#[test]
+fn test_slow_client() {
+    init();
+
+    const SIZE: usize = 65536;
+    const NUM_BUFFERS: usize = 400;
+
+    let mut h = gst_check::Harness::new("ts-tcpserversink");
+    let tcpserversink = h.element().unwrap();
+    tcpserversink.set_property("port", 0i32);
+    tcpserversink.set_property("sync", false);
+
+    let (conn_tx, conn_rx) = mpsc::channel();
+    let conn_tx = Mutex::new(conn_tx);
+    tcpserversink.connect("client-connected", false, move |args| {
+        let port = args[2].get::<i32>().unwrap();
+        conn_tx.lock().unwrap().send(port).unwrap();
+        None
+    });
+
+    let (disconn_tx, disconn_rx) = mpsc::channel();
+    let disconn_tx = Mutex::new(disconn_tx);
+    tcpserversink.connect("client-disconnected", false, move |args| {
+        let port = args[2].get::<i32>().unwrap();
+        disconn_tx.lock().unwrap().send(port).unwrap();
+        None
+    });
+
+    h.play();
+    h.set_src_caps_str("foo/bar");
+
+    let port = tcpserversink.property::<i32>("current-port");
+
+    let slow = net::TcpStream::connect(("127.0.0.1", port as u16)).unwrap();
+    let slow_port = slow.local_addr().unwrap().port() as i32;
+    assert_eq!(conn_rx.recv().unwrap(), slow_port);
+
+    let mut fast = net::TcpStream::connect(("127.0.0.1", port as u16)).unwrap();
+    assert_eq!(
+        conn_rx.recv().unwrap(),
+        fast.local_addr().unwrap().port() as i32
+    );
+
+    for i in 0..NUM_BUFFERS {
+        let buffer = gst::Buffer::from_mut_slice(vec![i as u8; SIZE]);
+        assert_eq!(h.push(buffer), Ok(gst::FlowSuccess::Ok));
+
+        let mut data = vec![0u8; SIZE];
+        fast.read_exact(&mut data).unwrap();
+        assert!(data.iter().all(|b| *b == i as u8));
+    }
+
+    assert_eq!(
+        disconn_rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+        slow_port
+    );
+    assert!(disconn_rx.try_recv().is_err());
+
+    drop(slow);
+}
@@ -0,0 +1,193 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use gio::prelude::*;
+use gst::glib;
+use gst::prelude::*;
+
+use std::io::Write;
+use std::net;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex};
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstthreadshare::plugin_register_static().expect("gstthreadshare tcpserversrc test");
+    });
+}
+
+#[test]
+fn test_rfc4571() {
+    init();
+
+    let pipeline = gst::Pipeline::new(None);
+
+    let tcpserversrc = gst::ElementFactory::make("ts-tcpserversrc", None).unwrap();
+    let appsink = gst::ElementFactory::make("appsink", None).unwrap();
+    appsink.set_property("sync", false);
+
+    pipeline.add_many(&[&tcpserversrc, &appsink]).unwrap();
+    tcpserversrc.link(&appsink).unwrap();
+
+    tcpserversrc.set_property("port", 0i32);
+    tcpserversrc.set_property_from_str("framing", "rfc4571");
+
+    let (conn_tx, conn_rx) = mpsc::channel();
+    let conn_tx = Mutex::new(conn_tx);
+    tcpserversrc.connect("client-connected", false, move |args| {
+        let port = args[2].get::<i32>().unwrap();
+        conn_tx.lock().unwrap().send(port).unwrap();
+        None
+    });
+
+    pipeline.set_state(gst::State::Playing).unwrap();
+
+    let port = tcpserversrc.property::<i32>("current-port");
+    assert_ne!(port, 0);
+
+    let mut socket = net::TcpStream::connect(("127.0.0.1", port as u16)).unwrap();
+    let client_port = socket.local_addr().unwrap().port() as i32;
+
+    // Two frames of different sizes, written in a single go so that the
+    // element has to split them according to their length prefixes
+    let mut data = Vec::new();
+    for size in [100u16, 300] {
+        data.extend_from_slice(&size.to_be_bytes());
+        data.extend(std::iter::repeat(size as u8).take(size as usize));
+    }
+    socket.write_all(&data).unwrap();
+
+    assert_eq!(conn_rx.recv().unwrap(), client_port);
+
+    let appsink = appsink.dynamic_cast::<gst_app::AppSink>().unwrap();
+    for size in [100usize, 300] {
+        let sample = appsink
+            .try_pull_sample(5 * gst::ClockTime::SECOND)
+            .expect("no sample");
+        let buffer = sample.buffer().unwrap();
+        let map = buffer.map_readable().unwrap();
+        assert_eq!(map.len(), size);
+        assert!(map.iter().all(|b| *b == size as u8));
+
+        let meta = buffer.meta::<gst_net::NetAddressMeta>().unwrap();
+        let addr = meta
+            .addr()
+            .dynamic_cast::<gio::InetSocketAddress>()
+            .unwrap();
+        assert_eq!(addr.port() as i32, client_port);
+    }
+
+    pipeline.set_state(gst::State::Null).unwrap();
+}
+
+#[test]
+fn test_client_framing() {
+    init();
+
+    let pipeline = gst::Pipeline::new(None);
+
+    let tcpserversrc = gst::ElementFactory::make("ts-tcpserversrc", None).unwrap();
+    let appsink = gst::ElementFactory::make("appsink", None).unwrap();
+    appsink.set_property("sync", false);
+
+    pipeline.add_many(&[&tcpserversrc, &appsink]).unwrap();
+    tcpserversrc.link(&appsink).unwrap();
+
+    tcpserversrc.set_property("port", 0i32);
+
+    let framing_type = tcpserversrc.find_property("framing").unwrap().value_type();
+    let rfc4571 = glib::EnumClass::new(framing_type)
+        .unwrap()
+        .to_value_by_nick("rfc4571")
+        .unwrap();
+
+    // Only the first client uses RFC 4571 framing
+    let (conn_tx, conn_rx) = mpsc::channel();
+    let conn_tx = Mutex::new(conn_tx);
+    let first = AtomicBool::new(true);
+    tcpserversrc.connect("client-connected", false, move |args| {
+        let element = args[0].get::<gst::Element>().unwrap();
+        let host = args[1].get::<String>().unwrap();
+        let port = args[2].get::<i32>().unwrap();
+
+        if first.swap(false, Ordering::SeqCst) {
+            element.emit_by_name::<()>("set-client-framing", &[&host, &port, &rfc4571]);
+        }
+
+        conn_tx.lock().unwrap().send(port).unwrap();
+        None
+    });
+
+    pipeline.set_state(gst::State::Playing).unwrap();
+
+    let port = tcpserversrc.property::<i32>("current-port");
+    assert_ne!(port, 0);
+
+    let appsink = appsink.dynamic_cast::<gst_app::AppSink>().unwrap();
+    let pull = || {
+        let sample = appsink
+            .try_pull_sample(5 * gst::ClockTime::SECOND)
+            .expect("no sample");
+        let buffer = sample.buffer().unwrap();
+
+        let meta = buffer.meta::<gst_net::NetAddressMeta>().unwrap();
+        let addr = meta
+            .addr()
+            .dynamic_cast::<gio::InetSocketAddress>()
+            .unwrap();
+
+        (buffer.map_readable().unwrap().to_vec(), addr.port() as i32)
+    };
+
+    let mut framed = net::TcpStream::connect(("127.0.0.1", port as u16)).unwrap();
+    let framed_port = framed.local_addr().unwrap().port() as i32;
+    assert_eq!(conn_rx.recv().unwrap(), framed_port);
+
+    let mut raw = net::TcpStream::connect(("127.0.0.1", port as u16)).unwrap();
+    let raw_port = raw.local_addr().unwrap().port() as i32;
+    assert_eq!(conn_rx.recv().unwrap(), raw_port);
+
+    let mut data = 100u16.to_be_bytes().to_vec();
+    data.extend(std::iter::repeat(1u8).take(100));
+    framed.write_all(&data).unwrap();
+
+    let (data, port) = pull();
+    assert_eq!(port, framed_port);
+    assert_eq!(data, vec![1u8; 100]);
+
+    // Without framing, the length prefix is part of the data
+    let mut data = 50u16.to_be_bytes().to_vec();
+    data.extend(std::iter::repeat(2u8).take(50));
+    raw.write_all(&data).unwrap();
+
+    let mut received = Vec::new();
+    while received.len() < 52 {
+        let (data, port) = pull();
+        assert_eq!(port, raw_port);
+        received.extend_from_slice(&data);
+    }
+    assert_eq!(received[..2], 50u16.to_be_bytes());
+    assert!(received[2..].iter().all(|b| *b == 2));
+
+    pipeline.set_state(gst::State::Null).unwrap();
+}
@@ -0,0 +1,266 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use gst::prelude::*;
+
+use gstthreadshare::runtime::Context;
+
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstthreadshare::plugin_register_static().expect("gstthreadshare tee test");
+    });
+}
+
+fn collect_samples(appsink: &gst::Element) -> Arc<Mutex<Vec<gst::Sample>>> {
+    let samples = Arc::new(Mutex::new(Vec::new()));
+
+    let appsink = appsink.clone().dynamic_cast::<gst_app::AppSink>().unwrap();
+    let samples_clone = samples.clone();
+    appsink.set_callbacks(
+        gst_app::AppSinkCallbacks::builder()
+            .new_sample(move |appsink| {
+                let sample = appsink.pull_sample().unwrap();
+
+                samples_clone.lock().unwrap().push(sample);
+
+                Ok(gst::FlowSuccess::Ok)
+            })
+            .build(),
+    );
+
+    samples
+}
+
+#[test]
+fn test_push() {
+    init();
+
+    let pipeline = gst::Pipeline::new(None);
+    let fakesrc = gst::ElementFactory::make("fakesrc", None).unwrap();
+    let tee = gst::ElementFactory::make("ts-tee", None).unwrap();
+    let appsink_0 = gst::ElementFactory::make("appsink", None).unwrap();
+    let appsink_1 = gst::ElementFactory::make("appsink", None).unwrap();
+
+    pipeline
+        .add_many(&[&fakesrc, &tee, &appsink_0, &appsink_1])
+        .unwrap();
+    fakesrc.link(&tee).unwrap();
+    tee.link(&appsink_0).unwrap();
+    tee.link(&appsink_1).unwrap();
+
+    fakesrc.set_property("num-buffers", 3i32);
+    tee.set_property("context", "tee::test_push");
+
+    let samples_0 = collect_samples(&appsink_0);
+    let samples_1 = collect_samples(&appsink_1);
+
+    pipeline.set_state(gst::State::Playing).unwrap();
+
+    let mut eos = false;
+    let bus = pipeline.bus().unwrap();
+    while let Some(msg) = bus.timed_pop(5 * gst::ClockTime::SECOND) {
+        use gst::MessageView;
+        match msg.view() {
+            MessageView::Eos(..) => {
+                eos = true;
+                break;
+            }
+            MessageView::Error(err) => unreachable!("tee::test_push {:?}", err),
+            _ => (),
+        }
+    }
+
+    assert!(eos);
+    for samples in [samples_0, samples_1] {
+        let samples = samples.lock().unwrap();
+        assert_eq!(samples.len(), 3);
+
+        for sample in samples.iter() {
+            assert!(sample.buffer().is_some());
+        }
+    }
+
+    let stats = tee.property::<gst::Structure>("stats");
+    assert_eq!(stats.get::<u64>("src_0").unwrap(), 0);
+    assert_eq!(stats.get::<u64>("src_1").unwrap(), 0);
+
+    pipeline.set_state(gst::State::Null).unwrap();
+}
+
+#[test]
+fn test_request_release() {
+    init();
+
+    let tee = gst::ElementFactory::make("ts-tee", None).unwrap();
+
+    let src_0 = tee.request_pad_simple("src_%u").unwrap();
+    let src_1 = tee.request_pad_simple("src_%u").unwrap();
+    assert_eq!(src_0.name(), "src_0");
+    assert_eq!(src_1.name(), "src_1");
+    assert_eq!(tee.src_pads().len(), 2);
+
+    tee.set_state(gst::State::Paused).unwrap();
+
+    // Requesting and releasing branches while running
+    let src_2 = tee.request_pad_simple("src_%u").unwrap();
+    assert_eq!(src_2.name(), "src_2");
+    tee.release_request_pad(&src_0);
+    assert_eq!(tee.src_pads().len(), 2);
+
+    let stats = tee.property::<gst::Structure>("stats");
+    assert!(!stats.has_field("src_0"));
+    assert!(stats.has_field("src_1"));
+    assert!(stats.has_field("src_2"));
+
+    tee.set_state(gst::State::Null).unwrap();
+
+    tee.release_request_pad(&src_1);
+    tee.release_request_pad(&src_2);
+    assert!(tee.src_pads().is_empty());
+}
+
+/* Blocks the thread of the Context `name` until the returned Sender is dropped */
+fn block_context(name: &str) -> mpsc::Sender<()> {
+    let (blocked_sender, blocked_receiver) = mpsc::channel();
+    let (release_sender, release_receiver) = mpsc::channel::<()>();
+
+    let context = Context::acquire(name, Duration::ZERO).unwrap();
+    let _ = context.spawn(async move {
+        blocked_sender.send(()).unwrap();
+        let _ = release_receiver.recv();
+    });
+
+    blocked_receiver
+        .recv_timeout(Duration::from_secs(5))
+        .expect("Context not blocked");
+
+    release_sender
+}
+
+/* Pushes 6 buffers while the branches of a tee holding 2 buffers per branch
+ * are blocked, then checks which buffers went through each branch */
+fn check_leaky(leaky: &str, expected: &[u64]) {
+    init();
+
+    let pipeline = gst::Pipeline::new(None);
+    let appsrc = gst::ElementFactory::make("appsrc", None).unwrap();
+    let tee = gst::ElementFactory::make("ts-tee", None).unwrap();
+    let appsink_0 = gst::ElementFactory::make("appsink", None).unwrap();
+    let appsink_1 = gst::ElementFactory::make("appsink", None).unwrap();
+
+    pipeline
+        .add_many(&[&appsrc, &tee, &appsink_0, &appsink_1])
+        .unwrap();
+    appsrc.link(&tee).unwrap();
+    tee.link(&appsink_0).unwrap();
+    tee.link(&appsink_1).unwrap();
+
+    let context = format!("tee::test_leaky_{}", leaky);
+    appsrc.set_property("format", gst::Format::Time);
+    tee.set_property("context", &context);
+    tee.set_property("max-size-buffers", 2u32);
+    tee.set_property("max-size-bytes", 0u32);
+    tee.set_property("max-size-time", 0u64);
+    tee.set_property_from_str("leaky", leaky);
+    appsink_0.set_property("sync", false);
+    appsink_1.set_property("sync", false);
+
+    let samples_0 = collect_samples(&appsink_0);
+    let samples_1 = collect_samples(&appsink_1);
+
+    let appsrc = appsrc.dynamic_cast::<gst_app::AppSrc>().unwrap();
+
+    pipeline.set_state(gst::State::Playing).unwrap();
+
+    let release = block_context(&context);
+
+    for idx in 0..6u64 {
+        let mut buffer = gst::Buffer::from_slice(vec![0; 100]);
+        buffer
+            .get_mut()
+            .unwrap()
+            .set_pts(idx * 10 * gst::ClockTime::MSECOND);
+        appsrc.push_buffer(buffer).unwrap();
+    }
+
+    let dropped = |stats: &gst::Structure| {
+        (
+            stats.get::<u64>("src_0").unwrap(),
+            stats.get::<u64>("src_1").unwrap(),
+        )
+    };
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while dropped(&tee.property::<gst::Structure>("stats")) != (4, 4) {
+        assert!(Instant::now() < deadline, "Timed out waiting for drops");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    drop(release);
+    appsrc.end_of_stream().unwrap();
+
+    let mut eos = false;
+    let bus = pipeline.bus().unwrap();
+    while let Some(msg) = bus.timed_pop(5 * gst::ClockTime::SECOND) {
+        use gst::MessageView;
+        match msg.view() {
+            MessageView::Eos(..) => {
+                eos = true;
+                break;
+            }
+            MessageView::Error(err) => unreachable!("tee::test_leaky {:?}", err),
+            _ => (),
+        }
+    }
+
+    assert!(eos);
+    for samples in [samples_0, samples_1] {
+        let pts = samples
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|sample| sample.buffer().unwrap().pts().unwrap().mseconds())
+            .collect::<Vec<_>>();
+        assert_eq!(pts, expected);
+    }
+
+    // Each branch accounts for its own drops
+    assert_eq!(dropped(&tee.property::<gst::Structure>("stats")), (4, 4));
+
+    pipeline.set_state(gst::State::Null).unwrap();
+}
+
+#[test]
+fn test_leaky_upstream() {
+    // The new buffers are dropped
+    check_leaky("upstream", &[0, 10]);
+}
+
+#[test]
+fn test_leaky_downstream() {
+    // The old buffers are dropped
+    check_leaky("downstream", &[40, 50]);
+}
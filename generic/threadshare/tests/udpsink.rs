@@ -19,6 +19,7 @@
 
 use std::thread;
 
+use gio::prelude::*;
 use gst::prelude::*;
 
 fn init() {
@@ -103,3 +104,78 @@ fn test_chain() {
     let buf = gst::Buffer::from_slice(&[42, 43, 44, 45]);
     assert!(h.push(buf) == Ok(gst::FlowSuccess::Ok));
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_chain_list_batched() {
+    init();
+
+    for gso in [false, true] {
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+            .unwrap();
+        let port = socket.local_addr().unwrap().port();
+
+        let mut h = gst_check::Harness::new("ts-udpsink");
+        h.set_src_caps_str("foo/bar");
+        {
+            let udpsink = h.element().unwrap();
+            udpsink.set_property("clients", format!("127.0.0.1:{}", port));
+            udpsink.set_property("sync", false);
+            udpsink.set_property("batch-send", true);
+            udpsink.set_property("gso", gso);
+        }
+
+        // Equally sized buffers followed by a shorter one, as sent with GSO
+        let mut list = gst::BufferList::new();
+        {
+            let list = list.get_mut().unwrap();
+            for i in 0..9u8 {
+                list.add(gst::Buffer::from_mut_slice(vec![i; 100]));
+            }
+            list.add(gst::Buffer::from_mut_slice(vec![9; 50]));
+        }
+
+        assert_eq!(
+            h.srcpad().unwrap().push_list(list),
+            Ok(gst::FlowSuccess::Ok)
+        );
+
+        let mut buf = [0; 200];
+        for i in 0..10u8 {
+            let (amt, _) = socket.recv_from(&mut buf).unwrap();
+            assert_eq!(amt, if i < 9 { 100 } else { 50 });
+            assert!(buf[..amt].iter().all(|b| *b == i));
+        }
+    }
+}
+
+#[test]
+#[cfg(not(windows))]
+fn test_multicast_hops() {
+    init();
+
+    let mut h = gst_check::Harness::new("ts-udpsink");
+    h.set_src_caps_str("foo/bar");
+
+    let udpsink = h.element().unwrap();
+    udpsink.set_property("clients", "239.255.42.2:5040,ff15::42:5040");
+    udpsink.set_property("auto-multicast", false);
+    udpsink.set_property("ttl-mc", 7u32);
+    udpsink.set_property("sync", false);
+
+    // Sending fails on hosts without multicast routes, but
+    // the socket options are set up before sending anyway
+    let _ = h.push(gst::Buffer::from_slice([0; 4]));
+
+    let socket = udpsink
+        .property::<Option<gio::Socket>>("used-socket")
+        .unwrap();
+    assert_eq!(socket.multicast_ttl(), 7);
+
+    // Only available if the host supports IPv6
+    if let Some(socket) = udpsink.property::<Option<gio::Socket>>("used-socket-v6") {
+        assert_eq!(socket.multicast_ttl(), 7);
+    }
+}
@@ -158,3 +158,109 @@ fn test_socket_reuse() {
         assert_eq!(buffer.size(), 160);
     }
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_push_batched() {
+    init();
+
+    let mut h = gst_check::Harness::new("ts-udpsrc");
+
+    {
+        let udpsrc = h.element().unwrap();
+        udpsrc.set_property("port", 5010i32);
+        udpsrc.set_property("context", "test-push-batched");
+        udpsrc.set_property("batch-size", 8u32);
+    }
+
+    h.play();
+
+    thread::spawn(move || {
+        use std::net;
+        use std::time;
+
+        // Sleep 50ms to allow for the udpsrc to be ready to actually receive data
+        thread::sleep(time::Duration::from_millis(50));
+
+        let socket = net::UdpSocket::bind("0.0.0.0:0").unwrap();
+        for i in 0..20u8 {
+            socket
+                .send_to(&[i; 100][..(100 - i as usize)], "127.0.0.1:5010")
+                .unwrap();
+        }
+    });
+
+    for i in 0..20usize {
+        let buffer = h.pull().unwrap();
+        assert_eq!(buffer.size(), 100 - i);
+
+        let map = buffer.map_readable().unwrap();
+        assert!(map.iter().all(|b| *b as usize == i));
+    }
+}
+
+// Multicast over the loopback interface requires its MULTICAST flag to be set,
+// e.g. with `ip link set lo multicast on`
+#[cfg(target_os = "linux")]
+fn loopback_has_multicast() -> bool {
+    const IFF_MULTICAST: u32 = 0x1000;
+
+    std::fs::read_to_string("/sys/class/net/lo/flags")
+        .ok()
+        .and_then(|flags| u32::from_str_radix(flags.trim().trim_start_matches("0x"), 16).ok())
+        .map_or(false, |flags| flags & IFF_MULTICAST != 0)
+}
+
+#[cfg(target_os = "linux")]
+fn multicast_loopback(port: i32, source: &str) -> Option<gst::Buffer> {
+    let mut src_h = gst_check::Harness::new("ts-udpsrc");
+    {
+        let udpsrc = src_h.element().unwrap();
+        udpsrc.set_property("address", "239.255.42.1");
+        udpsrc.set_property("port", port);
+        udpsrc.set_property("multicast-iface", "lo");
+        udpsrc.set_property("multicast-source", source);
+        udpsrc.set_property("context", "test-multicast");
+    }
+    src_h.play();
+
+    let mut sink_h = gst_check::Harness::new("ts-udpsink");
+    sink_h.set_src_caps_str("foo/bar");
+    {
+        let udpsink = sink_h.element().unwrap();
+        udpsink.set_property("clients", format!("239.255.42.1:{}", port));
+        udpsink.set_property("multicast-iface", "lo");
+        udpsink.set_property("auto-multicast", false);
+        udpsink.set_property("sync", false);
+        udpsink.set_property("context", "test-multicast");
+    }
+
+    for _ in 0..5 {
+        let buffer = gst::Buffer::from_slice([1, 2, 3, 4]);
+        assert_eq!(sink_h.push(buffer), Ok(gst::FlowSuccess::Ok));
+        thread::sleep(std::time::Duration::from_millis(10));
+    }
+
+    src_h.try_pull()
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_multicast_source() {
+    init();
+
+    if !loopback_has_multicast() {
+        gst::info!(
+            gst::CAT_DEFAULT,
+            "Skipping test, loopback interface doesn't support multicast"
+        );
+        return;
+    }
+
+    // Packets from the selected source are received
+    let buffer = multicast_loopback(5020, "127.0.0.1").expect("no buffer received");
+    assert_eq!(buffer.map_readable().unwrap().as_slice(), &[1, 2, 3, 4]);
+
+    // Packets from other sources are filtered out
+    assert!(multicast_loopback(5021, "127.0.0.2").is_none());
+}
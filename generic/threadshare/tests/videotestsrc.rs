@@ -0,0 +1,73 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Library General Public
+// License as published by the Free Software Foundation; either
+// version 2 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Library General Public License for more details.
+//
+// You should have received a copy of the GNU Library General Public
+// License along with this library; if not, write to the
+// Free Software Foundation, Inc., 51 Franklin Street, Suite 500,
+// Boston, MA 02110-1335, USA.
+//
+// SPDX-License-Identifier: LGPL-2.1-or-later
+
+use gst::prelude::*;
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstthreadshare::plugin_register_static().expect("gstthreadshare videotestsrc test");
+    });
+}
+
+#[test]
+fn bars() {
+    init();
+
+    let mut h = gst_check::Harness::new("ts-videotestsrc");
+    {
+        let videotestsrc = h.element().unwrap();
+        videotestsrc.set_property("context", "videotestsrc-bars");
+        videotestsrc.set_property("num-buffers", 2i32);
+        videotestsrc.set_property_from_str("pattern", "bars");
+    }
+    h.set_sink_caps_str("video/x-raw,width=70,height=10,framerate=25/1");
+    h.use_systemclock();
+
+    h.play();
+
+    let mut last_pts = None;
+    for _ in 0..2 {
+        let buffer = h.pull().unwrap();
+        assert_eq!(buffer.size(), 70 * 10 * 4);
+        assert_eq!(buffer.duration(), Some(gst::ClockTime::from_mseconds(40)));
+        if let Some(last_pts) = last_pts {
+            assert_eq!(
+                buffer.pts().unwrap() - last_pts,
+                gst::ClockTime::from_mseconds(40)
+            );
+        }
+        last_pts = buffer.pts();
+
+        let map = buffer.map_readable().unwrap();
+        // First and last bars of the first line
+        assert_eq!(&map[0..4], &[191, 191, 191, 255]);
+        assert_eq!(&map[69 * 4..70 * 4], &[0, 0, 191, 255]);
+    }
+
+    loop {
+        let event = h.pull_event().unwrap();
+        if let gst::EventView::Eos(..) = event.view() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,424 @@
+// Copyright (C) 2016-2018 Sebastian Dröge <sebastian@centricular.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// SPDX-License-Identifier: MIT/Apache-2.0
+
+//! Client setup and helpers shared by the HTTP source and sink.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::future;
+use futures::prelude::*;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Client;
+use tokio::runtime;
+
+use once_cell::sync::Lazy;
+
+use gst::glib;
+use gst::prelude::*;
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "reqwestclient",
+        gst::DebugColorFlags::empty(),
+        Some("Rust HTTP client"),
+    )
+});
+
+pub static RUNTIME: Lazy<runtime::Runtime> = Lazy::new(|| {
+    runtime::Builder::new_multi_thread()
+        .enable_all()
+        .worker_threads(1)
+        .build()
+        .unwrap()
+});
+
+pub const REQWEST_CLIENT_CONTEXT: &str = "gst.reqwest.client";
+
+#[derive(Clone, Debug, glib::Boxed)]
+#[boxed_type(name = "ReqwestClientContext")]
+pub struct ClientContext(Arc<ClientContextInner>);
+
+#[derive(Debug)]
+struct ClientContextInner {
+    client: Client,
+}
+
+impl ClientContext {
+    pub fn client(&self) -> &Client {
+        &self.0.client
+    }
+}
+
+pub fn proxy_from_str(s: Option<String>) -> Result<Option<String>, glib::Error> {
+    match s {
+        None => Ok(None),
+        Some(s) if s.is_empty() => Ok(None),
+        Some(not_empty_str) => {
+            // If no protocol specified, prepend http for compatibility
+            // https://gstreamer.freedesktop.org/documentation/soup/souphttpsrc.html
+            let url_string = if !not_empty_str.contains("://") {
+                format!("http://{}", not_empty_str)
+            } else {
+                not_empty_str
+            };
+            match reqwest::Url::parse(&url_string) {
+                Ok(url) => {
+                    // this may urlencode and add trailing /
+                    Ok(Some(url.to_string()))
+                }
+                Err(err) => Err(glib::Error::new(
+                    gst::URIError::BadUri,
+                    format!("Failed to parse URI '{}': {:?}", url_string, err).as_str(),
+                )),
+            }
+        }
+    }
+}
+
+/// Settings that are specific to a client and prevent sharing it with other elements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientSettings {
+    // Notes about souphttpsrc compatibility:
+    // Internal representation of no proxy is None,
+    // but externally Some("").
+    // Default is set from env var 'http_proxy'.
+    // Prepends http:// if not protocol specified.
+    pub proxy: Option<String>,
+    // Nullable fields that behave normally:
+    pub proxy_id: Option<String>,
+    pub proxy_pw: Option<String>,
+    pub ssl_strict: bool,
+    pub ssl_ca_file: Option<String>,
+    pub tls_certificate_file: Option<String>,
+    pub tls_key_file: Option<String>,
+}
+
+impl Default for ClientSettings {
+    fn default() -> Self {
+        ClientSettings {
+            proxy: match proxy_from_str(std::env::var("http_proxy").ok()) {
+                Ok(a) => a,
+                Err(_) => None,
+            },
+            proxy_id: None,
+            proxy_pw: None,
+            ssl_strict: true,
+            ssl_ca_file: None,
+            tls_certificate_file: None,
+            tls_key_file: None,
+        }
+    }
+}
+
+impl ClientSettings {
+    fn is_shareable(&self) -> bool {
+        self.proxy.is_none()
+            && self.ssl_strict
+            && self.ssl_ca_file.is_none()
+            && self.tls_certificate_file.is_none()
+    }
+}
+
+/// Splits a PEM bundle into the single certificates.
+fn pem_certificates(bundle: &[u8]) -> Vec<&[u8]> {
+    const END: &[u8] = b"-----END CERTIFICATE-----";
+
+    let mut certs = Vec::new();
+    let mut rest = bundle;
+    while let Some(pos) = rest.windows(END.len()).position(|w| w == END) {
+        certs.push(&rest[..pos + END.len()]);
+        rest = &rest[pos + END.len()..];
+    }
+
+    certs
+}
+
+/// The client of an element, either configured by the element itself or shared with other
+/// elements through a `gst.reqwest.client` context.
+#[derive(Debug, Default)]
+pub struct SharedClient {
+    client: Mutex<Option<ClientContext>>,
+    external_client: Mutex<Option<ClientContext>>,
+}
+
+impl SharedClient {
+    /// Drops the configured client so that a new one is created on the next call to `ensure()`.
+    pub fn reset(&self) {
+        *self.client.lock().unwrap() = None;
+    }
+
+    /// Remembers the client of a `gst.reqwest.client` context passed to the element.
+    pub fn set_context(&self, context: &gst::Context) {
+        if context.context_type() == REQWEST_CLIENT_CONTEXT {
+            let mut external_client = self.external_client.lock().unwrap();
+            let s = context.structure();
+            *external_client = s
+                .get::<&ClientContext>("client")
+                .map(|c| Some(c.clone()))
+                .unwrap_or(None);
+        }
+    }
+
+    pub fn ensure(
+        &self,
+        element: &gst::Element,
+        pad: &gst::Pad,
+        settings: &ClientSettings,
+        error: gst::ResourceError,
+    ) -> Result<ClientContext, gst::ErrorMessage> {
+        let mut client_guard = self.client.lock().unwrap();
+        if let Some(ref client) = *client_guard {
+            gst::debug!(CAT, obj: element, "Using already configured client");
+            return Ok(client.clone());
+        }
+
+        // Attempt to acquire an existing client context from another element instance
+        // unless using proxy or TLS settings, because those are client specific.
+        if settings.is_shareable() {
+            let mut q = gst::query::Context::new(REQWEST_CLIENT_CONTEXT);
+            if pad.peer_query(&mut q) {
+                if let Some(context) = q.context_owned() {
+                    element.set_context(&context);
+                }
+            } else {
+                let _ = element.post_message(
+                    gst::message::NeedContext::builder(REQWEST_CLIENT_CONTEXT)
+                        .src(element)
+                        .build(),
+                );
+            }
+
+            // Hopefully now, self.set_context will have been synchronously called
+            if let Some(client) = self.external_client.lock().unwrap().clone() {
+                gst::debug!(CAT, obj: element, "Using shared client");
+                *client_guard = Some(client.clone());
+
+                return Ok(client);
+            }
+        }
+
+        let mut builder = Client::builder().cookie_store(true).gzip(true);
+
+        if let Some(proxy) = &settings.proxy {
+            // Proxy is url-checked on property set but perhaps this might still fail.
+            let mut p = reqwest::Proxy::all(proxy)
+                .map_err(|err| gst::error_msg!(error, ["Bad proxy URI: {}", err]))?;
+            if let Some(proxy_id) = &settings.proxy_id {
+                let proxy_pw = settings.proxy_pw.as_deref().unwrap_or("");
+                p = p.basic_auth(proxy_id, proxy_pw);
+            }
+            builder = builder.proxy(p);
+        }
+
+        if let Some(ca_file) = &settings.ssl_ca_file {
+            let bundle = std::fs::read(ca_file).map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::Settings,
+                    ["Failed to read CA file '{}': {}", ca_file, err]
+                )
+            })?;
+
+            for cert in pem_certificates(&bundle) {
+                let cert = reqwest::Certificate::from_pem(cert).map_err(|err| {
+                    gst::error_msg!(
+                        gst::ResourceError::Settings,
+                        ["Invalid certificate in CA file '{}': {}", ca_file, err]
+                    )
+                })?;
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        if let Some(cert_file) = &settings.tls_certificate_file {
+            let cert = std::fs::read(cert_file).map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::Settings,
+                    ["Failed to read certificate file '{}': {}", cert_file, err]
+                )
+            })?;
+
+            // The key can also be part of the certificate file
+            let key = match &settings.tls_key_file {
+                Some(key_file) => std::fs::read(key_file).map_err(|err| {
+                    gst::error_msg!(
+                        gst::ResourceError::Settings,
+                        ["Failed to read key file '{}': {}", key_file, err]
+                    )
+                })?,
+                None => cert.clone(),
+            };
+
+            let identity = reqwest::Identity::from_pkcs8_pem(&cert, &key).map_err(|err| {
+                gst::error_msg!(
+                    gst::ResourceError::Settings,
+                    ["Invalid client certificate or key: {}", err]
+                )
+            })?;
+            builder = builder.identity(identity);
+        }
+
+        if !settings.ssl_strict {
+            gst::warning!(CAT, obj: element, "Not verifying TLS certificates");
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        gst::debug!(CAT, obj: element, "Creating new client");
+        let client = ClientContext(Arc::new(ClientContextInner {
+            client: builder
+                .build()
+                .map_err(|err| gst::error_msg!(error, ["Failed to create Client: {}", err]))?,
+        }));
+
+        // Share created client with other elements, unless using proxy or TLS settings. Shared client
+        // never uses those. The alternative would be different contexts for different settings, or one
+        // context with a map from settings to client, but then, how and when to discard those, retaining
+        // reuse benefits?
+        if settings.is_shareable() {
+            gst::debug!(CAT, obj: element, "Sharing new client with other elements");
+            let mut context = gst::Context::new(REQWEST_CLIENT_CONTEXT, true);
+            {
+                let context = context.get_mut().unwrap();
+                let s = context.structure_mut();
+                s.set("client", &client);
+            }
+            element.set_context(&context);
+            let _ = element.post_message(
+                gst::message::HaveContext::builder(context)
+                    .src(element)
+                    .build(),
+            );
+        }
+
+        *client_guard = Some(client.clone());
+
+        Ok(client)
+    }
+}
+
+/// Appends all fields of the `extra-headers` structure to `headers`.
+///
+/// Array and list values are added as multiple headers with the same name.
+pub fn append_extra_headers(
+    element: &gst::Element,
+    headers: &mut HeaderMap,
+    extra_headers: &gst::Structure,
+) {
+    for (field, value) in extra_headers.iter() {
+        let field = match HeaderName::try_from(field) {
+            Ok(field) => field,
+            Err(err) => {
+                gst::warning!(
+                    CAT,
+                    obj: element,
+                    "Failed to transform extra-header field name '{}' to header name: {}",
+                    field,
+                    err,
+                );
+
+                continue;
+            }
+        };
+
+        let mut append_header = |field: &HeaderName, value: &glib::Value| {
+            let value = match value.transform::<String>() {
+                Ok(value) => value,
+                Err(_) => {
+                    gst::warning!(
+                        CAT,
+                        obj: element,
+                        "Failed to transform extra-header '{}' value to string",
+                        field
+                    );
+                    return;
+                }
+            };
+
+            let value = value.get::<Option<&str>>().unwrap().unwrap_or("");
+
+            let value = match value.parse::<HeaderValue>() {
+                Ok(value) => value,
+                Err(_) => {
+                    gst::warning!(
+                        CAT,
+                        obj: element,
+                        "Failed to transform extra-header '{}' value to header value",
+                        field
+                    );
+                    return;
+                }
+            };
+
+            headers.append(field.clone(), value);
+        };
+
+        if let Ok(values) = value.get::<gst::ArrayRef>() {
+            for value in values.as_slice() {
+                append_header(&field, value);
+            }
+        } else if let Ok(values) = value.get::<gst::ListRef>() {
+            for value in values.as_slice() {
+                append_header(&field, value);
+            }
+        } else {
+            append_header(&field, value);
+        }
+    }
+}
+
+/// Blocks on `future` on the shared runtime, optionally with a timeout.
+///
+/// Returns `Err(None)` if the future was aborted through the handle stored in `canceller`.
+pub fn wait<F, T>(
+    canceller: &Mutex<Option<future::AbortHandle>>,
+    timeout: Option<Duration>,
+    future: F,
+) -> Result<T, Option<gst::ErrorMessage>>
+where
+    F: Send + Future<Output = Result<T, gst::ErrorMessage>>,
+    T: Send + 'static,
+{
+    let mut canceller_guard = canceller.lock().unwrap();
+    let (abort_handle, abort_registration) = future::AbortHandle::new_pair();
+    canceller_guard.replace(abort_handle);
+    drop(canceller_guard);
+
+    // Wrap in a timeout
+    let future = async {
+        match timeout {
+            None => future.await,
+            Some(timeout) => match tokio::time::timeout(timeout, future).await {
+                Ok(res) => res,
+                Err(_) => Err(gst::error_msg!(
+                    gst::ResourceError::Read,
+                    ["Request timeout"]
+                )),
+            },
+        }
+    };
+
+    // And make abortable
+    let future = async {
+        match future::Abortable::new(future, abort_registration).await {
+            Ok(res) => res.map_err(Some),
+            Err(_) => Err(None),
+        }
+    };
+
+    let res = {
+        let _enter = RUNTIME.enter();
+        futures::executor::block_on(future)
+    };
+
+    /* Clear out the canceller */
+    let _ = canceller.lock().unwrap().take();
+
+    res
+}
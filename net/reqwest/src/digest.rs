@@ -0,0 +1,227 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// SPDX-License-Identifier: MIT/Apache-2.0
+
+//! HTTP Digest access authentication (RFC 7616).
+//!
+//! Only the `auth` quality of protection is supported, with the `MD5` and
+//! `SHA-256` algorithms and their session variants.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::SystemTime;
+
+use md5::Md5;
+use reqwest::header::{HeaderMap, WWW_AUTHENTICATE};
+use sha2::{Digest, Sha256};
+use url::{Position, Url};
+
+/// Nonce count of the `Authorization`, a new challenge is answered for each request.
+const NONCE_COUNT: &str = "00000001";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Algorithm {
+    Md5,
+    Sha256,
+}
+
+impl Algorithm {
+    fn hash(self, data: &str) -> String {
+        let digest = match self {
+            Algorithm::Md5 => Md5::digest(data.as_bytes()).to_vec(),
+            Algorithm::Sha256 => Sha256::digest(data.as_bytes()).to_vec(),
+        };
+
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// A `Digest` challenge sent by the server along with a `401 Unauthorized` response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: Algorithm,
+    session: bool,
+    qop: bool,
+}
+
+impl Challenge {
+    /// Returns the first supported `Digest` challenge of the response `headers`.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        headers
+            .get_all(WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find_map(Self::parse)
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim_start();
+        let (scheme, params) = value.split_at(value.find(' ')?);
+        if !scheme.eq_ignore_ascii_case("Digest") {
+            return None;
+        }
+
+        let mut realm = None;
+        let mut nonce = None;
+        let mut opaque = None;
+        let mut algorithm = (Algorithm::Md5, false);
+        let mut qop = None;
+
+        for (name, value) in parse_params(params) {
+            match name.to_ascii_lowercase().as_str() {
+                "realm" => realm = Some(value),
+                "nonce" => nonce = Some(value),
+                "opaque" => opaque = Some(value),
+                "algorithm" => {
+                    algorithm = match value.to_ascii_uppercase().as_str() {
+                        "MD5" => (Algorithm::Md5, false),
+                        "MD5-SESS" => (Algorithm::Md5, true),
+                        "SHA-256" => (Algorithm::Sha256, false),
+                        "SHA-256-SESS" => (Algorithm::Sha256, true),
+                        _ => return None,
+                    }
+                }
+                "qop" => {
+                    qop = Some(
+                        value
+                            .split(',')
+                            .any(|qop| qop.trim().eq_ignore_ascii_case("auth")),
+                    )
+                }
+                _ => (),
+            }
+        }
+
+        // Only offering auth-int, which is not supported
+        if qop == Some(false) {
+            return None;
+        }
+
+        Some(Challenge {
+            realm: realm?,
+            nonce: nonce?,
+            opaque,
+            algorithm: algorithm.0,
+            session: algorithm.1,
+            qop: qop.is_some(),
+        })
+    }
+
+    /// Returns the `Authorization` header answering the challenge for a `method` request on `uri`.
+    pub fn authorization(&self, user_id: &str, user_pw: &str, method: &str, uri: &Url) -> String {
+        let cnonce = cnonce();
+        let hash = |data: String| self.algorithm.hash(&data);
+        let digest_uri = &uri[Position::BeforePath..Position::AfterQuery];
+
+        let mut ha1 = hash(format!("{}:{}:{}", user_id, self.realm, user_pw));
+        if self.session {
+            ha1 = hash(format!("{}:{}:{}", ha1, self.nonce, cnonce));
+        }
+        let ha2 = hash(format!("{}:{}", method, digest_uri));
+
+        let mut authorization = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}",
+            quote(user_id),
+            quote(&self.realm),
+            quote(&self.nonce),
+            quote(digest_uri),
+            match (self.algorithm, self.session) {
+                (Algorithm::Md5, false) => "MD5",
+                (Algorithm::Md5, true) => "MD5-sess",
+                (Algorithm::Sha256, false) => "SHA-256",
+                (Algorithm::Sha256, true) => "SHA-256-sess",
+            }
+        );
+
+        let response = if self.qop {
+            authorization.push_str(&format!(
+                ", qop=auth, nc={}, cnonce=\"{}\"",
+                NONCE_COUNT, cnonce
+            ));
+            hash(format!(
+                "{}:{}:{}:{}:auth:{}",
+                ha1, self.nonce, NONCE_COUNT, cnonce, ha2
+            ))
+        } else {
+            hash(format!("{}:{}:{}", ha1, self.nonce, ha2))
+        };
+        authorization.push_str(&format!(", response=\"{}\"", response));
+
+        if let Some(ref opaque) = self.opaque {
+            authorization.push_str(&format!(", opaque=\"{}\"", quote(opaque)));
+        }
+
+        authorization
+    }
+}
+
+/// Splits the `name=value` parameters of a challenge, unquoting the values.
+fn parse_params(mut params: &str) -> Vec<(&str, String)> {
+    let mut res = Vec::new();
+
+    loop {
+        params = params.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        let eq = match params.find('=') {
+            Some(eq) => eq,
+            None => break,
+        };
+
+        let name = params[..eq].trim();
+        params = params[eq + 1..].trim_start();
+
+        let value = if let Some(quoted) = params.strip_prefix('"') {
+            let mut value = String::new();
+            let mut end = quoted.len();
+            let mut chars = quoted.char_indices();
+            while let Some((idx, c)) = chars.next() {
+                match c {
+                    '\\' => value.extend(chars.next().map(|(_, c)| c)),
+                    '"' => {
+                        end = idx + 1;
+                        break;
+                    }
+                    c => value.push(c),
+                }
+            }
+            params = &quoted[end..];
+
+            value
+        } else {
+            let end = params.find(',').unwrap_or(params.len());
+            let value = params[..end].trim().to_string();
+            params = &params[end..];
+
+            value
+        };
+
+        res.push((name, value));
+    }
+
+    res
+}
+
+fn quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Returns an unpredictable client nonce.
+fn cnonce() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0),
+    );
+
+    format!("{:016x}", hasher.finish())
+}
@@ -11,10 +11,16 @@
 
 use gst::glib;
 
+mod client;
+mod digest;
+mod reqwesthttpsink;
 mod reqwesthttpsrc;
 
 fn plugin_init(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
-    reqwesthttpsrc::register(plugin)
+    reqwesthttpsrc::register(plugin)?;
+    reqwesthttpsink::register(plugin)?;
+
+    Ok(())
 }
 
 gst::plugin_define!(
@@ -0,0 +1,953 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// SPDX-License-Identifier: MIT/Apache-2.0
+use std::io;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::channel::mpsc;
+use futures::future;
+use futures::prelude::*;
+use reqwest::{Body, RequestBuilder, Response, StatusCode};
+use url::Url;
+
+use once_cell::sync::Lazy;
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+use gst_base::subclass::prelude::*;
+
+use super::Method;
+use crate::client::{self, ClientSettings, SharedClient};
+
+const DEFAULT_LOCATION: Option<Url> = None;
+const DEFAULT_USER_AGENT: &str = concat!(
+    "GStreamer reqwesthttpsink ",
+    env!("CARGO_PKG_VERSION"),
+    "-",
+    env!("COMMIT_ID")
+);
+const DEFAULT_TIMEOUT: u32 = 15;
+const DEFAULT_KEEP_ALIVE: bool = true;
+const DEFAULT_METHOD: Method = Method::Post;
+const DEFAULT_CHUNKED: bool = true;
+
+#[derive(Debug, Clone)]
+struct Settings {
+    location: Option<Url>,
+    user_agent: String,
+    user_id: Option<String>,
+    user_pw: Option<String>,
+    timeout: u32,
+    extra_headers: Option<gst::Structure>,
+    cookies: Vec<String>,
+    keep_alive: bool,
+    method: Method,
+    content_type: Option<String>,
+    chunked: bool,
+    client: ClientSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            location: DEFAULT_LOCATION,
+            user_agent: DEFAULT_USER_AGENT.into(),
+            user_id: None,
+            user_pw: None,
+            timeout: DEFAULT_TIMEOUT,
+            extra_headers: None,
+            cookies: Vec::new(),
+            keep_alive: DEFAULT_KEEP_ALIVE,
+            method: DEFAULT_METHOD,
+            content_type: None,
+            chunked: DEFAULT_CHUNKED,
+            client: ClientSettings::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    Stopped,
+    Started {
+        // Sender for the body of a running chunked upload and the response of the request
+        sender: Option<mpsc::Sender<Result<Bytes, io::Error>>>,
+        response: Option<tokio::task::JoinHandle<Result<Response, reqwest::Error>>>,
+        // Whether a chunked upload was aborted, no new upload is started afterwards
+        failed: bool,
+        // Data collected for a single upload at EOS
+        data: Vec<u8>,
+    },
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State::Stopped
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ReqwestHttpSink {
+    client: SharedClient,
+    settings: Mutex<Settings>,
+    state: Mutex<State>,
+    canceller: Mutex<Option<future::AbortHandle>>,
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "reqwesthttpsink",
+        gst::DebugColorFlags::empty(),
+        Some("Rust HTTP sink"),
+    )
+});
+
+impl ReqwestHttpSink {
+    fn set_location(
+        &self,
+        _element: &super::ReqwestHttpSink,
+        uri: Option<&str>,
+    ) -> Result<(), glib::Error> {
+        let state = self.state.lock().unwrap();
+        if let State::Started { .. } = *state {
+            return Err(glib::Error::new(
+                gst::URIError::BadState,
+                "Changing the `location` property on a started `reqwesthttpsink` is not supported",
+            ));
+        }
+
+        let mut settings = self.settings.lock().unwrap();
+
+        let uri = match uri {
+            None => {
+                settings.location = DEFAULT_LOCATION;
+                return Ok(());
+            }
+            Some(uri) => uri,
+        };
+
+        let uri = Url::parse(uri).map_err(|err| {
+            glib::Error::new(
+                gst::URIError::BadUri,
+                format!("Failed to parse URI '{}': {:?}", uri, err).as_str(),
+            )
+        })?;
+
+        if uri.scheme() != "http" && uri.scheme() != "https" {
+            return Err(glib::Error::new(
+                gst::URIError::UnsupportedProtocol,
+                format!("Unsupported URI scheme '{}'", uri.scheme()).as_str(),
+            ));
+        }
+
+        settings.location = Some(uri);
+
+        Ok(())
+    }
+
+    /// Set a proxy-related property and throw away the client configured with the old value.
+    fn set_proxy_prop<F>(
+        &self,
+        property_name: &str,
+        desired_value: Option<String>,
+        prop_memory_location: F,
+    ) -> Result<(), glib::Error>
+    where
+        F: Fn(&mut ClientSettings) -> &mut Option<String>,
+    {
+        let state = self.state.lock().unwrap();
+        if let State::Started { .. } = *state {
+            return Err(glib::Error::new(
+                gst::URIError::BadState,
+                &format!(
+                    "Changing the `{}` property on a started `reqwesthttpsink` is not supported",
+                    property_name
+                ),
+            ));
+        }
+
+        let mut settings = self.settings.lock().unwrap();
+        let target_variable = prop_memory_location(&mut settings.client);
+        if &desired_value == target_variable {
+            return Ok(());
+        }
+
+        self.client.reset();
+        *target_variable = desired_value;
+
+        Ok(())
+    }
+
+    fn build_request(
+        &self,
+        sink: &super::ReqwestHttpSink,
+    ) -> Result<RequestBuilder, gst::ErrorMessage> {
+        use headers::{Connection, HeaderMapExt, UserAgent};
+        use reqwest::header::{self, HeaderMap, HeaderValue};
+
+        let settings = self.settings.lock().unwrap().clone();
+
+        let uri = settings.location.clone().ok_or_else(|| {
+            gst::error_msg!(gst::CoreError::StateChange, ["Can't start without an URI"])
+        })?;
+
+        gst::debug!(CAT, obj: sink, "Creating new request for {}", uri);
+
+        let client = self.client.ensure(
+            sink.upcast_ref(),
+            &sink.static_pad("sink").unwrap(),
+            &settings.client,
+            gst::ResourceError::OpenWrite,
+        )?;
+
+        let req = match settings.method {
+            Method::Post => client.client().post(uri),
+            Method::Put => client.client().put(uri),
+        };
+
+        let mut headers = HeaderMap::new();
+
+        if settings.keep_alive {
+            headers.typed_insert(Connection::keep_alive());
+        } else {
+            headers.typed_insert(Connection::close());
+        }
+
+        headers.typed_insert(settings.user_agent.parse::<UserAgent>().unwrap());
+
+        if let Some(ref content_type) = settings.content_type {
+            match content_type.parse::<HeaderValue>() {
+                Ok(content_type) => {
+                    headers.insert(header::CONTENT_TYPE, content_type);
+                }
+                Err(_) => {
+                    gst::warning!(CAT, obj: sink, "Invalid content type '{}'", content_type);
+                }
+            }
+        }
+
+        if let Some(ref extra_headers) = settings.extra_headers {
+            client::append_extra_headers(sink.upcast_ref(), &mut headers, extra_headers);
+        }
+
+        if !settings.cookies.is_empty() {
+            headers.insert(
+                header::COOKIE,
+                settings.cookies.join("; ").parse::<HeaderValue>().unwrap(),
+            );
+        }
+
+        let req = req.headers(headers);
+
+        let req = if let Some(ref user_id) = settings.user_id {
+            // HTTP auth available
+            req.basic_auth(user_id, settings.user_pw)
+        } else {
+            req
+        };
+
+        Ok(req)
+    }
+
+    /// Start a chunked upload whose body is fed from the returned sender.
+    fn start_chunked_upload(
+        &self,
+        sink: &super::ReqwestHttpSink,
+    ) -> Result<
+        (
+            mpsc::Sender<Result<Bytes, io::Error>>,
+            tokio::task::JoinHandle<Result<Response, reqwest::Error>>,
+        ),
+        gst::ErrorMessage,
+    > {
+        let req = self.build_request(sink)?;
+
+        // A body of unknown length is sent with chunked transfer encoding
+        let (sender, receiver) = mpsc::channel(1);
+        let req = req.body(Body::wrap_stream(receiver));
+
+        gst::debug!(CAT, obj: sink, "Starting chunked upload: {:?}", req);
+
+        let response = client::RUNTIME.spawn(req.send());
+
+        Ok((sender, response))
+    }
+
+    /// Abort a running chunked upload without finishing its body, so that the server doesn't
+    /// mistake the truncated upload for a complete one.
+    fn abort_chunked_upload(
+        sink: &super::ReqwestHttpSink,
+        sender: Option<mpsc::Sender<Result<Bytes, io::Error>>>,
+        response: Option<tokio::task::JoinHandle<Result<Response, reqwest::Error>>>,
+    ) {
+        let response = match response {
+            Some(response) => response,
+            None => return,
+        };
+
+        gst::debug!(CAT, obj: sink, "Aborting chunked upload");
+
+        response.abort();
+
+        // Closing the channel while the request is still running would terminate the body
+        // properly, so keep it open until the request is gone
+        client::RUNTIME.spawn(async move {
+            let _ = response.await;
+            drop(sender);
+        });
+    }
+
+    fn check_response(
+        &self,
+        sink: &super::ReqwestHttpSink,
+        res: Result<Response, reqwest::Error>,
+    ) -> Result<(), gst::ErrorMessage> {
+        let res = res.map_err(|err| {
+            gst::error_msg!(gst::ResourceError::Write, ["Upload failed: {:?}", err])
+        })?;
+
+        gst::debug!(CAT, obj: sink, "Received response: {:?}", res);
+
+        match res.status() {
+            status if status.is_success() => Ok(()),
+            StatusCode::NOT_FOUND => Err(gst::error_msg!(
+                gst::ResourceError::NotFound,
+                ["Resource '{}' not found", res.url()]
+            )),
+            StatusCode::UNAUTHORIZED
+            | StatusCode::PAYMENT_REQUIRED
+            | StatusCode::FORBIDDEN
+            | StatusCode::PROXY_AUTHENTICATION_REQUIRED => Err(gst::error_msg!(
+                gst::ResourceError::NotAuthorized,
+                [
+                    "Not Authorized for resource '{}': {}",
+                    res.url(),
+                    res.status()
+                ]
+            )),
+            status => Err(gst::error_msg!(
+                gst::ResourceError::Write,
+                ["Upload to '{}' failed: {}", res.url(), status]
+            )),
+        }
+    }
+
+    /// Waits for the response of a chunked upload after the body was finished or the request
+    /// stopped accepting data.
+    fn finish_chunked_upload(
+        &self,
+        sink: &super::ReqwestHttpSink,
+        response: tokio::task::JoinHandle<Result<Response, reqwest::Error>>,
+    ) -> Result<(), Option<gst::ErrorMessage>> {
+        let res = self.wait(async {
+            response.await.map_err(|err| {
+                gst::error_msg!(gst::ResourceError::Write, ["Upload failed: {}", err])
+            })
+        })?;
+
+        self.check_response(sink, res).map_err(Some)
+    }
+
+    fn finalize_upload(
+        &self,
+        sink: &super::ReqwestHttpSink,
+    ) -> Result<(), Option<gst::ErrorMessage>> {
+        let chunked = self.settings.lock().unwrap().chunked;
+
+        let (sender, response, failed, data) = match *self.state.lock().unwrap() {
+            State::Started {
+                ref mut sender,
+                ref mut response,
+                failed,
+                ref mut data,
+            } => (sender.take(), response.take(), failed, std::mem::take(data)),
+            State::Stopped => {
+                return Err(Some(gst::error_msg!(
+                    gst::LibraryError::Failed,
+                    ["Not started yet"]
+                )));
+            }
+        };
+
+        if chunked {
+            let response = match response {
+                Some(response) => response,
+                // The upload failed already and the error was reported when rendering
+                None if failed => {
+                    gst::debug!(CAT, obj: sink, "Upload failed before, not finalizing");
+                    return Ok(());
+                }
+                // Nothing was rendered, do an empty upload
+                None => self.start_chunked_upload(sink).map_err(Some)?.1,
+            };
+
+            // Closing the channel finishes the body
+            drop(sender);
+
+            return self.finish_chunked_upload(sink, response);
+        }
+
+        let req = self.build_request(sink).map_err(Some)?;
+        gst::debug!(CAT, obj: sink, "Uploading {} bytes", data.len());
+        let req = req.body(data);
+
+        let res = self.wait(async { Ok(req.send().await) })?;
+
+        self.check_response(sink, res).map_err(Some)
+    }
+
+    fn render_chunked(
+        &self,
+        sink: &super::ReqwestHttpSink,
+        buffer: &gst::Buffer,
+    ) -> Result<(), Option<gst::ErrorMessage>> {
+        let (sender, response) = match *self.state.lock().unwrap() {
+            State::Started { failed: true, .. } => {
+                return Err(Some(gst::error_msg!(
+                    gst::ResourceError::Write,
+                    ["Upload failed before"]
+                )));
+            }
+            State::Started {
+                ref mut sender,
+                ref mut response,
+                ..
+            } => (sender.take(), response.take()),
+            State::Stopped => {
+                return Err(Some(gst::error_msg!(
+                    gst::LibraryError::Failed,
+                    ["Not started yet"]
+                )));
+            }
+        };
+
+        let (mut sender, response) = match (sender, response) {
+            (Some(sender), Some(response)) => (sender, response),
+            _ => self.start_chunked_upload(sink).map_err(Some)?,
+        };
+
+        let map = buffer.map_readable().map_err(|_| {
+            Some(gst::error_msg!(
+                gst::CoreError::Failed,
+                ["Failed to map buffer"]
+            ))
+        })?;
+        let data = Bytes::copy_from_slice(&map);
+
+        let res = self.wait(async { Ok(sender.send(Ok(data)).await) });
+
+        let mut state = self.state.lock().unwrap();
+        let (state_sender, state_response, failed) = match *state {
+            State::Started {
+                ref mut sender,
+                ref mut response,
+                ref mut failed,
+                ..
+            } => (sender, response, failed),
+            State::Stopped => {
+                // Stopped while waiting
+                drop(state);
+                Self::abort_chunked_upload(sink, Some(sender), Some(response));
+                return Err(None);
+            }
+        };
+
+        match res {
+            Ok(Ok(())) => {
+                *state_sender = Some(sender);
+                *state_response = Some(response);
+
+                Ok(())
+            }
+            Err(None) => {
+                // Flushing, keep the upload running for the next buffers
+                *state_sender = Some(sender);
+                *state_response = Some(response);
+
+                Err(None)
+            }
+            Err(Some(err)) => {
+                *failed = true;
+                drop(state);
+
+                Self::abort_chunked_upload(sink, Some(sender), Some(response));
+
+                Err(Some(err))
+            }
+            Ok(Err(_)) => {
+                *failed = true;
+                drop(state);
+
+                // The request stopped reading the body, get the actual error from the response
+                gst::debug!(CAT, obj: sink, "Request stopped accepting data");
+                self.finish_chunked_upload(sink, response)?;
+
+                Err(Some(gst::error_msg!(
+                    gst::ResourceError::Write,
+                    ["Server closed the connection before the end of the stream"]
+                )))
+            }
+        }
+    }
+
+    fn wait<F, T>(&self, future: F) -> Result<T, Option<gst::ErrorMessage>>
+    where
+        F: Send + Future<Output = Result<T, gst::ErrorMessage>>,
+        T: Send + 'static,
+    {
+        let timeout = self.settings.lock().unwrap().timeout;
+        let timeout = if timeout == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(timeout.into()))
+        };
+
+        client::wait(&self.canceller, timeout, future)
+    }
+}
+
+impl ObjectImpl for ReqwestHttpSink {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecString::new(
+                    "location",
+                    "Location",
+                    "URL to upload to",
+                    None,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecString::new(
+                    "user-agent",
+                    "User-Agent",
+                    "Value of the User-Agent HTTP request header field",
+                    DEFAULT_USER_AGENT.into(),
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecString::new(
+                    "user-id",
+                    "User-id",
+                    "HTTP location URI user id for authentication",
+                    None,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecString::new(
+                    "user-pw",
+                    "User-pw",
+                    "HTTP location URI user password for authentication",
+                    None,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecUInt::new(
+                    "timeout",
+                    "Timeout",
+                    "Value in seconds to timeout a blocking I/O (0 = No timeout).",
+                    0,
+                    3600,
+                    DEFAULT_TIMEOUT,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecBoxed::new(
+                    "extra-headers",
+                    "Extra Headers",
+                    "Extra headers to append to the HTTP request",
+                    gst::Structure::static_type(),
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecBoxed::new(
+                    "cookies",
+                    "Cookies",
+                    "HTTP request cookies",
+                    Vec::<String>::static_type(),
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "keep-alive",
+                    "Keep Alive",
+                    "Use HTTP persistent connections",
+                    DEFAULT_KEEP_ALIVE,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecEnum::new(
+                    "method",
+                    "Method",
+                    "HTTP method used for the upload",
+                    Method::static_type(),
+                    DEFAULT_METHOD as i32,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecString::new(
+                    "content-type",
+                    "Content-Type",
+                    "Value of the Content-Type HTTP request header field",
+                    None,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "chunked",
+                    "Chunked",
+                    "Stream the data with chunked transfer encoding instead of uploading everything at EOS",
+                    DEFAULT_CHUNKED,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecString::new(
+                    "proxy",
+                    "Proxy",
+                    "HTTP proxy server URI",
+                    Some(""),
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecString::new(
+                    "proxy-id",
+                    "Proxy-id",
+                    "HTTP proxy URI user id for authentication",
+                    Some(""),
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecString::new(
+                    "proxy-pw",
+                    "Proxy-pw",
+                    "HTTP proxy URI user password for authentication",
+                    Some(""),
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        let res = match pspec.name() {
+            "location" => {
+                let location = value.get::<Option<&str>>().expect("type checked upstream");
+                self.set_location(obj, location)
+            }
+            "user-agent" => {
+                let mut settings = self.settings.lock().unwrap();
+                let user_agent = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream")
+                    .unwrap_or_else(|| DEFAULT_USER_AGENT.into());
+                settings.user_agent = user_agent;
+                Ok(())
+            }
+            "user-id" => {
+                let mut settings = self.settings.lock().unwrap();
+                let user_id = value.get().expect("type checked upstream");
+                settings.user_id = user_id;
+                Ok(())
+            }
+            "user-pw" => {
+                let mut settings = self.settings.lock().unwrap();
+                let user_pw = value.get().expect("type checked upstream");
+                settings.user_pw = user_pw;
+                Ok(())
+            }
+            "timeout" => {
+                let mut settings = self.settings.lock().unwrap();
+                let timeout = value.get().expect("type checked upstream");
+                settings.timeout = timeout;
+                Ok(())
+            }
+            "extra-headers" => {
+                let mut settings = self.settings.lock().unwrap();
+                let extra_headers = value.get().expect("type checked upstream");
+                settings.extra_headers = extra_headers;
+                Ok(())
+            }
+            "cookies" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.cookies = value.get::<Vec<String>>().expect("type checked upstream");
+                Ok(())
+            }
+            "keep-alive" => {
+                let mut settings = self.settings.lock().unwrap();
+                let keep_alive = value.get().expect("type checked upstream");
+                settings.keep_alive = keep_alive;
+                Ok(())
+            }
+            "method" => {
+                let mut settings = self.settings.lock().unwrap();
+                let method = value.get().expect("type checked upstream");
+                settings.method = method;
+                Ok(())
+            }
+            "content-type" => {
+                let mut settings = self.settings.lock().unwrap();
+                let content_type = value.get().expect("type checked upstream");
+                settings.content_type = content_type;
+                Ok(())
+            }
+            "chunked" => {
+                let mut settings = self.settings.lock().unwrap();
+                let chunked = value.get().expect("type checked upstream");
+                settings.chunked = chunked;
+                Ok(())
+            }
+            "proxy" => {
+                let proxy = client::proxy_from_str(
+                    value
+                        .get::<Option<String>>()
+                        .expect("type checked upstream"),
+                );
+                match proxy {
+                    Ok(proxy) => self
+                        .set_proxy_prop(pspec.name(), proxy, move |settings| &mut settings.proxy),
+                    Err(e) => Err(e),
+                }
+            }
+            "proxy-id" => {
+                let proxy_id = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream");
+                self.set_proxy_prop(pspec.name(), proxy_id, move |settings| {
+                    &mut settings.proxy_id
+                })
+            }
+            "proxy-pw" => {
+                let proxy_pw = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream");
+                self.set_proxy_prop(pspec.name(), proxy_pw, move |settings| {
+                    &mut settings.proxy_pw
+                })
+            }
+            _ => unimplemented!(),
+        };
+
+        if let Err(err) = res {
+            gst::error!(
+                CAT,
+                obj: obj,
+                "Failed to set property `{}`: {:?}",
+                pspec.name(),
+                err
+            );
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        let settings = self.settings.lock().unwrap();
+
+        match pspec.name() {
+            "location" => settings.location.as_ref().map(Url::to_string).to_value(),
+            "user-agent" => settings.user_agent.to_value(),
+            "user-id" => settings.user_id.to_value(),
+            "user-pw" => settings.user_pw.to_value(),
+            "timeout" => settings.timeout.to_value(),
+            "extra-headers" => settings.extra_headers.to_value(),
+            "cookies" => settings.cookies.to_value(),
+            "keep-alive" => settings.keep_alive.to_value(),
+            "method" => settings.method.to_value(),
+            "content-type" => settings.content_type.to_value(),
+            "chunked" => settings.chunked.to_value(),
+            // return None values as Some("") for compatibility with souphttpsrc
+            "proxy" => settings.client.proxy.as_deref().unwrap_or("").to_value(),
+            "proxy-id" => settings.client.proxy_id.to_value(),
+            "proxy-pw" => settings.client.proxy_pw.to_value(),
+            _ => unimplemented!(),
+        }
+    }
+}
+
+impl GstObjectImpl for ReqwestHttpSink {}
+
+impl ElementImpl for ReqwestHttpSink {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "HTTP Sink",
+                "Sink/Network/HTTP",
+                "Upload stream to an HTTP/HTTPS location",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::new_any();
+            let sink_pad_template = gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![sink_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn set_context(&self, element: &Self::Type, context: &gst::Context) {
+        self.client.set_context(context);
+
+        self.parent_set_context(element, context);
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        if let gst::StateChange::ReadyToNull = transition {
+            self.client.reset();
+        }
+
+        self.parent_change_state(element, transition)
+    }
+}
+
+impl BaseSinkImpl for ReqwestHttpSink {
+    fn start(&self, sink: &Self::Type) -> Result<(), gst::ErrorMessage> {
+        let mut state = self.state.lock().unwrap();
+
+        if self.settings.lock().unwrap().location.is_none() {
+            return Err(gst::error_msg!(
+                gst::CoreError::StateChange,
+                ["Can't start without an URI"]
+            ));
+        }
+
+        gst::debug!(CAT, obj: sink, "Started");
+
+        *state = State::Started {
+            sender: None,
+            response: None,
+            failed: false,
+            data: Vec::new(),
+        };
+
+        Ok(())
+    }
+
+    fn stop(&self, sink: &Self::Type) -> Result<(), gst::ErrorMessage> {
+        gst::debug!(CAT, obj: sink, "Stopping");
+
+        // Dropping the response handle doesn't abort a running upload
+        if let State::Started {
+            sender, response, ..
+        } = std::mem::take(&mut *self.state.lock().unwrap())
+        {
+            Self::abort_chunked_upload(sink, sender, response);
+        }
+
+        Ok(())
+    }
+
+    fn render(
+        &self,
+        sink: &Self::Type,
+        buffer: &gst::Buffer,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        gst::trace!(CAT, obj: sink, "Rendering {:?}", buffer);
+
+        let chunked = self.settings.lock().unwrap().chunked;
+
+        let res = if chunked {
+            self.render_chunked(sink, buffer)
+        } else {
+            let map = buffer.map_readable().map_err(|_| {
+                gst::element_error!(sink, gst::CoreError::Failed, ["Failed to map buffer"]);
+                gst::FlowError::Error
+            })?;
+
+            match *self.state.lock().unwrap() {
+                State::Started { ref mut data, .. } => {
+                    data.extend_from_slice(&map);
+                    Ok(())
+                }
+                State::Stopped => Err(Some(gst::error_msg!(
+                    gst::LibraryError::Failed,
+                    ["Not started yet"]
+                ))),
+            }
+        };
+
+        match res {
+            Ok(()) => Ok(gst::FlowSuccess::Ok),
+            Err(Some(err)) => {
+                gst::error!(CAT, obj: sink, "Upload failed: {}", err);
+                sink.post_error_message(err);
+                Err(gst::FlowError::Error)
+            }
+            Err(None) => {
+                gst::debug!(CAT, obj: sink, "Flushing");
+                Err(gst::FlowError::Flushing)
+            }
+        }
+    }
+
+    fn unlock(&self, _sink: &Self::Type) -> Result<(), gst::ErrorMessage> {
+        let canceller = self.canceller.lock().unwrap();
+        if let Some(ref canceller) = *canceller {
+            canceller.abort();
+        }
+        Ok(())
+    }
+
+    fn event(&self, sink: &Self::Type, event: gst::Event) -> bool {
+        if let gst::EventView::Eos(_) = event.view() {
+            match self.finalize_upload(sink) {
+                Ok(()) => (),
+                Err(Some(err)) => {
+                    gst::error!(CAT, obj: sink, "Failed to finalize the upload: {}", err);
+                    sink.post_error_message(err);
+                    return false;
+                }
+                Err(None) => {
+                    gst::debug!(CAT, obj: sink, "Flushing");
+                    return false;
+                }
+            }
+        }
+
+        BaseSinkImplExt::parent_event(self, sink, event)
+    }
+}
+
+impl URIHandlerImpl for ReqwestHttpSink {
+    const URI_TYPE: gst::URIType = gst::URIType::Sink;
+
+    fn protocols() -> &'static [&'static str] {
+        &["http", "https"]
+    }
+
+    fn uri(&self, _element: &Self::Type) -> Option<String> {
+        let settings = self.settings.lock().unwrap();
+
+        settings.location.as_ref().map(Url::to_string)
+    }
+
+    fn set_uri(&self, element: &Self::Type, uri: &str) -> Result<(), glib::Error> {
+        self.set_location(element, Some(uri))
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for ReqwestHttpSink {
+    const NAME: &'static str = "ReqwestHttpSink";
+    type Type = super::ReqwestHttpSink;
+    type ParentType = gst_base::BaseSink;
+    type Interfaces = (gst::URIHandler,);
+}
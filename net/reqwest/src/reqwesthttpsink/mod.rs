@@ -0,0 +1,37 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// SPDX-License-Identifier: MIT/Apache-2.0
+
+use gst::glib;
+use gst::prelude::*;
+
+mod imp;
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, glib::Enum)]
+#[repr(u32)]
+#[enum_type(name = "GstReqwestHttpSinkMethod")]
+pub(crate) enum Method {
+    #[enum_value(name = "POST: Upload with a POST request.", nick = "post")]
+    Post,
+    #[enum_value(name = "PUT: Upload with a PUT request.", nick = "put")]
+    Put,
+}
+
+glib::wrapper! {
+    pub struct ReqwestHttpSink(ObjectSubclass<imp::ReqwestHttpSink>) @extends gst_base::BaseSink, gst::Element, gst::Object, @implements gst::URIHandler;
+}
+
+pub fn register(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
+    gst::Element::register(
+        Some(plugin),
+        "reqwesthttpsink",
+        gst::Rank::None,
+        ReqwestHttpSink::static_type(),
+    )
+}
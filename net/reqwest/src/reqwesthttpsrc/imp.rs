@@ -7,14 +7,15 @@
 // except according to those terms.
 //
 // SPDX-License-Identifier: MIT/Apache-2.0
-use std::sync::{Arc, Mutex};
+use std::cmp;
+use std::sync::Mutex;
 use std::time::Duration;
 use std::u64;
 
+use bytes::Bytes;
 use futures::future;
 use futures::prelude::*;
-use reqwest::{Client, Response, StatusCode};
-use tokio::runtime;
+use reqwest::{Response, StatusCode};
 use url::Url;
 
 use once_cell::sync::Lazy;
@@ -26,6 +27,9 @@ use gst_base::prelude::*;
 use gst_base::subclass::base_src::CreateSuccess;
 use gst_base::subclass::prelude::*;
 
+use crate::client::{self, ClientContext, ClientSettings, SharedClient};
+use crate::digest;
+
 const DEFAULT_LOCATION: Option<Url> = None;
 const DEFAULT_USER_AGENT: &str = concat!(
     "GStreamer reqwesthttpsrc ",
@@ -37,7 +41,10 @@ const DEFAULT_IS_LIVE: bool = false;
 const DEFAULT_TIMEOUT: u32 = 15;
 const DEFAULT_COMPRESS: bool = false;
 const DEFAULT_IRADIO_MODE: bool = true;
+const DEFAULT_ICY_DEMUX: bool = false;
 const DEFAULT_KEEP_ALIVE: bool = true;
+const DEFAULT_RETRIES: i32 = 0;
+const DEFAULT_RETRY_BACKOFF: u32 = 1000;
 
 #[derive(Debug, Clone)]
 struct Settings {
@@ -50,16 +57,12 @@ struct Settings {
     extra_headers: Option<gst::Structure>,
     cookies: Vec<String>,
     iradio_mode: bool,
+    icy_demux: bool,
     keep_alive: bool,
-    // Notes about souphttpsrc compatibility:
-    // Internal representation of no proxy is None,
-    // but externally Some("").
-    // Default is set from env var 'http_proxy'.
-    // Prepends http:// if not protocol specified.
-    proxy: Option<String>,
-    // Nullable fields that behave normally:
-    proxy_id: Option<String>,
-    proxy_pw: Option<String>,
+    retries: i32,
+    retry_backoff: u32,
+    bearer_token: Option<String>,
+    client: ClientSettings,
 }
 
 impl Default for Settings {
@@ -74,54 +77,16 @@ impl Default for Settings {
             extra_headers: None,
             cookies: Vec::new(),
             iradio_mode: DEFAULT_IRADIO_MODE,
+            icy_demux: DEFAULT_ICY_DEMUX,
             keep_alive: DEFAULT_KEEP_ALIVE,
-            proxy: match proxy_from_str(std::env::var("http_proxy").ok()) {
-                Ok(a) => a,
-                Err(_) => None,
-            },
-            proxy_id: None,
-            proxy_pw: None,
+            retries: DEFAULT_RETRIES,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+            bearer_token: None,
+            client: ClientSettings::default(),
         }
     }
 }
 
-fn proxy_from_str(s: Option<String>) -> Result<Option<String>, glib::Error> {
-    match s {
-        None => Ok(None),
-        Some(s) if s.is_empty() => Ok(None),
-        Some(not_empty_str) => {
-            // If no protocol specified, prepend http for compatibility
-            // https://gstreamer.freedesktop.org/documentation/soup/souphttpsrc.html
-            let url_string = if !not_empty_str.contains("://") {
-                format!("http://{}", not_empty_str)
-            } else {
-                not_empty_str
-            };
-            match reqwest::Url::parse(&url_string) {
-                Ok(url) => {
-                    // this may urlencode and add trailing /
-                    Ok(Some(url.to_string()))
-                }
-                Err(err) => Err(glib::Error::new(
-                    gst::URIError::BadUri,
-                    format!("Failed to parse URI '{}': {:?}", url_string, err).as_str(),
-                )),
-            }
-        }
-    }
-}
-
-const REQWEST_CLIENT_CONTEXT: &str = "gst.reqwest.client";
-
-#[derive(Clone, Debug, glib::Boxed)]
-#[boxed_type(name = "ReqwestClientContext")]
-struct ClientContext(Arc<ClientContextInner>);
-
-#[derive(Debug)]
-struct ClientContextInner {
-    client: Client,
-}
-
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 enum State {
@@ -135,9 +100,151 @@ enum State {
         stop: Option<u64>,
         caps: Option<gst::Caps>,
         tags: Option<gst::TagList>,
+        icy: Option<IcyDemux>,
+        // Number of consecutive failed reconnection attempts
+        failed_attempts: u32,
+        // Whether the next buffer has to be marked as discontinuous
+        discont: bool,
     },
 }
 
+/// Strips ICY metadata blocks from the response body and parses them into tags.
+///
+/// Every `metaint` bytes of audio data a single length byte follows, giving the length of the
+/// following metadata block in multiples of 16 bytes.
+#[derive(Debug)]
+struct IcyDemux {
+    metaint: usize,
+    // Audio bytes until the next metadata block
+    remaining: usize,
+    // Expected length and collected data of the current metadata block
+    meta: Option<(usize, Vec<u8>)>,
+    // Data that was received already but not processed yet
+    pending: Option<Bytes>,
+    // Offset in the demuxed audio stream
+    offset: u64,
+}
+
+impl IcyDemux {
+    fn new(metaint: usize) -> Self {
+        IcyDemux {
+            metaint,
+            remaining: metaint,
+            meta: None,
+            pending: None,
+            offset: 0,
+        }
+    }
+
+    /// Returns the audio data at the beginning of `data` together with the tags from all
+    /// metadata blocks preceding it. Anything after the audio data is kept as pending.
+    fn demux(&mut self, mut data: Bytes) -> (Option<Bytes>, Vec<gst::TagList>) {
+        let mut tags = Vec::new();
+
+        while !data.is_empty() {
+            if self.remaining > 0 {
+                let audio = data.split_to(cmp::min(self.remaining, data.len()));
+                self.remaining -= audio.len();
+                self.offset += audio.len() as u64;
+                if !data.is_empty() {
+                    self.pending = Some(data);
+                }
+
+                return (Some(audio), tags);
+            }
+
+            let complete = match self.meta {
+                None => {
+                    let len = data.split_to(1)[0] as usize * 16;
+                    if len == 0 {
+                        self.remaining = self.metaint;
+                    } else {
+                        self.meta = Some((len, Vec::with_capacity(len)));
+                    }
+
+                    false
+                }
+                Some((len, ref mut meta)) => {
+                    let n = cmp::min(len - meta.len(), data.len());
+                    meta.extend_from_slice(&data.split_to(n));
+
+                    meta.len() == len
+                }
+            };
+
+            if complete {
+                let (_, meta) = self.meta.take().unwrap();
+                tags.extend(parse_icy_metadata(&meta));
+                self.remaining = self.metaint;
+            }
+        }
+
+        (None, tags)
+    }
+}
+
+/// Parses a metadata block of the form `StreamTitle='...';StreamUrl='...';`
+fn parse_icy_metadata(data: &[u8]) -> Option<gst::TagList> {
+    let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+    let data = match std::str::from_utf8(&data[..end]) {
+        Ok(data) => data.to_owned(),
+        // Fall back to Latin-1, which is what most servers send
+        Err(_) => data[..end].iter().map(|&b| b as char).collect(),
+    };
+
+    let mut tags = gst::TagList::new();
+    {
+        let tags = tags.get_mut().unwrap();
+
+        for field in data.split("';") {
+            let (key, value) = match field.split_once("='") {
+                Some((key, value)) if !value.is_empty() => (key, value),
+                _ => continue,
+            };
+
+            match key.trim() {
+                "StreamTitle" => {
+                    tags.add::<gst::tags::Title>(&value, gst::TagMergeMode::Replace);
+                }
+                "StreamUrl" => {
+                    tags.add::<gst::tags::Location>(&value, gst::TagMergeMode::Replace);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    if tags.n_tags() > 0 {
+        Some(tags)
+    } else {
+        None
+    }
+}
+
+/// Caps for the audio stream inside an ICY stream with the given content type
+fn icy_content_caps(content_type: &mime::Mime) -> Option<gst::Caps> {
+    match (
+        content_type.type_().as_str(),
+        content_type.subtype().as_str(),
+    ) {
+        ("audio", "mpeg") | ("audio", "mp3") => Some(
+            gst::Caps::builder("audio/mpeg")
+                .field("mpegversion", 1i32)
+                .build(),
+        ),
+        ("audio", "aac") | ("audio", "aacp") => Some(
+            gst::Caps::builder("audio/mpeg")
+                .field("mpegversion", 4i32)
+                .field("stream-format", "adts")
+                .build(),
+        ),
+        ("audio", "ogg") | ("application", "ogg") => {
+            Some(gst::Caps::builder("application/ogg").build())
+        }
+        _ => None,
+    }
+}
+
 impl Default for State {
     fn default() -> Self {
         State::Stopped
@@ -146,8 +253,7 @@ impl Default for State {
 
 #[derive(Debug, Default)]
 pub struct ReqwestHttpSrc {
-    client: Mutex<Option<ClientContext>>,
-    external_client: Mutex<Option<ClientContext>>,
+    client: SharedClient,
     settings: Mutex<Settings>,
     state: Mutex<State>,
     canceller: Mutex<Option<future::AbortHandle>>,
@@ -161,14 +267,6 @@ static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
     )
 });
 
-static RUNTIME: Lazy<runtime::Runtime> = Lazy::new(|| {
-    runtime::Builder::new_multi_thread()
-        .enable_all()
-        .worker_threads(1)
-        .build()
-        .unwrap()
-});
-
 impl ReqwestHttpSrc {
     fn set_location(
         &self,
@@ -210,17 +308,18 @@ impl ReqwestHttpSrc {
         Ok(())
     }
 
-    /// Set a proxy-related property and perform necessary state checks and modifications to client.
-    fn set_proxy_prop<F>(
+    /// Set a client-related property and perform necessary state checks and modifications to client.
+    fn set_client_prop<T, F>(
         &self,
         property_name: &str,
-        desired_value: Option<String>,
+        desired_value: T,
         prop_memory_location: F,
     ) -> Result<(), glib::Error>
     where
-        F: Fn(&mut Settings) -> &mut Option<String>,
+        T: PartialEq,
+        F: Fn(&mut ClientSettings) -> &mut T,
     {
-        // Proxy props can only be changed when not started.
+        // Client props can only be changed when not started.
         let state = self.state.lock().unwrap();
         if let State::Started { .. } = *state {
             return Err(glib::Error::new(
@@ -234,16 +333,16 @@ impl ReqwestHttpSrc {
 
         // Get memory address of specific variable to change.
         let mut settings = self.settings.lock().unwrap();
-        let target_variable = prop_memory_location(&mut settings);
+        let target_variable = prop_memory_location(&mut settings.client);
         if &desired_value == target_variable {
             return Ok(());
         }
 
-        // If the Proxy is changed we need to throw away the old client since it isn't properly
-        // configured with a proxy anymore. Since element is not started, an existing client
+        // If the Proxy or TLS settings are changed we need to throw away the old client since it
+        // isn't properly configured anymore. Since element is not started, an existing client
         // without proxy will be used, or a new one with/without proxy will be built on next call
         // to ensure_client.
-        *self.client.lock().unwrap() = None;
+        self.client.reset();
         *target_variable = desired_value;
 
         Ok(())
@@ -252,84 +351,14 @@ impl ReqwestHttpSrc {
     fn ensure_client(
         &self,
         src: &super::ReqwestHttpSrc,
-        proxy: Option<String>,
-        proxy_id: Option<String>,
-        proxy_pw: Option<String>,
+        settings: &ClientSettings,
     ) -> Result<ClientContext, gst::ErrorMessage> {
-        let mut client_guard = self.client.lock().unwrap();
-        if let Some(ref client) = *client_guard {
-            gst::debug!(CAT, obj: src, "Using already configured client");
-            return Ok(client.clone());
-        }
-
-        // Attempt to acquire an existing client context from another element instance
-        // unless using proxy, because proxy is client specific.
-        if proxy.is_none() {
-            let srcpad = src.static_pad("src").unwrap();
-            let mut q = gst::query::Context::new(REQWEST_CLIENT_CONTEXT);
-            if srcpad.peer_query(&mut q) {
-                if let Some(context) = q.context_owned() {
-                    src.set_context(&context);
-                }
-            } else {
-                let _ = src.post_message(
-                    gst::message::NeedContext::builder(REQWEST_CLIENT_CONTEXT)
-                        .src(src)
-                        .build(),
-                );
-            }
-
-            // Hopefully now, self.set_context will have been synchronously called
-            if let Some(client) = self.external_client.lock().unwrap().clone() {
-                gst::debug!(CAT, obj: src, "Using shared client");
-                *client_guard = Some(client.clone());
-
-                return Ok(client);
-            }
-        }
-
-        let mut builder = Client::builder().cookie_store(true).gzip(true);
-
-        if let Some(proxy) = &proxy {
-            // Proxy is url-checked on property set but perhaps this might still fail.
-            let mut p = reqwest::Proxy::all(proxy).map_err(|err| {
-                gst::error_msg!(gst::ResourceError::OpenRead, ["Bad proxy URI: {}", err])
-            })?;
-            if let Some(proxy_id) = &proxy_id {
-                let proxy_pw = proxy_pw.as_deref().unwrap_or("");
-                p = p.basic_auth(proxy_id, proxy_pw);
-            }
-            builder = builder.proxy(p);
-        }
-
-        gst::debug!(CAT, obj: src, "Creating new client");
-        let client = ClientContext(Arc::new(ClientContextInner {
-            client: builder.build().map_err(|err| {
-                gst::error_msg!(
-                    gst::ResourceError::OpenRead,
-                    ["Failed to create Client: {}", err]
-                )
-            })?,
-        }));
-
-        // Share created client with other elements, unless using proxy. Shared client never uses proxy.
-        // The alternative would be different contexts for different proxy settings, or one context with a
-        // map from proxy settings to client, but then, how and when to discard those, retaining reuse benefits?
-        if proxy.is_none() {
-            gst::debug!(CAT, obj: src, "Sharing new client with other elements");
-            let mut context = gst::Context::new(REQWEST_CLIENT_CONTEXT, true);
-            {
-                let context = context.get_mut().unwrap();
-                let s = context.structure_mut();
-                s.set("client", &client);
-            }
-            src.set_context(&context);
-            let _ = src.post_message(gst::message::HaveContext::builder(context).src(src).build());
-        }
-
-        *client_guard = Some(client.clone());
-
-        Ok(client)
+        self.client.ensure(
+            src.upcast_ref(),
+            &src.static_pad("src").unwrap(),
+            settings,
+            gst::ResourceError::OpenRead,
+        )
     }
 
     fn do_request(
@@ -338,18 +367,32 @@ impl ReqwestHttpSrc {
         uri: Url,
         start: u64,
         stop: Option<u64>,
+    ) -> Result<State, Option<gst::ErrorMessage>> {
+        self.send_request(src, uri, start, stop, None, true)
+    }
+
+    /// Sends a request for `uri` and, if `retry_auth` is set, retries it once if the server
+    /// rejects its credentials: answering its `Digest` challenge with the `user-id` and
+    /// `user-pw`, or else with a new token from the `request-token` signal.
+    fn send_request(
+        &self,
+        src: &super::ReqwestHttpSrc,
+        uri: Url,
+        start: u64,
+        stop: Option<u64>,
+        digest_authorization: Option<String>,
+        retry_auth: bool,
     ) -> Result<State, Option<gst::ErrorMessage>> {
         use headers::{Connection, ContentLength, ContentRange, HeaderMapExt, Range, UserAgent};
-        use reqwest::header::{self, HeaderMap, HeaderName, HeaderValue};
+        use reqwest::header::{self, HeaderMap, HeaderValue};
 
         gst::debug!(CAT, obj: src, "Creating new request for {}", uri);
 
         let settings = self.settings.lock().unwrap().clone();
 
         let req = self
-            .ensure_client(src, settings.proxy, settings.proxy_id, settings.proxy_pw)?
-            .0
-            .client
+            .ensure_client(src, &settings.client)?
+            .client()
             .get(uri.clone());
 
         let mut headers = HeaderMap::new();
@@ -381,66 +424,7 @@ impl ReqwestHttpSrc {
         };
 
         if let Some(ref extra_headers) = settings.extra_headers {
-            for (field, value) in extra_headers.iter() {
-                let field = match HeaderName::try_from(field) {
-                    Ok(field) => field,
-                    Err(err) => {
-                        gst::warning!(
-                            CAT,
-                            obj: src,
-                            "Failed to transform extra-header field name '{}' to header name: {}",
-                            field,
-                            err,
-                        );
-
-                        continue;
-                    }
-                };
-
-                let mut append_header = |field: &HeaderName, value: &glib::Value| {
-                    let value = match value.transform::<String>() {
-                        Ok(value) => value,
-                        Err(_) => {
-                            gst::warning!(
-                                CAT,
-                                obj: src,
-                                "Failed to transform extra-header '{}' value to string",
-                                field
-                            );
-                            return;
-                        }
-                    };
-
-                    let value = value.get::<Option<&str>>().unwrap().unwrap_or("");
-
-                    let value = match value.parse::<HeaderValue>() {
-                        Ok(value) => value,
-                        Err(_) => {
-                            gst::warning!(
-                                CAT,
-                                obj: src,
-                                "Failed to transform extra-header '{}' value to header value",
-                                field
-                            );
-                            return;
-                        }
-                    };
-
-                    headers.append(field.clone(), value);
-                };
-
-                if let Ok(values) = value.get::<gst::ArrayRef>() {
-                    for value in values.as_slice() {
-                        append_header(&field, value);
-                    }
-                } else if let Ok(values) = value.get::<gst::ListRef>() {
-                    for value in values.as_slice() {
-                        append_header(&field, value);
-                    }
-                } else {
-                    append_header(&field, value);
-                }
-            }
+            client::append_extra_headers(src.upcast_ref(), &mut headers, extra_headers);
         }
 
         if !settings.cookies.is_empty() {
@@ -457,7 +441,11 @@ impl ReqwestHttpSrc {
         // Add all headers for the request here
         let req = req.headers(headers);
 
-        let req = if let Some(ref user_id) = settings.user_id {
+        let req = if let Some(authorization) = digest_authorization {
+            req.header(header::AUTHORIZATION, authorization)
+        } else if let Some(ref bearer_token) = settings.bearer_token {
+            req.bearer_auth(bearer_token)
+        } else if let Some(ref user_id) = settings.user_id {
             // HTTP auth available
             req.basic_auth(user_id, settings.user_pw)
         } else {
@@ -492,6 +480,42 @@ impl ReqwestHttpSrc {
 
         if !res.status().is_success() {
             match res.status() {
+                StatusCode::UNAUTHORIZED if retry_auth => {
+                    let challenge = digest::Challenge::from_headers(res.headers());
+                    if let (Some(challenge), None, Some(user_id)) =
+                        (challenge, &settings.bearer_token, &settings.user_id)
+                    {
+                        gst::debug!(CAT, obj: src, "Not authorized, answering digest challenge");
+                        let authorization = challenge.authorization(
+                            user_id,
+                            settings.user_pw.as_deref().unwrap_or(""),
+                            "GET",
+                            &uri,
+                        );
+                        return self.send_request(
+                            src,
+                            uri,
+                            start,
+                            stop,
+                            Some(authorization),
+                            false,
+                        );
+                    }
+
+                    gst::debug!(CAT, obj: src, "Not authorized, requesting new token");
+                    if let Some(token) =
+                        src.emit_by_name::<Option<String>>("request-token", &[&uri.to_string()])
+                    {
+                        self.settings.lock().unwrap().bearer_token = Some(token);
+                        return self.send_request(src, uri, start, stop, None, false);
+                    }
+
+                    gst::error!(CAT, obj: src, "Not authorized: {}", res.status());
+                    return Err(Some(gst::error_msg!(
+                        gst::ResourceError::NotAuthorized,
+                        ["Not Authorized for resource '{}': {}", uri, res.status()]
+                    )));
+                }
                 StatusCode::NOT_FOUND => {
                     gst::error!(CAT, obj: src, "Resource not found");
                     return Err(Some(gst::error_msg!(
@@ -520,9 +544,21 @@ impl ReqwestHttpSrc {
         }
 
         let headers = res.headers();
+
+        let icy_metaint = headers
+            .get("icy-metaint")
+            .and_then(|s| s.to_str().ok())
+            .and_then(|s| s.parse::<i32>().ok());
+
+        // When stripping the metadata the byte positions don't correspond to the response anymore
+        let icy = icy_metaint
+            .filter(|&icy_metaint| settings.icy_demux && icy_metaint > 0)
+            .map(|icy_metaint| IcyDemux::new(icy_metaint as usize));
+
         let size = headers
             .typed_get::<ContentLength>()
-            .map(|ContentLength(cl)| cl + start);
+            .map(|ContentLength(cl)| cl + start)
+            .filter(|_| icy.is_none());
 
         let accept_byte_ranges = headers
             .get(header::ACCEPT_RANGES)
@@ -546,15 +582,11 @@ impl ReqwestHttpSrc {
             )));
         }
 
-        let mut caps = headers
-            .get("icy-metaint")
-            .and_then(|s| s.to_str().ok())
-            .and_then(|s| s.parse::<i32>().ok())
-            .map(|icy_metaint| {
-                gst::Caps::builder("application/x-icy")
-                    .field("metadata-interval", icy_metaint)
-                    .build()
-            });
+        let mut caps = icy_metaint.filter(|_| icy.is_none()).map(|icy_metaint| {
+            gst::Caps::builder("application/x-icy")
+                .field("metadata-interval", icy_metaint)
+                .build()
+        });
 
         if let Some(content_type) = headers
             .get(header::CONTENT_TYPE)
@@ -562,7 +594,9 @@ impl ReqwestHttpSrc {
             .and_then(|content_type| content_type.parse::<mime::Mime>().ok())
         {
             gst::debug!(CAT, obj: src, "Got content type {}", content_type);
-            if let Some(ref mut caps) = caps {
+            if icy.is_some() {
+                caps = icy_content_caps(&content_type);
+            } else if let Some(ref mut caps) = caps {
                 let caps = caps.get_mut().unwrap();
                 let s = caps.structure_mut(0).unwrap();
                 s.set("content-type", &content_type.as_ref());
@@ -615,55 +649,161 @@ impl ReqwestHttpSrc {
             stop,
             caps,
             tags: if tags.n_tags() > 0 { Some(tags) } else { None },
+            icy,
+            failed_attempts: 0,
+            discont: false,
         })
     }
 
-    fn wait<F, T>(&self, future: F) -> Result<T, Option<gst::ErrorMessage>>
-    where
-        F: Send + Future<Output = Result<T, gst::ErrorMessage>>,
-        T: Send + 'static,
-    {
-        let timeout = self.settings.lock().unwrap().timeout;
+    /// Re-issue the request after the response body failed with `err`.
+    ///
+    /// Seekable resources are resumed from the current position with a `Range` header, for
+    /// everything else a new request is made and the next buffer is flagged as discontinuous.
+    /// Every attempt is reported with a `reqwesthttpsrc-retry` element message.
+    fn reconnect(
+        &self,
+        src: &super::ReqwestHttpSrc,
+        mut err: gst::ErrorMessage,
+    ) -> Result<(), Option<gst::ErrorMessage>> {
+        let (retries, retry_backoff) = {
+            let settings = self.settings.lock().unwrap();
+            (settings.retries, settings.retry_backoff)
+        };
 
-        let mut canceller = self.canceller.lock().unwrap();
-        let (abort_handle, abort_registration) = future::AbortHandle::new_pair();
-        canceller.replace(abort_handle);
-        drop(canceller);
+        loop {
+            let (uri, seekable, position, stop, icy_offset, attempt) =
+                match *self.state.lock().unwrap() {
+                    State::Started {
+                        ref uri,
+                        seekable,
+                        position,
+                        stop,
+                        ref icy,
+                        ref mut failed_attempts,
+                        ..
+                    } => {
+                        *failed_attempts += 1;
+                        (
+                            uri.clone(),
+                            seekable,
+                            position,
+                            stop,
+                            icy.as_ref().map(|icy| icy.offset),
+                            *failed_attempts,
+                        )
+                    }
+                    State::Stopped => {
+                        return Err(Some(gst::error_msg!(
+                            gst::LibraryError::Failed,
+                            ["Not started yet"]
+                        )));
+                    }
+                };
 
-        // Wrap in a timeout
-        let future = async {
-            if timeout == 0 {
-                future.await
-            } else {
-                let res = tokio::time::timeout(Duration::from_secs(timeout.into()), future).await;
-
-                match res {
-                    Ok(res) => res,
-                    Err(_) => Err(gst::error_msg!(
-                        gst::ResourceError::Read,
-                        ["Request timeout"]
-                    )),
-                }
+            if retries >= 0 && attempt > retries as u32 {
+                gst::debug!(CAT, obj: src, "Giving up after {} retries", retries);
+                return Err(Some(err));
             }
-        };
 
-        // And make abortable
-        let future = async {
-            match future::Abortable::new(future, abort_registration).await {
-                Ok(res) => res.map_err(Some),
-                Err(_) => Err(None),
+            gst::info!(
+                CAT,
+                obj: src,
+                "Reconnecting at offset {} (attempt {})",
+                position,
+                attempt
+            );
+
+            let _ = src.post_message(
+                gst::message::Element::builder(
+                    gst::Structure::builder("reqwesthttpsrc-retry")
+                        .field("attempt", attempt)
+                        .field("retries", retries)
+                        .field("position", position)
+                        .field("error", err.to_string())
+                        .build(),
+                )
+                .src(src)
+                .build(),
+            );
+
+            let delay =
+                Duration::from_millis(retry_backoff.into()) * 2u32.pow((attempt - 1).min(16));
+            self.wait_abortable(async {
+                tokio::time::sleep(delay).await;
+                Ok(())
+            })?;
+
+            let res = if seekable {
+                self.do_request(src, uri, position, stop)
+            } else {
+                self.do_request(src, uri, 0, None)
+            };
+
+            match res {
+                Ok(State::Started {
+                    uri,
+                    response,
+                    seekable,
+                    size,
+                    stop,
+                    caps,
+                    tags,
+                    mut icy,
+                    ..
+                }) => {
+                    // Continue the offsets of the demuxed audio stream
+                    if let (Some(icy), Some(icy_offset)) = (icy.as_mut(), icy_offset) {
+                        icy.offset = icy_offset;
+                    }
+
+                    *self.state.lock().unwrap() = State::Started {
+                        uri,
+                        response,
+                        seekable,
+                        position,
+                        size,
+                        stop,
+                        caps,
+                        tags,
+                        icy,
+                        failed_attempts: attempt,
+                        // Bytes might have been skipped if we couldn't resume at the exact position
+                        discont: !seekable,
+                    };
+
+                    return Ok(());
+                }
+                Ok(State::Stopped) => unreachable!(),
+                Err(Some(new_err)) => {
+                    gst::debug!(CAT, obj: src, "Reconnecting failed: {:?}", new_err);
+                    err = new_err;
+                }
+                Err(None) => return Err(None),
             }
-        };
+        }
+    }
 
-        let res = {
-            let _enter = RUNTIME.enter();
-            futures::executor::block_on(future)
+    fn wait<F, T>(&self, future: F) -> Result<T, Option<gst::ErrorMessage>>
+    where
+        F: Send + Future<Output = Result<T, gst::ErrorMessage>>,
+        T: Send + 'static,
+    {
+        let timeout = self.settings.lock().unwrap().timeout;
+        let timeout = if timeout == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(timeout.into()))
         };
 
-        /* Clear out the canceller */
-        let _ = self.canceller.lock().unwrap().take();
+        client::wait(&self.canceller, timeout, future)
+    }
 
-        res
+    fn wait_abortable<F, T>(&self, future: F) -> Result<T, Option<gst::ErrorMessage>>
+    where
+        F: Send + Future<Output = Result<T, gst::ErrorMessage>>,
+        T: Send + 'static,
+    {
+        client::wait(&self.canceller, None, future)
     }
 }
 
@@ -695,14 +835,14 @@ impl ObjectImpl for ReqwestHttpSrc {
                 glib::ParamSpecString::new(
                     "user-id",
                     "User-id",
-                    "HTTP location URI user id for authentication",
+                    "HTTP location URI user id for basic or digest authentication",
                     None,
                     glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
                 ),
                 glib::ParamSpecString::new(
                     "user-pw",
                     "User-pw",
-                    "HTTP location URI user password for authentication",
+                    "HTTP location URI user password for basic or digest authentication",
                     None,
                     glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
                 ),
@@ -743,6 +883,13 @@ impl ObjectImpl for ReqwestHttpSrc {
                     DEFAULT_IRADIO_MODE,
                     glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
                 ),
+                glib::ParamSpecBoolean::new(
+                    "icy-demux",
+                    "ICY Demux",
+                    "Strip interleaved shoutcast/icecast metadata from the stream and send it as tags (requires iradio-mode)",
+                    DEFAULT_ICY_DEMUX,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
                 glib::ParamSpecBoolean::new(
                     "keep-alive",
                     "Keep Alive",
@@ -750,6 +897,59 @@ impl ObjectImpl for ReqwestHttpSrc {
                     DEFAULT_KEEP_ALIVE,
                     glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
                 ),
+                glib::ParamSpecInt::new(
+                    "retries",
+                    "Retries",
+                    "Maximum number of reconnection attempts after a connection loss (0 = fail on connection loss, -1 = unlimited)",
+                    -1,
+                    i32::MAX,
+                    DEFAULT_RETRIES,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecUInt::new(
+                    "retry-backoff",
+                    "Retry Backoff",
+                    "Delay in milliseconds before the first reconnection attempt, doubled for every further attempt",
+                    0,
+                    u32::MAX,
+                    DEFAULT_RETRY_BACKOFF,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecString::new(
+                    "bearer-token",
+                    "Bearer Token",
+                    "Token for HTTP bearer authentication",
+                    None,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "ssl-strict",
+                    "SSL Strict",
+                    "Strict SSL certificate checking",
+                    true,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecString::new(
+                    "ssl-ca-file",
+                    "SSL CA File",
+                    "Location of a PEM file with additional CA certificates to trust",
+                    None,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecString::new(
+                    "tls-certificate-file",
+                    "TLS Certificate File",
+                    "Location of a PEM file with the client certificate for TLS authentication",
+                    None,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecString::new(
+                    "tls-key-file",
+                    "TLS Key File",
+                    "Location of a PEM file with the PKCS#8 private key of the client certificate (default: read from the certificate file)",
+                    None,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
                 glib::ParamSpecString::new(
                     "proxy",
                     "Proxy",
@@ -777,6 +977,19 @@ impl ObjectImpl for ReqwestHttpSrc {
         PROPERTIES.as_ref()
     }
 
+    fn signals() -> &'static [glib::subclass::Signal] {
+        static SIGNALS: Lazy<Vec<glib::subclass::Signal>> = Lazy::new(|| {
+            vec![glib::subclass::Signal::builder(
+                "request-token",
+                &[String::static_type().into()],
+                String::static_type().into(),
+            )
+            .build()]
+        });
+
+        SIGNALS.as_ref()
+    }
+
     fn set_property(
         &self,
         obj: &Self::Type,
@@ -844,21 +1057,75 @@ impl ObjectImpl for ReqwestHttpSrc {
                 settings.iradio_mode = iradio_mode;
                 Ok(())
             }
+            "icy-demux" => {
+                let mut settings = self.settings.lock().unwrap();
+                let icy_demux = value.get().expect("type checked upstream");
+                settings.icy_demux = icy_demux;
+                Ok(())
+            }
             "keep-alive" => {
                 let mut settings = self.settings.lock().unwrap();
                 let keep_alive = value.get().expect("type checked upstream");
                 settings.keep_alive = keep_alive;
                 Ok(())
             }
+            "retries" => {
+                let mut settings = self.settings.lock().unwrap();
+                let retries = value.get().expect("type checked upstream");
+                settings.retries = retries;
+                Ok(())
+            }
+            "retry-backoff" => {
+                let mut settings = self.settings.lock().unwrap();
+                let retry_backoff = value.get().expect("type checked upstream");
+                settings.retry_backoff = retry_backoff;
+                Ok(())
+            }
+            "bearer-token" => {
+                let mut settings = self.settings.lock().unwrap();
+                let bearer_token = value.get().expect("type checked upstream");
+                settings.bearer_token = bearer_token;
+                Ok(())
+            }
+            "ssl-strict" => {
+                let ssl_strict = value.get().expect("type checked upstream");
+                self.set_client_prop(pspec.name(), ssl_strict, move |settings| {
+                    &mut settings.ssl_strict
+                })
+            }
+            "ssl-ca-file" => {
+                let ssl_ca_file = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream");
+                self.set_client_prop(pspec.name(), ssl_ca_file, move |settings| {
+                    &mut settings.ssl_ca_file
+                })
+            }
+            "tls-certificate-file" => {
+                let tls_certificate_file = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream");
+                self.set_client_prop(pspec.name(), tls_certificate_file, move |settings| {
+                    &mut settings.tls_certificate_file
+                })
+            }
+            "tls-key-file" => {
+                let tls_key_file = value
+                    .get::<Option<String>>()
+                    .expect("type checked upstream");
+                self.set_client_prop(pspec.name(), tls_key_file, move |settings| {
+                    &mut settings.tls_key_file
+                })
+            }
             "proxy" => {
-                let proxy = proxy_from_str(
+                let proxy = client::proxy_from_str(
                     value
                         .get::<Option<String>>()
                         .expect("type checked upstream"),
                 );
                 match proxy {
                     Ok(proxy) => self
-                        .set_proxy_prop(pspec.name(), proxy, move |settings| &mut settings.proxy),
+                        .set_client_prop(pspec.name(), proxy, move |settings| &mut settings.proxy),
                     Err(e) => Err(e),
                 }
             }
@@ -866,7 +1133,7 @@ impl ObjectImpl for ReqwestHttpSrc {
                 let proxy_id = value
                     .get::<Option<String>>()
                     .expect("type checked upstream");
-                self.set_proxy_prop(pspec.name(), proxy_id, move |settings| {
+                self.set_client_prop(pspec.name(), proxy_id, move |settings| {
                     &mut settings.proxy_id
                 })
             }
@@ -874,7 +1141,7 @@ impl ObjectImpl for ReqwestHttpSrc {
                 let proxy_pw = value
                     .get::<Option<String>>()
                     .expect("type checked upstream");
-                self.set_proxy_prop(pspec.name(), proxy_pw, move |settings| {
+                self.set_client_prop(pspec.name(), proxy_pw, move |settings| {
                     &mut settings.proxy_pw
                 })
             }
@@ -933,21 +1200,48 @@ impl ObjectImpl for ReqwestHttpSrc {
                 let settings = self.settings.lock().unwrap();
                 settings.iradio_mode.to_value()
             }
+            "icy-demux" => {
+                let settings = self.settings.lock().unwrap();
+                settings.icy_demux.to_value()
+            }
             "keep-alive" => {
                 let settings = self.settings.lock().unwrap();
                 settings.keep_alive.to_value()
             }
+            "retries" => {
+                let settings = self.settings.lock().unwrap();
+                settings.retries.to_value()
+            }
+            "retry-backoff" => {
+                let settings = self.settings.lock().unwrap();
+                settings.retry_backoff.to_value()
+            }
+            "bearer-token" => {
+                let settings = self.settings.lock().unwrap();
+                settings.bearer_token.to_value()
+            }
+            "ssl-strict" => self.settings.lock().unwrap().client.ssl_strict.to_value(),
+            "ssl-ca-file" => self.settings.lock().unwrap().client.ssl_ca_file.to_value(),
+            "tls-certificate-file" => self
+                .settings
+                .lock()
+                .unwrap()
+                .client
+                .tls_certificate_file
+                .to_value(),
+            "tls-key-file" => self.settings.lock().unwrap().client.tls_key_file.to_value(),
             // return None values as Some("") for compatibility with souphttpsrc
             "proxy" => self
                 .settings
                 .lock()
                 .unwrap()
+                .client
                 .proxy
                 .as_deref()
                 .unwrap_or("")
                 .to_value(),
-            "proxy-id" => self.settings.lock().unwrap().proxy_id.to_value(),
-            "proxy-pw" => self.settings.lock().unwrap().proxy_pw.to_value(),
+            "proxy-id" => self.settings.lock().unwrap().client.proxy_id.to_value(),
+            "proxy-pw" => self.settings.lock().unwrap().client.proxy_pw.to_value(),
             _ => unimplemented!(),
         }
     }
@@ -993,14 +1287,7 @@ impl ElementImpl for ReqwestHttpSrc {
     }
 
     fn set_context(&self, element: &Self::Type, context: &gst::Context) {
-        if context.context_type() == REQWEST_CLIENT_CONTEXT {
-            let mut external_client = self.external_client.lock().unwrap();
-            let s = context.structure();
-            *external_client = s
-                .get::<&ClientContext>("client")
-                .map(|c| Some(c.clone()))
-                .unwrap_or(None);
-        }
+        self.client.set_context(context);
 
         self.parent_set_context(element, context);
     }
@@ -1011,7 +1298,7 @@ impl ElementImpl for ReqwestHttpSrc {
         transition: gst::StateChange,
     ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
         if let gst::StateChange::ReadyToNull = transition {
-            *self.client.lock().unwrap() = None;
+            self.client.reset();
         }
 
         self.parent_change_state(element, transition)
@@ -1143,122 +1430,183 @@ impl PushSrcImpl for ReqwestHttpSrc {
         src: &Self::Type,
         _buffer: Option<&mut gst::BufferRef>,
     ) -> Result<CreateSuccess, gst::FlowError> {
-        let mut state = self.state.lock().unwrap();
+        loop {
+            let mut state = self.state.lock().unwrap();
+
+            let (response, position, caps, tags, icy) = match *state {
+                State::Started {
+                    ref mut response,
+                    ref mut position,
+                    ref mut tags,
+                    ref mut caps,
+                    ref mut icy,
+                    ..
+                } => (response, position, caps, tags, icy),
+                State::Stopped => {
+                    gst::element_error!(src, gst::LibraryError::Failed, ["Not started yet"]);
+
+                    return Err(gst::FlowError::Error);
+                }
+            };
 
-        let (response, position, caps, tags) = match *state {
-            State::Started {
-                ref mut response,
-                ref mut position,
-                ref mut tags,
-                ref mut caps,
-                ..
-            } => (response, position, caps, tags),
-            State::Stopped => {
-                gst::element_error!(src, gst::LibraryError::Failed, ["Not started yet"]);
+            let offset = *position;
 
-                return Err(gst::FlowError::Error);
-            }
-        };
+            // Data left over from the last chunk after stripping ICY metadata
+            let pending = icy.as_mut().and_then(|icy| icy.pending.take());
 
-        let offset = *position;
+            let current_response = match (&pending, response.take()) {
+                (Some(_), response) => response,
+                (None, Some(response)) => Some(response),
+                (None, None) => {
+                    gst::error!(CAT, obj: src, "Don't have a response");
+                    gst::element_error!(src, gst::ResourceError::Read, ["Don't have a response"]);
 
-        let mut current_response = match response.take() {
-            Some(response) => response,
-            None => {
-                gst::error!(CAT, obj: src, "Don't have a response");
-                gst::element_error!(src, gst::ResourceError::Read, ["Don't have a response"]);
+                    return Err(gst::FlowError::Error);
+                }
+            };
 
-                return Err(gst::FlowError::Error);
-            }
-        };
+            let tags = tags.take();
+            let caps = caps.take();
+            drop(state);
 
-        let tags = tags.take();
-        let caps = caps.take();
-        drop(state);
+            if let Some(caps) = caps {
+                gst::debug!(CAT, obj: src, "Setting caps {:?}", caps);
+                src.set_caps(&caps)
+                    .map_err(|_| gst::FlowError::NotNegotiated)?;
+            }
 
-        if let Some(caps) = caps {
-            gst::debug!(CAT, obj: src, "Setting caps {:?}", caps);
-            src.set_caps(&caps)
-                .map_err(|_| gst::FlowError::NotNegotiated)?;
-        }
+            if let Some(tags) = tags {
+                gst::debug!(CAT, obj: src, "Sending iradio tags {:?}", tags);
+                let pad = src.static_pad("src").unwrap();
+                pad.push_event(gst::event::Tag::new(tags));
+            }
 
-        if let Some(tags) = tags {
-            gst::debug!(CAT, obj: src, "Sending iradio tags {:?}", tags);
-            let pad = src.static_pad("src").unwrap();
-            pad.push_event(gst::event::Tag::new(tags));
-        }
+            let from_network = pending.is_none();
+            let (res, current_response) = match (pending, current_response) {
+                (Some(pending), current_response) => (Ok(Some(pending)), current_response),
+                (None, Some(mut current_response)) => {
+                    let future = async {
+                        current_response.chunk().await.map_err(move |err| {
+                            gst::error_msg!(
+                                gst::ResourceError::Read,
+                                ["Failed to read chunk at offset {}: {:?}", offset, err]
+                            )
+                        })
+                    };
+                    let res = self.wait(future);
 
-        let future = async {
-            current_response.chunk().await.map_err(move |err| {
-                gst::error_msg!(
-                    gst::ResourceError::Read,
-                    ["Failed to read chunk at offset {}: {:?}", offset, err]
-                )
-            })
-        };
-        let res = self.wait(future);
+                    (res, Some(current_response))
+                }
+                (None, None) => unreachable!(),
+            };
 
-        let res = match res {
-            Ok(res) => res,
-            Err(Some(err)) => {
-                gst::debug!(CAT, obj: src, "Error {:?}", err);
-                src.post_error_message(err);
-                return Err(gst::FlowError::Error);
-            }
-            Err(None) => {
-                gst::debug!(CAT, obj: src, "Flushing");
-                return Err(gst::FlowError::Flushing);
-            }
-        };
+            let res = match res {
+                Ok(res) => res,
+                Err(Some(err)) => {
+                    gst::debug!(CAT, obj: src, "Error {:?}", err);
+                    match self.reconnect(src, err) {
+                        Ok(()) => continue,
+                        Err(Some(err)) => {
+                            src.post_error_message(err);
+                            return Err(gst::FlowError::Error);
+                        }
+                        Err(None) => {
+                            gst::debug!(CAT, obj: src, "Flushing");
+                            return Err(gst::FlowError::Flushing);
+                        }
+                    }
+                }
+                Err(None) => {
+                    gst::debug!(CAT, obj: src, "Flushing");
+                    return Err(gst::FlowError::Flushing);
+                }
+            };
 
-        let mut state = self.state.lock().unwrap();
-        let (response, position) = match *state {
-            State::Started {
-                ref mut response,
-                ref mut position,
-                ..
-            } => (response, position),
-            State::Stopped => {
-                gst::element_error!(src, gst::LibraryError::Failed, ["Not started yet"]);
+            let mut state = self.state.lock().unwrap();
+            let (response, position, icy, failed_attempts, discont) = match *state {
+                State::Started {
+                    ref mut response,
+                    ref mut position,
+                    ref mut icy,
+                    ref mut failed_attempts,
+                    ref mut discont,
+                    ..
+                } => (response, position, icy, failed_attempts, discont),
+                State::Stopped => {
+                    gst::element_error!(src, gst::LibraryError::Failed, ["Not started yet"]);
+
+                    return Err(gst::FlowError::Error);
+                }
+            };
 
-                return Err(gst::FlowError::Error);
+            if let Some(current_response) = current_response {
+                *response = Some(current_response);
             }
-        };
 
-        match res {
-            Some(chunk) => {
-                /* do something with the chunk and store the body again in the state */
+            let chunk = match res {
+                Some(chunk) => chunk,
+                None => {
+                    /* No further data, end of stream */
+                    gst::debug!(CAT, obj: src, "End of stream");
+                    return Err(gst::FlowError::Eos);
+                }
+            };
 
-                gst::trace!(
-                    CAT,
-                    obj: src,
-                    "Chunk of {} bytes received at offset {}",
-                    chunk.len(),
-                    offset
-                );
-                let size = chunk.len();
-                assert_ne!(chunk.len(), 0);
+            /* do something with the chunk and store the body again in the state */
 
-                *position += size as u64;
+            gst::trace!(
+                CAT,
+                obj: src,
+                "Chunk of {} bytes received at offset {}",
+                chunk.len(),
+                offset
+            );
+            assert_ne!(chunk.len(), 0);
 
-                let mut buffer = gst::Buffer::from_slice(chunk);
+            if from_network {
+                *position += chunk.len() as u64;
+                *failed_attempts = 0;
+            }
 
-                *response = Some(current_response);
+            let (data, offset, discont, icy_tags) = match icy {
+                Some(ref mut icy) => match icy.demux(chunk) {
+                    (Some(audio), icy_tags) => {
+                        let audio_offset = icy.offset - audio.len() as u64;
+                        (Some(audio), audio_offset, std::mem::take(discont), icy_tags)
+                    }
+                    (None, icy_tags) => (None, icy.offset, false, icy_tags),
+                },
+                None => (Some(chunk), offset, std::mem::take(discont), Vec::new()),
+            };
+            drop(state);
 
-                {
-                    let buffer = buffer.get_mut().unwrap();
-                    buffer.set_offset(offset);
-                    buffer.set_offset_end(offset + size as u64);
+            if !icy_tags.is_empty() {
+                let pad = src.static_pad("src").unwrap();
+                for tags in icy_tags {
+                    gst::debug!(CAT, obj: src, "Sending ICY metadata tags {:?}", tags);
+                    pad.push_event(gst::event::Tag::new(tags));
                 }
-
-                Ok(CreateSuccess::NewBuffer(buffer))
             }
-            None => {
-                /* No further data, end of stream */
-                gst::debug!(CAT, obj: src, "End of stream");
-                *response = Some(current_response);
-                Err(gst::FlowError::Eos)
+
+            let data = match data {
+                Some(data) => data,
+                // Only metadata in this chunk, read the next one
+                None => continue,
+            };
+            let size = data.len();
+
+            let mut buffer = gst::Buffer::from_slice(data);
+
+            {
+                let buffer = buffer.get_mut().unwrap();
+                buffer.set_offset(offset);
+                buffer.set_offset_end(offset + size as u64);
+                if discont {
+                    buffer.set_flags(gst::BufferFlags::DISCONT);
+                }
             }
+
+            return Ok(CreateSuccess::NewBuffer(buffer));
         }
     }
 }
@@ -0,0 +1,236 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// SPDX-License-Identifier: MIT/Apache-2.0
+
+use gst::prelude::*;
+
+use std::sync::mpsc;
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        // clear this environment because it affects the default settings
+        std::env::remove_var("http_proxy");
+        gst::init().unwrap();
+        gstreqwest::plugin_register_static().expect("reqwesthttpsink tests");
+    });
+}
+
+/// A request as received by the HTTP server of the test harness
+#[derive(Debug)]
+struct Request {
+    method: hyper::Method,
+    headers: hyper::HeaderMap,
+    body: Vec<u8>,
+}
+
+/// Our custom test harness around the HTTP sink
+struct Harness {
+    sink: gst::Element,
+    pad: gst::Pad,
+    receiver: mpsc::Receiver<Request>,
+    rt: Option<tokio::runtime::Runtime>,
+}
+
+impl Harness {
+    /// Creates a new HTTP sink and test harness around it
+    ///
+    /// `status`: Status code the HTTP server responds with to every request
+    /// `setup_func`: Setup function for the HTTP sink, should only set properties and similar
+    fn new<G: FnOnce(&gst::Element)>(status: hyper::StatusCode, setup_func: G) -> Harness {
+        use hyper::service::{make_service_fn, service_fn};
+        use hyper::{Body, Response, Server};
+
+        // Create the HTTP sink
+        let sink = gst::ElementFactory::make("reqwesthttpsink", None).unwrap();
+        sink.set_property("sync", false);
+
+        // Source pad that feeds the sink
+        let pad = gst::Pad::builder(Some("src"), gst::PadDirection::Src).build();
+        pad.link(&sink.static_pad("sink").unwrap()).unwrap();
+        pad.set_active(true).unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+
+        // Create the tokio runtime used for the HTTP server in this test
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        // Create an HTTP sever that listens on localhost on some random, free port and forwards
+        // every request including its complete body to the test
+        let addr = ([127, 0, 0, 1], 0).into();
+
+        let make_service = make_service_fn(move |_ctx| {
+            let sender = sender.clone();
+            async move {
+                Ok::<_, hyper::Error>(service_fn(move |req: hyper::Request<Body>| {
+                    let sender = sender.clone();
+                    async move {
+                        let (parts, body) = req.into_parts();
+                        let body = hyper::body::to_bytes(body).await?;
+                        let _ = sender.send(Request {
+                            method: parts.method,
+                            headers: parts.headers,
+                            body: body.to_vec(),
+                        });
+
+                        Ok::<_, hyper::Error>(
+                            Response::builder()
+                                .status(status)
+                                .body(Body::empty())
+                                .unwrap(),
+                        )
+                    }
+                }))
+            }
+        });
+
+        let (local_addr_sender, local_addr_receiver) = tokio::sync::oneshot::channel();
+
+        // Spawn the server in the background so that it can handle requests
+        rt.spawn(async move {
+            let server = Server::bind(&addr).serve(make_service);
+            local_addr_sender.send(server.local_addr()).unwrap();
+
+            let _ = server.await;
+        });
+
+        let local_addr = futures::executor::block_on(local_addr_receiver).unwrap();
+        sink.set_property("location", format!("http://{}/upload", local_addr));
+
+        // Let the test setup anything needed on the HTTP sink now
+        setup_func(&sink);
+
+        Harness {
+            sink,
+            pad,
+            receiver,
+            rt: Some(rt),
+        }
+    }
+
+    /// Sets the sink to Playing and pushes the initial events
+    fn play(&self) {
+        self.sink.set_state(gst::State::Playing).unwrap();
+
+        assert!(self.pad.push_event(gst::event::StreamStart::new("test")));
+        assert!(self
+            .pad
+            .push_event(gst::event::Caps::new(&gst::Caps::new_any())));
+        let segment = gst::FormattedSegment::<gst::format::Bytes>::new();
+        assert!(self.pad.push_event(gst::event::Segment::new(&segment)));
+    }
+
+    fn push(&self, data: &'static [u8]) -> Result<gst::FlowSuccess, gst::FlowError> {
+        self.pad.push(gst::Buffer::from_slice(data))
+    }
+
+    fn push_eos(&self) -> bool {
+        self.pad.push_event(gst::event::Eos::new())
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        self.pad.set_active(false).unwrap();
+        self.sink.set_state(gst::State::Null).unwrap();
+
+        self.rt.take().unwrap();
+    }
+}
+
+#[test]
+fn test_chunked_post() {
+    init();
+
+    let h = Harness::new(hyper::StatusCode::OK, |sink| {
+        sink.set_property("content-type", "video/mp2t");
+    });
+
+    h.play();
+    assert_eq!(h.push(b"Hello "), Ok(gst::FlowSuccess::Ok));
+    assert_eq!(h.push(b"World"), Ok(gst::FlowSuccess::Ok));
+    assert!(h.push_eos());
+
+    let req = h.receiver.recv().unwrap();
+    assert_eq!(req.method, hyper::Method::POST);
+    assert_eq!(req.headers.get("transfer-encoding").unwrap(), "chunked");
+    assert_eq!(req.headers.get("content-type").unwrap(), "video/mp2t");
+    assert_eq!(req.headers.get("connection").unwrap(), "keep-alive");
+    assert_eq!(req.body, b"Hello World");
+}
+
+#[test]
+fn test_put_at_eos() {
+    init();
+
+    let h = Harness::new(hyper::StatusCode::CREATED, |sink| {
+        sink.set_property_from_str("method", "put");
+        sink.set_property("chunked", false);
+        sink.set_property(
+            "extra-headers",
+            &gst::Structure::builder("headers")
+                .field("foo", "bar")
+                .build(),
+        );
+        sink.set_property(
+            "cookies",
+            &vec![String::from("foo=1"), String::from("bar=2")],
+        );
+    });
+
+    h.play();
+    assert_eq!(h.push(b"Hello "), Ok(gst::FlowSuccess::Ok));
+    assert_eq!(h.push(b"World"), Ok(gst::FlowSuccess::Ok));
+
+    // Nothing is uploaded before EOS
+    assert!(h.receiver.try_recv().is_err());
+
+    assert!(h.push_eos());
+
+    let req = h.receiver.recv().unwrap();
+    assert_eq!(req.method, hyper::Method::PUT);
+    assert_eq!(req.headers.get("content-length").unwrap(), "11");
+    assert_eq!(req.headers.get("foo").unwrap(), "bar");
+    assert_eq!(req.headers.get("cookie").unwrap(), "foo=1; bar=2");
+    assert_eq!(req.body, b"Hello World");
+}
+
+#[test]
+fn test_upload_error() {
+    init();
+
+    let h = Harness::new(hyper::StatusCode::FORBIDDEN, |sink| {
+        sink.set_property("chunked", false);
+    });
+
+    let bus = gst::Bus::new();
+    h.sink.set_bus(Some(&bus));
+
+    h.play();
+    assert_eq!(h.push(b"Hello World"), Ok(gst::FlowSuccess::Ok));
+    assert!(!h.push_eos());
+
+    let msg = bus
+        .timed_pop_filtered(gst::ClockTime::NONE, &[gst::MessageType::Error])
+        .unwrap();
+    match msg.view() {
+        gst::MessageView::Error(err) => {
+            assert_eq!(
+                err.error().kind::<gst::ResourceError>(),
+                Some(gst::ResourceError::NotAuthorized)
+            );
+        }
+        _ => unreachable!(),
+    }
+}
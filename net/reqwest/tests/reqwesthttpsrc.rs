@@ -13,7 +13,9 @@
 use gst::glib;
 use gst::prelude::*;
 
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc};
+
+use tokio_rustls::rustls;
 
 fn init() {
     use std::sync::Once;
@@ -34,6 +36,8 @@ struct Harness {
     pad: gst::Pad,
     receiver: Option<mpsc::Receiver<Message>>,
     rt: Option<tokio::runtime::Runtime>,
+    /// Element messages posted by the source so far
+    element_messages: Vec<gst::Structure>,
 }
 
 /// Messages sent from our test harness
@@ -57,10 +61,23 @@ impl Harness {
     >(
         http_func: F,
         setup_func: G,
+    ) -> Harness {
+        Self::with_tls(http_func, setup_func, None)
+    }
+
+    /// Creates a new HTTP source and test harness around it, serving HTTPS with `tls_config`
+    /// if set
+    fn with_tls<
+        F: FnMut(hyper::Request<hyper::Body>) -> hyper::Response<hyper::Body> + Send + 'static,
+        G: FnOnce(&gst::Element),
+    >(
+        http_func: F,
+        setup_func: G,
+        tls_config: Option<Arc<rustls::ServerConfig>>,
     ) -> Harness {
         use hyper::service::{make_service_fn, service_fn};
         use hyper::Server;
-        use std::sync::{Arc, Mutex};
+        use std::sync::Mutex;
 
         // Create the HTTP source
         let src = gst::ElementFactory::make("reqwesthttpsrc", None).unwrap();
@@ -111,41 +128,85 @@ impl Harness {
             .unwrap();
 
         // Create an HTTP sever that listens on localhost on some random, free port
-        let addr = ([127, 0, 0, 1], 0).into();
+        let addr: std::net::SocketAddr = ([127, 0, 0, 1], 0).into();
 
         // Whenever a new client is connecting, a new service function is requested. For each
         // client we use the same service function, which simply calls the function used by the
         // test
         let http_func = Arc::new(Mutex::new(http_func));
-        let make_service = make_service_fn(move |_ctx| {
+        let new_service = move || {
             let http_func = http_func.clone();
-            async move {
+            service_fn(move |req| {
                 let http_func = http_func.clone();
-                Ok::<_, hyper::Error>(service_fn(move |req| {
-                    let http_func = http_func.clone();
-                    async move { Ok::<_, hyper::Error>((&mut *http_func.lock().unwrap())(req)) }
-                }))
-            }
-        });
+                async move { Ok::<_, hyper::Error>((&mut *http_func.lock().unwrap())(req)) }
+            })
+        };
 
         let (local_addr_sender, local_addr_receiver) = tokio::sync::oneshot::channel();
+        let scheme = if tls_config.is_some() {
+            "https"
+        } else {
+            "http"
+        };
 
         // Spawn the server in the background so that it can handle requests
         rt.spawn(async move {
             // Bind the server, retrieve the local port that was selected in the end and set this as
             // the location property on the source
-            let server = Server::bind(&addr).serve(make_service);
-            let local_addr = server.local_addr();
+            let res = match tls_config {
+                None => {
+                    let make_service = make_service_fn(move |_ctx| {
+                        let service = new_service();
+                        async move { Ok::<_, hyper::Error>(service) }
+                    });
 
-            local_addr_sender.send(local_addr).unwrap();
+                    let server = Server::bind(&addr).serve(make_service);
+                    local_addr_sender.send(server.local_addr()).unwrap();
 
-            if let Err(e) = server.await {
+                    server.await
+                }
+                Some(tls_config) => {
+                    let make_service = make_service_fn(move |_ctx| {
+                        let service = new_service();
+                        async move { Ok::<_, hyper::Error>(service) }
+                    });
+
+                    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+                    local_addr_sender
+                        .send(listener.local_addr().unwrap())
+                        .unwrap();
+
+                    // Connections failing the TLS handshake are skipped
+                    let acceptor = tokio_rustls::TlsAcceptor::from(tls_config);
+                    let incoming = futures::stream::unfold(
+                        (listener, acceptor),
+                        |(listener, acceptor)| async move {
+                            loop {
+                                let stream = match listener.accept().await {
+                                    Ok((stream, _)) => stream,
+                                    Err(err) => return Some((Err(err), (listener, acceptor))),
+                                };
+
+                                if let Ok(stream) = acceptor.accept(stream).await {
+                                    return Some((Ok(stream), (listener, acceptor)));
+                                }
+                            }
+                        },
+                    );
+
+                    Server::builder(hyper::server::accept::from_stream(incoming))
+                        .serve(make_service)
+                        .await
+                }
+            };
+
+            if let Err(e) = res {
                 let _ = sender.send(Message::ServerError(format!("{:?}", e)));
             }
         });
 
         let local_addr = futures::executor::block_on(local_addr_receiver).unwrap();
-        src.set_property("location", format!("http://{}/", local_addr));
+        src.set_property("location", format!("{}://{}/", scheme, local_addr));
 
         // Let the test setup anything needed on the HTTP source now
         setup_func(&src);
@@ -155,6 +216,7 @@ impl Harness {
             pad,
             receiver: Some(receiver),
             rt: Some(rt),
+            element_messages: Vec::new(),
         }
     }
 
@@ -181,6 +243,10 @@ impl Harness {
                         MessageView::Error(err) => {
                             return err.error();
                         }
+                        MessageView::Element(_) => {
+                            self.element_messages
+                                .push(msg.structure().unwrap().to_owned());
+                        }
                         _ => (),
                     }
                 }
@@ -305,6 +371,10 @@ impl Harness {
                                 err.debug().unwrap_or_else(|| String::from("None"))
                             );
                         }
+                        MessageView::Element(_) => {
+                            self.element_messages
+                                .push(msg.structure().unwrap().to_owned());
+                        }
                         _ => (),
                     }
                 }
@@ -670,6 +740,104 @@ fn test_iradio_mode() {
     }
 }
 
+#[test]
+fn test_icy_demux() {
+    init();
+
+    // Set up a harness that returns an ICY stream with a metadata interval of 16 bytes and check
+    // if the metadata is stripped and sent as tags at the right position
+    let mut h = Harness::new(
+        |req| {
+            use hyper::{Body, Response};
+
+            let headers = req.headers();
+            assert_eq!(headers.get("icy-metadata").unwrap(), "1");
+
+            let mut meta =
+                b"StreamTitle='Artist - Title';StreamUrl='http://www.example.com/';".to_vec();
+            meta.resize(80, 0);
+
+            let mut body = Vec::new();
+            body.extend_from_slice(&[1u8; 16]);
+            body.push(5);
+            body.extend_from_slice(&meta);
+            body.extend_from_slice(&[2u8; 16]);
+            body.push(0);
+            body.extend_from_slice(&[3u8; 8]);
+
+            Response::builder()
+                .header("icy-metaint", "16")
+                .header("Content-Type", "audio/mpeg")
+                .body(Body::from(body))
+                .unwrap()
+        },
+        |src| {
+            src.set_property("icy-demux", true);
+        },
+    );
+
+    // Set the HTTP source to Playing so that everything can start
+    h.run(|src| {
+        src.set_state(gst::State::Playing).unwrap();
+    });
+
+    // Collect the audio data and remember at which offset the title tags arrived
+    let mut data = Vec::new();
+    let mut title_offset = None;
+    loop {
+        match h.receiver.as_mut().unwrap().recv().unwrap() {
+            Message::ServerError(err) => {
+                panic!("Got server error: {}", err);
+            }
+            Message::Event(ev) => match ev.view() {
+                gst::EventView::Eos(_) => break,
+                gst::EventView::Tag(tag) => {
+                    let tags = tag.tag();
+                    if let Some(title) = tags.get::<gst::tags::Title>() {
+                        assert_eq!(title.get(), "Artist - Title");
+                        assert_eq!(
+                            tags.get::<gst::tags::Location>().unwrap().get(),
+                            "http://www.example.com/"
+                        );
+                        title_offset = Some(data.len());
+                    }
+                }
+                _ => (),
+            },
+            Message::Message(msg) => match msg.view() {
+                gst::MessageView::Error(err) => {
+                    panic!(
+                        "Got error: {} ({})",
+                        err.error(),
+                        err.debug().unwrap_or_else(|| String::from("None"))
+                    );
+                }
+                _ => (),
+            },
+            Message::Buffer(buffer) => {
+                assert_eq!(buffer.offset(), data.len() as u64);
+                let map = buffer.map_readable().unwrap();
+                data.extend_from_slice(&map);
+            }
+        }
+    }
+
+    let mut expected_output = vec![1u8; 16];
+    expected_output.extend_from_slice(&[2u8; 16]);
+    expected_output.extend_from_slice(&[3u8; 8]);
+    assert_eq!(data, expected_output);
+    assert_eq!(title_offset, Some(16));
+
+    let srcpad = h.src.static_pad("src").unwrap();
+    let caps = srcpad.current_caps().unwrap();
+    assert_eq!(
+        caps,
+        gst::Caps::builder("audio/mpeg")
+            .field("mpegversion", 1i32)
+            .build()
+    );
+}
+
 #[test]
 fn test_audio_l16() {
     use std::io::{Cursor, Read};
@@ -796,6 +964,159 @@ fn test_authorization() {
     assert_eq!(cursor.position(), 11);
 }
 
+#[test]
+fn test_digest_authorization() {
+    use md5::{Digest, Md5};
+    use std::collections::HashMap;
+    use std::io::{Cursor, Read};
+    init();
+
+    let md5 = |data: String| {
+        Md5::digest(data.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()
+    };
+
+    // Set up a harness that returns "Hello World" for any HTTP request
+    // but requires digest authentication first
+    let mut h = Harness::new(
+        move |req| {
+            use hyper::{Body, Response};
+            use reqwest::StatusCode;
+
+            // The first request carries basic authorization
+            let digest = req
+                .headers()
+                .get("authorization")
+                .and_then(|authorization| authorization.to_str().ok())
+                .and_then(|authorization| authorization.strip_prefix("Digest "));
+
+            if let Some(digest) = digest {
+                let params = digest
+                    .split(", ")
+                    .map(|param| {
+                        let (name, value) = param.split_once('=').unwrap();
+                        (name, value.trim_matches('"'))
+                    })
+                    .collect::<HashMap<_, _>>();
+
+                assert_eq!(params["username"], "user");
+                assert_eq!(params["realm"], "realm");
+                assert_eq!(params["nonce"], "1234");
+                assert_eq!(params["opaque"], "5678");
+                assert_eq!(params["uri"], "/");
+                assert_eq!(params["algorithm"], "MD5");
+                assert_eq!(params["qop"], "auth");
+
+                let ha1 = md5("user:realm:password".to_string());
+                let ha2 = md5("GET:/".to_string());
+                let response = md5(format!(
+                    "{}:1234:{}:{}:auth:{}",
+                    ha1, params["nc"], params["cnonce"], ha2
+                ));
+                assert_eq!(params["response"], response);
+
+                Response::new(Body::from("Hello World"))
+            } else {
+                Response::builder()
+                    .status(StatusCode::UNAUTHORIZED.as_u16())
+                    .header(
+                        "WWW-Authenticate",
+                        "Digest realm=\"realm\", qop=\"auth,auth-int\", nonce=\"1234\", \
+                         opaque=\"5678\", algorithm=MD5",
+                    )
+                    .body(Body::empty())
+                    .unwrap()
+            }
+        },
+        |src| {
+            src.set_property("user-id", "user");
+            src.set_property("user-pw", "password");
+        },
+    );
+
+    // Set the HTTP source to Playing so that everything can start
+    h.run(|src| {
+        src.set_state(gst::State::Playing).unwrap();
+    });
+
+    // And now check if the data we receive is exactly what we expect it to be
+    let expected_output = "Hello World";
+    let mut cursor = Cursor::new(expected_output);
+
+    while let Some(buffer) = h.wait_buffer_or_eos() {
+        let map = buffer.map_readable().unwrap();
+        let mut read_buf = vec![0; map.size()];
+        assert_eq!(cursor.read(&mut read_buf).unwrap(), map.size());
+        assert_eq!(&*map, &*read_buf);
+    }
+
+    // Check if everything was read
+    assert_eq!(cursor.position(), 11);
+}
+
+#[test]
+fn test_bearer_token_refresh() {
+    use std::io::{Cursor, Read};
+    init();
+
+    // Set up a harness that returns "Hello World" for any HTTP request with the right bearer
+    // token and rejects the initial, expired one
+    let mut h = Harness::new(
+        |req| {
+            use hyper::{Body, Response};
+            use reqwest::StatusCode;
+
+            let headers = req.headers();
+            match headers.get("authorization") {
+                Some(authorization) if authorization == "Bearer new-token" => {
+                    Response::new(Body::from("Hello World"))
+                }
+                Some(authorization) => {
+                    assert_eq!(authorization, "Bearer old-token");
+                    Response::builder()
+                        .status(StatusCode::UNAUTHORIZED.as_u16())
+                        .header("WWW-Authenticate", "Bearer error=\"invalid_token\"")
+                        .body(Body::empty())
+                        .unwrap()
+                }
+                None => panic!("Received no Authorization header"),
+            }
+        },
+        |src| {
+            src.set_property("bearer-token", "old-token");
+            src.connect("request-token", false, |args| {
+                assert!(args[1].get::<String>().unwrap().starts_with("http://"));
+                Some("new-token".to_value())
+            });
+        },
+    );
+
+    // Set the HTTP source to Playing so that everything can start
+    h.run(|src| {
+        src.set_state(gst::State::Playing).unwrap();
+    });
+
+    // And now check if the data we receive is exactly what we expect it to be
+    let expected_output = "Hello World";
+    let mut cursor = Cursor::new(expected_output);
+
+    while let Some(buffer) = h.wait_buffer_or_eos() {
+        let map = buffer.map_readable().unwrap();
+        let mut read_buf = vec![0; map.size()];
+        assert_eq!(cursor.read(&mut read_buf).unwrap(), map.size());
+        assert_eq!(&*map, &*read_buf);
+    }
+
+    // Check if everything was read
+    assert_eq!(cursor.position(), 11);
+    assert_eq!(
+        h.src.property::<Option<String>>("bearer-token").as_deref(),
+        Some("new-token")
+    );
+}
+
 #[test]
 fn test_404_error() {
     use reqwest::StatusCode;
@@ -1119,6 +1440,153 @@ fn test_seek_with_stop_position() {
     }
 }
 
+#[test]
+fn test_reconnect_after_connection_loss() {
+    use std::io::{Cursor, Read};
+    init();
+
+    // Harness that aborts the connection after half of the data and checks if the source
+    // resumes from the current position with a Range request
+    let mut h = Harness::new(
+        |req| {
+            use hyper::{Body, Response};
+
+            let mut data_full = vec![0; 8192];
+            for (i, d) in data_full.iter_mut().enumerate() {
+                *d = (i % 256) as u8;
+            }
+
+            let headers = req.headers();
+            if let Some(range) = headers.get("Range") {
+                if range == "bytes=4096-" {
+                    Response::builder()
+                        .header("content-length", 8192 - 4096)
+                        .header("accept-ranges", "bytes")
+                        .header("content-range", "bytes 4096-8191/8192")
+                        .body(Body::from(data_full.split_off(4096)))
+                        .unwrap()
+                } else {
+                    panic!("Received an unexpected Range header")
+                }
+            } else {
+                data_full.truncate(4096);
+                let chunks: Vec<Result<Vec<u8>, std::io::Error>> = vec![
+                    Ok(data_full),
+                    Err(std::io::Error::new(
+                        std::io::ErrorKind::ConnectionReset,
+                        "connection lost",
+                    )),
+                ];
+
+                Response::builder()
+                    .header("content-length", 8192)
+                    .header("accept-ranges", "bytes")
+                    .body(Body::wrap_stream(futures::stream::iter(chunks)))
+                    .unwrap()
+            }
+        },
+        |src| {
+            src.set_property("retries", 1i32);
+            src.set_property("retry-backoff", 0u32);
+        },
+    );
+
+    h.run(|src| {
+        src.set_state(gst::State::Playing).unwrap();
+    });
+
+    let mut expected_output = vec![0; 8192];
+    for (i, d) in expected_output.iter_mut().enumerate() {
+        *d = (i % 256) as u8;
+    }
+    let mut cursor = Cursor::new(expected_output);
+
+    while let Some(buffer) = h.wait_buffer_or_eos() {
+        assert_eq!(buffer.offset(), cursor.position());
+        // The resource was resumed at the exact position so nothing was skipped
+        assert!(!buffer.flags().contains(gst::BufferFlags::DISCONT));
+
+        let map = buffer.map_readable().unwrap();
+        let mut read_buf = vec![0; map.size()];
+
+        assert_eq!(cursor.read(&mut read_buf).unwrap(), map.size());
+        assert_eq!(&*map, &*read_buf);
+    }
+
+    // Check if everything was read
+    assert_eq!(cursor.position(), 8192);
+
+    // The reconnection was reported
+    let retries = h
+        .element_messages
+        .iter()
+        .filter(|s| s.name() == "reqwesthttpsrc-retry")
+        .collect::<Vec<_>>();
+    assert_eq!(retries.len(), 1);
+    assert_eq!(retries[0].get::<u32>("attempt").unwrap(), 1);
+    assert_eq!(retries[0].get::<i32>("retries").unwrap(), 1);
+    assert_eq!(retries[0].get::<u64>("position").unwrap(), 4096);
+}
+
+#[test]
+fn test_reconnect_gives_up_after_retries() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    init();
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    let requests_clone = requests.clone();
+
+    // Harness that aborts the connection of the first request before any data
+    // and makes all later requests fail
+    let mut h = Harness::new(
+        move |_req| {
+            use hyper::{Body, Response};
+
+            if requests_clone.fetch_add(1, Ordering::SeqCst) == 0 {
+                let chunks: Vec<Result<Vec<u8>, std::io::Error>> = vec![Err(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionReset,
+                    "connection lost",
+                ))];
+
+                Response::builder()
+                    .header("content-length", 8192)
+                    .body(Body::wrap_stream(futures::stream::iter(chunks)))
+                    .unwrap()
+            } else {
+                Response::builder()
+                    .status(hyper::StatusCode::SERVICE_UNAVAILABLE)
+                    .body(Body::empty())
+                    .unwrap()
+            }
+        },
+        |src| {
+            src.set_property("retries", 2i32);
+            src.set_property("retry-backoff", 0u32);
+        },
+    );
+
+    h.run(|src| {
+        src.set_state(gst::State::Playing).unwrap();
+    });
+
+    let err_code = h.wait_for_error();
+    assert!(err_code.is::<gst::ResourceError>());
+
+    // The initial request and one request per retry
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+    let attempts = h
+        .element_messages
+        .iter()
+        .filter(|s| s.name() == "reqwesthttpsrc-retry")
+        .map(|s| {
+            assert_eq!(s.get::<i32>("retries").unwrap(), 2);
+            s.get::<u32>("attempt").unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(attempts, vec![1, 2]);
+}
+
 #[test]
 fn test_cookies() {
     init();
@@ -1288,3 +1756,196 @@ fn test_proxy() {
     // Don't leave threads hanging around.
     proxy_server.join().unwrap();
 }
+
+/// A test CA and the certificates it signed, the PEM files are stored in a temporary directory
+struct TestCertificates {
+    dir: std::path::PathBuf,
+    ca: rustls::Certificate,
+    server: rustls::Certificate,
+    server_key: rustls::PrivateKey,
+}
+
+impl TestCertificates {
+    fn new(name: &str) -> Self {
+        use rcgen::{
+            BasicConstraints, Certificate, CertificateParams, DnType, ExtendedKeyUsagePurpose,
+            IsCa, SanType,
+        };
+
+        let dir =
+            std::env::temp_dir().join(format!("reqwesthttpsrc-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut params = CertificateParams::new(vec![]);
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "Test CA");
+        let ca = Certificate::from_params(params).unwrap();
+        std::fs::write(dir.join("ca.pem"), ca.serialize_pem().unwrap()).unwrap();
+
+        let mut params = CertificateParams::new(vec![]);
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "Test server");
+        params.subject_alt_names = vec![SanType::IpAddress([127, 0, 0, 1].into())];
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+        let server = Certificate::from_params(params).unwrap();
+
+        let mut params = CertificateParams::new(vec![]);
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "Test client");
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+        let client = Certificate::from_params(params).unwrap();
+        let client_cert = client.serialize_pem_with_signer(&ca).unwrap();
+        let client_key = client.serialize_private_key_pem();
+        std::fs::write(dir.join("client.pem"), &client_cert).unwrap();
+        std::fs::write(dir.join("client-key.pem"), &client_key).unwrap();
+        std::fs::write(dir.join("client-with-key.pem"), client_cert + &client_key).unwrap();
+
+        TestCertificates {
+            dir,
+            ca: rustls::Certificate(ca.serialize_der().unwrap()),
+            server: rustls::Certificate(server.serialize_der_with_signer(&ca).unwrap()),
+            server_key: rustls::PrivateKey(server.serialize_private_key_der()),
+        }
+    }
+
+    fn path(&self, file: &str) -> String {
+        self.dir.join(file).to_str().unwrap().to_string()
+    }
+
+    /// Configuration of a server using the certificate signed by the CA, requiring clients to
+    /// authenticate with a certificate signed by the CA if `client_auth` is set
+    fn server_config(&self, client_auth: bool) -> Arc<rustls::ServerConfig> {
+        let builder = rustls::ServerConfig::builder().with_safe_defaults();
+
+        let builder = if client_auth {
+            let mut roots = rustls::RootCertStore::empty();
+            roots.add(&self.ca).unwrap();
+            builder
+                .with_client_cert_verifier(rustls::server::AllowAnyAuthenticatedClient::new(roots))
+        } else {
+            builder.with_no_client_auth()
+        };
+
+        Arc::new(
+            builder
+                .with_single_cert(vec![self.server.clone()], self.server_key.clone())
+                .unwrap(),
+        )
+    }
+}
+
+impl Drop for TestCertificates {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Runs an HTTPS harness returning "Hello World" and checks that it is received
+fn check_hello_world_over_tls<G: FnOnce(&gst::Element)>(
+    tls_config: Arc<rustls::ServerConfig>,
+    setup_func: G,
+) {
+    use std::io::{Cursor, Read};
+
+    let mut h = Harness::with_tls(
+        |_req| {
+            use hyper::{Body, Response};
+
+            Response::new(Body::from("Hello World"))
+        },
+        setup_func,
+        Some(tls_config),
+    );
+
+    h.run(|src| {
+        src.set_state(gst::State::Playing).unwrap();
+    });
+
+    let expected_output = "Hello World";
+    let mut cursor = Cursor::new(expected_output);
+
+    while let Some(buffer) = h.wait_buffer_or_eos() {
+        let map = buffer.map_readable().unwrap();
+        let mut read_buf = vec![0; map.size()];
+        assert_eq!(cursor.read(&mut read_buf).unwrap(), map.size());
+        assert_eq!(&*map, &*read_buf);
+    }
+
+    assert_eq!(cursor.position(), 11);
+}
+
+/// Runs an HTTPS harness and checks that the request fails
+fn check_error_over_tls<G: FnOnce(&gst::Element)>(
+    tls_config: Arc<rustls::ServerConfig>,
+    setup_func: G,
+) {
+    let mut h = Harness::with_tls(
+        |_req| unreachable!("Request should not have been received"),
+        setup_func,
+        Some(tls_config),
+    );
+
+    h.run(|src| {
+        let _ = src.set_state(gst::State::Playing);
+    });
+
+    let err = h.wait_for_error();
+    assert_eq!(
+        err.kind::<gst::ResourceError>(),
+        Some(gst::ResourceError::OpenRead)
+    );
+}
+
+#[test]
+fn test_ssl_ca_file() {
+    init();
+
+    let certs = TestCertificates::new("ssl-ca-file");
+
+    check_hello_world_over_tls(certs.server_config(false), |src| {
+        src.set_property("ssl-ca-file", certs.path("ca.pem"));
+    });
+}
+
+#[test]
+fn test_ssl_strict() {
+    init();
+
+    let certs = TestCertificates::new("ssl-strict");
+
+    // The server certificate is not signed by a trusted CA
+    check_error_over_tls(certs.server_config(false), |_src| ());
+
+    // Unless certificates are not verified
+    check_hello_world_over_tls(certs.server_config(false), |src| {
+        src.set_property("ssl-strict", false);
+    });
+}
+
+#[test]
+fn test_tls_client_certificate() {
+    init();
+
+    let certs = TestCertificates::new("tls-client-certificate");
+
+    check_hello_world_over_tls(certs.server_config(true), |src| {
+        src.set_property("ssl-ca-file", certs.path("ca.pem"));
+        src.set_property("tls-certificate-file", certs.path("client.pem"));
+        src.set_property("tls-key-file", certs.path("client-key.pem"));
+    });
+
+    // The key is read from the certificate file by default
+    check_hello_world_over_tls(certs.server_config(true), |src| {
+        src.set_property("ssl-ca-file", certs.path("ca.pem"));
+        src.set_property("tls-certificate-file", certs.path("client-with-key.pem"));
+    });
+
+    // The server requires a client certificate
+    check_error_over_tls(certs.server_config(true), |src| {
+        src.set_property("ssl-ca-file", certs.path("ca.pem"));
+    });
+}
@@ -33,12 +33,13 @@ use std::sync::Mutex;
 use atomic_refcell::AtomicRefCell;
 
 use super::packet::*;
+use super::speaker;
 
 use serde_derive::{Deserialize, Serialize};
 
 use once_cell::sync::Lazy;
 
-use super::AwsTranscriberResultStability;
+use super::{AwsTranscriberResultStability, AwsTranscriberVocabFilterMethod};
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "PascalCase")]
@@ -49,6 +50,8 @@ struct TranscriptItem {
     #[serde(rename = "Type")]
     type_: String,
     stable: bool,
+    #[serde(default)]
+    speaker: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -66,6 +69,8 @@ struct TranscriptResult {
     start_time: f32,
     is_partial: bool,
     result_id: String,
+    #[serde(default)]
+    channel_id: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -105,6 +110,10 @@ static RUNTIME: Lazy<runtime::Runtime> = Lazy::new(|| {
 const DEFAULT_LATENCY: gst::ClockTime = gst::ClockTime::from_seconds(8);
 const DEFAULT_LATENESS: gst::ClockTime = gst::ClockTime::from_seconds(0);
 const DEFAULT_STABILITY: AwsTranscriberResultStability = AwsTranscriberResultStability::Low;
+const DEFAULT_VOCABULARY_FILTER_METHOD: AwsTranscriberVocabFilterMethod =
+    AwsTranscriberVocabFilterMethod::Mask;
+const DEFAULT_SHOW_SPEAKER_LABEL: bool = false;
+const DEFAULT_ENABLE_CHANNEL_IDENTIFICATION: bool = false;
 const GRANULARITY: gst::ClockTime = gst::ClockTime::from_mseconds(100);
 
 #[derive(Debug, Clone)]
@@ -117,6 +126,11 @@ struct Settings {
     results_stability: AwsTranscriberResultStability,
     access_key: Option<String>,
     secret_access_key: Option<String>,
+    show_speaker_label: bool,
+    enable_channel_identification: bool,
+    language_model: Option<String>,
+    vocabulary_filter: Option<String>,
+    vocabulary_filter_method: AwsTranscriberVocabFilterMethod,
 }
 
 impl Default for Settings {
@@ -130,6 +144,11 @@ impl Default for Settings {
             results_stability: DEFAULT_STABILITY,
             access_key: None,
             secret_access_key: None,
+            show_speaker_label: DEFAULT_SHOW_SPEAKER_LABEL,
+            enable_channel_identification: DEFAULT_ENABLE_CHANNEL_IDENTIFICATION,
+            language_model: None,
+            vocabulary_filter: None,
+            vocabulary_filter_method: DEFAULT_VOCABULARY_FILTER_METHOD,
         }
     }
 }
@@ -142,7 +161,9 @@ struct State {
     in_segment: gst::FormattedSegment<gst::ClockTime>,
     out_segment: gst::FormattedSegment<gst::ClockTime>,
     seqnum: gst::Seqnum,
-    buffers: VecDeque<gst::Buffer>,
+    /* Items ready for pushing, each optionally preceded by a speaker change event */
+    buffers: VecDeque<(Option<gst::Event>, gst::Buffer)>,
+    speaker: speaker::Speaker,
     send_eos: bool,
     discont: bool,
     partial_index: usize,
@@ -161,6 +182,7 @@ impl Default for State {
             out_segment: gst::FormattedSegment::new(),
             seqnum: gst::Seqnum::next(),
             buffers: VecDeque::new(),
+            speaker: speaker::Speaker::default(),
             send_eos: false,
             discont: true,
             partial_index: 0,
@@ -228,7 +250,7 @@ impl Transcriber {
 
         let send_eos = state.send_eos && state.buffers.is_empty();
 
-        while let Some(buf) = state.buffers.front() {
+        while let Some((_, buf)) = state.buffers.front() {
             let pts = buf.pts().unwrap();
             gst::trace!(
                 CAT,
@@ -242,7 +264,7 @@ impl Transcriber {
 
             if pts + latency.saturating_sub(3 * GRANULARITY) < now - start_time {
                 /* Safe unwrap, we know we have an item */
-                let mut buf = state.buffers.pop_front().unwrap();
+                let (event, mut buf) = state.buffers.pop_front().unwrap();
 
                 {
                     let buf_mut = buf.get_mut().unwrap();
@@ -250,7 +272,7 @@ impl Transcriber {
                     buf_mut.set_pts(start_time + pts);
                 }
 
-                items.push(buf);
+                items.push((event, buf));
             } else {
                 break;
             }
@@ -269,7 +291,7 @@ impl Transcriber {
                 .push_event(gst::event::Eos::builder().seqnum(seqnum).build());
         }
 
-        for mut buf in items.drain(..) {
+        for (event, mut buf) in items.drain(..) {
             let mut pts = buf.pts().unwrap();
             let mut duration = buf.duration().unwrap();
 
@@ -310,6 +332,13 @@ impl Transcriber {
 
             last_position = pts + duration;
 
+            if let Some(event) = event {
+                gst::debug!(CAT, obj: element, "Pushing {:?}", event);
+                if !self.srcpad.push_event(event) {
+                    return false;
+                }
+            }
+
             gst::debug!(CAT, "Pushing buffer: {} -> {}", pts, pts + duration);
 
             if self.srcpad.push(buf).is_err() {
@@ -363,6 +392,7 @@ impl Transcriber {
         element: &super::Transcriber,
         state: &mut State,
         alternative: &TranscriptAlternative,
+        channel: Option<&String>,
         partial: bool,
     ) {
         let lateness = self.settings.lock().unwrap().lateness;
@@ -405,6 +435,29 @@ impl Transcriber {
             );
             let mut buf = gst::Buffer::from_mut_slice(item.content.clone().into_bytes());
 
+            /* Downstream elements such as tttocea608 pick this up to mark
+             * the speaker change in the captions */
+            let speaker_event = if (item.speaker.is_some() && item.speaker != state.speaker.speaker)
+                || (channel.is_some() && channel != state.speaker.channel.as_ref())
+            {
+                state.speaker = speaker::Speaker {
+                    speaker: item.speaker.clone(),
+                    channel: channel.cloned(),
+                };
+
+                gst::debug!(CAT, obj: element, "Speaker changed to {:?}", state.speaker);
+
+                Some(
+                    gst::event::CustomDownstream::builder(speaker::speaker_structure(
+                        &state.speaker,
+                    ))
+                    .seqnum(state.seqnum)
+                    .build(),
+                )
+            } else {
+                None
+            };
+
             {
                 let buf = buf.get_mut().unwrap();
 
@@ -415,11 +468,18 @@ impl Transcriber {
 
                 buf.set_pts(start_time);
                 buf.set_duration(end_time - start_time);
+
+                #[cfg(feature = "v1_20")]
+                if state.speaker != speaker::Speaker::default() {
+                    if let Err(err) = speaker::add_speaker_meta(buf, &state.speaker) {
+                        gst::warning!(CAT, obj: element, "Failed to add speaker meta: {}", err);
+                    }
+                }
             }
 
             state.partial_index += 1;
 
-            state.buffers.push_back(buf);
+            state.buffers.push_back((speaker_event, buf));
         }
 
         if !partial {
@@ -538,7 +598,13 @@ impl Transcriber {
                         if let Some(alternative) = result.alternatives.get(0) {
                             let mut state = self.state.lock().unwrap();
 
-                            self.enqueue(element, &mut state, alternative, result.is_partial)
+                            self.enqueue(
+                                element,
+                                &mut state,
+                                alternative,
+                                result.channel_id.as_ref(),
+                                result.is_partial,
+                            )
                         }
                     }
 
@@ -673,6 +739,48 @@ impl Transcriber {
         }
     }
 
+    fn sink_query(
+        &self,
+        pad: &gst::Pad,
+        element: &super::Transcriber,
+        query: &mut gst::QueryRef,
+    ) -> bool {
+        use gst::QueryViewMut;
+
+        gst::log!(CAT, obj: pad, "Handling query {:?}", query);
+
+        match query.view_mut() {
+            QueryViewMut::Caps(q) => {
+                /* Stereo input is only transcribed with channel identification,
+                 * otherwise upstream has to downmix to mono */
+                let channels = if self.settings.lock().unwrap().enable_channel_identification {
+                    2i32
+                } else {
+                    1i32
+                };
+
+                let mut caps = pad.pad_template_caps();
+                {
+                    let caps = caps.make_mut();
+                    let s = caps.structure_mut(0).unwrap();
+                    s.set("channels", channels);
+                }
+
+                let caps = match q.filter() {
+                    Some(filter) => {
+                        filter.intersect_with_mode(&caps, gst::CapsIntersectMode::First)
+                    }
+                    None => caps,
+                };
+
+                q.set_result(&caps);
+
+                true
+            }
+            _ => pad.query_default(Some(element), query),
+        }
+    }
+
     fn sink_event(&self, pad: &gst::Pad, element: &super::Transcriber, event: gst::Event) -> bool {
         use gst::EventView;
 
@@ -846,6 +954,7 @@ impl Transcriber {
         let in_caps = self.sinkpad.current_caps().unwrap();
         let s = in_caps.structure(0).unwrap();
         let sample_rate = s.get::<i32>("rate").unwrap();
+        let channels = s.get::<i32>("channels").unwrap();
 
         let settings = self.settings.lock().unwrap();
 
@@ -861,6 +970,22 @@ impl Transcriber {
             ));
         }
 
+        if settings.enable_channel_identification != (channels == 2) {
+            gst::error!(
+                CAT,
+                obj: element,
+                "enable-channel-identification must be set if and only if the input is stereo, got {} channels",
+                channels
+            );
+            return Err(error_msg!(
+                gst::LibraryError::Settings,
+                [
+                    "enable-channel-identification must be set if and only if the input is stereo, got {} channels",
+                    channels
+                ]
+            ));
+        }
+
         gst::info!(CAT, obj: element, "Connecting ..");
 
         let creds = match (
@@ -923,6 +1048,31 @@ impl Transcriber {
             signed.add_param("vocabulary-name", vocabulary);
         }
 
+        if let Some(ref language_model) = settings.language_model {
+            signed.add_param("language-model-name", language_model);
+        }
+
+        if let Some(ref vocabulary_filter) = settings.vocabulary_filter {
+            signed.add_param("vocabulary-filter-name", vocabulary_filter);
+            signed.add_param(
+                "vocabulary-filter-method",
+                match settings.vocabulary_filter_method {
+                    AwsTranscriberVocabFilterMethod::Remove => "remove",
+                    AwsTranscriberVocabFilterMethod::Mask => "mask",
+                    AwsTranscriberVocabFilterMethod::Tag => "tag",
+                },
+            );
+        }
+
+        if settings.show_speaker_label {
+            signed.add_param("show-speaker-label", "true");
+        }
+
+        if settings.enable_channel_identification {
+            signed.add_param("enable-channel-identification", "true");
+            signed.add_param("number-of-channels", &channels.to_string());
+        }
+
         if let Some(ref session_id) = settings.session_id {
             gst::debug!(CAT, obj: element, "Using session ID: {}", session_id);
             signed.add_param("session-id", session_id);
@@ -1055,6 +1205,13 @@ impl ObjectSubclass for Transcriber {
                     |transcriber, element| transcriber.sink_event(pad, element, event),
                 )
             })
+            .query_function(|pad, parent, query| {
+                Transcriber::catch_panic_pad_function(
+                    parent,
+                    || false,
+                    |transcriber, element| transcriber.sink_query(pad, element, query),
+                )
+            })
             .build();
 
         let templ = klass.pad_template("src").unwrap();
@@ -1143,6 +1300,47 @@ impl ObjectImpl for Transcriber {
                     DEFAULT_STABILITY as i32,
                     glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
                 ),
+                glib::ParamSpecBoolean::new(
+                    "show-speaker-label",
+                    "Show Speaker Label",
+                    "Identify the speakers in the stream and signal speaker changes downstream",
+                    DEFAULT_SHOW_SPEAKER_LABEL,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "enable-channel-identification",
+                    "Enable Channel Identification",
+                    "Transcribe the two channels of a stereo stream separately and signal \
+                        channel changes downstream, mono input is expected when disabled",
+                    DEFAULT_ENABLE_CHANNEL_IDENTIFICATION,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecString::new(
+                    "language-model-name",
+                    "Language Model Name",
+                    "The name of a custom language model, see \
+                        <https://docs.aws.amazon.com/transcribe/latest/dg/custom-language-models.html> \
+                        for more information",
+                    None,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecString::new(
+                    "vocabulary-filter-name",
+                    "Vocabulary Filter Name",
+                    "The name of a custom vocabulary filter, see \
+                        <https://docs.aws.amazon.com/transcribe/latest/dg/vocabulary-filtering.html> \
+                        for more information",
+                    None,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecEnum::new(
+                    "vocabulary-filter-method",
+                    "Vocabulary Filter Method",
+                    "How words matching the vocabulary filter are handled",
+                    AwsTranscriberVocabFilterMethod::static_type(),
+                    DEFAULT_VOCABULARY_FILTER_METHOD as i32,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
                 glib::ParamSpecString::new(
                     "access-key",
                     "Access Key",
@@ -1209,6 +1407,29 @@ impl ObjectImpl for Transcriber {
                     .get::<AwsTranscriberResultStability>()
                     .expect("type checked upstream");
             }
+            "show-speaker-label" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.show_speaker_label = value.get().expect("type checked upstream");
+            }
+            "enable-channel-identification" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.enable_channel_identification =
+                    value.get().expect("type checked upstream");
+            }
+            "language-model-name" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.language_model = value.get().expect("type checked upstream");
+            }
+            "vocabulary-filter-name" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.vocabulary_filter = value.get().expect("type checked upstream");
+            }
+            "vocabulary-filter-method" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.vocabulary_filter_method = value
+                    .get::<AwsTranscriberVocabFilterMethod>()
+                    .expect("type checked upstream");
+            }
             "access-key" => {
                 let mut settings = self.settings.lock().unwrap();
                 settings.access_key = value.get().expect("type checked upstream");
@@ -1247,6 +1468,26 @@ impl ObjectImpl for Transcriber {
                 let settings = self.settings.lock().unwrap();
                 settings.results_stability.to_value()
             }
+            "show-speaker-label" => {
+                let settings = self.settings.lock().unwrap();
+                settings.show_speaker_label.to_value()
+            }
+            "enable-channel-identification" => {
+                let settings = self.settings.lock().unwrap();
+                settings.enable_channel_identification.to_value()
+            }
+            "language-model-name" => {
+                let settings = self.settings.lock().unwrap();
+                settings.language_model.to_value()
+            }
+            "vocabulary-filter-name" => {
+                let settings = self.settings.lock().unwrap();
+                settings.vocabulary_filter.to_value()
+            }
+            "vocabulary-filter-method" => {
+                let settings = self.settings.lock().unwrap();
+                settings.vocabulary_filter_method.to_value()
+            }
             "access-key" => {
                 let settings = self.settings.lock().unwrap();
                 settings.access_key.to_value()
@@ -1292,7 +1533,7 @@ impl ElementImpl for Transcriber {
             let sink_caps = gst::Caps::builder("audio/x-raw")
                 .field("format", "S16LE")
                 .field("rate", gst::IntRange::new(8000i32, 48000))
-                .field("channels", 1)
+                .field("channels", gst::IntRange::new(1i32, 2))
                 .build();
             let sink_pad_template = gst::PadTemplate::new(
                 "sink",
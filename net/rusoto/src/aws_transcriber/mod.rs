@@ -11,6 +11,7 @@ use gst::prelude::*;
 
 mod imp;
 mod packet;
+mod speaker;
 
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, glib::Enum)]
 #[repr(u32)]
@@ -31,11 +32,27 @@ pub enum AwsTranscriberResultStability {
     Low = 2,
 }
 
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, glib::Enum)]
+#[repr(u32)]
+#[enum_type(name = "GstAwsTranscriberVocabFilterMethod")]
+#[non_exhaustive]
+pub enum AwsTranscriberVocabFilterMethod {
+    #[enum_value(name = "Remove: delete filtered words", nick = "remove")]
+    Remove = 0,
+    #[enum_value(name = "Mask: replace filtered words with ***", nick = "mask")]
+    Mask = 1,
+    #[enum_value(name = "Tag: keep filtered words but tag them", nick = "tag")]
+    Tag = 2,
+}
+
 glib::wrapper! {
     pub struct Transcriber(ObjectSubclass<imp::Transcriber>) @extends gst::Element, gst::Object;
 }
 
 pub fn register(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
+    #[cfg(feature = "v1_20")]
+    speaker::register_speaker_meta();
+
     gst::Element::register(
         Some(plugin),
         "awstranscriber",
@@ -0,0 +1,112 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Speaker and channel information of transcriptions
+//!
+//! The transcriber announces a change of speaker or channel with a custom
+//! downstream event holding the structure built by [`speaker_structure`],
+//! before the first buffer spoken by the new speaker. Elements consuming the
+//! transcription, such as `tttocea608`, recognize it by its name.
+//!
+//! With the `v1_20` feature, each transcribed buffer additionally carries
+//! the current speaker and channel in a custom meta, see [`add_speaker_meta`].
+
+/// Name of the custom downstream event announcing a change of speaker or channel
+pub const SPEAKER_EVENT_NAME: &str = "transcription/speaker";
+
+/// Name of the custom meta carrying the speaker and channel of a buffer
+#[cfg(feature = "v1_20")]
+pub const SPEAKER_META_NAME: &str = "GstTranscriptionSpeakerMeta";
+
+/// Speaker and channel of a part of a transcription, as identified by the
+/// transcription service, e.g. `spk_0` and `ch_1`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Speaker {
+    pub speaker: Option<String>,
+    pub channel: Option<String>,
+}
+
+impl Speaker {
+    fn fill_structure(&self, s: &mut gst::StructureRef) {
+        s.set("speaker", &self.speaker);
+        s.set("channel", &self.channel);
+    }
+}
+
+/// Builds the structure of the custom downstream event announcing that
+/// `speaker` starts speaking
+pub fn speaker_structure(speaker: &Speaker) -> gst::Structure {
+    let mut s = gst::Structure::new_empty(SPEAKER_EVENT_NAME);
+    speaker.fill_structure(&mut s);
+
+    s
+}
+
+/// Registers the speaker meta, needs to be called before adding it to buffers
+#[cfg(feature = "v1_20")]
+pub fn register_speaker_meta() {
+    if !gst::meta::CustomMeta::is_registered(SPEAKER_META_NAME) {
+        gst::meta::CustomMeta::register(SPEAKER_META_NAME, &[]);
+    }
+}
+
+/// Attaches `speaker` to `buffer`
+#[cfg(feature = "v1_20")]
+pub fn add_speaker_meta(
+    buffer: &mut gst::BufferRef,
+    speaker: &Speaker,
+) -> Result<(), gst::glib::BoolError> {
+    let mut meta = gst::meta::CustomMeta::add(buffer, SPEAKER_META_NAME)?;
+    speaker.fill_structure(meta.mut_structure());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speaker_event() {
+        gst::init().unwrap();
+
+        let speaker = Speaker {
+            speaker: Some("spk_1".to_string()),
+            channel: None,
+        };
+
+        let event = gst::event::CustomDownstream::new(speaker_structure(&speaker));
+        assert_eq!(event.type_(), gst::EventType::CustomDownstream);
+
+        let s = event.structure().unwrap();
+        assert_eq!(s.name(), "transcription/speaker");
+        assert_eq!(s.get::<Option<&str>>("speaker").unwrap(), Some("spk_1"));
+        assert_eq!(s.get::<Option<&str>>("channel").unwrap(), None);
+    }
+
+    #[cfg(feature = "v1_20")]
+    #[test]
+    fn test_speaker_meta() {
+        gst::init().unwrap();
+        register_speaker_meta();
+
+        let speaker = Speaker {
+            speaker: Some("spk_0".to_string()),
+            channel: Some("ch_1".to_string()),
+        };
+
+        let mut buffer = gst::Buffer::new();
+        assert!(gst::meta::CustomMeta::from_buffer(&buffer, SPEAKER_META_NAME).is_err());
+
+        add_speaker_meta(buffer.get_mut().unwrap(), &speaker).unwrap();
+        let meta = gst::meta::CustomMeta::from_buffer(&buffer, SPEAKER_META_NAME).unwrap();
+        let s = meta.structure();
+        assert_eq!(s.get::<Option<&str>>("speaker").unwrap(), Some("spk_0"));
+        assert_eq!(s.get::<Option<&str>>("channel").unwrap(), Some("ch_1"));
+    }
+}
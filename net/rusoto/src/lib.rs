@@ -16,7 +16,7 @@ mod s3src;
 mod s3url;
 mod s3utils;
 
-pub use aws_transcriber::AwsTranscriberResultStability;
+pub use aws_transcriber::{AwsTranscriberResultStability, AwsTranscriberVocabFilterMethod};
 
 fn plugin_init(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
     s3sink::register(plugin)?;
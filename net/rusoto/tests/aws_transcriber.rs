@@ -0,0 +1,110 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use gst::prelude::*;
+
+use gstrusoto::AwsTranscriberVocabFilterMethod;
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstrusoto::plugin_register_static().expect("rusoto test");
+    });
+}
+
+fn sink_channels(transcriber: &gst::Element) -> Vec<i32> {
+    let caps = transcriber.static_pad("sink").unwrap().query_caps(None);
+
+    caps.iter()
+        .map(|s| s.get::<i32>("channels").unwrap())
+        .collect()
+}
+
+#[test]
+fn test_settings() {
+    init();
+
+    let transcriber = gst::ElementFactory::make("awstranscriber", None).unwrap();
+
+    assert!(!transcriber.property::<bool>("show-speaker-label"));
+    assert!(!transcriber.property::<bool>("enable-channel-identification"));
+    assert_eq!(
+        transcriber.property::<Option<String>>("language-model-name"),
+        None
+    );
+    assert_eq!(
+        transcriber.property::<Option<String>>("vocabulary-filter-name"),
+        None
+    );
+    assert_eq!(
+        transcriber.property::<AwsTranscriberVocabFilterMethod>("vocabulary-filter-method"),
+        AwsTranscriberVocabFilterMethod::Mask
+    );
+
+    transcriber.set_property("show-speaker-label", true);
+    transcriber.set_property("enable-channel-identification", true);
+    transcriber.set_property("language-model-name", "my-model");
+    transcriber.set_property("vocabulary-filter-name", "my-filter");
+    transcriber.set_property(
+        "vocabulary-filter-method",
+        AwsTranscriberVocabFilterMethod::Tag,
+    );
+
+    assert!(transcriber.property::<bool>("show-speaker-label"));
+    assert!(transcriber.property::<bool>("enable-channel-identification"));
+    assert_eq!(
+        transcriber
+            .property::<Option<String>>("language-model-name")
+            .as_deref(),
+        Some("my-model")
+    );
+    assert_eq!(
+        transcriber
+            .property::<Option<String>>("vocabulary-filter-name")
+            .as_deref(),
+        Some("my-filter")
+    );
+    assert_eq!(
+        transcriber.property::<AwsTranscriberVocabFilterMethod>("vocabulary-filter-method"),
+        AwsTranscriberVocabFilterMethod::Tag
+    );
+}
+
+/* Stereo is only accepted with channel identification, otherwise
+ * upstream has to downmix */
+#[test]
+fn test_sink_caps_channels() {
+    init();
+
+    let transcriber = gst::ElementFactory::make("awstranscriber", None).unwrap();
+    let sinkpad = transcriber.static_pad("sink").unwrap();
+
+    let mono = gst::Caps::builder("audio/x-raw")
+        .field("format", "S16LE")
+        .field("rate", 16_000i32)
+        .field("channels", 1i32)
+        .build();
+    let stereo = gst::Caps::builder("audio/x-raw")
+        .field("format", "S16LE")
+        .field("rate", 16_000i32)
+        .field("channels", 2i32)
+        .build();
+
+    assert_eq!(sink_channels(&transcriber), vec![1]);
+    assert!(sinkpad.query_accept_caps(&mono));
+    assert!(!sinkpad.query_accept_caps(&stereo));
+
+    transcriber.set_property("enable-channel-identification", true);
+
+    assert_eq!(sink_channels(&transcriber), vec![2]);
+    assert!(!sinkpad.query_accept_caps(&mono));
+    assert!(sinkpad.query_accept_caps(&stereo));
+}
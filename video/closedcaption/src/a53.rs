@@ -0,0 +1,368 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Reading and writing of ATSC A/53 closed caption user data, carried in
+//! `user_data_registered_itu_t_t35` SEI messages of H.264 and H.265 access
+//! units in Annex B byte-stream format.
+
+/// SEI payload type of `user_data_registered_itu_t_t35`
+const ITU_T_T35_PAYLOAD_TYPE: u32 = 4;
+
+/// ITU-T T.35 country code (USA), provider code (ATSC), user identifier
+/// ("GA94") and user_data_type_code (cc_data)
+const A53_HEADER: [u8; 8] = [0xb5, 0x00, 0x31, b'G', b'A', b'9', b'4', 0x03];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    H264,
+    H265,
+}
+
+impl Codec {
+    /// Only byte-stream format with access unit alignment is supported
+    pub fn from_caps(caps: &gst::CapsRef) -> Option<Codec> {
+        let s = caps.structure(0)?;
+
+        let codec = match s.name() {
+            "video/x-h264" => Codec::H264,
+            "video/x-h265" => Codec::H265,
+            _ => return None,
+        };
+
+        if s.get::<&str>("stream-format").ok() != Some("byte-stream")
+            || s.get::<&str>("alignment").ok() != Some("au")
+        {
+            return None;
+        }
+
+        Some(codec)
+    }
+
+    fn header_len(self) -> usize {
+        match self {
+            Codec::H264 => 1,
+            Codec::H265 => 2,
+        }
+    }
+
+    fn nal_type(self, header: u8) -> u8 {
+        match self {
+            Codec::H264 => header & 0x1f,
+            Codec::H265 => (header >> 1) & 0x3f,
+        }
+    }
+
+    fn is_sei(self, nal_type: u8) -> bool {
+        match self {
+            Codec::H264 => nal_type == 6,
+            /* prefix and suffix SEI */
+            Codec::H265 => nal_type == 39 || nal_type == 40,
+        }
+    }
+
+    fn is_vcl(self, nal_type: u8) -> bool {
+        match self {
+            Codec::H264 => (1..=5).contains(&nal_type),
+            Codec::H265 => nal_type < 32,
+        }
+    }
+
+    fn sei_header(self) -> &'static [u8] {
+        match self {
+            Codec::H264 => &[0x06],
+            /* prefix SEI, layer 0, temporal id 0 */
+            Codec::H265 => &[0x4e, 0x01],
+        }
+    }
+}
+
+/// Splits an Annex B byte stream into NAL units, together with the offset
+/// of their start code
+fn nal_units(data: &[u8]) -> Vec<(usize, &[u8])> {
+    let mut starts = Vec::new();
+
+    let mut i = 0;
+    while i + 3 <= data.len() {
+        if data[i] == 0 && data[i + 1] == 0 && data[i + 2] == 1 {
+            /* 4 byte start codes */
+            let start_code = if i > 0 && data[i - 1] == 0 { i - 1 } else { i };
+            starts.push((start_code, i + 3));
+            i += 3;
+        } else {
+            i += 1;
+        }
+    }
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(n, &(start_code, offset))| {
+            let end = starts
+                .get(n + 1)
+                .map_or(data.len(), |&(next_start_code, _)| next_start_code);
+            (start_code, &data[offset..end])
+        })
+        .collect()
+}
+
+/// Removes the emulation prevention bytes from a NAL unit payload
+fn unescape(data: &[u8]) -> Vec<u8> {
+    let mut rbsp = Vec::with_capacity(data.len());
+    let mut zeros = 0;
+
+    for &byte in data {
+        if zeros >= 2 && byte == 0x03 {
+            zeros = 0;
+            continue;
+        }
+
+        zeros = if byte == 0 { zeros + 1 } else { 0 };
+        rbsp.push(byte);
+    }
+
+    rbsp
+}
+
+/// Appends `rbsp` to `nal`, inserting emulation prevention bytes as needed
+fn escape(rbsp: &[u8], nal: &mut Vec<u8>) {
+    let mut zeros = 0;
+
+    for &byte in rbsp {
+        if zeros >= 2 && byte <= 0x03 {
+            nal.push(0x03);
+            zeros = 0;
+        }
+
+        zeros = if byte == 0 { zeros + 1 } else { 0 };
+        nal.push(byte);
+    }
+}
+
+fn read_ff_coded(data: &[u8]) -> Option<(u32, &[u8])> {
+    let mut value = 0u32;
+
+    for (i, &byte) in data.iter().enumerate() {
+        value += byte as u32;
+        if byte != 0xff {
+            return Some((value, &data[i + 1..]));
+        }
+    }
+
+    None
+}
+
+fn write_ff_coded(mut value: usize, data: &mut Vec<u8>) {
+    while value >= 0xff {
+        data.push(0xff);
+        value -= 0xff;
+    }
+    data.push(value as u8);
+}
+
+/// Splits the RBSP of an SEI NAL unit into (payload type, payload) pairs
+fn sei_messages(mut rbsp: &[u8]) -> Vec<(u32, &[u8])> {
+    let mut messages = Vec::new();
+
+    /* Stop at the rbsp_trailing_bits */
+    while rbsp.len() >= 2 && rbsp[0] != 0x80 {
+        let (payload_type, data) = match read_ff_coded(rbsp) {
+            Some(res) => res,
+            None => break,
+        };
+        let (payload_size, data) = match read_ff_coded(data) {
+            Some(res) => res,
+            None => break,
+        };
+
+        let payload_size = payload_size as usize;
+        if payload_size > data.len() {
+            break;
+        }
+
+        messages.push((payload_type, &data[..payload_size]));
+        rbsp = &data[payload_size..];
+    }
+
+    messages
+}
+
+/// Returns the `cc_data` triplets of an A/53 `user_data_registered_itu_t_t35`
+/// payload
+fn parse_a53_payload(payload: &[u8]) -> Option<&[u8]> {
+    let data = payload.strip_prefix(&A53_HEADER[..])?;
+
+    let flags = *data.first()?;
+    /* process_cc_data_flag */
+    if flags & 0x40 == 0 {
+        return None;
+    }
+
+    let len = (flags & 0x1f) as usize * 3;
+
+    /* skip em_data */
+    data.get(2..2 + len)
+}
+
+/// Collects the `cc_data` triplets of all A/53 SEI messages of an access unit
+pub fn extract_cc_data(codec: Codec, au: &[u8]) -> Vec<u8> {
+    let mut cc_data = Vec::new();
+
+    for (_, nal) in nal_units(au) {
+        if nal.len() <= codec.header_len() || !codec.is_sei(codec.nal_type(nal[0])) {
+            continue;
+        }
+
+        let rbsp = unescape(&nal[codec.header_len()..]);
+        for (payload_type, payload) in sei_messages(&rbsp) {
+            if payload_type != ITU_T_T35_PAYLOAD_TYPE {
+                continue;
+            }
+
+            if let Some(data) = parse_a53_payload(payload) {
+                cc_data.extend_from_slice(data);
+            }
+        }
+    }
+
+    cc_data
+}
+
+/// Inserts an SEI NAL unit carrying `cc_data` right before the first VCL NAL
+/// unit of an access unit
+pub fn insert_cc_data(codec: Codec, au: &[u8], cc_data: &[u8]) -> Vec<u8> {
+    assert!(cc_data.len() % 3 == 0);
+    assert!(cc_data.len() / 3 <= 0x1f);
+
+    let mut payload = Vec::with_capacity(A53_HEADER.len() + 3 + cc_data.len());
+    payload.extend_from_slice(&A53_HEADER);
+    /* process_cc_data_flag, cc_count */
+    payload.push(0x40 | (cc_data.len() / 3) as u8);
+    /* em_data */
+    payload.push(0xff);
+    payload.extend_from_slice(cc_data);
+    /* marker_bits */
+    payload.push(0xff);
+
+    let mut rbsp = Vec::with_capacity(payload.len() + 3);
+    write_ff_coded(ITU_T_T35_PAYLOAD_TYPE as usize, &mut rbsp);
+    write_ff_coded(payload.len(), &mut rbsp);
+    rbsp.extend_from_slice(&payload);
+    /* rbsp_trailing_bits */
+    rbsp.push(0x80);
+
+    let mut sei = vec![0x00, 0x00, 0x00, 0x01];
+    sei.extend_from_slice(codec.sei_header());
+    escape(&rbsp, &mut sei);
+
+    let position = nal_units(au)
+        .into_iter()
+        .find(|(_, nal)| !nal.is_empty() && codec.is_vcl(codec.nal_type(nal[0])))
+        .map_or(au.len(), |(start_code, _)| start_code);
+
+    let mut res = Vec::with_capacity(au.len() + sei.len());
+    res.extend_from_slice(&au[..position]);
+    res.extend_from_slice(&sei);
+    res.extend_from_slice(&au[position..]);
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /* Access unit delimiter followed by an IDR slice */
+    const H264_AU: [u8; 18] = [
+        0x00, 0x00, 0x00, 0x01, 0x09, 0xf0, 0x00, 0x00, 0x01, 0x65, 0x88, 0x84, 0x00, 0x00, 0x03,
+        0x01, 0x20, 0x40,
+    ];
+
+    /* Access unit delimiter followed by an IDR slice */
+    const H265_AU: [u8; 20] = [
+        0x00, 0x00, 0x00, 0x01, 0x46, 0x01, 0x10, 0x00, 0x00, 0x01, 0x26, 0x01, 0xaf, 0x00, 0x00,
+        0x03, 0x01, 0x20, 0x40, 0x80,
+    ];
+
+    #[test]
+    fn test_insert_h264() {
+        let cc_data = [0xfc, 0x94, 0x20, 0xf9, 0x80, 0x80];
+        let au = insert_cc_data(Codec::H264, &H264_AU, &cc_data);
+
+        assert_eq!(
+            au,
+            vec![
+                0x00, 0x00, 0x00, 0x01, 0x09, 0xf0, 0x00, 0x00, 0x00, 0x01, 0x06, 0x04, 0x11, 0xb5,
+                0x00, 0x31, b'G', b'A', b'9', b'4', 0x03, 0x42, 0xff, 0xfc, 0x94, 0x20, 0xf9, 0x80,
+                0x80, 0xff, 0x80, 0x00, 0x00, 0x01, 0x65, 0x88, 0x84, 0x00, 0x00, 0x03, 0x01, 0x20,
+                0x40,
+            ]
+        );
+        assert_eq!(extract_cc_data(Codec::H264, &au), cc_data.to_vec());
+    }
+
+    #[test]
+    fn test_insert_h265() {
+        let cc_data = [0xfc, 0x94, 0x2c, 0xfa, 0x00, 0x00];
+        let au = insert_cc_data(Codec::H265, &H265_AU, &cc_data);
+
+        assert_eq!(
+            au,
+            vec![
+                0x00, 0x00, 0x00, 0x01, 0x46, 0x01, 0x10, 0x00, 0x00, 0x00, 0x01, 0x4e, 0x01, 0x04,
+                0x11, 0xb5, 0x00, 0x31, b'G', b'A', b'9', b'4', 0x03, 0x42, 0xff, 0xfc, 0x94, 0x2c,
+                0xfa, 0x00, 0x00, 0xff, 0x80, 0x00, 0x00, 0x01, 0x26, 0x01, 0xaf, 0x00, 0x00, 0x03,
+                0x01, 0x20, 0x40, 0x80,
+            ]
+        );
+        assert_eq!(extract_cc_data(Codec::H265, &au), cc_data.to_vec());
+    }
+
+    #[test]
+    fn test_emulation_prevention() {
+        let cc_data = [0xfa, 0x00, 0x00, 0x00, 0x00, 0x01, 0xfc, 0x80, 0x80];
+        let au = insert_cc_data(Codec::H264, &H264_AU, &cc_data);
+
+        /* No start code may be emulated inside the SEI NAL unit */
+        assert_eq!(nal_units(&au).len(), 3);
+        assert!(au
+            .windows(8)
+            .any(|w| w == [0xfa, 0x00, 0x00, 0x03, 0x00, 0x00, 0x03, 0x01]));
+        assert_eq!(extract_cc_data(Codec::H264, &au), cc_data.to_vec());
+    }
+
+    #[test]
+    fn test_extract_ignores_other_sei() {
+        let mut au = H264_AU[..6].to_vec();
+        /* recovery point SEI followed by unregistered user data */
+        au.extend_from_slice(&[
+            0x00, 0x00, 0x01, 0x06, 0x06, 0x01, 0xc4, 0x05, 0x02, 0xaa, 0xbb, 0x80,
+        ]);
+        au.extend_from_slice(&H264_AU[6..]);
+
+        assert!(extract_cc_data(Codec::H264, &au).is_empty());
+        assert!(extract_cc_data(Codec::H264, &H264_AU).is_empty());
+    }
+
+    #[test]
+    fn test_codec_from_caps() {
+        let caps = gst::Caps::builder("video/x-h264")
+            .field("stream-format", "byte-stream")
+            .field("alignment", "au")
+            .build();
+        assert_eq!(Codec::from_caps(&caps), Some(Codec::H264));
+
+        let caps = gst::Caps::builder("video/x-h265")
+            .field("stream-format", "hvc1")
+            .field("alignment", "au")
+            .build();
+        assert_eq!(Codec::from_caps(&caps), None);
+
+        let caps = gst::Caps::builder("video/x-raw").build();
+        assert_eq!(Codec::from_caps(&caps), None);
+    }
+}
@@ -7,6 +7,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use super::ffi;
+use crate::ttutils::TextStyle;
 use std::mem;
 
 #[derive(Copy, Clone, Debug)]
@@ -62,6 +63,31 @@ impl CaptionFrame {
             String::from_utf8(data).map_err(|_| Error)
         }
     }
+
+    /// Returns the displayed character at `row` x `col`, with its style
+    /// and whether it is underlined, or `None` for empty cells
+    pub fn read_char(&self, row: u32, col: u32) -> Option<(char, TextStyle, bool)> {
+        unsafe {
+            let mut style = 0;
+            let mut underline = 0;
+
+            let data = ffi::caption_frame_read_char(
+                &self.0 as *const _ as *mut _,
+                row as i32,
+                col as i32,
+                &mut style,
+                &mut underline,
+            );
+
+            let c = std::ffi::CStr::from_ptr(data)
+                .to_str()
+                .ok()?
+                .chars()
+                .next()?;
+
+            Some((c, style.into(), underline != 0))
+        }
+    }
 }
 
 impl Default for CaptionFrame {
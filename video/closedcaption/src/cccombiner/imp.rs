@@ -0,0 +1,778 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use gst::glib;
+use gst::glib::translate::IntoGlib;
+use gst_base::prelude::*;
+use gst_base::subclass::prelude::*;
+use gst_video::VideoCaptionType;
+
+use once_cell::sync::Lazy;
+
+use std::mem;
+use std::sync::Mutex;
+
+use crate::a53;
+use crate::ccutils;
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "cccombiner",
+        gst::DebugColorFlags::empty(),
+        Some("Closed Caption Combiner"),
+    )
+});
+
+const DEFAULT_CAPTION_TYPE: VideoCaptionType = VideoCaptionType::Unknown;
+const DEFAULT_INSERT_SEI: bool = false;
+
+#[derive(Debug, Clone, Copy)]
+struct Settings {
+    caption_type: VideoCaptionType,
+    insert_sei: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            caption_type: DEFAULT_CAPTION_TYPE,
+            insert_sei: DEFAULT_INSERT_SEI,
+        }
+    }
+}
+
+/// The video buffer captions are currently collected for. It stays queued
+/// on the video pad until it is output, as the aggregator only runs in
+/// non-live mode once all pads have data.
+#[derive(Debug)]
+struct CurrentVideo {
+    buffer: gst::Buffer,
+    segment: gst::FormattedSegment<gst::ClockTime>,
+    caps: Option<gst::Caps>,
+    running_time: gst::ClockTime,
+    end_running_time: Option<gst::ClockTime>,
+}
+
+#[derive(Debug)]
+struct State {
+    /// Caption type of the caption pad
+    caption_type: VideoCaptionType,
+    /// Captions collected for the current video buffer
+    captions: Vec<(VideoCaptionType, gst::Buffer)>,
+    cdp_sequence_counter: u16,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            caption_type: VideoCaptionType::Unknown,
+            captions: Vec::new(),
+            cdp_sequence_counter: 0,
+        }
+    }
+}
+
+pub struct CCCombiner {
+    video_sinkpad: gst_base::AggregatorPad,
+    caption_sinkpad: Mutex<Option<gst_base::AggregatorPad>>,
+    state: Mutex<State>,
+    settings: Mutex<Settings>,
+}
+
+impl CCCombiner {
+    fn time_segment(
+        pad: &gst_base::AggregatorPad,
+    ) -> Result<gst::FormattedSegment<gst::ClockTime>, gst::FlowError> {
+        pad.segment().downcast::<gst::ClockTime>().map_err(|_| {
+            gst::error!(CAT, obj: pad, "Only TIME segments supported");
+            gst::FlowError::NotSupported
+        })
+    }
+
+    fn current_video(&self, agg: &super::CCCombiner) -> Result<CurrentVideo, gst::FlowError> {
+        let buffer = match self.video_sinkpad.peek_buffer() {
+            Some(buffer) => buffer,
+            None if self.video_sinkpad.is_eos() => {
+                gst::debug!(CAT, obj: agg, "Video pad is EOS, we're done");
+                return Err(gst::FlowError::Eos);
+            }
+            None => return Err(gst_base::AGGREGATOR_FLOW_NEED_DATA),
+        };
+
+        let segment = Self::time_segment(&self.video_sinkpad)?;
+        let caps = self.video_sinkpad.current_caps();
+
+        let pts = match buffer.pts() {
+            Some(pts) => pts,
+            None => {
+                gst::element_error!(
+                    agg,
+                    gst::StreamError::Format,
+                    ["Video buffers without PTS are not supported"]
+                );
+                return Err(gst::FlowError::Error);
+            }
+        };
+
+        let running_time = match segment.to_running_time(pts) {
+            Some(running_time) => running_time,
+            None => {
+                gst::debug!(CAT, obj: agg, "Dropping video buffer outside segment");
+                self.video_sinkpad.drop_buffer();
+                return Err(gst_base::AGGREGATOR_FLOW_NEED_DATA);
+            }
+        };
+
+        let duration = buffer.duration().or_else(|| {
+            caps.as_ref()
+                .and_then(|caps| caps.structure(0))
+                .and_then(|s| s.get::<gst::Fraction>("framerate").ok())
+                .filter(|framerate| framerate.numer() > 0)
+                .and_then(|framerate| {
+                    gst::ClockTime::SECOND
+                        .mul_div_floor(framerate.denom() as u64, framerate.numer() as u64)
+                })
+        });
+        let end_running_time =
+            duration.and_then(|duration| segment.to_running_time(pts + duration));
+
+        Ok(CurrentVideo {
+            buffer,
+            segment,
+            caps,
+            running_time,
+            end_running_time,
+        })
+    }
+
+    /// Collects the caption buffers falling into the running time range of
+    /// the current video buffer
+    fn collect_captions(
+        &self,
+        agg: &super::CCCombiner,
+        state: &mut State,
+        current: &CurrentVideo,
+        pad: &gst_base::AggregatorPad,
+        timeout: bool,
+    ) -> Result<(), gst::FlowError> {
+        let running_time = current.running_time;
+        let end_running_time = current.end_running_time.unwrap_or(running_time);
+
+        loop {
+            let buffer = match pad.peek_buffer() {
+                Some(buffer) => buffer,
+                None if pad.is_eos() || timeout => return Ok(()),
+                None => {
+                    gst::trace!(CAT, obj: agg, "Waiting for caption data");
+                    return Err(gst_base::AGGREGATOR_FLOW_NEED_DATA);
+                }
+            };
+
+            let segment = Self::time_segment(pad)?;
+
+            let caption_running_time = match segment.to_running_time(buffer.pts()) {
+                Some(caption_running_time) => caption_running_time,
+                None => {
+                    gst::debug!(CAT, obj: pad, "Dropping caption buffer without running time");
+                    pad.drop_buffer();
+                    continue;
+                }
+            };
+
+            // Belongs to a later video buffer
+            if caption_running_time > running_time && caption_running_time >= end_running_time {
+                return Ok(());
+            }
+
+            // Gap events are queued as empty buffers
+            if buffer.flags().contains(gst::BufferFlags::GAP) && buffer.size() == 0 {
+                let gap_end = buffer
+                    .pts()
+                    .opt_add(buffer.duration())
+                    .and_then(|end| segment.to_running_time(end))
+                    .unwrap_or(caption_running_time);
+
+                if gap_end > end_running_time {
+                    return Ok(());
+                }
+
+                pad.drop_buffer();
+                if gap_end == end_running_time {
+                    return Ok(());
+                }
+
+                continue;
+            }
+
+            if caption_running_time < running_time {
+                gst::debug!(
+                    CAT,
+                    obj: pad,
+                    "Dropping late caption buffer at {}, current video running time {}",
+                    caption_running_time,
+                    running_time
+                );
+                pad.drop_buffer();
+                continue;
+            }
+
+            gst::log!(
+                CAT,
+                obj: pad,
+                "Collecting caption buffer at {} for video running time {}",
+                caption_running_time,
+                running_time
+            );
+
+            state.captions.push((state.caption_type, buffer));
+            pad.drop_buffer();
+        }
+    }
+
+    fn cc_data(
+        &self,
+        agg: &super::CCCombiner,
+        captions: &[(VideoCaptionType, gst::Buffer)],
+    ) -> Vec<u8> {
+        let mut cc_data = Vec::new();
+
+        for (caption_type, buffer) in captions {
+            let map = match buffer.map_readable() {
+                Ok(map) => map,
+                Err(_) => {
+                    gst::warning!(CAT, obj: agg, "Failed to map caption buffer readable");
+                    continue;
+                }
+            };
+
+            match ccutils::to_cc_data(*caption_type, &map) {
+                Ok(data) => cc_data.extend(data),
+                Err(err) => {
+                    gst::warning!(CAT, obj: agg, "{}", &err.to_string());
+                    gst::element_warning!(agg, gst::StreamError::Decode, [&err.to_string()]);
+                }
+            }
+        }
+
+        cc_data
+    }
+
+    fn insert_sei(
+        &self,
+        agg: &super::CCCombiner,
+        codec: a53::Codec,
+        buffer: gst::Buffer,
+        mut cc_data: Vec<u8>,
+    ) -> Result<gst::Buffer, gst::FlowError> {
+        if cc_data.is_empty() {
+            return Ok(buffer);
+        }
+
+        if cc_data.len() > 0x1f * 3 {
+            gst::warning!(
+                CAT,
+                obj: agg,
+                "Too many cc_data triplets ({}), dropping some",
+                cc_data.len() / 3
+            );
+            cc_data.truncate(0x1f * 3);
+        }
+
+        let map = buffer.map_readable().map_err(|_| {
+            gst::element_error!(
+                agg,
+                gst::ResourceError::Read,
+                ["Failed to map buffer readable"]
+            );
+
+            gst::FlowError::Error
+        })?;
+
+        let data = a53::insert_cc_data(codec, &map, &cc_data);
+        drop(map);
+
+        let mut outbuf = gst::Buffer::from_mut_slice(data);
+        buffer
+            .copy_into(
+                outbuf.get_mut().unwrap(),
+                gst::BufferCopyFlags::FLAGS
+                    | gst::BufferCopyFlags::TIMESTAMPS
+                    | gst::BufferCopyFlags::META,
+                0,
+                None,
+            )
+            .map_err(|_| {
+                gst::error!(CAT, obj: agg, "Failed to copy buffer metadata");
+                gst::FlowError::Error
+            })?;
+
+        Ok(outbuf)
+    }
+
+    fn attach_captions(
+        &self,
+        agg: &super::CCCombiner,
+        state: &mut State,
+        settings: &Settings,
+        current: CurrentVideo,
+        captions: Vec<(VideoCaptionType, gst::Buffer)>,
+    ) -> Result<gst::Buffer, gst::FlowError> {
+        let mut buffer = current.buffer;
+
+        if captions.is_empty() {
+            return Ok(buffer);
+        }
+
+        let codec = current
+            .caps
+            .as_ref()
+            .and_then(|caps| a53::Codec::from_caps(caps));
+        if let (true, Some(codec)) = (settings.insert_sei, codec) {
+            let cc_data = self.cc_data(agg, &captions);
+            return self.insert_sei(agg, codec, buffer, cc_data);
+        }
+
+        let output_type = settings.caption_type;
+
+        // No conversion needed
+        if captions.iter().all(|(caption_type, _)| {
+            output_type == VideoCaptionType::Unknown || *caption_type == output_type
+        }) {
+            let buffer_mut = buffer.make_mut();
+            for (caption_type, caption) in &captions {
+                let map = match caption.map_readable() {
+                    Ok(map) => map,
+                    Err(_) => {
+                        gst::warning!(CAT, obj: agg, "Failed to map caption buffer readable");
+                        continue;
+                    }
+                };
+
+                if !map.is_empty() {
+                    gst_video::VideoCaptionMeta::add(buffer_mut, *caption_type, &map);
+                }
+            }
+
+            return Ok(buffer);
+        }
+
+        let cc_data = self.cc_data(agg, &captions);
+        let data = match output_type {
+            VideoCaptionType::Cea608Raw => ccutils::cc_data_to_cea608(&cc_data),
+            VideoCaptionType::Cea608S3341a => ccutils::cc_data_to_s334_1a(&cc_data),
+            VideoCaptionType::Cea708Raw => cc_data,
+            VideoCaptionType::Cea708Cdp => {
+                let framerate = current
+                    .caps
+                    .as_ref()
+                    .and_then(|caps| caps.structure(0))
+                    .and_then(|s| s.get::<gst::Fraction>("framerate").ok())
+                    .and_then(|framerate| {
+                        ccutils::cdp_framerate(framerate.numer(), framerate.denom())
+                    });
+
+                let framerate = match framerate {
+                    Some(framerate) => framerate,
+                    None => {
+                        gst::element_error!(
+                            agg,
+                            gst::StreamError::Format,
+                            ["Video framerate not supported for CDP output"]
+                        );
+                        return Err(gst::FlowError::NotNegotiated);
+                    }
+                };
+
+                let cdp = ccutils::write_cdp(
+                    framerate,
+                    state.cdp_sequence_counter,
+                    &ccutils::cdp_cc_data(framerate, &cc_data),
+                );
+                state.cdp_sequence_counter = state.cdp_sequence_counter.wrapping_add(1);
+
+                cdp
+            }
+            _ => unreachable!(),
+        };
+
+        if !data.is_empty() {
+            gst_video::VideoCaptionMeta::add(buffer.make_mut(), output_type, &data);
+        }
+
+        Ok(buffer)
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for CCCombiner {
+    const NAME: &'static str = "RsCCCombiner";
+    type Type = super::CCCombiner;
+    type ParentType = gst_base::Aggregator;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        let templ = klass.pad_template("sink").unwrap();
+        let video_sinkpad =
+            gst::PadBuilder::<gst_base::AggregatorPad>::from_template(&templ, Some("sink")).build();
+
+        Self {
+            video_sinkpad,
+            caption_sinkpad: Mutex::new(None),
+            state: Mutex::new(State::default()),
+            settings: Mutex::new(Settings::default()),
+        }
+    }
+}
+
+impl ObjectImpl for CCCombiner {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecEnum::new(
+                    "caption-type",
+                    "Caption Type",
+                    "Type of the attached caption meta, captions are converted as needed \
+                     (unknown: keep the type of the caption stream)",
+                    VideoCaptionType::static_type(),
+                    DEFAULT_CAPTION_TYPE.into_glib(),
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_PLAYING,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "insert-sei",
+                    "Insert SEI",
+                    "Insert captions as A/53 SEI into byte-stream H.264/H.265 access units \
+                     instead of attaching caption meta",
+                    DEFAULT_INSERT_SEI,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_PLAYING,
+                ),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(&self.video_sinkpad).unwrap();
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        match pspec.name() {
+            "caption-type" => {
+                self.settings.lock().unwrap().caption_type = value
+                    .get::<VideoCaptionType>()
+                    .expect("type checked upstream");
+            }
+            "insert-sei" => {
+                self.settings.lock().unwrap().insert_sei =
+                    value.get().expect("type checked upstream");
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            "caption-type" => {
+                let settings = self.settings.lock().unwrap();
+                settings.caption_type.to_value()
+            }
+            "insert-sei" => {
+                let settings = self.settings.lock().unwrap();
+                settings.insert_sei.to_value()
+            }
+            _ => unimplemented!(),
+        }
+    }
+}
+
+impl GstObjectImpl for CCCombiner {}
+
+impl ElementImpl for CCCombiner {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Closed Caption Combiner",
+                "Filter/Video/ClosedCaption",
+                "Combines GstVideoCaptionMeta or A/53 SEI closed captions with video buffers",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::new_any();
+
+            let src_pad_template = gst::PadTemplate::with_gtype(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+                gst_base::AggregatorPad::static_type(),
+            )
+            .unwrap();
+
+            let sink_pad_template = gst::PadTemplate::with_gtype(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+                gst_base::AggregatorPad::static_type(),
+            )
+            .unwrap();
+
+            let mut caps = gst::Caps::new_empty();
+            {
+                let caps = caps.get_mut().unwrap();
+
+                let s = gst::Structure::builder("closedcaption/x-cea-608")
+                    .field("format", gst::List::new(["raw", "s334-1a"]))
+                    .build();
+                caps.append_structure(s);
+
+                let s = gst::Structure::builder("closedcaption/x-cea-708")
+                    .field("format", gst::List::new(["cc_data", "cdp"]))
+                    .build();
+                caps.append_structure(s);
+            }
+
+            let caption_pad_template = gst::PadTemplate::with_gtype(
+                "caption",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Request,
+                &caps,
+                gst_base::AggregatorPad::static_type(),
+            )
+            .unwrap();
+
+            vec![src_pad_template, sink_pad_template, caption_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn request_new_pad(
+        &self,
+        element: &Self::Type,
+        templ: &gst::PadTemplate,
+        name: Option<String>,
+        _caps: Option<&gst::Caps>,
+    ) -> Option<gst::Pad> {
+        let caption_templ = element.pad_template("caption").unwrap();
+        if templ != &caption_templ || (name.is_some() && name.as_deref() != Some("caption")) {
+            gst::error!(CAT, obj: element, "Wrong pad template or name");
+            return None;
+        }
+
+        let mut caption_sinkpad = self.caption_sinkpad.lock().unwrap();
+        if caption_sinkpad.is_some() {
+            gst::error!(CAT, obj: element, "Already have a caption sinkpad");
+            return None;
+        }
+
+        let sinkpad =
+            gst::PadBuilder::<gst_base::AggregatorPad>::from_template(templ, Some("caption"))
+                .build();
+
+        *caption_sinkpad = Some(sinkpad.clone());
+        drop(caption_sinkpad);
+
+        element.add_pad(&sinkpad).unwrap();
+
+        Some(sinkpad.upcast())
+    }
+
+    fn release_pad(&self, element: &Self::Type, pad: &gst::Pad) {
+        let mut caption_sinkpad = self.caption_sinkpad.lock().unwrap();
+
+        if caption_sinkpad.as_ref().map(|p| p.upcast_ref()) == Some(pad) {
+            *caption_sinkpad = None;
+            drop(caption_sinkpad);
+            element.remove_pad(pad).unwrap();
+            gst::debug!(CAT, obj: element, "Removed caption sinkpad {:?}", pad);
+        }
+
+        self.state.lock().unwrap().caption_type = VideoCaptionType::Unknown;
+    }
+}
+
+impl AggregatorImpl for CCCombiner {
+    fn start(&self, _agg: &Self::Type) -> Result<(), gst::ErrorMessage> {
+        *self.state.lock().unwrap() = State::default();
+
+        Ok(())
+    }
+
+    fn stop(&self, _agg: &Self::Type) -> Result<(), gst::ErrorMessage> {
+        *self.state.lock().unwrap() = State::default();
+
+        Ok(())
+    }
+
+    fn flush(&self, agg: &Self::Type) -> Result<gst::FlowSuccess, gst::FlowError> {
+        let mut state = self.state.lock().unwrap();
+        state.captions.clear();
+        drop(state);
+
+        self.parent_flush(agg)
+    }
+
+    fn sink_event(
+        &self,
+        agg: &Self::Type,
+        agg_pad: &gst_base::AggregatorPad,
+        event: gst::Event,
+    ) -> bool {
+        use gst::EventView;
+
+        match event.view() {
+            EventView::Caps(c) if agg_pad != &self.video_sinkpad => {
+                let caps = c.caps_owned();
+                gst::debug!(CAT, obj: agg_pad, "Received caption caps {}", caps);
+
+                let caption_type = VideoCaptionType::from_caps(&caps);
+                if caption_type == VideoCaptionType::Unknown {
+                    gst::error!(CAT, obj: agg_pad, "Unsupported caption caps {}", caps);
+                    return false;
+                }
+
+                self.state.lock().unwrap().caption_type = caption_type;
+
+                self.parent_sink_event(agg, agg_pad, event)
+            }
+            _ => self.parent_sink_event(agg, agg_pad, event),
+        }
+    }
+
+    fn sink_query(
+        &self,
+        agg: &Self::Type,
+        agg_pad: &gst_base::AggregatorPad,
+        query: &mut gst::QueryRef,
+    ) -> bool {
+        use gst::QueryViewMut;
+
+        match query.view_mut() {
+            // Video negotiation and allocation is done with downstream
+            QueryViewMut::Caps(..)
+            | QueryViewMut::AcceptCaps(..)
+            | QueryViewMut::Allocation(..)
+                if agg_pad == &self.video_sinkpad =>
+            {
+                agg.static_pad("src").unwrap().peer_query(query)
+            }
+            // Captions are expected at the video framerate
+            QueryViewMut::Caps(q) => {
+                let mut caps = agg_pad.pad_template_caps();
+
+                if let Some(framerate) = self
+                    .video_sinkpad
+                    .current_caps()
+                    .as_ref()
+                    .and_then(|caps| caps.structure(0))
+                    .and_then(|s| s.get::<gst::Fraction>("framerate").ok())
+                {
+                    let caps = caps.make_mut();
+                    for s in caps.iter_mut() {
+                        s.set("framerate", framerate);
+                    }
+                }
+
+                let caps = match q.filter() {
+                    Some(filter) => {
+                        filter.intersect_with_mode(&caps, gst::CapsIntersectMode::First)
+                    }
+                    None => caps,
+                };
+
+                gst::debug!(CAT, obj: agg_pad, "Returning caption caps {}", caps);
+                q.set_result(&caps);
+
+                true
+            }
+            _ => self.parent_sink_query(agg, agg_pad, query),
+        }
+    }
+
+    fn next_time(&self, _agg: &Self::Type) -> Option<gst::ClockTime> {
+        // Only time out while waiting for the captions of a video buffer
+        let buffer = self.video_sinkpad.peek_buffer()?;
+        let segment = Self::time_segment(&self.video_sinkpad).ok()?;
+
+        segment.to_running_time(buffer.pts())
+    }
+
+    fn update_src_caps(
+        &self,
+        _agg: &Self::Type,
+        _caps: &gst::Caps,
+    ) -> Result<gst::Caps, gst::FlowError> {
+        // The video caps are output unchanged
+        self.video_sinkpad
+            .current_caps()
+            .ok_or(gst_base::AGGREGATOR_FLOW_NEED_DATA)
+    }
+
+    fn aggregate(
+        &self,
+        agg: &Self::Type,
+        timeout: bool,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        gst::log!(CAT, obj: agg, "Aggregate called: timeout {}", timeout);
+
+        let settings = *self.settings.lock().unwrap();
+        let mut state = self.state.lock().unwrap();
+
+        let mut current = self.current_video(agg)?;
+
+        let caption_sinkpad = self.caption_sinkpad.lock().unwrap().clone();
+        if let Some(ref caption_sinkpad) = caption_sinkpad {
+            self.collect_captions(agg, &mut state, &current, caption_sinkpad, timeout)?;
+        }
+
+        self.video_sinkpad.drop_buffer();
+        let captions = mem::take(&mut state.captions);
+
+        // The output segment starts at zero, timestamps are running times
+        {
+            let buffer = current.buffer.make_mut();
+            buffer.set_pts(current.running_time);
+            buffer.set_dts(current.segment.to_running_time(buffer.dts()));
+        }
+
+        let caps = current.caps.clone();
+        let buffer = self.attach_captions(agg, &mut state, &settings, current, captions)?;
+        drop(state);
+
+        if let Some(caps) = caps {
+            let current_src_caps = agg.static_pad("src").unwrap().current_caps();
+            if Some(&caps) != current_src_caps.as_ref() {
+                gst::info!(
+                    CAT,
+                    obj: agg,
+                    "Caps change from {:?} to {:?}",
+                    current_src_caps,
+                    caps
+                );
+                agg.set_src_caps(&caps);
+            }
+        }
+
+        gst::log!(CAT, obj: agg, "Finishing buffer {:?}", buffer);
+        agg.finish_buffer(buffer)
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+// Example command-line:
+//
+// gst-launch-1.0 videotestsrc ! video/x-raw,framerate=30000/1001 ! queue ! \
+//   rscccombiner name=c caption-type=cea708-raw ! cea608overlay ! autovideosink \
+//   filesrc location=input.scc ! sccparse ! queue ! c.caption
+//
+// Caption buffers are attached as GstVideoCaptionMeta to the video buffer
+// whose running time range they fall into, or inserted as A/53 SEI into
+// byte-stream H.264/H.265 access units with insert-sei=true. The video
+// buffers are output with running time timestamps.
+
+use gst::glib;
+use gst::prelude::*;
+
+mod imp;
+
+glib::wrapper! {
+    pub struct CCCombiner(ObjectSubclass<imp::CCCombiner>) @extends gst_base::Aggregator, gst::Element, gst::Object;
+}
+
+pub fn register(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
+    gst::Element::register(
+        Some(plugin),
+        "rscccombiner",
+        gst::Rank::None,
+        CCCombiner::static_type(),
+    )
+}
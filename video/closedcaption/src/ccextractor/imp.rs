@@ -0,0 +1,486 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+use gst_video::VideoCaptionType;
+
+use once_cell::sync::Lazy;
+
+use std::sync::Mutex;
+
+use crate::a53;
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "ccextractor",
+        gst::DebugColorFlags::empty(),
+        Some("Closed Caption Extractor"),
+    )
+});
+
+const DEFAULT_REMOVE_CAPTION_META: bool = false;
+
+#[derive(Debug, Clone, Copy)]
+struct Settings {
+    remove_caption_meta: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            remove_caption_meta: DEFAULT_REMOVE_CAPTION_META,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    /// Caption type currently configured on the caption pad
+    caption_type: VideoCaptionType,
+    framerate: Option<gst::Fraction>,
+    /// Set for byte-stream H.264/H.265 input, which might carry A/53 SEI
+    codec: Option<a53::Codec>,
+    segment: Option<gst::Event>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            caption_type: VideoCaptionType::Unknown,
+            framerate: None,
+            codec: None,
+            segment: None,
+        }
+    }
+}
+
+pub struct CCExtractor {
+    srcpad: gst::Pad,
+    sinkpad: gst::Pad,
+    captionpad: Mutex<Option<gst::Pad>>,
+    flow_combiner: Mutex<gst_base::UniqueFlowCombiner>,
+    state: Mutex<State>,
+    settings: Mutex<Settings>,
+}
+
+impl CCExtractor {
+    fn caption_caps(caption_type: VideoCaptionType, framerate: Option<gst::Fraction>) -> gst::Caps {
+        let mut caps = caption_type.to_caps();
+
+        if let Some(framerate) = framerate {
+            let caps = caps.make_mut();
+            let s = caps.structure_mut(0).unwrap();
+            s.set("framerate", &framerate);
+        }
+
+        caps
+    }
+
+    /// Returns the caption pad, creating it or updating its caps if needed
+    fn caption_pad(
+        &self,
+        element: &super::CCExtractor,
+        caption_type: VideoCaptionType,
+    ) -> gst::Pad {
+        let mut captionpad = self.captionpad.lock().unwrap();
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(pad) = captionpad.clone() {
+            if state.caption_type != caption_type {
+                gst::debug!(
+                    CAT,
+                    obj: element,
+                    "Caption type changed from {:?} to {:?}",
+                    state.caption_type,
+                    caption_type
+                );
+
+                state.caption_type = caption_type;
+                let caps = Self::caption_caps(caption_type, state.framerate);
+                drop(state);
+                drop(captionpad);
+
+                pad.push_event(gst::event::Caps::new(&caps));
+            }
+
+            return pad;
+        }
+
+        gst::debug!(
+            CAT,
+            obj: element,
+            "Adding caption pad for caption type {:?}",
+            caption_type
+        );
+
+        state.caption_type = caption_type;
+        let caps = Self::caption_caps(caption_type, state.framerate);
+        let segment = state.segment.clone();
+        drop(state);
+
+        let templ = element.element_class().pad_template("caption").unwrap();
+        let pad = gst::Pad::builder_with_template(&templ, Some("caption"))
+            .flags(gst::PadFlags::FIXED_CAPS)
+            .build();
+
+        pad.set_active(true).unwrap();
+
+        let stream_id = pad.create_stream_id(element, Some("caption"));
+        pad.push_event(gst::event::StreamStart::new(&stream_id));
+        pad.push_event(gst::event::Caps::new(&caps));
+        if let Some(segment) = segment {
+            pad.push_event(segment);
+        }
+
+        self.flow_combiner.lock().unwrap().add_pad(&pad);
+        *captionpad = Some(pad.clone());
+        drop(captionpad);
+
+        element.add_pad(&pad).unwrap();
+
+        pad
+    }
+
+    fn sink_chain(
+        &self,
+        pad: &gst::Pad,
+        element: &super::CCExtractor,
+        mut buffer: gst::Buffer,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        gst::log!(CAT, obj: pad, "Handling buffer {:?}", buffer);
+
+        let settings = *self.settings.lock().unwrap();
+        let codec = self.state.lock().unwrap().codec;
+
+        let mut captions = buffer
+            .iter_meta::<gst_video::VideoCaptionMeta>()
+            .map(|meta| (meta.caption_type(), meta.data().to_vec()))
+            .collect::<Vec<_>>();
+
+        if !captions.is_empty() {
+            if settings.remove_caption_meta {
+                let buffer = buffer.make_mut();
+                while let Some(meta) = buffer.meta_mut::<gst_video::VideoCaptionMeta>() {
+                    if meta.remove().is_err() {
+                        gst::warning!(CAT, obj: element, "Failed to remove caption meta");
+                        break;
+                    }
+                }
+            }
+        } else if let Some(codec) = codec {
+            let map = buffer.map_readable().map_err(|_| {
+                gst::element_error!(
+                    element,
+                    gst::ResourceError::Read,
+                    ["Failed to map buffer readable"]
+                );
+
+                gst::FlowError::Error
+            })?;
+
+            let cc_data = a53::extract_cc_data(codec, &map);
+            if !cc_data.is_empty() {
+                captions.push((VideoCaptionType::Cea708Raw, cc_data));
+            }
+        }
+
+        if captions.is_empty() {
+            let captionpad = self.captionpad.lock().unwrap().clone();
+
+            // Let downstream know that there are no captions for this frame
+            if let (Some(captionpad), Some(pts)) = (captionpad, buffer.pts()) {
+                gst::trace!(CAT, obj: element, "Sending gap event at {}", pts);
+                captionpad.push_event(
+                    gst::event::Gap::builder(pts)
+                        .duration(buffer.duration())
+                        .build(),
+                );
+            }
+        }
+
+        for (caption_type, data) in captions {
+            let captionpad = self.caption_pad(element, caption_type);
+
+            let mut caption_buffer = gst::Buffer::from_mut_slice(data);
+            {
+                let caption_buffer = caption_buffer.get_mut().unwrap();
+                caption_buffer.set_pts(buffer.pts());
+                caption_buffer.set_dts(buffer.dts());
+                caption_buffer.set_duration(buffer.duration());
+            }
+
+            gst::log!(CAT, obj: &captionpad, "Pushing caption buffer {:?}", caption_buffer);
+
+            let res = captionpad.push(caption_buffer);
+            self.flow_combiner
+                .lock()
+                .unwrap()
+                .update_pad_flow(&captionpad, res)?;
+        }
+
+        let res = self.srcpad.push(buffer);
+        self.flow_combiner
+            .lock()
+            .unwrap()
+            .update_pad_flow(&self.srcpad, res)
+    }
+
+    fn sink_event(&self, pad: &gst::Pad, element: &super::CCExtractor, event: gst::Event) -> bool {
+        use gst::EventView;
+
+        gst::log!(CAT, obj: pad, "Handling event {:?}", event);
+        match event.view() {
+            EventView::Caps(c) => {
+                let caps = c.caps_owned();
+                let framerate = caps
+                    .structure(0)
+                    .and_then(|s| s.get::<gst::Fraction>("framerate").ok());
+                let codec = a53::Codec::from_caps(&caps);
+
+                let captionpad = self.captionpad.lock().unwrap().clone();
+                let mut state = self.state.lock().unwrap();
+                let framerate_changed = state.framerate != framerate;
+                state.framerate = framerate;
+                state.codec = codec;
+                let caption_type = state.caption_type;
+                drop(state);
+
+                // The caption caps carry the video framerate
+                if let (Some(captionpad), true) = (captionpad, framerate_changed) {
+                    let caption_caps = Self::caption_caps(caption_type, framerate);
+                    captionpad.push_event(gst::event::Caps::new(&caption_caps));
+                }
+
+                self.srcpad.push_event(event)
+            }
+            // The caption pad has its own stream
+            EventView::StreamStart(..) => self.srcpad.push_event(event),
+            EventView::Segment(..) => {
+                self.state.lock().unwrap().segment = Some(event.clone());
+                pad.event_default(Some(element), event)
+            }
+            EventView::FlushStop(..) => {
+                self.flow_combiner.lock().unwrap().reset();
+                pad.event_default(Some(element), event)
+            }
+            _ => pad.event_default(Some(element), event),
+        }
+    }
+
+    fn sink_query(
+        &self,
+        pad: &gst::Pad,
+        _element: &super::CCExtractor,
+        query: &mut gst::QueryRef,
+    ) -> bool {
+        gst::log!(CAT, obj: pad, "Handling query {:?}", query);
+
+        // The caption pad is not involved in negotiation or allocation
+        self.srcpad.peer_query(query)
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for CCExtractor {
+    const NAME: &'static str = "RsCCExtractor";
+    type Type = super::CCExtractor;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        let templ = klass.pad_template("sink").unwrap();
+        let sinkpad = gst::Pad::builder_with_template(&templ, Some("sink"))
+            .chain_function(|pad, parent, buffer| {
+                CCExtractor::catch_panic_pad_function(
+                    parent,
+                    || Err(gst::FlowError::Error),
+                    |this, element| this.sink_chain(pad, element, buffer),
+                )
+            })
+            .event_function(|pad, parent, event| {
+                CCExtractor::catch_panic_pad_function(
+                    parent,
+                    || false,
+                    |this, element| this.sink_event(pad, element, event),
+                )
+            })
+            .query_function(|pad, parent, query| {
+                CCExtractor::catch_panic_pad_function(
+                    parent,
+                    || false,
+                    |this, element| this.sink_query(pad, element, query),
+                )
+            })
+            .build();
+
+        let templ = klass.pad_template("src").unwrap();
+        let srcpad = gst::Pad::builder_with_template(&templ, Some("src")).build();
+
+        let mut flow_combiner = gst_base::UniqueFlowCombiner::new();
+        flow_combiner.add_pad(&srcpad);
+
+        Self {
+            srcpad,
+            sinkpad,
+            captionpad: Mutex::new(None),
+            flow_combiner: Mutex::new(flow_combiner),
+            state: Mutex::new(State::default()),
+            settings: Mutex::new(Settings::default()),
+        }
+    }
+}
+
+impl ObjectImpl for CCExtractor {
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(&self.sinkpad).unwrap();
+        obj.add_pad(&self.srcpad).unwrap();
+    }
+
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![glib::ParamSpecBoolean::new(
+                "remove-caption-meta",
+                "Remove Caption Meta",
+                "Remove the caption meta from the outgoing video buffers",
+                DEFAULT_REMOVE_CAPTION_META,
+                glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_PLAYING,
+            )]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        match pspec.name() {
+            "remove-caption-meta" => {
+                self.settings.lock().unwrap().remove_caption_meta =
+                    value.get().expect("type checked upstream");
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            "remove-caption-meta" => {
+                let settings = self.settings.lock().unwrap();
+                settings.remove_caption_meta.to_value()
+            }
+            _ => unimplemented!(),
+        }
+    }
+}
+
+impl GstObjectImpl for CCExtractor {}
+
+impl ElementImpl for CCExtractor {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Closed Caption Extractor",
+                "Filter/Video/ClosedCaption",
+                "Extracts GstVideoCaptionMeta or A/53 SEI closed captions from video buffers",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::new_any();
+
+            let sink_pad_template = gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            let src_pad_template = gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            let mut caps = gst::Caps::new_empty();
+            {
+                let caps = caps.get_mut().unwrap();
+
+                let s = gst::Structure::builder("closedcaption/x-cea-608")
+                    .field("format", gst::List::new(["raw", "s334-1a"]))
+                    .build();
+                caps.append_structure(s);
+
+                let s = gst::Structure::builder("closedcaption/x-cea-708")
+                    .field("format", gst::List::new(["cc_data", "cdp"]))
+                    .build();
+                caps.append_structure(s);
+            }
+
+            let caption_pad_template = gst::PadTemplate::new(
+                "caption",
+                gst::PadDirection::Src,
+                gst::PadPresence::Sometimes,
+                &caps,
+            )
+            .unwrap();
+
+            vec![sink_pad_template, src_pad_template, caption_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    #[allow(clippy::single_match)]
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        match transition {
+            gst::StateChange::ReadyToPaused => {
+                *self.state.lock().unwrap() = State::default();
+                self.flow_combiner.lock().unwrap().reset();
+            }
+            _ => (),
+        }
+
+        let ret = self.parent_change_state(element, transition)?;
+
+        match transition {
+            gst::StateChange::PausedToReady => {
+                *self.state.lock().unwrap() = State::default();
+
+                let captionpad = self.captionpad.lock().unwrap().take();
+                if let Some(captionpad) = captionpad {
+                    self.flow_combiner.lock().unwrap().remove_pad(&captionpad);
+                    element.remove_pad(&captionpad).unwrap();
+                }
+            }
+            _ => (),
+        }
+
+        Ok(ret)
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+// Example command-line:
+//
+// gst-launch-1.0 filesrc location=input.ts ! tsdemux ! h264parse ! \
+//   video/x-h264,stream-format=byte-stream,alignment=au ! rsccextractor name=e \
+//   e.caption ! queue ! cea708tojson ! fakesink dump=true \
+//   e.src ! queue ! avdec_h264 ! autovideosink
+//
+// Captions are taken from the GstVideoCaptionMeta of the video buffers, or
+// from the A/53 SEI of byte-stream H.264/H.265 access units if there is no
+// such meta. The "caption" pad is only added once the first caption shows up.
+
+use gst::glib;
+use gst::prelude::*;
+
+mod imp;
+
+glib::wrapper! {
+    pub struct CCExtractor(ObjectSubclass<imp::CCExtractor>) @extends gst::Element, gst::Object;
+}
+
+pub fn register(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
+    gst::Element::register(
+        Some(plugin),
+        "rsccextractor",
+        gst::Rank::None,
+        CCExtractor::static_type(),
+    )
+}
@@ -7,6 +7,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use byteorder::{BigEndian, ByteOrder};
+use gst_video::VideoCaptionType;
 use std::fmt;
 
 #[allow(clippy::enum_variant_names)]
@@ -129,11 +130,17 @@ pub fn extract_cdp(mut data: &[u8]) -> Result<&[u8], ParseError> {
         });
     }
 
-    /* TODO: validate checksum */
+    /* The checksum is not enforced here, see cdp_checksum_valid() */
 
     Ok(&data[..len])
 }
 
+/// Validates the checksum of a CDP packet: all bytes of the packet,
+/// including the checksum in the footer, sum up to zero
+pub fn cdp_checksum_valid(cdp: &[u8]) -> bool {
+    cdp.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) == 0
+}
+
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?} at byte {}: {}", self.code, self.byte, self.msg)
@@ -141,3 +148,191 @@ impl fmt::Display for ParseError {
 }
 
 impl std::error::Error for ParseError {}
+
+/// A framerate allowed in CDP packets, SMPTE 334-2 table 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CdpFramerate {
+    pub fps_n: i32,
+    pub fps_d: i32,
+    /// The value of the cdp_frame_rate field
+    pub code: u8,
+    /// Number of cc_data triplets per CDP packet
+    pub cc_count: usize,
+    /// Number of those triplets carrying CEA-608 byte pairs
+    pub cea608_count: usize,
+}
+
+/* logic from ccconverter */
+pub const CDP_FRAMERATES: [CdpFramerate; 8] = [
+    CdpFramerate {
+        fps_n: 24000,
+        fps_d: 1001,
+        code: 0x1,
+        cc_count: 25,
+        cea608_count: 3,
+    },
+    CdpFramerate {
+        fps_n: 24,
+        fps_d: 1,
+        code: 0x2,
+        cc_count: 25,
+        cea608_count: 3,
+    },
+    CdpFramerate {
+        fps_n: 25,
+        fps_d: 1,
+        code: 0x3,
+        cc_count: 24,
+        cea608_count: 2,
+    },
+    CdpFramerate {
+        fps_n: 30000,
+        fps_d: 1001,
+        code: 0x4,
+        cc_count: 20,
+        cea608_count: 2,
+    },
+    CdpFramerate {
+        fps_n: 30,
+        fps_d: 1,
+        code: 0x5,
+        cc_count: 20,
+        cea608_count: 2,
+    },
+    CdpFramerate {
+        fps_n: 50,
+        fps_d: 1,
+        code: 0x6,
+        cc_count: 12,
+        cea608_count: 1,
+    },
+    CdpFramerate {
+        fps_n: 60000,
+        fps_d: 1001,
+        code: 0x7,
+        cc_count: 10,
+        cea608_count: 1,
+    },
+    CdpFramerate {
+        fps_n: 60,
+        fps_d: 1,
+        code: 0x8,
+        cc_count: 10,
+        cea608_count: 1,
+    },
+];
+
+pub fn cdp_framerate(fps_n: i32, fps_d: i32) -> Option<&'static CdpFramerate> {
+    CDP_FRAMERATES
+        .iter()
+        .find(|framerate| framerate.fps_n == fps_n && framerate.fps_d == fps_d)
+}
+
+/// Wraps `cc_data` triplets in a CDP packet, without timecode
+pub fn write_cdp(framerate: &CdpFramerate, sequence_counter: u16, cc_data: &[u8]) -> Vec<u8> {
+    assert!(cc_data.len() % 3 == 0);
+    assert!(cc_data.len() / 3 <= 0x1f);
+
+    let len = 7 + 2 + cc_data.len() + 4;
+    let mut cdp = Vec::with_capacity(len);
+
+    cdp.extend_from_slice(&[0x96, 0x69, len as u8, (framerate.code << 4) | 0x0f]);
+    /* cc_data present, caption service active, reserved */
+    cdp.push(0x43);
+    cdp.extend_from_slice(&sequence_counter.to_be_bytes());
+
+    cdp.push(0x72);
+    cdp.push(0xe0 | (cc_data.len() / 3) as u8);
+    cdp.extend_from_slice(cc_data);
+
+    cdp.push(0x74);
+    cdp.extend_from_slice(&sequence_counter.to_be_bytes());
+
+    let sum = cdp.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    cdp.push(0u8.wrapping_sub(sum));
+
+    cdp
+}
+
+/// Converts caption data of the given type to `cc_data` triplets
+pub fn to_cc_data(caption_type: VideoCaptionType, data: &[u8]) -> Result<Vec<u8>, ParseError> {
+    match caption_type {
+        VideoCaptionType::Cea608Raw => Ok(data
+            .chunks_exact(2)
+            .flat_map(|pair| [0xfc, pair[0], pair[1]])
+            .collect()),
+        VideoCaptionType::Cea608S3341a => Ok(data
+            .chunks_exact(3)
+            .flat_map(|triple| {
+                let field = if triple[0] & 0x80 != 0 { 0xfc } else { 0xfd };
+                [field, triple[1], triple[2]]
+            })
+            .collect()),
+        VideoCaptionType::Cea708Raw => Ok(data[..data.len() - data.len() % 3].to_vec()),
+        VideoCaptionType::Cea708Cdp => extract_cdp(data).map(|cc_data| cc_data.to_vec()),
+        _ => Err(ParseError {
+            code: ParseErrorCode::WrongLayout,
+            byte: 0,
+            msg: format!("unsupported caption type {:?}", caption_type),
+        }),
+    }
+}
+
+/// Extracts the valid field 1 CEA-608 byte pairs from `cc_data` triplets
+pub fn cc_data_to_cea608(cc_data: &[u8]) -> Vec<u8> {
+    cc_data
+        .chunks_exact(3)
+        .filter(|triple| triple[0] & 0x07 == 0x04)
+        .flat_map(|triple| [triple[1], triple[2]])
+        .collect()
+}
+
+/// Converts the valid CEA-608 triplets of `cc_data` to SMPTE 334-1 Annex A
+pub fn cc_data_to_s334_1a(cc_data: &[u8]) -> Vec<u8> {
+    cc_data
+        .chunks_exact(3)
+        .filter(|triple| triple[0] & 0x06 == 0x04)
+        .flat_map(|triple| {
+            let field = if triple[0] & 0x01 == 0 { 0x80 } else { 0x00 };
+            [field, triple[1], triple[2]]
+        })
+        .collect()
+}
+
+/// Lays out `cc_data` triplets the way a CDP packet for `framerate` expects
+/// them: the CEA-608 triplets first, then the CEA-708 ones, each padded to
+/// their share of the packet. Triplets beyond that share are dropped.
+pub fn cdp_cc_data(framerate: &CdpFramerate, cc_data: &[u8]) -> Vec<u8> {
+    let mut res = Vec::with_capacity(framerate.cc_count * 3);
+
+    res.extend(
+        cc_data
+            .chunks_exact(3)
+            .filter(|triple| triple[0] & 0x02 == 0)
+            .take(framerate.cea608_count)
+            .flatten(),
+    );
+
+    while res.len() < framerate.cea608_count * 3 {
+        if res.is_empty() {
+            res.extend_from_slice(&[0xf8, 0x80, 0x80]);
+        } else {
+            res.extend_from_slice(&[0xf9, 0x80, 0x80]);
+        }
+    }
+
+    /* CEA-708 padding is re-added below */
+    res.extend(
+        cc_data
+            .chunks_exact(3)
+            .filter(|triple| triple[0] & 0x06 == 0x06)
+            .take(framerate.cc_count - framerate.cea608_count)
+            .flatten(),
+    );
+
+    while res.len() < framerate.cc_count * 3 {
+        res.extend_from_slice(&[0xfa, 0x00, 0x00]);
+    }
+
+    res
+}
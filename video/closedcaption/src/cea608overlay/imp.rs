@@ -19,6 +19,8 @@ use pango::prelude::*;
 
 use crate::caption_frame::{CaptionFrame, Status};
 use crate::ccutils::extract_cdp;
+use crate::cea708utils::{self, Cea708Decoder};
+use crate::ttutils::TextStyle;
 
 static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
     gst::DebugCategory::new(
@@ -30,12 +32,18 @@ static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
 
 const DEFAULT_FIELD: i32 = -1;
 const DEFAULT_BLACK_BACKGROUND: bool = false;
+const DEFAULT_SERVICE_NUMBER: i32 = -1;
 
-#[derive(Debug)]
+/* Size of the CEA-608 character grid */
+const GRID_ROWS: i32 = 15;
+const GRID_COLUMNS: i32 = 32;
+
+#[derive(Debug, Clone)]
 struct Settings {
     field: i32,
     black_background: bool,
     timeout: Option<gst::ClockTime>,
+    service_number: i32,
 }
 
 impl Default for Settings {
@@ -44,16 +52,139 @@ impl Default for Settings {
             field: DEFAULT_FIELD,
             black_background: DEFAULT_BLACK_BACKGROUND,
             timeout: gst::ClockTime::NONE,
+            service_number: DEFAULT_SERVICE_NUMBER,
         }
     }
 }
 
+impl Settings {
+    /// The CEA-708 service to render, if any
+    fn cea708_service(&self) -> Option<u8> {
+        if self.service_number > 0 {
+            Some(self.service_number as u8)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Rgba {
+    r: f64,
+    g: f64,
+    b: f64,
+    a: f64,
+}
+
+impl Rgba {
+    const BLACK: Rgba = Rgba {
+        r: 0.0,
+        g: 0.0,
+        b: 0.0,
+        a: 1.0,
+    };
+
+    /// Returns the color and italics of a CEA-608 style
+    fn from_text_style(style: TextStyle) -> (Self, bool) {
+        let (r, g, b) = match style {
+            TextStyle::White | TextStyle::ItalicWhite => (1.0, 1.0, 1.0),
+            TextStyle::Green => (0.0, 1.0, 0.0),
+            TextStyle::Blue => (0.0, 0.0, 1.0),
+            TextStyle::Cyan => (0.0, 1.0, 1.0),
+            TextStyle::Red => (1.0, 0.0, 0.0),
+            TextStyle::Yellow => (1.0, 1.0, 0.0),
+            TextStyle::Magenta => (1.0, 0.0, 1.0),
+        };
+
+        (Rgba { r, g, b, a: 1.0 }, style == TextStyle::ItalicWhite)
+    }
+}
+
+impl From<cea708utils::Color> for Rgba {
+    fn from(color: cea708utils::Color) -> Self {
+        Rgba {
+            r: color.r as f64 / 3.0,
+            g: color.g as f64 / 3.0,
+            b: color.b as f64 / 3.0,
+            // Flashing is rendered as solid
+            a: match color.opacity {
+                0 | 1 => 1.0,
+                2 => 0.5,
+                _ => 0.0,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CellStyle {
+    foreground: Rgba,
+    background: Option<Rgba>,
+    italic: bool,
+    underline: bool,
+}
+
+/* Consecutive cells sharing the same style, positioned in pixels */
+#[derive(Debug)]
+struct Run {
+    x: i32,
+    y: i32,
+    cells: Vec<char>,
+    style: CellStyle,
+}
+
+/* The background of a CEA-708 window */
+#[derive(Debug)]
+struct Fill {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    color: Rgba,
+}
+
+/* What needs rendering for the current captions */
+#[derive(Debug, Default)]
+struct Scene {
+    fills: Vec<Fill>,
+    runs: Vec<Run>,
+}
+
+impl Scene {
+    fn push_cell(&mut self, x: i32, y: i32, cell_width: i32, c: char, style: CellStyle) {
+        if let Some(run) = self.runs.last_mut() {
+            if run.y == y && run.x + run.cells.len() as i32 * cell_width == x && run.style == style
+            {
+                run.cells.push(c);
+                return;
+            }
+        }
+
+        self.runs.push(Run {
+            x,
+            y,
+            cells: vec![c],
+            style,
+        });
+    }
+}
+
+/* The safe title area captions are displayed in, the center 80% of the frame */
+fn safe_area(video_info: &gst_video::VideoInfo) -> (i32, i32, i32, i32) {
+    let width = video_info.width() as i32;
+    let height = video_info.height() as i32;
+
+    (width / 10, height / 10, width * 8 / 10, height * 8 / 10)
+}
+
 struct State {
     video_info: Option<gst_video::VideoInfo>,
     layout: Option<pango::Layout>,
+    cell_width: i32,
+    cell_height: i32,
     caption_frame: CaptionFrame,
+    cea708_decoder: Cea708Decoder,
     composition: Option<gst_video::VideoOverlayComposition>,
-    left_alignment: i32,
     attach: bool,
     selected_field: Option<u8>,
     last_cc_pts: Option<gst::ClockTime>,
@@ -69,9 +200,11 @@ impl Default for State {
         Self {
             video_info: None,
             layout: None,
+            cell_width: 0,
+            cell_height: 0,
             caption_frame: CaptionFrame::default(),
+            cea708_decoder: Cea708Decoder::new(),
             composition: None,
-            left_alignment: 0,
             attach: false,
             selected_field: None,
             last_cc_pts: gst::ClockTime::NONE,
@@ -88,10 +221,10 @@ pub struct Cea608Overlay {
 
 impl Cea608Overlay {
     // FIXME: we want to render the text in the largest 32 x 15 characters
-    // that will fit the viewport. This is a truly terrible way to determine
-    // the appropriate font size, but we only need to run that on resolution
-    // changes, and the API that would allow us to precisely control the
-    // line height has not yet been exposed by the bindings:
+    // that will fit the safe title area. This is a truly terrible way to
+    // determine the appropriate font size, but we only need to run that on
+    // resolution changes, and the API that would allow us to precisely control
+    // the line height has not yet been exposed by the bindings:
     //
     // https://blogs.gnome.org/mclasen/2019/07/27/more-text-rendering-updates/
     //
@@ -130,49 +263,208 @@ impl Cea608Overlay {
         layout.set_alignment(pango::Alignment::Left);
         let mut font_desc = pango::FontDescription::from_string("monospace");
 
+        let (_, _, safe_width, safe_height) = safe_area(video_info);
+        let grid_text =
+            "12345678901234567890123456789012\n2\n3\n4\n5\n6\n7\n8\n9\n0\n1\n2\n3\n4\n5";
+
         let mut font_size = 1;
-        let mut left_alignment = 0;
         loop {
             font_desc.set_size(font_size * pango::SCALE);
             layout.set_font_description(Some(&font_desc));
-            layout.set_text(
-                "12345678901234567890123456789012\n2\n3\n4\n5\n6\n7\n8\n9\n0\n1\n2\n3\n4\n5",
-            );
+            layout.set_text(grid_text);
             let (_ink_rect, logical_rect) = layout.extents();
-            if logical_rect.width() > video_info.width() as i32 * pango::SCALE
-                || logical_rect.height() > video_info.height() as i32 * pango::SCALE
+            if logical_rect.width() > safe_width * pango::SCALE
+                || logical_rect.height() > safe_height * pango::SCALE
             {
-                font_desc.set_size((font_size - 1) * pango::SCALE);
-                layout.set_font_description(Some(&font_desc));
                 break;
             }
-            left_alignment = (video_info.width() as i32 - logical_rect.width() / pango::SCALE) / 2;
             font_size += 1;
         }
 
-        if self.settings.lock().unwrap().black_background {
-            let attrs = pango::AttrList::new();
-            let attr = pango::AttrColor::new_background(0, 0, 0);
-            attrs.insert(attr);
-            layout.set_attributes(Some(&attrs));
-        }
+        font_desc.set_size(std::cmp::max(font_size - 1, 1) * pango::SCALE);
+        layout.set_font_description(Some(&font_desc));
+        layout.set_text(grid_text);
+        let (_ink_rect, logical_rect) = layout.extents();
 
-        state.left_alignment = left_alignment;
+        state.cell_width = std::cmp::max(logical_rect.width() / pango::SCALE / GRID_COLUMNS, 1);
+        state.cell_height = std::cmp::max(logical_rect.height() / pango::SCALE / GRID_ROWS, 1);
         state.layout = Some(layout);
 
         Ok(gst::FlowSuccess::Ok)
     }
 
-    fn overlay_text(&self, element: &super::Cea608Overlay, text: &str, state: &mut State) {
+    /* The CEA-608 grid is centered in the frame */
+    fn cea608_scene(&self, state: &State, black_background: bool) -> Scene {
+        let video_info = state.video_info.as_ref().unwrap();
+        let (cell_width, cell_height) = (state.cell_width, state.cell_height);
+        let left = (video_info.width() as i32 - GRID_COLUMNS * cell_width) / 2;
+        let top = (video_info.height() as i32 - GRID_ROWS * cell_height) / 2;
+
+        let mut scene = Scene::default();
+
+        for row in 0..GRID_ROWS {
+            for col in 0..GRID_COLUMNS {
+                if let Some((c, style, underline)) =
+                    state.caption_frame.read_char(row as u32, col as u32)
+                {
+                    let (foreground, italic) = Rgba::from_text_style(style);
+
+                    scene.push_cell(
+                        left + col * cell_width,
+                        top + row * cell_height,
+                        cell_width,
+                        c,
+                        CellStyle {
+                            foreground,
+                            background: if black_background {
+                                Some(Rgba::BLACK)
+                            } else {
+                                None
+                            },
+                            italic,
+                            underline,
+                        },
+                    );
+                }
+            }
+        }
+
+        scene
+    }
+
+    /* CEA-708 windows are positioned by their anchor in the safe title area,
+     * and use the cell size of the CEA-608 grid */
+    fn cea708_scene(&self, state: &State, service_number: u8) -> Scene {
+        let mut scene = Scene::default();
+
+        let service = match state.cea708_decoder.service(service_number) {
+            Some(service) => service,
+            None => return scene,
+        };
+
+        let video_info = state.video_info.as_ref().unwrap();
+        let (safe_left, safe_top, safe_width, safe_height) = safe_area(video_info);
+        let (cell_width, cell_height) = (state.cell_width, state.cell_height);
+
+        for window in service.visible_windows() {
+            let width = window.column_count as i32 * cell_width;
+            let height = window.row_count as i32 * cell_height;
+
+            let (anchor_vertical, anchor_horizontal) = window.anchor();
+            let x = safe_left + (anchor_horizontal * safe_width as f64) as i32
+                - match window.anchor_point % 3 {
+                    0 => 0,
+                    1 => width / 2,
+                    _ => width,
+                };
+            let y = safe_top + (anchor_vertical * safe_height as f64) as i32
+                - match window.anchor_point / 3 {
+                    0 => 0,
+                    1 => height / 2,
+                    _ => height,
+                };
+            let x = x.clamp(0, std::cmp::max(video_info.width() as i32 - width, 0));
+            let y = y.clamp(0, std::cmp::max(video_info.height() as i32 - height, 0));
+
+            let fill_color = window.attributes.fill_color;
+            if fill_color.opacity != 3 {
+                scene.fills.push(Fill {
+                    x,
+                    y,
+                    width,
+                    height,
+                    color: fill_color.into(),
+                });
+            }
+
+            for (row_idx, row) in window.rows[..window.row_count].iter().enumerate() {
+                let cells = &row[..window.column_count];
+
+                let (first, last) = match (
+                    cells.iter().position(|cell| cell.is_some()),
+                    cells.iter().rposition(|cell| cell.is_some()),
+                ) {
+                    (Some(first), Some(last)) => (first as i32, last as i32),
+                    _ => continue,
+                };
+
+                let column_count = window.column_count as i32;
+                let shift = match window.attributes.justify {
+                    // Right
+                    1 => column_count - 1 - last,
+                    // Center
+                    2 => (column_count - (last - first + 1)) / 2 - first,
+                    // Left and full
+                    _ => 0,
+                };
+
+                for (col, cell) in cells.iter().enumerate() {
+                    let cell = match cell {
+                        Some(cell) => cell,
+                        None => continue,
+                    };
+
+                    let background = cell.pen.color.background;
+
+                    scene.push_cell(
+                        x + (col as i32 + shift) * cell_width,
+                        y + row_idx as i32 * cell_height,
+                        cell_width,
+                        cell.c,
+                        CellStyle {
+                            foreground: cell.pen.color.foreground.into(),
+                            background: if background.opacity != 3 {
+                                Some(background.into())
+                            } else {
+                                None
+                            },
+                            italic: cell.pen.attributes.italics,
+                            underline: cell.pen.attributes.underline,
+                        },
+                    );
+                }
+            }
+        }
+
+        scene
+    }
+
+    fn overlay_scene(&self, element: &super::Cea608Overlay, scene: &Scene, state: &mut State) {
         let video_info = state.video_info.as_ref().unwrap();
         let layout = state.layout.as_ref().unwrap();
-        layout.set_text(text);
-        let (_ink_rect, logical_rect) = layout.extents();
-        let height = logical_rect.height() / pango::SCALE;
-        let width = logical_rect.width() / pango::SCALE;
+        let (cell_width, cell_height) = (state.cell_width, state.cell_height);
+
+        let (left, top, right, bottom) = scene
+            .fills
+            .iter()
+            .map(|fill| (fill.x, fill.y, fill.width, fill.height))
+            .chain(scene.runs.iter().map(|run| {
+                (
+                    run.x,
+                    run.y,
+                    run.cells.len() as i32 * cell_width,
+                    cell_height,
+                )
+            }))
+            .fold(
+                (i32::MAX, i32::MAX, i32::MIN, i32::MIN),
+                |(left, top, right, bottom), (x, y, width, height)| {
+                    (
+                        left.min(x),
+                        top.min(y),
+                        right.max(x + width),
+                        bottom.max(y + height),
+                    )
+                },
+            );
+
+        let left = left.max(0);
+        let top = top.max(0);
+        let width = right.min(video_info.width() as i32) - left;
+        let height = bottom.min(video_info.height() as i32) - top;
 
         // No text actually needs rendering
-        if width == 0 || height == 0 {
+        if width <= 0 || height <= 0 {
             state.composition = None;
             return;
         }
@@ -213,23 +505,73 @@ impl Cea608Overlay {
             cr.set_source_rgba(0.0, 0.0, 0.0, 0.0);
             cr.paint().ok()?;
 
-            // Render text outline
-            cr.save().ok()?;
             cr.set_operator(cairo::Operator::Over);
+            cr.translate(-left as f64, -top as f64);
+
+            // Render window backgrounds
+            for fill in &scene.fills {
+                let color = fill.color;
+                cr.set_source_rgba(color.r, color.g, color.b, color.a);
+                cr.rectangle(
+                    fill.x as f64,
+                    fill.y as f64,
+                    fill.width as f64,
+                    fill.height as f64,
+                );
+                cr.fill().ok()?;
+            }
 
-            cr.set_source_rgba(0.0, 0.0, 0.0, 1.0);
+            let font_desc = layout.font_description()?;
+
+            for run in &scene.runs {
+                let style = run.style;
+
+                // Render cell backgrounds
+                if let Some(color) = style.background {
+                    cr.set_source_rgba(color.r, color.g, color.b, color.a);
+                    cr.rectangle(
+                        run.x as f64,
+                        run.y as f64,
+                        (run.cells.len() as i32 * cell_width) as f64,
+                        cell_height as f64,
+                    );
+                    cr.fill().ok()?;
+                }
+
+                let mut run_font_desc = font_desc.clone();
+                if style.italic {
+                    run_font_desc.set_style(pango::Style::Italic);
+                }
+                layout.set_font_description(Some(&run_font_desc));
+
+                let attrs = pango::AttrList::new();
+                if style.underline {
+                    attrs.insert(pango::AttrInt::new_underline(pango::Underline::Single));
+                }
+                layout.set_attributes(Some(&attrs));
+
+                // Each character is rendered in its own cell of the grid
+                for (idx, c) in run.cells.iter().enumerate() {
+                    let x = (run.x + idx as i32 * cell_width) as f64;
 
-            pangocairo::functions::layout_path(&cr, layout);
-            cr.stroke().ok()?;
-            cr.restore().ok()?;
+                    layout.set_text(c.encode_utf8(&mut [0; 4]));
 
-            // Render text
-            cr.save().ok()?;
-            cr.set_source_rgba(255.0, 255.0, 255.0, 1.0);
+                    // Render text outline
+                    cr.set_source_rgba(0.0, 0.0, 0.0, style.foreground.a);
+                    cr.move_to(x, run.y as f64);
+                    pangocairo::functions::layout_path(&cr, layout);
+                    cr.stroke().ok()?;
 
-            pangocairo::functions::show_layout(&cr, layout);
+                    // Render text
+                    let color = style.foreground;
+                    cr.set_source_rgba(color.r, color.g, color.b, color.a);
+                    cr.move_to(x, run.y as f64);
+                    pangocairo::functions::show_layout(&cr, layout);
+                }
+            }
 
-            cr.restore().ok()?;
+            layout.set_font_description(Some(&font_desc));
+            layout.set_attributes(None);
             drop(cr);
 
             // Safety: The surface still owns a mutable reference to the buffer but our reference
@@ -259,8 +601,8 @@ impl Cea608Overlay {
 
         let rect = gst_video::VideoOverlayRectangle::new_raw(
             &buffer,
-            state.left_alignment,
-            (video_info.height() as i32 - height) / 2,
+            left,
+            top,
             width as u32,
             height as u32,
             gst_video::VideoOverlayFormatFlags::PREMULTIPLIED_ALPHA,
@@ -322,11 +664,27 @@ impl Cea608Overlay {
         }
     }
 
+    fn decode_cea608(
+        &self,
+        element: &super::Cea608Overlay,
+        state: &mut State,
+        settings: &Settings,
+        cc_data: u16,
+        pts: gst::ClockTime,
+    ) {
+        if let Ok(Status::Ready) = state.caption_frame.decode(cc_data, 0.0) {
+            let scene = self.cea608_scene(state, settings.black_background);
+            self.overlay_scene(element, &scene, state);
+        }
+
+        self.reset_timeout(state, pts);
+    }
+
     fn decode_cc_data(
         &self,
-        pad: &gst::Pad,
         element: &super::Cea608Overlay,
         state: &mut State,
+        settings: &Settings,
         data: &[u8],
         pts: gst::ClockTime,
     ) {
@@ -334,6 +692,18 @@ impl Cea608Overlay {
             gst::warning!(CAT, "cc_data length is not a multiple of 3, truncating");
         }
 
+        if let Some(service_number) = settings.cea708_service() {
+            let updated = state.cea708_decoder.push_cc_data(data);
+
+            if updated.contains(&service_number) {
+                let scene = self.cea708_scene(state, service_number);
+                self.overlay_scene(element, &scene, state);
+                self.reset_timeout(state, pts);
+            }
+
+            return;
+        }
+
         for triple in data.chunks_exact(3) {
             let cc_valid = (triple[0] & 0x04) == 0x04;
             let cc_type = triple[0] & 0x03;
@@ -351,26 +721,13 @@ impl Cea608Overlay {
                     }
 
                     if Some(cc_type) == state.selected_field {
-                        if let Ok(Status::Ready) = state
-                            .caption_frame
-                            .decode((triple[1] as u16) << 8 | triple[2] as u16, 0.0)
-                        {
-                            let text = match state.caption_frame.to_text(true) {
-                                Ok(text) => text,
-                                Err(_) => {
-                                    gst::error!(
-                                        CAT,
-                                        obj: pad,
-                                        "Failed to convert caption frame to text"
-                                    );
-                                    continue;
-                                }
-                            };
-
-                            self.overlay_text(element, &text, state);
-                        }
-
-                        self.reset_timeout(state, pts);
+                        self.decode_cea608(
+                            element,
+                            state,
+                            settings,
+                            (triple[1] as u16) << 8 | triple[2] as u16,
+                            pts,
+                        );
                     }
                 } else {
                     break;
@@ -381,9 +738,9 @@ impl Cea608Overlay {
 
     fn decode_s334_1a(
         &self,
-        pad: &gst::Pad,
         element: &super::Cea608Overlay,
         state: &mut State,
+        settings: &Settings,
         data: &[u8],
         pts: gst::ClockTime,
     ) {
@@ -392,7 +749,8 @@ impl Cea608Overlay {
         }
 
         for triple in data.chunks_exact(3) {
-            let cc_type = triple[0] & 0x01;
+            // The first bit is set for field 1, which is field 0 here
+            let cc_type = if triple[0] & 0x80 != 0 { 0 } else { 1 };
             if state.selected_field.is_none() {
                 state.selected_field = Some(cc_type);
                 gst::info!(
@@ -404,22 +762,13 @@ impl Cea608Overlay {
             }
 
             if Some(cc_type) == state.selected_field {
-                if let Ok(Status::Ready) = state
-                    .caption_frame
-                    .decode((triple[1] as u16) << 8 | triple[2] as u16, 0.0)
-                {
-                    let text = match state.caption_frame.to_text(true) {
-                        Ok(text) => text,
-                        Err(_) => {
-                            gst::error!(CAT, obj: pad, "Failed to convert caption frame to text");
-                            continue;
-                        }
-                    };
-
-                    self.overlay_text(element, &text, state);
-                }
-
-                self.reset_timeout(state, pts);
+                self.decode_cea608(
+                    element,
+                    state,
+                    settings,
+                    (triple[1] as u16) << 8 | triple[2] as u16,
+                    pts,
+                );
             }
         }
     }
@@ -441,6 +790,7 @@ impl Cea608Overlay {
             gst::FlowError::Error
         })?;
 
+        let settings = self.settings.lock().unwrap().clone();
         let mut state = self.state.lock().unwrap();
 
         if self.srcpad.check_reconfigure() {
@@ -455,7 +805,7 @@ impl Cea608Overlay {
             if meta.caption_type() == gst_video::VideoCaptionType::Cea708Cdp {
                 match extract_cdp(meta.data()) {
                     Ok(data) => {
-                        self.decode_cc_data(pad, element, &mut state, data, pts);
+                        self.decode_cc_data(element, &mut state, &settings, data, pts);
                     }
                     Err(e) => {
                         gst::warning!(CAT, "{}", &e.to_string());
@@ -463,38 +813,28 @@ impl Cea608Overlay {
                     }
                 }
             } else if meta.caption_type() == gst_video::VideoCaptionType::Cea708Raw {
-                self.decode_cc_data(pad, element, &mut state, meta.data(), pts);
+                self.decode_cc_data(element, &mut state, &settings, meta.data(), pts);
+            } else if settings.cea708_service().is_some() {
+                // CEA-608 only streams have no CEA-708 services
+                continue;
             } else if meta.caption_type() == gst_video::VideoCaptionType::Cea608S3341a {
-                self.decode_s334_1a(pad, element, &mut state, meta.data(), pts);
+                self.decode_s334_1a(element, &mut state, &settings, meta.data(), pts);
             } else if meta.caption_type() == gst_video::VideoCaptionType::Cea608Raw {
                 let data = meta.data();
                 assert!(data.len() % 2 == 0);
                 for i in 0..data.len() / 2 {
-                    if let Ok(Status::Ready) = state
-                        .caption_frame
-                        .decode((data[i * 2] as u16) << 8 | data[i * 2 + 1] as u16, 0.0)
-                    {
-                        let text = match state.caption_frame.to_text(true) {
-                            Ok(text) => text,
-                            Err(_) => {
-                                gst::error!(
-                                    CAT,
-                                    obj: pad,
-                                    "Failed to convert caption frame to text"
-                                );
-                                continue;
-                            }
-                        };
-
-                        self.overlay_text(element, &text, &mut state);
-                    }
-
-                    self.reset_timeout(&mut state, pts);
+                    self.decode_cea608(
+                        element,
+                        &mut state,
+                        &settings,
+                        (data[i * 2] as u16) << 8 | data[i * 2 + 1] as u16,
+                        pts,
+                    );
                 }
             }
         }
 
-        if let Some(timeout) = self.settings.lock().unwrap().timeout {
+        if let Some(timeout) = settings.timeout {
             if let Some(interval) = pts.opt_saturating_sub(state.last_cc_pts) {
                 if interval > timeout {
                     gst::info!(CAT, obj: element, "Reached timeout, clearing overlay");
@@ -550,6 +890,7 @@ impl Cea608Overlay {
             EventView::FlushStop(..) => {
                 let mut state = self.state.lock().unwrap();
                 state.caption_frame = CaptionFrame::default();
+                state.cea708_decoder = Cea708Decoder::new();
                 state.composition = None;
                 pad.event_default(Some(element), event)
             }
@@ -616,10 +957,19 @@ impl ObjectImpl for Cea608Overlay {
                 glib::ParamSpecBoolean::new(
                     "black-background",
                     "Black background",
-                    "Whether a black background should be drawn behind text",
+                    "Whether a black background should be drawn behind CEA-608 text",
                     DEFAULT_BLACK_BACKGROUND,
                     glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_PLAYING,
                 ),
+                glib::ParamSpecInt::new(
+                    "service-number",
+                    "Service Number",
+                    "The CEA-708 service to render instead of CEA-608, when available (-1=CEA-608)",
+                    -1,
+                    cea708utils::MAX_SERVICE_NUMBER as i32,
+                    DEFAULT_SERVICE_NUMBER,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_PLAYING,
+                ),
                 glib::ParamSpecUInt64::new(
                     "timeout",
                     "Timeout",
@@ -655,10 +1005,13 @@ impl ObjectImpl for Cea608Overlay {
             }
             "black-background" => {
                 let mut settings = self.settings.lock().unwrap();
-                let mut state = self.state.lock().unwrap();
 
                 settings.black_background = value.get().expect("type checked upstream");
-                let _ = state.layout.take();
+            }
+            "service-number" => {
+                let mut settings = self.settings.lock().unwrap();
+
+                settings.service_number = value.get().expect("type checked upstream");
             }
             "timeout" => {
                 let mut settings = self.settings.lock().unwrap();
@@ -684,6 +1037,10 @@ impl ObjectImpl for Cea608Overlay {
                 let settings = self.settings.lock().unwrap();
                 settings.black_background.to_value()
             }
+            "service-number" => {
+                let settings = self.settings.lock().unwrap();
+                settings.service_number.to_value()
+            }
             "timeout" => {
                 let settings = self.settings.lock().unwrap();
                 if let Some(timeout) = settings.timeout {
@@ -711,7 +1068,7 @@ impl ElementImpl for Cea608Overlay {
             gst::subclass::ElementMetadata::new(
                 "Cea 608 overlay",
                 "Video/Overlay/Subtitle",
-                "Renders CEA 608 closed caption meta over raw video frames",
+                "Renders CEA 608 or CEA 708 closed caption meta over raw video frames",
                 "Mathieu Duponchelle <mathieu@centricular.com>",
             )
         });
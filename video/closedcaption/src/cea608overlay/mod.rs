@@ -11,6 +11,12 @@
 // gst-launch-1.0 cccombiner name=ccc ! cea608overlay ! autovideosink \
 //   videotestsrc ! video/x-raw, width=1280, height=720 ! queue ! ccc.sink \
 //   filesrc location=input.srt ! subparse ! tttocea608 ! queue ! ccc.caption
+//
+// Or, to render the windows of the first CEA-708 service instead:
+//
+// gst-launch-1.0 cccombiner name=ccc ! cea608overlay service-number=1 ! autovideosink \
+//   videotestsrc ! video/x-raw, width=1280, height=720 ! queue ! ccc.sink \
+//   filesrc location=input.srt ! subparse ! tttocea708 ! queue ! ccc.caption
 
 use gst::glib;
 use gst::prelude::*;
@@ -8,9 +8,11 @@
 
 // TODO:
 //
-//  * For now the element completely discards data for channel 1,
-//    as it has never really been used. Ideally it should handle it,
-//    not critical.
+//  * Only one of the four caption channels is decoded at a time, the
+//    Text channels (T1 - T4) are discarded.
+//
+//  * XDS packets are only extracted from field 2, and only the most common
+//    types are interpreted, see xds.rs.
 //
 //  * A few control commands aren't supported, see TODO in
 //    decode_control. The only notable command is delete_to_end_of_row,
@@ -30,7 +32,8 @@ use gst::prelude::*;
 use gst::subclass::prelude::*;
 
 use crate::ffi;
-use crate::ttutils::{Cea608Mode, Chunk, Line, Lines, TextStyle};
+use crate::ttutils::{Cea608Channel, Cea608Mode, Chunk, Line, Lines, TextStyle};
+use crate::xds::{XdsDecoder, XdsPacket, XdsStatus};
 
 use atomic_refcell::AtomicRefCell;
 
@@ -40,6 +43,13 @@ use std::collections::BTreeMap;
 use std::sync::Mutex;
 
 const DEFAULT_UNBUFFERED: bool = false;
+const DEFAULT_CHANNEL: Cea608Channel = Cea608Channel::Cc1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Raw,
+    S334_1a,
+}
 
 #[derive(Debug)]
 struct TimestampedLines {
@@ -183,12 +193,14 @@ impl From<Row> for Line {
 #[derive(Clone)]
 struct Settings {
     unbuffered: bool,
+    channel: Cea608Channel,
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
             unbuffered: DEFAULT_UNBUFFERED,
+            channel: DEFAULT_CHANNEL,
         }
     }
 }
@@ -205,6 +217,13 @@ struct State {
     cursor: Cursor,
     pending_lines: Option<TimestampedLines>,
     settings: Settings,
+    format: Option<Format>,
+    /* Data channel of the last channel-bearing code, basic
+     * characters inherit it */
+    data_channel: i32,
+    xds: XdsDecoder,
+    program_name: Option<String>,
+    network_name: Option<String>,
 }
 
 impl Default for State {
@@ -226,6 +245,11 @@ impl Default for State {
             },
             pending_lines: None,
             settings: Settings::default(),
+            format: None,
+            data_channel: 0,
+            xds: XdsDecoder::new(),
+            program_name: None,
+            network_name: None,
         }
     }
 }
@@ -274,6 +298,16 @@ fn is_control(cc_data: u16) -> bool {
     0x1420 == (0x7670 & cc_data) || 0x1720 == (0x7770 & cc_data)
 }
 
+/* Control, preamble, midrow and special / extended character codes
+ * carry the data channel, the first byte being in the 0x10 - 0x1F range */
+fn data_channel(cc_data: u16) -> Option<i32> {
+    if 0x1000 == (0x7000 & cc_data) {
+        Some(((cc_data & 0x0800) >> 11) as i32)
+    } else {
+        None
+    }
+}
+
 fn parse_control(cc_data: u16) -> (ffi::eia608_control_t, i32) {
     unsafe {
         let mut chan = 0;
@@ -288,7 +322,6 @@ struct Preamble {
     row: i32,
     col: i32,
     style: TextStyle,
-    chan: i32,
     underline: i32,
 }
 
@@ -313,14 +346,12 @@ fn parse_preamble(cc_data: u16) -> Preamble {
             row,
             col,
             style: style.into(),
-            chan,
             underline,
         }
     }
 }
 
 struct MidrowChange {
-    chan: i32,
     style: TextStyle,
     underline: bool,
 }
@@ -334,14 +365,13 @@ fn parse_midrowchange(cc_data: u16) -> MidrowChange {
         ffi::eia608_parse_midrowchange(cc_data, &mut chan, &mut style, &mut underline);
 
         MidrowChange {
-            chan,
             style: style.into(),
             underline: underline > 0,
         }
     }
 }
 
-fn eia608_to_utf8(cc_data: u16) -> (Option<char>, Option<char>, i32) {
+fn eia608_to_utf8(cc_data: u16) -> (Option<char>, Option<char>) {
     unsafe {
         let mut chan = 0;
         let mut char1 = [0u8; 5usize];
@@ -378,7 +408,7 @@ fn eia608_to_utf8(cc_data: u16) -> (Option<char>, Option<char>, i32) {
             None
         };
 
-        (char1, char2, chan)
+        (char1, char2)
     }
 }
 
@@ -555,10 +585,6 @@ impl State {
     ) -> Option<TimestampedLines> {
         let preamble = parse_preamble(cc_data);
 
-        if preamble.chan != 0 {
-            return None;
-        }
-
         gst::log!(CAT, obj: element, "preamble: {:?}", preamble);
 
         let drain_roll_up = self.cursor.row != preamble.row as u32;
@@ -647,10 +673,6 @@ impl State {
 
         gst::log!(CAT, obj: element, "Command for CC {}", chan);
 
-        if chan != 0 {
-            return None;
-        }
-
         match cmd {
             ffi::eia608_control_t_eia608_control_resume_direct_captioning => {
                 return self.update_mode(element, Cea608Mode::PaintOn);
@@ -762,11 +784,7 @@ impl State {
     }
 
     fn decode_text(&mut self, element: &super::Cea608ToJson, cc_data: u16) {
-        let (char1, char2, chan) = eia608_to_utf8(cc_data);
-
-        if chan != 0 {
-            return;
-        }
+        let (char1, char2) = eia608_to_utf8(cc_data);
 
         if let Some(row) = self.rows.get_mut(&self.cursor.row) {
             if is_westeu(cc_data) {
@@ -797,9 +815,7 @@ impl State {
         if let Some(row) = self.rows.get_mut(&self.cursor.row) {
             let midrowchange = parse_midrowchange(cc_data);
 
-            if midrowchange.chan == 0 {
-                row.push_midrow(&mut self.cursor, midrowchange.style, midrowchange.underline);
-            }
+            row.push_midrow(&mut self.cursor, midrowchange.style, midrowchange.underline);
         }
     }
 
@@ -819,6 +835,15 @@ impl State {
         self.current_pts = pts;
         self.current_duration = duration;
 
+        if let Some(chan) = data_channel(cc_data) {
+            self.data_channel = chan;
+        }
+
+        if self.data_channel != self.settings.channel.data_channel() {
+            gst::trace!(CAT, obj: element, "Data for CC {}, ignoring", self.data_channel);
+            return None;
+        }
+
         if is_xds(cc_data) {
             gst::log!(CAT, obj: element, "XDS, ignoring");
         } else if is_control(cc_data) {
@@ -843,6 +868,44 @@ impl State {
         }
         None
     }
+
+    /* Returns the updated tags when the packet changes them */
+    fn handle_xds_packet(
+        &mut self,
+        element: &super::Cea608ToJson,
+        packet: &XdsPacket,
+    ) -> Option<gst::TagList> {
+        gst::debug!(CAT, obj: element, "XDS packet: {:?}", packet);
+
+        if let Some(name) = packet.program_name() {
+            if self.program_name.as_ref() == Some(&name) {
+                return None;
+            }
+            self.program_name = Some(name);
+        } else if let Some(name) = packet.network_name() {
+            if self.network_name.as_ref() == Some(&name) {
+                return None;
+            }
+            self.network_name = Some(name);
+        } else {
+            return None;
+        }
+
+        let mut tags = gst::TagList::new();
+        {
+            let tags = tags.get_mut().unwrap();
+
+            if let Some(ref name) = self.program_name {
+                tags.add::<gst::tags::Title>(&name.as_str(), gst::TagMergeMode::Replace);
+            }
+
+            if let Some(ref name) = self.network_name {
+                tags.add::<gst::tags::Organization>(&name.as_str(), gst::TagMergeMode::Replace);
+            }
+        }
+
+        Some(tags)
+    }
 }
 
 impl Cea608ToJson {
@@ -903,30 +966,87 @@ impl Cea608ToJson {
             gst::FlowError::Error
         })?;
 
-        if data.len() < 2 {
+        let field = state.settings.channel.field();
+
+        /* The field of each byte pair, raw data is assumed to
+         * belong to the field of the selected channel */
+        let cc_data = match state.format {
+            Some(Format::S334_1a) => data
+                .chunks_exact(3)
+                .map(|triple| {
+                    let cc_field = if triple[0] & 0x80 != 0 { 1 } else { 2 };
+                    (cc_field, (triple[1] as u16) << 8 | triple[2] as u16)
+                })
+                .collect::<Vec<_>>(),
+            _ if data.len() >= 2 => vec![(field, (data[0] as u16) << 8 | data[1] as u16)],
+            _ => vec![],
+        };
+
+        if cc_data.is_empty() {
             gst::error!(CAT, obj: pad, "Invalid closed caption packet size");
 
             return Ok(gst::FlowSuccess::Ok);
         }
 
-        let cc_data = (data[0] as u16) << 8 | data[1] as u16;
+        let mut outputs = vec![];
+        let mut messages = vec![];
+        let mut tags = None;
+
+        for (cc_field, cc_data) in cc_data {
+            if cc_field == 2 {
+                match state.xds.decode(cc_data) {
+                    XdsStatus::NotXds => (),
+                    XdsStatus::Partial => continue,
+                    XdsStatus::Packet(packet) => {
+                        if let Some(updated_tags) = state.handle_xds_packet(element, &packet) {
+                            tags = Some(updated_tags);
+                        }
+                        messages.push(packet.to_structure(pts));
+                        continue;
+                    }
+                    XdsStatus::Invalid => {
+                        gst::debug!(CAT, obj: pad, "Discarding invalid XDS packet");
+                        continue;
+                    }
+                }
+            }
 
-        dump(element, cc_data, pts, duration);
+            if cc_field != field {
+                continue;
+            }
+
+            dump(element, cc_data, pts, duration);
 
-        if let Some(lines) = state.handle_cc_data(element, pts, duration, cc_data) {
-            drop(state);
-            self.output(element, lines)
-        } else if state.settings.unbuffered {
-            drop(state);
+            if let Some(lines) = state.handle_cc_data(element, pts, duration, cc_data) {
+                outputs.push(lines);
+            }
+        }
+
+        let unbuffered = state.settings.unbuffered;
+        drop(state);
+        drop(data);
+
+        for s in messages {
+            let _ = element.post_message(gst::message::Element::builder(s).src(element).build());
+        }
+
+        if let Some(tags) = tags {
+            self.srcpad.push_event(gst::event::Tag::new(tags));
+        }
+
+        if outputs.is_empty() && unbuffered {
             self.srcpad.push_event(
                 gst::event::Gap::builder(pts.unwrap())
                     .duration(duration)
                     .build(),
             );
-            Ok(gst::FlowSuccess::Ok)
-        } else {
-            Ok(gst::FlowSuccess::Ok)
         }
+
+        for lines in outputs {
+            self.output(element, lines)?;
+        }
+
+        Ok(gst::FlowSuccess::Ok)
     }
 
     fn sink_event(&self, pad: &gst::Pad, element: &super::Cea608ToJson, event: gst::Event) -> bool {
@@ -934,7 +1054,24 @@ impl Cea608ToJson {
 
         gst::log!(CAT, obj: pad, "Handling event {:?}", event);
         match event.view() {
-            EventView::Caps(..) => {
+            EventView::Caps(c) => {
+                let format = c
+                    .caps()
+                    .structure(0)
+                    .and_then(|s| s.get::<&str>("format").ok())
+                    .and_then(|format| match format {
+                        "raw" => Some(Format::Raw),
+                        "s334-1a" => Some(Format::S334_1a),
+                        _ => None,
+                    });
+
+                if format.is_none() {
+                    gst::error!(CAT, obj: pad, "Invalid caps {:?}", c.caps());
+                    return false;
+                }
+
+                self.state.borrow_mut().format = format;
+
                 // We send our own caps downstream
                 let caps = gst::Caps::builder("application/x-json")
                     .field("format", "cea608")
@@ -944,8 +1081,10 @@ impl Cea608ToJson {
             EventView::FlushStop(..) => {
                 let mut state = self.state.borrow_mut();
                 let old_settings = state.settings.clone();
+                let old_format = state.format;
                 *state = State::default();
                 state.settings = old_settings;
+                state.format = old_format;
                 drop(state);
                 pad.event_default(Some(element), event)
             }
@@ -1014,14 +1153,25 @@ impl ObjectImpl for Cea608ToJson {
 
     fn properties() -> &'static [glib::ParamSpec] {
         static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
-            vec![glib::ParamSpecBoolean::new(
-                "unbuffered",
-                "Unbuffered",
-                "Whether captions should be output at display time, \
+            vec![
+                glib::ParamSpecBoolean::new(
+                    "unbuffered",
+                    "Unbuffered",
+                    "Whether captions should be output at display time, \
                      instead of waiting to determine durations. Useful with live input",
-                DEFAULT_UNBUFFERED,
-                glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
-            )]
+                    DEFAULT_UNBUFFERED,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecEnum::new(
+                    "channel",
+                    "Channel",
+                    "The caption channel to decode, XDS packets are extracted \
+                     from field 2 in any case",
+                    Cea608Channel::static_type(),
+                    DEFAULT_CHANNEL as i32,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+            ]
         });
 
         PROPERTIES.as_ref()
@@ -1039,6 +1189,10 @@ impl ObjectImpl for Cea608ToJson {
                 self.settings.lock().unwrap().unbuffered =
                     value.get().expect("type checked upstream");
             }
+            "channel" => {
+                self.settings.lock().unwrap().channel =
+                    value.get::<Cea608Channel>().expect("type checked upstream");
+            }
             _ => unimplemented!(),
         }
     }
@@ -1049,6 +1203,10 @@ impl ObjectImpl for Cea608ToJson {
                 let settings = self.settings.lock().unwrap();
                 settings.unbuffered.to_value()
             }
+            "channel" => {
+                let settings = self.settings.lock().unwrap();
+                settings.channel.to_value()
+            }
             _ => unimplemented!(),
         }
     }
@@ -1083,7 +1241,7 @@ impl ElementImpl for Cea608ToJson {
             .unwrap();
 
             let caps = gst::Caps::builder("closedcaption/x-cea-608")
-                .field("format", "raw")
+                .field("format", gst::List::new(["raw", "s334-1a"]))
                 .build();
 
             let sink_pad_template = gst::PadTemplate::new(
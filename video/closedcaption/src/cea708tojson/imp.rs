@@ -0,0 +1,460 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+// TODO:
+//
+//  * Delay commands (DLY, DLC) are executed immediately
+//
+//  * Pen sizes, fonts, offsets and window fill / border attributes are
+//    decoded but can't be represented in the JSON model, only the
+//    foreground color, italics and underline are output
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+
+use crate::ccutils::extract_cdp;
+use crate::cea708utils::{Cea708Decoder, MAX_SERVICE_NUMBER};
+use crate::ttutils::Lines;
+
+use atomic_refcell::AtomicRefCell;
+
+use once_cell::sync::Lazy;
+
+use std::sync::Mutex;
+
+const DEFAULT_SERVICE_NUMBER: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CCFormat {
+    Cc708Cdp,
+    Cc708CcData,
+}
+
+#[derive(Debug)]
+struct TimestampedLines {
+    lines: Lines,
+    pts: Option<gst::ClockTime>,
+    duration: Option<gst::ClockTime>,
+}
+
+#[derive(Debug, Clone)]
+struct Settings {
+    service_number: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            service_number: DEFAULT_SERVICE_NUMBER,
+        }
+    }
+}
+
+struct State {
+    settings: Settings,
+    format: Option<CCFormat>,
+    decoder: Cea708Decoder,
+    /* The JSON of the last lines we decoded, to only
+     * output when the displayed text actually changes */
+    last_json: Option<String>,
+    pending: Option<TimestampedLines>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            settings: Settings::default(),
+            format: None,
+            decoder: Cea708Decoder::new(),
+            last_json: None,
+            pending: None,
+        }
+    }
+}
+
+pub struct Cea708ToJson {
+    srcpad: gst::Pad,
+    sinkpad: gst::Pad,
+
+    state: AtomicRefCell<State>,
+    settings: Mutex<Settings>,
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "cea708tojson",
+        gst::DebugColorFlags::empty(),
+        Some("CEA-708 to JSON Element"),
+    )
+});
+
+impl State {
+    fn handle_cc_data(
+        &mut self,
+        element: &super::Cea708ToJson,
+        pts: gst::ClockTime,
+        cc_data: &[u8],
+    ) -> Option<TimestampedLines> {
+        let service_number = self.settings.service_number as u8;
+
+        let updated = self.decoder.push_cc_data(cc_data);
+        if !updated.contains(&service_number) {
+            return None;
+        }
+
+        let lines = self.decoder.service(service_number)?.to_lines();
+
+        let json = match serde_json::to_string(&lines) {
+            Ok(json) => json,
+            Err(err) => {
+                gst::warning!(CAT, obj: element, "Failed to serialize lines: {}", err);
+                return None;
+            }
+        };
+
+        if self.last_json.as_ref() == Some(&json) {
+            gst::log!(CAT, obj: element, "Displayed text did not change");
+            return None;
+        }
+
+        // Nothing was ever displayed, no need to output a clear
+        if self.last_json.is_none() && lines.lines.is_empty() {
+            return None;
+        }
+
+        self.last_json = Some(json);
+
+        let ret = self.drain_pending(pts);
+
+        self.pending = Some(TimestampedLines {
+            lines,
+            pts: Some(pts),
+            duration: None,
+        });
+
+        ret
+    }
+
+    fn drain_pending(
+        &mut self,
+        pts: impl Into<Option<gst::ClockTime>>,
+    ) -> Option<TimestampedLines> {
+        let pts = pts.into();
+
+        self.pending.take().map(|mut lines| {
+            lines.duration = match (pts, lines.pts) {
+                (Some(end), Some(start)) => Some(end.saturating_sub(start)),
+                _ => None,
+            };
+
+            lines
+        })
+    }
+}
+
+impl Cea708ToJson {
+    fn output(
+        &self,
+        element: &super::Cea708ToJson,
+        lines: TimestampedLines,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        gst::debug!(CAT, obj: element, "outputting: {:?}", lines);
+
+        let json = serde_json::to_string(&lines.lines).map_err(|err| {
+            gst::element_error!(
+                element,
+                gst::ResourceError::Write,
+                ["Failed to serialize as json {}", err]
+            );
+
+            gst::FlowError::Error
+        })?;
+
+        let mut buf = gst::Buffer::from_mut_slice(json.into_bytes());
+        {
+            let buf_mut = buf.get_mut().unwrap();
+            buf_mut.set_pts(lines.pts);
+            buf_mut.set_duration(lines.duration);
+        }
+
+        gst::log!(CAT, obj: element, "Pushing {:?}", buf);
+
+        self.srcpad.push(buf)
+    }
+
+    fn sink_chain(
+        &self,
+        pad: &gst::Pad,
+        element: &super::Cea708ToJson,
+        buffer: gst::Buffer,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        gst::trace!(CAT, obj: pad, "Handling buffer {:?}", buffer);
+
+        let mut state = self.state.borrow_mut();
+
+        let pts = match buffer.pts() {
+            Some(pts) => pts,
+            None => {
+                gst::error!(CAT, obj: pad, "Require timestamped buffers");
+                return Err(gst::FlowError::Error);
+            }
+        };
+
+        let format = match state.format {
+            Some(format) => format,
+            None => {
+                gst::error!(CAT, obj: pad, "No caps set");
+                return Err(gst::FlowError::NotNegotiated);
+            }
+        };
+
+        let data = buffer.map_readable().map_err(|_| {
+            gst::error!(CAT, obj: pad, "Can't map buffer readable");
+
+            gst::FlowError::Error
+        })?;
+
+        let cc_data = match format {
+            CCFormat::Cc708CcData => data.as_slice(),
+            CCFormat::Cc708Cdp => match extract_cdp(data.as_slice()) {
+                Ok(cc_data) => cc_data,
+                Err(err) => {
+                    gst::warning!(CAT, obj: pad, "Invalid CDP packet: {}", err);
+                    return Ok(gst::FlowSuccess::Ok);
+                }
+            },
+        };
+
+        if let Some(lines) = state.handle_cc_data(element, pts, cc_data) {
+            drop(data);
+            drop(state);
+            self.output(element, lines)
+        } else {
+            Ok(gst::FlowSuccess::Ok)
+        }
+    }
+
+    fn sink_event(&self, pad: &gst::Pad, element: &super::Cea708ToJson, event: gst::Event) -> bool {
+        use gst::EventView;
+
+        gst::log!(CAT, obj: pad, "Handling event {:?}", event);
+        match event.view() {
+            EventView::Caps(c) => {
+                let format = c
+                    .caps()
+                    .structure(0)
+                    .and_then(|s| s.get::<&str>("format").ok())
+                    .and_then(|format| match format {
+                        "cdp" => Some(CCFormat::Cc708Cdp),
+                        "cc_data" => Some(CCFormat::Cc708CcData),
+                        _ => None,
+                    });
+
+                if format.is_none() {
+                    gst::error!(CAT, obj: pad, "Invalid caps {:?}", c.caps());
+                    return false;
+                }
+
+                self.state.borrow_mut().format = format;
+
+                // We send our own caps downstream
+                let caps = gst::Caps::builder("application/x-json")
+                    .field("format", "cea708")
+                    .build();
+                self.srcpad.push_event(gst::event::Caps::new(&caps))
+            }
+            EventView::FlushStop(..) => {
+                let mut state = self.state.borrow_mut();
+                let old_settings = state.settings.clone();
+                let old_format = state.format;
+                *state = State::default();
+                state.settings = old_settings;
+                state.format = old_format;
+                drop(state);
+                pad.event_default(Some(element), event)
+            }
+            EventView::Eos(..) => {
+                let lines = self.state.borrow_mut().drain_pending(None);
+                if let Some(lines) = lines {
+                    let _ = self.output(element, lines);
+                }
+
+                pad.event_default(Some(element), event)
+            }
+            _ => pad.event_default(Some(element), event),
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for Cea708ToJson {
+    const NAME: &'static str = "Cea708ToJson";
+    type Type = super::Cea708ToJson;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        let templ = klass.pad_template("sink").unwrap();
+        let sinkpad = gst::Pad::builder_with_template(&templ, Some("sink"))
+            .chain_function(|pad, parent, buffer| {
+                Cea708ToJson::catch_panic_pad_function(
+                    parent,
+                    || Err(gst::FlowError::Error),
+                    |this, element| this.sink_chain(pad, element, buffer),
+                )
+            })
+            .event_function(|pad, parent, event| {
+                Cea708ToJson::catch_panic_pad_function(
+                    parent,
+                    || false,
+                    |this, element| this.sink_event(pad, element, event),
+                )
+            })
+            .build();
+
+        let templ = klass.pad_template("src").unwrap();
+        let srcpad = gst::Pad::builder_with_template(&templ, Some("src"))
+            .flags(gst::PadFlags::FIXED_CAPS)
+            .build();
+
+        Self {
+            srcpad,
+            sinkpad,
+            state: AtomicRefCell::new(State::default()),
+            settings: Mutex::new(Settings::default()),
+        }
+    }
+}
+
+impl ObjectImpl for Cea708ToJson {
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(&self.sinkpad).unwrap();
+        obj.add_pad(&self.srcpad).unwrap();
+    }
+
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![glib::ParamSpecUInt::new(
+                "service-number",
+                "Service Number",
+                "The CEA-708 caption service to decode",
+                1,
+                MAX_SERVICE_NUMBER as u32,
+                DEFAULT_SERVICE_NUMBER,
+                glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+            )]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        match pspec.name() {
+            "service-number" => {
+                self.settings.lock().unwrap().service_number =
+                    value.get().expect("type checked upstream");
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            "service-number" => {
+                let settings = self.settings.lock().unwrap();
+                settings.service_number.to_value()
+            }
+            _ => unimplemented!(),
+        }
+    }
+}
+
+impl GstObjectImpl for Cea708ToJson {}
+
+impl ElementImpl for Cea708ToJson {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "CEA-708 to TT",
+                "Generic",
+                "Converts CEA-708 Closed Captions to JSON",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::builder("application/x-json").build();
+
+            let src_pad_template = gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            let caps = gst::Caps::builder("closedcaption/x-cea-708")
+                .field("format", gst::List::new(["cdp", "cc_data"]))
+                .build();
+
+            let sink_pad_template = gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![src_pad_template, sink_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    #[allow(clippy::single_match)]
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        match transition {
+            gst::StateChange::ReadyToPaused => {
+                let mut state = self.state.borrow_mut();
+                *state = State::default();
+                state.settings = self.settings.lock().unwrap().clone();
+            }
+            _ => (),
+        }
+
+        let ret = self.parent_change_state(element, transition)?;
+
+        match transition {
+            gst::StateChange::PausedToReady => {
+                let mut state = self.state.borrow_mut();
+                *state = State::default();
+            }
+            _ => (),
+        }
+
+        Ok(ret)
+    }
+}
@@ -0,0 +1,1188 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! CEA-708 (DTVCC) decoding.
+//!
+//! [`Cea708Decoder`] reassembles the DTVCC packets carried by `cc_data`
+//! triplets, splits them into service blocks and interprets the commands
+//! of each service (1 to 63) into a set of [`Window`]s, following
+//! CEA-708-E section 7 and 8.
+
+use crate::ttutils::{Chunk, Line, Lines, TextStyle};
+
+use std::collections::{BTreeMap, VecDeque};
+
+pub const MAX_SERVICE_NUMBER: u8 = 63;
+pub const N_WINDOWS: usize = 8;
+pub const MAX_ROWS: usize = 15;
+pub const MAX_COLUMNS: usize = 42;
+
+/* Size of the grid used by the JSON model (see ttutils::Line),
+ * inherited from CEA-608 */
+const JSON_ROWS: u32 = 15;
+const JSON_COLUMNS: u32 = 32;
+
+/* Size of the anchor grid for absolute positioning, 16:9 */
+const ANCHOR_VERTICAL_MAX: u32 = 75;
+const ANCHOR_HORIZONTAL_MAX: u32 = 210;
+
+/// A color with 2 bits per component.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    /// 0: solid, 1: flash, 2: translucent, 3: transparent
+    pub opacity: u8,
+}
+
+impl Color {
+    pub const WHITE: Color = Color {
+        r: 2,
+        g: 2,
+        b: 2,
+        opacity: 0,
+    };
+
+    pub const BLACK: Color = Color {
+        r: 0,
+        g: 0,
+        b: 0,
+        opacity: 0,
+    };
+
+    pub const TRANSPARENT: Color = Color {
+        r: 0,
+        g: 0,
+        b: 0,
+        opacity: 3,
+    };
+
+    fn parse(byte: u8) -> Self {
+        Color {
+            opacity: byte >> 6,
+            r: (byte >> 4) & 0x03,
+            g: (byte >> 2) & 0x03,
+            b: byte & 0x03,
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        (self.opacity & 0x03) << 6 | (self.r & 0x03) << 4 | (self.g & 0x03) << 2 | (self.b & 0x03)
+    }
+
+    /// Returns the solid color and italics matching a CEA-608 style
+    pub fn from_text_style(style: TextStyle) -> (Self, bool) {
+        let (r, g, b) = match style {
+            TextStyle::Green => (0, 2, 0),
+            TextStyle::Blue => (0, 0, 2),
+            TextStyle::Cyan => (0, 2, 2),
+            TextStyle::Red => (2, 0, 0),
+            TextStyle::Yellow => (2, 2, 0),
+            TextStyle::Magenta => (2, 0, 2),
+            TextStyle::White | TextStyle::ItalicWhite => (2, 2, 2),
+        };
+
+        (
+            Color {
+                r,
+                g,
+                b,
+                opacity: 0,
+            },
+            style == TextStyle::ItalicWhite,
+        )
+    }
+
+    /// Returns the closest CEA-608 style for this color
+    pub fn to_text_style(self, italics: bool) -> TextStyle {
+        let style = match (self.r >= 2, self.g >= 2, self.b >= 2) {
+            (false, true, false) => TextStyle::Green,
+            (false, false, true) => TextStyle::Blue,
+            (false, true, true) => TextStyle::Cyan,
+            (true, false, false) => TextStyle::Red,
+            (true, true, false) => TextStyle::Yellow,
+            (true, false, true) => TextStyle::Magenta,
+            _ => TextStyle::White,
+        };
+
+        if italics && style == TextStyle::White {
+            TextStyle::ItalicWhite
+        } else {
+            style
+        }
+    }
+}
+
+// Not all attributes can be represented in the JSON model yet
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PenAttributes {
+    /// 0: small, 1: standard, 2: large
+    pub size: u8,
+    /// 0: subscript, 1: normal, 2: superscript
+    pub offset: u8,
+    pub text_tag: u8,
+    pub font_style: u8,
+    pub edge_type: u8,
+    pub underline: bool,
+    pub italics: bool,
+}
+
+impl Default for PenAttributes {
+    fn default() -> Self {
+        PenAttributes {
+            size: 1,
+            offset: 1,
+            text_tag: 0,
+            font_style: 0,
+            edge_type: 0,
+            underline: false,
+            italics: false,
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PenColor {
+    pub foreground: Color,
+    pub background: Color,
+    pub edge: Color,
+}
+
+impl Default for PenColor {
+    fn default() -> Self {
+        PenColor {
+            foreground: Color::WHITE,
+            background: Color::BLACK,
+            edge: Color::BLACK,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Pen {
+    pub attributes: PenAttributes,
+    pub color: PenColor,
+}
+
+impl Pen {
+    /* Predefined pen styles, CEA-708-E table 28 */
+    fn predefined(style: u8) -> Self {
+        let mut pen = Pen::default();
+
+        pen.attributes.font_style = match style {
+            2 => 1,
+            3 => 2,
+            4 | 6 => 3,
+            5 | 7 => 4,
+            _ => 0,
+        };
+
+        if style >= 6 {
+            pen.attributes.edge_type = 3;
+            pen.color.background = Color::TRANSPARENT;
+        }
+
+        pen
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WindowAttributes {
+    /// 0: left, 1: right, 2: center, 3: full
+    pub justify: u8,
+    /// 0: left to right, 1: right to left, 2: top to bottom, 3: bottom to top
+    pub print_direction: u8,
+    /// Same values as `print_direction`
+    pub scroll_direction: u8,
+    pub word_wrap: bool,
+    /// 0: snap, 1: fade, 2: wipe
+    pub display_effect: u8,
+    pub effect_direction: u8,
+    pub effect_speed: u8,
+    pub fill_color: Color,
+    pub border_type: u8,
+    pub border_color: Color,
+}
+
+impl Default for WindowAttributes {
+    fn default() -> Self {
+        WindowAttributes {
+            justify: 0,
+            print_direction: 0,
+            scroll_direction: 3,
+            word_wrap: false,
+            display_effect: 0,
+            effect_direction: 0,
+            effect_speed: 0,
+            fill_color: Color::BLACK,
+            border_type: 0,
+            border_color: Color::BLACK,
+        }
+    }
+}
+
+impl WindowAttributes {
+    /* Predefined window styles, CEA-708-E table 27 */
+    fn predefined(style: u8) -> Self {
+        let mut attributes = WindowAttributes::default();
+
+        match style {
+            2 | 5 => attributes.fill_color = Color::TRANSPARENT,
+            3 | 6 => attributes.justify = 2,
+            _ => (),
+        }
+
+        match style {
+            4 | 5 | 6 => attributes.word_wrap = true,
+            7 => {
+                attributes.print_direction = 2;
+                attributes.scroll_direction = 1;
+                attributes.word_wrap = true;
+            }
+            _ => (),
+        }
+
+        attributes
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub c: char,
+    pub pen: Pen,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct Window {
+    /// 0 is the highest priority
+    pub priority: u8,
+    pub visible: bool,
+    pub row_lock: bool,
+    pub column_lock: bool,
+    /// Whether the anchor coordinates are percentages of the screen size
+    pub relative_positioning: bool,
+    pub anchor_vertical: u8,
+    pub anchor_horizontal: u8,
+    /// 0 to 8, from top left to bottom right
+    pub anchor_point: u8,
+    pub row_count: usize,
+    pub column_count: usize,
+    pub attributes: WindowAttributes,
+    pub pen: Pen,
+    pub pen_row: usize,
+    pub pen_column: usize,
+    /// MAX_ROWS x MAX_COLUMNS, only the top left
+    /// `row_count` x `column_count` part is displayed
+    pub rows: Vec<Vec<Option<Cell>>>,
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Window {
+            priority: 0,
+            visible: false,
+            row_lock: false,
+            column_lock: false,
+            relative_positioning: false,
+            anchor_vertical: 0,
+            anchor_horizontal: 0,
+            anchor_point: 0,
+            row_count: 1,
+            column_count: 1,
+            attributes: WindowAttributes::default(),
+            pen: Pen::default(),
+            pen_row: 0,
+            pen_column: 0,
+            rows: vec![vec![None; MAX_COLUMNS]; MAX_ROWS],
+        }
+    }
+}
+
+impl Window {
+    /* DefineWindow, CEA-708-E section 8.10.5.2 */
+    fn define(&mut self, params: &[u8], existing: bool) {
+        self.visible = params[0] & 0x20 != 0;
+        self.row_lock = params[0] & 0x10 != 0;
+        self.column_lock = params[0] & 0x08 != 0;
+        self.priority = params[0] & 0x07;
+        self.relative_positioning = params[1] & 0x80 != 0;
+        self.anchor_vertical = params[1] & 0x7f;
+        self.anchor_horizontal = params[2];
+        self.anchor_point = (params[3] >> 4).min(8);
+        self.row_count = ((params[3] & 0x0f) as usize + 1).min(MAX_ROWS);
+        self.column_count = ((params[4] & 0x3f) as usize + 1).min(MAX_COLUMNS);
+
+        // Style 0 means the predefined style 1 for new windows
+        // and no change for existing ones
+        let window_style = (params[5] >> 3) & 0x07;
+        let pen_style = params[5] & 0x07;
+        if window_style != 0 || !existing {
+            self.attributes = WindowAttributes::predefined(window_style.max(1));
+        }
+        if pen_style != 0 || !existing {
+            self.pen = Pen::predefined(pen_style.max(1));
+        }
+
+        self.pen_row = self.pen_row.min(self.row_count - 1);
+        self.pen_column = self.pen_column.min(self.column_count - 1);
+    }
+
+    fn clear(&mut self) {
+        for row in self.rows.iter_mut() {
+            row.iter_mut().for_each(|cell| *cell = None);
+        }
+    }
+
+    fn scroll_up(&mut self) {
+        self.rows[..self.row_count].rotate_left(1);
+        self.rows[self.row_count - 1]
+            .iter_mut()
+            .for_each(|cell| *cell = None);
+    }
+
+    fn carriage_return(&mut self) {
+        self.pen_column = 0;
+        if self.pen_row + 1 < self.row_count {
+            self.pen_row += 1;
+        } else {
+            self.scroll_up();
+        }
+    }
+
+    fn put(&mut self, c: char) {
+        if self.pen_column >= self.column_count {
+            self.carriage_return();
+        }
+
+        self.rows[self.pen_row][self.pen_column] = Some(Cell { c, pen: self.pen });
+        self.pen_column += 1;
+    }
+
+    fn backspace(&mut self) {
+        if self.pen_column > 0 {
+            self.pen_column -= 1;
+            self.rows[self.pen_row][self.pen_column] = None;
+        }
+    }
+
+    /// Returns the position of the anchor point, as fractions of the
+    /// height and width of the safe title area
+    pub fn anchor(&self) -> (f64, f64) {
+        let (vertical_max, horizontal_max) = if self.relative_positioning {
+            (100, 100)
+        } else {
+            (ANCHOR_VERTICAL_MAX, ANCHOR_HORIZONTAL_MAX)
+        };
+
+        (
+            (self.anchor_vertical as f64 / vertical_max as f64).min(1.0),
+            (self.anchor_horizontal as f64 / horizontal_max as f64).min(1.0),
+        )
+    }
+
+    /// Returns the top left position of the window in the grid of
+    /// the JSON model (15 rows of 32 columns)
+    pub fn origin(&self) -> (u32, u32) {
+        let (row, column) = if self.relative_positioning {
+            (
+                self.anchor_vertical as u32 * JSON_ROWS / 100,
+                self.anchor_horizontal as u32 * JSON_COLUMNS / 100,
+            )
+        } else {
+            (
+                self.anchor_vertical as u32 * JSON_ROWS / ANCHOR_VERTICAL_MAX,
+                self.anchor_horizontal as u32 * JSON_COLUMNS / ANCHOR_HORIZONTAL_MAX,
+            )
+        };
+
+        let row_count = self.row_count as u32;
+        let column_count = self.column_count as u32;
+
+        let row = match self.anchor_point / 3 {
+            0 => row,
+            1 => row.saturating_sub((row_count - 1) / 2),
+            _ => row.saturating_sub(row_count - 1),
+        };
+        let column = match self.anchor_point % 3 {
+            0 => column,
+            1 => column.saturating_sub((column_count - 1) / 2),
+            _ => column.saturating_sub(column_count - 1),
+        };
+
+        (
+            row.min(JSON_ROWS.saturating_sub(row_count)),
+            column.min(JSON_COLUMNS.saturating_sub(column_count)),
+        )
+    }
+
+    /// Converts the displayed rows of the window to lines of the JSON model
+    pub fn to_lines(&self) -> Vec<Line> {
+        let (origin_row, origin_column) = self.origin();
+        let mut lines = vec![];
+
+        for (idx, row) in self.rows[..self.row_count].iter().enumerate() {
+            let cells = &row[..self.column_count];
+
+            let first = match cells.iter().position(|cell| cell.is_some()) {
+                Some(first) => first,
+                None => continue,
+            };
+
+            let mut chunks: Vec<Chunk> = vec![];
+            let mut spaces = 0;
+
+            for cell in &cells[first..] {
+                let cell = match cell {
+                    Some(cell) => cell,
+                    None => {
+                        spaces += 1;
+                        continue;
+                    }
+                };
+
+                let style = cell
+                    .pen
+                    .color
+                    .foreground
+                    .to_text_style(cell.pen.attributes.italics);
+                let underline = cell.pen.attributes.underline;
+
+                match chunks.last_mut() {
+                    Some(chunk) if chunk.style == style && chunk.underline == underline => {
+                        chunk.text.extend(std::iter::repeat(' ').take(spaces));
+                        chunk.text.push(cell.c);
+                    }
+                    _ => {
+                        let mut text = " ".repeat(spaces);
+                        text.push(cell.c);
+                        chunks.push(Chunk {
+                            style,
+                            underline,
+                            text,
+                        });
+                    }
+                }
+
+                spaces = 0;
+            }
+
+            lines.push(Line {
+                column: Some((origin_column + first as u32).min(JSON_COLUMNS - 1)),
+                row: Some((origin_row + idx as u32).min(JSON_ROWS - 1)),
+                chunks,
+                carriage_return: None,
+            });
+        }
+
+        lines
+    }
+}
+
+/* Replacement for the characters which can't be displayed, CEA-708-E section 7.1.2 */
+const UNSUPPORTED_CHAR: char = '_';
+
+/* G2 code set, CEA-708-E table 8 */
+fn g2_to_char(code: u8) -> char {
+    match code {
+        0x20 => ' ',
+        0x21 => '\u{a0}',
+        0x25 => '…',
+        0x2a => 'Š',
+        0x2c => 'Œ',
+        0x30 => '█',
+        0x31 => '‘',
+        0x32 => '’',
+        0x33 => '“',
+        0x34 => '”',
+        0x35 => '•',
+        0x39 => '™',
+        0x3a => 'š',
+        0x3c => 'œ',
+        0x3d => '℠',
+        0x3f => 'Ÿ',
+        0x76 => '⅛',
+        0x77 => '⅜',
+        0x78 => '⅝',
+        0x79 => '⅞',
+        0x7a => '│',
+        0x7b => '┐',
+        0x7c => '└',
+        0x7d => '─',
+        0x7e => '┘',
+        0x7f => '┌',
+        _ => UNSUPPORTED_CHAR,
+    }
+}
+
+/// The state of a caption service.
+#[derive(Clone, Debug, Default)]
+pub struct ServiceDecoder {
+    windows: [Option<Window>; N_WINDOWS],
+    current_window: Option<usize>,
+}
+
+impl ServiceDecoder {
+    pub fn reset(&mut self) {
+        *self = ServiceDecoder::default();
+    }
+
+    /// Returns the visible windows, from the lowest to the highest priority
+    pub fn visible_windows(&self) -> Vec<&Window> {
+        let mut windows = self
+            .windows
+            .iter()
+            .flatten()
+            .filter(|window| window.visible)
+            .collect::<Vec<_>>();
+        windows.sort_by_key(|window| std::cmp::Reverse(window.priority));
+
+        windows
+    }
+
+    /// Returns the displayed text in the JSON model
+    pub fn to_lines(&self) -> Lines {
+        let lines = self
+            .visible_windows()
+            .into_iter()
+            .flat_map(|window| window.to_lines())
+            .collect::<Vec<_>>();
+
+        let clear = if lines.is_empty() { Some(true) } else { None };
+
+        Lines {
+            lines,
+            mode: None,
+            clear,
+        }
+    }
+
+    fn current_window(&mut self) -> Option<&mut Window> {
+        match self.current_window {
+            Some(id) => self.windows[id].as_mut(),
+            None => None,
+        }
+    }
+
+    fn for_each_window(&mut self, bitmap: u8, mut f: impl FnMut(&mut Option<Window>)) {
+        for (id, window) in self.windows.iter_mut().enumerate() {
+            if bitmap & (1 << id) != 0 {
+                f(window);
+            }
+        }
+    }
+
+    fn put(&mut self, c: char) {
+        if let Some(window) = self.current_window() {
+            window.put(c);
+        }
+    }
+
+    /// Decodes the data of a service block
+    pub fn decode(&mut self, mut data: &[u8]) {
+        while let Some((&code, rest)) = data.split_first() {
+            data = rest;
+
+            let n_params = match code {
+                0x10 => 1,
+                0x11..=0x17 => 1,
+                0x18..=0x1f => 2,
+                0x88..=0x8e => 1,
+                0x90 | 0x92 => 2,
+                0x91 => 3,
+                0x97 => 4,
+                0x98..=0x9f => 6,
+                _ => 0,
+            };
+
+            if data.len() < n_params {
+                // Commands can't span multiple service blocks
+                break;
+            }
+            let (params, rest) = data.split_at(n_params);
+            data = rest;
+
+            match code {
+                // C0
+                0x08 => {
+                    if let Some(window) = self.current_window() {
+                        window.backspace();
+                    }
+                }
+                0x0c => {
+                    if let Some(window) = self.current_window() {
+                        window.clear();
+                        window.pen_row = 0;
+                        window.pen_column = 0;
+                    }
+                }
+                0x0d => {
+                    if let Some(window) = self.current_window() {
+                        window.carriage_return();
+                    }
+                }
+                0x0e => {
+                    if let Some(window) = self.current_window() {
+                        let pen_row = window.pen_row;
+                        window.rows[pen_row]
+                            .iter_mut()
+                            .for_each(|cell| *cell = None);
+                        window.pen_column = 0;
+                    }
+                }
+                0x10 => data = self.decode_ext1(params[0], data),
+                0x18 => {
+                    let c = char::from_u32((params[0] as u32) << 8 | params[1] as u32)
+                        .unwrap_or(UNSUPPORTED_CHAR);
+                    self.put(c);
+                }
+                0x00..=0x1f => (),
+                // G0
+                0x7f => self.put('♪'),
+                0x20..=0x7e => self.put(code as char),
+                // C1
+                0x80..=0x87 => {
+                    let id = (code - 0x80) as usize;
+                    if self.windows[id].is_some() {
+                        self.current_window = Some(id);
+                    }
+                }
+                0x88 => self.for_each_window(params[0], |window| {
+                    if let Some(window) = window {
+                        window.clear();
+                    }
+                }),
+                0x89 => self.for_each_window(params[0], |window| {
+                    if let Some(window) = window {
+                        window.visible = true;
+                    }
+                }),
+                0x8a => self.for_each_window(params[0], |window| {
+                    if let Some(window) = window {
+                        window.visible = false;
+                    }
+                }),
+                0x8b => self.for_each_window(params[0], |window| {
+                    if let Some(window) = window {
+                        window.visible = !window.visible;
+                    }
+                }),
+                0x8c => {
+                    self.for_each_window(params[0], |window| *window = None);
+                    if let Some(id) = self.current_window {
+                        if self.windows[id].is_none() {
+                            self.current_window = None;
+                        }
+                    }
+                }
+                // Delays are not supported, the commands are executed immediately
+                0x8d | 0x8e => (),
+                0x8f => self.reset(),
+                0x90 => {
+                    if let Some(window) = self.current_window() {
+                        let attributes = &mut window.pen.attributes;
+                        attributes.text_tag = params[0] >> 4;
+                        attributes.offset = (params[0] >> 2) & 0x03;
+                        attributes.size = params[0] & 0x03;
+                        attributes.italics = params[1] & 0x80 != 0;
+                        attributes.underline = params[1] & 0x40 != 0;
+                        attributes.edge_type = (params[1] >> 3) & 0x07;
+                        attributes.font_style = params[1] & 0x07;
+                    }
+                }
+                0x91 => {
+                    if let Some(window) = self.current_window() {
+                        window.pen.color = PenColor {
+                            foreground: Color::parse(params[0]),
+                            background: Color::parse(params[1]),
+                            edge: Color::parse(params[2] & 0x3f),
+                        };
+                    }
+                }
+                0x92 => {
+                    if let Some(window) = self.current_window() {
+                        window.pen_row = ((params[0] & 0x0f) as usize).min(window.row_count - 1);
+                        window.pen_column =
+                            ((params[1] & 0x3f) as usize).min(window.column_count - 1);
+                    }
+                }
+                0x97 => {
+                    if let Some(window) = self.current_window() {
+                        let attributes = &mut window.attributes;
+                        attributes.fill_color = Color::parse(params[0]);
+                        attributes.border_color = Color::parse(params[1] & 0x3f);
+                        attributes.border_type = (params[1] >> 6) | ((params[2] & 0x80) >> 5);
+                        attributes.word_wrap = params[2] & 0x40 != 0;
+                        attributes.print_direction = (params[2] >> 4) & 0x03;
+                        attributes.scroll_direction = (params[2] >> 2) & 0x03;
+                        attributes.justify = params[2] & 0x03;
+                        attributes.effect_speed = params[3] >> 4;
+                        attributes.effect_direction = (params[3] >> 2) & 0x03;
+                        attributes.display_effect = params[3] & 0x03;
+                    }
+                }
+                0x98..=0x9f => {
+                    let id = (code - 0x98) as usize;
+                    let existing = self.windows[id].is_some();
+                    self.windows[id]
+                        .get_or_insert_with(Window::default)
+                        .define(params, existing);
+                    self.current_window = Some(id);
+                }
+                0x80..=0x9f => (),
+                // G1
+                0xa0..=0xff => self.put(code as char),
+            }
+        }
+    }
+
+    /* Extended code sets, CEA-708-E section 7.1.8 to 7.1.11 */
+    fn decode_ext1<'a>(&mut self, code: u8, data: &'a [u8]) -> &'a [u8] {
+        let skip = match code {
+            // C2
+            0x00..=0x07 => 0,
+            0x08..=0x0f => 1,
+            0x10..=0x17 => 2,
+            0x18..=0x1f => 3,
+            // G2
+            0x20..=0x7f => {
+                self.put(g2_to_char(code));
+                0
+            }
+            // C3
+            0x80..=0x87 => 4,
+            0x88..=0x8f => 5,
+            0x90..=0x9f => data
+                .first()
+                .map(|len| 1 + (len & 0x3f) as usize)
+                .unwrap_or(0),
+            // G3, only the [CC] icon is defined
+            0xa0 => {
+                self.put('㏄');
+                0
+            }
+            0xa1..=0xff => {
+                self.put(UNSUPPORTED_CHAR);
+                0
+            }
+        };
+
+        &data[skip.min(data.len())..]
+    }
+}
+
+/// Decodes the DTVCC data of a CEA-708 stream into its caption services.
+#[derive(Debug, Default)]
+pub struct Cea708Decoder {
+    packet: Vec<u8>,
+    services: BTreeMap<u8, ServiceDecoder>,
+}
+
+impl Cea708Decoder {
+    pub fn new() -> Self {
+        Cea708Decoder::default()
+    }
+
+    pub fn service(&self, service_number: u8) -> Option<&ServiceDecoder> {
+        self.services.get(&service_number)
+    }
+
+    /* Size of the DTVCC packet being reassembled, including its header */
+    fn packet_size(&self) -> Option<usize> {
+        self.packet.first().map(|header| match header & 0x3f {
+            0 => 128,
+            size_code => size_code as usize * 2,
+        })
+    }
+
+    /// Pushes `cc_data` triplets and returns the numbers of the services
+    /// updated by the DTVCC packets they completed
+    pub fn push_cc_data(&mut self, cc_data: &[u8]) -> Vec<u8> {
+        let mut updated = vec![];
+
+        for triplet in cc_data.chunks_exact(3) {
+            let cc_valid = triplet[0] & 0x04 == 0x04;
+            let cc_type = triplet[0] & 0x03;
+
+            if !cc_valid {
+                continue;
+            }
+
+            match cc_type {
+                // DTVCC_PACKET_START, drops any incomplete packet
+                0x03 => {
+                    self.packet.clear();
+                    self.packet.extend_from_slice(&triplet[1..]);
+                }
+                // DTVCC_PACKET_DATA
+                0x02 if !self.packet.is_empty() => self.packet.extend_from_slice(&triplet[1..]),
+                // CEA-608
+                _ => continue,
+            }
+
+            if let Some(size) = self.packet_size() {
+                if self.packet.len() >= size {
+                    let packet = std::mem::take(&mut self.packet);
+                    self.decode_packet(&packet[..size], &mut updated);
+                }
+            }
+        }
+
+        updated
+    }
+
+    /* Service blocks, CEA-708-E section 6.2 */
+    fn decode_packet(&mut self, packet: &[u8], updated: &mut Vec<u8>) {
+        let mut data = &packet[1..];
+
+        while let Some((&header, rest)) = data.split_first() {
+            data = rest;
+
+            let mut service_number = header >> 5;
+            let block_size = (header & 0x1f) as usize;
+
+            if service_number == 0 {
+                // Null block, the rest of the packet is padding
+                break;
+            }
+
+            if service_number == 7 {
+                match data.split_first() {
+                    Some((&extended, rest)) => {
+                        service_number = extended & 0x3f;
+                        data = rest;
+                    }
+                    None => break,
+                }
+            }
+
+            if block_size > data.len() {
+                break;
+            }
+            let (block, rest) = data.split_at(block_size);
+            data = rest;
+
+            if !(1..=MAX_SERVICE_NUMBER).contains(&service_number) {
+                continue;
+            }
+
+            self.services
+                .entry(service_number)
+                .or_default()
+                .decode(block);
+
+            if !updated.contains(&service_number) {
+                updated.push(service_number);
+            }
+        }
+    }
+}
+
+/* Inverse of g2_to_char() */
+fn char_to_g2(c: char) -> Option<u8> {
+    (0x20..=0x7f).find(|code| *code != 0x20 && g2_to_char(*code) == c)
+}
+
+/// Encodes commands for a caption service into DTVCC packets.
+#[derive(Debug)]
+pub struct Cea708Encoder {
+    service_number: u8,
+    sequence_no: u8,
+    /* Commands can't span service blocks, queue them separately */
+    commands: VecDeque<Vec<u8>>,
+}
+
+impl Cea708Encoder {
+    pub fn new(service_number: u8) -> Self {
+        assert!((1..=MAX_SERVICE_NUMBER).contains(&service_number));
+
+        Cea708Encoder {
+            service_number,
+            sequence_no: 0,
+            commands: VecDeque::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    fn command(&mut self, command: &[u8]) {
+        self.commands.push_back(command.to_vec());
+    }
+
+    pub fn define_window(&mut self, id: u8, window: &Window) {
+        assert!((id as usize) < N_WINDOWS);
+
+        self.command(&[
+            0x98 + id,
+            (window.visible as u8) << 5
+                | (window.row_lock as u8) << 4
+                | (window.column_lock as u8) << 3
+                | (window.priority & 0x07),
+            (window.relative_positioning as u8) << 7 | (window.anchor_vertical & 0x7f),
+            window.anchor_horizontal,
+            (window.anchor_point & 0x0f) << 4 | ((window.row_count - 1) as u8 & 0x0f),
+            (window.column_count - 1) as u8 & 0x3f,
+            /* Predefined window and pen style 1 */
+            0x09,
+        ]);
+    }
+
+    pub fn clear_windows(&mut self, bitmap: u8) {
+        self.command(&[0x88, bitmap]);
+    }
+
+    pub fn display_windows(&mut self, bitmap: u8) {
+        self.command(&[0x89, bitmap]);
+    }
+
+    pub fn delete_windows(&mut self, bitmap: u8) {
+        self.command(&[0x8c, bitmap]);
+    }
+
+    pub fn set_pen_attributes(&mut self, attributes: &PenAttributes) {
+        self.command(&[
+            0x90,
+            (attributes.text_tag & 0x0f) << 4
+                | (attributes.offset & 0x03) << 2
+                | (attributes.size & 0x03),
+            (attributes.italics as u8) << 7
+                | (attributes.underline as u8) << 6
+                | (attributes.edge_type & 0x07) << 3
+                | (attributes.font_style & 0x07),
+        ]);
+    }
+
+    pub fn set_pen_color(&mut self, color: &PenColor) {
+        self.command(&[
+            0x91,
+            color.foreground.to_byte(),
+            color.background.to_byte(),
+            color.edge.to_byte() & 0x3f,
+        ]);
+    }
+
+    pub fn set_pen_location(&mut self, row: u8, column: u8) {
+        self.command(&[0x92, row & 0x0f, column & 0x3f]);
+    }
+
+    pub fn carriage_return(&mut self) {
+        self.command(&[0x0d]);
+    }
+
+    /// Queues a character, returns `false` if it had to be replaced
+    pub fn put(&mut self, c: char) -> bool {
+        match c {
+            '♪' => self.command(&[0x7f]),
+            ' '..='~' | '\u{a0}'..='ÿ' => self.command(&[c as u8]),
+            _ => {
+                if let Some(code) = char_to_g2(c) {
+                    self.command(&[0x10, code]);
+                } else if c == '㏄' {
+                    self.command(&[0x10, 0xa0]);
+                } else if (c as u32) <= 0xffff {
+                    let code = (c as u32 as u16).to_be_bytes();
+                    self.command(&[0x18, code[0], code[1]]);
+                } else {
+                    self.command(&[UNSUPPORTED_CHAR as u8]);
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns the `cc_data` triplets of the next DTVCC packet,
+    /// using at most `max_triplets`, or an empty vector if no
+    /// command is pending
+    pub fn cc_data(&mut self, max_triplets: usize) -> Vec<u8> {
+        let max_packet_size = (max_triplets * 2).min(128);
+        let extended = self.service_number > 6;
+        let block_header_size = if extended { 2 } else { 1 };
+
+        let mut packet = vec![0u8];
+
+        while !self.commands.is_empty() {
+            let available = max_packet_size.saturating_sub(packet.len() + block_header_size);
+
+            let mut block = vec![];
+            while let Some(command) = self.commands.front() {
+                if block.len() + command.len() > available.min(31) {
+                    break;
+                }
+
+                block.extend(self.commands.pop_front().unwrap());
+            }
+
+            if block.is_empty() {
+                break;
+            }
+
+            if extended {
+                packet.push((7 << 5) | block.len() as u8);
+                packet.push(self.service_number);
+            } else {
+                packet.push((self.service_number << 5) | block.len() as u8);
+            }
+            packet.extend(block);
+        }
+
+        if packet.len() == 1 {
+            return vec![];
+        }
+
+        /* Pad with a null service block header */
+        if packet.len() % 2 == 1 {
+            packet.push(0x00);
+        }
+
+        let size_code = (packet.len() / 2) as u8 & 0x3f;
+        packet[0] = self.sequence_no << 6 | size_code;
+        self.sequence_no = (self.sequence_no + 1) % 4;
+
+        let mut cc_data = Vec::with_capacity(packet.len() / 2 * 3);
+        for (idx, pair) in packet.chunks(2).enumerate() {
+            /* cc_valid, DTVCC_PACKET_START or DTVCC_PACKET_DATA */
+            cc_data.push(if idx == 0 { 0xff } else { 0xfe });
+            cc_data.extend_from_slice(pair);
+        }
+
+        cc_data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet_to_cc_data(packet: &[u8]) -> Vec<u8> {
+        let mut cc_data = vec![];
+        for (idx, pair) in packet.chunks(2).enumerate() {
+            cc_data.push(if idx == 0 { 0xff } else { 0xfe });
+            cc_data.push(pair[0]);
+            cc_data.push(pair.get(1).copied().unwrap_or(0));
+        }
+
+        cc_data
+    }
+
+    fn text(lines: &Lines) -> Vec<String> {
+        lines
+            .lines
+            .iter()
+            .map(|line| {
+                line.chunks
+                    .iter()
+                    .map(|chunk| chunk.text.as_str())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_window_text() {
+        let mut decoder = Cea708Decoder::new();
+
+        // DefineWindow 0, visible, 2 rows of 16 columns, then "Hi" CR "there"
+        let mut block = vec![0x98, 0x20, 0x00, 0x00, 0x01, 0x0f, 0x09];
+        block.extend_from_slice(b"Hi\rthere");
+        let mut packet = vec![0x00, (1 << 5) | block.len() as u8];
+        packet.extend_from_slice(&block);
+        packet[0] = ((packet.len() + 1) / 2) as u8;
+        packet.resize(packet[0] as usize * 2, 0);
+
+        assert_eq!(decoder.push_cc_data(&packet_to_cc_data(&packet)), vec![1]);
+
+        let lines = decoder.service(1).unwrap().to_lines();
+        assert_eq!(text(&lines), vec!["Hi", "there"]);
+        assert_eq!(lines.lines[0].row, Some(0));
+        assert_eq!(lines.lines[1].row, Some(1));
+        assert_eq!(lines.clear, None);
+
+        // HideWindows 0
+        let packet = [0x02, (1 << 5) | 2, 0x8a, 0x01];
+        assert_eq!(decoder.push_cc_data(&packet_to_cc_data(&packet)), vec![1]);
+        let lines = decoder.service(1).unwrap().to_lines();
+        assert!(lines.lines.is_empty());
+        assert_eq!(lines.clear, Some(true));
+    }
+
+    #[test]
+    fn test_extended_service() {
+        let mut decoder = Cea708Decoder::new();
+
+        // Service 42, in an extended service block
+        let block = [0x98, 0x20, 0x00, 0x00, 0x00, 0x0f, 0x09, 0x10, 0x39];
+        let mut packet = vec![0x00, (7 << 5) | block.len() as u8, 42];
+        packet.extend_from_slice(&block);
+        packet[0] = ((packet.len() + 1) / 2) as u8;
+        packet.resize(packet[0] as usize * 2, 0);
+
+        assert_eq!(decoder.push_cc_data(&packet_to_cc_data(&packet)), vec![42]);
+        assert!(decoder.service(1).is_none());
+
+        let lines = decoder.service(42).unwrap().to_lines();
+        assert_eq!(text(&lines), vec!["™"]);
+    }
+
+    #[test]
+    fn test_scroll_up() {
+        let mut service = ServiceDecoder::default();
+
+        // 2 rows of 4 columns, pen style 1
+        service.decode(&[0x98, 0x20, 0x00, 0x00, 0x01, 0x03, 0x09]);
+        service.decode(b"ab\rcd\ref");
+
+        assert_eq!(text(&service.to_lines()), vec!["cd", "ef"]);
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let mut encoder = Cea708Encoder::new(3);
+        let mut decoder = Cea708Decoder::new();
+
+        let window = Window {
+            visible: true,
+            row_count: 2,
+            column_count: 32,
+            ..Default::default()
+        };
+        encoder.define_window(0, &window);
+        encoder.set_pen_color(&PenColor {
+            foreground: Color::from_text_style(TextStyle::Yellow).0,
+            ..Default::default()
+        });
+        for c in "Hello “world” ♪ ½".chars() {
+            assert!(encoder.put(c));
+        }
+        encoder.carriage_return();
+        for c in "bye".chars() {
+            assert!(encoder.put(c));
+        }
+
+        let mut updated = vec![];
+        while !encoder.is_empty() {
+            let cc_data = encoder.cc_data(18);
+            assert!(!cc_data.is_empty());
+            assert!(cc_data.len() <= 18 * 3);
+            updated.extend(decoder.push_cc_data(&cc_data));
+        }
+        assert!(updated.iter().all(|service| *service == 3));
+
+        let lines = decoder.service(3).unwrap().to_lines();
+        assert_eq!(text(&lines), vec!["Hello “world” ♪ ½", "bye"]);
+        assert_eq!(lines.lines[0].chunks[0].style, TextStyle::Yellow);
+        assert_eq!(lines.lines[1].chunks[0].style, TextStyle::Yellow);
+    }
+}
@@ -0,0 +1,333 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+
+use crate::ttml::{self, Cue};
+use crate::ttutils::Lines;
+
+use once_cell::sync::Lazy;
+
+use std::sync::Mutex;
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "jsontottml",
+        gst::DebugColorFlags::empty(),
+        Some("JSON to TTML"),
+    )
+});
+
+const DEFAULT_FRAGMENTED: bool = false;
+
+#[derive(Debug, Clone)]
+struct Settings {
+    fragmented: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            fragmented: DEFAULT_FRAGMENTED,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    settings: Settings,
+    /* Cues of the whole document, when not fragmented */
+    cues: Vec<Cue>,
+}
+
+pub struct JsonToTtml {
+    srcpad: gst::Pad,
+    sinkpad: gst::Pad,
+    settings: Mutex<Settings>,
+    state: Mutex<State>,
+}
+
+impl JsonToTtml {
+    fn document_buffer(
+        cues: &[Cue],
+        pts: gst::ClockTime,
+        duration: Option<gst::ClockTime>,
+    ) -> gst::Buffer {
+        let mut buffer = gst::Buffer::from_mut_slice(ttml::write_document(cues).into_bytes());
+        {
+            let buffer = buffer.get_mut().unwrap();
+            buffer.set_pts(pts);
+            buffer.set_duration(duration);
+        }
+
+        buffer
+    }
+
+    fn sink_chain(
+        &self,
+        pad: &gst::Pad,
+        _element: &super::JsonToTtml,
+        buffer: gst::Buffer,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        gst::trace!(CAT, obj: pad, "Handling buffer {:?}", buffer);
+
+        let pts = buffer.pts().ok_or_else(|| {
+            gst::error!(CAT, obj: pad, "Require timestamped buffers");
+            gst::FlowError::Error
+        })?;
+
+        let duration = buffer.duration().ok_or_else(|| {
+            gst::error!(CAT, obj: pad, "Require buffers with duration");
+            gst::FlowError::Error
+        })?;
+
+        let data = buffer.map_readable().map_err(|_| {
+            gst::error!(CAT, obj: pad, "Can't map buffer readable");
+
+            gst::FlowError::Error
+        })?;
+
+        let lines: Lines = serde_json::from_slice(&data).map_err(|err| {
+            gst::error!(CAT, obj: pad, "Failed to parse input as json: {}", err);
+
+            gst::FlowError::Error
+        })?;
+
+        let mut state = self.state.lock().unwrap();
+
+        /* Clear buffers don't need an explicit cue */
+        let cue = if lines.lines.iter().any(|line| !line.chunks.is_empty()) {
+            Some(Cue {
+                begin: pts,
+                end: Some(pts + duration),
+                lines,
+            })
+        } else {
+            None
+        };
+
+        if !state.settings.fragmented {
+            state.cues.extend(cue);
+            return Ok(gst::FlowSuccess::Ok);
+        }
+
+        drop(state);
+
+        /* Each fragment is a complete document, empty when no
+         * caption is displayed */
+        let cues = cue.into_iter().collect::<Vec<_>>();
+        let buffer = Self::document_buffer(&cues, pts, Some(duration));
+
+        gst::log!(CAT, obj: pad, "Pushing {:?}", buffer);
+
+        self.srcpad.push(buffer)
+    }
+
+    fn sink_event(&self, pad: &gst::Pad, element: &super::JsonToTtml, event: gst::Event) -> bool {
+        use gst::EventView;
+
+        gst::log!(CAT, obj: pad, "Handling event {:?}", event);
+
+        match event.view() {
+            EventView::Caps(_) => {
+                // We send our own caps downstream
+                let caps = gst::Caps::builder("application/ttml+xml").build();
+                self.srcpad.push_event(gst::event::Caps::new(&caps))
+            }
+            EventView::Gap(ev) => {
+                let fragmented = self.state.lock().unwrap().settings.fragmented;
+
+                if fragmented {
+                    let (pts, duration) = ev.get();
+                    let buffer = Self::document_buffer(&[], pts, duration);
+
+                    let _ = self.srcpad.push(buffer);
+                }
+
+                true
+            }
+            EventView::FlushStop(_) => {
+                self.state.lock().unwrap().cues.clear();
+
+                pad.event_default(Some(element), event)
+            }
+            EventView::Eos(_) => {
+                let cues = std::mem::take(&mut self.state.lock().unwrap().cues);
+
+                if let Some(first) = cues.first() {
+                    let pts = first.begin;
+                    let end = cues.iter().filter_map(|cue| cue.end).max();
+                    let buffer = Self::document_buffer(&cues, pts, end.map(|end| end - pts));
+
+                    gst::log!(CAT, obj: pad, "Pushing {:?}", buffer);
+
+                    let _ = self.srcpad.push(buffer);
+                }
+
+                pad.event_default(Some(element), event)
+            }
+            _ => pad.event_default(Some(element), event),
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for JsonToTtml {
+    const NAME: &'static str = "JsonToTtml";
+    type Type = super::JsonToTtml;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        let templ = klass.pad_template("sink").unwrap();
+        let sinkpad = gst::Pad::builder_with_template(&templ, Some("sink"))
+            .chain_function(|pad, parent, buffer| {
+                JsonToTtml::catch_panic_pad_function(
+                    parent,
+                    || Err(gst::FlowError::Error),
+                    |this, element| this.sink_chain(pad, element, buffer),
+                )
+            })
+            .event_function(|pad, parent, event| {
+                JsonToTtml::catch_panic_pad_function(
+                    parent,
+                    || false,
+                    |this, element| this.sink_event(pad, element, event),
+                )
+            })
+            .flags(gst::PadFlags::FIXED_CAPS)
+            .build();
+
+        let templ = klass.pad_template("src").unwrap();
+        let srcpad = gst::Pad::builder_with_template(&templ, Some("src"))
+            .flags(gst::PadFlags::FIXED_CAPS)
+            .build();
+
+        Self {
+            srcpad,
+            sinkpad,
+            settings: Mutex::new(Settings::default()),
+            state: Mutex::new(State::default()),
+        }
+    }
+}
+
+impl ObjectImpl for JsonToTtml {
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(&self.sinkpad).unwrap();
+        obj.add_pad(&self.srcpad).unwrap();
+    }
+
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![glib::ParamSpecBoolean::new(
+                "fragmented",
+                "Fragmented",
+                "Whether to output one document per caption, with empty documents \
+                 for gaps, as expected for stpp tracks, instead of a single document \
+                 at EOS",
+                DEFAULT_FRAGMENTED,
+                glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+            )]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        match pspec.name() {
+            "fragmented" => {
+                self.settings.lock().unwrap().fragmented =
+                    value.get().expect("type checked upstream");
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            "fragmented" => {
+                let settings = self.settings.lock().unwrap();
+                settings.fragmented.to_value()
+            }
+            _ => unimplemented!(),
+        }
+    }
+}
+
+impl GstObjectImpl for JsonToTtml {}
+
+impl ElementImpl for JsonToTtml {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "JSON to TTML",
+                "Generic",
+                "Converts JSON to TTML",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let caps = gst::Caps::builder("application/x-json")
+                .field("format", gst::List::new(["cea608", "cea708"]))
+                .build();
+
+            let sink_pad_template = gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            let caps = gst::Caps::builder("application/ttml+xml").build();
+
+            let src_pad_template = gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![src_pad_template, sink_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        if transition == gst::StateChange::ReadyToPaused {
+            let mut state = self.state.lock().unwrap();
+            *state = State::default();
+            state.settings = self.settings.lock().unwrap().clone();
+        }
+
+        self.parent_change_state(element, transition)
+    }
+}
@@ -0,0 +1,35 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+// Example command-line, converting CEA-608 captions to a TTML file:
+//
+// gst-launch-1.0 filesrc location=input.scc ! sccparse ! cea608tojson ! \
+//   jsontottml ! filesink location=output.ttml
+//
+// Or, as an stpp track:
+//
+// gst-launch-1.0 filesrc location=input.scc ! sccparse ! cea608tojson ! \
+//   jsontottml fragmented=true ! mp4mux ! filesink location=output.mp4
+
+use gst::glib;
+use gst::prelude::*;
+
+mod imp;
+
+glib::wrapper! {
+    pub struct JsonToTtml(ObjectSubclass<imp::JsonToTtml>) @extends gst::Element, gst::Object;
+}
+
+pub fn register(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
+    gst::Element::register(
+        Some(plugin),
+        "jsontottml",
+        gst::Rank::None,
+        JsonToTtml::static_type(),
+    )
+}
@@ -15,12 +15,18 @@ use gst::glib;
 #[allow(clippy::useless_transmute, clippy::trivially_copy_pass_by_ref)]
 mod ffi;
 
+mod a53;
 mod caption_frame;
+mod cccombiner;
 mod ccdetect;
+mod ccextractor;
 mod ccutils;
 mod cea608overlay;
 mod cea608tojson;
 mod cea608tott;
+mod cea708tojson;
+mod cea708utils;
+mod jsontottml;
 mod jsontovtt;
 mod line_reader;
 mod mcc_enc;
@@ -28,10 +34,15 @@ mod mcc_parse;
 mod parser_utils;
 mod scc_enc;
 mod scc_parse;
+mod subtitle_parse;
 mod transcriberbin;
+mod ttml;
+mod ttmlparse;
 mod tttocea608;
+mod tttocea708;
 mod tttojson;
 mod ttutils;
+mod xds;
 
 fn plugin_init(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
     mcc_parse::register(plugin)?;
@@ -40,11 +51,18 @@ fn plugin_init(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
     scc_enc::register(plugin)?;
     cea608tott::register(plugin)?;
     tttocea608::register(plugin)?;
+    tttocea708::register(plugin)?;
     cea608overlay::register(plugin)?;
     ccdetect::register(plugin)?;
+    ccextractor::register(plugin)?;
+    cccombiner::register(plugin)?;
     tttojson::register(plugin)?;
     cea608tojson::register(plugin)?;
+    cea708tojson::register(plugin)?;
     jsontovtt::register(plugin)?;
+    jsontottml::register(plugin)?;
+    ttmlparse::register(plugin)?;
+    subtitle_parse::register(plugin)?;
     transcriberbin::register(plugin)?;
     Ok(())
 }
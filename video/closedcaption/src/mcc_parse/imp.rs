@@ -18,9 +18,13 @@ use std::sync::{Mutex, MutexGuard};
 use once_cell::sync::Lazy;
 
 use super::parser::{MccLine, MccParser};
+use crate::ccutils;
 use crate::line_reader::LineReader;
 use crate::parser_utils::TimeCode;
 
+/// Minimum distance between two entries of the seek index
+const INDEX_INTERVAL: gst::ClockTime = gst::ClockTime::SECOND;
+
 static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
     gst::DebugCategory::new(
         "mccparse",
@@ -41,6 +45,11 @@ struct PullState {
     stream_id: String,
     offset: u64,
     duration: Option<gst::ClockTime>,
+    // Offsets of the current and the next line
+    line_start: u64,
+    line_offset: u64,
+    // Line offsets by timestamp, filled while parsing
+    index: Vec<(gst::ClockTime, u64)>,
 }
 
 impl PullState {
@@ -50,8 +59,22 @@ impl PullState {
             stream_id: pad.create_stream_id(element, Some("src")).to_string(),
             offset: 0,
             duration: gst::ClockTime::NONE,
+            line_start: 0,
+            line_offset: 0,
+            index: Vec::new(),
         }
     }
+
+    /// Moves the read position to the last indexed line before `position`,
+    /// returns whether the beginning of the file, and thus the header, was skipped
+    fn seek_index(&mut self, position: gst::ClockTime) -> bool {
+        let idx = self.index.partition_point(|&(time, _)| time <= position);
+
+        self.offset = if idx > 0 { self.index[idx - 1].1 } else { 0 };
+        self.line_offset = self.offset;
+
+        self.offset > 0
+    }
 }
 
 #[derive(Debug)]
@@ -72,6 +95,7 @@ struct State {
 
     // seeking
     seeking: bool,
+    seek_flush: bool,
     discont: bool,
     seek_seqnum: Option<gst::Seqnum>,
     last_raw_line: Vec<u8>,
@@ -94,6 +118,7 @@ impl Default for State {
             segment: gst::FormattedSegment::new(),
             pull: None,
             seeking: false,
+            seek_flush: false,
             discont: false,
             seek_seqnum: None,
             last_raw_line: Vec::new(),
@@ -140,6 +165,20 @@ fn parse_timecode_rate(
     Ok((framerate, drop_frame))
 }
 
+/// Checks the checksum following the user data words of an ancillary data
+/// packet, the sum of all the preceding bytes. Files written by `mccenc`
+/// only sum up the user data words, so that is accepted too.
+fn anc_checksum_valid(packet: &[u8], checksum: u8) -> bool {
+    let udw_sum = packet[3..]
+        .iter()
+        .fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    let sum = packet[..3]
+        .iter()
+        .fold(udw_sum, |sum, byte| sum.wrapping_add(*byte));
+
+    checksum == sum || checksum == udw_sum
+}
+
 impl State {
     #[allow(clippy::type_complexity)]
     fn line(&mut self, drain: bool) -> Result<Option<MccLine>, (&[u8], nom::error::Error<&[u8]>)> {
@@ -152,6 +191,11 @@ impl State {
                     return Ok(None);
                 }
                 Some(line) => {
+                    if let Some(pull) = &mut self.pull {
+                        pull.line_start = pull.line_offset;
+                        pull.line_offset += line.len() as u64;
+                    }
+
                     self.last_raw_line = line.to_vec();
                     line
                 }
@@ -164,6 +208,20 @@ impl State {
             .map_err(|err| (line, err))
     }
 
+    /// Remembers where the current line starts, to avoid parsing the
+    /// file from the beginning when seeking
+    fn update_index(&mut self, time: gst::ClockTime) {
+        if let Some(pull) = &mut self.pull {
+            if pull
+                .index
+                .last()
+                .map_or(true, |&(last, _)| time >= last + INDEX_INTERVAL)
+            {
+                pull.index.push((time, pull.line_start));
+            }
+        }
+    }
+
     fn handle_timecode(
         &mut self,
         element: &super::MccParse,
@@ -171,6 +229,16 @@ impl State {
         drop_frame: bool,
         tc: TimeCode,
     ) -> Result<ValidVideoTimeCode, gst::FlowError> {
+        // Drop-frame timecodes skipping the wrong frame numbers would
+        // otherwise silently make the timestamps drift
+        if drop_frame && tc.is_dropped_frame((framerate.numer() / 1000) as u32) {
+            gst::element_warning!(
+                element,
+                gst::StreamError::Decode,
+                ["Invalid drop-frame timecode {:?}", tc]
+            );
+        }
+
         match parse_timecode(framerate, drop_frame, tc) {
             Ok(timecode) => Ok(timecode),
             Err(timecode) => {
@@ -433,6 +501,28 @@ impl MccParse {
                         continue;
                     }
 
+                    let len = len as usize;
+                    if let Some(&checksum) = data.get(3 + len) {
+                        if !anc_checksum_valid(&data[..(3 + len)], checksum) {
+                            gst::element_warning!(
+                                element,
+                                gst::StreamError::Decode,
+                                ["Dropping caption line with invalid checksum at {:?}", tc]
+                            );
+                            continue;
+                        }
+                    }
+
+                    if format == Format::Cea708Cdp
+                        && !ccutils::cdp_checksum_valid(&data[3..(3 + len)])
+                    {
+                        gst::element_warning!(
+                            element,
+                            gst::StreamError::Decode,
+                            ["CDP packet with invalid checksum at {:?}", tc]
+                        );
+                    }
+
                     state = self.handle_line(element, tc, data, format, state)?;
                 }
                 Ok(Some(MccLine::Caption(tc, None))) => {
@@ -507,6 +597,7 @@ impl MccParse {
         let nsecs = timecode.time_since_daily_jam();
 
         state.last_timecode = Some(timecode);
+        state.update_index(nsecs);
 
         if state
             .segment
@@ -516,7 +607,7 @@ impl MccParse {
             state.seeking = false;
             state.discont = true;
             state.replay_last_line = true;
-            state.need_flush_stop = true;
+            state.need_flush_stop = state.seek_flush;
         }
 
         drop(state);
@@ -535,6 +626,7 @@ impl MccParse {
         let (framerate, drop_frame) = parse_timecode_rate(state.timecode_rate)?;
         let events = state.create_events(element, Some(format), framerate);
         let timecode = state.handle_timecode(element, framerate, drop_frame, tc)?;
+        state.update_index(timecode.time_since_daily_jam());
 
         let len = data[2] as usize;
         let mut buffer = gst::Buffer::from_mut_slice(OffsetVec {
@@ -735,24 +827,55 @@ impl MccParse {
     fn push_eos(&self, element: &super::MccParse) {
         let mut state = self.state.lock().unwrap();
 
-        if state.seeking {
+        if state.seeking && state.seek_flush {
             state.need_flush_stop = true;
         }
 
         match parse_timecode_rate(state.timecode_rate) {
             Ok((framerate, _)) => {
                 let mut events = state.create_events(element, None, framerate);
-                let mut eos_event = gst::event::Eos::builder();
 
-                if let Some(seek_seqnum) = state.seek_seqnum {
-                    eos_event = eos_event.seqnum(seek_seqnum);
-                }
+                // Segment seeks are finished with segment-done instead of EOS
+                let segment_done = if state.segment.flags().contains(gst::SegmentFlags::SEGMENT) {
+                    let position = state
+                        .segment
+                        .stop()
+                        .or_else(|| state.pull.as_ref().and_then(|pull| pull.duration));
+
+                    Some(gst::GenericFormattedValue::Time(position))
+                } else {
+                    None
+                };
 
-                events.push(eos_event.build());
+                if let Some(position) = segment_done {
+                    let mut segment_done_event = gst::event::SegmentDone::builder(position);
+
+                    if let Some(seek_seqnum) = state.seek_seqnum {
+                        segment_done_event = segment_done_event.seqnum(seek_seqnum);
+                    }
+
+                    events.push(segment_done_event.build());
+                } else {
+                    let mut eos_event = gst::event::Eos::builder();
+
+                    if let Some(seek_seqnum) = state.seek_seqnum {
+                        eos_event = eos_event.seqnum(seek_seqnum);
+                    }
+
+                    events.push(eos_event.build());
+                }
 
                 // Drop our state mutex while we push out events
                 drop(state);
 
+                if let Some(position) = segment_done {
+                    let _ = element.post_message(
+                        gst::message::SegmentDone::builder(position)
+                            .src(element)
+                            .build(),
+                    );
+                }
+
                 for event in events {
                     gst::debug!(CAT, obj: element, "Pushing event {:?}", event);
                     self.srcpad.push_event(event);
@@ -879,6 +1002,7 @@ impl MccParse {
         state.parser.reset();
         if let Some(pull) = &mut state.pull {
             pull.offset = 0;
+            pull.line_offset = 0;
         }
         state.segment = gst::FormattedSegment::new();
         state.need_segment = true;
@@ -941,10 +1065,13 @@ impl MccParse {
     }
 
     fn perform_seek(&self, event: &gst::event::Seek, element: &super::MccParse) -> bool {
-        if self.state.lock().unwrap().pull.is_none() {
-            gst::error!(CAT, obj: element, "seeking is only supported in pull mode");
-            return false;
-        }
+        let duration = match self.state.lock().unwrap().pull {
+            None => {
+                gst::error!(CAT, obj: element, "seeking is only supported in pull mode");
+                return false;
+            }
+            Some(ref pull) => pull.duration,
+        };
 
         let (rate, flags, start_type, start, stop_type, stop) = event.get();
 
@@ -964,65 +1091,102 @@ impl MccParse {
             }
         };
 
-        if !flags.contains(gst::SeekFlags::FLUSH) {
-            gst::error!(CAT, obj: element, "only flushing seeks are supported");
+        if rate < 0.0 {
+            gst::error!(CAT, obj: element, "reverse playback is not supported");
             return false;
         }
 
-        if start_type == gst::SeekType::End || stop_type == gst::SeekType::End {
-            gst::error!(CAT, obj: element, "Relative seeks are not supported");
+        if (start_type == gst::SeekType::End || stop_type == gst::SeekType::End)
+            && duration.is_none()
+        {
+            gst::error!(CAT, obj: element, "Relative seeks need a known duration");
             return false;
         }
 
+        let flush = flags.contains(gst::SeekFlags::FLUSH);
         let seek_seqnum = event.seqnum();
 
-        let event = gst::event::FlushStart::builder()
-            .seqnum(seek_seqnum)
-            .build();
+        if flush {
+            let event = gst::event::FlushStart::builder()
+                .seqnum(seek_seqnum)
+                .build();
 
-        gst::debug!(CAT, obj: element, "Sending event {:?} upstream", event);
-        self.sinkpad.push_event(event);
+            gst::debug!(CAT, obj: element, "Sending event {:?} upstream", event);
+            self.sinkpad.push_event(event);
 
-        let event = gst::event::FlushStart::builder()
-            .seqnum(seek_seqnum)
-            .build();
+            let event = gst::event::FlushStart::builder()
+                .seqnum(seek_seqnum)
+                .build();
 
-        gst::debug!(CAT, obj: element, "Pushing event {:?}", event);
-        self.srcpad.push_event(event);
+            gst::debug!(CAT, obj: element, "Pushing event {:?}", event);
+            self.srcpad.push_event(event);
+        }
 
         let _ = self.sinkpad.pause_task();
 
+        // Wait for the streaming thread to be done with the current buffer
+        let _stream_lock = self.sinkpad.stream_lock();
+
         let mut state = self.state.lock().unwrap();
-        let pull = state.pull.as_ref().unwrap();
 
         if start_type == gst::SeekType::Set {
-            start = start.opt_min(pull.duration).or(start);
+            start = start.opt_min(duration).or(start);
         }
 
         if stop_type == gst::SeekType::Set {
-            stop = stop.opt_min(pull.duration).or(stop);
+            stop = stop.opt_min(duration).or(stop);
         }
 
+        // Non-flushing seeks continue the running time of the current segment
+        let mut segment = if flush {
+            gst::FormattedSegment::new()
+        } else {
+            let mut segment = state.segment.clone();
+            segment.set_position(state.last_position);
+            segment
+        };
+
+        segment.set_duration(duration);
+        segment.do_seek(rate, flags, start_type, start, stop_type, stop);
+
         state.seeking = true;
+        state.seek_flush = flush;
         state.seek_seqnum = Some(seek_seqnum);
 
+        let timecode_rate = state.timecode_rate;
         state = self.flush(state);
 
-        let event = gst::event::FlushStop::builder(true)
-            .seqnum(seek_seqnum)
-            .build();
+        if flush {
+            let event = gst::event::FlushStop::builder(true)
+                .seqnum(seek_seqnum)
+                .build();
 
-        /* Drop our state while we push a serialized event upstream */
-        drop(state);
+            /* Drop our state while we push a serialized event upstream */
+            drop(state);
 
-        gst::debug!(CAT, obj: element, "Sending event {:?} upstream", event);
-        self.sinkpad.push_event(event);
+            gst::debug!(CAT, obj: element, "Sending event {:?} upstream", event);
+            self.sinkpad.push_event(event);
 
-        state = self.state.lock().unwrap();
+            state = self.state.lock().unwrap();
+        }
 
-        state
-            .segment
-            .do_seek(rate, flags, start_type, start, stop_type, stop);
+        let seek_position = segment.start().unwrap_or(gst::ClockTime::ZERO);
+        state.segment = segment;
+
+        // The header with the timecode rate is not parsed again when
+        // continuing from an indexed line
+        if state.pull.as_mut().unwrap().seek_index(seek_position) {
+            gst::debug!(
+                CAT,
+                obj: element,
+                "Continuing from indexed line before {}",
+                seek_position
+            );
+            state.parser = MccParser::new_scan_captions();
+            state.timecode_rate = timecode_rate;
+        }
+
+        drop(state);
 
         match self.start_task(element) {
             Err(error) => {
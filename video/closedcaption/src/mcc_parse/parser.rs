@@ -225,7 +225,9 @@ fn mcc_payload_item(s: &[u8]) -> IResult<&[u8], Either<u8, &'static [u8]>> {
             map(tag("R"), |_| Either::Right([0xfd, 0x80, 0x80].as_ref())),
             map(tag("S"), |_| Either::Right([0x96, 0x69].as_ref())),
             map(tag("T"), |_| Either::Right([0x61, 0x01].as_ref())),
-            map(tag("U"), |_| Either::Right([0xe1, 0x00, 0x00].as_ref())),
+            map(tag("U"), |_| {
+                Either::Right([0xe1, 0x00, 0x00, 0x00].as_ref())
+            }),
             map(tag("Z"), |_| Either::Right([0x00].as_ref())),
             map(take_while_m_n(2, 2, is_hex_digit), |s: &[u8]| {
                 let hex_to_u8 = |v: u8| match v {
@@ -602,8 +604,8 @@ mod tests {
                         0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00,
                         0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00,
                         0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00,
-                        0x73, 0x91, 0xE1, 0x00, 0x00, 0xC1, 0x3F, 0xFF, 0x74, 0x00, 0x00, 0xAE,
-                        0xB4
+                        0x73, 0x91, 0xE1, 0x00, 0x00, 0x00, 0xC1, 0x3F, 0xFF, 0x74, 0x00, 0x00,
+                        0xAE, 0xB4
                     ])
                 ),
             ))
@@ -628,8 +630,8 @@ mod tests {
                         0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00,
                         0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00,
                         0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00,
-                        0x73, 0x91, 0xE1, 0x00, 0x00, 0xC1, 0x3F, 0xFF, 0x74, 0x00, 0x00, 0xAE,
-                        0xB4
+                        0x73, 0x91, 0xE1, 0x00, 0x00, 0x00, 0xC1, 0x3F, 0xFF, 0x74, 0x00, 0x00,
+                        0xAE, 0xB4
                     ])
                 ),
             ))
@@ -654,8 +656,8 @@ mod tests {
                         0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00,
                         0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00,
                         0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00, 0xFA, 0x00, 0x00,
-                        0x73, 0x91, 0xE1, 0x00, 0x00, 0xC1, 0x3F, 0xFF, 0x74, 0x00, 0x00, 0xAE,
-                        0xB4
+                        0x73, 0x91, 0xE1, 0x00, 0x00, 0x00, 0xC1, 0x3F, 0xFF, 0x74, 0x00, 0x00,
+                        0xAE, 0xB4
                     ])
                 ),
             ))
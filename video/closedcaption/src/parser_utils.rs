@@ -17,6 +17,20 @@ pub struct TimeCode {
     pub drop_frame: bool,
 }
 
+impl TimeCode {
+    /// Whether this timecode uses one of the frame numbers that are skipped with drop-frame
+    /// timecodes at `fps` (30 or 60): the first ones of every minute, except every tenth minute
+    pub fn is_dropped_frame(&self, fps: u32) -> bool {
+        let dropped = match fps {
+            30 => 2,
+            60 => 4,
+            _ => return false,
+        };
+
+        self.seconds == 0 && self.minutes % 10 != 0 && self.frames < dropped
+    }
+}
+
 /// Parser for parsing a run of ASCII, decimal digits and converting them into a `u32`
 pub fn digits(s: &[u8]) -> IResult<&[u8], u32> {
     use nom::bytes::complete::take_while;
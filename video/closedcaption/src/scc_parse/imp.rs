@@ -21,6 +21,9 @@ use super::parser::{SccLine, SccParser};
 use crate::line_reader::LineReader;
 use crate::parser_utils::TimeCode;
 
+/// Minimum distance between two entries of the seek index
+const INDEX_INTERVAL: gst::ClockTime = gst::ClockTime::SECOND;
+
 static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
     gst::DebugCategory::new(
         "sccparse",
@@ -35,6 +38,11 @@ struct PullState {
     stream_id: String,
     offset: u64,
     duration: Option<gst::ClockTime>,
+    // Offsets of the current and the next line
+    line_start: u64,
+    line_offset: u64,
+    // Line offsets by timestamp, filled while parsing
+    index: Vec<(gst::ClockTime, u64)>,
 }
 
 impl PullState {
@@ -44,8 +52,22 @@ impl PullState {
             stream_id: pad.create_stream_id(element, Some("src")).to_string(),
             offset: 0,
             duration: gst::ClockTime::NONE,
+            line_start: 0,
+            line_offset: 0,
+            index: Vec::new(),
         }
     }
+
+    /// Moves the read position to the last indexed line before `position`,
+    /// returns whether the beginning of the file, and thus the header, was skipped
+    fn seek_index(&mut self, position: gst::ClockTime) -> bool {
+        let idx = self.index.partition_point(|&(time, _)| time <= position);
+
+        self.offset = if idx > 0 { self.index[idx - 1].1 } else { 0 };
+        self.line_offset = self.offset;
+
+        self.offset > 0
+    }
 }
 
 #[derive(Debug)]
@@ -57,6 +79,7 @@ struct State {
     framerate: Option<gst::Fraction>,
     last_position: Option<gst::ClockTime>,
     last_timecode: Option<gst_video::ValidVideoTimeCode>,
+    drop_frame: Option<bool>,
     segment: gst::FormattedSegment<gst::ClockTime>,
 
     // Pull mode
@@ -64,6 +87,7 @@ struct State {
 
     // seeking
     seeking: bool,
+    seek_flush: bool,
     discont: bool,
     seek_seqnum: Option<gst::Seqnum>,
     need_flush_stop: bool,
@@ -79,9 +103,11 @@ impl Default for State {
             framerate: None,
             last_position: None,
             last_timecode: None,
+            drop_frame: None,
             segment: gst::FormattedSegment::new(),
             pull: None,
             seeking: false,
+            seek_flush: false,
             discont: false,
             seek_seqnum: None,
             need_flush_stop: false,
@@ -96,11 +122,7 @@ fn parse_timecode(
     let mut tc = tc.clone();
     // Workaround for various SCC files having invalid drop frame timecodes:
     // Every full minute the first two timecodes are skipped, except for every tenth minute.
-    if tc.drop_frame
-        && tc.seconds == 0
-        && tc.minutes % 10 != 0
-        && (tc.frames == 0 || tc.frames == 1)
-    {
+    if tc.drop_frame && tc.is_dropped_frame(30) {
         tc.frames = 2;
     }
 
@@ -132,18 +154,69 @@ impl State {
             Some(line) => line,
         };
 
+        if let Some(pull) = &mut self.pull {
+            pull.line_start = pull.line_offset;
+            pull.line_offset += line.len() as u64;
+        }
+
         self.parser
             .parse_line(line)
             .map(Option::Some)
             .map_err(|err| (line, err))
     }
 
+    /// Warns about drop-frame timecodes breaking the sequence, the
+    /// timestamps would silently drift otherwise
+    fn check_timecode(&mut self, tc: &TimeCode, element: &super::SccParse) {
+        if self
+            .drop_frame
+            .map_or(false, |drop_frame| drop_frame != tc.drop_frame)
+        {
+            gst::element_warning!(
+                element,
+                gst::StreamError::Decode,
+                [
+                    "Timecode {:?} switches between drop-frame and non-drop-frame",
+                    tc
+                ]
+            );
+        }
+        self.drop_frame = Some(tc.drop_frame);
+
+        if tc.drop_frame && tc.is_dropped_frame(30) {
+            gst::element_warning!(
+                element,
+                gst::StreamError::Decode,
+                [
+                    "Invalid drop-frame timecode {:?}, using frame 2 instead",
+                    tc
+                ]
+            );
+        }
+    }
+
+    /// Remembers where the current line starts, to avoid parsing the
+    /// file from the beginning when seeking
+    fn update_index(&mut self, time: gst::ClockTime) {
+        if let Some(pull) = &mut self.pull {
+            if pull
+                .index
+                .last()
+                .map_or(true, |&(last, _)| time >= last + INDEX_INTERVAL)
+            {
+                pull.index.push((time, pull.line_start));
+            }
+        }
+    }
+
     fn handle_timecode(
         &mut self,
         tc: &TimeCode,
         framerate: gst::Fraction,
         element: &super::SccParse,
     ) -> Result<gst_video::ValidVideoTimeCode, gst::FlowError> {
+        self.check_timecode(tc, element);
+
         match parse_timecode(framerate, tc) {
             Ok(timecode) => Ok(timecode),
             Err(err) => {
@@ -357,6 +430,7 @@ impl SccParse {
 
         let mut timecode = state.handle_timecode(&tc, framerate, element)?;
         let start_time = timecode.time_since_daily_jam();
+        state.update_index(start_time);
         let segment_start = state.segment.start();
         let clip_buffers = if state.seeking {
             // If we are in the middle of seeking, check whether this line
@@ -380,7 +454,7 @@ impl SccParse {
             if segment_start.map_or(false, |seg_start| stop_time > seg_start) {
                 state.seeking = false;
                 state.discont = true;
-                state.need_flush_stop = true;
+                state.need_flush_stop = state.seek_flush;
             }
 
             // Still need to scan lines to find the first buffer
@@ -625,22 +699,53 @@ impl SccParse {
     fn push_eos(&self, element: &super::SccParse) {
         let mut state = self.state.lock().unwrap();
 
-        if state.seeking {
+        if state.seeking && state.seek_flush {
             state.need_flush_stop = true;
         }
 
         let mut events = state.create_events(element, None);
-        let mut eos_event = gst::event::Eos::builder();
 
-        if let Some(seek_seqnum) = state.seek_seqnum {
-            eos_event = eos_event.seqnum(seek_seqnum);
-        }
+        // Segment seeks are finished with segment-done instead of EOS
+        let segment_done = if state.segment.flags().contains(gst::SegmentFlags::SEGMENT) {
+            let position = state
+                .segment
+                .stop()
+                .or_else(|| state.pull.as_ref().and_then(|pull| pull.duration));
+
+            Some(gst::GenericFormattedValue::Time(position))
+        } else {
+            None
+        };
+
+        if let Some(position) = segment_done {
+            let mut segment_done_event = gst::event::SegmentDone::builder(position);
+
+            if let Some(seek_seqnum) = state.seek_seqnum {
+                segment_done_event = segment_done_event.seqnum(seek_seqnum);
+            }
+
+            events.push(segment_done_event.build());
+        } else {
+            let mut eos_event = gst::event::Eos::builder();
+
+            if let Some(seek_seqnum) = state.seek_seqnum {
+                eos_event = eos_event.seqnum(seek_seqnum);
+            }
 
-        events.push(eos_event.build());
+            events.push(eos_event.build());
+        }
 
         // Drop our state mutex while we push out events
         drop(state);
 
+        if let Some(position) = segment_done {
+            let _ = element.post_message(
+                gst::message::SegmentDone::builder(position)
+                    .src(element)
+                    .build(),
+            );
+        }
+
         for event in events {
             gst::debug!(CAT, obj: element, "Pushing event {:?}", event);
             self.srcpad.push_event(event);
@@ -757,12 +862,14 @@ impl SccParse {
         state.parser.reset();
         if let Some(pull) = &mut state.pull {
             pull.offset = 0;
+            pull.line_offset = 0;
         }
         state.segment = gst::FormattedSegment::new();
         state.need_segment = true;
         state.pending_events.clear();
         state.last_position = None;
         state.last_timecode = None;
+        state.drop_frame = None;
 
         drop(state);
 
@@ -816,10 +923,13 @@ impl SccParse {
     }
 
     fn perform_seek(&self, event: &gst::event::Seek, element: &super::SccParse) -> bool {
-        if self.state.lock().unwrap().pull.is_none() {
-            gst::error!(CAT, obj: element, "seeking is only supported in pull mode");
-            return false;
-        }
+        let duration = match self.state.lock().unwrap().pull {
+            None => {
+                gst::error!(CAT, obj: element, "seeking is only supported in pull mode");
+                return false;
+            }
+            Some(ref pull) => pull.duration,
+        };
 
         let (rate, flags, start_type, start, stop_type, stop) = event.get();
 
@@ -839,65 +949,98 @@ impl SccParse {
             }
         };
 
-        if !flags.contains(gst::SeekFlags::FLUSH) {
-            gst::error!(CAT, obj: element, "only flushing seeks are supported");
+        if rate < 0.0 {
+            gst::error!(CAT, obj: element, "reverse playback is not supported");
             return false;
         }
 
-        if start_type == gst::SeekType::End || stop_type == gst::SeekType::End {
-            gst::error!(CAT, obj: element, "Relative seeks are not supported");
+        if (start_type == gst::SeekType::End || stop_type == gst::SeekType::End)
+            && duration.is_none()
+        {
+            gst::error!(CAT, obj: element, "Relative seeks need a known duration");
             return false;
         }
 
+        let flush = flags.contains(gst::SeekFlags::FLUSH);
         let seek_seqnum = event.seqnum();
 
-        let event = gst::event::FlushStart::builder()
-            .seqnum(seek_seqnum)
-            .build();
+        if flush {
+            let event = gst::event::FlushStart::builder()
+                .seqnum(seek_seqnum)
+                .build();
 
-        gst::debug!(CAT, obj: element, "Sending event {:?} upstream", event);
-        self.sinkpad.push_event(event);
+            gst::debug!(CAT, obj: element, "Sending event {:?} upstream", event);
+            self.sinkpad.push_event(event);
 
-        let event = gst::event::FlushStart::builder()
-            .seqnum(seek_seqnum)
-            .build();
+            let event = gst::event::FlushStart::builder()
+                .seqnum(seek_seqnum)
+                .build();
 
-        gst::debug!(CAT, obj: element, "Pushing event {:?}", event);
-        self.srcpad.push_event(event);
+            gst::debug!(CAT, obj: element, "Pushing event {:?}", event);
+            self.srcpad.push_event(event);
+        }
 
         let _ = self.sinkpad.pause_task();
 
+        // Wait for the streaming thread to be done with the current buffer
+        let _stream_lock = self.sinkpad.stream_lock();
+
         let mut state = self.state.lock().unwrap();
-        let pull = state.pull.as_ref().unwrap();
 
         if start_type == gst::SeekType::Set {
-            start = start.opt_min(pull.duration).or(start);
+            start = start.opt_min(duration).or(start);
         }
 
         if stop_type == gst::SeekType::Set {
-            stop = stop.opt_min(pull.duration).or(stop);
+            stop = stop.opt_min(duration).or(stop);
         }
 
+        // Non-flushing seeks continue the running time of the current segment
+        let mut segment = if flush {
+            gst::FormattedSegment::new()
+        } else {
+            let mut segment = state.segment.clone();
+            segment.set_position(state.last_position);
+            segment
+        };
+
+        segment.set_duration(duration);
+        segment.do_seek(rate, flags, start_type, start, stop_type, stop);
+
         state.seeking = true;
+        state.seek_flush = flush;
         state.seek_seqnum = Some(seek_seqnum);
 
         state = self.flush(state);
 
-        let event = gst::event::FlushStop::builder(true)
-            .seqnum(seek_seqnum)
-            .build();
+        if flush {
+            let event = gst::event::FlushStop::builder(true)
+                .seqnum(seek_seqnum)
+                .build();
 
-        /* Drop our state while we push a serialized event upstream */
-        drop(state);
+            /* Drop our state while we push a serialized event upstream */
+            drop(state);
 
-        gst::debug!(CAT, obj: element, "Sending event {:?} upstream", event);
-        self.sinkpad.push_event(event);
+            gst::debug!(CAT, obj: element, "Sending event {:?} upstream", event);
+            self.sinkpad.push_event(event);
 
-        state = self.state.lock().unwrap();
+            state = self.state.lock().unwrap();
+        }
+
+        let seek_position = segment.start().unwrap_or(gst::ClockTime::ZERO);
+        state.segment = segment;
+
+        if state.pull.as_mut().unwrap().seek_index(seek_position) {
+            gst::debug!(
+                CAT,
+                obj: element,
+                "Continuing from indexed line before {}",
+                seek_position
+            );
+            state.parser = SccParser::new_scan_captions();
+        }
 
-        state
-            .segment
-            .do_seek(rate, flags, start_type, start, stop_type, stop);
+        drop(state);
 
         match self.start_task(element) {
             Err(error) => {
@@ -0,0 +1,1076 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+use gst::{element_error, loggable_error};
+
+use std::cmp;
+use std::sync::{Mutex, MutexGuard};
+
+use once_cell::sync::Lazy;
+
+use super::parser::{parse_timing_line, Cue, SubtitleParser};
+use crate::line_reader::LineReader;
+use crate::ttutils::{TextStyle, SPEAKER_EVENT_NAME};
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "subtitleparse",
+        gst::DebugColorFlags::empty(),
+        Some("WebVTT and SRT Parser Element"),
+    )
+});
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    PangoMarkup,
+    Json,
+}
+
+#[derive(Debug)]
+struct PullState {
+    need_stream_start: bool,
+    stream_id: String,
+    offset: u64,
+    duration: Option<gst::ClockTime>,
+}
+
+impl PullState {
+    fn new(element: &super::SubtitleParse, pad: &gst::Pad) -> Self {
+        Self {
+            need_stream_start: true,
+            stream_id: pad.create_stream_id(element, Some("src")).to_string(),
+            offset: 0,
+            duration: gst::ClockTime::NONE,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    reader: LineReader<gst::MappedBuffer<gst::buffer::Readable>>,
+    parser: SubtitleParser,
+    format: Option<OutputFormat>,
+    need_caps: bool,
+    need_segment: bool,
+    pending_events: Vec<gst::Event>,
+    last_position: Option<gst::ClockTime>,
+    last_voice: Option<String>,
+    segment: gst::FormattedSegment<gst::ClockTime>,
+
+    // Pull mode
+    pull: Option<PullState>,
+
+    // seeking
+    seeking: bool,
+    discont: bool,
+    seek_seqnum: Option<gst::Seqnum>,
+    need_flush_stop: bool,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            reader: LineReader::new(),
+            parser: SubtitleParser::new(),
+            format: None,
+            need_caps: false,
+            need_segment: true,
+            pending_events: Vec::new(),
+            last_position: None,
+            last_voice: None,
+            segment: gst::FormattedSegment::new(),
+            pull: None,
+            seeking: false,
+            discont: false,
+            seek_seqnum: None,
+            need_flush_stop: false,
+        }
+    }
+}
+
+fn markup_color(style: TextStyle) -> Option<&'static str> {
+    match style {
+        TextStyle::White | TextStyle::ItalicWhite => None,
+        TextStyle::Green => Some("#00ff00"),
+        TextStyle::Blue => Some("#0000ff"),
+        TextStyle::Cyan => Some("#00ffff"),
+        TextStyle::Red => Some("#ff0000"),
+        TextStyle::Yellow => Some("#ffff00"),
+        TextStyle::Magenta => Some("#ff00ff"),
+    }
+}
+
+fn cue_data(cue: &Cue, format: OutputFormat) -> Result<Vec<u8>, serde_json::Error> {
+    let data = match format {
+        OutputFormat::Json => return serde_json::to_vec(&cue.to_lines()),
+        OutputFormat::Text => cue
+            .lines
+            .iter()
+            .map(|spans| {
+                spans
+                    .iter()
+                    .map(|span| span.text.as_str())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        OutputFormat::PangoMarkup => cue
+            .lines
+            .iter()
+            .map(|spans| {
+                spans
+                    .iter()
+                    .map(|span| {
+                        let mut markup = glib::markup_escape_text(&span.text).to_string();
+
+                        if let Some(color) = span.style.color.and_then(markup_color) {
+                            markup = format!("<span foreground=\"{}\">{}</span>", color, markup);
+                        }
+                        if span.style.underline {
+                            markup = format!("<u>{}</u>", markup);
+                        }
+                        if span.style.bold {
+                            markup = format!("<b>{}</b>", markup);
+                        }
+                        if span.style.italic {
+                            markup = format!("<i>{}</i>", markup);
+                        }
+
+                        markup
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+
+    Ok(data.into_bytes())
+}
+
+impl State {
+    fn create_events(&mut self, element: &super::SubtitleParse) -> Vec<gst::Event> {
+        let mut events = Vec::new();
+
+        if self.need_flush_stop {
+            let mut b = gst::event::FlushStop::builder(true);
+
+            if let Some(seek_seqnum) = self.seek_seqnum {
+                b = b.seqnum(seek_seqnum);
+            }
+
+            events.push(b.build());
+            self.need_flush_stop = false;
+        }
+
+        if let Some(pull) = &mut self.pull {
+            if pull.need_stream_start {
+                events.push(gst::event::StreamStart::new(&pull.stream_id));
+                pull.need_stream_start = false;
+            }
+        }
+
+        if self.need_caps {
+            let caps = match self.format.unwrap() {
+                OutputFormat::Text => gst::Caps::builder("text/x-raw")
+                    .field("format", "utf8")
+                    .build(),
+                OutputFormat::PangoMarkup => gst::Caps::builder("text/x-raw")
+                    .field("format", "pango-markup")
+                    .build(),
+                OutputFormat::Json => gst::Caps::builder("application/x-json")
+                    .field("format", "cea608")
+                    .build(),
+            };
+
+            events.push(gst::event::Caps::new(&caps));
+            gst::info!(CAT, obj: element, "Caps changed to {:?}", &caps);
+            self.need_caps = false;
+        }
+
+        if self.need_segment {
+            let mut b = gst::event::Segment::builder(&self.segment);
+
+            if let Some(seek_seqnum) = self.seek_seqnum {
+                b = b.seqnum(seek_seqnum);
+            }
+
+            events.push(b.build());
+            self.need_segment = false;
+        }
+
+        events.append(&mut self.pending_events);
+        events
+    }
+}
+
+pub struct SubtitleParse {
+    srcpad: gst::Pad,
+    sinkpad: gst::Pad,
+    state: Mutex<State>,
+}
+
+impl SubtitleParse {
+    fn negotiate(&self, element: &super::SubtitleParse) -> Result<OutputFormat, gst::FlowError> {
+        let mut downstream_caps = match self.srcpad.allowed_caps() {
+            None => self.srcpad.pad_template_caps(),
+            Some(caps) => caps,
+        };
+
+        if downstream_caps.is_empty() {
+            gst::error!(CAT, obj: element, "Empty downstream caps");
+            return Err(gst::FlowError::NotNegotiated);
+        }
+
+        downstream_caps.fixate();
+
+        gst::debug!(
+            CAT,
+            obj: element,
+            "Negotiating for downstream caps {}",
+            downstream_caps
+        );
+
+        let s = downstream_caps.structure(0).unwrap();
+        Ok(if s.name() == "application/x-json" {
+            OutputFormat::Json
+        } else if s.get::<&str>("format").ok() == Some("pango-markup") {
+            OutputFormat::PangoMarkup
+        } else {
+            OutputFormat::Text
+        })
+    }
+
+    fn handle_buffer(
+        &self,
+        element: &super::SubtitleParse,
+        buffer: Option<gst::Buffer>,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        if self.state.lock().unwrap().format.is_none() {
+            let format = self.negotiate(element)?;
+            let mut state = self.state.lock().unwrap();
+            state.format = Some(format);
+            state.need_caps = true;
+        }
+
+        let mut state = self.state.lock().unwrap();
+
+        let drain = if let Some(buffer) = buffer {
+            let buffer = buffer.into_mapped_buffer_readable().map_err(|_| {
+                element_error!(
+                    element,
+                    gst::ResourceError::Read,
+                    ["Failed to map buffer readable"]
+                );
+
+                gst::FlowError::Error
+            })?;
+
+            state.reader.push(buffer);
+            false
+        } else {
+            true
+        };
+
+        loop {
+            let State { reader, parser, .. } = &mut *state;
+
+            let cue = match reader.line_with_drain(drain) {
+                Some(line) => parser.parse_line(line).map_err(|err| {
+                    element_error!(
+                        element,
+                        gst::StreamError::Decode,
+                        ["Couldn't parse line '{:?}': {:?}", line, err]
+                    );
+
+                    gst::FlowError::Error
+                })?,
+                None if drain => match parser.drain() {
+                    Some(cue) => Some(cue),
+                    None => {
+                        if state.pull.is_some() {
+                            break Err(gst::FlowError::Eos);
+                        }
+                        break Ok(gst::FlowSuccess::Ok);
+                    }
+                },
+                None => break Ok(gst::FlowSuccess::Ok),
+            };
+
+            if let Some(cue) = cue {
+                state = self.handle_cue(cue, element, state)?;
+            }
+        }
+    }
+
+    fn handle_cue(
+        &self,
+        cue: Cue,
+        element: &super::SubtitleParse,
+        mut state: MutexGuard<State>,
+    ) -> Result<MutexGuard<State>, gst::FlowError> {
+        gst::trace!(CAT, obj: element, "Got cue {:?}", cue);
+
+        let segment_start = state.segment.start();
+
+        if state.seeking {
+            // If we are in the middle of seeking, check whether this cue
+            // is displayed at the start position, and if so, unset seeking flag
+            if segment_start.map_or(true, |seg_start| cue.end > seg_start) {
+                state.seeking = false;
+                state.discont = true;
+                state.need_flush_stop = true;
+            } else {
+                // Still need to scan cues to find the first buffer
+                return Ok(state);
+            }
+        }
+
+        // Clip to the segment, the cues of a file may overlap
+        let start = segment_start.map_or(cue.start, |seg_start| cue.start.max(seg_start));
+        let mut end = cue.end;
+        let mut send_eos = false;
+
+        if let Some(seg_stop) = state.segment.stop() {
+            if start >= seg_stop {
+                return Err(gst::FlowError::Eos);
+            }
+
+            send_eos = end >= seg_stop;
+            end = end.min(seg_stop);
+        }
+
+        if end <= start {
+            gst::trace!(CAT, obj: element, "Skip segment clipped cue {:?}", cue);
+            return Ok(state);
+        }
+
+        let format = state.format.unwrap();
+        let data = cue_data(&cue, format).map_err(|err| {
+            element_error!(
+                element,
+                gst::ResourceError::Write,
+                ["Failed to serialize as json {}", err]
+            );
+
+            gst::FlowError::Error
+        })?;
+
+        let mut buffer = gst::Buffer::from_mut_slice(data);
+        {
+            let buffer = buffer.get_mut().unwrap();
+            buffer.set_pts(start);
+            buffer.set_duration(end - start);
+
+            if state.discont {
+                buffer.set_flags(gst::BufferFlags::DISCONT);
+                state.discont = false;
+            }
+        }
+
+        if state
+            .last_position
+            .map_or(true, |last_position| start >= last_position)
+        {
+            state.last_position = Some(start);
+        }
+
+        let mut events = state.create_events(element);
+
+        // Voice spans are announced the same way as transcribers announce
+        // speaker changes
+        if cue.voice.is_some() && cue.voice != state.last_voice {
+            state.last_voice = cue.voice.clone();
+
+            let mut b = gst::event::CustomDownstream::builder(
+                gst::Structure::builder(SPEAKER_EVENT_NAME)
+                    .field("speaker", &cue.voice)
+                    .build(),
+            );
+
+            if let Some(seek_seqnum) = state.seek_seqnum {
+                b = b.seqnum(seek_seqnum);
+            }
+
+            events.push(b.build());
+        }
+
+        // Drop our state mutex while we push out buffers or events
+        drop(state);
+
+        for event in events {
+            gst::debug!(CAT, obj: element, "Pushing event {:?}", event);
+            self.srcpad.push_event(event);
+        }
+
+        self.srcpad.push(buffer).map_err(|err| {
+            gst::error!(CAT, obj: element, "Pushing buffer returned {:?}", err);
+            err
+        })?;
+
+        if send_eos {
+            return Err(gst::FlowError::Eos);
+        }
+
+        Ok(self.state.lock().unwrap())
+    }
+
+    fn sink_activate(
+        &self,
+        pad: &gst::Pad,
+        element: &super::SubtitleParse,
+    ) -> Result<(), gst::LoggableError> {
+        let mode = {
+            let mut query = gst::query::Scheduling::new();
+            let mut state = self.state.lock().unwrap();
+
+            state.pull = None;
+
+            if !pad.peer_query(&mut query) {
+                gst::debug!(CAT, obj: pad, "Scheduling query failed on peer");
+                gst::PadMode::Push
+            } else if query
+                .has_scheduling_mode_with_flags(gst::PadMode::Pull, gst::SchedulingFlags::SEEKABLE)
+            {
+                gst::debug!(CAT, obj: pad, "Activating in Pull mode");
+
+                state.pull = Some(PullState::new(element, &self.srcpad));
+
+                gst::PadMode::Pull
+            } else {
+                gst::debug!(CAT, obj: pad, "Activating in Push mode");
+                gst::PadMode::Push
+            }
+        };
+
+        pad.activate_mode(mode, true)?;
+        Ok(())
+    }
+
+    fn start_task(&self, element: &super::SubtitleParse) -> Result<(), gst::LoggableError> {
+        let element_weak = element.downgrade();
+        let pad_weak = self.sinkpad.downgrade();
+        let res = self.sinkpad.start_task(move || {
+            let element = match element_weak.upgrade() {
+                Some(element) => element,
+                None => {
+                    if let Some(pad) = pad_weak.upgrade() {
+                        let _ = pad.pause_task();
+                    }
+                    return;
+                }
+            };
+
+            let parse = element.imp();
+            parse.loop_fn(&element);
+        });
+        if res.is_err() {
+            return Err(loggable_error!(CAT, "Failed to start pad task"));
+        }
+        Ok(())
+    }
+
+    fn sink_activatemode(
+        &self,
+        _pad: &gst::Pad,
+        element: &super::SubtitleParse,
+        mode: gst::PadMode,
+        active: bool,
+    ) -> Result<(), gst::LoggableError> {
+        if mode == gst::PadMode::Pull {
+            if active {
+                self.start_task(element)?;
+            } else {
+                let _ = self.sinkpad.stop_task();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The end of the last cue, cues don't have to be sorted but in
+    /// practice the last ones are the latest
+    fn scan_duration(
+        &self,
+        element: &super::SubtitleParse,
+    ) -> Result<Option<gst::ClockTime>, gst::LoggableError> {
+        gst::debug!(CAT, obj: element, "Scanning duration");
+
+        /* First let's query the bytes duration upstream */
+        let mut q = gst::query::Duration::new(gst::Format::Bytes);
+
+        if !self.sinkpad.peer_query(&mut q) {
+            return Err(loggable_error!(CAT, "Failed to query upstream duration"));
+        }
+
+        let size = match q.result().try_into().unwrap() {
+            Some(gst::format::Bytes(size)) => size,
+            None => {
+                return Err(loggable_error!(CAT, "Failed to query upstream duration"));
+            }
+        };
+
+        let mut offset = size;
+        let mut buffers = Vec::new();
+        let mut last_end = None;
+
+        loop {
+            let scan_size = cmp::min(offset, 4096);
+
+            offset -= scan_size;
+
+            match self.sinkpad.pull_range(offset, scan_size as u32) {
+                Ok(buffer) => {
+                    buffers.push(buffer);
+                }
+                Err(flow) => {
+                    return Err(loggable_error!(
+                        CAT,
+                        "Failed to pull buffer while scanning duration: {:?}",
+                        flow
+                    ));
+                }
+            }
+
+            let mut reader = LineReader::new();
+
+            for buf in buffers.iter().rev() {
+                let buf = buf
+                    .clone()
+                    .into_mapped_buffer_readable()
+                    .map_err(|_| loggable_error!(CAT, "Failed to map buffer readable"))?;
+
+                reader.push(buf);
+            }
+
+            while let Some(line) = reader.line_with_drain(true) {
+                if let Some((_, end)) = parse_timing_line(line) {
+                    last_end = cmp::max(last_end, Some(end));
+                }
+            }
+
+            if last_end.is_some() || offset == 0 {
+                gst::debug!(
+                    CAT,
+                    obj: element,
+                    "Duration scan done, last_end: {:?}",
+                    last_end
+                );
+                break (Ok(last_end));
+            }
+        }
+    }
+
+    fn push_eos(&self, element: &super::SubtitleParse) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.seeking {
+            state.need_flush_stop = true;
+        }
+
+        let mut events = state.create_events(element);
+        let mut eos_event = gst::event::Eos::builder();
+
+        if let Some(seek_seqnum) = state.seek_seqnum {
+            eos_event = eos_event.seqnum(seek_seqnum);
+        }
+
+        events.push(eos_event.build());
+
+        // Drop our state mutex while we push out events
+        drop(state);
+
+        for event in events {
+            gst::debug!(CAT, obj: element, "Pushing event {:?}", event);
+            self.srcpad.push_event(event);
+        }
+    }
+
+    fn loop_fn(&self, element: &super::SubtitleParse) {
+        let mut state = self.state.lock().unwrap();
+        let mut pull = state.pull.as_mut().unwrap();
+        let scan_duration = pull.duration.is_none();
+        let offset = pull.offset;
+
+        pull.offset += 4096;
+
+        drop(state);
+
+        let buffer = match self.sinkpad.pull_range(offset, 4096) {
+            Ok(buffer) => Some(buffer),
+            Err(gst::FlowError::Eos) => None,
+            Err(gst::FlowError::Flushing) => {
+                gst::debug!(CAT, obj: &self.sinkpad, "Pausing after pulling buffer, reason: flushing");
+
+                let _ = self.sinkpad.pause_task();
+                return;
+            }
+            Err(flow) => {
+                gst::error!(CAT, obj: &self.sinkpad, "Failed to pull, reason: {:?}", flow);
+
+                element_error!(
+                    element,
+                    gst::StreamError::Failed,
+                    ["Streaming stopped, failed to pull buffer"]
+                );
+
+                let _ = self.sinkpad.pause_task();
+                return;
+            }
+        };
+
+        if scan_duration {
+            match self.scan_duration(element) {
+                Ok(duration) => {
+                    let mut state = self.state.lock().unwrap();
+                    let mut pull = state.pull.as_mut().unwrap();
+                    pull.duration = Some(duration.unwrap_or(gst::ClockTime::ZERO));
+                }
+                Err(err) => {
+                    err.log();
+
+                    element_error!(
+                        element,
+                        gst::StreamError::Decode,
+                        ["Failed to scan duration"]
+                    );
+
+                    let _ = self.sinkpad.pause_task();
+                    return;
+                }
+            }
+        }
+
+        if let Err(flow) = self.handle_buffer(element, buffer) {
+            match flow {
+                gst::FlowError::Flushing => {
+                    gst::debug!(CAT, obj: element, "Pausing after flow {:?}", flow);
+                }
+                gst::FlowError::Eos => {
+                    self.push_eos(element);
+
+                    gst::debug!(CAT, obj: element, "Pausing after flow {:?}", flow);
+                }
+                _ => {
+                    self.push_eos(element);
+
+                    gst::error!(CAT, obj: element, "Pausing after flow {:?}", flow);
+
+                    element_error!(
+                        element,
+                        gst::StreamError::Failed,
+                        ["Streaming stopped, reason: {:?}", flow]
+                    );
+                }
+            }
+
+            let _ = self.sinkpad.pause_task();
+        }
+    }
+
+    fn sink_chain(
+        &self,
+        pad: &gst::Pad,
+        element: &super::SubtitleParse,
+        buffer: gst::Buffer,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        gst::log!(CAT, obj: pad, "Handling buffer {:?}", buffer);
+
+        self.handle_buffer(element, Some(buffer))
+    }
+
+    fn flush(&self, mut state: MutexGuard<State>) -> MutexGuard<State> {
+        state.reader.clear();
+        state.parser.reset();
+        if let Some(pull) = &mut state.pull {
+            pull.offset = 0;
+        }
+        state.segment = gst::FormattedSegment::new();
+        state.need_segment = true;
+        state.pending_events.clear();
+        state.last_position = None;
+        state.last_voice = None;
+
+        drop(state);
+
+        self.state.lock().unwrap()
+    }
+
+    fn sink_event(
+        &self,
+        pad: &gst::Pad,
+        element: &super::SubtitleParse,
+        event: gst::Event,
+    ) -> bool {
+        use gst::EventView;
+
+        gst::log!(CAT, obj: pad, "Handling event {:?}", event);
+
+        match event.view() {
+            EventView::Caps(_) => {
+                // We send a proper caps event from the chain function later
+                gst::log!(CAT, obj: pad, "Dropping caps event");
+                true
+            }
+            EventView::Segment(_) => {
+                // We send a gst::Format::Time segment event later when needed
+                gst::log!(CAT, obj: pad, "Dropping segment event");
+                true
+            }
+            EventView::FlushStop(_) => {
+                let state = self.state.lock().unwrap();
+                let state = self.flush(state);
+                drop(state);
+
+                pad.event_default(Some(element), event)
+            }
+            EventView::Eos(_) => {
+                gst::log!(CAT, obj: pad, "Draining");
+                if let Err(err) = self.handle_buffer(element, None) {
+                    gst::error!(CAT, obj: pad, "Failed to drain parser: {:?}", err);
+                }
+                pad.event_default(Some(element), event)
+            }
+            _ => {
+                if event.is_sticky()
+                    && !self.srcpad.has_current_caps()
+                    && event.type_() > gst::EventType::Caps
+                {
+                    gst::log!(CAT, obj: pad, "Deferring sticky event until we have caps");
+                    let mut state = self.state.lock().unwrap();
+                    state.pending_events.push(event);
+                    true
+                } else {
+                    pad.event_default(Some(element), event)
+                }
+            }
+        }
+    }
+
+    fn perform_seek(&self, event: &gst::event::Seek, element: &super::SubtitleParse) -> bool {
+        if self.state.lock().unwrap().pull.is_none() {
+            gst::error!(CAT, obj: element, "seeking is only supported in pull mode");
+            return false;
+        }
+
+        let (rate, flags, start_type, start, stop_type, stop) = event.get();
+
+        let mut start: Option<gst::ClockTime> = match start.try_into() {
+            Ok(start) => start,
+            Err(_) => {
+                gst::error!(CAT, obj: element, "seek has invalid format");
+                return false;
+            }
+        };
+
+        let mut stop: Option<gst::ClockTime> = match stop.try_into() {
+            Ok(stop) => stop,
+            Err(_) => {
+                gst::error!(CAT, obj: element, "seek has invalid format");
+                return false;
+            }
+        };
+
+        if !flags.contains(gst::SeekFlags::FLUSH) {
+            gst::error!(CAT, obj: element, "only flushing seeks are supported");
+            return false;
+        }
+
+        if start_type == gst::SeekType::End || stop_type == gst::SeekType::End {
+            gst::error!(CAT, obj: element, "Relative seeks are not supported");
+            return false;
+        }
+
+        let seek_seqnum = event.seqnum();
+
+        let event = gst::event::FlushStart::builder()
+            .seqnum(seek_seqnum)
+            .build();
+
+        gst::debug!(CAT, obj: element, "Sending event {:?} upstream", event);
+        self.sinkpad.push_event(event);
+
+        let event = gst::event::FlushStart::builder()
+            .seqnum(seek_seqnum)
+            .build();
+
+        gst::debug!(CAT, obj: element, "Pushing event {:?}", event);
+        self.srcpad.push_event(event);
+
+        let _ = self.sinkpad.pause_task();
+
+        let mut state = self.state.lock().unwrap();
+        let pull = state.pull.as_ref().unwrap();
+
+        if start_type == gst::SeekType::Set {
+            start = start.opt_min(pull.duration).or(start);
+        }
+
+        if stop_type == gst::SeekType::Set {
+            stop = stop.opt_min(pull.duration).or(stop);
+        }
+
+        state.seeking = true;
+        state.seek_seqnum = Some(seek_seqnum);
+
+        state = self.flush(state);
+
+        let event = gst::event::FlushStop::builder(true)
+            .seqnum(seek_seqnum)
+            .build();
+
+        /* Drop our state while we push a serialized event upstream */
+        drop(state);
+
+        gst::debug!(CAT, obj: element, "Sending event {:?} upstream", event);
+        self.sinkpad.push_event(event);
+
+        state = self.state.lock().unwrap();
+
+        state
+            .segment
+            .do_seek(rate, flags, start_type, start, stop_type, stop);
+
+        match self.start_task(element) {
+            Err(error) => {
+                error.log();
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn src_event(&self, pad: &gst::Pad, element: &super::SubtitleParse, event: gst::Event) -> bool {
+        use gst::EventView;
+
+        gst::log!(CAT, obj: pad, "Handling event {:?}", event);
+        match event.view() {
+            EventView::Seek(e) => self.perform_seek(e, element),
+            _ => pad.event_default(Some(element), event),
+        }
+    }
+
+    fn src_query(
+        &self,
+        pad: &gst::Pad,
+        element: &super::SubtitleParse,
+        query: &mut gst::QueryRef,
+    ) -> bool {
+        use gst::QueryViewMut;
+
+        gst::log!(CAT, obj: pad, "Handling query {:?}", query);
+
+        match query.view_mut() {
+            QueryViewMut::Seeking(q) => {
+                let state = self.state.lock().unwrap();
+
+                let fmt = q.format();
+
+                if fmt == gst::Format::Time {
+                    if let Some(pull) = state.pull.as_ref() {
+                        q.set(
+                            true,
+                            gst::GenericFormattedValue::Time(gst::ClockTime::ZERO.into()),
+                            gst::GenericFormattedValue::Time(pull.duration),
+                        );
+                        true
+                    } else {
+                        false
+                    }
+                } else {
+                    false
+                }
+            }
+            QueryViewMut::Position(q) => {
+                // For Time answer ourselfs, otherwise forward
+                if q.format() == gst::Format::Time {
+                    let state = self.state.lock().unwrap();
+                    q.set(state.last_position);
+                    true
+                } else {
+                    self.sinkpad.peer_query(query)
+                }
+            }
+            QueryViewMut::Duration(q) => {
+                // For Time answer ourselfs, otherwise forward
+                let state = self.state.lock().unwrap();
+                if q.format() == gst::Format::Time {
+                    match state.pull.as_ref().and_then(|pull| pull.duration) {
+                        Some(duration) => {
+                            q.set(duration);
+                            true
+                        }
+                        None => false,
+                    }
+                } else {
+                    self.sinkpad.peer_query(query)
+                }
+            }
+            _ => pad.query_default(Some(element), query),
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for SubtitleParse {
+    const NAME: &'static str = "RsSubtitleParse";
+    type Type = super::SubtitleParse;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        let templ = klass.pad_template("sink").unwrap();
+        let sinkpad = gst::Pad::builder_with_template(&templ, Some("sink"))
+            .activate_function(|pad, parent| {
+                SubtitleParse::catch_panic_pad_function(
+                    parent,
+                    || Err(loggable_error!(CAT, "Panic activating sink pad")),
+                    |parse, element| parse.sink_activate(pad, element),
+                )
+            })
+            .activatemode_function(|pad, parent, mode, active| {
+                SubtitleParse::catch_panic_pad_function(
+                    parent,
+                    || Err(loggable_error!(CAT, "Panic activating sink pad with mode")),
+                    |parse, element| parse.sink_activatemode(pad, element, mode, active),
+                )
+            })
+            .chain_function(|pad, parent, buffer| {
+                SubtitleParse::catch_panic_pad_function(
+                    parent,
+                    || Err(gst::FlowError::Error),
+                    |parse, element| parse.sink_chain(pad, element, buffer),
+                )
+            })
+            .event_function(|pad, parent, event| {
+                SubtitleParse::catch_panic_pad_function(
+                    parent,
+                    || false,
+                    |parse, element| parse.sink_event(pad, element, event),
+                )
+            })
+            .build();
+
+        let templ = klass.pad_template("src").unwrap();
+        let srcpad = gst::Pad::builder_with_template(&templ, Some("src"))
+            .event_function(|pad, parent, event| {
+                SubtitleParse::catch_panic_pad_function(
+                    parent,
+                    || false,
+                    |parse, element| parse.src_event(pad, element, event),
+                )
+            })
+            .query_function(|pad, parent, query| {
+                SubtitleParse::catch_panic_pad_function(
+                    parent,
+                    || false,
+                    |parse, element| parse.src_query(pad, element, query),
+                )
+            })
+            .build();
+
+        Self {
+            srcpad,
+            sinkpad,
+            state: Mutex::new(State::default()),
+        }
+    }
+}
+
+impl ObjectImpl for SubtitleParse {
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(&self.sinkpad).unwrap();
+        obj.add_pad(&self.srcpad).unwrap();
+    }
+}
+
+impl GstObjectImpl for SubtitleParse {}
+
+impl ElementImpl for SubtitleParse {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "Subtitle Parse",
+                "Parser/Subtitle",
+                "Parses WebVTT and SRT subtitle files into timed text or JSON",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let mut caps = gst::Caps::new_empty();
+            {
+                let caps = caps.get_mut().unwrap();
+                caps.append(
+                    gst::Caps::builder("text/x-raw")
+                        .field("format", gst::List::new(["utf8", "pango-markup"]))
+                        .build(),
+                );
+                caps.append(
+                    gst::Caps::builder("application/x-json")
+                        .field("format", "cea608")
+                        .build(),
+                );
+            }
+
+            let src_pad_template = gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            let mut caps = gst::Caps::new_empty();
+            {
+                let caps = caps.get_mut().unwrap();
+                caps.append(gst::Caps::builder("application/x-subtitle-vtt").build());
+                caps.append(gst::Caps::builder("application/x-subtitle").build());
+            }
+
+            let sink_pad_template = gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![src_pad_template, sink_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        match transition {
+            gst::StateChange::ReadyToPaused | gst::StateChange::PausedToReady => {
+                // Reset the whole state
+                let mut state = self.state.lock().unwrap();
+                *state = State::default();
+            }
+            _ => (),
+        }
+
+        self.parent_change_state(element, transition)
+    }
+}
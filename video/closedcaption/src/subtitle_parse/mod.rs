@@ -0,0 +1,37 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+// Example command-lines:
+//
+// gst-launch-1.0 filesrc location=input.srt ! subtitleparse ! text/x-raw ! \
+//   fakesink dump=true
+//
+// gst-launch-1.0 filesrc location=input.vtt ! subtitleparse ! application/x-json ! \
+//   tttocea608 ! appsink
+//
+// The format is detected from the content: files starting with a `WEBVTT`
+// header are parsed as WebVTT, anything else as SRT.
+
+use gst::glib;
+use gst::prelude::*;
+
+mod imp;
+mod parser;
+
+glib::wrapper! {
+    pub struct SubtitleParse(ObjectSubclass<imp::SubtitleParse>) @extends gst::Element, gst::Object;
+}
+
+pub fn register(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
+    gst::Element::register(
+        Some(plugin),
+        "subtitleparse",
+        gst::Rank::None,
+        SubtitleParse::static_type(),
+    )
+}
@@ -0,0 +1,793 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::cea708utils::Color;
+use crate::parser_utils::{digits, digits_range};
+use crate::ttutils::{Chunk, Line, Lines, TextStyle};
+
+use nom::IResult;
+
+const GRID_ROWS: i32 = 15;
+const GRID_COLUMNS: i32 = 32;
+
+/* Position and size of the safe title area, in percents of the video */
+const SAFE_AREA_ORIGIN: f64 = 10.0;
+const SAFE_AREA_EXTENT: f64 = 80.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    WebVtt,
+    Srt,
+}
+
+/// The `line` cue setting
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LinePosition {
+    /// Line number, counted from the bottom when negative
+    Lines(i32),
+    Percent(f64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+    Left,
+    Right,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CueSettings {
+    pub line: Option<LinePosition>,
+    /// In percents of the video width
+    pub position: Option<f64>,
+    pub align: Option<Align>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SpanStyle {
+    pub italic: bool,
+    pub bold: bool,
+    pub underline: bool,
+    /// One of the CEA-608 colors
+    pub color: Option<TextStyle>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Span {
+    pub style: SpanStyle,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cue {
+    pub start: gst::ClockTime,
+    pub end: gst::ClockTime,
+    pub settings: CueSettings,
+    /// Name of the first voice span
+    pub voice: Option<String>,
+    pub lines: Vec<Vec<Span>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    /* Before the first non-empty line */
+    Start,
+    /* WebVTT header, until the first empty line */
+    Header,
+    /* Between blocks */
+    Blocks,
+    /* After a cue identifier */
+    Timing,
+    /* NOTE, STYLE and REGION blocks */
+    Skip,
+    Text,
+}
+
+/* A cue whose text is being read */
+#[derive(Debug)]
+struct PendingCue {
+    start: gst::ClockTime,
+    end: gst::ClockTime,
+    settings: CueSettings,
+    text: Vec<String>,
+}
+
+/// WebVTT and SRT parser, fed line by line
+#[derive(Debug)]
+pub struct SubtitleParser {
+    format: Option<Format>,
+    state: State,
+    cue: Option<PendingCue>,
+}
+
+/// Parser for timestamps in the form `hh:mm:ss.ttt` or `mm:ss.ttt`,
+/// SRT files use a comma as the decimal separator
+fn timestamp(s: &[u8]) -> IResult<&[u8], gst::ClockTime> {
+    use nom::bytes::complete::take_while_m_n;
+    use nom::character::complete::{char, one_of};
+    use nom::character::is_digit;
+    use nom::combinator::{map, opt};
+    use nom::error::context;
+    use nom::sequence::{preceded, tuple};
+
+    context(
+        "invalid timestamp",
+        map(
+            tuple((
+                digits,
+                char(':'),
+                digits_range(0..60),
+                opt(preceded(char(':'), digits_range(0..60))),
+                one_of(".,"),
+                take_while_m_n(1, 3, is_digit),
+            )),
+            |(first, _, second, third, _, fraction): (_, _, _, _, _, &[u8])| {
+                let (hours, minutes, seconds) = match third {
+                    Some(seconds) => (first, second, seconds),
+                    None => (0, first, second),
+                };
+
+                let mut ms = fraction
+                    .iter()
+                    .fold(0u64, |acc, digit| acc * 10 + (digit - b'0') as u64);
+                for _ in fraction.len()..3 {
+                    ms *= 10;
+                }
+
+                gst::ClockTime::from_mseconds(
+                    (hours as u64 * 3600 + minutes as u64 * 60 + seconds as u64) * 1000 + ms,
+                )
+            },
+        ),
+    )(s)
+}
+
+/// Parser for a timing line in the form `start --> end settings`,
+/// returning the unparsed settings
+fn timing(s: &[u8]) -> IResult<&[u8], (gst::ClockTime, gst::ClockTime)> {
+    use nom::bytes::complete::tag;
+    use nom::character::complete::{space0, space1};
+    use nom::error::context;
+    use nom::sequence::tuple;
+
+    let (rest, (start, _, _, _, end)) = context(
+        "invalid timing line",
+        tuple((timestamp, space1, tag("-->"), space1, timestamp)),
+    )(s)?;
+
+    let (rest, _) = space0(rest)?;
+
+    Ok((rest, (start, end)))
+}
+
+/// Returns the timing of `line` when it's a timing line
+pub fn parse_timing_line(line: &[u8]) -> Option<(gst::ClockTime, gst::ClockTime)> {
+    timing(trim_line(line)).ok().map(|(_, timing)| timing)
+}
+
+fn trim_line(line: &[u8]) -> &[u8] {
+    let line = line
+        .strip_prefix(&[0xEFu8, 0xBBu8, 0xBFu8][..])
+        .unwrap_or(line);
+    let len = line
+        .iter()
+        .rposition(|b| *b != b'\n' && *b != b'\r')
+        .map_or(0, |pos| pos + 1);
+
+    &line[..len]
+}
+
+/* WebVTT cue settings, SRT coordinates are ignored */
+fn parse_settings(s: &str) -> CueSettings {
+    let mut settings = CueSettings::default();
+
+    for setting in s.split_whitespace() {
+        let (name, value) = match setting.split_once(':') {
+            Some(setting) => setting,
+            None => continue,
+        };
+        /* Alignments of the line and position settings are not supported */
+        let value = value.split(',').next().unwrap();
+
+        match name {
+            "line" => {
+                settings.line = if let Some(percent) = value.strip_suffix('%') {
+                    percent.parse().ok().map(LinePosition::Percent)
+                } else {
+                    value.parse().ok().map(LinePosition::Lines)
+                };
+            }
+            "position" => {
+                settings.position = value.strip_suffix('%').and_then(|p| p.parse().ok());
+            }
+            "align" => {
+                settings.align = match value {
+                    "start" => Some(Align::Start),
+                    "center" | "middle" => Some(Align::Center),
+                    "end" => Some(Align::End),
+                    "left" => Some(Align::Left),
+                    "right" => Some(Align::Right),
+                    _ => None,
+                };
+            }
+            _ => (),
+        }
+    }
+
+    settings
+}
+
+/* The closest CEA-608 color, for WebVTT color classes and the color
+ * attribute of SRT font tags */
+fn parse_color(s: &str) -> Option<TextStyle> {
+    let s = s.trim().trim_matches('"');
+
+    let (r, g, b) = if let Some(hex) = s.strip_prefix('#') {
+        let component = |idx: usize| u8::from_str_radix(hex.get(idx..idx + 2)?, 16).ok();
+        (component(0)?, component(2)?, component(4)?)
+    } else {
+        match s.to_ascii_lowercase().as_str() {
+            "white" | "black" => (255, 255, 255),
+            "lime" | "green" => (0, 255, 0),
+            "blue" => (0, 0, 255),
+            "cyan" | "aqua" => (0, 255, 255),
+            "red" => (255, 0, 0),
+            "yellow" => (255, 255, 0),
+            "magenta" | "fuchsia" => (255, 0, 255),
+            _ => return None,
+        }
+    };
+
+    Some(
+        Color {
+            r: r >> 6,
+            g: g >> 6,
+            b: b >> 6,
+            opacity: 0,
+        }
+        .to_text_style(false),
+    )
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        "lrm" => Some('\u{200e}'),
+        "rlm" => Some('\u{200f}'),
+        _ => {
+            let code = entity.strip_prefix('#')?;
+            let code = match code.strip_prefix('x').or_else(|| code.strip_prefix('X')) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+
+            char::from_u32(code)
+        }
+    }
+}
+
+/* A tag of the cue text, with the resulting style */
+#[derive(Debug)]
+struct OpenTag {
+    name: String,
+    style: SpanStyle,
+    /* Ruby text is not rendered */
+    hidden: bool,
+}
+
+/* Appends `text` to the spans, merging spans of the same style */
+fn push_text(spans: &mut Vec<Span>, style: SpanStyle, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+
+    match spans.last_mut() {
+        Some(span) if span.style == style => span.text.push_str(text),
+        _ => spans.push(Span {
+            style,
+            text: text.to_string(),
+        }),
+    }
+}
+
+/* Appends the pending text with the style of the innermost open tag */
+fn flush(lines: &mut [Vec<Span>], stack: &[OpenTag], current: &mut String) {
+    let (style, hidden) = stack
+        .last()
+        .map_or((SpanStyle::default(), false), |tag| (tag.style, tag.hidden));
+
+    if !hidden {
+        push_text(lines.last_mut().unwrap(), style, current);
+    }
+
+    current.clear();
+}
+
+/* SRT files often contain SSA override blocks, only the alignment is
+ * interpreted */
+fn parse_override(block: &str, settings: &mut CueSettings) {
+    for tag in block.split('\\') {
+        let alignment = match tag.strip_prefix("an").and_then(|a| a.parse::<u32>().ok()) {
+            Some(alignment @ 1..=9) => alignment,
+            _ => continue,
+        };
+
+        settings.line = match (alignment - 1) / 3 {
+            0 => None,
+            1 => Some(LinePosition::Percent(50.0)),
+            _ => Some(LinePosition::Lines(0)),
+        };
+
+        settings.align = match (alignment - 1) % 3 {
+            0 => Some(Align::Left),
+            1 => Some(Align::Center),
+            _ => Some(Align::Right),
+        };
+    }
+}
+
+/// Parses the markup of the cue text into lines of spans, returns the
+/// first voice as well
+fn parse_cue_text(
+    text: &str,
+    format: Format,
+    settings: &mut CueSettings,
+) -> (Vec<Vec<Span>>, Option<String>) {
+    let mut lines = vec![vec![]];
+    let mut voice = None;
+    let mut stack: Vec<OpenTag> = vec![];
+    let mut current = String::new();
+
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\n' => {
+                flush(&mut lines, &stack, &mut current);
+                lines.push(vec![]);
+            }
+            '<' => {
+                let end = match text[idx..].find('>') {
+                    Some(end) => idx + end,
+                    None => {
+                        current.push(c);
+                        continue;
+                    }
+                };
+
+                flush(&mut lines, &stack, &mut current);
+
+                let tag = &text[idx + 1..end];
+                while chars.peek().map_or(false, |(next, _)| *next <= end) {
+                    chars.next();
+                }
+
+                if let Some(name) = tag.strip_prefix('/') {
+                    let name = name.trim().to_ascii_lowercase();
+                    if let Some(pos) = stack.iter().rposition(|tag| tag.name == name) {
+                        stack.truncate(pos);
+                    }
+                    continue;
+                }
+
+                let (name, annotation) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+                let mut classes = name.split('.');
+                let name = classes.next().unwrap().to_ascii_lowercase();
+
+                /* Timestamps of karaoke-style cues */
+                if name.starts_with(|c: char| c.is_ascii_digit()) {
+                    continue;
+                }
+
+                let (mut style, mut hidden) = stack
+                    .last()
+                    .map_or((SpanStyle::default(), false), |tag| (tag.style, tag.hidden));
+
+                match name.as_str() {
+                    "i" => style.italic = true,
+                    "b" => style.bold = true,
+                    "u" => style.underline = true,
+                    "rt" => hidden = true,
+                    "v" => {
+                        if voice.is_none() && !annotation.trim().is_empty() {
+                            voice = Some(annotation.trim().to_string());
+                        }
+                    }
+                    "font" if format == Format::Srt => {
+                        if let Some(color) = annotation.split_whitespace().find_map(|attribute| {
+                            attribute.strip_prefix("color=").and_then(parse_color)
+                        }) {
+                            style.color = Some(color);
+                        }
+                    }
+                    _ => (),
+                }
+
+                /* Color classes apply to any element */
+                if let Some(color) = classes.find_map(parse_color) {
+                    style.color = Some(color);
+                }
+
+                stack.push(OpenTag {
+                    name,
+                    style,
+                    hidden,
+                });
+            }
+            '&' => {
+                let entity = text[idx + 1..]
+                    .find(';')
+                    .filter(|end| *end <= 8)
+                    .and_then(|end| decode_entity(&text[idx + 1..idx + 1 + end]).map(|c| (end, c)));
+
+                match entity {
+                    Some((end, decoded)) => {
+                        /* Directional marks are not rendered */
+                        if decoded != '\u{200e}' && decoded != '\u{200f}' {
+                            current.push(decoded);
+                        }
+                        for _ in 0..=end {
+                            chars.next();
+                        }
+                    }
+                    None => current.push(c),
+                }
+            }
+            '{' if format == Format::Srt && text[idx..].starts_with("{\\") => {
+                match text[idx..].find('}') {
+                    Some(end) => {
+                        parse_override(&text[idx + 1..idx + end], settings);
+                        while chars.peek().map_or(false, |(next, _)| *next <= idx + end) {
+                            chars.next();
+                        }
+                    }
+                    None => current.push(c),
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+
+    flush(&mut lines, &stack, &mut current);
+
+    for line in &mut lines {
+        if let Some(span) = line.first_mut() {
+            span.text = span.text.trim_start().to_string();
+        }
+        if let Some(span) = line.last_mut() {
+            span.text = span.text.trim_end().to_string();
+        }
+        line.retain(|span| !span.text.is_empty());
+    }
+
+    (lines, voice)
+}
+
+impl Cue {
+    /// Converts to the JSON model, positioning the lines on the CEA-608
+    /// grid according to the cue settings
+    pub fn to_lines(&self) -> Lines {
+        let lines = self
+            .lines
+            .iter()
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let n_lines = (lines.len() as i32).min(GRID_ROWS);
+
+        let first_row = match self.settings.line {
+            None => GRID_ROWS - n_lines,
+            Some(LinePosition::Lines(line)) if line >= 0 => line,
+            Some(LinePosition::Lines(line)) => GRID_ROWS + line - (n_lines - 1),
+            Some(LinePosition::Percent(percent)) => {
+                ((percent - SAFE_AREA_ORIGIN) * GRID_ROWS as f64 / SAFE_AREA_EXTENT).round() as i32
+            }
+        }
+        .clamp(0, GRID_ROWS - n_lines);
+
+        let align = self.settings.align.unwrap_or(Align::Center);
+        let position = self.settings.position.unwrap_or(match align {
+            Align::Start | Align::Left => 0.0,
+            Align::Center => 50.0,
+            Align::End | Align::Right => 100.0,
+        });
+        let x = (position - SAFE_AREA_ORIGIN) * GRID_COLUMNS as f64 / SAFE_AREA_EXTENT;
+
+        let lines = lines
+            .into_iter()
+            .take(n_lines as usize)
+            .enumerate()
+            .map(|(idx, spans)| {
+                /* Style changes take one column */
+                let chunks = spans
+                    .iter()
+                    .map(|span| Chunk {
+                        style: match (span.style.color, span.style.italic) {
+                            (None | Some(TextStyle::White), true) => TextStyle::ItalicWhite,
+                            (Some(color), _) => color,
+                            (None, false) => TextStyle::White,
+                        },
+                        underline: span.style.underline,
+                        text: span.text.trim().to_string(),
+                    })
+                    .filter(|chunk| !chunk.text.is_empty())
+                    .collect::<Vec<_>>();
+
+                let len = chunks
+                    .iter()
+                    .map(|chunk| chunk.text.chars().count() as i32 + 1)
+                    .sum::<i32>()
+                    - 1;
+
+                let column = match align {
+                    Align::Start | Align::Left => x,
+                    Align::Center => x - len as f64 / 2.0,
+                    Align::End | Align::Right => x - len as f64,
+                }
+                .round() as i32;
+
+                Line {
+                    column: Some(column.clamp(0, (GRID_COLUMNS - len).max(0)) as u32),
+                    row: Some((first_row + idx as i32) as u32),
+                    chunks,
+                    carriage_return: None,
+                }
+            })
+            .collect();
+
+        Lines {
+            lines,
+            mode: None,
+            clear: None,
+        }
+    }
+}
+
+impl SubtitleParser {
+    pub fn new() -> Self {
+        Self {
+            format: None,
+            state: State::Start,
+            cue: None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn finish_cue(&mut self) -> Option<Cue> {
+        let cue = self.cue.take()?;
+        let mut settings = cue.settings;
+        let (lines, voice) = parse_cue_text(
+            &cue.text.join("\n"),
+            self.format.unwrap_or(Format::Srt),
+            &mut settings,
+        );
+
+        Some(Cue {
+            start: cue.start,
+            end: cue.end,
+            settings,
+            voice,
+            lines,
+        })
+    }
+
+    /// Parses a line, returns the cue it terminates if any
+    pub fn parse_line(&mut self, line: &[u8]) -> Result<Option<Cue>, std::str::Utf8Error> {
+        let line = std::str::from_utf8(trim_line(line))?;
+        let is_empty = line.trim().is_empty();
+
+        if self.state == State::Start {
+            if is_empty {
+                return Ok(None);
+            }
+
+            if line == "WEBVTT" || line.starts_with("WEBVTT ") || line.starts_with("WEBVTT\t") {
+                self.format = Some(Format::WebVtt);
+                self.state = State::Header;
+                return Ok(None);
+            }
+
+            self.format = Some(Format::Srt);
+            self.state = State::Blocks;
+        }
+
+        match self.state {
+            State::Header | State::Skip => {
+                if is_empty {
+                    self.state = State::Blocks;
+                }
+                Ok(None)
+            }
+            State::Blocks | State::Timing => {
+                if is_empty {
+                    return Ok(None);
+                }
+
+                if let Ok((settings, (start, end))) = timing(line.as_bytes()) {
+                    self.cue = Some(PendingCue {
+                        start,
+                        end,
+                        settings: parse_settings(std::str::from_utf8(settings).unwrap()),
+                        text: vec![],
+                    });
+                    self.state = State::Text;
+                } else if self.state == State::Blocks
+                    && self.format == Some(Format::WebVtt)
+                    && ["NOTE", "STYLE", "REGION"].iter().any(|block| {
+                        line.strip_prefix(block)
+                            .map_or(false, |rest| rest.is_empty() || rest.starts_with(' '))
+                    })
+                {
+                    self.state = State::Skip;
+                } else {
+                    /* Cue identifier, or counter in SRT files */
+                    self.state = State::Timing;
+                }
+
+                Ok(None)
+            }
+            State::Text => {
+                if is_empty {
+                    self.state = State::Blocks;
+                    return Ok(self.finish_cue());
+                }
+
+                if let Some(ref mut cue) = self.cue {
+                    cue.text.push(line.to_string());
+                }
+
+                Ok(None)
+            }
+            State::Start => unreachable!(),
+        }
+    }
+
+    /// Returns the last cue of a file without a trailing empty line
+    pub fn drain(&mut self) -> Option<Cue> {
+        self.state = State::Blocks;
+        self.finish_cue()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(data: &str) -> Vec<Cue> {
+        let mut parser = SubtitleParser::new();
+        let mut cues = data
+            .split_inclusive('\n')
+            .filter_map(|line| parser.parse_line(line.as_bytes()).unwrap())
+            .collect::<Vec<_>>();
+        cues.extend(parser.drain());
+        cues
+    }
+
+    fn text(cue: &Cue) -> Vec<String> {
+        cue.lines
+            .iter()
+            .map(|spans| spans.iter().map(|span| span.text.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(
+            timestamp(b"01:02:03.456".as_ref()),
+            Ok((b"".as_ref(), gst::ClockTime::from_mseconds(3_723_456)))
+        );
+        assert_eq!(
+            timestamp(b"02:03,4 -->".as_ref()),
+            Ok((b" -->".as_ref(), gst::ClockTime::from_mseconds(123_400)))
+        );
+        assert!(timestamp(b"01:60.000".as_ref()).is_err());
+
+        assert_eq!(
+            parse_timing_line(b"00:00:01,000 --> 00:00:02,500\r\n"),
+            Some((
+                gst::ClockTime::from_mseconds(1000),
+                gst::ClockTime::from_mseconds(2500)
+            ))
+        );
+        assert_eq!(parse_timing_line(b"1\n"), None);
+    }
+
+    #[test]
+    fn test_webvtt() {
+        let cues = parse(
+            "WEBVTT - Test\n\
+             \n\
+             NOTE a comment\n\
+             with two lines\n\
+             \n\
+             intro\n\
+             00:01.000 --> 00:03.000 line:0 position:10% align:start\n\
+             <v Alice>Hello <i>big</i></v>\n\
+             <c.yellow.bg_blue>world</c> &amp; co&lrm;\n\
+             \n\
+             00:00:04.000 --> 00:00:05.000\n\
+             <b>Bye</b> <u>now</u><00:00:04.500> <ruby>Ka<rt>ka</rt></ruby>",
+        );
+
+        assert_eq!(cues.len(), 2);
+
+        assert_eq!(cues[0].start, gst::ClockTime::from_seconds(1));
+        assert_eq!(cues[0].end, gst::ClockTime::from_seconds(3));
+        assert_eq!(cues[0].voice.as_deref(), Some("Alice"));
+        assert_eq!(text(&cues[0]), vec!["Hello big", "world & co"]);
+        assert!(cues[0].lines[0][1].style.italic);
+        assert_eq!(cues[0].lines[1][0].style.color, Some(TextStyle::Yellow));
+        assert_eq!(cues[0].lines[1][1].style, SpanStyle::default());
+
+        let lines = cues[0].to_lines();
+        assert_eq!(lines.lines[0].row, Some(0));
+        assert_eq!(lines.lines[1].row, Some(1));
+        assert_eq!(lines.lines[0].column, Some(0));
+        assert_eq!(lines.lines[0].chunks[1].style, TextStyle::ItalicWhite);
+        assert_eq!(lines.lines[1].chunks[0].style, TextStyle::Yellow);
+
+        assert_eq!(cues[1].start, gst::ClockTime::from_seconds(4));
+        assert_eq!(text(&cues[1]), vec!["Bye now Ka"]);
+        assert!(cues[1].lines[0][0].style.bold);
+        assert!(cues[1].lines[0][2].style.underline);
+    }
+
+    #[test]
+    fn test_srt() {
+        let cues = parse(
+            "\u{feff}1\r\n\
+             00:00:01,000 --> 00:00:02,000\r\n\
+             {\\an8}<font color=\"#ff0000\">Red</font> text\r\n\
+             \r\n\
+             2\r\n\
+             00:00:03,000 --> 00:00:04,000\r\n\
+             First line\r\n\
+             Second line\r\n",
+        );
+
+        assert_eq!(cues.len(), 2);
+
+        assert_eq!(text(&cues[0]), vec!["Red text"]);
+        assert_eq!(cues[0].lines[0][0].style.color, Some(TextStyle::Red));
+        assert_eq!(cues[0].settings.line, Some(LinePosition::Lines(0)));
+
+        let lines = cues[0].to_lines();
+        assert_eq!(lines.lines[0].row, Some(0));
+        /* "Red text" is 8 columns wide, centered */
+        assert_eq!(lines.lines[0].column, Some(12));
+
+        assert_eq!(cues[1].start, gst::ClockTime::from_seconds(3));
+        assert_eq!(text(&cues[1]), vec!["First line", "Second line"]);
+
+        /* Bottom rows by default */
+        let lines = cues[1].to_lines();
+        assert_eq!(lines.lines[0].row, Some(13));
+        assert_eq!(lines.lines[1].row, Some(14));
+    }
+
+    #[test]
+    fn test_settings() {
+        let settings = parse_settings("line:-1 position:90% align:end size:50%");
+        assert_eq!(settings.line, Some(LinePosition::Lines(-1)));
+        assert_eq!(settings.position, Some(90.0));
+        assert_eq!(settings.align, Some(Align::End));
+
+        let settings = parse_settings("line:50%,center align:bogus");
+        assert_eq!(settings.line, Some(LinePosition::Percent(50.0)));
+        assert_eq!(settings.align, None);
+    }
+}
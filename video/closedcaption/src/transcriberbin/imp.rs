@@ -87,7 +87,6 @@ impl TranscriberBin {
         gst::debug!(CAT, obj: element, "Building transcription bin");
 
         let aqueue_transcription = gst::ElementFactory::make("queue", Some("transqueue"))?;
-        let ccconverter = gst::ElementFactory::make("ccconverter", None)?;
 
         state.transcription_bin.add_many(&[
             &aqueue_transcription,
@@ -96,7 +95,6 @@ impl TranscriberBin {
             &state.transcriber_queue,
             &state.textwrap,
             &state.tttocea608,
-            &ccconverter,
             &state.cccapsfilter,
         ])?;
 
@@ -107,7 +105,6 @@ impl TranscriberBin {
             &state.transcriber_queue,
             &state.textwrap,
             &state.tttocea608,
-            &ccconverter,
             &state.cccapsfilter,
         ])?;
 
@@ -212,13 +209,15 @@ impl TranscriberBin {
 
     fn setup_transcription(&self, element: &super::TranscriberBin, state: &State) {
         let settings = self.settings.lock().unwrap();
-        let mut cc_caps = settings.cc_caps.clone();
 
-        let cc_caps_mut = cc_caps.make_mut();
-        let s = cc_caps_mut.structure_mut(0).unwrap();
-
-        s.set("framerate", &state.framerate.unwrap());
+        // The combiner converts the captions to the expected format, but
+        // tttocea608 has to produce them at the video framerate
+        let caption_type = gst_video::VideoCaptionType::from_caps(&settings.cc_caps);
+        state.cccombiner.set_property("caption-type", caption_type);
 
+        let cc_caps = gst::Caps::builder("closedcaption/x-cea-608")
+            .field("framerate", state.framerate.unwrap())
+            .build();
         state.cccapsfilter.set_property("caps", &cc_caps);
 
         let max_size_time = settings.latency + settings.accumulate_time;
@@ -425,7 +424,7 @@ impl TranscriberBin {
         let internal_bin = gst::Bin::new(Some("internal"));
         let transcription_bin = gst::Bin::new(Some("transcription-bin"));
         let audio_tee = gst::ElementFactory::make("tee", None)?;
-        let cccombiner = gst::ElementFactory::make("cccombiner", Some("cccombiner"))?;
+        let cccombiner = gst::ElementFactory::make("rscccombiner", Some("cccombiner"))?;
         let textwrap = gst::ElementFactory::make("textwrap", Some("textwrap"))?;
         let tttocea608 = gst::ElementFactory::make("tttocea608", Some("tttocea608"))?;
         let transcriber_aconv = gst::ElementFactory::make("audioconvert", None)?;
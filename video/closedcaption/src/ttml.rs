@@ -0,0 +1,990 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! TTML documents, following the IMSC1 Text profile.
+//!
+//! Captions are converted from and to the JSON model of ttutils: the
+//! 15 rows of the CEA-608 grid are mapped to regions in the safe title
+//! area (the center 80% of the root container), and the CEA-608 styles
+//! to `tts:color` and `tts:fontStyle`.
+
+use crate::cea708utils::Color;
+use crate::ttutils::{Chunk, Line, Lines, TextStyle};
+
+use anyhow::{Context as _, Error};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+const GRID_ROWS: u32 = 15;
+const GRID_COLUMNS: u32 = 32;
+
+/* Position and size of the safe title area, in percents */
+const SAFE_AREA_ORIGIN: f64 = 10.0;
+const SAFE_AREA_EXTENT: f64 = 80.0;
+
+/// A caption, with the timing of its `p` element
+#[derive(Debug, Clone)]
+pub struct Cue {
+    pub begin: gst::ClockTime,
+    /// `None` when active until the end of the document
+    pub end: Option<gst::ClockTime>,
+    pub lines: Lines,
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn format_time(time: gst::ClockTime) -> String {
+    let ms = time.mseconds();
+
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        ms / 3_600_000,
+        (ms / 60_000) % 60,
+        (ms / 1000) % 60,
+        ms % 1000
+    )
+}
+
+fn style_attributes(style: TextStyle, underline: bool) -> String {
+    let color = match style {
+        TextStyle::White | TextStyle::ItalicWhite => None,
+        TextStyle::Green => Some("lime"),
+        TextStyle::Blue => Some("blue"),
+        TextStyle::Cyan => Some("cyan"),
+        TextStyle::Red => Some("red"),
+        TextStyle::Yellow => Some("yellow"),
+        TextStyle::Magenta => Some("magenta"),
+    };
+
+    let mut attributes = String::new();
+
+    if let Some(color) = color {
+        write!(attributes, " tts:color=\"{}\"", color).unwrap();
+    }
+
+    if style == TextStyle::ItalicWhite {
+        attributes.push_str(" tts:fontStyle=\"italic\"");
+    }
+
+    if underline {
+        attributes.push_str(" tts:textDecoration=\"underline\"");
+    }
+
+    attributes
+}
+
+/* Lines without a row are stacked at the bottom of the grid */
+fn line_rows(lines: &[Line]) -> Vec<u32> {
+    let n_lines = lines.len() as u32;
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| match line.row {
+            Some(row) => row.min(GRID_ROWS - 1),
+            None => (GRID_ROWS + idx as u32).saturating_sub(n_lines),
+        })
+        .collect()
+}
+
+/// Serializes `cues` as an IMSC1 Text profile document, with one region
+/// per row of the CEA-608 grid in use
+pub fn write_document(cues: &[Cue]) -> String {
+    let mut rows = cues
+        .iter()
+        .flat_map(|cue| line_rows(&cue.lines.lines))
+        .collect::<Vec<_>>();
+    rows.sort_unstable();
+    rows.dedup();
+
+    let mut data = String::new();
+
+    data.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    data.push_str(
+        "<tt xmlns=\"http://www.w3.org/ns/ttml\" \
+         xmlns:ttp=\"http://www.w3.org/ns/ttml#parameter\" \
+         xmlns:tts=\"http://www.w3.org/ns/ttml#styling\" \
+         ttp:profile=\"http://www.w3.org/ns/ttml/profile/imsc1/text\" \
+         ttp:cellResolution=\"32 15\" xml:lang=\"en\">\n",
+    );
+    data.push_str("  <head>\n    <styling>\n");
+    data.push_str(
+        "      <style xml:id=\"base\" tts:fontFamily=\"monospaceSansSerif\" \
+         tts:fontSize=\"1c\" tts:color=\"white\" tts:backgroundColor=\"black\"/>\n",
+    );
+    data.push_str("    </styling>\n    <layout>\n");
+
+    let row_height = SAFE_AREA_EXTENT / GRID_ROWS as f64;
+    for row in rows {
+        writeln!(
+            data,
+            "      <region xml:id=\"row{}\" tts:origin=\"{:.2}% {:.2}%\" tts:extent=\"{:.2}% {:.2}%\"/>",
+            row,
+            SAFE_AREA_ORIGIN,
+            SAFE_AREA_ORIGIN + row as f64 * row_height,
+            SAFE_AREA_EXTENT,
+            row_height
+        )
+        .unwrap();
+    }
+
+    data.push_str("    </layout>\n  </head>\n  <body style=\"base\">\n    <div>\n");
+
+    for cue in cues {
+        let end = cue
+            .end
+            .map(|end| format!(" end=\"{}\"", format_time(end)))
+            .unwrap_or_default();
+
+        for (line, row) in cue.lines.lines.iter().zip(line_rows(&cue.lines.lines)) {
+            write!(
+                data,
+                "      <p begin=\"{}\"{} region=\"row{}\" xml:space=\"preserve\">",
+                format_time(cue.begin),
+                end,
+                row
+            )
+            .unwrap();
+
+            if let Some(column) = line.column {
+                data.push_str(&" ".repeat(column.min(GRID_COLUMNS - 1) as usize));
+            }
+
+            for chunk in &line.chunks {
+                write!(
+                    data,
+                    "<span{}>{}</span>",
+                    style_attributes(chunk.style, chunk.underline),
+                    escape(&chunk.text)
+                )
+                .unwrap();
+            }
+
+            data.push_str("</p>\n");
+        }
+    }
+
+    data.push_str("    </div>\n  </body>\n</tt>\n");
+
+    data
+}
+
+/// Returns the size of the first complete document in `data`, that is
+/// up to the end tag of the root `tt` element
+pub fn document_size(data: &[u8]) -> Option<usize> {
+    let mut offset = 0;
+
+    while let Some(pos) = data[offset..].windows(2).position(|w| w == b"</") {
+        let start = offset + pos + 2;
+        let end = start + data[start..].iter().position(|b| *b == b'>')?;
+
+        let name = &data[start..end];
+        let name_len = name
+            .iter()
+            .position(|b| b.is_ascii_whitespace())
+            .unwrap_or(name.len());
+
+        if local_name(&name[..name_len]) == b"tt" {
+            return Some(end + 1);
+        }
+
+        offset = end;
+    }
+
+    None
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Timing {
+    frame_rate: f64,
+    tick_rate: f64,
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        Timing {
+            frame_rate: 30.0,
+            tick_rate: 1.0,
+        }
+    }
+}
+
+fn seconds_to_time(seconds: f64) -> Option<gst::ClockTime> {
+    if seconds.is_finite() && seconds >= 0.0 {
+        Some(gst::ClockTime::from_nseconds(
+            (seconds * 1_000_000_000.0).round() as u64,
+        ))
+    } else {
+        None
+    }
+}
+
+/* Clock times (hh:mm:ss.fraction or hh:mm:ss:frames) and offset times
+ * (<number><metric>), TTML2 section 10.3.1 */
+fn parse_time(s: &str, timing: &Timing) -> Option<gst::ClockTime> {
+    let s = s.trim();
+
+    if s.contains(':') {
+        let mut parts = s.split(':');
+        let hours = parts.next()?.parse::<u64>().ok()?;
+        let minutes = parts.next()?.parse::<u64>().ok()?;
+        let seconds = parts.next()?.parse::<f64>().ok()?;
+        let frames = match parts.next() {
+            Some(frames) => frames.parse::<f64>().ok()?,
+            None => 0.0,
+        };
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        return seconds_to_time(
+            (hours * 3600 + minutes * 60) as f64 + seconds + frames / timing.frame_rate,
+        );
+    }
+
+    let metric_pos = s.find(|c: char| c.is_ascii_alphabetic())?;
+    let (value, metric) = s.split_at(metric_pos);
+    let value = value.parse::<f64>().ok()?;
+
+    let seconds = match metric {
+        "h" => value * 3600.0,
+        "m" => value * 60.0,
+        "s" => value,
+        "ms" => value / 1000.0,
+        "f" => value / timing.frame_rate,
+        "t" => value / timing.tick_rate,
+        _ => return None,
+    };
+
+    seconds_to_time(seconds)
+}
+
+/* Named colors, #rrggbb[aa] and rgb[a](r, g, b[, a]) */
+fn parse_color(s: &str) -> Option<(u8, u8, u8)> {
+    let s = s.trim();
+
+    if let Some(hex) = s.strip_prefix('#') {
+        if hex.len() != 6 && hex.len() != 8 {
+            return None;
+        }
+
+        let component = |idx: usize| u8::from_str_radix(hex.get(idx..idx + 2)?, 16).ok();
+
+        return Some((component(0)?, component(2)?, component(4)?));
+    }
+
+    if let Some(args) = s
+        .strip_prefix("rgba(")
+        .or_else(|| s.strip_prefix("rgb("))
+        .and_then(|args| args.strip_suffix(')'))
+    {
+        let mut components = args.split(',').map(|c| c.trim().parse::<u8>().ok());
+
+        return Some((
+            components.next()??,
+            components.next()??,
+            components.next()??,
+        ));
+    }
+
+    match s.to_ascii_lowercase().as_str() {
+        "white" => Some((255, 255, 255)),
+        "silver" => Some((192, 192, 192)),
+        "gray" => Some((128, 128, 128)),
+        "black" => Some((0, 0, 0)),
+        "red" => Some((255, 0, 0)),
+        "maroon" => Some((128, 0, 0)),
+        "yellow" => Some((255, 255, 0)),
+        "olive" => Some((128, 128, 0)),
+        "lime" => Some((0, 255, 0)),
+        "green" => Some((0, 128, 0)),
+        "cyan" | "aqua" => Some((0, 255, 255)),
+        "teal" => Some((0, 128, 128)),
+        "blue" => Some((0, 0, 255)),
+        "navy" => Some((0, 0, 128)),
+        "magenta" | "fuchsia" => Some((255, 0, 255)),
+        "purple" => Some((128, 0, 128)),
+        _ => None,
+    }
+}
+
+/* Percentages of the root container, TTML2 section 10.3.15 */
+fn parse_percentages(s: &str) -> Option<(f64, f64)> {
+    let mut values = s
+        .split_whitespace()
+        .map(|value| value.strip_suffix('%')?.parse::<f64>().ok());
+
+    Some((values.next()??, values.next()??))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct SpanStyle {
+    color: Option<(u8, u8, u8)>,
+    italic: Option<bool>,
+    underline: Option<bool>,
+}
+
+impl SpanStyle {
+    fn merge(&mut self, other: &SpanStyle) {
+        self.color = other.color.or(self.color);
+        self.italic = other.italic.or(self.italic);
+        self.underline = other.underline.or(self.underline);
+    }
+
+    /* The closest CEA-608 style */
+    fn text_style(&self) -> TextStyle {
+        let (r, g, b) = self.color.unwrap_or((255, 255, 255));
+
+        Color {
+            r: r >> 6,
+            g: g >> 6,
+            b: b >> 6,
+            opacity: 0,
+        }
+        .to_text_style(self.italic.unwrap_or(false))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Region {
+    /// In percents of the root container
+    origin: (f64, f64),
+    extent: Option<(f64, f64)>,
+    display_align_after: bool,
+}
+
+impl Region {
+    /* The rows of the CEA-608 grid covered by the region */
+    fn rows(&self) -> (u32, u32) {
+        let to_row = |y: f64| (y - SAFE_AREA_ORIGIN) * GRID_ROWS as f64 / SAFE_AREA_EXTENT;
+        let max_row = (GRID_ROWS - 1) as f64;
+
+        let first = to_row(self.origin.1).round().clamp(0.0, max_row);
+        let last = match self.extent {
+            Some((_, height)) => {
+                (to_row(self.origin.1 + height).round() - 1.0).clamp(first, max_row)
+            }
+            None => first,
+        };
+
+        (first as u32, last as u32)
+    }
+
+    fn column(&self) -> u32 {
+        ((self.origin.0 - SAFE_AREA_ORIGIN) * GRID_COLUMNS as f64 / SAFE_AREA_EXTENT)
+            .round()
+            .clamp(0.0, (GRID_COLUMNS - 1) as f64) as u32
+    }
+}
+
+/* The inherited state of an element being parsed */
+#[derive(Debug, Clone)]
+struct Context {
+    begin: gst::ClockTime,
+    end: Option<gst::ClockTime>,
+    style: SpanStyle,
+    region: Option<String>,
+    preserve_space: bool,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Context {
+            begin: gst::ClockTime::ZERO,
+            end: None,
+            style: SpanStyle::default(),
+            region: None,
+            preserve_space: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Paragraph {
+    begin: gst::ClockTime,
+    end: Option<gst::ClockTime>,
+    region: Option<String>,
+    preserve_space: bool,
+    lines: Vec<Vec<Chunk>>,
+}
+
+#[derive(Debug, Default)]
+struct Parser {
+    timing: Timing,
+    styles: HashMap<String, SpanStyle>,
+    regions: HashMap<String, Region>,
+    stack: Vec<Context>,
+    in_head: bool,
+    paragraph: Option<Paragraph>,
+    cues: Vec<Cue>,
+}
+
+fn local_name(name: &[u8]) -> &[u8] {
+    name.rsplit(|b| *b == b':').next().unwrap_or(name)
+}
+
+/* Removes the spaces around a line, returns the number of leading spaces */
+fn trim_line(chunks: &mut Vec<Chunk>) -> u32 {
+    let mut leading = 0;
+
+    while let Some(chunk) = chunks.first_mut() {
+        let trimmed = chunk.text.trim_start_matches(' ');
+        leading += (chunk.text.len() - trimmed.len()) as u32;
+        chunk.text = trimmed.to_string();
+
+        if !chunk.text.is_empty() {
+            break;
+        }
+
+        chunks.remove(0);
+    }
+
+    while let Some(chunk) = chunks.last_mut() {
+        chunk.text = chunk.text.trim_end_matches(' ').to_string();
+
+        if !chunk.text.is_empty() {
+            break;
+        }
+
+        chunks.pop();
+    }
+
+    leading
+}
+
+impl Parser {
+    fn attributes(
+        reader: &Reader<&[u8]>,
+        e: &BytesStart,
+    ) -> Result<HashMap<Vec<u8>, String>, Error> {
+        let mut attributes = HashMap::new();
+
+        for attr in e.attributes() {
+            let attr = attr?;
+            let value = attr.unescape_and_decode_value(reader)?;
+            // xml:space is the only attribute we need from the xml
+            // namespace that could clash with a local name
+            let key = match attr.key {
+                b"xml:space" => b"xml:space".to_vec(),
+                key => local_name(key).to_vec(),
+            };
+
+            attributes.insert(key, value);
+        }
+
+        Ok(attributes)
+    }
+
+    /* Referenced and inline styles */
+    fn span_style(&self, attributes: &HashMap<Vec<u8>, String>) -> SpanStyle {
+        let mut style = SpanStyle::default();
+
+        if let Some(ids) = attributes.get(&b"style"[..]) {
+            for id in ids.split_whitespace() {
+                if let Some(referenced) = self.styles.get(id) {
+                    style.merge(referenced);
+                }
+            }
+        }
+
+        style.merge(&SpanStyle {
+            color: attributes.get(&b"color"[..]).and_then(|c| parse_color(c)),
+            italic: attributes
+                .get(&b"fontStyle"[..])
+                .map(|s| s == "italic" || s == "oblique"),
+            underline: attributes
+                .get(&b"textDecoration"[..])
+                .map(|s| s.split_whitespace().any(|d| d == "underline")),
+        });
+
+        style
+    }
+
+    fn parse_timing(&mut self, attributes: &HashMap<Vec<u8>, String>) {
+        let rate = |name: &[u8]| {
+            attributes
+                .get(name)
+                .and_then(|r| r.trim().parse::<f64>().ok())
+                .filter(|r| *r > 0.0)
+        };
+
+        if let Some(frame_rate) = rate(b"frameRate") {
+            let multiplier = attributes
+                .get(&b"frameRateMultiplier"[..])
+                .and_then(|m| {
+                    let mut parts = m.split_whitespace().map(|v| v.parse::<f64>().ok());
+                    Some(parts.next()?? / parts.next()??)
+                })
+                .filter(|m| m.is_finite() && *m > 0.0)
+                .unwrap_or(1.0);
+
+            self.timing.frame_rate = frame_rate * multiplier;
+        }
+
+        if let Some(tick_rate) = rate(b"tickRate") {
+            self.timing.tick_rate = tick_rate;
+        }
+    }
+
+    fn start(&mut self, reader: &Reader<&[u8]>, e: &BytesStart) -> Result<(), Error> {
+        let attributes = Self::attributes(reader, e)?;
+        let name = local_name(e.name()).to_vec();
+        let parent = self.stack.last().cloned().unwrap_or_default();
+
+        match name.as_slice() {
+            b"tt" => self.parse_timing(&attributes),
+            b"head" => self.in_head = true,
+            b"style" if self.in_head => {
+                if let Some(id) = attributes.get(&b"id"[..]) {
+                    let style = self.span_style(&attributes);
+                    self.styles.insert(id.clone(), style);
+                }
+            }
+            b"region" if self.in_head => {
+                if let (Some(id), Some(origin)) = (
+                    attributes.get(&b"id"[..]),
+                    attributes
+                        .get(&b"origin"[..])
+                        .and_then(|o| parse_percentages(o)),
+                ) {
+                    self.regions.insert(
+                        id.clone(),
+                        Region {
+                            origin,
+                            extent: attributes
+                                .get(&b"extent"[..])
+                                .and_then(|e| parse_percentages(e)),
+                            display_align_after: attributes
+                                .get(&b"displayAlign"[..])
+                                .map_or(false, |a| a == "after"),
+                        },
+                    );
+                }
+            }
+            b"br" => {
+                if let Some(ref mut paragraph) = self.paragraph {
+                    paragraph.lines.push(vec![]);
+                }
+            }
+            _ => (),
+        }
+
+        if self.in_head {
+            self.stack.push(parent);
+            return Ok(());
+        }
+
+        let time = |name: &[u8]| {
+            attributes
+                .get(name)
+                .and_then(|t| parse_time(t, &self.timing))
+        };
+
+        /* Parallel time containment: times are relative to the begin
+         * of the parent, and clipped to its end */
+        let begin = time(b"begin").map_or(parent.begin, |begin| parent.begin + begin);
+        let end = match (time(b"end"), time(b"dur")) {
+            (Some(end), _) => Some(parent.begin + end),
+            (None, Some(dur)) => Some(begin + dur),
+            (None, None) => parent.end,
+        };
+        let end = match (end, parent.end) {
+            (Some(end), Some(parent_end)) => Some(end.min(parent_end)),
+            (end, _) => end,
+        };
+
+        let mut style = parent.style;
+        style.merge(&self.span_style(&attributes));
+
+        let context = Context {
+            begin,
+            end,
+            style,
+            region: attributes.get(&b"region"[..]).cloned().or(parent.region),
+            preserve_space: attributes
+                .get(&b"xml:space"[..])
+                .map_or(parent.preserve_space, |s| s == "preserve"),
+        };
+
+        if name == b"p" {
+            self.paragraph = Some(Paragraph {
+                begin: context.begin,
+                end: context.end,
+                region: context.region.clone(),
+                preserve_space: context.preserve_space,
+                lines: vec![vec![]],
+            });
+        }
+
+        self.stack.push(context);
+
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) {
+        let context = match self.stack.last() {
+            Some(context) => context,
+            None => return,
+        };
+
+        let paragraph = match self.paragraph {
+            Some(ref mut paragraph) => paragraph,
+            None => return,
+        };
+
+        let chunks = paragraph.lines.last_mut().unwrap();
+
+        let text = if context.preserve_space {
+            text.replace(|c| c == '\n' || c == '\r' || c == '\t', " ")
+        } else {
+            /* Collapse white space, without duplicating the separators
+             * between chunks */
+            let mut collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+            let after_space = chunks
+                .last()
+                .map_or(true, |chunk| chunk.text.ends_with(' '));
+            if text.starts_with(char::is_whitespace) && !after_space {
+                collapsed.insert(0, ' ');
+            }
+            if text.ends_with(char::is_whitespace)
+                && !collapsed.is_empty()
+                && !collapsed.ends_with(' ')
+            {
+                collapsed.push(' ');
+            }
+
+            collapsed
+        };
+
+        if text.is_empty() {
+            return;
+        }
+
+        let style = context.style.text_style();
+        let underline = context.style.underline.unwrap_or(false);
+
+        match chunks.last_mut() {
+            Some(chunk) if chunk.style == style && chunk.underline == underline => {
+                chunk.text.push_str(&text)
+            }
+            _ => chunks.push(Chunk {
+                style,
+                underline,
+                text,
+            }),
+        }
+    }
+
+    fn end(&mut self, name: &[u8]) {
+        self.stack.pop();
+
+        match local_name(name) {
+            b"head" => self.in_head = false,
+            b"p" => {
+                if let Some(paragraph) = self.paragraph.take() {
+                    self.push_paragraph(paragraph);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn push_paragraph(&mut self, paragraph: Paragraph) {
+        let region = paragraph
+            .region
+            .as_ref()
+            .and_then(|id| self.regions.get(id));
+
+        let mut lines = paragraph
+            .lines
+            .into_iter()
+            .filter_map(|mut chunks| {
+                let leading = trim_line(&mut chunks);
+
+                if chunks.is_empty() {
+                    return None;
+                }
+
+                /* Preserved leading spaces indent the line */
+                let indent = if paragraph.preserve_space {
+                    Some(leading)
+                } else {
+                    None
+                };
+
+                let column = match (region.map(|region| region.column()), indent) {
+                    (None, None) => None,
+                    (column, indent) => {
+                        Some((column.unwrap_or(0) + indent.unwrap_or(0)).min(GRID_COLUMNS - 1))
+                    }
+                };
+
+                Some(Line {
+                    column,
+                    row: None,
+                    chunks,
+                    carriage_return: None,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if lines.is_empty() {
+            return;
+        }
+
+        let n_lines = lines.len() as u32;
+        let first_row = match region {
+            Some(region) => {
+                let (first_row, last_row) = region.rows();
+
+                if region.display_align_after {
+                    (last_row + 1).saturating_sub(n_lines).max(first_row)
+                } else {
+                    first_row
+                }
+            }
+            None => GRID_ROWS.saturating_sub(n_lines),
+        };
+
+        for (idx, line) in lines.iter_mut().enumerate() {
+            line.row = Some((first_row + idx as u32).min(GRID_ROWS - 1));
+        }
+
+        self.cues.push(Cue {
+            begin: paragraph.begin,
+            end: paragraph.end,
+            lines: Lines {
+                lines,
+                mode: None,
+                clear: None,
+            },
+        });
+    }
+}
+
+/// Parses a TTML document into cues, sorted by begin time. Paragraphs
+/// active over the same interval are merged into the same cue
+pub fn parse_document(data: &[u8]) -> Result<Vec<Cue>, Error> {
+    let data = std::str::from_utf8(data).context("Document is not valid UTF-8")?;
+
+    let mut reader = Reader::from_str(data);
+    reader.trim_text(false);
+
+    let mut parser = Parser::default();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader
+            .read_event(&mut buf)
+            .with_context(|| format!("Invalid document at {}", reader.buffer_position()))?
+        {
+            Event::Start(e) => parser.start(&reader, &e)?,
+            Event::Empty(e) => {
+                parser.start(&reader, &e)?;
+                parser.end(e.name());
+            }
+            Event::End(e) => parser.end(e.name()),
+            Event::Text(e) => {
+                let text = e.unescape_and_decode(&reader)?;
+                parser.text(&text);
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+
+        buf.clear();
+    }
+
+    let mut cues: Vec<Cue> = vec![];
+
+    parser.cues.sort_by_key(|cue| cue.begin);
+    for cue in parser.cues {
+        match cues.last_mut() {
+            Some(last) if last.begin == cue.begin && last.end == cue.end => {
+                last.lines.lines.extend(cue.lines.lines);
+                last.lines.lines.sort_by_key(|line| line.row);
+            }
+            _ => cues.push(cue),
+        }
+    }
+
+    Ok(cues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(cue: &Cue) -> Vec<String> {
+        cue.lines
+            .lines
+            .iter()
+            .map(|line| {
+                line.chunks
+                    .iter()
+                    .map(|chunk| chunk.text.as_str())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let timing = Timing::default();
+
+        assert_eq!(
+            parse_time("00:01:02.500", &timing),
+            Some(gst::ClockTime::from_mseconds(62_500))
+        );
+        assert_eq!(
+            parse_time("00:00:01:15", &timing),
+            Some(gst::ClockTime::from_mseconds(1500))
+        );
+        assert_eq!(
+            parse_time("1.5s", &timing),
+            Some(gst::ClockTime::from_mseconds(1500))
+        );
+        assert_eq!(
+            parse_time("250ms", &timing),
+            Some(gst::ClockTime::from_mseconds(250))
+        );
+        assert_eq!(
+            parse_time("90f", &timing),
+            Some(gst::ClockTime::from_seconds(3))
+        );
+        assert_eq!(parse_time("1.5x", &timing), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let document = br##"<?xml version="1.0" encoding="UTF-8"?>
+<tt xmlns="http://www.w3.org/ns/ttml" xmlns:tts="http://www.w3.org/ns/ttml#styling"
+    xmlns:ttp="http://www.w3.org/ns/ttml#parameter" ttp:tickRate="10000000">
+  <head>
+    <styling>
+      <style xml:id="s1" tts:color="#ffff00"/>
+    </styling>
+    <layout>
+      <region xml:id="bottom" tts:origin="10% 70%" tts:extent="80% 20%" tts:displayAlign="after"/>
+    </layout>
+  </head>
+  <body>
+    <div begin="10s">
+      <p begin="10000000t" end="30000000t" region="bottom">
+        Hello <span style="s1">yellow</span><br/>
+        <span tts:fontStyle="italic">World</span>
+      </p>
+      <p begin="4s" dur="1s">Bye &amp; <span tts:textDecoration="underline">thanks</span></p>
+    </div>
+  </body>
+</tt>
+"##;
+
+        let cues = parse_document(document).unwrap();
+        assert_eq!(cues.len(), 2);
+
+        assert_eq!(cues[0].begin, gst::ClockTime::from_seconds(11));
+        assert_eq!(cues[0].end, Some(gst::ClockTime::from_seconds(13)));
+        assert_eq!(text(&cues[0]), vec!["Hello yellow", "World"]);
+
+        let lines = &cues[0].lines.lines;
+        assert_eq!(lines[0].chunks[0].style, TextStyle::White);
+        assert_eq!(lines[0].chunks[1].style, TextStyle::Yellow);
+        assert_eq!(lines[1].chunks[0].style, TextStyle::ItalicWhite);
+        /* Displayed at the bottom of the region, rows 11 to 14 */
+        assert_eq!(lines[0].row, Some(13));
+        assert_eq!(lines[1].row, Some(14));
+        assert_eq!(lines[0].column, Some(0));
+
+        assert_eq!(cues[1].begin, gst::ClockTime::from_seconds(14));
+        assert_eq!(cues[1].end, Some(gst::ClockTime::from_seconds(15)));
+        assert_eq!(text(&cues[1]), vec!["Bye & thanks"]);
+        assert!(cues[1].lines.lines[0].chunks[1].underline);
+        /* No region, bottom row */
+        assert_eq!(cues[1].lines.lines[0].row, Some(14));
+        assert_eq!(cues[1].lines.lines[0].column, None);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let cues = vec![Cue {
+            begin: gst::ClockTime::from_mseconds(1500),
+            end: Some(gst::ClockTime::from_mseconds(3250)),
+            lines: Lines {
+                lines: vec![
+                    Line {
+                        column: Some(4),
+                        row: Some(13),
+                        chunks: vec![Chunk {
+                            style: TextStyle::Green,
+                            underline: false,
+                            text: "<Hello>".to_string(),
+                        }],
+                        carriage_return: None,
+                    },
+                    Line {
+                        column: Some(0),
+                        row: Some(14),
+                        chunks: vec![
+                            Chunk {
+                                style: TextStyle::White,
+                                underline: false,
+                                text: "big ".to_string(),
+                            },
+                            Chunk {
+                                style: TextStyle::ItalicWhite,
+                                underline: true,
+                                text: "World".to_string(),
+                            },
+                        ],
+                        carriage_return: None,
+                    },
+                ],
+                mode: None,
+                clear: None,
+            },
+        }];
+
+        let document = write_document(&cues);
+        assert_eq!(document_size(document.as_bytes()), Some(document.len() - 1));
+
+        let parsed = parse_document(document.as_bytes()).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].begin, cues[0].begin);
+        assert_eq!(parsed[0].end, cues[0].end);
+
+        let lines = &parsed[0].lines.lines;
+        assert_eq!(text(&parsed[0]), vec!["<Hello>", "big World"]);
+        assert_eq!(lines[0].row, Some(13));
+        assert_eq!(lines[1].row, Some(14));
+        assert_eq!(lines[0].column, Some(4));
+        assert_eq!(lines[1].column, Some(0));
+        assert_eq!(lines[0].chunks[0].style, TextStyle::Green);
+        assert_eq!(lines[1].chunks[1].style, TextStyle::ItalicWhite);
+        assert!(lines[1].chunks[1].underline);
+    }
+}
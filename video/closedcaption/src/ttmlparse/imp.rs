@@ -0,0 +1,499 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+
+use crate::ttml::{self, Cue};
+use crate::ttutils::{Cea608Mode, Lines};
+
+use once_cell::sync::Lazy;
+
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug)]
+enum Output {
+    Buffer(gst::Buffer),
+    Gap(gst::ClockTime, gst::ClockTime),
+}
+
+struct State {
+    format: Option<Format>,
+    segment: Option<gst::Event>,
+    need_segment: bool,
+    /* Incomplete document */
+    data: Vec<u8>,
+    /* Time span of the input the document was read from */
+    start: Option<gst::ClockTime>,
+    end: Option<gst::ClockTime>,
+    /* End of the last output */
+    last_end: Option<gst::ClockTime>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            format: None,
+            segment: None,
+            need_segment: true,
+            data: vec![],
+            start: None,
+            end: None,
+            last_end: None,
+        }
+    }
+}
+
+pub struct TtmlParse {
+    srcpad: gst::Pad,
+    sinkpad: gst::Pad,
+
+    state: Mutex<State>,
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "ttmlparse",
+        gst::DebugColorFlags::empty(),
+        Some("TTML parser"),
+    )
+});
+
+impl State {
+    fn push_gap(&mut self, outputs: &mut Vec<Output>, until: gst::ClockTime) {
+        if let Some(position) = self.last_end.or(self.start) {
+            if until > position {
+                outputs.push(Output::Gap(position, until - position));
+            }
+        }
+
+        self.last_end = Some(self.last_end.map_or(until, |last_end| last_end.max(until)));
+    }
+
+    fn cue_buffer(format: Format, cue: &Cue) -> Result<gst::Buffer, serde_json::Error> {
+        let data = match format {
+            Format::Text => cue
+                .lines
+                .lines
+                .iter()
+                .map(|line| {
+                    line.chunks
+                        .iter()
+                        .map(|chunk| chunk.text.as_str())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Format::Json => serde_json::to_string(&Lines {
+                lines: cue.lines.lines.clone(),
+                mode: Some(Cea608Mode::PopOn),
+                clear: None,
+            })?,
+        };
+
+        Ok(gst::Buffer::from_mut_slice(data.into_bytes()))
+    }
+
+    /* Clips the cues of a document to the time span of its input, and
+     * fills the holes between them with gaps */
+    fn handle_cues(
+        &mut self,
+        element: &super::TtmlParse,
+        cues: Vec<Cue>,
+    ) -> Result<Vec<Output>, gst::FlowError> {
+        let format = self.format.unwrap();
+        let mut outputs = vec![];
+
+        for cue in cues {
+            let begin = self.start.map_or(cue.begin, |start| cue.begin.max(start));
+            let end = match (cue.end, self.end) {
+                (Some(end), Some(input_end)) => Some(end.min(input_end)),
+                (end, input_end) => end.or(input_end),
+            };
+
+            if end.map_or(false, |end| end <= begin) {
+                gst::debug!(CAT, obj: element, "Dropping cue outside input {:?}", cue);
+                continue;
+            }
+
+            self.push_gap(&mut outputs, begin);
+
+            let mut buffer = Self::cue_buffer(format, &cue).map_err(|err| {
+                gst::element_error!(
+                    element,
+                    gst::ResourceError::Write,
+                    ["Failed to serialize as json {}", err]
+                );
+
+                gst::FlowError::Error
+            })?;
+            {
+                let buffer = buffer.get_mut().unwrap();
+                buffer.set_pts(begin);
+                buffer.set_duration(end.map(|end| end - begin));
+            }
+            outputs.push(Output::Buffer(buffer));
+
+            if let Some(end) = end {
+                self.last_end = Some(self.last_end.map_or(end, |last_end| last_end.max(end)));
+            }
+        }
+
+        if let Some(end) = self.end {
+            self.push_gap(&mut outputs, end);
+        }
+
+        Ok(outputs)
+    }
+
+    fn parse(
+        &mut self,
+        element: &super::TtmlParse,
+        document: &[u8],
+    ) -> Result<Vec<Output>, gst::FlowError> {
+        let cues = ttml::parse_document(document).map_err(|err| {
+            gst::element_error!(
+                element,
+                gst::StreamError::Decode,
+                ["Failed to parse TTML document: {:?}", err]
+            );
+
+            gst::FlowError::Error
+        })?;
+
+        gst::debug!(CAT, obj: element, "Parsed {} cues", cues.len());
+
+        self.handle_cues(element, cues)
+    }
+}
+
+impl TtmlParse {
+    fn negotiate(&self, element: &super::TtmlParse) -> Option<Format> {
+        let mut downstream_caps = match self.srcpad.allowed_caps() {
+            None => self.srcpad.pad_template_caps(),
+            Some(caps) => caps,
+        };
+
+        if downstream_caps.is_empty() {
+            gst::error!(CAT, obj: element, "Empty downstream caps");
+            return None;
+        }
+
+        downstream_caps.fixate();
+
+        gst::debug!(
+            CAT,
+            obj: element,
+            "Negotiating for downstream caps {}",
+            downstream_caps
+        );
+
+        let s = downstream_caps.structure(0).unwrap();
+        let (format, caps) = if s.name() == "application/x-json" {
+            (
+                Format::Json,
+                gst::Caps::builder("application/x-json")
+                    .field("format", "cea608")
+                    .build(),
+            )
+        } else {
+            (
+                Format::Text,
+                gst::Caps::builder("text/x-raw")
+                    .field("format", "utf8")
+                    .build(),
+            )
+        };
+
+        if self.srcpad.push_event(gst::event::Caps::new(&caps)) {
+            Some(format)
+        } else {
+            None
+        }
+    }
+
+    /* Sends caps and segment before the first output */
+    fn prepare_output(
+        &self,
+        element: &super::TtmlParse,
+        state: &mut State,
+    ) -> Result<Vec<gst::Event>, gst::FlowError> {
+        let mut events = vec![];
+
+        if state.format.is_none() {
+            state.format = self.negotiate(element);
+            if state.format.is_none() {
+                return Err(gst::FlowError::NotNegotiated);
+            }
+        }
+
+        if state.need_segment {
+            let segment = state.segment.clone().unwrap_or_else(|| {
+                gst::event::Segment::new(&gst::FormattedSegment::<gst::ClockTime>::new())
+            });
+            events.push(segment);
+            state.need_segment = false;
+        }
+
+        Ok(events)
+    }
+
+    fn output(&self, events: Vec<gst::Event>, outputs: Vec<Output>) -> Result<(), gst::FlowError> {
+        for event in events {
+            self.srcpad.push_event(event);
+        }
+
+        for output in outputs {
+            match output {
+                Output::Buffer(buffer) => {
+                    gst::log!(CAT, obj: &self.srcpad, "Pushing {:?}", buffer);
+                    self.srcpad.push(buffer)?;
+                }
+                Output::Gap(pts, duration) => {
+                    self.srcpad
+                        .push_event(gst::event::Gap::builder(pts).duration(duration).build());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn sink_chain(
+        &self,
+        pad: &gst::Pad,
+        element: &super::TtmlParse,
+        buffer: gst::Buffer,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        gst::trace!(CAT, obj: pad, "Handling buffer {:?}", buffer);
+
+        let data = buffer.map_readable().map_err(|_| {
+            gst::error!(CAT, obj: pad, "Can't map buffer readable");
+
+            gst::FlowError::Error
+        })?;
+
+        let mut state = self.state.lock().unwrap();
+
+        let events = self.prepare_output(element, &mut state)?;
+
+        if state.data.is_empty() {
+            state.start = buffer.pts();
+        }
+        state.end = buffer.pts().opt_add(buffer.duration());
+        state.data.extend_from_slice(&data);
+
+        let mut outputs = vec![];
+        while let Some(size) = ttml::document_size(&state.data) {
+            let document = state.data.drain(..size).collect::<Vec<_>>();
+            outputs.extend(state.parse(element, &document)?);
+
+            /* The next document starts in this buffer */
+            let remaining = state
+                .data
+                .iter()
+                .position(|b| !b.is_ascii_whitespace())
+                .unwrap_or(state.data.len());
+            state.data.drain(..remaining);
+            state.start = buffer.pts();
+        }
+
+        drop(state);
+
+        self.output(events, outputs)?;
+
+        Ok(gst::FlowSuccess::Ok)
+    }
+
+    fn sink_event(&self, pad: &gst::Pad, element: &super::TtmlParse, event: gst::Event) -> bool {
+        use gst::EventView;
+
+        gst::log!(CAT, obj: pad, "Handling event {:?}", event);
+        match event.view() {
+            EventView::Caps(..) => {
+                let format = self.negotiate(element);
+                self.state.lock().unwrap().format = format;
+
+                format.is_some()
+            }
+            EventView::Segment(ev) => {
+                let mut state = self.state.lock().unwrap();
+
+                /* Segments in other formats are replaced with a time segment
+                 * before the first output */
+                state.segment = if ev.segment().format() == gst::Format::Time {
+                    Some(event.clone())
+                } else {
+                    None
+                };
+                state.need_segment = true;
+
+                true
+            }
+            EventView::FlushStop(..) => {
+                let mut state = self.state.lock().unwrap();
+                state.data.clear();
+                state.start = None;
+                state.end = None;
+                state.last_end = None;
+                drop(state);
+
+                pad.event_default(Some(element), event)
+            }
+            EventView::Eos(..) => {
+                let mut state = self.state.lock().unwrap();
+
+                if state.data.iter().any(|b| !b.is_ascii_whitespace()) {
+                    gst::warning!(
+                        CAT,
+                        obj: pad,
+                        "Discarding {} bytes of incomplete document",
+                        state.data.len()
+                    );
+                }
+                state.data.clear();
+
+                let events = self.prepare_output(element, &mut state).unwrap_or_default();
+                drop(state);
+
+                let _ = self.output(events, vec![]);
+
+                pad.event_default(Some(element), event)
+            }
+            _ => pad.event_default(Some(element), event),
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for TtmlParse {
+    const NAME: &'static str = "TtmlParse";
+    type Type = super::TtmlParse;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        let templ = klass.pad_template("sink").unwrap();
+        let sinkpad = gst::Pad::builder_with_template(&templ, Some("sink"))
+            .chain_function(|pad, parent, buffer| {
+                TtmlParse::catch_panic_pad_function(
+                    parent,
+                    || Err(gst::FlowError::Error),
+                    |this, element| this.sink_chain(pad, element, buffer),
+                )
+            })
+            .event_function(|pad, parent, event| {
+                TtmlParse::catch_panic_pad_function(
+                    parent,
+                    || false,
+                    |this, element| this.sink_event(pad, element, event),
+                )
+            })
+            .flags(gst::PadFlags::FIXED_CAPS)
+            .build();
+
+        let templ = klass.pad_template("src").unwrap();
+        let srcpad = gst::Pad::builder_with_template(&templ, Some("src"))
+            .flags(gst::PadFlags::FIXED_CAPS)
+            .build();
+
+        Self {
+            srcpad,
+            sinkpad,
+            state: Mutex::new(State::default()),
+        }
+    }
+}
+
+impl ObjectImpl for TtmlParse {
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(&self.sinkpad).unwrap();
+        obj.add_pad(&self.srcpad).unwrap();
+    }
+}
+
+impl GstObjectImpl for TtmlParse {}
+
+impl ElementImpl for TtmlParse {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "TTML parser",
+                "Parser/Subtitle",
+                "Parses TTML subtitles into timed text or JSON",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let mut caps = gst::Caps::new_empty();
+            {
+                let caps = caps.get_mut().unwrap();
+                caps.append(
+                    gst::Caps::builder("text/x-raw")
+                        .field("format", "utf8")
+                        .build(),
+                );
+                caps.append(
+                    gst::Caps::builder("application/x-json")
+                        .field("format", "cea608")
+                        .build(),
+                );
+            }
+
+            let src_pad_template = gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            let caps = gst::Caps::builder("application/ttml+xml").build();
+
+            let sink_pad_template = gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![src_pad_template, sink_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        if transition == gst::StateChange::ReadyToPaused {
+            let mut state = self.state.lock().unwrap();
+            *state = State::default();
+        }
+
+        self.parent_change_state(element, transition)
+    }
+}
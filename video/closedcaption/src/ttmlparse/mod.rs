@@ -0,0 +1,33 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+// Example command-line:
+//
+// gst-launch-1.0 filesrc location=input.ttml ! ttmlparse ! application/x-json ! \
+//   tttocea608 ! fakesink
+//
+// The samples of `stpp` tracks can be parsed too, cues are then clipped
+// to the time span of each sample.
+
+use gst::glib;
+use gst::prelude::*;
+
+mod imp;
+
+glib::wrapper! {
+    pub struct TtmlParse(ObjectSubclass<imp::TtmlParse>) @extends gst::Element, gst::Object;
+}
+
+pub fn register(plugin: &gst::Plugin) -> Result<(), glib::BoolError> {
+    gst::Element::register(
+        Some(plugin),
+        "ttmlparse",
+        gst::Rank::None,
+        TtmlParse::static_type(),
+    )
+}
@@ -15,7 +15,7 @@ use once_cell::sync::Lazy;
 use crate::ffi;
 use std::sync::Mutex;
 
-use crate::ttutils::{Cea608Mode, Chunk, Line, Lines, TextStyle};
+use crate::ttutils::{Cea608Mode, Chunk, Line, Lines, SpeakerPrefix, TextStyle};
 
 fn is_punctuation(word: &str) -> bool {
     word == "." || word == "," || word == "?" || word == "!" || word == ";" || word == ":"
@@ -88,12 +88,14 @@ const DEFAULT_FPS_D: i32 = 1;
 const DEFAULT_MODE: Cea608Mode = Cea608Mode::RollUp2;
 const DEFAULT_ORIGIN_ROW: i32 = -1;
 const DEFAULT_ORIGIN_COLUMN: u32 = 0;
+const DEFAULT_SPEAKER_PREFIX: SpeakerPrefix = SpeakerPrefix::Chevrons;
 
 #[derive(Debug, Clone)]
 struct Settings {
     mode: Cea608Mode,
     origin_row: i32,
     origin_column: u32,
+    speaker_prefix: SpeakerPrefix,
 }
 
 impl Default for Settings {
@@ -102,6 +104,7 @@ impl Default for Settings {
             mode: DEFAULT_MODE,
             origin_row: DEFAULT_ORIGIN_ROW,
             origin_column: DEFAULT_ORIGIN_COLUMN,
+            speaker_prefix: DEFAULT_SPEAKER_PREFIX,
         }
     }
 }
@@ -118,6 +121,7 @@ struct State {
     column: u32,
     mode: Cea608Mode,
     force_clear: bool,
+    pending_speaker_prefix: Option<String>,
 }
 
 impl Default for State {
@@ -134,6 +138,7 @@ impl Default for State {
             underline: false,
             mode: Cea608Mode::PopOn,
             force_clear: false,
+            pending_speaker_prefix: None,
         }
     }
 }
@@ -847,7 +852,12 @@ impl TtToCea608 {
                     gst::FlowError::Error
                 })?;
 
-                let phrases: Vec<&str> = data.split('\n').collect();
+                let mut phrases: Vec<String> = data.split('\n').map(String::from).collect();
+
+                if let Some(prefix) = state.pending_speaker_prefix.take() {
+                    phrases[0].insert_str(0, &prefix);
+                }
+
                 let mut row = match settings.origin_row {
                     -1 => match settings.mode {
                         Cea608Mode::PopOn | Cea608Mode::PaintOn => {
@@ -866,7 +876,7 @@ impl TtToCea608 {
                         chunks: vec![Chunk {
                             style: TextStyle::White,
                             underline: false,
-                            text: phrase.to_string(),
+                            text: phrase.clone(),
                         }],
                     });
                     if settings.mode == Cea608Mode::PopOn || settings.mode == Cea608Mode::PaintOn {
@@ -988,6 +998,31 @@ impl TtToCea608 {
 
                 pad.event_default(Some(element), event)
             }
+            EventView::CustomDownstream(_) => {
+                let mut state = self.state.lock().unwrap();
+
+                /* With JSON input, upstream is in charge of marking speaker changes */
+                if !state.json_input {
+                    let speaker_prefix = self.settings.lock().unwrap().speaker_prefix;
+
+                    if let Some(prefix) = event
+                        .structure()
+                        .and_then(|s| speaker_prefix.for_structure(s))
+                    {
+                        gst::debug!(
+                            CAT,
+                            obj: element,
+                            "Speaker changed, prefixing with {}",
+                            prefix
+                        );
+                        state.pending_speaker_prefix = Some(prefix);
+                    }
+                }
+
+                drop(state);
+
+                pad.event_default(Some(element), event)
+            }
             EventView::FlushStop(_) => {
                 let mut state = self.state.lock().unwrap();
                 let settings = self.settings.lock().unwrap();
@@ -1080,6 +1115,14 @@ impl ObjectImpl for TtToCea608 {
                     DEFAULT_ORIGIN_COLUMN,
                     glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_PLAYING,
                 ),
+                glib::ParamSpecEnum::new(
+                    "speaker-prefix",
+                    "Speaker prefix",
+                    "How to mark speaker changes signalled by the transcriber",
+                    SpeakerPrefix::static_type(),
+                    DEFAULT_SPEAKER_PREFIX as i32,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_PLAYING,
+                ),
             ]
         });
 
@@ -1120,6 +1163,11 @@ impl ObjectImpl for TtToCea608 {
                 state.force_clear = true;
                 state.column = settings.origin_column;
             }
+            "speaker-prefix" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.speaker_prefix =
+                    value.get::<SpeakerPrefix>().expect("type checked upstream");
+            }
             _ => unimplemented!(),
         }
     }
@@ -1138,6 +1186,10 @@ impl ObjectImpl for TtToCea608 {
                 let settings = self.settings.lock().unwrap();
                 settings.origin_column.to_value()
             }
+            "speaker-prefix" => {
+                let settings = self.settings.lock().unwrap();
+                settings.speaker_prefix.to_value()
+            }
             _ => unimplemented!(),
         }
     }
@@ -0,0 +1,1114 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+// The CEA-608 compatibility bytes are generated by an internal tttocea608
+// instance, fed with the same input as this element. Its output is collected
+// frame by frame and inserted in the field 1 cc_data of our CDP packets.
+
+use gst::glib;
+use gst::prelude::*;
+use gst::subclass::prelude::*;
+
+use once_cell::sync::Lazy;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::ccutils::{cdp_framerate, write_cdp, CdpFramerate, CDP_FRAMERATES};
+use crate::cea708utils::{
+    Cea708Encoder, Color, PenAttributes, PenColor, Window, MAX_SERVICE_NUMBER,
+};
+use crate::ttutils::{Cea608Mode, Chunk, Line, Lines, TextStyle};
+
+const DEFAULT_FPS_N: i32 = 30;
+const DEFAULT_FPS_D: i32 = 1;
+
+const DEFAULT_MODE: Cea608Mode = Cea608Mode::RollUp2;
+const DEFAULT_SERVICE_NUMBER: u32 = 1;
+const DEFAULT_CEA608: bool = false;
+
+/* Size of the grid of the JSON model, see ttutils::Line */
+const ROWS: u32 = 15;
+const COLUMNS: u32 = 32;
+
+#[derive(Debug, Clone)]
+struct Settings {
+    mode: Cea608Mode,
+    service_number: u32,
+    cea608: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            mode: DEFAULT_MODE,
+            service_number: DEFAULT_SERVICE_NUMBER,
+            cea608: DEFAULT_CEA608,
+        }
+    }
+}
+
+/* Output of the internal tttocea608 */
+#[derive(Debug, Default)]
+struct Cea608Output {
+    framerate: Option<gst::Fraction>,
+    /* Frame number and cc_data */
+    pairs: VecDeque<(u64, u16)>,
+}
+
+struct State {
+    settings: Settings,
+    framerate: &'static CdpFramerate,
+    json_input: bool,
+    encoder: Cea708Encoder,
+    cdp_sequence_counter: u16,
+    last_frame_no: u64,
+    max_frame_no: u64,
+    erase_display_frame_no: Option<u64>,
+    mode: Option<Cea608Mode>,
+    /* The currently displayed window, windows 0 and 1 alternate in pop-on mode */
+    window: Option<u8>,
+    pen: Option<(TextStyle, bool)>,
+    column: u32,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        let settings = Settings::default();
+
+        State {
+            framerate: cdp_framerate(DEFAULT_FPS_N, DEFAULT_FPS_D).unwrap(),
+            json_input: false,
+            encoder: Cea708Encoder::new(settings.service_number as u8),
+            settings,
+            cdp_sequence_counter: 0,
+            last_frame_no: 0,
+            max_frame_no: 0,
+            erase_display_frame_no: None,
+            mode: None,
+            window: None,
+            pen: None,
+            column: 0,
+        }
+    }
+}
+
+static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
+    gst::DebugCategory::new(
+        "tttocea708",
+        gst::DebugColorFlags::empty(),
+        Some("TT CEA 708 Element"),
+    )
+});
+
+impl State {
+    fn new(settings: &Settings) -> Self {
+        State {
+            settings: settings.clone(),
+            encoder: Cea708Encoder::new(settings.service_number as u8),
+            ..Default::default()
+        }
+    }
+
+    fn frame_pts(&self, frame_no: u64) -> gst::ClockTime {
+        (frame_no * gst::ClockTime::SECOND)
+            .mul_div_round(self.framerate.fps_d as u64, self.framerate.fps_n as u64)
+            .unwrap()
+    }
+
+    fn frame_no(&self, ts: gst::ClockTime) -> u64 {
+        ts.mul_div_round(self.framerate.fps_n as u64, self.framerate.fps_d as u64)
+            .unwrap()
+            .seconds()
+    }
+
+    fn check_erase_display(&mut self, element: &super::TtToCea708) {
+        if let Some(erase_display_frame_no) = self.erase_display_frame_no {
+            if self.last_frame_no + 1 >= erase_display_frame_no {
+                gst::debug!(CAT, obj: element, "Erasing display");
+                self.erase_display_frame_no = None;
+                if let Some(window) = self.window.take() {
+                    self.encoder.delete_windows(1 << window);
+                }
+            }
+        }
+    }
+
+    /* Outputs the CDP packet for the current frame */
+    fn cdp(
+        &mut self,
+        element: &super::TtToCea708,
+        bufferlist: &mut gst::BufferListRef,
+        cea608: &mut VecDeque<(u64, u16)>,
+    ) {
+        self.check_erase_display(element);
+
+        let framerate = self.framerate;
+        let mut cc_data = Vec::with_capacity(framerate.cc_count * 3);
+
+        /* Drop the compatibility bytes we are late for */
+        while cea608
+            .front()
+            .map_or(false, |(frame_no, _)| *frame_no < self.last_frame_no)
+        {
+            let (frame_no, _) = cea608.pop_front().unwrap();
+            gst::debug!(CAT, obj: element, "Dropping CEA-608 data for frame {}", frame_no);
+        }
+
+        match cea608.front() {
+            Some((frame_no, pair)) if *frame_no == self.last_frame_no => {
+                cc_data.push(0xfc);
+                cc_data.extend_from_slice(&pair.to_be_bytes());
+                cea608.pop_front();
+            }
+            _ => cc_data.extend_from_slice(&[0xf8, 0x80, 0x80]),
+        }
+
+        /* Field 2 is always padding */
+        for _ in 1..framerate.cea608_count {
+            cc_data.extend_from_slice(&[0xf9, 0x80, 0x80]);
+        }
+
+        cc_data.extend(
+            self.encoder
+                .cc_data(framerate.cc_count - framerate.cea608_count),
+        );
+
+        while cc_data.len() < framerate.cc_count * 3 {
+            cc_data.extend_from_slice(&[0xfa, 0x00, 0x00]);
+        }
+
+        let cdp = write_cdp(framerate, self.cdp_sequence_counter, &cc_data);
+        self.cdp_sequence_counter = self.cdp_sequence_counter.wrapping_add(1);
+
+        let pts = self.frame_pts(self.last_frame_no);
+
+        if self.last_frame_no < self.max_frame_no {
+            self.last_frame_no += 1;
+        } else {
+            gst::debug!(CAT, obj: element, "More text than bandwidth!");
+        }
+
+        let duration = self.frame_pts(self.last_frame_no) - pts;
+
+        gst::trace!(CAT, obj: element, "{} -> {}: {:x?}", pts, pts + duration, cdp);
+
+        let mut buf = gst::Buffer::from_mut_slice(cdp);
+        {
+            let buf_mut = buf.get_mut().unwrap();
+            buf_mut.set_pts(pts);
+            buf_mut.set_duration(duration);
+        }
+
+        bufferlist.add(buf);
+    }
+
+    fn pad(
+        &mut self,
+        element: &super::TtToCea708,
+        bufferlist: &mut gst::BufferListRef,
+        cea608: &mut VecDeque<(u64, u16)>,
+        frame_no: u64,
+    ) {
+        while self.last_frame_no < frame_no {
+            self.cdp(element, bufferlist, cea608);
+        }
+    }
+
+    fn set_pen(&mut self, style: TextStyle, underline: bool) {
+        if self.pen == Some((style, underline)) {
+            return;
+        }
+
+        let (foreground, italics) = Color::from_text_style(style);
+
+        self.encoder.set_pen_attributes(&PenAttributes {
+            italics,
+            underline,
+            ..Default::default()
+        });
+        self.encoder.set_pen_color(&PenColor {
+            foreground,
+            ..Default::default()
+        });
+
+        self.pen = Some((style, underline));
+    }
+
+    fn define_window(&mut self, id: u8, visible: bool, row: u32, row_count: u32) {
+        let window = Window {
+            visible,
+            relative_positioning: true,
+            /* Rounded up, so that decoders map it back to the same row */
+            anchor_vertical: ((row * 100 + ROWS - 1) / ROWS) as u8,
+            anchor_horizontal: 0,
+            row_count: row_count as usize,
+            column_count: COLUMNS as usize,
+            ..Default::default()
+        };
+
+        self.encoder.define_window(id, &window);
+
+        /* DefineWindow selects the window and its predefined pen style */
+        self.pen = Some((TextStyle::White, false));
+    }
+
+    fn put_chunk(&mut self, element: &super::TtToCea708, chunk: &Chunk) {
+        self.set_pen(chunk.style, chunk.underline);
+
+        for c in chunk.text.chars() {
+            if self.column >= COLUMNS {
+                gst::warning!(
+                    CAT,
+                    obj: element,
+                    "Dropping characters after 32nd column: {}",
+                    c
+                );
+                break;
+            }
+
+            if !self.encoder.put(c) {
+                gst::warning!(CAT, obj: element, "Can't encode character {:?}", c);
+            }
+            self.column += 1;
+        }
+    }
+
+    fn pop_on(&mut self, element: &super::TtToCea708, lines: &Lines) {
+        let first_row = lines
+            .lines
+            .iter()
+            .filter_map(|line| line.row)
+            .min()
+            .unwrap_or(ROWS - lines.lines.len() as u32)
+            .min(ROWS - 1);
+
+        let mut row = first_row;
+        let mut rows = vec![];
+        for line in &lines.lines {
+            if let Some(line_row) = line.row {
+                row = line_row;
+            }
+
+            if row >= ROWS {
+                gst::warning!(
+                    CAT,
+                    obj: element,
+                    "Dropping line after 15th row: {:?}",
+                    line
+                );
+                continue;
+            }
+
+            rows.push((row - first_row, line));
+            row += 1;
+        }
+
+        let row_count = rows.iter().map(|(row, _)| row + 1).max().unwrap_or(1);
+
+        /* Fill a hidden window, then swap it with the displayed one */
+        let old_window = self.window;
+        let new_window = match old_window {
+            Some(0) => 1,
+            _ => 0,
+        };
+
+        self.define_window(new_window, false, first_row, row_count);
+
+        for (row, line) in rows {
+            let column = line.column.unwrap_or(0).min(COLUMNS - 1);
+            self.encoder.set_pen_location(row as u8, column as u8);
+            self.column = column;
+
+            for chunk in &line.chunks {
+                self.put_chunk(element, chunk);
+            }
+        }
+
+        self.encoder.display_windows(1 << new_window);
+        if let Some(old_window) = old_window {
+            self.encoder.delete_windows(1 << old_window);
+        }
+        self.window = Some(new_window);
+    }
+
+    fn paint_on(&mut self, element: &super::TtToCea708, lines: &Lines) {
+        if self.window.is_none() {
+            self.define_window(0, true, 0, ROWS);
+            self.window = Some(0);
+        }
+
+        let mut row = 13;
+        for line in &lines.lines {
+            if let Some(line_row) = line.row {
+                row = line_row;
+            }
+
+            if row >= ROWS {
+                gst::warning!(
+                    CAT,
+                    obj: element,
+                    "Dropping line after 15th row: {:?}",
+                    line
+                );
+                continue;
+            }
+
+            let column = line.column.unwrap_or(0).min(COLUMNS - 1);
+            self.encoder.set_pen_location(row as u8, column as u8);
+            self.column = column;
+
+            for chunk in &line.chunks {
+                self.put_chunk(element, chunk);
+            }
+
+            row += 1;
+        }
+    }
+
+    fn roll_up(&mut self, element: &super::TtToCea708, lines: &Lines, row_count: u32) {
+        if self.window.is_none() {
+            let base_row = lines
+                .lines
+                .iter()
+                .find_map(|line| line.row)
+                .unwrap_or(ROWS - 1)
+                .clamp(row_count - 1, ROWS - 1);
+
+            self.define_window(0, true, base_row + 1 - row_count, row_count);
+            self.window = Some(0);
+            self.column = 0;
+        }
+
+        for (idx, line) in lines.lines.iter().enumerate() {
+            let carriage_return = line.carriage_return.unwrap_or(idx > 0);
+
+            if carriage_return && self.column > 0 {
+                self.encoder.carriage_return();
+                self.column = 0;
+            }
+
+            for chunk in &line.chunks {
+                /* Wrap the text ourselves at word boundaries */
+                for word in chunk.text.split_whitespace() {
+                    let len = word.chars().count() as u32;
+                    let space = if self.column > 0 { 1 } else { 0 };
+
+                    if self.column > 0 && self.column + space + len > COLUMNS {
+                        self.encoder.carriage_return();
+                        self.column = 0;
+                    } else if space > 0 {
+                        self.put_chunk(
+                            element,
+                            &Chunk {
+                                style: chunk.style,
+                                underline: false,
+                                text: String::from(" "),
+                            },
+                        );
+                    }
+
+                    self.put_chunk(
+                        element,
+                        &Chunk {
+                            style: chunk.style,
+                            underline: chunk.underline,
+                            text: word.to_string(),
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        if let Some(window) = self.window {
+            self.encoder.clear_windows(1 << window);
+        }
+        self.erase_display_frame_no = None;
+        self.column = 0;
+    }
+
+    fn generate(
+        &mut self,
+        element: &super::TtToCea708,
+        bufferlist: &mut gst::BufferListRef,
+        cea608: &mut VecDeque<(u64, u16)>,
+        pts: gst::ClockTime,
+        duration: gst::ClockTime,
+        lines: Lines,
+    ) {
+        let frame_no = self.frame_no(pts);
+
+        if self.last_frame_no == 0 {
+            gst::debug!(CAT, obj: element, "Initial skip to frame no {}", frame_no);
+            self.last_frame_no = pts
+                .mul_div_floor(self.framerate.fps_n as u64, self.framerate.fps_d as u64)
+                .unwrap()
+                .seconds();
+        }
+
+        self.max_frame_no = self.frame_no(pts + duration);
+
+        self.pad(element, bufferlist, cea608, frame_no);
+
+        let mode = lines.mode.unwrap_or(self.settings.mode);
+        if self.mode != Some(mode) {
+            gst::debug!(CAT, obj: element, "Switching to mode {:?}", mode);
+
+            /* All modes use different window layouts */
+            if self.mode.is_some() {
+                self.encoder.delete_windows(0xff);
+            }
+            self.mode = Some(mode);
+            self.window = None;
+            self.erase_display_frame_no = None;
+            self.column = 0;
+        }
+
+        if lines.clear == Some(true) {
+            self.clear();
+        }
+
+        match mode {
+            Cea608Mode::PopOn => {
+                if !lines.lines.is_empty() {
+                    self.pop_on(element, &lines);
+                    self.erase_display_frame_no = Some(self.max_frame_no);
+                }
+            }
+            Cea608Mode::PaintOn => self.paint_on(element, &lines),
+            Cea608Mode::RollUp2 => self.roll_up(element, &lines, 2),
+            Cea608Mode::RollUp3 => self.roll_up(element, &lines, 3),
+            Cea608Mode::RollUp4 => self.roll_up(element, &lines, 4),
+        }
+
+        let max_frame_no = self.max_frame_no;
+        self.pad(element, bufferlist, cea608, max_frame_no);
+    }
+}
+
+pub struct TtToCea708 {
+    srcpad: gst::Pad,
+    sinkpad: gst::Pad,
+
+    /* Internal tttocea608 and the pads feeding it and collecting its output */
+    cea608_element: gst::Element,
+    cea608_srcpad: gst::Pad,
+    cea608_sinkpad: gst::Pad,
+    cea608_output: Arc<Mutex<Cea608Output>>,
+
+    // Ordered by locking order
+    state: Mutex<State>,
+    settings: Mutex<Settings>,
+}
+
+impl TtToCea708 {
+    fn cea608_enabled(&self) -> bool {
+        self.state.lock().unwrap().settings.cea608
+    }
+
+    fn sink_chain(
+        &self,
+        pad: &gst::Pad,
+        element: &super::TtToCea708,
+        buffer: gst::Buffer,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        gst::log!(CAT, obj: element, "Handling {:?}", buffer);
+
+        let pts = buffer.pts().ok_or_else(|| {
+            gst::element_error!(
+                element,
+                gst::StreamError::Format,
+                ["Stream with timestamped buffers required"]
+            );
+            gst::FlowError::Error
+        })?;
+
+        let duration = buffer.duration().ok_or_else(|| {
+            gst::element_error!(
+                element,
+                gst::StreamError::Format,
+                ["Buffers of stream need to have a duration"]
+            );
+            gst::FlowError::Error
+        })?;
+
+        if self.cea608_enabled() {
+            if let Err(err) = self.cea608_srcpad.push(buffer.clone()) {
+                gst::error!(CAT, obj: element, "Failed to generate CEA-608: {:?}", err);
+                return Err(err);
+            }
+        }
+
+        let data = buffer.map_readable().map_err(|_| {
+            gst::error!(CAT, obj: pad, "Can't map buffer readable");
+
+            gst::FlowError::Error
+        })?;
+
+        let mut state = self.state.lock().unwrap();
+
+        let lines = if state.json_input {
+            serde_json::from_slice::<Lines>(&data).map_err(|err| {
+                gst::error!(CAT, obj: pad, "Failed to parse input as json: {}", err);
+
+                gst::FlowError::Error
+            })?
+        } else {
+            let data = std::str::from_utf8(&data).map_err(|err| {
+                gst::error!(CAT, obj: pad, "Can't decode utf8: {}", err);
+
+                gst::FlowError::Error
+            })?;
+
+            let phrases: Vec<&str> = data.split('\n').collect();
+
+            let mut row = match state.settings.mode {
+                Cea608Mode::PopOn | Cea608Mode::PaintOn => {
+                    ROWS.saturating_sub(phrases.len() as u32)
+                }
+                Cea608Mode::RollUp2 | Cea608Mode::RollUp3 | Cea608Mode::RollUp4 => ROWS - 1,
+            };
+
+            let mut lines = Lines {
+                lines: Vec::new(),
+                mode: Some(state.settings.mode),
+                clear: None,
+            };
+
+            for phrase in phrases {
+                lines.lines.push(Line {
+                    carriage_return: None,
+                    column: None,
+                    row: Some(row),
+                    chunks: vec![Chunk {
+                        style: TextStyle::White,
+                        underline: false,
+                        text: phrase.to_string(),
+                    }],
+                });
+
+                if !state.settings.mode.is_rollup() {
+                    row += 1;
+                }
+            }
+
+            lines
+        };
+
+        let mut bufferlist = gst::BufferList::new();
+        let mut_list = bufferlist.get_mut().unwrap();
+
+        {
+            let mut cea608_output = self.cea608_output.lock().unwrap();
+            state.generate(
+                element,
+                mut_list,
+                &mut cea608_output.pairs,
+                pts,
+                duration,
+                lines,
+            );
+        }
+
+        drop(state);
+
+        self.srcpad.push_list(bufferlist)
+    }
+
+    fn sink_event(&self, pad: &gst::Pad, element: &super::TtToCea708, event: gst::Event) -> bool {
+        gst::log!(CAT, obj: pad, "Handling event {:?}", event);
+
+        use gst::EventView;
+
+        /* The internal tttocea608 sees the same stream as us */
+        if self.cea608_enabled() && !matches!(event.view(), EventView::Caps(_)) {
+            let _ = self.cea608_srcpad.push_event(event.clone());
+        }
+
+        match event.view() {
+            EventView::Caps(e) => {
+                let mut downstream_caps = match self.srcpad.allowed_caps() {
+                    None => self.srcpad.pad_template_caps(),
+                    Some(caps) => caps,
+                };
+
+                if downstream_caps.is_empty() {
+                    gst::error!(CAT, obj: pad, "Empty downstream caps");
+                    return false;
+                }
+
+                let caps = downstream_caps.make_mut();
+                let s = caps.structure_mut(0).unwrap();
+
+                s.fixate_field_nearest_fraction(
+                    "framerate",
+                    gst::Fraction::new(DEFAULT_FPS_N, DEFAULT_FPS_D),
+                );
+                s.fixate();
+
+                let framerate = s.get::<gst::Fraction>("framerate").unwrap();
+                let cdp_framerate = match cdp_framerate(framerate.numer(), framerate.denom()) {
+                    Some(cdp_framerate) => cdp_framerate,
+                    None => {
+                        gst::error!(CAT, obj: pad, "Unsupported framerate {}", framerate);
+                        return false;
+                    }
+                };
+
+                let mut state = self.state.lock().unwrap();
+                state.framerate = cdp_framerate;
+
+                let upstream_caps = e.caps();
+                let s = upstream_caps.structure(0).unwrap();
+                state.json_input = s.name() == "application/x-json";
+
+                let cea608 = state.settings.cea608;
+
+                drop(state);
+
+                if cea608 {
+                    self.cea608_output.lock().unwrap().framerate = Some(framerate);
+                    if !self.cea608_srcpad.push_event(event.clone()) {
+                        gst::error!(CAT, obj: pad, "Internal tttocea608 refused caps");
+                        return false;
+                    }
+                }
+
+                gst::debug!(CAT, obj: pad, "Pushing caps {}", caps);
+
+                self.srcpad
+                    .push_event(gst::event::Caps::new(&downstream_caps))
+            }
+            EventView::Gap(e) => {
+                let mut state = self.state.lock().unwrap();
+
+                let (timestamp, duration) = e.get();
+
+                if state.last_frame_no == 0 {
+                    state.last_frame_no = timestamp
+                        .mul_div_floor(state.framerate.fps_n as u64, state.framerate.fps_d as u64)
+                        .unwrap()
+                        .seconds();
+
+                    gst::debug!(
+                        CAT,
+                        obj: element,
+                        "Initial skip to frame no {}",
+                        state.last_frame_no
+                    );
+                }
+
+                let frame_no = state.frame_no(timestamp + duration.unwrap_or(gst::ClockTime::ZERO));
+                state.max_frame_no = frame_no;
+
+                let mut bufferlist = gst::BufferList::new();
+                let mut_list = bufferlist.get_mut().unwrap();
+
+                {
+                    let mut cea608_output = self.cea608_output.lock().unwrap();
+                    state.pad(element, mut_list, &mut cea608_output.pairs, frame_no);
+                }
+
+                drop(state);
+
+                let _ = self.srcpad.push_list(bufferlist);
+
+                true
+            }
+            EventView::Eos(_) => {
+                let mut state = self.state.lock().unwrap();
+                let mut cea608_output = self.cea608_output.lock().unwrap();
+
+                /* Output everything that is still pending, and wait for
+                 * the erasure of the last pop-on caption */
+                let mut frame_no = state.last_frame_no;
+                if let Some(erase_display_frame_no) = state.erase_display_frame_no {
+                    frame_no = frame_no.max(erase_display_frame_no);
+                }
+                if let Some((last_frame_no, _)) = cea608_output.pairs.back() {
+                    frame_no = frame_no.max(*last_frame_no + 1);
+                }
+
+                let mut bufferlist = gst::BufferList::new();
+                let mut_list = bufferlist.get_mut().unwrap();
+
+                state.max_frame_no = frame_no;
+                state.pad(element, mut_list, &mut cea608_output.pairs, frame_no);
+
+                while !state.encoder.is_empty() {
+                    state.max_frame_no += 1;
+                    state.cdp(element, mut_list, &mut cea608_output.pairs);
+                }
+
+                drop(cea608_output);
+                drop(state);
+
+                if !bufferlist.is_empty() {
+                    let _ = self.srcpad.push_list(bufferlist);
+                }
+
+                pad.event_default(Some(element), event)
+            }
+            EventView::FlushStop(_) => {
+                let mut state = self.state.lock().unwrap();
+                let settings = state.settings.clone();
+                let framerate = state.framerate;
+                let json_input = state.json_input;
+
+                *state = State::new(&settings);
+                state.framerate = framerate;
+                state.json_input = json_input;
+
+                self.cea608_output.lock().unwrap().pairs.clear();
+
+                drop(state);
+
+                pad.event_default(Some(element), event)
+            }
+            _ => pad.event_default(Some(element), event),
+        }
+    }
+
+    fn cea608_sink_chain(
+        output: &Mutex<Cea608Output>,
+        buffer: gst::Buffer,
+    ) -> Result<gst::FlowSuccess, gst::FlowError> {
+        let mut output = output.lock().unwrap();
+
+        let framerate = output.framerate.ok_or(gst::FlowError::NotNegotiated)?;
+        let pts = buffer.pts().ok_or(gst::FlowError::Error)?;
+        let map = buffer.map_readable().map_err(|_| gst::FlowError::Error)?;
+
+        if map.len() < 2 {
+            return Ok(gst::FlowSuccess::Ok);
+        }
+
+        let frame_no = pts
+            .mul_div_round(framerate.numer() as u64, framerate.denom() as u64)
+            .unwrap()
+            .seconds();
+
+        output
+            .pairs
+            .push_back((frame_no, u16::from_be_bytes([map[0], map[1]])));
+
+        Ok(gst::FlowSuccess::Ok)
+    }
+
+    fn cea608_sink_query(output: &Mutex<Cea608Output>, query: &mut gst::QueryRef) -> bool {
+        use gst::QueryViewMut;
+
+        match query.view_mut() {
+            QueryViewMut::Caps(q) => {
+                let mut caps = gst::Caps::builder("closedcaption/x-cea-608").field("format", "raw");
+                if let Some(framerate) = output.lock().unwrap().framerate {
+                    caps = caps.field("framerate", framerate);
+                }
+                let caps = caps.build();
+
+                let caps = match q.filter() {
+                    Some(filter) => {
+                        filter.intersect_with_mode(&caps, gst::CapsIntersectMode::First)
+                    }
+                    None => caps,
+                };
+
+                q.set_result(&caps);
+
+                true
+            }
+            QueryViewMut::AcceptCaps(q) => {
+                q.set_result(true);
+
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for TtToCea708 {
+    const NAME: &'static str = "TtToCea708";
+    type Type = super::TtToCea708;
+    type ParentType = gst::Element;
+
+    fn with_class(klass: &Self::Class) -> Self {
+        let templ = klass.pad_template("sink").unwrap();
+        let sinkpad = gst::Pad::builder_with_template(&templ, Some("sink"))
+            .chain_function(|pad, parent, buffer| {
+                TtToCea708::catch_panic_pad_function(
+                    parent,
+                    || Err(gst::FlowError::Error),
+                    |this, element| this.sink_chain(pad, element, buffer),
+                )
+            })
+            .event_function(|pad, parent, event| {
+                TtToCea708::catch_panic_pad_function(
+                    parent,
+                    || false,
+                    |this, element| this.sink_event(pad, element, event),
+                )
+            })
+            .flags(gst::PadFlags::FIXED_CAPS)
+            .build();
+
+        let templ = klass.pad_template("src").unwrap();
+        let srcpad = gst::Pad::builder_with_template(&templ, Some("src"))
+            .flags(gst::PadFlags::FIXED_CAPS)
+            .build();
+
+        let cea608_element = gst::ElementFactory::make("tttocea608", None)
+            .expect("tttocea608 is registered by the same plugin");
+        let cea608_output = Arc::new(Mutex::new(Cea608Output::default()));
+
+        let cea608_srcpad = gst::Pad::builder(Some("cea608_src"), gst::PadDirection::Src).build();
+        let cea608_sinkpad = gst::Pad::builder(Some("cea608_sink"), gst::PadDirection::Sink)
+            .chain_function({
+                let output = cea608_output.clone();
+                move |_pad, _parent, buffer| TtToCea708::cea608_sink_chain(&output, buffer)
+            })
+            .event_function(|_pad, _parent, _event| true)
+            .query_function({
+                let output = cea608_output.clone();
+                move |_pad, _parent, query| TtToCea708::cea608_sink_query(&output, query)
+            })
+            .build();
+
+        cea608_srcpad
+            .link(&cea608_element.static_pad("sink").unwrap())
+            .unwrap();
+        cea608_element
+            .static_pad("src")
+            .unwrap()
+            .link(&cea608_sinkpad)
+            .unwrap();
+
+        Self {
+            srcpad,
+            sinkpad,
+            cea608_element,
+            cea608_srcpad,
+            cea608_sinkpad,
+            cea608_output,
+            state: Mutex::new(State::default()),
+            settings: Mutex::new(Settings::default()),
+        }
+    }
+}
+
+impl ObjectImpl for TtToCea708 {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecEnum::new(
+                    "mode",
+                    "Mode",
+                    "Which mode to operate in, for both CEA-708 and CEA-608",
+                    Cea608Mode::static_type(),
+                    DEFAULT_MODE as i32,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecUInt::new(
+                    "service-number",
+                    "Service Number",
+                    "The CEA-708 caption service to encode",
+                    1,
+                    MAX_SERVICE_NUMBER as u32,
+                    DEFAULT_SERVICE_NUMBER,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecBoolean::new(
+                    "cea608",
+                    "CEA-608",
+                    "Whether to also output CEA-608 compatibility bytes for field 1",
+                    DEFAULT_CEA608,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+
+        obj.add_pad(&self.sinkpad).unwrap();
+        obj.add_pad(&self.srcpad).unwrap();
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        match pspec.name() {
+            "mode" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.mode = value.get::<Cea608Mode>().expect("type checked upstream");
+                self.cea608_element.set_property("mode", settings.mode);
+            }
+            "service-number" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.service_number = value.get().expect("type checked upstream");
+            }
+            "cea608" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.cea608 = value.get().expect("type checked upstream");
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            "mode" => {
+                let settings = self.settings.lock().unwrap();
+                settings.mode.to_value()
+            }
+            "service-number" => {
+                let settings = self.settings.lock().unwrap();
+                settings.service_number.to_value()
+            }
+            "cea608" => {
+                let settings = self.settings.lock().unwrap();
+                settings.cea608.to_value()
+            }
+            _ => unimplemented!(),
+        }
+    }
+}
+
+impl GstObjectImpl for TtToCea708 {}
+
+impl ElementImpl for TtToCea708 {
+    fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gst::subclass::ElementMetadata> = Lazy::new(|| {
+            gst::subclass::ElementMetadata::new(
+                "TT to CEA-708",
+                "Generic",
+                "Converts timed text to CEA-708 Closed Captions in CDP packets",
+                "agent <agent@local>",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gst::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+            let mut caps = gst::Caps::new_empty();
+            {
+                let caps = caps.get_mut().unwrap();
+
+                let s = gst::Structure::builder("text/x-raw").build();
+                caps.append_structure(s);
+
+                let s = gst::Structure::builder("application/x-json")
+                    .field("format", "cea608")
+                    .build();
+                caps.append_structure(s);
+            }
+
+            let sink_pad_template = gst::PadTemplate::new(
+                "sink",
+                gst::PadDirection::Sink,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            let framerates = gst::List::new(
+                CDP_FRAMERATES
+                    .iter()
+                    .map(|framerate| gst::Fraction::new(framerate.fps_n, framerate.fps_d)),
+            );
+
+            let caps = gst::Caps::builder("closedcaption/x-cea-708")
+                .field("format", "cdp")
+                .field("framerate", framerates)
+                .build();
+
+            let src_pad_template = gst::PadTemplate::new(
+                "src",
+                gst::PadDirection::Src,
+                gst::PadPresence::Always,
+                &caps,
+            )
+            .unwrap();
+
+            vec![src_pad_template, sink_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+
+    fn change_state(
+        &self,
+        element: &Self::Type,
+        transition: gst::StateChange,
+    ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+        gst::trace!(CAT, obj: element, "Changing state {:?}", transition);
+
+        match transition {
+            gst::StateChange::ReadyToPaused => {
+                let mut state = self.state.lock().unwrap();
+                let settings = self.settings.lock().unwrap();
+                *state = State::new(&settings);
+                *self.cea608_output.lock().unwrap() = Cea608Output::default();
+            }
+            gst::StateChange::NullToReady => {
+                self.cea608_srcpad.set_active(true).unwrap();
+                self.cea608_sinkpad.set_active(true).unwrap();
+            }
+            _ => (),
+        }
+
+        /* The internal tttocea608 follows our state, before we start and after we stop */
+        if matches!(
+            transition,
+            gst::StateChange::NullToReady
+                | gst::StateChange::ReadyToPaused
+                | gst::StateChange::PausedToPlaying
+        ) {
+            self.cea608_element
+                .set_state(transition.next())
+                .map_err(|_| gst::StateChangeError)?;
+        }
+
+        let ret = self.parent_change_state(element, transition)?;
+
+        match transition {
+            gst::StateChange::PausedToReady => {
+                let mut state = self.state.lock().unwrap();
+                *state = State::default();
+            }
+            gst::StateChange::ReadyToNull => {
+                self.cea608_srcpad.set_active(false).unwrap();
+                self.cea608_sinkpad.set_active(false).unwrap();
+            }
+            _ => (),
+        }
+
+        if matches!(
+            transition,
+            gst::StateChange::PlayingToPaused
+                | gst::StateChange::PausedToReady
+                | gst::StateChange::ReadyToNull
+        ) {
+            let _ = self.cea608_element.set_state(transition.next());
+        }
+
+        Ok(ret)
+    }
+}
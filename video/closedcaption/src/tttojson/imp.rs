@@ -15,7 +15,7 @@ use once_cell::sync::Lazy;
 use std::cmp::min;
 use std::sync::Mutex;
 
-use crate::ttutils::{Cea608Mode, Chunk, Line, Lines, TextStyle};
+use crate::ttutils::{Cea608Mode, Chunk, Line, Lines, SpeakerPrefix, TextStyle};
 
 static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
     gst::DebugCategory::new(
@@ -26,22 +26,33 @@ static CAT: Lazy<gst::DebugCategory> = Lazy::new(|| {
 });
 
 const DEFAULT_MODE: Cea608Mode = Cea608Mode::RollUp2;
+const DEFAULT_SPEAKER_PREFIX: SpeakerPrefix = SpeakerPrefix::Chevrons;
 
 #[derive(Debug, Clone)]
 struct Settings {
     mode: Cea608Mode,
+    speaker_prefix: SpeakerPrefix,
 }
 
 impl Default for Settings {
     fn default() -> Self {
-        Settings { mode: DEFAULT_MODE }
+        Settings {
+            mode: DEFAULT_MODE,
+            speaker_prefix: DEFAULT_SPEAKER_PREFIX,
+        }
     }
 }
 
+#[derive(Debug, Default)]
+struct State {
+    pending_speaker_prefix: Option<String>,
+}
+
 pub struct TtToJson {
     srcpad: gst::Pad,
     sinkpad: gst::Pad,
     settings: Mutex<Settings>,
+    state: Mutex<State>,
 }
 
 impl TtToJson {
@@ -84,7 +95,14 @@ impl TtToJson {
 
         let mut row = min(15usize.saturating_sub(text.lines().count()), 13usize) as u32;
 
+        let mut speaker_prefix = self.state.lock().unwrap().pending_speaker_prefix.take();
+
         for phrase in text.lines() {
+            let text = match speaker_prefix.take() {
+                Some(prefix) => prefix + phrase,
+                None => phrase.to_string(),
+            };
+
             lines.lines.push(Line {
                 carriage_return: Some(true),
                 column: Some(0),
@@ -93,7 +111,7 @@ impl TtToJson {
                     // Default CEA 608 styling
                     style: TextStyle::White,
                     underline: false,
-                    text: phrase.to_string(),
+                    text,
                 }],
             });
 
@@ -133,6 +151,29 @@ impl TtToJson {
                     .build();
                 self.srcpad.push_event(gst::event::Caps::new(&caps))
             }
+            EventView::CustomDownstream(_) => {
+                let speaker_prefix = self.settings.lock().unwrap().speaker_prefix;
+
+                if let Some(prefix) = event
+                    .structure()
+                    .and_then(|s| speaker_prefix.for_structure(s))
+                {
+                    gst::debug!(
+                        CAT,
+                        obj: element,
+                        "Speaker changed, prefixing with {}",
+                        prefix
+                    );
+                    self.state.lock().unwrap().pending_speaker_prefix = Some(prefix);
+                }
+
+                pad.event_default(Some(element), event)
+            }
+            EventView::FlushStop(_) => {
+                *self.state.lock().unwrap() = State::default();
+
+                pad.event_default(Some(element), event)
+            }
             EventView::Eos(_) => pad.event_default(Some(element), event),
             _ => pad.event_default(Some(element), event),
         }
@@ -216,6 +257,7 @@ impl ObjectSubclass for TtToJson {
             srcpad,
             sinkpad,
             settings: Mutex::new(Settings::default()),
+            state: Mutex::new(State::default()),
         }
     }
 }
@@ -223,14 +265,24 @@ impl ObjectSubclass for TtToJson {
 impl ObjectImpl for TtToJson {
     fn properties() -> &'static [glib::ParamSpec] {
         static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
-            vec![glib::ParamSpecEnum::new(
-                "mode",
-                "Mode",
-                "Which mode to operate in",
-                Cea608Mode::static_type(),
-                DEFAULT_MODE as i32,
-                glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
-            )]
+            vec![
+                glib::ParamSpecEnum::new(
+                    "mode",
+                    "Mode",
+                    "Which mode to operate in",
+                    Cea608Mode::static_type(),
+                    DEFAULT_MODE as i32,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_READY,
+                ),
+                glib::ParamSpecEnum::new(
+                    "speaker-prefix",
+                    "Speaker prefix",
+                    "How to mark speaker changes signalled by the transcriber",
+                    SpeakerPrefix::static_type(),
+                    DEFAULT_SPEAKER_PREFIX as i32,
+                    glib::ParamFlags::READWRITE | gst::PARAM_FLAG_MUTABLE_PLAYING,
+                ),
+            ]
         });
 
         PROPERTIES.as_ref()
@@ -255,6 +307,11 @@ impl ObjectImpl for TtToJson {
                 let mut settings = self.settings.lock().unwrap();
                 settings.mode = value.get::<Cea608Mode>().expect("type checked upstream");
             }
+            "speaker-prefix" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.speaker_prefix =
+                    value.get::<SpeakerPrefix>().expect("type checked upstream");
+            }
             _ => unimplemented!(),
         }
     }
@@ -265,6 +322,10 @@ impl ObjectImpl for TtToJson {
                 let settings = self.settings.lock().unwrap();
                 settings.mode.to_value()
             }
+            "speaker-prefix" => {
+                let settings = self.settings.lock().unwrap();
+                settings.speaker_prefix.to_value()
+            }
             _ => unimplemented!(),
         }
     }
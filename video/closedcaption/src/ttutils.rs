@@ -22,6 +22,39 @@ pub enum Cea608Mode {
     RollUp4,
 }
 
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, glib::Enum)]
+#[repr(u32)]
+#[enum_type(name = "GstTtSpeakerPrefix")]
+pub enum SpeakerPrefix {
+    #[enum_value(name = "None: don't mark speaker changes", nick = "none")]
+    None,
+    #[enum_value(name = "Chevrons: start new speakers with >>", nick = "chevrons")]
+    Chevrons,
+    #[enum_value(
+        name = "Label: start new speakers with their label, e.g. spk_0:",
+        nick = "label"
+    )]
+    Label,
+}
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, glib::Enum)]
+#[repr(u32)]
+#[enum_type(name = "GstCea608Channel")]
+pub enum Cea608Channel {
+    #[enum_value(name = "CC1: primary captions, field 1", nick = "cc1")]
+    Cc1,
+    #[enum_value(name = "CC2: field 1, data channel 2", nick = "cc2")]
+    Cc2,
+    #[enum_value(name = "CC3: secondary captions, field 2", nick = "cc3")]
+    Cc3,
+    #[enum_value(name = "CC4: field 2, data channel 2", nick = "cc4")]
+    Cc4,
+}
+
+/* Name of the custom downstream event sent by transcribers to announce
+ * a change of speaker or channel */
+pub const SPEAKER_EVENT_NAME: &str = "transcription/speaker";
+
 #[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
 pub enum TextStyle {
     White,
@@ -80,3 +113,49 @@ impl Cea608Mode {
         *self == Cea608Mode::RollUp2 || *self == Cea608Mode::RollUp3 || *self == Cea608Mode::RollUp4
     }
 }
+
+impl Cea608Channel {
+    /// The field carrying this channel, 1 or 2
+    pub fn field(&self) -> u8 {
+        match self {
+            Cea608Channel::Cc1 | Cea608Channel::Cc2 => 1,
+            Cea608Channel::Cc3 | Cea608Channel::Cc4 => 2,
+        }
+    }
+
+    /// The data channel within the field, as signalled by the
+    /// channel bit of control codes: 0 or 1
+    pub fn data_channel(&self) -> i32 {
+        match self {
+            Cea608Channel::Cc1 | Cea608Channel::Cc3 => 0,
+            Cea608Channel::Cc2 | Cea608Channel::Cc4 => 1,
+        }
+    }
+}
+
+impl SpeakerPrefix {
+    /// Returns the text to prepend to the first caption following the
+    /// speaker change announced by the custom event structure `s`, if any
+    pub fn for_structure(&self, s: &gst::StructureRef) -> Option<String> {
+        if s.name() != SPEAKER_EVENT_NAME {
+            return None;
+        }
+
+        match self {
+            SpeakerPrefix::None => None,
+            SpeakerPrefix::Chevrons => Some(">> ".to_string()),
+            SpeakerPrefix::Label => {
+                let label = s
+                    .get::<Option<String>>("speaker")
+                    .ok()
+                    .flatten()
+                    .or_else(|| s.get::<Option<String>>("channel").ok().flatten());
+
+                match label {
+                    Some(label) => Some(format!("{}: ", label)),
+                    None => Some(">> ".to_string()),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,446 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Extended Data Services, CEA-608-E section 9
+//!
+//! XDS packets are carried in field 2, interleaved with the CC3 / CC4
+//! and Text3 / Text4 data. The packets are accumulated by libcaption,
+//! this module validates and interprets them.
+
+use super::ffi;
+use gst::glib;
+use std::mem;
+
+/* Name of the element messages posted for each XDS packet */
+pub const XDS_MESSAGE_NAME: &str = "cea608/xds";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum XdsClass {
+    Current,
+    Future,
+    Channel,
+    Miscellaneous,
+    PublicService,
+    Reserved,
+    Private,
+}
+
+impl XdsClass {
+    /* From the start code of the packet */
+    fn from_start_code(code: u8) -> Option<Self> {
+        match code {
+            0x01 => Some(XdsClass::Current),
+            0x03 => Some(XdsClass::Future),
+            0x05 => Some(XdsClass::Channel),
+            0x07 => Some(XdsClass::Miscellaneous),
+            0x09 => Some(XdsClass::PublicService),
+            0x0b => Some(XdsClass::Reserved),
+            0x0d => Some(XdsClass::Private),
+            _ => None,
+        }
+    }
+
+    pub fn nick(&self) -> &'static str {
+        match self {
+            XdsClass::Current => "current",
+            XdsClass::Future => "future",
+            XdsClass::Channel => "channel",
+            XdsClass::Miscellaneous => "miscellaneous",
+            XdsClass::PublicService => "public-service",
+            XdsClass::Reserved => "reserved",
+            XdsClass::Private => "private",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XdsPacket {
+    pub class: XdsClass,
+    pub type_: u8,
+    /// Without parity bits nor the trailing null byte of odd length packets
+    pub content: Vec<u8>,
+}
+
+fn xds_string(content: &[u8]) -> String {
+    content
+        .iter()
+        .filter(|b| (0x20..0x7f).contains(*b))
+        .map(|b| *b as char)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+impl XdsPacket {
+    /// Program Name (Title), 9.5.1.3
+    pub fn program_name(&self) -> Option<String> {
+        if matches!(self.class, XdsClass::Current | XdsClass::Future) && self.type_ == 0x03 {
+            Some(xds_string(&self.content))
+        } else {
+            None
+        }
+    }
+
+    /// Program Description, 9.5.1.10, returns the row and its text
+    pub fn program_description(&self) -> Option<(u8, String)> {
+        if matches!(self.class, XdsClass::Current | XdsClass::Future)
+            && (0x10..=0x17).contains(&self.type_)
+        {
+            Some((self.type_ - 0x10, xds_string(&self.content)))
+        } else {
+            None
+        }
+    }
+
+    /// Network Name (Affiliation), 9.5.2.1
+    pub fn network_name(&self) -> Option<String> {
+        if self.class == XdsClass::Channel && self.type_ == 0x01 {
+            Some(xds_string(&self.content))
+        } else {
+            None
+        }
+    }
+
+    /// Call Letters and Native Channel, 9.5.2.2
+    pub fn call_letters(&self) -> Option<String> {
+        if self.class == XdsClass::Channel && self.type_ == 0x02 {
+            Some(xds_string(&self.content))
+        } else {
+            None
+        }
+    }
+
+    /// Content Advisory, 9.5.1.5, e.g. "TV-PG D L" or "MPA PG-13"
+    pub fn content_advisory(&self) -> Option<String> {
+        if !matches!(self.class, XdsClass::Current | XdsClass::Future)
+            || self.type_ != 0x05
+            || self.content.len() < 2
+        {
+            return None;
+        }
+
+        let (c1, c2) = (self.content[0], self.content[1]);
+        let a = (c1 >> 3) & 0x03;
+        let d = c1 & 0x20 != 0;
+        let v = c2 & 0x20 != 0;
+        let s = c2 & 0x10 != 0;
+        let l = c2 & 0x08 != 0;
+
+        match a {
+            // MPA
+            0 | 2 => {
+                let rating = match c1 & 0x07 {
+                    0 => "N/A",
+                    1 => "G",
+                    2 => "PG",
+                    3 => "PG-13",
+                    4 => "R",
+                    5 => "NC-17",
+                    6 => "X",
+                    _ => "Not Rated",
+                };
+                Some(format!("MPA {}", rating))
+            }
+            // U.S. TV Parental Guidelines
+            1 => {
+                let g = c2 & 0x07;
+                let mut rating = match g {
+                    1 => "TV-Y",
+                    2 => "TV-Y7",
+                    3 => "TV-G",
+                    4 => "TV-PG",
+                    5 => "TV-14",
+                    6 => "TV-MA",
+                    _ => "None",
+                }
+                .to_string();
+
+                if g == 2 && v {
+                    rating.push_str(" FV");
+                } else if (4..=6).contains(&g) {
+                    for (flag, name) in [(d && g != 6, " D"), (l, " L"), (s, " S"), (v, " V")] {
+                        if flag {
+                            rating.push_str(name);
+                        }
+                    }
+                }
+
+                Some(rating)
+            }
+            // Canadian English and French, a2 is in place of D, a3 of L
+            _ => match (l, d, c2 & 0x07) {
+                (false, false, g) => Some(format!(
+                    "CA-EN {}",
+                    ["E", "C", "C8+", "G", "PG", "14+", "18+", "Invalid"][g as usize]
+                )),
+                (false, true, g) => Some(format!(
+                    "CA-FR {}",
+                    [
+                        "E", "G", "8 ans +", "13 ans +", "16 ans +", "18 ans +", "Invalid",
+                        "Invalid"
+                    ][g as usize]
+                )),
+                _ => None,
+            },
+        }
+    }
+
+    /// Describes the packet in the structure of an element message
+    pub fn to_structure(&self, pts: Option<gst::ClockTime>) -> gst::Structure {
+        let mut s = gst::Structure::builder(XDS_MESSAGE_NAME)
+            .field("class", self.class.nick())
+            .field("type", self.type_ as u32)
+            .field("content", glib::Bytes::from(&self.content))
+            .field("timestamp", pts)
+            .build();
+
+        if let Some(name) = self.program_name() {
+            s.set("program-name", name);
+        } else if let Some((row, description)) = self.program_description() {
+            s.set("description-row", row as u32);
+            s.set("description", description);
+        } else if let Some(rating) = self.content_advisory() {
+            s.set("rating", rating);
+        } else if let Some(name) = self.network_name() {
+            s.set("network-name", name);
+        } else if let Some(letters) = self.call_letters() {
+            s.set("call-letters", letters);
+        }
+
+        s
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum XdsStatus {
+    /// The byte pair is not part of an XDS packet
+    NotXds,
+    /// The byte pair was consumed, the packet is not complete yet
+    Partial,
+    Packet(XdsPacket),
+    /// The byte pair was consumed but the packet was invalid
+    Invalid,
+}
+
+pub struct XdsDecoder {
+    xds: ffi::xds_t,
+    /* Whether the byte pairs following belong to the packet,
+     * caption control codes suspend it until a continue code */
+    active: bool,
+}
+
+impl XdsDecoder {
+    pub fn new() -> Self {
+        unsafe {
+            let mut xds = mem::MaybeUninit::uninit();
+            ffi::xds_init(xds.as_mut_ptr());
+            Self {
+                xds: xds.assume_init(),
+                active: false,
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        unsafe {
+            ffi::xds_init(&mut self.xds);
+        }
+        self.active = false;
+    }
+
+    fn checksum_is_valid(&self) -> bool {
+        let sum = self.xds.content[..self.xds.size as usize].iter().fold(
+            self.xds.class_code as u32 + self.xds.type_ as u32 + 0x0f,
+            |sum, b| sum + *b as u32,
+        ) + self.xds.checksum as u32;
+
+        sum & 0x7f == 0
+    }
+
+    /// Decodes a field 2 byte pair, including parity bits
+    pub fn decode(&mut self, cc_data: u16) -> XdsStatus {
+        let b1 = ((cc_data >> 8) & 0x7f) as u8;
+
+        match b1 {
+            0x00 => XdsStatus::NotXds,
+            // Start codes
+            0x01 | 0x03 | 0x05 | 0x07 | 0x09 | 0x0b | 0x0d => {
+                self.reset();
+                unsafe {
+                    ffi::xds_decode(&mut self.xds, cc_data);
+                }
+                /* libcaption only keeps the low nibble of the type */
+                self.xds.type_ = (cc_data & 0x7f) as u8;
+                self.active = true;
+                XdsStatus::Partial
+            }
+            // Continue codes
+            0x02 | 0x04 | 0x06 | 0x08 | 0x0a | 0x0c | 0x0e => {
+                self.active = self.xds.state == 1
+                    && self.xds.class_code + 1 == b1
+                    && self.xds.type_ as u16 == cc_data & 0x7f;
+                XdsStatus::Partial
+            }
+            // End code
+            0x0f => {
+                if !self.active {
+                    return XdsStatus::Invalid;
+                }
+
+                self.active = false;
+
+                /* libcaption expects the parity bit on the end code */
+                let res = unsafe { ffi::xds_decode(&mut self.xds, 0x8f00 | (cc_data & 0x00ff)) };
+                if res != ffi::libcaption_stauts_t_LIBCAPTION_READY as i32 {
+                    return XdsStatus::Invalid;
+                }
+
+                let class = match XdsClass::from_start_code(self.xds.class_code) {
+                    Some(class) if self.checksum_is_valid() => class,
+                    _ => return XdsStatus::Invalid,
+                };
+
+                let mut content = self.xds.content[..self.xds.size as usize].to_vec();
+                if content.last() == Some(&0) {
+                    content.pop();
+                }
+
+                XdsStatus::Packet(XdsPacket {
+                    class,
+                    type_: self.xds.type_,
+                    content,
+                })
+            }
+            // Caption control codes
+            0x10..=0x1f => {
+                self.active = false;
+                XdsStatus::NotXds
+            }
+            _ => {
+                if !self.active {
+                    return XdsStatus::NotXds;
+                }
+
+                let res = unsafe { ffi::xds_decode(&mut self.xds, cc_data) };
+                if res == ffi::libcaption_stauts_t_LIBCAPTION_OK as i32 {
+                    XdsStatus::Partial
+                } else {
+                    self.active = false;
+                    XdsStatus::Invalid
+                }
+            }
+        }
+    }
+}
+
+impl Default for XdsDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_parity(b: u8) -> u8 {
+        if b.count_ones() % 2 == 0 {
+            b | 0x80
+        } else {
+            b
+        }
+    }
+
+    fn packet(class: u8, type_: u8, content: &[u8]) -> Vec<u16> {
+        let mut bytes = vec![class, type_];
+        bytes.extend_from_slice(content);
+        if content.len() % 2 == 1 {
+            bytes.push(0);
+        }
+        bytes.push(0x0f);
+
+        let sum = bytes.iter().map(|b| *b as u32).sum::<u32>();
+        bytes.push(((128 - sum % 128) % 128) as u8);
+
+        bytes
+            .chunks(2)
+            .map(|pair| (with_parity(pair[0]) as u16) << 8 | with_parity(pair[1]) as u16)
+            .collect()
+    }
+
+    fn decode_all(decoder: &mut XdsDecoder, pairs: &[u16]) -> Vec<XdsStatus> {
+        pairs
+            .iter()
+            .map(|pair| decoder.decode(*pair))
+            .filter(|status| *status != XdsStatus::Partial)
+            .collect()
+    }
+
+    #[test]
+    fn test_program_name() {
+        let mut decoder = XdsDecoder::new();
+
+        let statuses = decode_all(&mut decoder, &packet(0x01, 0x03, b"Nature"));
+        assert_eq!(statuses.len(), 1);
+
+        match &statuses[0] {
+            XdsStatus::Packet(packet) => {
+                assert_eq!(packet.class, XdsClass::Current);
+                assert_eq!(packet.program_name(), Some("Nature".to_string()));
+            }
+            status => panic!("Unexpected status {:?}", status),
+        }
+    }
+
+    #[test]
+    fn test_content_advisory() {
+        let mut decoder = XdsDecoder::new();
+
+        // U.S. TV, TV-PG with D and L
+        let statuses = decode_all(&mut decoder, &packet(0x01, 0x05, &[0x68, 0x4c]));
+
+        match &statuses[0] {
+            XdsStatus::Packet(packet) => {
+                assert_eq!(packet.content_advisory(), Some("TV-PG D L".to_string()));
+            }
+            status => panic!("Unexpected status {:?}", status),
+        }
+    }
+
+    #[test]
+    fn test_invalid_checksum() {
+        let mut decoder = XdsDecoder::new();
+
+        let mut pairs = packet(0x05, 0x01, b"PBS");
+        *pairs.last_mut().unwrap() ^= 0x0001;
+
+        assert_eq!(decode_all(&mut decoder, &pairs), vec![XdsStatus::Invalid]);
+    }
+
+    #[test]
+    fn test_interrupted() {
+        let mut decoder = XdsDecoder::new();
+
+        let pairs = packet(0x05, 0x02, b"WGBH02");
+
+        let mut statuses = decode_all(&mut decoder, &pairs[..2]);
+        /* A caption control code, and text for CC3 */
+        statuses.extend(decode_all(&mut decoder, &[0x1520, 0xc1c2]));
+        /* Continue code */
+        statuses.extend(decode_all(&mut decoder, &[0x0602]));
+        statuses.extend(decode_all(&mut decoder, &pairs[2..]));
+
+        assert_eq!(statuses[..2], [XdsStatus::NotXds, XdsStatus::NotXds]);
+        match &statuses[2] {
+            XdsStatus::Packet(packet) => {
+                assert_eq!(packet.call_letters(), Some("WGBH02".to_string()));
+            }
+            status => panic!("Unexpected status {:?}", status),
+        }
+    }
+}
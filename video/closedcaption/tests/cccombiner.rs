@@ -0,0 +1,242 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use gst::prelude::*;
+use gst_video::VideoCaptionType;
+use pretty_assertions::assert_eq;
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstrsclosedcaption::plugin_register_static().unwrap();
+    });
+}
+
+fn buffer(data: &[u8], pts: gst::ClockTime, duration: gst::ClockTime) -> gst::Buffer {
+    let mut buf = gst::Buffer::from_mut_slice(data.to_vec());
+    {
+        let buf = buf.get_mut().unwrap();
+        buf.set_pts(pts);
+        buf.set_duration(duration);
+    }
+
+    buf
+}
+
+fn captions(buf: &gst::Buffer) -> Vec<(VideoCaptionType, Vec<u8>)> {
+    buf.iter_meta::<gst_video::VideoCaptionMeta>()
+        .map(|meta| (meta.caption_type(), meta.data().to_vec()))
+        .collect()
+}
+
+#[test]
+fn test_combine() {
+    init();
+
+    let mut h = gst_check::Harness::with_padnames("rscccombiner", Some("sink"), Some("src"));
+    // Allow queueing more than a single buffer per pad
+    h.element()
+        .unwrap()
+        .set_property("latency", gst::ClockTime::SECOND);
+    let mut h_caption =
+        gst_check::Harness::with_element(&h.element().unwrap(), Some("caption"), None);
+
+    h.set_src_caps_str("video/x-raw,framerate=30/1");
+    h_caption.set_src_caps_str("closedcaption/x-cea-608,format=raw,framerate=30/1");
+
+    let frame_duration = gst::ClockTime::from_nseconds(33_333_333);
+
+    assert_eq!(
+        h_caption.push(buffer(&[0x94, 0x20], gst::ClockTime::ZERO, frame_duration)),
+        Ok(gst::FlowSuccess::Ok)
+    );
+    assert_eq!(
+        h.push(buffer(&[0; 16], gst::ClockTime::ZERO, frame_duration)),
+        Ok(gst::FlowSuccess::Ok)
+    );
+    // The next caption lets the combiner know that the first frame is complete
+    assert_eq!(
+        h_caption.push(buffer(&[0x94, 0x2c], frame_duration, frame_duration)),
+        Ok(gst::FlowSuccess::Ok)
+    );
+
+    let outbuf = h.pull().unwrap();
+    assert_eq!(outbuf.pts(), Some(gst::ClockTime::ZERO));
+    assert_eq!(
+        captions(&outbuf),
+        vec![(VideoCaptionType::Cea608Raw, vec![0x94, 0x20])]
+    );
+
+    assert_eq!(
+        h.push(buffer(&[0; 16], frame_duration, frame_duration)),
+        Ok(gst::FlowSuccess::Ok)
+    );
+    h_caption.push_event(gst::event::Eos::new());
+
+    let outbuf = h.pull().unwrap();
+    assert_eq!(outbuf.pts(), Some(frame_duration));
+    assert_eq!(
+        captions(&outbuf),
+        vec![(VideoCaptionType::Cea608Raw, vec![0x94, 0x2c])]
+    );
+
+    // Without caption data the video is passed through as is
+    assert_eq!(
+        h.push(buffer(&[0; 16], 2 * frame_duration, frame_duration)),
+        Ok(gst::FlowSuccess::Ok)
+    );
+
+    let outbuf = h.pull().unwrap();
+    assert_eq!(outbuf.pts(), Some(2 * frame_duration));
+    assert!(captions(&outbuf).is_empty());
+
+    assert_eq!(
+        h.srcpad().unwrap().peer().unwrap().current_caps().unwrap(),
+        gst::Caps::builder("video/x-raw")
+            .field("framerate", gst::Fraction::new(30, 1))
+            .build()
+    );
+}
+
+#[test]
+fn test_convert_cdp() {
+    init();
+
+    let mut h = gst_check::Harness::with_padnames("rscccombiner", Some("sink"), Some("src"));
+    // Allow queueing more than a single buffer per pad
+    h.element()
+        .unwrap()
+        .set_property("latency", gst::ClockTime::SECOND);
+    h.element()
+        .unwrap()
+        .set_property("caption-type", VideoCaptionType::Cea708Cdp);
+    let mut h_caption =
+        gst_check::Harness::with_element(&h.element().unwrap(), Some("caption"), None);
+
+    h.set_src_caps_str("video/x-raw,framerate=30000/1001");
+    h_caption.set_src_caps_str("closedcaption/x-cea-608,format=s334-1a,framerate=30000/1001");
+
+    let frame_duration = gst::ClockTime::from_nseconds(33_366_667);
+
+    assert_eq!(
+        h_caption.push(buffer(
+            &[0x80, 0x94, 0x20],
+            gst::ClockTime::ZERO,
+            frame_duration
+        )),
+        Ok(gst::FlowSuccess::Ok)
+    );
+    h_caption.push_event(gst::event::Eos::new());
+    assert_eq!(
+        h.push(buffer(&[0; 16], gst::ClockTime::ZERO, frame_duration)),
+        Ok(gst::FlowSuccess::Ok)
+    );
+
+    let outbuf = h.pull().unwrap();
+    let captions = captions(&outbuf);
+    assert_eq!(captions.len(), 1);
+
+    let (caption_type, cdp) = &captions[0];
+    assert_eq!(*caption_type, VideoCaptionType::Cea708Cdp);
+
+    /* 20 cc_data triplets at 29.97 fps */
+    assert_eq!(cdp.len(), 73);
+    assert_eq!(&cdp[..5], &[0x96, 0x69, 73, 0x4f, 0x43]);
+    assert_eq!(&cdp[7..9], &[0x72, 0xf4]);
+    /* field 1 data, then field 2 and CEA-708 padding */
+    assert_eq!(
+        &cdp[9..18],
+        &[0xfc, 0x94, 0x20, 0xf9, 0x80, 0x80, 0xfa, 0x00, 0x00]
+    );
+    assert_eq!(cdp.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)), 0);
+}
+
+#[test]
+fn test_insert_sei() {
+    init();
+
+    let mut h = gst_check::Harness::with_padnames("rscccombiner", Some("sink"), Some("src"));
+    // Allow queueing more than a single buffer per pad
+    h.element()
+        .unwrap()
+        .set_property("latency", gst::ClockTime::SECOND);
+    h.element().unwrap().set_property("insert-sei", true);
+    let mut h_caption =
+        gst_check::Harness::with_element(&h.element().unwrap(), Some("caption"), None);
+
+    h.set_src_caps_str("video/x-h264,stream-format=byte-stream,alignment=au,framerate=30/1");
+    h_caption.set_src_caps_str("closedcaption/x-cea-708,format=cc_data,framerate=30/1");
+
+    let frame_duration = gst::ClockTime::from_nseconds(33_333_333);
+
+    // Access unit delimiter and an IDR slice
+    let au = [
+        0x00, 0x00, 0x00, 0x01, 0x09, 0xf0, 0x00, 0x00, 0x01, 0x65, 0x88, 0x84, 0x00, 0x00, 0x03,
+        0x01, 0x20, 0x40,
+    ];
+
+    assert_eq!(
+        h_caption.push(buffer(
+            &[0xfc, 0x94, 0x20, 0xf9, 0x80, 0x80],
+            gst::ClockTime::ZERO,
+            frame_duration
+        )),
+        Ok(gst::FlowSuccess::Ok)
+    );
+    h_caption.push_event(gst::event::Eos::new());
+    assert_eq!(
+        h.push(buffer(&au, gst::ClockTime::ZERO, frame_duration)),
+        Ok(gst::FlowSuccess::Ok)
+    );
+
+    let outbuf = h.pull().unwrap();
+    assert!(captions(&outbuf).is_empty());
+    assert_eq!(outbuf.pts(), Some(gst::ClockTime::ZERO));
+    assert_eq!(
+        outbuf.map_readable().unwrap().as_slice(),
+        &[
+            0x00, 0x00, 0x00, 0x01, 0x09, 0xf0, 0x00, 0x00, 0x00, 0x01, 0x06, 0x04, 0x11, 0xb5,
+            0x00, 0x31, b'G', b'A', b'9', b'4', 0x03, 0x42, 0xff, 0xfc, 0x94, 0x20, 0xf9, 0x80,
+            0x80, 0xff, 0x80, 0x00, 0x00, 0x01, 0x65, 0x88, 0x84, 0x00, 0x00, 0x03, 0x01, 0x20,
+            0x40,
+        ][..]
+    );
+}
+
+#[test]
+fn test_caption_caps_framerate() {
+    init();
+
+    let mut h = gst_check::Harness::with_padnames("rscccombiner", Some("sink"), Some("src"));
+    let _h_caption = gst_check::Harness::with_element(&h.element().unwrap(), Some("caption"), None);
+    let caption_pad = h.element().unwrap().static_pad("caption").unwrap();
+
+    h.set_src_caps_str("video/x-raw,framerate=25/1");
+
+    // Captions are requested at the video framerate
+    let caps = caption_pad.query_caps(None);
+    assert!(!caps.is_empty());
+    for s in caps.iter() {
+        assert_eq!(
+            s.get::<gst::Fraction>("framerate").unwrap(),
+            gst::Fraction::new(25, 1)
+        );
+    }
+
+    let caption_caps = gst::Caps::builder("closedcaption/x-cea-608")
+        .field("format", "raw")
+        .field("framerate", gst::Fraction::new(30000, 1001))
+        .build();
+    assert!(!h_caption
+        .sinkpad()
+        .unwrap()
+        .query_accept_caps(&caption_caps));
+}
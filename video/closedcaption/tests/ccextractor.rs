@@ -0,0 +1,207 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use gst::prelude::*;
+use gst::EventView;
+use gst_video::VideoCaptionType;
+use pretty_assertions::assert_eq;
+
+use std::sync::{Arc, Mutex};
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstrsclosedcaption::plugin_register_static().unwrap();
+    });
+}
+
+#[derive(Default)]
+struct Collected {
+    buffers: Vec<gst::Buffer>,
+    events: Vec<gst::Event>,
+}
+
+/// Links the caption pad to a pad collecting buffers and events once it is added
+fn collect_captions(element: &gst::Element) -> Arc<Mutex<Collected>> {
+    let collected = Arc::new(Mutex::new(Collected::default()));
+
+    let collected_clone = collected.clone();
+    let collected_clone2 = collected.clone();
+    let sinkpad = gst::Pad::builder(Some("sink"), gst::PadDirection::Sink)
+        .chain_function(move |_pad, _parent, buffer| {
+            collected_clone.lock().unwrap().buffers.push(buffer);
+            Ok(gst::FlowSuccess::Ok)
+        })
+        .event_function(move |_pad, _parent, event| {
+            collected_clone2.lock().unwrap().events.push(event);
+            true
+        })
+        .build();
+    sinkpad.set_active(true).unwrap();
+
+    element.connect_pad_added(move |_element, pad| {
+        if pad.name() == "caption" {
+            pad.link(&sinkpad).unwrap();
+        }
+    });
+
+    collected
+}
+
+fn video_buffer(data: &[u8], pts: gst::ClockTime, caption: Option<&[u8]>) -> gst::Buffer {
+    let mut buf = gst::Buffer::from_mut_slice(data.to_vec());
+    {
+        let buf = buf.get_mut().unwrap();
+        buf.set_pts(pts);
+        buf.set_duration(gst::ClockTime::from_nseconds(33_333_333));
+        if let Some(caption) = caption {
+            gst_video::VideoCaptionMeta::add(buf, VideoCaptionType::Cea608Raw, caption);
+        }
+    }
+
+    buf
+}
+
+#[test]
+fn test_extract_meta() {
+    init();
+
+    let mut h = gst_check::Harness::new("rsccextractor");
+    let collected = collect_captions(&h.element().unwrap());
+    h.set_src_caps_str("video/x-raw,framerate=30/1");
+
+    let frame_duration = gst::ClockTime::from_nseconds(33_333_333);
+    for (i, caption) in [Some([0x94, 0x20]), None, Some([0x94, 0x2c])]
+        .iter()
+        .enumerate()
+    {
+        let buf = video_buffer(
+            &[0; 16],
+            i as u64 * frame_duration,
+            caption.as_ref().map(|c| &c[..]),
+        );
+        assert_eq!(h.push(buf), Ok(gst::FlowSuccess::Ok));
+
+        // Video is passed through unchanged
+        let outbuf = h.pull().unwrap();
+        assert_eq!(outbuf.pts(), Some(i as u64 * frame_duration));
+        assert_eq!(
+            outbuf.meta::<gst_video::VideoCaptionMeta>().is_some(),
+            caption.is_some()
+        );
+    }
+
+    let collected = collected.lock().unwrap();
+
+    let captions = collected
+        .buffers
+        .iter()
+        .map(|buf| (buf.pts().unwrap(), buf.map_readable().unwrap().to_vec()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        captions,
+        vec![
+            (gst::ClockTime::ZERO, vec![0x94, 0x20]),
+            (2 * frame_duration, vec![0x94, 0x2c]),
+        ]
+    );
+
+    let caps = collected
+        .events
+        .iter()
+        .find_map(|event| match event.view() {
+            EventView::Caps(c) => Some(c.caps_owned()),
+            _ => None,
+        })
+        .expect("No caps event");
+    assert_eq!(
+        caps,
+        gst::Caps::builder("closedcaption/x-cea-608")
+            .field("format", "raw")
+            .field("framerate", gst::Fraction::new(30, 1))
+            .build()
+    );
+
+    // The frame without captions is signalled with a gap event
+    let gaps = collected
+        .events
+        .iter()
+        .filter_map(|event| match event.view() {
+            EventView::Gap(gap) => Some(gap.get()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(gaps, vec![(frame_duration, Some(frame_duration))]);
+}
+
+#[test]
+fn test_remove_caption_meta() {
+    init();
+
+    let mut h = gst_check::Harness::new("rsccextractor");
+    h.element()
+        .unwrap()
+        .set_property("remove-caption-meta", true);
+    let collected = collect_captions(&h.element().unwrap());
+    h.set_src_caps_str("video/x-raw,framerate=30/1");
+
+    let buf = video_buffer(&[0; 16], gst::ClockTime::ZERO, Some(&[0x94, 0x20]));
+    assert_eq!(h.push(buf), Ok(gst::FlowSuccess::Ok));
+
+    let outbuf = h.pull().unwrap();
+    assert!(outbuf.meta::<gst_video::VideoCaptionMeta>().is_none());
+
+    assert_eq!(collected.lock().unwrap().buffers.len(), 1);
+}
+
+#[test]
+fn test_extract_sei() {
+    init();
+
+    let mut h = gst_check::Harness::new("rsccextractor");
+    let collected = collect_captions(&h.element().unwrap());
+    h.set_src_caps_str("video/x-h264,stream-format=byte-stream,alignment=au,framerate=30/1");
+
+    // Access unit delimiter, A/53 SEI and an IDR slice
+    let au = [
+        0x00, 0x00, 0x00, 0x01, 0x09, 0xf0, 0x00, 0x00, 0x00, 0x01, 0x06, 0x04, 0x11, 0xb5, 0x00,
+        0x31, b'G', b'A', b'9', b'4', 0x03, 0x42, 0xff, 0xfc, 0x94, 0x20, 0xf9, 0x80, 0x80, 0xff,
+        0x80, 0x00, 0x00, 0x01, 0x65, 0x88, 0x84, 0x00, 0x00, 0x03, 0x01, 0x20, 0x40,
+    ];
+
+    let buf = video_buffer(&au, gst::ClockTime::ZERO, None);
+    assert_eq!(h.push(buf), Ok(gst::FlowSuccess::Ok));
+    let outbuf = h.pull().unwrap();
+    assert_eq!(outbuf.map_readable().unwrap().as_slice(), &au[..]);
+
+    let collected = collected.lock().unwrap();
+    assert_eq!(collected.buffers.len(), 1);
+    assert_eq!(
+        collected.buffers[0].map_readable().unwrap().as_slice(),
+        &[0xfc, 0x94, 0x20, 0xf9, 0x80, 0x80]
+    );
+
+    let caps = collected
+        .events
+        .iter()
+        .find_map(|event| match event.view() {
+            EventView::Caps(c) => Some(c.caps_owned()),
+            _ => None,
+        })
+        .expect("No caps event");
+    assert_eq!(
+        caps,
+        gst::Caps::builder("closedcaption/x-cea-708")
+            .field("format", "cc_data")
+            .field("framerate", gst::Fraction::new(30, 1))
+            .build()
+    );
+}
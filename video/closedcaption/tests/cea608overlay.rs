@@ -0,0 +1,138 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use gst::prelude::*;
+use gst_video::VideoCaptionType;
+
+use pretty_assertions::assert_eq;
+
+const WIDTH: i32 = 1280;
+const HEIGHT: i32 = 720;
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstrsclosedcaption::plugin_register_static().unwrap();
+    });
+}
+
+fn new_harness(service_number: i32) -> gst_check::Harness {
+    let mut h = gst_check::Harness::new("cea608overlay");
+    h.element()
+        .unwrap()
+        .set_property("service-number", service_number);
+
+    // Upstream supports the overlay composition meta, so the overlay is attached
+    // to the buffers instead of blended and its placement can be checked
+    let caps = format!(
+        "video/x-raw(meta:GstVideoOverlayComposition),format=BGRA,width={},height={},framerate=30/1",
+        WIDTH, HEIGHT
+    );
+    h.set_caps_str(&caps, &caps);
+
+    h
+}
+
+/* Pushes a video frame with the caption and returns the rectangle of the
+ * rendered overlay as (x, y, width, height) */
+fn push_caption(
+    h: &mut gst_check::Harness,
+    caption_type: VideoCaptionType,
+    caption: &[u8],
+) -> (i32, i32, u32, u32) {
+    let mut buf = gst::Buffer::with_size((WIDTH * HEIGHT * 4) as usize).unwrap();
+    {
+        let buf = buf.get_mut().unwrap();
+        buf.set_pts(gst::ClockTime::ZERO);
+        gst_video::VideoCaptionMeta::add(buf, caption_type, caption);
+    }
+
+    assert_eq!(h.push(buf), Ok(gst::FlowSuccess::Ok));
+
+    let outbuf = h.pull().unwrap();
+    let meta = outbuf
+        .meta::<gst_video::VideoOverlayCompositionMeta>()
+        .expect("No overlay rendered");
+    let composition = meta.overlay();
+    assert_eq!(composition.n_rectangles(), 1);
+
+    composition.rectangle(0).unwrap().render_rectangle()
+}
+
+/* Sets the odd parity bit of a CEA-608 byte */
+fn with_parity(byte: u8) -> u8 {
+    if (byte & 0x7f).count_ones() % 2 == 0 {
+        byte | 0x80
+    } else {
+        byte & 0x7f
+    }
+}
+
+#[test]
+fn test_cea608_placement() {
+    init();
+
+    let mut h = new_harness(-1);
+
+    // Pop-on caption: RCL, PAC row 15 column 0 white, "HELLO!", EOC
+    let caption = [
+        0x14, 0x20, 0x14, 0x60, b'H', b'E', b'L', b'L', b'O', b'!', 0x14, 0x2f,
+    ]
+    .iter()
+    .map(|byte| with_parity(*byte))
+    .collect::<Vec<u8>>();
+
+    let (x, y, width, height) = push_caption(&mut h, VideoCaptionType::Cea608Raw, &caption);
+
+    // The overlay covers exactly the 6 cells of the text
+    assert!(width > 0 && width % 6 == 0);
+    assert!(height > 0);
+    let cell_width = (width / 6) as i32;
+    let cell_height = height as i32;
+
+    // The 32 x 15 grid fits in the safe title area and is centered in the frame
+    assert!(32 * cell_width <= WIDTH * 8 / 10);
+    assert!(15 * cell_height <= HEIGHT * 8 / 10);
+    assert_eq!(x, (WIDTH - 32 * cell_width) / 2);
+    assert_eq!(y, (HEIGHT - 15 * cell_height) / 2 + 14 * cell_height);
+}
+
+#[test]
+fn test_cea708_placement() {
+    init();
+
+    let mut h = new_harness(1);
+
+    // DefineWindow 0: visible, anchored by its bottom center at the bottom center
+    // of the safe title area, 1 row of 10 columns, window & pen style 1, then "HELLO!"
+    let mut block = vec![0x98, 0x20, 0x80 | 100, 50, 0x70, 0x09, 0x09];
+    block.extend_from_slice(b"HELLO!");
+    let mut packet = vec![0x00, (1 << 5) | block.len() as u8];
+    packet.extend_from_slice(&block);
+    packet[0] = ((packet.len() + 1) / 2) as u8;
+    packet.resize(packet[0] as usize * 2, 0);
+
+    let mut cc_data = vec![];
+    for (idx, pair) in packet.chunks(2).enumerate() {
+        cc_data.extend_from_slice(&[if idx == 0 { 0xff } else { 0xfe }, pair[0], pair[1]]);
+    }
+
+    let (x, y, width, height) = push_caption(&mut h, VideoCaptionType::Cea708Raw, &cc_data);
+
+    // The opaque window fill covers the 10 columns of the window
+    assert!(width > 0 && width % 10 == 0);
+    assert!(height > 0);
+    let (width, height) = (width as i32, height as i32);
+
+    // Safe title area: (128, 72) 1024 x 576
+    assert_eq!(x, 128 + 1024 / 2 - width / 2);
+    assert_eq!(y, 72 + 576 - height);
+}
@@ -0,0 +1,173 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use gst::prelude::*;
+use gst::ClockTime;
+use pretty_assertions::assert_eq;
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstrsclosedcaption::plugin_register_static().unwrap();
+    });
+}
+
+fn with_parity(b: u8) -> u8 {
+    if b.count_ones() % 2 == 0 {
+        b | 0x80
+    } else {
+        b
+    }
+}
+
+fn new_timed_buffer<T: AsRef<[u8]> + Send + 'static>(slice: T, frame_no: u64) -> gst::Buffer {
+    let frame_duration = ClockTime::SECOND.mul_div_round(1001, 30000).unwrap();
+
+    let mut buf = gst::Buffer::from_slice(slice);
+    let buf_ref = buf.get_mut().unwrap();
+    buf_ref.set_pts(frame_duration * frame_no);
+    buf_ref.set_duration(frame_duration);
+    buf
+}
+
+/* Concatenates the text of each line of a JSON buffer */
+fn lines_text(buf: &gst::Buffer) -> Vec<String> {
+    let map = buf.map_readable().unwrap();
+    let lines: serde_json::Value = serde_json::from_slice(map.as_slice()).unwrap();
+
+    lines["lines"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|line| {
+            line["chunks"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|chunk| chunk["text"].as_str().unwrap())
+                .collect::<String>()
+        })
+        .collect()
+}
+
+/* CC1 and CC2 pop-on captions, interleaved */
+const INTERLEAVED: [[u8; 2]; 9] = [
+    // resume_caption_loading, CC1 then CC2
+    [0x94, 0x20],
+    [0x1c, 0x20],
+    // preamble, row 15, CC1
+    [0x94, 0x40],
+    // "No", basic characters follow the last channel
+    [0xce, 0xef],
+    // preamble, row 15, CC2
+    [0x1c, 0x40],
+    // "Hi"
+    [0xc8, 0xe9],
+    // end_of_caption, CC1 then CC2
+    [0x94, 0x2f],
+    [0x1c, 0x2f],
+    [0x80, 0x80],
+];
+
+fn decode_interleaved(channel: &str) -> Vec<String> {
+    let mut h = gst_check::Harness::new_parse(&format!("cea608tojson channel={}", channel));
+    h.set_src_caps_str("closedcaption/x-cea-608,format=raw");
+
+    for (frame_no, cc_data) in INTERLEAVED.iter().enumerate() {
+        let inbuf = new_timed_buffer(*cc_data, frame_no as u64);
+        assert_eq!(h.push(inbuf), Ok(gst::FlowSuccess::Ok));
+    }
+
+    h.push_event(gst::event::Eos::new());
+
+    let mut texts = vec![];
+    while h.buffers_in_queue() != 0 {
+        texts.extend(lines_text(&h.pull().unwrap()));
+    }
+
+    texts
+}
+
+#[test]
+fn test_channel_selection() {
+    init();
+
+    assert_eq!(decode_interleaved("cc1"), vec!["No".to_string()]);
+    assert_eq!(decode_interleaved("cc2"), vec!["Hi".to_string()]);
+}
+
+/* Builds the s334-1a triplets of an XDS packet, in field 2 */
+fn xds_packet(class: u8, type_: u8, content: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![class, type_];
+    bytes.extend_from_slice(content);
+    if content.len() % 2 == 1 {
+        bytes.push(0);
+    }
+    bytes.push(0x0f);
+
+    let sum = bytes.iter().map(|b| *b as u32).sum::<u32>();
+    bytes.push(((128 - sum % 128) % 128) as u8);
+
+    bytes
+        .chunks(2)
+        .flat_map(|pair| [0x00, with_parity(pair[0]), with_parity(pair[1])])
+        .collect()
+}
+
+#[test]
+fn test_xds() {
+    init();
+
+    let mut h = gst_check::Harness::new_parse("cea608tojson");
+    h.set_src_caps_str("closedcaption/x-cea-608,format=s334-1a");
+
+    let bus = gst::Bus::new();
+    h.element().unwrap().set_bus(Some(&bus));
+
+    /* Program name, with field 1 padding */
+    let mut data = vec![0x80, 0x80, 0x80];
+    data.extend(xds_packet(0x01, 0x03, b"Nature"));
+
+    let inbuf = new_timed_buffer(data, 0);
+    assert_eq!(h.push(inbuf), Ok(gst::FlowSuccess::Ok));
+
+    let msg = bus
+        .pop_filtered(&[gst::MessageType::Element])
+        .expect("no XDS message");
+    let s = msg.structure().unwrap();
+    assert_eq!(s.name(), "cea608/xds");
+    assert_eq!(s.get::<&str>("class").unwrap(), "current");
+    assert_eq!(s.get::<u32>("type").unwrap(), 0x03);
+    assert_eq!(s.get::<&str>("program-name").unwrap(), "Nature");
+
+    let mut title = None;
+    while h.events_in_queue() != 0 {
+        if let gst::EventView::Tag(ev) = h.pull_event().unwrap().view() {
+            title = ev
+                .tag()
+                .get::<gst::tags::Title>()
+                .map(|title| title.get().to_string());
+        }
+    }
+    assert_eq!(title.as_deref(), Some("Nature"));
+
+    /* Repeated packets don't update the tags again */
+    let inbuf = new_timed_buffer(xds_packet(0x01, 0x03, b"Nature"), 1);
+    assert_eq!(h.push(inbuf), Ok(gst::FlowSuccess::Ok));
+
+    assert!(bus.pop_filtered(&[gst::MessageType::Element]).is_some());
+    while h.events_in_queue() != 0 {
+        assert!(!matches!(
+            h.pull_event().unwrap().view(),
+            gst::EventView::Tag(..)
+        ));
+    }
+}
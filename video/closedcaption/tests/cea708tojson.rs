@@ -0,0 +1,112 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#![allow(clippy::single_match)]
+
+use gst::prelude::*;
+use gst::EventView;
+use pretty_assertions::assert_eq;
+use std::path::PathBuf;
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstrsclosedcaption::plugin_register_static().expect("cea708tojson test");
+    });
+}
+
+/* Concatenates the text of each line of a JSON buffer */
+fn lines_text(buf: &gst::Buffer) -> Vec<String> {
+    let map = buf.map_readable().unwrap();
+    let lines: serde_json::Value = serde_json::from_slice(map.as_slice()).unwrap();
+
+    lines["lines"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|line| {
+            line["chunks"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|chunk| chunk["text"].as_str().unwrap())
+                .collect::<String>()
+        })
+        .collect()
+}
+
+#[test]
+fn test_decode() {
+    init();
+
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/captions-test_708.mcc");
+
+    let mut h = gst_check::Harness::new_parse(&format!(
+        "filesrc location={:?} ! mccparse ! cea708tojson",
+        path
+    ));
+
+    h.play();
+
+    let mut outputs = vec![];
+    loop {
+        let mut done = false;
+
+        while h.events_in_queue() != 0 {
+            let event = h.pull_event();
+
+            if let Ok(event) = event {
+                match event.view() {
+                    EventView::Caps(ev) => {
+                        let s = ev.caps().structure(0).unwrap();
+                        assert_eq!(s.name(), "application/x-json");
+                        assert_eq!(s.get::<&str>("format").unwrap(), "cea708");
+                    }
+                    EventView::Eos(_) => {
+                        done = true;
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        while h.buffers_in_queue() != 0 {
+            let buf = h.pull().unwrap();
+            assert!(buf.pts().is_some());
+            outputs.push(lines_text(&buf));
+        }
+
+        if done {
+            break;
+        }
+    }
+
+    let texts = outputs.concat();
+
+    for expected in [
+        "These are 708 captions",
+        "(top left)",
+        "(middle)",
+        "(bottom left)",
+    ] {
+        assert!(
+            texts.iter().any(|text| text.contains(expected)),
+            "{:?} not found in {:?}",
+            expected,
+            texts
+        );
+    }
+
+    /* The captions are eventually all hidden */
+    assert_eq!(outputs.last(), Some(&vec![]));
+}
@@ -202,3 +202,58 @@ fn test_pull() {
         }
     }
 }
+
+/// Lines with an invalid checksum are dropped with a warning
+#[test]
+fn test_checksum() {
+    init();
+
+    let data = std::str::from_utf8(include_bytes!("captions-test_708.mcc")).unwrap();
+    let data = data
+        .lines()
+        .map(|line| {
+            if line.starts_with("00:00:05:00\t") {
+                /* The ANC checksum is the last byte */
+                assert!(line.ends_with("AB"));
+                format!("{}AC\n", &line[..line.len() - 2])
+            } else {
+                format!("{}\n", line)
+            }
+        })
+        .collect::<String>();
+
+    let mut h = gst_check::Harness::new("mccparse");
+    h.set_src_caps_str("application/x-mcc");
+
+    let bus = gst::Bus::new();
+    h.element().unwrap().set_bus(Some(&bus));
+
+    assert_eq!(
+        h.push(gst::Buffer::from_slice(data.into_bytes())),
+        Ok(gst::FlowSuccess::Ok)
+    );
+    h.push_event(gst::event::Eos::new());
+
+    let mut n_buffers = 0;
+    while let Some(buf) = h.try_pull() {
+        let tc_meta = buf
+            .meta::<gst_video::VideoTimeCodeMeta>()
+            .expect("No timecode meta");
+        let tc = tc_meta.tc();
+        assert!(tc.seconds() != 5 || tc.frames() != 0);
+        n_buffers += 1;
+    }
+
+    assert_eq!(n_buffers, 577);
+
+    let msg = bus
+        .iter()
+        .find(|msg| msg.type_() == gst::MessageType::Warning)
+        .expect("No warning posted");
+    match msg.view() {
+        gst::MessageView::Warning(warning) => {
+            assert!(warning.error().to_string().contains("invalid checksum"));
+        }
+        _ => unreachable!(),
+    }
+}
@@ -273,3 +273,109 @@ fn test_pull() {
         }
     }
 }
+
+#[test]
+fn test_pull_segment_seek() {
+    init();
+
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/dn2018-1217.scc");
+
+    let mut h = gst_check::Harness::new_parse(&format!("filesrc location={:?} ! sccparse", path));
+
+    h.play();
+
+    /* Let's first pull until EOS, which also indexes the whole file */
+    loop {
+        let mut done = false;
+
+        while h.events_in_queue() != 0 {
+            let event = h.pull_event();
+
+            if let Ok(event) = event {
+                match event.view() {
+                    EventView::Eos(_) => {
+                        done = true;
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        while h.buffers_in_queue() != 0 {
+            let _ = h.pull();
+        }
+
+        if done {
+            break;
+        }
+    }
+
+    let duration = h.query_duration::<gst::ClockTime>().expect("No duration");
+
+    /* Segment seek until the end of the file, relative to the duration */
+    h.push_upstream_event(gst::event::Seek::new(
+        1.0,
+        gst::SeekFlags::FLUSH | gst::SeekFlags::SEGMENT,
+        gst::SeekType::Set,
+        gst::GenericFormattedValue::Time(Some(18 * gst::ClockTime::SECOND)),
+        gst::SeekType::End,
+        gst::GenericFormattedValue::Time(Some(gst::ClockTime::ZERO)),
+    ));
+
+    /* Then loop over one second with a non-flushing seek */
+    let mut seeks = vec![(
+        gst::SeekFlags::SEGMENT,
+        18 * gst::ClockTime::SECOND,
+        19 * gst::ClockTime::SECOND,
+    )];
+    let mut stop = duration;
+
+    loop {
+        let mut segment_done = None;
+
+        while h.buffers_in_queue() != 0 {
+            if let Ok(buffer) = h.pull() {
+                let pts = buffer.pts().unwrap();
+                let end_time = pts + buffer.duration().unwrap();
+
+                assert!(end_time >= 18 * gst::ClockTime::SECOND && pts < stop);
+            }
+        }
+
+        while h.events_in_queue() != 0 {
+            let event = h.pull_event();
+
+            if let Ok(event) = event {
+                match event.view() {
+                    EventView::Eos(_) => unreachable!(),
+                    EventView::SegmentDone(ev) => {
+                        segment_done = Some(ev.get());
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        if let Some(position) = segment_done {
+            assert_eq!(position, gst::GenericFormattedValue::Time(Some(stop)));
+
+            match seeks.pop() {
+                Some((flags, start, new_stop)) => {
+                    stop = new_stop;
+                    h.push_upstream_event(gst::event::Seek::new(
+                        1.0,
+                        flags,
+                        gst::SeekType::Set,
+                        gst::GenericFormattedValue::Time(Some(start)),
+                        gst::SeekType::Set,
+                        gst::GenericFormattedValue::Time(Some(stop)),
+                    ));
+                }
+                None => break,
+            }
+        }
+    }
+}
@@ -0,0 +1,268 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#![allow(clippy::single_match)]
+
+use gst::prelude::*;
+use gst::ClockTime;
+use gst::EventView;
+use pretty_assertions::assert_eq;
+use std::path::PathBuf;
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstrsclosedcaption::plugin_register_static().unwrap();
+    });
+}
+
+/// Pushes the whole file in small buffers and pulls out all the cues as
+/// (pts, duration, text)
+fn parse(h: &mut gst_check::Harness, data: &[u8]) -> Vec<(ClockTime, ClockTime, String)> {
+    for chunk in data.chunks(7) {
+        assert_eq!(
+            h.push(gst::Buffer::from_mut_slice(chunk.to_vec())),
+            Ok(gst::FlowSuccess::Ok)
+        );
+    }
+    h.push_event(gst::event::Eos::new());
+
+    let mut cues = Vec::new();
+    while let Some(buf) = h.try_pull() {
+        let map = buf.map_readable().unwrap();
+        cues.push((
+            buf.pts().unwrap(),
+            buf.duration().unwrap(),
+            std::str::from_utf8(&map).unwrap().to_string(),
+        ));
+    }
+
+    cues
+}
+
+#[test]
+fn test_parse_vtt() {
+    init();
+
+    let mut h = gst_check::Harness::new("subtitleparse");
+    h.set_src_caps_str("application/x-subtitle-vtt");
+    h.set_sink_caps_str("text/x-raw,format=utf8");
+
+    let cues = parse(&mut h, include_bytes!("captions.vtt"));
+
+    assert_eq!(
+        cues,
+        vec![
+            (
+                ClockTime::from_seconds(1),
+                ClockTime::from_seconds(2),
+                "Hello there,\nhow are you?".to_string()
+            ),
+            (
+                ClockTime::from_seconds(4),
+                ClockTime::from_seconds(2),
+                "Fine, thanks!".to_string()
+            ),
+            (
+                ClockTime::from_mseconds(7500),
+                ClockTime::from_mseconds(1500),
+                "Good to hear & see you.".to_string()
+            ),
+            (
+                ClockTime::from_seconds(20),
+                ClockTime::from_seconds(2),
+                "The end.".to_string()
+            ),
+        ]
+    );
+
+    let caps = h
+        .sinkpad()
+        .expect("harness has no sinkpad")
+        .current_caps()
+        .expect("pad has no caps");
+    assert_eq!(
+        caps,
+        gst::Caps::builder("text/x-raw")
+            .field("format", "utf8")
+            .build()
+    );
+
+    /* Voice spans are forwarded as speaker events */
+    let mut speakers = Vec::new();
+    while let Some(event) = h.try_pull_event() {
+        match event.view() {
+            EventView::CustomDownstream(ev) => {
+                let s = ev.structure().unwrap();
+                assert_eq!(s.name(), "transcription/speaker");
+                speakers.push(s.get::<Option<String>>("speaker").unwrap());
+            }
+            _ => (),
+        }
+    }
+
+    assert_eq!(
+        speakers,
+        vec![Some("Anna".to_string()), Some("Bob".to_string())]
+    );
+}
+
+#[test]
+fn test_parse_srt_markup() {
+    init();
+
+    let mut h = gst_check::Harness::new("subtitleparse");
+    h.set_src_caps_str("application/x-subtitle");
+    h.set_sink_caps_str("text/x-raw,format=pango-markup");
+
+    let cues = parse(&mut h, include_bytes!("captions.srt"));
+
+    assert_eq!(
+        cues,
+        vec![
+            (
+                ClockTime::from_seconds(1),
+                ClockTime::from_seconds(2),
+                "Hello there,\n<b>how</b> are you?".to_string()
+            ),
+            (
+                ClockTime::from_seconds(4),
+                ClockTime::from_seconds(2),
+                "<span foreground=\"#ff0000\">Fine</span>, thanks!".to_string()
+            ),
+            (
+                ClockTime::from_seconds(20),
+                ClockTime::from_seconds(2),
+                "The end.".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_json() {
+    init();
+
+    let mut h = gst_check::Harness::new("subtitleparse");
+    h.set_src_caps_str("application/x-subtitle-vtt");
+    h.set_sink_caps_str("application/x-json,format=cea608");
+
+    let cues = parse(&mut h, include_bytes!("captions.vtt"));
+
+    assert_eq!(cues.len(), 4);
+
+    /* The second cue is at the top left of the safe area */
+    let lines: serde_json::Value = serde_json::from_str(&cues[1].2).unwrap();
+    assert_eq!(lines["lines"][0]["row"], 0);
+    assert_eq!(lines["lines"][0]["column"], 0);
+    assert_eq!(lines["lines"][0]["chunks"][0]["style"], "ItalicWhite");
+    assert_eq!(lines["lines"][0]["chunks"][0]["text"], "Fine");
+
+    /* The others are at the bottom */
+    let lines: serde_json::Value = serde_json::from_str(&cues[0].2).unwrap();
+    assert_eq!(lines["lines"][0]["row"], 13);
+    assert_eq!(lines["lines"][1]["row"], 14);
+}
+
+#[test]
+fn test_pull() {
+    init();
+
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/captions.vtt");
+
+    let mut h =
+        gst_check::Harness::new_parse(&format!("filesrc location={:?} ! subtitleparse", path));
+
+    h.play();
+
+    /* Let's first pull until EOS */
+    loop {
+        let mut done = false;
+
+        while h.events_in_queue() != 0 {
+            let event = h.pull_event();
+
+            if let Ok(event) = event {
+                match event.view() {
+                    EventView::Eos(_) => {
+                        done = true;
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        while h.buffers_in_queue() != 0 {
+            let _ = h.pull();
+        }
+
+        if done {
+            break;
+        }
+    }
+
+    /* The duration is the end of the last cue */
+    assert_eq!(
+        h.query_duration::<ClockTime>(),
+        Some(ClockTime::from_seconds(22))
+    );
+
+    /* Now seek and check that we receive buffers with appropriate PTS */
+    h.push_upstream_event(gst::event::Seek::new(
+        1.0,
+        gst::SeekFlags::FLUSH,
+        gst::SeekType::Set,
+        gst::GenericFormattedValue::Time(Some(ClockTime::from_seconds(5))),
+        gst::SeekType::Set,
+        gst::GenericFormattedValue::Time(Some(ClockTime::from_seconds(8))),
+    ));
+
+    let mut pts = Vec::new();
+    loop {
+        let mut done = false;
+
+        while h.buffers_in_queue() != 0 {
+            if let Ok(buffer) = h.pull() {
+                let start = buffer.pts().unwrap();
+                let end = start + buffer.duration().unwrap();
+
+                assert!(start >= ClockTime::from_seconds(5) && end <= ClockTime::from_seconds(8));
+                pts.push(start);
+            }
+        }
+
+        while h.events_in_queue() != 0 {
+            let event = h.pull_event();
+
+            if let Ok(event) = event {
+                match event.view() {
+                    EventView::Eos(_) => {
+                        done = true;
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        if done {
+            break;
+        }
+    }
+
+    /* The cue displayed at the seek position is clipped */
+    assert_eq!(
+        pts,
+        vec![ClockTime::from_seconds(5), ClockTime::from_mseconds(7500)]
+    );
+}
@@ -0,0 +1,208 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use gst::glib;
+use gst::prelude::*;
+
+use std::time::{Duration, Instant};
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstrsclosedcaption::plugin_register_static().unwrap();
+
+        // transcriberbin creates an awstranscriber before it can be replaced
+        if gst::ElementFactory::find("awstranscriber").is_none() {
+            gst::Element::register(
+                None,
+                "awstranscriber",
+                gst::Rank::None,
+                FakeTranscriber::static_type(),
+            )
+            .unwrap();
+        }
+    });
+}
+
+// Transcriber stand-in outputting a word for every audio buffer
+mod imp {
+    use super::*;
+    use gst::subclass::prelude::*;
+    use once_cell::sync::Lazy;
+
+    pub struct FakeTranscriber {
+        sinkpad: gst::Pad,
+        srcpad: gst::Pad,
+    }
+
+    impl FakeTranscriber {
+        fn sink_chain(&self, buffer: gst::Buffer) -> Result<gst::FlowSuccess, gst::FlowError> {
+            let mut outbuf = gst::Buffer::from_slice("word");
+            {
+                let outbuf = outbuf.get_mut().unwrap();
+                outbuf.set_pts(buffer.pts());
+                outbuf.set_duration(buffer.duration());
+            }
+
+            self.srcpad.push(outbuf)
+        }
+
+        fn sink_event(
+            &self,
+            pad: &gst::Pad,
+            element: &super::FakeTranscriber,
+            event: gst::Event,
+        ) -> bool {
+            match event.view() {
+                gst::EventView::Caps(_) => self.srcpad.push_event(gst::event::Caps::new(
+                    &gst::Caps::builder("text/x-raw")
+                        .field("format", "utf8")
+                        .build(),
+                )),
+                _ => pad.event_default(Some(element), event),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for FakeTranscriber {
+        const NAME: &'static str = "ClosedCaptionTestFakeTranscriber";
+        type Type = super::FakeTranscriber;
+        type ParentType = gst::Element;
+
+        fn with_class(klass: &Self::Class) -> Self {
+            let templ = klass.pad_template("sink").unwrap();
+            let sinkpad = gst::Pad::builder_with_template(&templ, Some("sink"))
+                .chain_function(|_pad, parent, buffer| {
+                    FakeTranscriber::catch_panic_pad_function(
+                        parent,
+                        || Err(gst::FlowError::Error),
+                        |transcriber, _element| transcriber.sink_chain(buffer),
+                    )
+                })
+                .event_function(|pad, parent, event| {
+                    FakeTranscriber::catch_panic_pad_function(
+                        parent,
+                        || false,
+                        |transcriber, element| transcriber.sink_event(pad, element, event),
+                    )
+                })
+                .build();
+
+            let templ = klass.pad_template("src").unwrap();
+            let srcpad = gst::Pad::from_template(&templ, Some("src"));
+
+            Self { sinkpad, srcpad }
+        }
+    }
+
+    impl ObjectImpl for FakeTranscriber {
+        fn constructed(&self, obj: &Self::Type) {
+            self.parent_constructed(obj);
+
+            obj.add_pad(&self.sinkpad).unwrap();
+            obj.add_pad(&self.srcpad).unwrap();
+        }
+    }
+
+    impl GstObjectImpl for FakeTranscriber {}
+
+    impl ElementImpl for FakeTranscriber {
+        fn pad_templates() -> &'static [gst::PadTemplate] {
+            static PAD_TEMPLATES: Lazy<Vec<gst::PadTemplate>> = Lazy::new(|| {
+                vec![
+                    gst::PadTemplate::new(
+                        "sink",
+                        gst::PadDirection::Sink,
+                        gst::PadPresence::Always,
+                        &gst::Caps::builder("audio/x-raw").build(),
+                    )
+                    .unwrap(),
+                    gst::PadTemplate::new(
+                        "src",
+                        gst::PadDirection::Src,
+                        gst::PadPresence::Always,
+                        &gst::Caps::builder("text/x-raw")
+                            .field("format", "utf8")
+                            .build(),
+                    )
+                    .unwrap(),
+                ]
+            });
+
+            PAD_TEMPLATES.as_ref()
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct FakeTranscriber(ObjectSubclass<imp::FakeTranscriber>) @extends gst::Element, gst::Object;
+}
+
+/* The captions are produced at the video framerate, not at the
+ * 30000/1001 default of tttocea608 */
+#[test]
+fn test_caption_framerate() {
+    init();
+
+    let pipeline = gst::parse_launch(
+        "videotestsrc is-live=true ! video/x-raw,framerate=25/1 ! transcriberbin name=t \
+         latency=1000 ! fakesink async=false \
+         audiotestsrc is-live=true ! t.sink_audio t.src_audio ! fakesink async=false",
+    )
+    .unwrap()
+    .downcast::<gst::Pipeline>()
+    .unwrap();
+
+    let transcriberbin = pipeline.by_name("t").unwrap();
+    transcriberbin.set_property(
+        "transcriber",
+        glib::Object::new::<FakeTranscriber>(&[]).unwrap(),
+    );
+    transcriberbin.set_property(
+        "cc-caps",
+        gst::Caps::builder("closedcaption/x-cea-708")
+            .field("format", "cdp")
+            .build(),
+    );
+
+    pipeline.set_state(gst::State::Playing).unwrap();
+
+    let tttocea608_srcpad = transcriberbin
+        .downcast_ref::<gst::Bin>()
+        .unwrap()
+        .by_name("tttocea608")
+        .unwrap()
+        .static_pad("src")
+        .unwrap();
+
+    let start = Instant::now();
+    let caps = loop {
+        if let Some(caps) = tttocea608_srcpad.current_caps() {
+            break caps;
+        }
+
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "tttocea608 did not negotiate"
+        );
+        std::thread::sleep(Duration::from_millis(10));
+    };
+
+    pipeline.set_state(gst::State::Null).unwrap();
+
+    let s = caps.structure(0).unwrap();
+    assert_eq!(s.name(), "closedcaption/x-cea-608");
+    assert_eq!(
+        s.get::<gst::Fraction>("framerate").unwrap(),
+        gst::Fraction::new(25, 1)
+    );
+}
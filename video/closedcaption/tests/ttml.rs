@@ -0,0 +1,159 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use gst::ClockTime;
+use pretty_assertions::assert_eq;
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstrsclosedcaption::plugin_register_static().unwrap();
+    });
+}
+
+fn new_timed_buffer<T: AsRef<[u8]> + Send + 'static>(
+    slice: T,
+    timestamp: ClockTime,
+    duration: ClockTime,
+) -> gst::buffer::Buffer {
+    let mut buf = gst::Buffer::from_slice(slice);
+    let buf_ref = buf.get_mut().unwrap();
+    buf_ref.set_pts(timestamp);
+    buf_ref.set_duration(duration);
+    buf
+}
+
+const HELLO: &str = r#"{"lines":[{"column":0,"row":13,"chunks":[{"style":"Yellow","underline":false,"text":"Hello"}],"carriage_return":null},{"column":2,"row":14,"chunks":[{"style":"ItalicWhite","underline":true,"text":"World"}],"carriage_return":null}],"mode":"PopOn","clear":null}"#;
+const BYE: &str = r#"{"lines":[{"column":0,"row":14,"chunks":[{"style":"White","underline":false,"text":"Bye & <thanks>"}],"carriage_return":null}],"mode":"PopOn","clear":null}"#;
+const CLEAR: &str = r#"{"lines":[],"mode":null,"clear":true}"#;
+
+/* Encode then parse back */
+#[test]
+fn test_roundtrip() {
+    init();
+
+    let mut h = gst_check::Harness::new_parse(
+        "jsontottml ! ttmlparse ! capsfilter caps=application/x-json",
+    );
+    h.set_src_caps_str("application/x-json,format=cea608");
+
+    for (json, pts, duration) in [(HELLO, 1, 2), (CLEAR, 3, 1), (BYE, 4, 1)] {
+        let inbuf = new_timed_buffer(
+            json,
+            ClockTime::from_seconds(pts),
+            ClockTime::from_seconds(duration),
+        );
+        assert_eq!(h.push(inbuf), Ok(gst::FlowSuccess::Ok));
+    }
+
+    /* The document is written at EOS */
+    assert_eq!(h.buffers_in_queue(), 0);
+    h.push_event(gst::event::Eos::new());
+
+    let expected = [(HELLO, 1, 2), (BYE, 4, 1)];
+    assert_eq!(h.buffers_in_queue(), expected.len() as u32);
+
+    for (json, pts, duration) in expected {
+        let outbuf = h.pull().unwrap();
+        assert_eq!(outbuf.pts(), Some(ClockTime::from_seconds(pts)));
+        assert_eq!(outbuf.duration(), Some(ClockTime::from_seconds(duration)));
+
+        let data = outbuf.map_readable().unwrap();
+        let output: serde_json::Value = serde_json::from_slice(&data).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(output, expected);
+    }
+}
+
+/* One document per caption, and empty documents for gaps */
+#[test]
+fn test_fragmented() {
+    init();
+
+    let mut h = gst_check::Harness::new_parse("jsontottml fragmented=true");
+    h.set_src_caps_str("application/x-json,format=cea608");
+
+    let inbuf = new_timed_buffer(HELLO, ClockTime::SECOND, ClockTime::SECOND);
+    assert_eq!(h.push(inbuf), Ok(gst::FlowSuccess::Ok));
+
+    h.push_event(
+        gst::event::Gap::builder(2 * ClockTime::SECOND)
+            .duration(ClockTime::SECOND)
+            .build(),
+    );
+
+    let outbuf = h.pull().unwrap();
+    assert_eq!(outbuf.pts(), Some(ClockTime::SECOND));
+    assert_eq!(outbuf.duration(), Some(ClockTime::SECOND));
+    let data = outbuf.map_readable().unwrap();
+    let document = std::str::from_utf8(&data).unwrap();
+    assert!(document.starts_with("<?xml"));
+    assert!(document.contains("tts:color=\"yellow\">Hello</span>"));
+    assert!(document.contains("tts:fontStyle=\"italic\""));
+    assert!(document.contains("<region xml:id=\"row13\""));
+    assert!(document.trim_end().ends_with("</tt>"));
+
+    let outbuf = h.pull().unwrap();
+    assert_eq!(outbuf.pts(), Some(2 * ClockTime::SECOND));
+    assert_eq!(outbuf.duration(), Some(ClockTime::SECOND));
+    let data = outbuf.map_readable().unwrap();
+    let document = std::str::from_utf8(&data).unwrap();
+    assert!(!document.contains("<p "));
+    assert!(document.trim_end().ends_with("</tt>"));
+}
+
+/* A document split over buffers without timestamps, as read from a file */
+#[test]
+fn test_parse_text() {
+    init();
+
+    let mut h = gst_check::Harness::new_parse("ttmlparse");
+    h.set_src_caps_str("application/ttml+xml");
+
+    let document = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tt xmlns="http://www.w3.org/ns/ttml">
+  <body>
+    <div>
+      <p begin="00:00:01.000" end="00:00:02.500">First<br/>caption</p>
+      <p begin="3s" end="4s">Second caption</p>
+    </div>
+  </body>
+</tt>
+"#;
+
+    let (first, second) = document.split_at(100);
+    assert_eq!(
+        h.push(gst::Buffer::from_slice(first.to_string())),
+        Ok(gst::FlowSuccess::Ok)
+    );
+    assert_eq!(h.buffers_in_queue(), 0);
+    assert_eq!(
+        h.push(gst::Buffer::from_slice(second.to_string())),
+        Ok(gst::FlowSuccess::Ok)
+    );
+
+    let expected = [
+        ("First\ncaption", 1000, 1500),
+        ("Second caption", 3000, 1000),
+    ];
+
+    for (text, pts, duration) in expected {
+        let outbuf = h.pull().unwrap();
+        assert_eq!(outbuf.pts(), Some(ClockTime::from_mseconds(pts)));
+        assert_eq!(outbuf.duration(), Some(ClockTime::from_mseconds(duration)));
+
+        let data = outbuf.map_readable().unwrap();
+        assert_eq!(std::str::from_utf8(&data).unwrap(), text);
+    }
+
+    let caps = h.sinkpad().unwrap().current_caps().unwrap();
+    assert_eq!(caps.structure(0).unwrap().name(), "text/x-raw");
+}
@@ -551,3 +551,40 @@ fn test_word_wrap_roll_up() {
         assert_eq!(e.2, &*data);
     }
 }
+
+/* Check that speaker changes signalled by the transcriber are marked */
+#[test]
+fn test_speaker_prefix() {
+    init();
+
+    let mut h = gst_check::Harness::new_parse("tttocea608 mode=roll-up2 speaker-prefix=chevrons");
+    h.set_src_caps_str("text/x-raw");
+
+    while h.events_in_queue() != 0 {
+        let _event = h.pull_event().unwrap();
+    }
+
+    let s = gst::Structure::builder("transcription/speaker")
+        .field("speaker", "spk_0")
+        .field("channel", None::<String>)
+        .build();
+    assert!(h.push_event(gst::event::CustomDownstream::new(s)));
+
+    let inbuf = new_timed_buffer(&"Hi", ClockTime::SECOND, ClockTime::SECOND);
+    assert_eq!(h.push(inbuf), Ok(gst::FlowSuccess::Ok));
+
+    let expected: [[u8; 2usize]; 5] = [
+        [0x94, 0x25], /* roll_up_2 */
+        [0x94, 0x70], /* preamble */
+        [0x3e, 0x3e], /* > > */
+        [0x20, 0xc8], /* SPACE H */
+        [0xe9, 0x80], /* i nil */
+    ];
+
+    for (i, e) in expected.iter().enumerate() {
+        let outbuf = h.try_pull().unwrap();
+
+        let data = outbuf.map_readable().unwrap();
+        assert_eq!(e, &*data, "Unexpected data for {}th buffer", i + 1);
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright (C) 2026 agent <agent@local>
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License, v2.0.
+// If a copy of the MPL was not distributed with this file, You can obtain one at
+// <https://mozilla.org/MPL/2.0/>.
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use gst::ClockTime;
+use pretty_assertions::assert_eq;
+
+fn init() {
+    use std::sync::Once;
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        gst::init().unwrap();
+        gstrsclosedcaption::plugin_register_static().unwrap();
+    });
+}
+
+fn new_timed_buffer<T: AsRef<[u8]> + Send + 'static>(
+    slice: T,
+    timestamp: ClockTime,
+    duration: ClockTime,
+) -> gst::buffer::Buffer {
+    let mut buf = gst::Buffer::from_slice(slice);
+    let buf_ref = buf.get_mut().unwrap();
+    buf_ref.set_pts(timestamp);
+    buf_ref.set_duration(duration);
+    buf
+}
+
+/* Checks the CDP framing and returns the cc_data triplets */
+fn cdp_cc_data(cdp: &[u8]) -> &[u8] {
+    assert_eq!(&cdp[..2], &[0x96, 0x69]);
+    assert_eq!(cdp[2] as usize, cdp.len());
+    /* 30 fps */
+    assert_eq!(cdp[3], 0x5f);
+    assert_eq!(cdp[7], 0x72);
+    assert_eq!(cdp.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)), 0);
+
+    let cc_count = (cdp[8] & 0x1f) as usize;
+    assert_eq!(cc_count, 20);
+
+    &cdp[9..9 + cc_count * 3]
+}
+
+#[test]
+fn test_non_timed_buffer() {
+    init();
+
+    let mut h = gst_check::Harness::new_parse("tttocea708 mode=pop-on");
+    h.set_src_caps_str("text/x-raw");
+
+    let inbuf = gst::Buffer::from_slice(&"Hello");
+
+    assert_eq!(h.push(inbuf), Err(gst::FlowError::Error));
+}
+
+/* Check that we output one CDP packet per frame with increasing
+ * sequence counters, and that the text can be decoded back */
+#[test]
+fn test_one_timed_buffer_and_eos() {
+    init();
+
+    let mut h = gst_check::Harness::new_parse("tttocea708 mode=pop-on");
+    h.set_src_caps_str("text/x-raw");
+
+    while h.events_in_queue() != 0 {
+        let _event = h.pull_event().unwrap();
+    }
+
+    let inbuf = new_timed_buffer(&"Hello", ClockTime::SECOND, ClockTime::SECOND);
+    assert_eq!(h.push(inbuf), Ok(gst::FlowSuccess::Ok));
+
+    h.push_event(gst::event::Eos::new());
+
+    let mut expected_pts = ClockTime::SECOND;
+    let mut sequence_counter = 0u16;
+    let mut cc_data = vec![];
+
+    while h.buffers_in_queue() != 0 {
+        let outbuf = h.pull().unwrap();
+        assert_eq!(outbuf.pts(), Some(expected_pts));
+        expected_pts += outbuf.duration().unwrap();
+
+        let data = outbuf.map_readable().unwrap();
+        assert_eq!(u16::from_be_bytes([data[5], data[6]]), sequence_counter);
+        sequence_counter += 1;
+
+        cc_data.extend_from_slice(cdp_cc_data(&data));
+    }
+
+    /* Until the caption is removed */
+    assert!(expected_pts >= 2 * ClockTime::SECOND);
+
+    /* CEA-608 was not enabled */
+    assert!(cc_data.chunks(3).all(|triplet| triplet[0] & 0x07 != 0x04));
+
+    /* The text made it to service 1 */
+    let text = cc_data
+        .chunks(3)
+        .filter(|triplet| triplet[0] & 0x06 == 0x06)
+        .flat_map(|triplet| &triplet[1..])
+        .copied()
+        .collect::<Vec<u8>>();
+    assert!(text.windows(5).any(|w| w == b"Hello"));
+}
+
+/* Encode then decode */
+#[test]
+fn test_roundtrip() {
+    init();
+
+    let mut h = gst_check::Harness::new_parse("tttocea708 mode=pop-on ! cea708tojson");
+    h.set_src_caps_str("text/x-raw");
+
+    let inbuf = new_timed_buffer(&"Hello\nWorld", ClockTime::SECOND, ClockTime::SECOND);
+    assert_eq!(h.push(inbuf), Ok(gst::FlowSuccess::Ok));
+
+    h.push_event(gst::event::Eos::new());
+
+    let outbuf = h.pull().unwrap();
+    assert!(outbuf.pts().unwrap() >= ClockTime::SECOND);
+
+    let data = outbuf.map_readable().unwrap();
+    let lines: serde_json::Value = serde_json::from_slice(&data).unwrap();
+
+    assert_eq!(lines["lines"][0]["row"], 13);
+    assert_eq!(lines["lines"][0]["chunks"][0]["text"], "Hello");
+    assert_eq!(lines["lines"][1]["row"], 14);
+    assert_eq!(lines["lines"][1]["chunks"][0]["text"], "World");
+}
+
+/* With cea608=true, field 1 carries the output of tttocea608 */
+#[test]
+fn test_cea608() {
+    init();
+
+    let mut h = gst_check::Harness::new_parse("tttocea708 mode=pop-on cea608=true");
+    h.set_src_caps_str("text/x-raw");
+
+    let inbuf = new_timed_buffer(&"Hello", ClockTime::SECOND, ClockTime::SECOND);
+    assert_eq!(h.push(inbuf), Ok(gst::FlowSuccess::Ok));
+
+    h.push_event(gst::event::Eos::new());
+
+    let mut field1 = vec![];
+    while h.buffers_in_queue() != 0 {
+        let outbuf = h.pull().unwrap();
+        let data = outbuf.map_readable().unwrap();
+
+        for triplet in cdp_cc_data(&data).chunks(3) {
+            if triplet[0] == 0xfc {
+                field1.push([triplet[1], triplet[2]]);
+            }
+        }
+    }
+
+    /* resume_caption_loading, then erase_non_displayed_memory */
+    assert_eq!(&field1[..2], &[[0x94, 0x20], [0x94, 0xae]]);
+    assert!(field1.contains(&[0x94, 0x2f]));
+}